@@ -0,0 +1 @@
+DROP INDEX consumables_barcode_unique;
@@ -0,0 +1,47 @@
+-- Normalize barcodes: strip whitespace and dashes and pad UPC-A to EAN-13.
+UPDATE consumables
+SET barcode = regexp_replace(barcode, '\s|-', '', 'g')
+WHERE barcode IS NOT NULL;
+
+UPDATE consumables
+SET barcode = NULL
+WHERE barcode = '';
+
+UPDATE consumables
+SET barcode = '0' || barcode
+WHERE barcode ~ '^[0-9]{12}$';
+
+-- Barcodes that aren't a valid EAN-8 or EAN-13 can no longer be saved, move
+-- them to the comments so they aren't lost.
+UPDATE consumables
+SET barcode = NULL,
+    comments = concat_ws(E'\n\n', comments, 'Invalid barcode ' || barcode)
+WHERE barcode IS NOT NULL
+AND CASE
+    WHEN barcode !~ '^([0-9]{8}|[0-9]{13})$' THEN true
+    ELSE right(barcode, 1)::integer <> (10 - (
+        SELECT sum(
+            substr(barcode, length(barcode) - i, 1)::integer
+            * CASE WHEN i % 2 = 1 THEN 3 ELSE 1 END
+        )
+        FROM generate_series(1, length(barcode) - 1) AS i
+    ) % 10) % 10
+END;
+
+-- Keep the barcode on the oldest consumable only, moving it to the comments
+-- of any duplicates so it isn't lost.
+UPDATE consumables
+SET barcode = NULL,
+    comments = concat_ws(E'\n\n', comments, 'Duplicate barcode ' || barcode)
+WHERE id IN (
+    SELECT id FROM (
+        SELECT id, row_number() OVER (PARTITION BY barcode ORDER BY id) AS n
+        FROM consumables
+        WHERE barcode IS NOT NULL
+    ) AS duplicates
+    WHERE n > 1
+);
+
+CREATE UNIQUE INDEX consumables_barcode_unique
+ON consumables (barcode)
+WHERE barcode IS NOT NULL;
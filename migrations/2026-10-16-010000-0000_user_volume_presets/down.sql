@@ -0,0 +1,2 @@
+ALTER TABLE users
+DROP COLUMN volume_presets;
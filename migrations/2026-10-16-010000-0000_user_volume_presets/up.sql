@@ -0,0 +1,2 @@
+ALTER TABLE users
+ADD COLUMN volume_presets INTEGER[] NOT NULL DEFAULT '{250,330,500}';
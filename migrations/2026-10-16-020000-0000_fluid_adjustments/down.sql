@@ -0,0 +1 @@
+DROP TABLE fluid_adjustments;
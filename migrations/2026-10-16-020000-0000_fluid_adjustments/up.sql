@@ -0,0 +1,13 @@
+CREATE TABLE fluid_adjustments(
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    time TIMESTAMPTZ NOT NULL,
+    utc_offset INTEGER NOT NULL,
+    mls INTEGER NOT NULL,
+    comments TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    FOREIGN KEY (user_id) REFERENCES users (id)
+);
+CREATE INDEX idx_fluid_adjustments_user_id ON fluid_adjustments(user_id, time);
+SELECT diesel_manage_updated_at('fluid_adjustments');
@@ -0,0 +1,2 @@
+DROP TABLE medication_doses;
+DROP TABLE medications;
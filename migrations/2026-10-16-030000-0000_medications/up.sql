@@ -0,0 +1,33 @@
+CREATE TABLE medications(
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    consumable_id BIGINT NOT NULL,
+    dose NUMERIC NOT NULL,
+    dose_unit TEXT NOT NULL,
+    route consumption_type NOT NULL,
+    doses_per_day INTEGER,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    comments TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    FOREIGN KEY (user_id) REFERENCES users (id),
+    FOREIGN KEY (consumable_id) REFERENCES consumables (id)
+);
+CREATE INDEX idx_medications_user_id ON medications(user_id);
+SELECT diesel_manage_updated_at('medications');
+
+CREATE TABLE medication_doses(
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    medication_id BIGINT NOT NULL,
+    time TIMESTAMPTZ NOT NULL,
+    utc_offset INTEGER NOT NULL,
+    dose NUMERIC NOT NULL,
+    comments TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    FOREIGN KEY (user_id) REFERENCES users (id),
+    FOREIGN KEY (medication_id) REFERENCES medications (id)
+);
+CREATE INDEX idx_medication_doses_user_id ON medication_doses(user_id, time);
+SELECT diesel_manage_updated_at('medication_doses');
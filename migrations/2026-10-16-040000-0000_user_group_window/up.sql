@@ -0,0 +1,2 @@
+ALTER TABLE users
+ADD COLUMN group_window_minutes INTEGER;
@@ -0,0 +1,2 @@
+ALTER TABLE users
+ADD COLUMN landing_date TEXT;
@@ -0,0 +1,2 @@
+ALTER TABLE consumption_consumables
+DROP COLUMN portion;
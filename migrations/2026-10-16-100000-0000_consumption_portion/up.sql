@@ -0,0 +1,2 @@
+ALTER TABLE consumption_consumables
+ADD COLUMN portion NUMERIC NOT NULL DEFAULT 1;
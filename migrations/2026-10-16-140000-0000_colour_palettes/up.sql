@@ -0,0 +1,14 @@
+CREATE TABLE colour_palettes(
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    name TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    colours JSONB NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    FOREIGN KEY (user_id) REFERENCES users (id)
+);
+CREATE INDEX idx_colour_palettes_user_id ON colour_palettes(user_id);
+CREATE UNIQUE INDEX idx_colour_palettes_active ON colour_palettes(user_id, kind) WHERE is_active;
+SELECT diesel_manage_updated_at('colour_palettes');
@@ -0,0 +1,2 @@
+ALTER TABLE wees
+DROP COLUMN wee_urge_id;
@@ -0,0 +1,3 @@
+ALTER TABLE wees
+ADD COLUMN wee_urge_id BIGINT REFERENCES wee_urges (id) ON DELETE SET NULL;
+CREATE INDEX idx_wees_wee_urge_id ON wees(wee_urge_id);
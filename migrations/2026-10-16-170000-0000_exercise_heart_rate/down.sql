@@ -0,0 +1,6 @@
+ALTER TABLE users
+DROP COLUMN max_heart_rate;
+
+ALTER TABLE exercises
+DROP COLUMN max_heart_rate,
+DROP COLUMN avg_heart_rate;
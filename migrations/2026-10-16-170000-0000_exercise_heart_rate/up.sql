@@ -0,0 +1,6 @@
+ALTER TABLE exercises
+ADD COLUMN avg_heart_rate INTEGER,
+ADD COLUMN max_heart_rate INTEGER;
+
+ALTER TABLE users
+ADD COLUMN max_heart_rate INTEGER;
@@ -0,0 +1,2 @@
+UPDATE users
+SET email = LOWER(TRIM(email));
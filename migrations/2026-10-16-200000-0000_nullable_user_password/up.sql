@@ -0,0 +1,6 @@
+ALTER TABLE users
+ALTER COLUMN password DROP NOT NULL;
+
+UPDATE users
+SET password = NULL
+WHERE password = '';
@@ -0,0 +1,2 @@
+ALTER TABLE consumptions
+DROP COLUMN planned;
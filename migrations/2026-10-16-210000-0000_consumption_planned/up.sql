@@ -0,0 +1,2 @@
+ALTER TABLE consumptions
+ADD COLUMN planned BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,5 @@
+ALTER TABLE consumables
+DROP COLUMN calories,
+DROP COLUMN protein,
+DROP COLUMN fat,
+DROP COLUMN carbohydrate;
@@ -0,0 +1,5 @@
+ALTER TABLE consumables
+ADD COLUMN calories NUMERIC,
+ADD COLUMN protein NUMERIC,
+ADD COLUMN fat NUMERIC,
+ADD COLUMN carbohydrate NUMERIC;
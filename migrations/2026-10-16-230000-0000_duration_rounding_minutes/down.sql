@@ -0,0 +1,2 @@
+ALTER TABLE users
+DROP COLUMN duration_rounding_minutes;
@@ -0,0 +1,2 @@
+ALTER TABLE users
+ADD COLUMN duration_rounding_minutes INTEGER;
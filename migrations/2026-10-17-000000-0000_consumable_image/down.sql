@@ -0,0 +1,2 @@
+ALTER TABLE consumables
+DROP COLUMN image_key;
@@ -0,0 +1,2 @@
+ALTER TABLE consumables
+ADD COLUMN image_key TEXT;
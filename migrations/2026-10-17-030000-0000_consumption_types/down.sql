@@ -0,0 +1,2 @@
+ALTER TABLE consumptions DROP COLUMN custom_type_id;
+DROP TABLE consumption_types;
@@ -0,0 +1,16 @@
+CREATE TABLE consumption_types(
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    name TEXT NOT NULL,
+    icon TEXT NOT NULL,
+    base_type consumption_type NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    FOREIGN KEY (user_id) REFERENCES users (id),
+    UNIQUE (user_id, name)
+);
+CREATE INDEX idx_consumption_types_user_id ON consumption_types(user_id);
+SELECT diesel_manage_updated_at('consumption_types');
+
+ALTER TABLE consumptions
+    ADD COLUMN custom_type_id BIGINT REFERENCES consumption_types (id) ON DELETE SET NULL;
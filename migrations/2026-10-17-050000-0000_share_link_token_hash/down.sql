@@ -0,0 +1,4 @@
+-- The tokens cannot be recovered from their hashes.
+DELETE FROM share_links;
+ALTER TABLE share_links
+RENAME COLUMN token_hash TO token;
@@ -0,0 +1,5 @@
+ALTER TABLE share_links
+RENAME COLUMN token TO token_hash;
+-- Same encoding as hash_token: unpadded URL safe base64 of the SHA-256.
+UPDATE share_links
+SET token_hash = translate(rtrim(encode(sha256(convert_to(token_hash, 'UTF8')), 'base64'), '='), '+/', '-_');
@@ -50,6 +50,41 @@ in
         Used to generate the OIDC redirect URL. Not used if OIDC not configured.
       '';
     };
+    secure_cookies = mkOption {
+      type = types.bool;
+      default = true;
+      description = lib.mdDoc ''
+        Mark session cookies as secure. Only disable if the service is not served over HTTPS.
+      '';
+    };
+    same_site = mkOption {
+      type = types.enum [
+        "strict"
+        "lax"
+        "none"
+      ];
+      default = "strict";
+      description = lib.mdDoc ''
+        The SameSite policy for session cookies.
+      '';
+    };
+    session_inactivity_days = mkOption {
+      type = types.int;
+      default = 7;
+      description = lib.mdDoc ''
+        Number of days of inactivity before a session expires.
+      '';
+    };
+
+    session_max_lifetime = mkOption {
+      type = types.nullOr types.str;
+      default = null;
+      example = "12h";
+      description = lib.mdDoc ''
+        How long after logging in a session ends, regardless of activity,
+        such as `30m`, `12h` or `7d`. Unlimited if null.
+      '';
+    };
     secretsFile = mkOption {
       type = types.nullOr types.str;
       default = null;
@@ -62,6 +97,11 @@ in
         - `OIDC_CLIENT_ID` - The Client ID for the OIDC.
         - `OIDC_CLIENT_SECRET` - The Client secret for the OIDC.
         - `OIDC_AUTH_SCOPE` - "openid profile groups email" or similar.
+        - `OIDC_NAME` - Name shown on the login button, defaults to "OIDC".
+
+        For more than one provider, set `OIDC_PROVIDERS` to a comma
+        separated list of ids and prefix each of the above with the upper
+        case id, e.g. `OIDC_WORK_CLIENT_ID`.
       '';
     };
   };
@@ -92,7 +132,13 @@ in
         RUST_LOG = "info";
         PORT = toString cfg.port;
         BASE_URL = cfg.base_url;
+        SESSION_SECURE = lib.boolToString cfg.secure_cookies;
+        SESSION_SAME_SITE = cfg.same_site;
+        SESSION_INACTIVITY_DAYS = toString cfg.session_inactivity_days;
         DATABASE_URL = "postgresql:///penguin_nurse?host=/var/run/postgresql";
+        STORAGE_DIR = cfg.data_dir;
+      } // lib.optionalAttrs (cfg.session_max_lifetime != null) {
+        SESSION_MAX_LIFETIME = cfg.session_max_lifetime;
       };
     };
   };
@@ -12,6 +12,20 @@ pub fn NavButton(on_click: Callback<()>, children: Element) -> Element {
     }
 }
 
+#[component]
+pub fn PrintButton() -> Element {
+    rsx! {
+        button {
+            r#type: "button",
+            class: "btn btn-outline btn-accent align-top print:hidden",
+            onclick: move |_e| {
+                let _ = document::eval("window.print();");
+            },
+            "Print"
+        }
+    }
+}
+
 #[component]
 pub fn CreateButton(on_click: Callback<()>, children: Element) -> Element {
     rsx! {
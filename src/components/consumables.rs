@@ -1,34 +1,52 @@
 use std::{num::ParseIntError, str::FromStr};
 
+use bigdecimal::ToPrimitive;
 use chrono::{DateTime, Local, Utc};
 use dioxus::prelude::*;
 use dioxus_fullstack::ServerFnError;
-use dioxus_router::ToQueryArgument;
+use dioxus_router::{Link, ToQueryArgument};
 use itertools::intersperse;
 use tap::Pipe;
 use thiserror::Error;
 
 use crate::{
-    components::events::Markdown,
+    Route,
+    components::{
+        buttons::ActionButton,
+        events::Markdown,
+        timeline::{DialogReference, EntryFocus},
+    },
+    dt::{display_date, get_date_for_dt, get_today},
     forms::{
         Barcode, Dialog, EditError, FieldValue, FormCloseButton, FormDeleteButton, FormEditButton,
         FormSaveCancelButton, InputBoolean, InputConsumable, InputConsumableUnitType,
-        InputConsumptionTypeMaybe, InputNumber, InputOptionDateTimeUtc, InputString, InputTextArea,
-        Saving, ValidationError, validate_barcode, validate_brand, validate_comments,
-        validate_consumable_millilitres, validate_consumable_quantity, validate_consumable_unit,
-        validate_consumption_type_maybe, validate_maybe_date_time, validate_name,
+        InputConsumptionTypeMaybe, InputDate, InputNumber, InputOptionDateTimeUtc, InputQuantity,
+        InputString, InputTextArea, Saving, UnsavedChanges, ValidationError, use_dirty,
+        validate_barcode, validate_brand, validate_comments, validate_consumable_quantity,
+        validate_consumable_unit, validate_consumption_type_maybe, validate_date,
+        validate_destroyed, validate_ingredient_millilitres, validate_maybe_date_time,
+        validate_name, validate_nutrition_value,
     },
-    functions::consumables::{
-        create_consumable, create_nested_consumable, delete_consumable, delete_nested_consumable,
-        get_child_consumables, update_consumable, update_nested_consumable,
+    functions::{
+        consumables::{
+            create_consumable, create_nested_consumable, delete_consumable,
+            delete_nested_consumable, get_child_consumables, get_consumable_by_barcode,
+            get_consumables_with_name, lookup_barcode, update_consumable, update_nested_consumable,
+        },
+        consumptions::get_consumptions_for_consumable,
+        stats::consumable_totals,
     },
+    i18n::{format_prefs, t},
     models::{
-        ChangeConsumable, ChangeNestedConsumable, Consumable, ConsumableId, ConsumableItem,
-        ConsumableUnit, ConsumptionType, MaybeSet, NestedConsumable, NestedConsumableId,
-        NewConsumable, NewNestedConsumable,
+        COMMENTS_PREVIEW_CHARS, CONSUMABLE_SEARCH_LIMIT, ChangeConsumable, ChangeNestedConsumable,
+        Consumable, ConsumableId, ConsumableItem, ConsumableTotals, ConsumableUnit,
+        ConsumptionType, EntryId, MaybeSet, NestedConsumable, NestedConsumableId, NewConsumable,
+        NewNestedConsumable, SortDirection, comments_preview, format_datetime_long,
     },
+    use_timezone, use_user,
 };
 
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Operation {
     Create,
@@ -46,6 +64,10 @@ struct Validate {
     created: Memo<Result<Option<DateTime<Utc>>, ValidationError>>,
     destroyed: Memo<Result<Option<DateTime<Utc>>, ValidationError>>,
     consumption_type: Memo<Result<Option<ConsumptionType>, ValidationError>>,
+    calories: Memo<Result<Option<bigdecimal::BigDecimal>, ValidationError>>,
+    protein: Memo<Result<Option<bigdecimal::BigDecimal>, ValidationError>>,
+    fat: Memo<Result<Option<bigdecimal::BigDecimal>, ValidationError>>,
+    carbohydrate: Memo<Result<Option<bigdecimal::BigDecimal>, ValidationError>>,
 }
 
 async fn do_save(op: &Operation, validate: &Validate) -> Result<Consumable, EditError> {
@@ -58,6 +80,10 @@ async fn do_save(op: &Operation, validate: &Validate) -> Result<Consumable, Edit
     let created: Option<DateTime<Utc>> = validate.created.read().clone()?;
     let destroyed: Option<DateTime<Utc>> = validate.destroyed.read().clone()?;
     let consumption_type = validate.consumption_type.read().clone()?;
+    let calories = validate.calories.read().clone()?;
+    let protein = validate.protein.read().clone()?;
+    let fat = validate.fat.read().clone()?;
+    let carbohydrate = validate.carbohydrate.read().clone()?;
 
     match op {
         Operation::Create => {
@@ -71,6 +97,10 @@ async fn do_save(op: &Operation, validate: &Validate) -> Result<Consumable, Edit
                 created,
                 destroyed,
                 consumption_type,
+                calories,
+                protein,
+                fat,
+                carbohydrate,
             };
             create_consumable(updates).await.map_err(EditError::Server)
         }
@@ -85,6 +115,10 @@ async fn do_save(op: &Operation, validate: &Validate) -> Result<Consumable, Edit
                 created: MaybeSet::Set(created),
                 destroyed: MaybeSet::Set(destroyed),
                 consumption_type: MaybeSet::Set(consumption_type),
+                calories: MaybeSet::Set(calories),
+                protein: MaybeSet::Set(protein),
+                fat: MaybeSet::Set(fat),
+                carbohydrate: MaybeSet::Set(carbohydrate),
             };
             update_consumable(consumable.id, changes)
                 .await
@@ -93,11 +127,35 @@ async fn do_save(op: &Operation, validate: &Validate) -> Result<Consumable, Edit
     }
 }
 
+/// Copy product details into the fields that are still empty, returning the
+/// labels of the fields filled in.
+fn fill_from_product(
+    mut unit: Signal<Option<ConsumableUnit>>,
+    product_unit: Option<ConsumableUnit>,
+    fields: [(&'static str, Signal<String>, Option<String>); 6],
+) -> Vec<&'static str> {
+    let mut filled = Vec::new();
+    if let (None, Some(product_unit)) = (unit(), product_unit) {
+        unit.set(Some(product_unit));
+        filled.push("unit");
+    }
+    for (label, mut value, product_value) in fields {
+        if let Some(product_value) = product_value
+            && value.read().trim().is_empty()
+        {
+            value.set(product_value);
+            filled.push(label);
+        }
+    }
+    filled
+}
+
 #[component]
 pub fn ConsumableUpdate(
     op: Operation,
     on_cancel: Callback,
     on_save: Callback<Consumable>,
+    on_open_existing: Callback<Consumable>,
 ) -> Element {
     let name = use_signal(|| match &op {
         Operation::Create => String::new(),
@@ -144,6 +202,27 @@ pub fn ConsumableUpdate(
         Operation::Update { consumable } => consumable.consumption_type,
     });
 
+    let calories = use_signal(|| match &op {
+        Operation::Create => String::new(),
+        Operation::Update { consumable } => consumable.calories.as_raw(),
+    });
+
+    let protein = use_signal(|| match &op {
+        Operation::Create => String::new(),
+        Operation::Update { consumable } => consumable.protein.as_raw(),
+    });
+
+    let fat = use_signal(|| match &op {
+        Operation::Create => String::new(),
+        Operation::Update { consumable } => consumable.fat.as_raw(),
+    });
+
+    let carbohydrate = use_signal(|| match &op {
+        Operation::Create => String::new(),
+        Operation::Update { consumable } => consumable.carbohydrate.as_raw(),
+    });
+
+    let created_validate = use_memo(move || validate_maybe_date_time(&created()));
     let validate = Validate {
         name: use_memo(move || validate_name(&name())),
         brand: use_memo(move || validate_brand(&brand())),
@@ -151,10 +230,121 @@ pub fn ConsumableUpdate(
         is_organic: use_memo(move || Ok(is_organic())),
         unit: use_memo(move || validate_consumable_unit(unit())),
         comments: use_memo(move || validate_comments(&comments())),
-        created: use_memo(move || validate_maybe_date_time(&created())),
-        destroyed: use_memo(move || validate_maybe_date_time(&destroyed())),
+        created: created_validate,
+        destroyed: use_memo(move || validate_destroyed(&created_validate.read(), &destroyed())),
         consumption_type: use_memo(move || validate_consumption_type_maybe(consumption_type())),
+        calories: use_memo(move || validate_nutrition_value(&calories())),
+        protein: use_memo(move || validate_nutrition_value(&protein())),
+        fat: use_memo(move || validate_nutrition_value(&fat())),
+        carbohydrate: use_memo(move || validate_nutrition_value(&carbohydrate())),
+    };
+
+    let consumable_id = match &op {
+        Operation::Create => None,
+        Operation::Update { consumable } => Some(consumable.id),
     };
+    let validate_barcode = validate.barcode;
+    let existing = use_resource(move || async move {
+        let Ok(Some(barcode)) = validate_barcode() else {
+            return None;
+        };
+        get_consumable_by_barcode(barcode)
+            .await
+            .ok()
+            .flatten()
+            .filter(|existing| Some(existing.id) != consumable_id)
+    });
+    let existing = use_memo(move || existing().flatten());
+
+    // Checked when leaving the name or brand rather than on every key press.
+    let mut name_to_check: Signal<Option<(String, Option<String>)>> = use_signal(|| None);
+    let validate_name_clone = validate.name;
+    let validate_brand_clone = validate.brand;
+    let check_name = use_callback(move |()| {
+        let (Ok(name), Ok(brand)) = (validate_name_clone(), validate_brand_clone()) else {
+            return;
+        };
+        let value = Some((name, brand));
+        if *name_to_check.peek() != value {
+            name_to_check.set(value);
+        }
+    });
+    let same_name = use_resource(move || async move {
+        let Some((name, brand)) = name_to_check() else {
+            return Vec::new();
+        };
+        get_consumables_with_name(name, brand)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|other| Some(other.id) != consumable_id)
+            .collect()
+    });
+    let same_name = use_memo(move || same_name().unwrap_or_default());
+
+    // Fields filled in from the product database after scanning a new barcode.
+    let mut looked_up = use_signal(|| None::<Result<Vec<&'static str>, ServerFnError>>);
+    let on_scan = move |barcode: String| {
+        spawn(async move {
+            looked_up.set(None);
+            // A barcode that is already used gets a warning instead.
+            let in_use = get_consumable_by_barcode(barcode.clone())
+                .await
+                .map(|found| found.is_some_and(|found| Some(found.id) != consumable_id));
+            if !matches!(in_use, Ok(false)) {
+                return;
+            }
+            match lookup_barcode(barcode).await {
+                Ok(Some(product)) => {
+                    let fields = [
+                        ("name", name, product.name),
+                        ("brand", brand, product.brand),
+                        (
+                            "calories",
+                            calories,
+                            product.calories.as_ref().map(ToString::to_string),
+                        ),
+                        (
+                            "protein",
+                            protein,
+                            product.protein.as_ref().map(ToString::to_string),
+                        ),
+                        ("fat", fat, product.fat.as_ref().map(ToString::to_string)),
+                        (
+                            "carbohydrate",
+                            carbohydrate,
+                            product.carbohydrate.as_ref().map(ToString::to_string),
+                        ),
+                    ];
+                    looked_up.set(Some(Ok(fill_from_product(unit, product.unit, fields))));
+                }
+                Ok(None) => {}
+                Err(err) => looked_up.set(Some(Err(err))),
+            }
+        });
+    };
+
+    let dirty = use_dirty(move || {
+        (
+            (
+                name(),
+                brand(),
+                barcode(),
+                is_organic(),
+                unit(),
+                comments(),
+                created(),
+            ),
+            (
+                destroyed(),
+                consumption_type(),
+                calories(),
+                protein(),
+                fat(),
+                carbohydrate(),
+            ),
+        )
+    });
 
     let mut saving = use_signal(|| Saving::No);
 
@@ -164,12 +354,17 @@ pub fn ConsumableUpdate(
         validate.name.read().is_err()
             || validate.brand.read().is_err()
             || validate.barcode.read().is_err()
+            || existing.read().is_some()
             || validate.is_organic.read().is_err()
             || validate.unit.read().is_err()
             || validate.comments.read().is_err()
             || validate.created.read().is_err()
             || validate.destroyed.read().is_err()
             || validate.consumption_type.read().is_err()
+            || validate.calories.read().is_err()
+            || validate.protein.read().is_err()
+            || validate.fat.read().is_err()
+            || validate.carbohydrate.read().is_err()
             || disabled()
     });
 
@@ -186,6 +381,7 @@ pub fn ConsumableUpdate(
             match result {
                 Ok(consumable) => {
                     saving.set(Saving::Finished(Ok(())));
+                    dirty.reset();
                     on_save(consumable);
                 }
                 Err(err) => saving.set(Saving::Finished(Err(err))),
@@ -193,15 +389,18 @@ pub fn ConsumableUpdate(
         });
     });
 
+    let basis = unit().map_or("", |unit| unit.nutrition_basis_label());
+
     rsx! {
 
         h3 { class: "text-lg font-bold",
             match &op {
-                Operation::Create => "Create Consumable".to_string(),
+                Operation::Create => t("Create Consumable").to_string(),
                 Operation::Update { consumable } => {
-                    format!("Edit Consumable {}", consumable.name)
+                    format!("{} {}", t("Edit Consumable"), consumable.name)
                 }
             }
+            UnsavedChanges { dirty: dirty.is_dirty() }
         }
         p { class: "py-4", "Press ESC key or click the button below to close" }
         form {
@@ -219,6 +418,7 @@ pub fn ConsumableUpdate(
                 value: name,
                 validate: validate.name,
                 disabled,
+                on_blur: check_name,
             }
             InputString {
                 id: "brand",
@@ -226,6 +426,26 @@ pub fn ConsumableUpdate(
                 value: brand,
                 validate: validate.brand,
                 disabled,
+                on_blur: check_name,
+            }
+            if !same_name().is_empty() {
+                div { class: "alert alert-warning mb-5",
+                    div { "A consumable with this name and brand already exists:" }
+                    for other in same_name() {
+                        {
+                            let open = other.clone();
+                            rsx! {
+                                div { key: "{other.id}",
+                                    ConsumableLabel { consumable: other, compact: true }
+                                    ActionButton {
+                                        on_click: move |()| on_open_existing(open.clone()),
+                                        "Open existing"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
             }
             InputString {
                 id: "barcode",
@@ -234,7 +454,33 @@ pub fn ConsumableUpdate(
                 validate: validate.barcode,
                 disabled,
             }
-            Barcode { barcode }
+            Barcode { barcode, on_scan }
+            match looked_up() {
+                Some(Ok(filled)) if !filled.is_empty() => {
+                    let filled = filled.join(", ");
+                    rsx! {
+                        div { class: "alert alert-info", "Filled in {filled} from the product database" }
+                    }
+                }
+                Some(Err(err)) => rsx! {
+                    div { class: "alert alert-warning", "Could not look up barcode: {err}" }
+                },
+                _ => rsx! {},
+            }
+            if let Some(existing_consumable) = existing() {
+                div { class: "alert alert-warning",
+                    "Barcode is already used by "
+                    ConsumableLabel { consumable: existing_consumable }
+                    ActionButton {
+                        on_click: move |()| {
+                            if let Some(existing) = existing() {
+                                on_open_existing(existing);
+                            }
+                        },
+                        "Open existing"
+                    }
+                }
+            }
             InputBoolean {
                 id: "is_organic",
                 label: "Is Organic",
@@ -255,6 +501,38 @@ pub fn ConsumableUpdate(
                 validate: validate.consumption_type,
                 disabled,
             }
+            InputNumber {
+                id: "calories",
+                label: format!("Calories (kcal {basis})"),
+                value: calories,
+                validate: validate.calories,
+                disabled,
+                step: bigdecimal::BigDecimal::from(1),
+            }
+            InputNumber {
+                id: "protein",
+                label: format!("Protein (g {basis})"),
+                value: protein,
+                validate: validate.protein,
+                disabled,
+                step: bigdecimal::BigDecimal::new(1.into(), 1),
+            }
+            InputNumber {
+                id: "fat",
+                label: format!("Fat (g {basis})"),
+                value: fat,
+                validate: validate.fat,
+                disabled,
+                step: bigdecimal::BigDecimal::new(1.into(), 1),
+            }
+            InputNumber {
+                id: "carbohydrate",
+                label: format!("Carbohydrate (g {basis})"),
+                value: carbohydrate,
+                validate: validate.carbohydrate,
+                disabled,
+                step: bigdecimal::BigDecimal::new(1.into(), 1),
+            }
             InputTextArea {
                 id: "comments",
                 label: "Comments",
@@ -282,8 +560,8 @@ pub fn ConsumableUpdate(
                 on_save: move |()| on_save(()),
                 on_cancel: move |()| on_cancel(()),
                 title: match &op {
-                    Operation::Create => "Create",
-                    Operation::Update { .. } => "Save",
+                    Operation::Create => t("Create"),
+                    Operation::Update { .. } => t("Save"),
                 },
                 saving,
             }
@@ -298,6 +576,10 @@ pub fn ConsumableDelete(
     on_delete: Callback<Consumable>,
 ) -> Element {
     let mut saving = use_signal(|| Saving::No);
+    let consumable_id = consumable.id;
+    let nested_consumables =
+        use_resource(move || async move { get_child_consumables(consumable_id).await });
+    let loaded_nested = nested_consumables.read().clone();
 
     let disabled = use_memo(move || saving.read().is_saving());
 
@@ -323,7 +605,24 @@ pub fn ConsumableDelete(
             {consumable.name.clone()}
         }
         p { class: "py-4", "Press ESC key or click the button below to close" }
-        ConsumableSummary { consumable: consumable.clone() }
+        ConsumableSummary {
+            consumable: consumable.clone(),
+            nested_consumables: loaded_nested.clone().and_then(Result::ok),
+        }
+        match loaded_nested {
+            Some(Ok(nested)) => rsx! {
+                ConsumableItemList { list: nested }
+            },
+            Some(Err(err)) => rsx! {
+                div { class: "alert alert-error",
+                    "Error loading ingredients: "
+                    {err.to_string()}
+                }
+            },
+            None => rsx! {
+                p { class: "alert alert-info", "Loading ingredients..." }
+            },
+        }
         form {
             novalidate: true,
             action: "javascript:void(0)",
@@ -490,6 +789,7 @@ pub fn ConsumableDialog(
                             on_change(consumable.clone());
                             show_update_ingredients(consumable)
                         },
+                        on_open_existing: show_update_basic,
                     }
                 }
             }
@@ -533,6 +833,7 @@ pub fn ConsumableDialog(
         ActiveDialog::NestedIngredient(parent, consumable) => {
             let parent_clone_1 = parent.clone();
             let parent_clone_2 = parent.clone();
+            let parent_clone_3 = parent.clone();
             rsx! {
                 Dialog {
                     ConsumableUpdate {
@@ -544,6 +845,9 @@ pub fn ConsumableDialog(
                             on_change(consumable.clone());
                             show_ingredient_update_ingredients((parent_clone_2.clone(), consumable.clone()));
                         },
+                        on_open_existing: move |consumable: Consumable| {
+                            show_ingredient_update_basic((parent_clone_3.clone(), consumable));
+                        },
                     }
                 }
             }
@@ -584,6 +888,128 @@ enum State {
     Finished(Result<(), ServerFnError>),
 }
 
+/// Send the file chosen in `input_id`, or a delete if there is no input, to
+/// the consumable image endpoint and report back the updated consumable.
+async fn send_consumable_image(
+    consumable_id: ConsumableId,
+    input_id: Option<&str>,
+) -> Result<Option<Consumable>, ServerFnError> {
+    let url = format!("/api/v1/consumables/{}/image", consumable_id.as_inner());
+    let script = format!(
+        r#"
+        const url = {url};
+        const inputId = {input_id};
+        let request = {{ method: "DELETE", credentials: "same-origin" }};
+        if (inputId !== null) {{
+            const input = document.getElementById(inputId);
+            const file = input && input.files[0];
+            request = file ? {{ method: "POST", credentials: "same-origin", body: file }} : null;
+            if (input) {{ input.value = ""; }}
+        }}
+        if (request === null) {{
+            dioxus.send({{ ok: null }});
+        }} else {{
+            try {{
+                const response = await fetch(url, request);
+                if (response.ok) {{
+                    dioxus.send({{ ok: await response.json() }});
+                }} else {{
+                    dioxus.send({{ error: await response.text() }});
+                }}
+            }} catch (err) {{
+                dioxus.send({{ error: String(err) }});
+            }}
+        }}
+        "#,
+        url = serde_json::json!(url),
+        input_id = serde_json::json!(input_id),
+    );
+
+    let result: serde_json::Value = document::eval(&script)
+        .recv()
+        .await
+        .map_err(|err| ServerFnError::new(err.to_string()))?;
+
+    if let Some(error) = result.get("error") {
+        return Err(ServerFnError::new(
+            error.as_str().unwrap_or("Upload failed").to_string(),
+        ));
+    }
+    match result.get("ok") {
+        Some(serde_json::Value::Null) | None => Ok(None),
+        Some(consumable) => serde_json::from_value(consumable.clone())
+            .map(Some)
+            .map_err(|err| ServerFnError::new(err.to_string())),
+    }
+}
+
+#[component]
+fn ConsumableImageUpload(consumable: Consumable, on_change: Callback<Consumable>) -> Element {
+    // The dialog's consumable is not reloaded, so track the latest here.
+    let mut current = use_signal(|| consumable.clone());
+    let mut state = use_signal(|| State::Idle);
+    let disabled = use_memo(move || State::Saving == *state.read());
+    let input_id = format!("consumable-image-{}", consumable.id.as_inner());
+    let consumable_id = consumable.id;
+
+    let send = use_callback(move |input_id: Option<String>| {
+        spawn(async move {
+            state.set(State::Saving);
+            let result = send_consumable_image(consumable_id, input_id.as_deref()).await;
+            match result {
+                Ok(Some(consumable)) => {
+                    state.set(State::Finished(Ok(())));
+                    current.set(consumable.clone());
+                    on_change(consumable);
+                }
+                Ok(None) => state.set(State::Idle),
+                Err(err) => state.set(State::Finished(Err(err))),
+            }
+        });
+    });
+
+    let input_id_clone = input_id.clone();
+    let current = current();
+    rsx! {
+        div { class: "flex flex-wrap items-center gap-2",
+            if let Some(image) = current.image_url(false) {
+                img {
+                    class: "max-h-48 rounded",
+                    alt: "{current.name}",
+                    src: image,
+                }
+            }
+            label { class: "label", r#for: "{input_id}", "Image" }
+            input {
+                r#type: "file",
+                id: "{input_id}",
+                class: "file-input file-input-bordered file-input-sm",
+                accept: "image/*",
+                disabled: disabled(),
+                onchange: move |_| send(Some(input_id_clone.clone())),
+            }
+            if current.image_key.is_some() {
+                button {
+                    r#type: "button",
+                    class: "btn btn-outline btn-error btn-sm",
+                    disabled: disabled(),
+                    onclick: move |_| send(None),
+                    "Remove image"
+                }
+            }
+            match state() {
+                State::Saving => rsx! {
+                    div { class: "alert alert-info", "Uploading..." }
+                },
+                State::Finished(Err(err)) => rsx! {
+                    div { class: "alert alert-error", {err.to_string()} }
+                },
+                State::Idle | State::Finished(Ok(())) => rsx! {},
+            }
+        }
+    }
+}
+
 #[component]
 pub fn ConsumableUpdateIngredients(
     consumable: ReadSignal<Consumable>,
@@ -682,6 +1108,10 @@ pub fn ConsumableUpdateIngredients(
                             consumable: consumable.clone(),
                             nested_consumables: Some(nested_consumables.clone()),
                         }
+                        ConsumableImageUpload {
+                            consumable: consumable.clone(),
+                            on_change,
+                        }
                         div { class: "p-4",
                             ul {
                                 for item in nested_consumables {
@@ -817,6 +1247,208 @@ pub fn ConsumableUpdateIngredients(
                 }
             }
         }
+
+        if !create_form() {
+            ConsumableTotalsSummary { consumable: consumable.clone() }
+            ConsumableUsedIn { consumable: consumable.clone() }
+        }
+    }
+}
+
+/// Days shown in a consumable's totals until another range is picked.
+const TOTALS_DEFAULT_DAYS: i64 = 30;
+
+/// How much of the consumable was had over a range of days, with a bar for
+/// each day.
+#[component]
+pub fn ConsumableTotalsSummary(consumable: ReadSignal<Consumable>) -> Element {
+    let user_id = use_user().ok().flatten().map(|user| user.id);
+    let today = get_today(use_timezone());
+
+    let from = use_signal(|| (today - chrono::TimeDelta::days(TOTALS_DEFAULT_DAYS - 1)).as_raw());
+    let to = use_signal(|| today.as_raw());
+    let validate_from = use_memo(move || validate_date(&from()));
+    let validate_to = use_memo(move || validate_date(&to()));
+    let disabled = use_memo(|| false);
+
+    let totals = use_resource(move || async move {
+        let (Some(user_id), Ok(start), Ok(end)) = (user_id, validate_from(), validate_to()) else {
+            return None;
+        };
+        Some(consumable_totals(user_id, consumable().id, start, end).await)
+    });
+
+    let unit = consumable().unit;
+
+    rsx! {
+        div { class: "p-4",
+            h4 { class: "font-bold", "Totals" }
+            div { class: "flex gap-2",
+                InputDate {
+                    id: "totals_from",
+                    label: "From",
+                    value: from,
+                    validate: validate_from,
+                    disabled,
+                }
+                InputDate {
+                    id: "totals_to",
+                    label: "To",
+                    value: to,
+                    validate: validate_to,
+                    disabled,
+                }
+            }
+            match totals() {
+                Some(Some(Ok(totals))) => rsx! {
+                    ConsumableTotalsView { totals, unit }
+                },
+                Some(Some(Err(err))) => rsx! {
+                    div { class: "alert alert-error",
+                        "Error loading totals: "
+                        {err.to_string()}
+                    }
+                },
+                Some(None) => rsx! {},
+                None => rsx! {
+                    p { "Loading..." }
+                },
+            }
+        }
+    }
+}
+
+#[component]
+fn ConsumableTotalsView(totals: ConsumableTotals, unit: ConsumableUnit) -> Element {
+    let zero = bigdecimal::BigDecimal::from(0);
+    // Chart the quantity unless only liquid was recorded.
+    let chart_liquid = totals.quantity == zero && totals.liquid_mls != zero;
+    let values: Vec<_> = totals
+        .days
+        .iter()
+        .map(|day| {
+            let value = if chart_liquid {
+                &day.liquid_mls
+            } else {
+                &day.quantity
+            };
+            (day.date, value.clone())
+        })
+        .collect();
+    let max = values
+        .iter()
+        .map(|(_, value)| value.clone())
+        .max()
+        .filter(|max| *max > zero);
+    let width = values.len();
+
+    rsx! {
+        p {
+            "You consumed "
+            if totals.quantity != zero {
+                "{totals.quantity.normalized()} {unit} "
+            }
+            if totals.quantity != zero && totals.liquid_mls != zero {
+                "and "
+            }
+            if totals.liquid_mls != zero {
+                "{totals.liquid_mls.normalized()} ml "
+            }
+            if totals.quantity == zero && totals.liquid_mls == zero {
+                "none "
+            }
+            "in {totals.consumptions} consumptions over the selected range."
+        }
+        if let Some(max) = max {
+            svg {
+                class: "w-full h-16 fill-current text-primary",
+                "viewBox": "0 0 {width} 100",
+                "preserveAspectRatio": "none",
+                for (n , (date , value)) in values.into_iter().enumerate() {
+                    {
+                        let height = (&value * bigdecimal::BigDecimal::from(100) / &max)
+                            .round(0)
+                            .to_i64()
+                            .unwrap_or(0);
+                        rsx! {
+                            rect {
+                                key: "{date}",
+                                x: "{n}",
+                                y: "{100 - height}",
+                                width: "0.9",
+                                height: "{height}",
+                                title { "{display_date(date)}: {value.normalized()}" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+const USED_IN_LIMIT: i64 = 20;
+
+/// The most recent consumptions that used the consumable, each linking to
+/// its day on the timeline.
+#[component]
+pub fn ConsumableUsedIn(consumable: ReadSignal<Consumable>) -> Element {
+    let timezone = use_timezone();
+    let used_in = use_resource(move || async move {
+        get_consumptions_for_consumable(consumable().id, USED_IN_LIMIT).await
+    });
+
+    let unit = consumable().unit;
+
+    rsx! {
+        div { class: "p-4",
+            h4 { class: "font-bold", "Used in" }
+            match used_in() {
+                Some(Ok(used_in)) if used_in.is_empty() => rsx! {
+                    p { "Not used in any consumptions." }
+                },
+                Some(Ok(used_in)) => rsx! {
+                    ul {
+                        for (consumption , item) in used_in {
+                            {
+                                let date = get_date_for_dt(consumption.time.to_utc(), timezone);
+                                rsx! {
+                                    li { key: "{consumption.id.as_inner()}",
+                                        Link {
+                                            to: Route::TimelineList {
+                                                date,
+                                                dialog: DialogReference::Idle,
+                                                sort: SortDirection::default(),
+                                                focus: EntryFocus(Some(EntryId::Consumption(consumption.id))),
+                                            },
+                                            class: "link",
+                                            {display_date(date)}
+                                            " "
+                                            {consumption.time.format("%H:%M").to_string()}
+                                        }
+                                        if let Some(quantity) = &item.quantity {
+                                            " "
+                                            {quantity.normalized().to_string()}
+                                            " "
+                                            {unit.to_string()}
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+                Some(Err(err)) => rsx! {
+                    div { class: "alert alert-error",
+                        "Error loading consumptions: "
+                        {err.to_string()}
+                    }
+                },
+                None => rsx! {
+                    p { "Loading..." }
+                },
+            }
+        }
     }
 }
 
@@ -864,8 +1496,12 @@ fn ConsumableNestedForm(
     });
 
     let validate = ValidateNested {
-        quantity: use_memo(move || validate_consumable_quantity(&quantity())),
-        liquid_mls: use_memo(move || validate_consumable_millilitres(&liquid_mls())),
+        quantity: use_memo(move || {
+            validate_consumable_quantity(&quantity(), consumable.read().unit)
+        }),
+        liquid_mls: use_memo(move || {
+            validate_ingredient_millilitres(&liquid_mls(), consumable.read().unit)
+        }),
         comments: use_memo(move || validate_comments(&comments())),
     };
 
@@ -906,19 +1542,24 @@ fn ConsumableNestedForm(
                     on_cancel(());
                 }
             },
-            InputNumber {
+            InputQuantity {
                 id: "quantity",
-                label: format!("Quantity ({})", consumable.read().unit.to_string()),
+                label: consumable.read().unit.quantity_label(),
+                unit: consumable.read().unit,
                 value: quantity,
                 validate: validate.quantity,
                 disabled,
             }
-            InputNumber {
-                id: "liquid_mls",
-                label: "Liquid Millilitres",
-                value: liquid_mls,
-                validate: validate.liquid_mls,
-                disabled,
+            // Still shown for units without liquid if a value needs clearing.
+            if consumable.read().unit.has_liquid() || !liquid_mls().is_empty() {
+                InputQuantity {
+                    id: "liquid_mls",
+                    label: "Liquid",
+                    unit: ConsumableUnit::Millilitres,
+                    value: liquid_mls,
+                    validate: validate.liquid_mls,
+                    disabled,
+                }
             }
             InputTextArea {
                 id: "comments",
@@ -947,16 +1588,76 @@ pub fn OrganicIcon() -> Element {
     rsx! {
         img {
             class: "w-5 dark:invert inline-block",
-            alt: "",
+            alt: "Organic",
+            title: "Organic",
             src: ORGANIC_SVG,
         }
     }
 }
 
+/// Shown when a search matched more consumables than are listed.
+#[component]
+pub fn SearchTruncatedHint() -> Element {
+    rsx! {
+        div { class: "text-sm text-warning",
+            "Showing the best {CONSUMABLE_SEARCH_LIMIT} matches, refine your search to see more."
+        }
+    }
+}
+
+/// Flags an item with no quantity, which leaves rollups incomplete.
+#[component]
+pub fn MissingQuantityIcon() -> Element {
+    rsx! {
+        span {
+            class: "text-warning",
+            title: "No quantity set, totals will be incomplete",
+            "⚠"
+        }
+    }
+}
+
+/// The consumable's thumbnail, or a generic icon if it has no image.
+///
+/// Icons are inverted in dark mode, so the thumbnail is inverted too to show
+/// it as it is.
 #[component]
-pub fn ConsumableIcon() -> Element {
+pub fn ConsumableIcon(consumable: Option<Consumable>) -> Element {
+    let thumbnail = consumable
+        .as_ref()
+        .and_then(|consumable| consumable.image_url(true));
+
     rsx! {
-        img { alt: "consumable", src: CONSUMABLE_SVG }
+        if let Some(thumbnail) = thumbnail {
+            img {
+                class: "rounded object-cover aspect-square dark:invert",
+                alt: "Consumable",
+                title: "Consumable",
+                src: thumbnail,
+                loading: "lazy",
+            }
+        } else {
+            img {
+                alt: "Consumable",
+                title: "Consumable",
+                src: CONSUMABLE_SVG,
+            }
+        }
+    }
+}
+
+/// The consumable's thumbnail, if it has an image.
+#[component]
+pub fn ConsumableThumbnail(consumable: Consumable) -> Element {
+    rsx! {
+        if let Some(thumbnail) = consumable.image_url(true) {
+            img {
+                class: "w-10 h-10 mr-2 rounded object-cover inline-block",
+                alt: "",
+                src: thumbnail,
+                loading: "lazy",
+            }
+        }
     }
 }
 
@@ -969,6 +1670,7 @@ pub fn ConsumableSummary(
 
     rsx! {
         div {
+            ConsumableThumbnail { consumable: consumable.clone() }
             if consumable.is_organic {
                 OrganicIcon {}
             }
@@ -1001,13 +1703,13 @@ pub fn ConsumableSummary(
         div {
             if let Some(created) = &consumable.created {
                 span { class: "sm:hidden", "Created: " }
-                {created.with_timezone(&Local).to_string()}
+                {format_datetime_long(&created.with_timezone(&Local).fixed_offset(), &format_prefs())}
             }
         }
         div {
             if let Some(destroyed) = &consumable.destroyed {
                 span { class: "sm:hidden", "Destroyed: " }
-                {destroyed.with_timezone(&Local).to_string()}
+                {format_datetime_long(&destroyed.with_timezone(&Local).fixed_offset(), &format_prefs())}
             }
         }
     }
@@ -1038,8 +1740,75 @@ pub fn consumable_errors(
     errors
 }
 
+fn same_char(a: char, b: char) -> bool {
+    a.to_lowercase().eq(b.to_lowercase())
+}
+
+/// Position of `needle` in `haystack`, ignoring case.
+fn find_chars(haystack: &[char], needle: &[char]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window.iter().zip(needle).all(|(a, b)| same_char(*a, *b)))
+}
+
+/// Split `text` into runs, flagging the part that matches `query` ignoring
+/// case. If the whole query does not appear, the longest run of it that does,
+/// of at least two characters, is flagged instead.
+fn split_match(text: &str, query: &str) -> Vec<(String, bool)> {
+    let text_chars = text.chars().collect::<Vec<_>>();
+    let query = query.trim().chars().collect::<Vec<_>>();
+
+    let shortest = query.len().clamp(1, 2);
+    for len in (shortest..=query.len()).rev() {
+        for window in query.windows(len) {
+            if let Some(start) = find_chars(&text_chars, window) {
+                let end = start + len;
+                return [
+                    (&text_chars[..start], false),
+                    (&text_chars[start..end], true),
+                    (&text_chars[end..], false),
+                ]
+                .into_iter()
+                .filter(|(chars, _)| !chars.is_empty())
+                .map(|(chars, matched)| (chars.iter().collect(), matched))
+                .collect();
+            }
+        }
+    }
+
+    vec![(text.to_string(), false)]
+}
+
+/// Text with the part matching a search query in bold.
+#[component]
+pub fn Highlighted(text: String, query: Option<String>) -> Element {
+    let parts = split_match(&text, query.as_deref().unwrap_or_default());
+
+    rsx! {
+        for (part , matched) in parts {
+            if matched {
+                b { {part} }
+            } else {
+                {part}
+            }
+        }
+    }
+}
+
 #[component]
-pub fn ConsumableLabel(consumable: Consumable) -> Element {
+pub fn ConsumableLabel(
+    consumable: Consumable,
+    query: Option<String>,
+    /// Show comments as a short preview that can be expanded, for pickers.
+    compact: Option<bool>,
+) -> Element {
+    let expanded = use_signal(|| false);
+    let preview = consumable
+        .comments
+        .as_deref()
+        .filter(|_| compact.unwrap_or(false) && !expanded())
+        .and_then(|comments| comments_preview(comments, COMMENTS_PREVIEW_CHARS));
+
     rsx! {
         if consumable.is_organic {
             div {
@@ -1047,27 +1816,58 @@ pub fn ConsumableLabel(consumable: Consumable) -> Element {
                 "Organic"
             }
         }
-        div { {consumable.name.clone()} }
+        div {
+            Highlighted { text: consumable.name.clone(), query: query.clone() }
+        }
         if let Some(brand) = &consumable.brand {
-            div { {brand.clone()} }
+            div {
+                Highlighted { text: brand.clone(), query }
+            }
         }
         if let Some(dt) = &consumable.created {
-            div { {dt.with_timezone(&Local).format("%Y-%m-%d").to_string()} }
+            div { {format_prefs().format_date(dt.with_timezone(&Local).date_naive())} }
         }
         if let Some(dt) = &consumable.destroyed {
             div {
                 "Destroyed: "
-                {dt.with_timezone(&Local).format("%Y-%m-%d").to_string()}
+                {format_prefs().format_date(dt.with_timezone(&Local).date_naive())}
             }
         }
-        if let Some(comments) = &consumable.comments {
+        if let Some((preview, truncated)) = preview {
+            div { class: "text-sm opacity-70",
+                "{preview}"
+                if truncated {
+                    ExpandCommentsButton { expanded, label: "more" }
+                }
+            }
+        } else if let Some(comments) = &consumable.comments {
             Markdown { content: comments.to_string() }
+            if compact.unwrap_or(false) && expanded() {
+                ExpandCommentsButton { expanded, label: "less" }
+            }
+        }
+    }
+}
+
+/// Toggles a compact label's comments without selecting the option it is in.
+#[component]
+fn ExpandCommentsButton(expanded: Signal<bool>, label: &'static str) -> Element {
+    rsx! {
+        button {
+            r#type: "button",
+            class: "btn btn-link btn-xs",
+            onclick: move |e| {
+                e.stop_propagation();
+                expanded.set(!expanded());
+            },
+            "{label}"
         }
     }
 }
 
 #[component]
 pub fn ConsumableItemSummary(item: ConsumableItem) -> Element {
+    let quantity_specified = item.is_quantity_specified();
     let mut quantity_list = Vec::new();
 
     if let Some(quantity) = item.nested.quantity {
@@ -1092,6 +1892,9 @@ pub fn ConsumableItemSummary(item: ConsumableItem) -> Element {
     rsx! {
         div {
             div {
+                if !quantity_specified {
+                    MissingQuantityIcon {}
+                }
                 if quantity_list.is_empty() {
                     {}
                 } else {
@@ -1130,6 +1933,47 @@ pub fn ConsumableUnitIcon(consumable_unit: ConsumableUnit) -> Element {
         ConsumableUnit::Number => "#",
     };
     rsx! {
-        div { class: "text-sm w-10 dark:invert inline-block", {text} }
+        div {
+            class: "text-sm w-10 dark:invert inline-block",
+            title: consumable_unit.as_title(),
+            {text}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn highlights_case_insensitive_match() {
+        assert_eq!(
+            split_match("Greek Yoghurt", "YOG"),
+            vec![
+                ("Greek ".to_string(), false),
+                ("Yog".to_string(), true),
+                ("hurt".to_string(), false)
+            ]
+        );
+    }
+
+    #[test]
+    fn highlights_longest_partial_match() {
+        assert_eq!(
+            split_match("Yoghurt", "yogurt"),
+            vec![("Yog".to_string(), true), ("hurt".to_string(), false)]
+        );
+    }
+
+    #[test]
+    fn leaves_unmatched_text_alone() {
+        assert_eq!(
+            split_match("Apple", "xyz"),
+            vec![("Apple".to_string(), false)]
+        );
+        assert_eq!(
+            split_match("Apple", " "),
+            vec![("Apple".to_string(), false)]
+        );
     }
 }
@@ -5,60 +5,81 @@ use itertools::intersperse;
 
 use crate::{
     components::{
-        consumables::{self, ConsumableLabel, ConsumableUpdate, ConsumableUpdateIngredients},
+        consumables::{
+            self, ConsumableLabel, ConsumableUpdate, ConsumableUpdateIngredients,
+            MissingQuantityIcon, SearchTruncatedHint,
+        },
         events::{EventDateTimeShort, Markdown},
-        times::time_delta_to_string,
+        times::time_delta_to_string_rounded,
     },
     forms::{
         Dialog, EditError, FieldValue, FormCloseButton, FormDeleteButton, FormEditButton,
-        FormSaveCancelButton, InputConsumable, InputConsumptionType, InputDateTime, InputDuration,
-        InputNumber, InputTextArea, Saving, ValidationError, validate_comments,
-        validate_consumable_millilitres, validate_consumable_quantity, validate_consumption_type,
-        validate_duration, validate_fixed_offset_date_time,
+        FormSaveCancelButton, InputBoolean, InputConsumable, InputConsumptionRoute, InputDateTime,
+        InputDuration, InputNumber, InputQuantity, InputTextArea, Saving, UnsavedChanges,
+        ValidationError, use_dirty, validate_comments, validate_consumable_millilitres,
+        validate_consumable_quantity, validate_consumption_portion, validate_consumption_route,
+        validate_duration, validate_fixed_offset_date_time, validate_ingredient_millilitres,
     },
-    functions::consumptions::{
-        create_consumption, create_consumption_consumable, delete_consumption,
-        delete_consumption_consumable, get_child_consumables, update_consumption,
-        update_consumption_consumable,
+    functions::{
+        consumables::search_consumables,
+        consumptions::{
+            create_consumption, create_consumption_consumable, create_consumption_consumables,
+            delete_consumption, delete_consumption_consumable, get_child_consumables,
+            get_consumptions_for_time_range, move_consumption_items, update_consumption,
+            update_consumption_consumable,
+        },
     },
+    i18n::t,
     models::{
-        ChangeConsumption, ChangeConsumptionConsumable, Consumable, Consumption,
-        ConsumptionConsumable, ConsumptionConsumableId, ConsumptionItem, ConsumptionType, MaybeSet,
-        NewConsumption, NewConsumptionConsumable, UserId,
+        ChangeConsumption, ChangeConsumptionConsumable, Consumable, ConsumableId,
+        ConsumableSearchResults, ConsumableUnit, Consumption, ConsumptionConsumable,
+        ConsumptionConsumableId, ConsumptionId, ConsumptionIntake, ConsumptionItem,
+        ConsumptionRoute, ConsumptionType, ConsumptionWithItems, EntryKind, LiquidBalance,
+        MaybeSet, NewConsumption, NewConsumptionConsumable, RangeOptions, SortDirection, UserId,
     },
+    use_consumption_types, use_duration_rounding, use_timezone,
 };
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Operation {
-    Create { user_id: UserId },
-    Update { consumption: Consumption },
+    Create {
+        user_id: UserId,
+        template: Option<NewConsumption>,
+    },
+    Update {
+        consumption: Consumption,
+    },
 }
 
 #[derive(Debug, Clone)]
 struct Validate {
     time: Memo<Result<DateTime<FixedOffset>, ValidationError>>,
     duration: Memo<Result<TimeDelta, ValidationError>>,
-    consumption_type: Memo<Result<ConsumptionType, ValidationError>>,
+    route: Memo<Result<ConsumptionRoute, ValidationError>>,
     liquid_mls: Memo<Result<Option<bigdecimal::BigDecimal>, ValidationError>>,
     comments: Memo<Result<Option<String>, ValidationError>>,
+    planned: Memo<Result<bool, ValidationError>>,
 }
 
 async fn do_save(op: &Operation, validate: &Validate) -> Result<Consumption, EditError> {
     let time = validate.time.read().clone()?;
     let duration = validate.duration.read().clone()?;
-    let consumption_type = validate.consumption_type.read().clone()?;
+    let route = validate.route.read().clone()?;
     let liquid_mls = validate.liquid_mls.read().clone()?;
     let comments = validate.comments.read().clone()?;
+    let planned = validate.planned.read().clone()?;
 
     match op {
-        Operation::Create { user_id } => {
+        Operation::Create { user_id, .. } => {
             let updates = NewConsumption {
                 user_id: *user_id,
                 time,
                 duration,
                 liquid_mls,
                 comments,
-                consumption_type,
+                consumption_type: route.consumption_type(),
+                custom_type_id: route.custom_type_id(),
+                planned,
             };
             create_consumption(updates).await.map_err(EditError::Server)
         }
@@ -67,9 +88,11 @@ async fn do_save(op: &Operation, validate: &Validate) -> Result<Consumption, Edi
                 user_id: MaybeSet::NoChange,
                 time: MaybeSet::Set(time),
                 duration: MaybeSet::Set(duration),
-                consumption_type: MaybeSet::Set(consumption_type),
+                consumption_type: MaybeSet::Set(route.consumption_type()),
+                custom_type_id: MaybeSet::Set(route.custom_type_id()),
                 liquid_mls: MaybeSet::Set(liquid_mls),
                 comments: MaybeSet::Set(comments),
+                planned: MaybeSet::Set(planned),
             };
             update_consumption(consumption.id, changes)
                 .await
@@ -90,33 +113,75 @@ pub fn ConsumptionUpdate(
     });
 
     let duration = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(consumption),
+            ..
+        } => consumption.duration.as_raw(),
         Operation::Create { .. } => String::new(),
         Operation::Update { consumption } => consumption.duration.as_raw(),
     });
 
-    let consumption_type = use_signal(|| match &op {
+    let consumption_types = use_consumption_types();
+    let route = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(consumption),
+            ..
+        } => Some(ConsumptionRoute::for_type(
+            consumption.consumption_type,
+            consumption.custom_type_id,
+            &consumption_types,
+        )),
         Operation::Create { .. } => None,
-        Operation::Update { consumption } => Some(consumption.consumption_type),
+        Operation::Update { consumption } => Some(ConsumptionRoute::for_consumption(
+            consumption,
+            &consumption_types,
+        )),
     });
 
     let liquid_mls = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(consumption),
+            ..
+        } => consumption.liquid_mls.as_raw(),
         Operation::Create { .. } => String::new(),
         Operation::Update { consumption } => consumption.liquid_mls.as_raw(),
     });
 
     let comments = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(consumption),
+            ..
+        } => consumption.comments.as_raw(),
         Operation::Create { .. } => String::new(),
         Operation::Update { consumption } => consumption.comments.as_raw(),
     });
 
+    let planned = use_signal(|| match &op {
+        Operation::Create { .. } => false,
+        Operation::Update { consumption } => consumption.planned,
+    });
+
+    let timezone = use_timezone();
     let validate = Validate {
-        time: use_memo(move || validate_fixed_offset_date_time(&time())),
+        time: use_memo(move || validate_fixed_offset_date_time(&time(), timezone)),
         duration: use_memo(move || validate_duration(&duration())),
-        consumption_type: use_memo(move || validate_consumption_type(consumption_type())),
+        route: use_memo(move || validate_consumption_route(route())),
         liquid_mls: use_memo(move || validate_consumable_millilitres(&liquid_mls())),
         comments: use_memo(move || validate_comments(&comments())),
+        planned: use_memo(move || Ok(planned())),
     };
 
+    let dirty = use_dirty(move || {
+        (
+            time(),
+            duration(),
+            route(),
+            liquid_mls(),
+            comments(),
+            planned(),
+        )
+    });
+
     let mut saving = use_signal(|| Saving::No);
 
     // disable form while waiting for response
@@ -124,9 +189,10 @@ pub fn ConsumptionUpdate(
     let disabled_save = use_memo(move || {
         validate.time.read().is_err()
             || validate.duration.read().is_err()
-            || validate.consumption_type.read().is_err()
+            || validate.route.read().is_err()
             || validate.liquid_mls.read().is_err()
             || validate.comments.read().is_err()
+            || validate.planned.read().is_err()
             || disabled()
     });
 
@@ -143,6 +209,7 @@ pub fn ConsumptionUpdate(
             match result {
                 Ok(consumable) => {
                     saving.set(Saving::Finished(Ok(())));
+                    dirty.reset();
                     on_save(consumable);
                 }
                 Err(err) => saving.set(Saving::Finished(Err(err))),
@@ -153,11 +220,12 @@ pub fn ConsumptionUpdate(
     rsx! {
         h3 { class: "text-lg font-bold",
             match &op {
-                Operation::Create { .. } => "Create Consumption".to_string(),
+                Operation::Create { .. } => t("Create Consumption").to_string(),
                 Operation::Update { consumption } => {
-                    format!("Edit Consumption {}", consumption.name())
+                    format!("{} {}", t("Edit Consumption"), consumption.name())
                 }
             }
+            UnsavedChanges { dirty: dirty.is_dirty() }
         }
         p { class: "py-4", "Press ESC key or click the button below to close" }
         form {
@@ -183,17 +251,19 @@ pub fn ConsumptionUpdate(
                 start_time: validate.time,
                 validate: validate.duration,
                 disabled,
+                kind: EntryKind::Consumption,
             }
-            InputConsumptionType {
+            InputConsumptionRoute {
                 id: "consumption_type",
                 label: "Type",
-                value: consumption_type,
-                validate: validate.consumption_type,
+                value: route,
+                validate: validate.route,
                 disabled,
             }
-            InputNumber {
+            InputQuantity {
                 id: "liquid_mls",
-                label: "Liquid Millilitres",
+                label: "Liquid",
+                unit: ConsumableUnit::Millilitres,
                 value: liquid_mls,
                 validate: validate.liquid_mls,
                 disabled,
@@ -205,13 +275,19 @@ pub fn ConsumptionUpdate(
                 validate: validate.comments,
                 disabled,
             }
+            InputBoolean {
+                id: "planned",
+                label: "Planned",
+                value: planned,
+                disabled,
+            }
             FormSaveCancelButton {
                 disabled: disabled_save,
                 on_save: move |()| on_save(()),
                 on_cancel: move |_| on_cancel(()),
                 title: match &op {
-                    Operation::Create { .. } => "Create",
-                    Operation::Update { .. } => "Save",
+                    Operation::Create { .. } => t("Create"),
+                    Operation::Update { .. } => t("Save"),
                 },
                 saving,
             }
@@ -219,13 +295,26 @@ pub fn ConsumptionUpdate(
     }
 }
 
+/// Delete a consumption, returning it along with its ingredients so that it
+/// can be restored.
+async fn delete_with_items(
+    consumption: Consumption,
+) -> Result<ConsumptionWithItems, ServerFnError> {
+    let items = get_child_consumables(consumption.id).await?;
+    delete_consumption(consumption.id).await?;
+    Ok(ConsumptionWithItems { consumption, items })
+}
+
 #[component]
 pub fn ConsumptionDelete(
     consumption: Consumption,
     on_cancel: Callback,
-    on_delete: Callback<Consumption>,
+    on_delete: Callback<ConsumptionWithItems>,
 ) -> Element {
     let mut saving = use_signal(|| Saving::No);
+    let consumption_id = consumption.id;
+    let items = use_resource(move || async move { get_child_consumables(consumption_id).await });
+    let loaded_items = items.read().clone();
 
     let disabled = use_memo(move || saving.read().is_saving());
 
@@ -235,10 +324,10 @@ pub fn ConsumptionDelete(
         spawn(async move {
             saving.set(Saving::Yes);
 
-            match delete_consumption(consumption.id).await {
-                Ok(_) => {
+            match delete_with_items(consumption).await {
+                Ok(deleted) => {
                     saving.set(Saving::Finished(Ok(())));
-                    on_delete(consumption.clone());
+                    on_delete(deleted);
                 }
                 Err(err) => saving.set(Saving::Finished(Err(EditError::Server(err)))),
             }
@@ -251,7 +340,24 @@ pub fn ConsumptionDelete(
             {consumption.name()}
         }
         p { class: "py-4", "Press ESC key or click the button below to close" }
-        ConsumptionSummary { consumption: consumption.clone() }
+        ConsumptionSummary {
+            consumption: consumption.clone(),
+            consumption_consumables: loaded_items.clone().and_then(Result::ok),
+        }
+        match loaded_items {
+            Some(Ok(items)) => rsx! {
+                ConsumptionItemList { list: items }
+            },
+            Some(Err(err)) => rsx! {
+                div { class: "alert alert-error",
+                    "Error loading ingredients: "
+                    {err.to_string()}
+                }
+            },
+            None => rsx! {
+                p { class: "alert alert-info", "Loading ingredients..." }
+            },
+        }
         form {
             novalidate: true,
             action: "javascript:void(0)",
@@ -291,13 +397,31 @@ pub fn ConsumptionTypeIcon(consumption_type: ConsumptionType) -> Element {
     };
     let alt = consumption_type.as_title();
     rsx! {
-        img { alt, src: icon }
+        img { alt, title: alt, src: icon }
+    }
+}
+
+/// Custom types show their own icon text. It sits inside an icon container
+/// that inverts images in dark mode, so the text is inverted back.
+#[component]
+pub fn ConsumptionRouteIcon(route: ConsumptionRoute) -> Element {
+    match route {
+        ConsumptionRoute::BuiltIn(consumption_type) => rsx! {
+            ConsumptionTypeIcon { consumption_type }
+        },
+        ConsumptionRoute::Custom(custom) => rsx! {
+            span {
+                class: "text-2xl dark:invert",
+                title: "{custom.name}",
+                "{custom.icon}"
+            }
+        },
     }
 }
 
 #[component]
 pub fn consumption_duration(duration: chrono::TimeDelta) -> Element {
-    let text = time_delta_to_string(duration);
+    let text = time_delta_to_string_rounded(duration, use_duration_rounding());
 
     rsx! {
         if duration.num_seconds() < 2 {
@@ -326,7 +450,7 @@ pub fn ConsumptionDialog(
     dialog: ActiveDialog,
     on_change: Callback<Consumption>,
     on_change_ingredients: Callback<Consumption>,
-    on_delete: Callback<Consumption>,
+    on_delete: Callback<ConsumptionWithItems>,
     show_update_basic: Callback<Consumption>,
     show_update_ingredients: Callback<Consumption>,
     show_ingredient_update_basic: Callback<(Consumption, Consumable)>,
@@ -381,6 +505,7 @@ pub fn ConsumptionDialog(
         ActiveDialog::NestedIngredient(parent, consumable) => {
             let parent_clone_1 = parent.clone();
             let parent_clone_2 = parent.clone();
+            let parent_clone_3 = parent.clone();
             rsx! {
                 Dialog {
                     ConsumableUpdate {
@@ -393,6 +518,9 @@ pub fn ConsumptionDialog(
                             on_change_ingredients(parent.clone());
                             show_ingredient_update_ingredients((parent_clone_2.clone(), consumable.clone()));
                         },
+                        on_open_existing: move |consumable: Consumable| {
+                            show_ingredient_update_basic((parent_clone_3.clone(), consumable));
+                        },
                     }
                 }
             }
@@ -450,12 +578,17 @@ pub fn ConsumptionUpdateIngredients(
     let mut consumption_consumables =
         use_resource(move || async move { get_child_consumables(consumption().id).await });
 
+    // The consumption as saved by reconciling its liquid, until the dialog
+    // is given a different one.
+    let mut reconciled: Signal<Option<Consumption>> = use_signal(|| None);
+
     use_effect(move || {
         let _trigger = consumption();
         selected_consumable.set(None);
+        reconciled.set(None);
     });
 
-    let consumption = consumption();
+    let consumption = reconciled().unwrap_or_else(|| consumption.cloned());
 
     let consumption_clone = consumption.clone();
     let consumption_clone_3 = consumption.clone();
@@ -495,6 +628,52 @@ pub fn ConsumptionUpdateIngredients(
         });
     });
 
+    let mut batch_form = use_signal(|| false);
+    let consumption_clone_7 = consumption.clone();
+    let add_consumables = use_callback(move |children: Vec<Consumable>| {
+        let consumption = consumption_clone_7.clone();
+        let existing: Vec<_> = match consumption_consumables.read().as_ref() {
+            Some(Ok(list)) => list.iter().map(|cc| cc.consumable.id).collect(),
+            _ => Vec::new(),
+        };
+        let child_ids = children
+            .iter()
+            .map(|child| child.id)
+            .filter(|id| !existing.contains(id))
+            .collect::<Vec<_>>();
+
+        spawn(async move {
+            state.set(State::Saving);
+            let result = create_consumption_consumables(consumption.id, child_ids)
+                .await
+                .map(|_created| ());
+            if result.is_ok() {
+                batch_form.set(false);
+            }
+            state.set(State::Finished(result));
+            consumption_consumables.restart();
+            on_change(consumption.clone());
+        });
+    });
+
+    let mut move_form = use_signal(|| false);
+    let consumption_clone_8 = consumption.clone();
+    let move_items = use_callback(
+        move |(to_id, consumable_ids): (ConsumptionId, Vec<ConsumableId>)| {
+            let consumption = consumption_clone_8.clone();
+            spawn(async move {
+                state.set(State::Saving);
+                let result = move_consumption_items(consumption.id, to_id, consumable_ids).await;
+                if result.is_ok() {
+                    move_form.set(false);
+                }
+                state.set(State::Finished(result));
+                consumption_consumables.restart();
+                on_change(consumption.clone());
+            });
+        },
+    );
+
     let remove_consumable = use_callback(move |child: ConsumptionConsumable| {
         let consumption_clone = consumption_clone_4.clone();
         spawn(async move {
@@ -524,15 +703,29 @@ pub fn ConsumptionUpdateIngredients(
 
         if !create_form() {
             match consumption_consumables() {
-                Some(Ok(consumption_consumables)) => {
+                Some(Ok(items)) => {
                     rsx! {
                         ConsumptionSummary {
                             consumption: consumption.clone(),
-                            consumption_consumables: Some(consumption_consumables.clone()),
+                            consumption_consumables: Some(items.clone()),
+                        }
+                        LiquidReconcile {
+                            consumption: ConsumptionWithItems {
+                                consumption: consumption.clone(),
+                                items: items.clone(),
+                            },
+                            on_consumption_change: move |consumption: Consumption| {
+                                reconciled.set(Some(consumption.clone()));
+                                on_change(consumption);
+                            },
+                            on_items_change: move |consumption: Consumption| {
+                                consumption_consumables.restart();
+                                on_change(consumption);
+                            },
                         }
                         div { class: "p-4",
                             ul {
-                                for item in consumption_consumables {
+                                for item in items {
                                     li {
                                         class: "p-4 mb-1 bg-gray-700 border-2 rounded-lg",
                                         class: if is_selected(&item) { "border-gray-50 text-gray-50" } else { "border-gray-500" },
@@ -637,6 +830,24 @@ pub fn ConsumptionUpdateIngredients(
                     }
                 }
             }
+        } else if batch_form() {
+            div { class: "p-4",
+                ConsumableMultiSelect {
+                    disabled,
+                    on_select: add_consumables,
+                    on_cancel: move |()| batch_form.set(false),
+                }
+            }
+        } else if let (true, Some(Ok(items))) = (move_form(), consumption_consumables()) {
+            div { class: "p-4",
+                MoveItemsForm {
+                    consumption: consumption.clone(),
+                    items,
+                    disabled,
+                    on_move: move_items,
+                    on_cancel: move |()| move_form.set(false),
+                }
+            }
         } else {
             div { class: "p-4",
                 InputConsumable {
@@ -657,6 +868,20 @@ pub fn ConsumptionUpdateIngredients(
                     disabled,
                 }
                 if !create_form() {
+                    FormEditButton {
+                        title: "Add Multiple",
+                        on_edit: move || {
+                            batch_form.set(true);
+                        },
+                    }
+                    if matches!(consumption_consumables(), Some(Ok(items)) if !items.is_empty()) {
+                        FormEditButton {
+                            title: "Move to…",
+                            on_edit: move || {
+                                move_form.set(true);
+                            },
+                        }
+                    }
                     FormEditButton {
                         title: "Edit",
                         on_edit: move || {
@@ -675,10 +900,245 @@ pub fn ConsumptionUpdateIngredients(
     }
 }
 
+/// Pick ingredients and another consumption from around the same time to
+/// move them to.
+#[component]
+fn MoveItemsForm(
+    consumption: Consumption,
+    items: Vec<ConsumptionItem>,
+    disabled: Memo<bool>,
+    on_move: Callback<(ConsumptionId, Vec<ConsumableId>)>,
+    on_cancel: Callback<()>,
+) -> Element {
+    let mut selected: Signal<Vec<ConsumableId>> = use_signal(Vec::new);
+    let mut target: Signal<Option<ConsumptionId>> = use_signal(|| None);
+    let consumption_types = use_consumption_types();
+
+    let id = consumption.id;
+    let user_id = consumption.user_id;
+    let time = consumption.time.to_utc();
+    let candidates = use_resource(move || async move {
+        let options = RangeOptions {
+            sort: Some(SortDirection::Descending),
+            limit: None,
+        };
+        get_consumptions_for_time_range(
+            user_id,
+            time - TimeDelta::days(1),
+            time + TimeDelta::days(1),
+            options,
+        )
+        .await
+        .map(|list| {
+            list.into_iter()
+                .filter(|candidate| candidate.consumption.id != id)
+                .collect::<Vec<_>>()
+        })
+    });
+
+    let mut toggle = move |consumable_id: ConsumableId| {
+        let mut selected = selected.write();
+        if let Some(index) = selected.iter().position(|id| *id == consumable_id) {
+            selected.remove(index);
+        } else {
+            selected.push(consumable_id);
+        }
+    };
+
+    let disabled_move =
+        use_memo(move || selected.read().is_empty() || target.read().is_none() || disabled());
+
+    rsx! {
+        div {
+            h4 { class: "font-bold", "Ingredients" }
+            ul {
+                for item in items {
+                    li {
+                        key: "{item.consumable.id}",
+                        class: "p-2 flex gap-2 items-center cursor-pointer",
+                        onclick: move |_e| toggle(item.consumable.id),
+                        input {
+                            r#type: "checkbox",
+                            class: "checkbox",
+                            checked: selected.read().contains(&item.consumable.id),
+                            disabled: disabled(),
+                        }
+                        {item.consumable.name.clone()}
+                    }
+                }
+            }
+            h4 { class: "font-bold", "Move to" }
+            match candidates() {
+                Some(Ok(list)) if !list.is_empty() => rsx! {
+                    ul {
+                        for candidate in list {
+                            {
+                                let candidate_id = candidate.consumption.id;
+                                let route = ConsumptionRoute::for_consumption(
+                                    &candidate.consumption,
+                                    &consumption_types,
+                                );
+                                let names = candidate
+                                    .items
+                                    .iter()
+                                    .map(|item| item.consumable.name.clone())
+                                    .collect::<Vec<_>>()
+                                    .join(", ");
+                                rsx! {
+                                    li {
+                                        key: "{candidate_id}",
+                                        class: "p-2 flex gap-2 items-center cursor-pointer",
+                                        onclick: move |_e| target.set(Some(candidate_id)),
+                                        input {
+                                            r#type: "radio",
+                                            class: "radio",
+                                            checked: target() == Some(candidate_id),
+                                            disabled: disabled(),
+                                        }
+                                        div {
+                                            EventDateTimeShort { time: candidate.consumption.time }
+                                            " {route.as_title()}"
+                                            div { class: "text-sm opacity-70", "{names}" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+                Some(Ok(_)) => rsx! {
+                    div { class: "alert alert-info", "No other consumptions within a day." }
+                },
+                Some(Err(err)) => rsx! {
+                    div { class: "alert alert-error",
+                        "Error loading consumptions: "
+                        {err.to_string()}
+                    }
+                },
+                None => rsx! {
+                    div { class: "alert alert-info", "Loading..." }
+                },
+            }
+            button {
+                r#type: "button",
+                class: "w-full btn btn-primary my-2",
+                disabled: disabled_move(),
+                onclick: move |_e| {
+                    if let Some(to_id) = target() {
+                        on_move((to_id, selected()));
+                    }
+                },
+                "Move {selected.read().len()} selected"
+            }
+            FormCloseButton { title: "Cancel", on_close: move || on_cancel(()) }
+        }
+    }
+}
+
+#[component]
+fn ConsumableMultiSelect(
+    disabled: Memo<bool>,
+    on_select: Callback<Vec<Consumable>>,
+    on_cancel: Callback<()>,
+) -> Element {
+    let mut search = use_signal(String::new);
+    let mut selected: Signal<Vec<Consumable>> = use_signal(Vec::new);
+
+    let results = use_resource(move || async move {
+        let query = search();
+        if query.is_empty() {
+            Ok(ConsumableSearchResults::default())
+        } else {
+            search_consumables(query, false, false).await
+        }
+    });
+
+    let mut toggle = move |consumable: Consumable| {
+        let mut selected = selected.write();
+        if let Some(index) = selected.iter().position(|c| c.id == consumable.id) {
+            selected.remove(index);
+        } else {
+            selected.push(consumable);
+        }
+    };
+
+    let is_selected =
+        move |consumable: &Consumable| selected.read().iter().any(|c| c.id == consumable.id);
+
+    let disabled_add = use_memo(move || selected.read().is_empty() || disabled());
+
+    rsx! {
+        div {
+            for consumable in selected() {
+                div {
+                    key: "{consumable.id}",
+                    class: "bg-green-500 rounded-sm border-green-100 text-white p-2 mb-2",
+                    onclick: move |_e| toggle(consumable.clone()),
+                    {consumable.name.clone()}
+                }
+            }
+            input {
+                r#type: "text",
+                class: "input w-full",
+                placeholder: "Search consumables",
+                value: search(),
+                disabled: disabled(),
+                oninput: move |e| search.set(e.value()),
+            }
+            match results() {
+                Some(Ok(results)) => rsx! {
+                    ul {
+                        for consumable in results.consumables {
+                            li {
+                                key: "{consumable.id}",
+                                class: "p-2 flex gap-2 items-center cursor-pointer",
+                                onclick: {
+                                    let consumable = consumable.clone();
+                                    move |_e| toggle(consumable.clone())
+                                },
+                                input {
+                                    r#type: "checkbox",
+                                    class: "checkbox",
+                                    checked: is_selected(&consumable),
+                                    disabled: disabled(),
+                                }
+                                ConsumableLabel {
+                                    consumable: consumable.clone(),
+                                    query: search(),
+                                    compact: true,
+                                }
+                            }
+                        }
+                    }
+                    if results.truncated {
+                        SearchTruncatedHint {}
+                    }
+                },
+                Some(Err(err)) => rsx! {
+                    div { class: "alert alert-error",
+                        "Error loading consumables: "
+                        {err.to_string()}
+                    }
+                },
+                None => rsx! {},
+            }
+            button {
+                r#type: "button",
+                class: "w-full btn btn-primary my-2",
+                disabled: disabled_add(),
+                onclick: move |_e| on_select(selected()),
+                "Add {selected.read().len()} selected"
+            }
+            FormCloseButton { title: "Cancel", on_close: move || on_cancel(()) }
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 struct ValidateConsumption {
     quantity: Memo<Result<Option<bigdecimal::BigDecimal>, ValidationError>>,
     liquid_mls: Memo<Result<Option<bigdecimal::BigDecimal>, ValidationError>>,
+    portion: Memo<Result<bigdecimal::BigDecimal, ValidationError>>,
     comments: Memo<Result<Option<String>, ValidationError>>,
 }
 
@@ -688,11 +1148,13 @@ async fn do_save_consumption(
 ) -> Result<ConsumptionConsumable, EditError> {
     let quantity = validate.quantity.read().clone()?;
     let liquid_mls = validate.liquid_mls.read().clone()?;
+    let portion = validate.portion.read().clone()?;
     let comments = validate.comments.read().clone()?;
 
     let updates = ChangeConsumptionConsumable {
         quantity: MaybeSet::Set(quantity),
         liquid_mls: MaybeSet::Set(liquid_mls),
+        portion: MaybeSet::Set(portion),
         comments: MaybeSet::Set(comments),
     };
     update_consumption_consumable(consumption.id, updates)
@@ -709,18 +1171,25 @@ fn ConsumableConsumptionForm(
 ) -> Element {
     let mut quantity = use_signal(|| consumption.read().quantity.as_raw());
     let mut liquid_mls = use_signal(|| consumption.read().liquid_mls.as_raw());
+    let mut portion = use_signal(|| consumption.read().portion.as_raw());
     let mut comments = use_signal(|| consumption.read().comments.as_raw());
 
     use_effect(move || {
         let nested = consumption.read();
         quantity.set(nested.quantity.as_raw());
         liquid_mls.set(nested.liquid_mls.as_raw());
+        portion.set(nested.portion.as_raw());
         comments.set(nested.comments.as_raw());
     });
 
     let validate = ValidateConsumption {
-        quantity: use_memo(move || validate_consumable_quantity(&quantity())),
-        liquid_mls: use_memo(move || validate_consumable_millilitres(&liquid_mls())),
+        quantity: use_memo(move || {
+            validate_consumable_quantity(&quantity(), consumable.read().unit)
+        }),
+        liquid_mls: use_memo(move || {
+            validate_ingredient_millilitres(&liquid_mls(), consumable.read().unit)
+        }),
+        portion: use_memo(move || validate_consumption_portion(&portion())),
         comments: use_memo(move || validate_comments(&comments())),
     };
 
@@ -730,6 +1199,7 @@ fn ConsumableConsumptionForm(
     let disabled_save = use_memo(move || {
         validate.quantity.read().is_err()
             || validate.liquid_mls.read().is_err()
+            || validate.portion.read().is_err()
             || validate.comments.read().is_err()
             || disabled()
     });
@@ -761,19 +1231,32 @@ fn ConsumableConsumptionForm(
                     on_cancel(());
                 }
             },
-            InputNumber {
+            InputQuantity {
                 id: "quantity",
-                label: format!("Quantity ({})", consumable.read().unit.to_string()),
+                label: consumable.read().unit.quantity_label(),
+                unit: consumable.read().unit,
                 value: quantity,
                 validate: validate.quantity,
                 disabled,
             }
+            // Still shown for units without liquid if a value needs clearing.
+            if consumable.read().unit.has_liquid() || !liquid_mls().is_empty() {
+                InputQuantity {
+                    id: "liquid_mls",
+                    label: "Liquid",
+                    unit: ConsumableUnit::Millilitres,
+                    value: liquid_mls,
+                    validate: validate.liquid_mls,
+                    disabled,
+                }
+            }
             InputNumber {
-                id: "liquid_mls",
-                label: "Liquid Millilitres",
-                value: liquid_mls,
-                validate: validate.liquid_mls,
+                id: "portion",
+                label: "Portion",
+                value: portion,
+                validate: validate.portion,
                 disabled,
+                step: bigdecimal::BigDecimal::new(25.into(), 2),
             }
             InputTextArea {
                 id: "comments",
@@ -800,9 +1283,10 @@ pub fn ConsumptionSummary(
     consumption_consumables: Option<Vec<ConsumptionItem>>,
 ) -> Element {
     let errors = consumption_errors(&consumption, consumption_consumables.as_ref());
+    let route = ConsumptionRoute::for_consumption(&consumption, &use_consumption_types());
 
     rsx! {
-        div { {consumption.consumption_type.as_title()} }
+        div { {route.as_title()} }
         div {
             EventDateTimeShort { time: consumption.time }
         }
@@ -830,6 +1314,100 @@ pub fn ConsumptionSummary(
     }
 }
 
+/// Offers to make the consumption's liquid and its ingredients' liquid agree,
+/// taking either one as authoritative.
+#[component]
+fn LiquidReconcile(
+    consumption: ConsumptionWithItems,
+    on_consumption_change: Callback<Consumption>,
+    on_items_change: Callback<Consumption>,
+) -> Element {
+    let mut state = use_signal(|| State::Idle);
+    let disabled = State::Saving == *state.read();
+
+    let balance = consumption.liquid_balance();
+    if balance.is_balanced() {
+        return rsx! {};
+    }
+
+    let distributed = consumption.distribute_liquid_mls();
+    let can_distribute = distributed.is_some();
+    let items_mls = balance.items_mls.clone();
+    let parent = consumption.consumption.clone();
+    let id = parent.id;
+
+    let use_items_total = move |_| {
+        let liquid_mls = Some(items_mls.clone()).filter(|mls| *mls > 0);
+        spawn(async move {
+            state.set(State::Saving);
+            let updates = ChangeConsumption {
+                liquid_mls: MaybeSet::Set(liquid_mls),
+                ..Default::default()
+            };
+            match update_consumption(id, updates).await {
+                Ok(consumption) => {
+                    state.set(State::Idle);
+                    on_consumption_change(consumption);
+                }
+                Err(err) => state.set(State::Finished(Err(err))),
+            }
+        });
+    };
+
+    let use_consumption_total = move |_| {
+        let distributed = distributed.clone().unwrap_or_default();
+        let parent = parent.clone();
+        spawn(async move {
+            state.set(State::Saving);
+            for (id, liquid_mls) in distributed {
+                let updates = ChangeConsumptionConsumable {
+                    liquid_mls: MaybeSet::Set(Some(liquid_mls)),
+                    ..Default::default()
+                };
+                if let Err(err) = update_consumption_consumable(id, updates).await {
+                    state.set(State::Finished(Err(err)));
+                    on_items_change(parent);
+                    return;
+                }
+            }
+            state.set(State::Idle);
+            on_items_change(parent);
+        });
+    };
+
+    rsx! {
+        div { class: "alert alert-warning flex flex-col items-start",
+            p {
+                "Ingredients add up to {balance.items_mls}ml but the consumption has {balance.consumption_mls}ml."
+            }
+            div { class: "flex gap-2",
+                button {
+                    r#type: "button",
+                    class: "btn btn-sm",
+                    disabled,
+                    onclick: use_items_total,
+                    "Use ingredient total"
+                }
+                if can_distribute {
+                    button {
+                        r#type: "button",
+                        class: "btn btn-sm",
+                        disabled,
+                        onclick: use_consumption_total,
+                        "Scale ingredients to consumption"
+                    }
+                }
+            }
+            if let State::Finished(Err(err)) = state() {
+                div { class: "text-error",
+                    "Error: "
+                    {err.to_string()}
+                }
+            }
+        }
+    }
+}
+
 pub fn consumption_errors(
     consumption: &Consumption,
     consumption_consumables: Option<&Vec<ConsumptionItem>>,
@@ -844,17 +1422,11 @@ pub fn consumption_errors(
     }
 
     if let Some(consumption_consumables) = &consumption_consumables {
-        let zero = bigdecimal::BigDecimal::from(0);
-        let expected_mls = consumption.liquid_mls.as_ref().unwrap_or(&zero);
-        let total_nested_mls: bigdecimal::BigDecimal = consumption_consumables
-            .iter()
-            .filter_map(|ci| ci.nested.liquid_mls.as_ref())
-            .cloned()
-            .sum();
-        if *expected_mls != total_nested_mls {
+        let balance = LiquidBalance::new(consumption, consumption_consumables);
+        if !balance.is_balanced() {
             errors.push(format!(
                 "Liquid ml total from ingredients {}ml does not match consumption liquid ml {}ml",
-                total_nested_mls, expected_mls,
+                balance.items_mls, balance.consumption_mls,
             ));
         }
     }
@@ -878,10 +1450,28 @@ pub fn consumption_errors(
     errors
 }
 
+#[component]
+pub fn ConsumptionIntakeTag(consumption: ConsumptionWithItems) -> Element {
+    let Some(intake) = consumption.intake() else {
+        return rsx! {};
+    };
+    let class = match intake {
+        ConsumptionIntake::Liquid => "badge-info",
+        ConsumptionIntake::Solid => "badge-warning",
+        ConsumptionIntake::Mixed => "badge-accent",
+    };
+
+    rsx! {
+        span { class: "badge badge-sm {class}", {intake.as_title()} }
+    }
+}
+
 #[component]
 pub fn ConsumptionDetails(consumption: Consumption) -> Element {
+    let route = ConsumptionRoute::for_consumption(&consumption, &use_consumption_types());
+
     rsx! {
-        div { {consumption.consumption_type.as_title()} }
+        div { {route.as_title()} }
         if let Some(comments) = &consumption.comments {
             Markdown { content: comments.to_string() }
         }
@@ -890,6 +1480,7 @@ pub fn ConsumptionDetails(consumption: Consumption) -> Element {
 
 #[component]
 pub fn ConsumptionItemSummary(item: ConsumptionItem) -> Element {
+    let quantity_specified = item.is_quantity_specified();
     let mut quantity_list = Vec::new();
 
     if let Some(quantity) = item.nested.quantity {
@@ -911,8 +1502,20 @@ pub fn ConsumptionItemSummary(item: ConsumptionItem) -> Element {
         });
     }
 
+    if item.nested.portion != 1 {
+        quantity_list.push(rsx! {
+            span {
+                "Portion: "
+                {item.nested.portion.normalized().to_string()}
+            }
+        });
+    }
+
     rsx! {
         div {
+            if !quantity_specified {
+                MissingQuantityIcon {}
+            }
             if quantity_list.is_empty() {
                 {}
             } else {
@@ -0,0 +1,139 @@
+use dioxus::prelude::*;
+use dioxus_fullstack::ServerFnError;
+
+use crate::{
+    forms::{EditError, InputString, Saving, ValidationError, validate_name},
+    functions::entry_templates::create_entry_template,
+    models::{
+        Entry, EntryKind, EntryTemplate, EntryTemplateData, EntryTemplateId, NewEntryTemplate,
+        UserId,
+    },
+    use_entry_templates, use_user,
+};
+
+/// The saved templates of `kind`, to start a new entry from.
+#[component]
+pub fn EntryTemplatePicker(kind: EntryKind, on_select: Callback<EntryTemplateId>) -> Element {
+    let templates: Vec<(EntryTemplateId, String)> = use_entry_templates()
+        .into_iter()
+        .filter(|template| template.kind() == kind)
+        .map(|template| (template.id, template.name))
+        .collect();
+
+    rsx! {
+        for (id, name) in templates {
+            button {
+                key: "{id}",
+                r#type: "button",
+                class: "btn btn-outline btn-secondary btn-sm align-top",
+                title: "New {kind.as_title()} from template",
+                onclick: move |_| on_select(id),
+                "{name}"
+            }
+        }
+    }
+}
+
+async fn do_save_template(
+    user_id: UserId,
+    entry: &Entry,
+    name: Memo<Result<String, ValidationError>>,
+) -> Result<EntryTemplate, EditError> {
+    let name = name.read().clone()?;
+    let data = EntryTemplateData::from_entry(entry)
+        .map_err(|err| ValidationError(format!("Cannot use entry as a template: {err}")))?;
+
+    create_entry_template(NewEntryTemplate {
+        user_id,
+        name,
+        data,
+    })
+    .await
+    .map_err(EditError::Server)
+}
+
+/// The id of the name field, so the forms of different kinds of entry don't
+/// clash when both are open.
+fn template_name_id(kind: EntryKind) -> &'static str {
+    match kind {
+        EntryKind::Poo => "template_name_poo",
+        EntryKind::Wee => "template_name_wee",
+        EntryKind::WeeUrge => "template_name_wee_urge",
+        EntryKind::Consumption => "template_name_consumption",
+        EntryKind::Exercise => "template_name_exercise",
+        EntryKind::HealthMetric => "template_name_health_metric",
+        EntryKind::Symptom => "template_name_symptom",
+        EntryKind::Reflux => "template_name_reflux",
+        EntryKind::Note => "template_name_note",
+        EntryKind::FluidAdjustment => "template_name_fluid_adjustment",
+        EntryKind::Medication => "template_name_medication",
+    }
+}
+
+/// Save an entry's fields as a named template.
+#[component]
+pub fn EntryTemplateCreate(entry: ReadSignal<Entry>) -> Element {
+    let mut templates: Resource<Result<Vec<EntryTemplate>, ServerFnError>> = use_context();
+    let user_id = use_user().ok().flatten().map(|user| user.id);
+
+    let id = template_name_id(entry().kind());
+    let mut name = use_signal(String::new);
+    let validate = use_memo(move || validate_name(&name()));
+
+    let mut saving = use_signal(|| Saving::No);
+
+    let disabled = use_memo(move || saving.read().is_saving());
+    let disabled_save = use_memo(move || validate.read().is_err() || disabled());
+
+    let on_save = move |_| {
+        let Some(user_id) = user_id else {
+            return;
+        };
+        spawn(async move {
+            saving.set(Saving::Yes);
+
+            match do_save_template(user_id, &entry(), validate).await {
+                Ok(_template) => {
+                    saving.set(Saving::Finished(Ok(())));
+                    name.set(String::new());
+                    templates.restart();
+                }
+                Err(err) => saving.set(Saving::Finished(Err(err))),
+            }
+        });
+    };
+
+    rsx! {
+        form {
+            novalidate: true,
+            action: "javascript:void(0);",
+            class: "w-full",
+            InputString {
+                id,
+                label: "Template name",
+                value: name,
+                validate,
+                disabled,
+            }
+            match &*saving.read() {
+                Saving::Finished(Ok(())) => rsx! {
+                    div { class: "alert alert-success", "Saved as a template." }
+                },
+                Saving::Finished(Err(err)) => rsx! {
+                    div { class: "alert alert-error",
+                        "Error: "
+                        {err.to_string()}
+                    }
+                },
+                _ => rsx! {},
+            }
+            button {
+                r#type: "submit",
+                class: "btn btn-primary btn-sm",
+                disabled: disabled_save(),
+                onclick: on_save,
+                "Save as template"
+            }
+        }
+    }
+}
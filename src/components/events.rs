@@ -3,7 +3,10 @@ use classes::classes;
 use dioxus::prelude::*;
 use palette::IntoColor;
 
-use crate::models::Urgency;
+use crate::{
+    i18n::format_prefs,
+    models::{Urgency, format_datetime},
+};
 
 const DAY_START_TIME: chrono::NaiveTime = chrono::NaiveTime::from_hms_opt(6, 30, 0).unwrap();
 const DAY_END_TIME: chrono::NaiveTime = chrono::NaiveTime::from_hms_opt(21, 0, 0).unwrap();
@@ -21,7 +24,7 @@ fn get_classes_for_time(date_time: chrono::DateTime<FixedOffset>) -> String {
 
 #[component]
 pub fn EventDateTimeShort(time: chrono::DateTime<FixedOffset>) -> Element {
-    let string = time.format("%Y-%m-%d %H:%M").to_string();
+    let string = format_datetime(&time, &format_prefs());
     let classes = get_classes_for_time(time);
 
     rsx! {
@@ -51,7 +54,11 @@ pub fn UrgencyIcon(urgency: Urgency) -> Element {
     };
 
     rsx! {
-        div { class: classes + "text-sm w-10 dark:invert inline-block", {icon} }
+        div {
+            class: classes + "text-sm w-10 dark:invert inline-block",
+            title: urgency.as_title(),
+            {icon}
+        }
     }
 }
 
@@ -94,9 +101,10 @@ pub fn event_colour(colour: Option<palette::Hsv>) -> Element {
     }
 }
 
-#[component]
-pub fn Markdown(content: String) -> Element {
-    use pulldown_cmark::{Options, Parser, html};
+/// Render markdown to HTML. Raw HTML in the markdown is shown as typed
+/// rather than passed through, so comments cannot inject markup.
+fn markdown_to_html(content: &str) -> String {
+    use pulldown_cmark::{Event, Options, Parser, html};
 
     let mut options = Options::empty();
     options.insert(Options::ENABLE_TABLES);
@@ -104,12 +112,43 @@ pub fn Markdown(content: String) -> Element {
     options.insert(Options::ENABLE_STRIKETHROUGH);
     options.insert(Options::ENABLE_TASKLISTS);
 
-    let parser = Parser::new_ext(&content, options);
+    let parser = Parser::new_ext(content, options).map(|event| match event {
+        Event::Html(raw) | Event::InlineHtml(raw) => Event::Text(raw),
+        event => event,
+    });
 
     let mut html_output = String::new();
     html::push_html(&mut html_output, parser);
+    html_output
+}
+
+#[component]
+pub fn Markdown(content: String) -> Element {
+    let html_output = markdown_to_html(&content);
 
     rsx! {
         div { class: "prose", dangerous_inner_html: "{html_output}" }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdown_escapes_raw_html() {
+        let html =
+            markdown_to_html("Hello <script>alert(1)</script>\n\n<img src=x onerror=alert(1)>");
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("<img"));
+        assert!(html.contains("&lt;script&gt;"));
+    }
+
+    #[test]
+    fn markdown_still_renders_formatting() {
+        assert_eq!(
+            markdown_to_html("**bold**"),
+            "<p><strong>bold</strong></p>\n"
+        );
+    }
+}
@@ -4,24 +4,35 @@ use dioxus::prelude::*;
 use crate::{
     components::{
         events::{EventDateTimeShort, Markdown},
-        times::time_delta_to_string,
+        times::{time_delta_to_string, time_delta_to_string_rounded},
     },
     forms::{
         Dialog, EditError, FieldValue, FormSaveCancelButton, InputDateTime, InputDuration,
         InputExerciseCalories, InputExerciseRpe, InputExerciseType, InputNumber, InputTextArea,
-        Saving, ValidationError, validate_comments, validate_distance, validate_duration,
-        validate_exercise_calories, validate_exercise_rpe, validate_exercise_type,
+        Saving, UnsavedChanges, ValidationError, use_dirty, validate_comments, validate_distance,
+        validate_duration, validate_exercise_calories, validate_exercise_heart_rate,
+        validate_exercise_max_heart_rate, validate_exercise_rpe, validate_exercise_type,
         validate_fixed_offset_date_time, validate_location,
     },
     functions::exercises::{create_exercise, delete_exercise, update_exercise},
-    models::{ChangeExercise, Exercise, ExerciseRpe, ExerciseType, MaybeSet, NewExercise, UserId},
+    i18n::t,
+    models::{
+        ChangeExercise, DistanceUnit, EntryKind, Exercise, ExerciseRpe, ExerciseType,
+        HeartRateZone, HeartRateZones, MaybeSet, NewExercise, UserId, format_distance,
+    },
+    use_duration_rounding, use_timezone, use_user,
 };
 use classes::classes;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Operation {
-    Create { user_id: UserId },
-    Update { exercise: Exercise },
+    Create {
+        user_id: UserId,
+        template: Option<NewExercise>,
+    },
+    Update {
+        exercise: Exercise,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -32,6 +43,8 @@ struct Validate {
     distance: Memo<Result<Option<bigdecimal::BigDecimal>, ValidationError>>,
     calories: Memo<Result<Option<i32>, ValidationError>>,
     rpe: Memo<Result<Option<ExerciseRpe>, ValidationError>>,
+    avg_heart_rate: Memo<Result<Option<i32>, ValidationError>>,
+    max_heart_rate: Memo<Result<Option<i32>, ValidationError>>,
     exercise_type: Memo<Result<ExerciseType, ValidationError>>,
     comments: Memo<Result<Option<String>, ValidationError>>,
 }
@@ -44,10 +57,12 @@ async fn do_save(op: &Operation, validate: &Validate) -> Result<Exercise, EditEr
     let distance = validate.distance.read().clone()?;
     let calories = validate.calories.read().clone()?;
     let rpe = validate.rpe.read().clone()?;
+    let avg_heart_rate = validate.avg_heart_rate.read().clone()?;
+    let max_heart_rate = validate.max_heart_rate.read().clone()?;
     let comments = validate.comments.read().clone()?;
 
     match op {
-        Operation::Create { user_id } => {
+        Operation::Create { user_id, .. } => {
             let updates = NewExercise {
                 user_id: *user_id,
                 time,
@@ -58,6 +73,8 @@ async fn do_save(op: &Operation, validate: &Validate) -> Result<Exercise, EditEr
                 rpe,
                 comments,
                 exercise_type,
+                avg_heart_rate,
+                max_heart_rate,
             };
             create_exercise(updates).await.map_err(EditError::Server)
         }
@@ -72,6 +89,8 @@ async fn do_save(op: &Operation, validate: &Validate) -> Result<Exercise, EditEr
                 calories: MaybeSet::Set(calories),
                 rpe: MaybeSet::Set(rpe),
                 comments: MaybeSet::Set(comments),
+                avg_heart_rate: MaybeSet::Set(avg_heart_rate),
+                max_heart_rate: MaybeSet::Set(max_heart_rate),
             };
             update_exercise(exercise.id, changes)
                 .await
@@ -88,21 +107,37 @@ pub fn ExerciseUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Exer
     });
 
     let duration = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(exercise),
+            ..
+        } => exercise.duration.as_raw(),
         Operation::Create { .. } => String::new(),
         Operation::Update { exercise } => exercise.duration.as_raw(),
     });
 
     let exercise_type = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(exercise),
+            ..
+        } => Some(exercise.exercise_type),
         Operation::Create { .. } => None,
         Operation::Update { exercise } => Some(exercise.exercise_type),
     });
 
     let location = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(exercise),
+            ..
+        } => exercise.location.as_raw(),
         Operation::Create { .. } => String::new(),
         Operation::Update { exercise } => exercise.location.as_raw(),
     });
 
     let distance = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(exercise),
+            ..
+        } => exercise.distance.as_raw(),
         Operation::Create { .. } => String::new(),
         Operation::Update { exercise } => exercise.distance.as_raw(),
     });
@@ -113,26 +148,75 @@ pub fn ExerciseUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Exer
     });
 
     let rpe = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(exercise),
+            ..
+        } => exercise.rpe,
         Operation::Create { .. } => None,
         Operation::Update { exercise } => exercise.rpe,
     });
 
+    let avg_heart_rate = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(exercise),
+            ..
+        } => exercise.avg_heart_rate.as_raw(),
+        Operation::Create { .. } => String::new(),
+        Operation::Update { exercise } => exercise.avg_heart_rate.as_raw(),
+    });
+
+    let max_heart_rate = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(exercise),
+            ..
+        } => exercise.max_heart_rate.as_raw(),
+        Operation::Create { .. } => String::new(),
+        Operation::Update { exercise } => exercise.max_heart_rate.as_raw(),
+    });
+
     let comments = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(exercise),
+            ..
+        } => exercise.comments.as_raw(),
         Operation::Create { .. } => String::new(),
         Operation::Update { exercise } => exercise.comments.as_raw(),
     });
 
+    let timezone = use_timezone();
     let validate = Validate {
-        time: use_memo(move || validate_fixed_offset_date_time(&time())),
+        time: use_memo(move || validate_fixed_offset_date_time(&time(), timezone)),
         duration: use_memo(move || validate_duration(&duration())),
         location: use_memo(move || validate_location(&location())),
         distance: use_memo(move || validate_distance(&distance())),
         calories: use_memo(move || validate_exercise_calories(&calories())),
         rpe: use_memo(move || validate_exercise_rpe(&rpe())),
+        avg_heart_rate: use_memo(move || validate_exercise_heart_rate(&avg_heart_rate())),
+        max_heart_rate: use_memo(move || {
+            let avg_heart_rate = validate_exercise_heart_rate(&avg_heart_rate())
+                .ok()
+                .flatten();
+            validate_exercise_max_heart_rate(&max_heart_rate(), avg_heart_rate)
+        }),
         exercise_type: use_memo(move || validate_exercise_type(exercise_type())),
         comments: use_memo(move || validate_comments(&comments())),
     };
 
+    let dirty = use_dirty(move || {
+        (
+            time(),
+            duration(),
+            exercise_type(),
+            location(),
+            distance(),
+            calories(),
+            rpe(),
+            avg_heart_rate(),
+            max_heart_rate(),
+            comments(),
+        )
+    });
+
     let mut saving = use_signal(|| Saving::No);
 
     // disable form while waiting for response
@@ -144,6 +228,8 @@ pub fn ExerciseUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Exer
             || validate.distance.read().is_err()
             || validate.calories.read().is_err()
             || validate.rpe.read().is_err()
+            || validate.avg_heart_rate.read().is_err()
+            || validate.max_heart_rate.read().is_err()
             || validate.exercise_type.read().is_err()
             || validate.comments.read().is_err()
             || disabled()
@@ -162,6 +248,7 @@ pub fn ExerciseUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Exer
             match result {
                 Ok(consumable) => {
                     saving.set(Saving::Finished(Ok(())));
+                    dirty.reset();
                     on_save(consumable);
                 }
                 Err(err) => saving.set(Saving::Finished(Err(err))),
@@ -172,9 +259,10 @@ pub fn ExerciseUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Exer
     rsx! {
         h3 { class: "text-lg font-bold",
             match &op {
-                Operation::Create { .. } => "Create Exercise".to_string(),
-                Operation::Update { exercise } => format!("Edit Exercise {}", exercise.name()),
+                Operation::Create { .. } => t("Create Exercise").to_string(),
+                Operation::Update { exercise } => format!("{} {}", t("Edit Exercise"), exercise.name()),
             }
+            UnsavedChanges { dirty: dirty.is_dirty() }
         }
         p { class: "py-4", "Press ESC key or click the button below to close" }
         form {
@@ -200,6 +288,7 @@ pub fn ExerciseUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Exer
                 start_time: validate.time,
                 validate: validate.duration,
                 disabled,
+                kind: EntryKind::Exercise,
             }
             InputTextArea {
                 id: "location",
@@ -229,6 +318,20 @@ pub fn ExerciseUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Exer
                 validate: validate.rpe,
                 disabled,
             }
+            InputNumber {
+                id: "avg_heart_rate",
+                label: "Average heart rate (30-250 bpm)",
+                value: avg_heart_rate,
+                validate: validate.avg_heart_rate,
+                disabled,
+            }
+            InputNumber {
+                id: "max_heart_rate",
+                label: "Max heart rate (30-250 bpm)",
+                value: max_heart_rate,
+                validate: validate.max_heart_rate,
+                disabled,
+            }
             InputExerciseType {
                 id: "exercise_type",
                 label: "Type",
@@ -248,8 +351,8 @@ pub fn ExerciseUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Exer
                 on_save: move |()| on_save(()),
                 on_cancel: move |_| on_cancel(()),
                 title: match &op {
-                    Operation::Create { .. } => "Create",
-                    Operation::Update { .. } => "Save",
+                    Operation::Create { .. } => t("Create"),
+                    Operation::Update { .. } => t("Save"),
                 },
                 saving,
             }
@@ -333,7 +436,7 @@ pub fn ExerciseTypeIcon(exercise_type: ExerciseType) -> Element {
     };
     let alt = exercise_type.as_title();
     rsx! {
-        img { alt, src: icon }
+        img { alt, title: alt, src: icon }
     }
 }
 
@@ -352,13 +455,17 @@ pub fn ExerciseRpeIcon(rpe: ExerciseRpe) -> Element {
         ExerciseRpe::Rpe10 => "10",
     };
     rsx! {
-        div { class: "text-sm w-10 dark:invert inline-block", {icon} }
+        div {
+            class: "text-sm w-10 dark:invert inline-block",
+            title: rpe.as_title(),
+            {icon}
+        }
     }
 }
 
 #[component]
 pub fn ExerciseDuration(duration: chrono::TimeDelta) -> Element {
-    let text = time_delta_to_string(duration);
+    let text = time_delta_to_string_rounded(duration, use_duration_rounding());
 
     rsx! {
         if duration.num_seconds() < 2 {
@@ -448,6 +555,38 @@ pub fn ExerciseRpeLabel(rpe: Option<ExerciseRpe>) -> Element {
     }
 }
 
+#[component]
+pub fn ExerciseDistance(distance: bigdecimal::BigDecimal) -> Element {
+    let unit = use_user()
+        .ok()
+        .flatten()
+        .and_then(|user| user.distance_unit)
+        .unwrap_or(DistanceUnit::Kilometres);
+
+    rsx! {
+        span { {format_distance(&distance, unit)} }
+    }
+}
+
+#[component]
+pub fn ExerciseHeartRateZone(zone: HeartRateZone, duration: chrono::TimeDelta) -> Element {
+    let classes = match zone {
+        HeartRateZone::Zone1 => classes!["text-blue-400"],
+        HeartRateZone::Zone2 => classes!["text-green-400"],
+        HeartRateZone::Zone3 => classes!["text-yellow-400"],
+        HeartRateZone::Zone4 => classes!["text-orange-400"],
+        HeartRateZone::Zone5 => classes!["text-red-400"],
+    };
+
+    rsx! {
+        span { class: classes,
+            {zone.as_title()}
+            " for "
+            {time_delta_to_string(duration)}
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum ActiveDialog {
     Change(Operation),
@@ -494,6 +633,11 @@ pub fn ExerciseSummary(exercise: Exercise) -> Element {
         div {
             ExerciseDuration { duration: exercise.duration }
         }
+        if let Some(distance) = &exercise.distance {
+            div {
+                ExerciseDistance { distance: distance.clone() }
+            }
+        }
         if let Some(comments) = &exercise.comments {
             Markdown { content: comments.to_string() }
         }
@@ -502,6 +646,12 @@ pub fn ExerciseSummary(exercise: Exercise) -> Element {
 
 #[component]
 pub fn ExerciseDetails(exercise: Exercise) -> Element {
+    let zone = use_user()
+        .ok()
+        .flatten()
+        .and_then(|user| user.max_heart_rate)
+        .and_then(|max_heart_rate| exercise.heart_rate_zone(&HeartRateZones::new(max_heart_rate)));
+
     rsx! {
         {exercise.exercise_type.as_title()}
         if let Some(location) = &exercise.location {
@@ -513,8 +663,7 @@ pub fn ExerciseDetails(exercise: Exercise) -> Element {
         if let Some(distance) = &exercise.distance {
             div {
                 "Distance: "
-                {distance.to_string()}
-                "km"
+                ExerciseDistance { distance: distance.clone() }
             }
         }
         if let Some(calories) = &exercise.calories {
@@ -529,6 +678,25 @@ pub fn ExerciseDetails(exercise: Exercise) -> Element {
                 ExerciseRpeLabel { rpe: Some(*rpe) }
             }
         }
+        if let Some(avg_heart_rate) = &exercise.avg_heart_rate {
+            div {
+                "Average heart rate: "
+                {avg_heart_rate.to_string()}
+                " bpm"
+            }
+        }
+        if let Some(max_heart_rate) = &exercise.max_heart_rate {
+            div {
+                "Max heart rate: "
+                {max_heart_rate.to_string()}
+                " bpm"
+            }
+        }
+        if let Some(zone) = zone {
+            div {
+                ExerciseHeartRateZone { zone, duration: exercise.duration }
+            }
+        }
         if let Some(comments) = &exercise.comments {
             Markdown { content: comments.to_string() }
         }
@@ -0,0 +1,338 @@
+use chrono::{DateTime, FixedOffset, Local, Utc};
+use dioxus::prelude::*;
+
+use crate::{
+    components::events::{EventDateTimeShort, Markdown},
+    forms::{
+        Dialog, EditError, FieldValue, FormSaveCancelButton, InputDateTime, InputString,
+        InputTextArea, Saving, UnsavedChanges, ValidationError, use_dirty, validate_comments,
+        validate_fixed_offset_date_time, validate_fluid_adjustment_millilitres,
+    },
+    functions::fluid_adjustments::{
+        create_fluid_adjustment, delete_fluid_adjustment, update_fluid_adjustment,
+    },
+    i18n::t,
+    models::{ChangeFluidAdjustment, FluidAdjustment, MaybeSet, NewFluidAdjustment, UserId},
+    use_timezone,
+};
+use classes::classes;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operation {
+    Create {
+        user_id: UserId,
+        template: Option<NewFluidAdjustment>,
+    },
+    Update {
+        fluid_adjustment: FluidAdjustment,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct Validate {
+    time: Memo<Result<DateTime<FixedOffset>, ValidationError>>,
+    mls: Memo<Result<i32, ValidationError>>,
+    comments: Memo<Result<Option<String>, ValidationError>>,
+}
+
+async fn do_save(op: &Operation, validate: &Validate) -> Result<FluidAdjustment, EditError> {
+    let time = validate.time.read().clone()?;
+    let mls = validate.mls.read().clone()?;
+    let comments = validate.comments.read().clone()?;
+
+    match op {
+        Operation::Create { user_id, .. } => {
+            let updates = NewFluidAdjustment {
+                user_id: *user_id,
+                time,
+                mls,
+                comments,
+            };
+            create_fluid_adjustment(updates)
+                .await
+                .map_err(EditError::Server)
+        }
+        Operation::Update { fluid_adjustment } => {
+            let changes = ChangeFluidAdjustment {
+                user_id: MaybeSet::NoChange,
+                time: MaybeSet::Set(time),
+                mls: MaybeSet::Set(mls),
+                comments: MaybeSet::Set(comments),
+            };
+            update_fluid_adjustment(fluid_adjustment.id, changes)
+                .await
+                .map_err(EditError::Server)
+        }
+    }
+}
+
+#[component]
+pub fn FluidAdjustmentUpdate(
+    op: Operation,
+    on_cancel: Callback,
+    on_save: Callback<FluidAdjustment>,
+) -> Element {
+    let time = use_signal(|| match &op {
+        Operation::Create { .. } => Utc::now().with_timezone(&Local).fixed_offset().as_raw(),
+        Operation::Update { fluid_adjustment } => fluid_adjustment.time.as_raw(),
+    });
+
+    let mls = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(fluid_adjustment),
+            ..
+        } => fluid_adjustment.mls.as_raw(),
+        Operation::Create { .. } => String::new(),
+        Operation::Update { fluid_adjustment } => fluid_adjustment.mls.as_raw(),
+    });
+
+    let comments = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(fluid_adjustment),
+            ..
+        } => fluid_adjustment.comments.as_raw(),
+        Operation::Create { .. } => String::new(),
+        Operation::Update { fluid_adjustment } => fluid_adjustment.comments.as_raw(),
+    });
+
+    let timezone = use_timezone();
+    let validate = Validate {
+        time: use_memo(move || validate_fixed_offset_date_time(&time(), timezone)),
+        mls: use_memo(move || validate_fluid_adjustment_millilitres(&mls())),
+        comments: use_memo(move || validate_comments(&comments())),
+    };
+
+    let dirty = use_dirty(move || (time(), mls(), comments()));
+
+    let mut saving = use_signal(|| Saving::No);
+
+    // disable form while waiting for response
+    let disabled = use_memo(move || saving.read().is_saving());
+    let disabled_save = use_memo(move || {
+        validate.time.read().is_err()
+            || validate.mls.read().is_err()
+            || validate.comments.read().is_err()
+            || disabled()
+    });
+
+    let op_clone = op.clone();
+    let validate_clone = validate.clone();
+    let on_save = use_callback(move |()| {
+        let op = op_clone.clone();
+        let validate = validate_clone.clone();
+        spawn(async move {
+            saving.set(Saving::Yes);
+
+            let result = do_save(&op, &validate).await;
+
+            match result {
+                Ok(fluid_adjustment) => {
+                    saving.set(Saving::Finished(Ok(())));
+                    dirty.reset();
+                    on_save(fluid_adjustment);
+                }
+                Err(err) => saving.set(Saving::Finished(Err(err))),
+            }
+        });
+    });
+
+    rsx! {
+        h3 { class: "text-lg font-bold",
+            match &op {
+                Operation::Create { .. } => t("Create Fluid Adjustment").to_string(),
+                Operation::Update { fluid_adjustment } => {
+                    format!("{} {}", t("Edit Fluid Adjustment"), fluid_adjustment.name())
+                }
+            }
+            UnsavedChanges { dirty: dirty.is_dirty() }
+        }
+        p { class: "py-4", "Press ESC key or click the button below to close" }
+        form {
+            novalidate: true,
+            action: "javascript:void(0)",
+            method: "dialog",
+            onkeyup: move |event| {
+                if event.key() == Key::Escape {
+                    on_cancel(());
+                }
+            },
+            InputDateTime {
+                id: "time",
+                label: "Time",
+                value: time,
+                validate: validate.time,
+                disabled,
+            }
+            InputString {
+                id: "mls",
+                label: "Adjustment (ml, negative for fluid out)",
+                value: mls,
+                validate: validate.mls,
+                disabled,
+            }
+            InputTextArea {
+                id: "comments",
+                label: "Comments",
+                value: comments,
+                validate: validate.comments,
+                disabled,
+            }
+            FormSaveCancelButton {
+                disabled: disabled_save,
+                on_save: move |()| on_save(()),
+                on_cancel: move |_| on_cancel(()),
+                title: match &op {
+                    Operation::Create { .. } => t("Create"),
+                    Operation::Update { .. } => t("Save"),
+                },
+                saving,
+            }
+        }
+    }
+}
+
+#[component]
+pub fn FluidAdjustmentDelete(
+    fluid_adjustment: FluidAdjustment,
+    on_cancel: Callback,
+    on_delete: Callback<FluidAdjustment>,
+) -> Element {
+    let mut saving = use_signal(|| Saving::No);
+
+    let disabled = use_memo(move || saving.read().is_saving());
+
+    let fluid_adjustment_clone = fluid_adjustment.clone();
+    let on_save = use_callback(move |()| {
+        let fluid_adjustment = fluid_adjustment_clone.clone();
+        spawn(async move {
+            saving.set(Saving::Yes);
+
+            match delete_fluid_adjustment(fluid_adjustment.id).await {
+                Ok(_) => {
+                    saving.set(Saving::Finished(Ok(())));
+                    on_delete(fluid_adjustment.clone());
+                }
+                Err(err) => saving.set(Saving::Finished(Err(EditError::Server(err)))),
+            }
+        });
+    });
+
+    rsx! {
+        h3 { class: "text-lg font-bold",
+            "Delete fluid adjustment "
+            {fluid_adjustment.name()}
+        }
+        p { class: "py-4", "Press ESC key or click the button below to close" }
+        FluidAdjustmentSummary { fluid_adjustment: fluid_adjustment.clone() }
+        form {
+            novalidate: true,
+            action: "javascript:void(0)",
+            method: "dialog",
+            onkeyup: move |event| {
+                if event.key() == Key::Escape {
+                    on_cancel(());
+                }
+            },
+            FormSaveCancelButton {
+                disabled,
+                on_save: move |()| on_save(()),
+                on_cancel: move |_| on_cancel(()),
+                title: "Delete",
+                saving,
+            }
+        }
+    }
+}
+
+const FLUID_ADJUSTMENT_SVG: Asset = asset!("/assets/fluid_adjustment.svg");
+
+#[component]
+pub fn fluid_adjustment_icon() -> Element {
+    let alt = fluid_adjustment_title();
+    let icon = FLUID_ADJUSTMENT_SVG;
+    rsx! {
+        img { alt, title: alt, src: icon }
+    }
+}
+
+#[component]
+pub fn fluid_adjustment_title() -> &'static str {
+    "Fluid Adjustment"
+}
+
+#[component]
+pub fn FluidAdjustmentMls(mls: i32) -> Element {
+    let classes = if mls < 0 {
+        classes!["text-warning"]
+    } else {
+        classes!["text-success"]
+    };
+
+    rsx! {
+        span { class: classes, {format!("{mls:+} ml")} }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ActiveDialog {
+    Change(Operation),
+    Delete(FluidAdjustment),
+    #[allow(dead_code)]
+    Idle,
+}
+
+#[component]
+pub fn FluidAdjustmentDialog(
+    dialog: ActiveDialog,
+    on_close: Callback<()>,
+    on_change: Callback<FluidAdjustment>,
+    on_delete: Callback<FluidAdjustment>,
+) -> Element {
+    match dialog {
+        ActiveDialog::Change(op) => {
+            rsx! {
+                Dialog {
+                    FluidAdjustmentUpdate { op, on_cancel: on_close, on_save: on_change }
+                }
+            }
+        }
+        ActiveDialog::Delete(fluid_adjustment) => {
+            rsx! {
+                Dialog {
+                    FluidAdjustmentDelete { fluid_adjustment, on_cancel: on_close, on_delete }
+                }
+            }
+        }
+        ActiveDialog::Idle => {
+            rsx! {}
+        }
+    }
+}
+
+#[component]
+pub fn FluidAdjustmentSummary(fluid_adjustment: FluidAdjustment) -> Element {
+    rsx! {
+        div { {fluid_adjustment_title()} }
+        div {
+            EventDateTimeShort { time: fluid_adjustment.time }
+        }
+        div {
+            FluidAdjustmentMls { mls: fluid_adjustment.mls }
+        }
+        if let Some(comments) = &fluid_adjustment.comments {
+            Markdown { content: comments.to_string() }
+        }
+    }
+}
+
+#[component]
+pub fn FluidAdjustmentDetails(fluid_adjustment: FluidAdjustment) -> Element {
+    rsx! {
+        div {
+            FluidAdjustmentMls { mls: fluid_adjustment.mls }
+        }
+        if let Some(comments) = &fluid_adjustment.comments {
+            Markdown { content: comments.to_string() }
+        }
+    }
+}
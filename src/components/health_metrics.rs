@@ -5,19 +5,26 @@ use crate::{
     components::events::{EventDateTimeShort, Markdown},
     forms::{
         Dialog, EditError, FieldValue, FormSaveCancelButton, InputDateTime, InputNumber,
-        InputTextArea, Saving, ValidationError, validate_blood_glucose, validate_comments,
-        validate_diastolic_bp, validate_fixed_offset_date_time, validate_height, validate_pulse,
-        validate_systolic_bp, validate_waist_circumference, validate_weight,
+        InputTextArea, Saving, UnsavedChanges, ValidationError, use_dirty, validate_blood_glucose,
+        validate_comments, validate_diastolic_bp, validate_fixed_offset_date_time, validate_height,
+        validate_pulse, validate_systolic_bp, validate_waist_circumference, validate_weight,
     },
     functions::health_metrics::{create_health_metric, delete_health_metric, update_health_metric},
+    i18n::{format_prefs, t},
     models::{ChangeHealthMetric, HealthMetric, MaybeSet, NewHealthMetric, UserId},
+    use_glucose_unit, use_timezone,
 };
 
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, PartialEq)]
 pub enum Operation {
-    Create { user_id: UserId },
-    Update { health_metric: HealthMetric },
+    Create {
+        user_id: UserId,
+        template: Option<NewHealthMetric>,
+    },
+    Update {
+        health_metric: HealthMetric,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -45,7 +52,7 @@ async fn do_save(op: &Operation, validate: &Validate) -> Result<HealthMetric, Ed
     let comments = validate.comments.read().clone()?;
 
     match op {
-        Operation::Create { user_id } => {
+        Operation::Create { user_id, .. } => {
             let updates = NewHealthMetric {
                 user_id: *user_id,
                 time,
@@ -93,44 +100,86 @@ pub fn HealthMetricUpdate(
         Operation::Update { health_metric } => health_metric.time.as_raw(),
     });
     let pulse = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(health_metric),
+            ..
+        } => health_metric.pulse.as_raw(),
         Operation::Create { .. } => String::new(),
         Operation::Update { health_metric } => health_metric.pulse.as_raw(),
     });
+    let glucose_unit = use_glucose_unit();
     let blood_glucose = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(health_metric),
+            ..
+        } => health_metric
+            .blood_glucose
+            .as_ref()
+            .map(|mmol| glucose_unit.convert_mmol(mmol))
+            .as_raw(),
         Operation::Create { .. } => String::new(),
-        Operation::Update { health_metric } => health_metric.blood_glucose.as_raw(),
+        Operation::Update { health_metric } => health_metric
+            .blood_glucose
+            .as_ref()
+            .map(|mmol| glucose_unit.convert_mmol(mmol))
+            .as_raw(),
     });
     let systolic_bp = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(health_metric),
+            ..
+        } => health_metric.systolic_bp.as_raw(),
         Operation::Create { .. } => String::new(),
         Operation::Update { health_metric } => health_metric.systolic_bp.as_raw(),
     });
     let diastolic_bp = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(health_metric),
+            ..
+        } => health_metric.diastolic_bp.as_raw(),
         Operation::Create { .. } => String::new(),
         Operation::Update { health_metric } => health_metric.diastolic_bp.as_raw(),
     });
     let weight = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(health_metric),
+            ..
+        } => health_metric.weight.as_raw(),
         Operation::Create { .. } => String::new(),
         Operation::Update { health_metric } => health_metric.weight.as_raw(),
     });
     let waist_circumference = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(health_metric),
+            ..
+        } => health_metric.waist_circumference.as_raw(),
         Operation::Create { .. } => String::new(),
         Operation::Update { health_metric } => health_metric.waist_circumference.as_raw(),
     });
     let height = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(health_metric),
+            ..
+        } => health_metric.height.as_raw(),
         Operation::Create { .. } => String::new(),
         Operation::Update { health_metric } => health_metric.height.as_raw(),
     });
     let comments = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(health_metric),
+            ..
+        } => health_metric.comments.as_raw(),
         Operation::Create { .. } => String::new(),
         Operation::Update { health_metric } => health_metric.comments.as_raw(),
     });
 
     let validate_systolic_bp = use_memo(move || validate_systolic_bp(&systolic_bp()));
 
+    let timezone = use_timezone();
     let validate = Validate {
-        time: use_memo(move || validate_fixed_offset_date_time(&time())),
+        time: use_memo(move || validate_fixed_offset_date_time(&time(), timezone)),
         pulse: use_memo(move || validate_pulse(&pulse())),
-        blood_glucose: use_memo(move || validate_blood_glucose(&blood_glucose())),
+        blood_glucose: use_memo(move || validate_blood_glucose(&blood_glucose(), glucose_unit)),
         systolic_bp: validate_systolic_bp,
         diastolic_bp: use_memo(move || {
             let v = validate_diastolic_bp(&diastolic_bp());
@@ -163,6 +212,20 @@ pub fn HealthMetricUpdate(
         comments: use_memo(move || validate_comments(&comments())),
     };
 
+    let dirty = use_dirty(move || {
+        (
+            time(),
+            pulse(),
+            blood_glucose(),
+            systolic_bp(),
+            diastolic_bp(),
+            weight(),
+            waist_circumference(),
+            height(),
+            comments(),
+        )
+    });
+
     let mut saving = use_signal(|| Saving::No);
 
     // disable form while waiting for response
@@ -192,6 +255,7 @@ pub fn HealthMetricUpdate(
             match result {
                 Ok(health_metric) => {
                     saving.set(Saving::Finished(Ok(())));
+                    dirty.reset();
                     on_save(health_metric);
                 }
                 Err(err) => saving.set(Saving::Finished(Err(err))),
@@ -202,11 +266,12 @@ pub fn HealthMetricUpdate(
     rsx! {
         h3 { class: "text-lg font-bold",
             match &op {
-                Operation::Create { .. } => "Create HealthMetric".to_string(),
+                Operation::Create { .. } => t("Create HealthMetric").to_string(),
                 Operation::Update { health_metric } => {
-                    format!("Edit HealthMetric {}", health_metric.id)
+                    format!("{} {}", t("Edit HealthMetric"), health_metric.id)
                 }
             }
+            UnsavedChanges { dirty: dirty.is_dirty() }
         }
         p { class: "py-4", "Press ESC key or click the button below to close" }
         form {
@@ -234,10 +299,11 @@ pub fn HealthMetricUpdate(
             }
             InputNumber {
                 id: "blood_glucose",
-                label: "Blood Glucose (mmol/L)",
+                label: format!("Blood Glucose ({})", glucose_unit.as_unit()),
                 value: blood_glucose,
                 validate: validate.blood_glucose,
                 disabled,
+                step: bigdecimal::BigDecimal::new(1.into(), glucose_unit.scale()),
             }
             InputNumber {
                 id: "systolic_bp",
@@ -287,8 +353,8 @@ pub fn HealthMetricUpdate(
                 on_save: move |()| on_save(()),
                 on_cancel: move |()| on_cancel(()),
                 title: match &op {
-                    Operation::Create { .. } => "Create",
-                    Operation::Update { .. } => "Save",
+                    Operation::Create { .. } => t("Create"),
+                    Operation::Update { .. } => t("Save"),
                 },
                 saving,
             }
@@ -324,8 +390,8 @@ pub fn HealthMetricDelete(
 
     rsx! {
         h3 { class: "text-lg font-bold",
-            "Delete health_metric "
-            {health_metric.id.to_string()}
+            "Delete health metric "
+            {health_metric.name()}
         }
         p { class: "py-4", "Press ESC key or click the button below to close" }
         HealthMetricSummary { health_metric: health_metric.clone() }
@@ -355,7 +421,7 @@ const WEE_SVG: Asset = asset!("/assets/health_metric.svg");
 pub fn HealthMetricIcon() -> Element {
     let alt = health_metric_title();
     rsx! {
-        img { alt, src: WEE_SVG }
+        img { alt, title: alt, src: WEE_SVG }
     }
 }
 
@@ -418,6 +484,9 @@ pub fn HealthMetricSummary(health_metric: HealthMetric) -> Element {
 
 #[component]
 pub fn HealthMetricDetails(health_metric: HealthMetric) -> Element {
+    let prefs = format_prefs();
+    let glucose_unit = use_glucose_unit();
+
     rsx! {
         if let Some(pulse) = &health_metric.pulse {
             div {
@@ -428,7 +497,9 @@ pub fn HealthMetricDetails(health_metric: HealthMetric) -> Element {
         if let Some(blood_glucose) = &health_metric.blood_glucose {
             div {
                 "Blood Glucose: "
-                {blood_glucose.to_string()}
+                {prefs.format_decimal(&glucose_unit.convert_mmol(blood_glucose))}
+                " "
+                {glucose_unit.as_unit()}
             }
         }
         if let (Some(systolic_bp), Some(diastolic_bp)) = (
@@ -446,7 +517,7 @@ pub fn HealthMetricDetails(health_metric: HealthMetric) -> Element {
         if let Some(weight) = &health_metric.weight {
             div {
                 "Weight: "
-                {weight.to_string()}
+                {prefs.format_decimal(weight)}
                 "kg"
             }
         }
@@ -460,7 +531,7 @@ pub fn HealthMetricDetails(health_metric: HealthMetric) -> Element {
         if let Some(waist_circumference) = &health_metric.waist_circumference {
             div {
                 "Waist Circumference: "
-                {waist_circumference.to_string()}
+                {prefs.format_decimal(waist_circumference)}
                 "cm"
             }
         }
@@ -0,0 +1,397 @@
+use std::ops::Deref;
+
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, FixedOffset, Local, Utc};
+use dioxus::prelude::*;
+
+use crate::{
+    components::{
+        events::{EventDateTimeShort, Markdown},
+        medications::{MedicationIcon, MedicationLabel, medication_title},
+    },
+    forms::{
+        Dialog, EditError, FieldValue, FormSaveCancelButton, InputDateTime, InputMedication,
+        InputNumber, InputTextArea, Saving, UnsavedChanges, ValidationError, use_dirty,
+        validate_comments, validate_fixed_offset_date_time, validate_medication,
+        validate_medication_dose,
+    },
+    functions::{
+        medication_doses::{
+            create_medication_dose, delete_medication_dose, update_medication_dose,
+        },
+        medications::get_medications,
+    },
+    i18n::t,
+    models::{
+        ChangeMedicationDose, MaybeSet, Medication, MedicationDose, MedicationDoseWithMedication,
+        NewMedicationDose, UserId,
+    },
+    use_timezone,
+};
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operation {
+    Create {
+        user_id: UserId,
+        template: Option<NewMedicationDose>,
+    },
+    Update {
+        medication_dose: MedicationDoseWithMedication,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct Validate {
+    medication: Memo<Result<Medication, ValidationError>>,
+    time: Memo<Result<DateTime<FixedOffset>, ValidationError>>,
+    dose: Memo<Result<BigDecimal, ValidationError>>,
+    comments: Memo<Result<Option<String>, ValidationError>>,
+}
+
+async fn do_save(op: &Operation, validate: &Validate) -> Result<MedicationDose, EditError> {
+    let medication = validate.medication.read().clone()?;
+    let time = validate.time.read().clone()?;
+    let dose = validate.dose.read().clone()?;
+    let comments = validate.comments.read().clone()?;
+
+    match op {
+        Operation::Create { user_id, .. } => {
+            let updates = NewMedicationDose {
+                user_id: *user_id,
+                medication_id: medication.id,
+                time,
+                dose,
+                comments,
+            };
+            create_medication_dose(updates)
+                .await
+                .map_err(EditError::Server)
+        }
+        Operation::Update { medication_dose } => {
+            let changes = ChangeMedicationDose {
+                user_id: MaybeSet::NoChange,
+                medication_id: MaybeSet::Set(medication.id),
+                time: MaybeSet::Set(time),
+                dose: MaybeSet::Set(dose),
+                comments: MaybeSet::Set(comments),
+            };
+            update_medication_dose(medication_dose.dose.id, changes)
+                .await
+                .map_err(EditError::Server)
+        }
+    }
+}
+
+#[component]
+pub fn MedicationDoseUpdate(
+    op: Operation,
+    on_cancel: Callback,
+    on_save: Callback<MedicationDose>,
+) -> Element {
+    let medications = use_resource(|| async { get_medications(false).await });
+
+    let mut medication = use_signal(|| match &op {
+        Operation::Create { .. } => None,
+        Operation::Update { medication_dose } => Some(medication_dose.medication.clone()),
+    });
+    let time = use_signal(|| match &op {
+        Operation::Create { .. } => Utc::now().with_timezone(&Local).fixed_offset().as_raw(),
+        Operation::Update { medication_dose } => medication_dose.dose.time.as_raw(),
+    });
+    let mut dose = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(medication_dose),
+            ..
+        } => medication_dose.dose.as_raw(),
+        Operation::Create { .. } => String::new(),
+        Operation::Update { medication_dose } => medication_dose.dose.dose.as_raw(),
+    });
+    let comments = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(medication_dose),
+            ..
+        } => medication_dose.comments.as_raw(),
+        Operation::Create { .. } => String::new(),
+        Operation::Update { medication_dose } => medication_dose.dose.comments.as_raw(),
+    });
+
+    // Select the template's medication once the list has loaded.
+    let template_medication_id = match &op {
+        Operation::Create {
+            template: Some(medication_dose),
+            ..
+        } => Some(medication_dose.medication_id),
+        _ => None,
+    };
+    use_effect(move || {
+        if let (Some(id), Some(Ok(list))) = (template_medication_id, medications.read().deref())
+            && medication.peek().is_none()
+            && let Some(found) = list.iter().find(|m| m.id == id)
+        {
+            medication.set(Some(found.clone()));
+        }
+    });
+
+    // Default the dose from the selected medication.
+    use_effect(move || {
+        if let Some(medication) = medication()
+            && dose.peek().is_empty()
+        {
+            dose.set(medication.dose.as_raw());
+        }
+    });
+
+    let timezone = use_timezone();
+    let validate = Validate {
+        medication: use_memo(move || validate_medication(medication())),
+        time: use_memo(move || validate_fixed_offset_date_time(&time(), timezone)),
+        dose: use_memo(move || validate_medication_dose(&dose())),
+        comments: use_memo(move || validate_comments(&comments())),
+    };
+
+    let dirty = use_dirty(move || (medication(), time(), dose(), comments()));
+
+    let mut saving = use_signal(|| Saving::No);
+
+    // disable form while waiting for response
+    let disabled = use_memo(move || saving.read().is_saving());
+    let disabled_save = use_memo(move || {
+        validate.medication.read().is_err()
+            || validate.time.read().is_err()
+            || validate.dose.read().is_err()
+            || validate.comments.read().is_err()
+            || disabled()
+    });
+
+    let op_clone = op.clone();
+    let validate_clone = validate.clone();
+    let on_save = use_callback(move |()| {
+        let op = op_clone.clone();
+        let validate = validate_clone.clone();
+        spawn(async move {
+            saving.set(Saving::Yes);
+
+            let result = do_save(&op, &validate).await;
+
+            match result {
+                Ok(medication_dose) => {
+                    saving.set(Saving::Finished(Ok(())));
+                    dirty.reset();
+                    on_save(medication_dose);
+                }
+                Err(err) => saving.set(Saving::Finished(Err(err))),
+            }
+        });
+    });
+
+    // Inactive medications are not offered, unless already selected.
+    let options = match medications.read().deref() {
+        Some(Ok(list)) => {
+            let mut list = list.clone();
+            if let Operation::Update { medication_dose } = &op
+                && !list.iter().any(|m| m.id == medication_dose.medication.id)
+            {
+                list.push(medication_dose.medication.clone());
+            }
+            list
+        }
+        _ => Vec::new(),
+    };
+
+    rsx! {
+        h3 { class: "text-lg font-bold",
+            match &op {
+                Operation::Create { .. } => t("Create Medication Dose").to_string(),
+                Operation::Update { medication_dose } => {
+                    format!("{} {}", t("Edit Medication Dose"), medication_dose.dose.name())
+                }
+            }
+            UnsavedChanges { dirty: dirty.is_dirty() }
+        }
+        p { class: "py-4", "Press ESC key or click the button below to close" }
+        if let Some(Err(err)) = medications.read().deref() {
+            div { class: "alert alert-error",
+                "Error loading medications: "
+                {err.to_string()}
+            }
+        }
+        form {
+            novalidate: true,
+            action: "javascript:void(0)",
+            method: "dialog",
+            onkeyup: move |event| {
+                if event.key() == Key::Escape {
+                    on_cancel(());
+                }
+            },
+            InputMedication {
+                id: "medication",
+                label: "Medication",
+                value: medication,
+                validate: validate.medication,
+                disabled,
+                medications: options,
+            }
+            InputDateTime {
+                id: "time",
+                label: "Time",
+                value: time,
+                validate: validate.time,
+                disabled,
+            }
+            InputNumber {
+                id: "dose",
+                label: "Dose",
+                value: dose,
+                validate: validate.dose,
+                disabled,
+            }
+            InputTextArea {
+                id: "comments",
+                label: "Comments",
+                value: comments,
+                validate: validate.comments,
+                disabled,
+            }
+            FormSaveCancelButton {
+                disabled: disabled_save,
+                on_save: move |()| on_save(()),
+                on_cancel: move |_| on_cancel(()),
+                title: match &op {
+                    Operation::Create { .. } => t("Create"),
+                    Operation::Update { .. } => t("Save"),
+                },
+                saving,
+            }
+        }
+    }
+}
+
+#[component]
+pub fn MedicationDoseDelete(
+    medication_dose: MedicationDoseWithMedication,
+    on_cancel: Callback,
+    on_delete: Callback<MedicationDoseWithMedication>,
+) -> Element {
+    let mut saving = use_signal(|| Saving::No);
+
+    let disabled = use_memo(move || saving.read().is_saving());
+
+    let medication_dose_clone = medication_dose.clone();
+    let on_save = use_callback(move |()| {
+        let medication_dose = medication_dose_clone.clone();
+        spawn(async move {
+            saving.set(Saving::Yes);
+
+            match delete_medication_dose(medication_dose.dose.id).await {
+                Ok(_) => {
+                    saving.set(Saving::Finished(Ok(())));
+                    on_delete(medication_dose.clone());
+                }
+                Err(err) => saving.set(Saving::Finished(Err(EditError::Server(err)))),
+            }
+        });
+    });
+
+    rsx! {
+        h3 { class: "text-lg font-bold",
+            "Delete medication dose "
+            {medication_dose.dose.name()}
+        }
+        p { class: "py-4", "Press ESC key or click the button below to close" }
+        MedicationDoseSummary { medication_dose: medication_dose.clone() }
+        form {
+            novalidate: true,
+            action: "javascript:void(0)",
+            method: "dialog",
+            onkeyup: move |event| {
+                if event.key() == Key::Escape {
+                    on_cancel(());
+                }
+            },
+            FormSaveCancelButton {
+                disabled,
+                on_save: move |()| on_save(()),
+                on_cancel: move |_| on_cancel(()),
+                title: "Delete",
+                saving,
+            }
+        }
+    }
+}
+
+#[component]
+pub fn medication_dose_icon() -> Element {
+    rsx! {
+        MedicationIcon {}
+    }
+}
+
+#[component]
+pub fn medication_dose_title() -> &'static str {
+    medication_title()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ActiveDialog {
+    Change(Operation),
+    Delete(MedicationDoseWithMedication),
+    #[allow(dead_code)]
+    Idle,
+}
+
+#[component]
+pub fn MedicationDoseDialog(
+    dialog: ActiveDialog,
+    on_close: Callback<()>,
+    on_change: Callback<MedicationDose>,
+    on_delete: Callback<MedicationDoseWithMedication>,
+) -> Element {
+    match dialog {
+        ActiveDialog::Change(op) => {
+            rsx! {
+                Dialog {
+                    MedicationDoseUpdate { op, on_cancel: on_close, on_save: on_change }
+                }
+            }
+        }
+        ActiveDialog::Delete(medication_dose) => {
+            rsx! {
+                Dialog {
+                    MedicationDoseDelete { medication_dose, on_cancel: on_close, on_delete }
+                }
+            }
+        }
+        ActiveDialog::Idle => {
+            rsx! {}
+        }
+    }
+}
+
+#[component]
+pub fn MedicationDoseSummary(medication_dose: MedicationDoseWithMedication) -> Element {
+    rsx! {
+        div { {medication_dose_title()} }
+        div {
+            EventDateTimeShort { time: medication_dose.dose.time }
+        }
+        MedicationDoseDetails { medication_dose }
+    }
+}
+
+#[component]
+pub fn MedicationDoseDetails(medication_dose: MedicationDoseWithMedication) -> Element {
+    let medication = &medication_dose.medication;
+    let dose = &medication_dose.dose;
+
+    rsx! {
+        div {
+            MedicationLabel { medication: medication.clone() }
+        }
+        div { "Taken: {dose.dose} {medication.dose_unit}" }
+        if let Some(comments) = &dose.comments {
+            Markdown { content: comments.to_string() }
+        }
+    }
+}
@@ -0,0 +1,500 @@
+use std::{num::ParseIntError, str::FromStr};
+
+use bigdecimal::BigDecimal;
+use dioxus::prelude::*;
+use dioxus_router::ToQueryArgument;
+use tap::Pipe;
+use thiserror::Error;
+
+use crate::{
+    components::events::Markdown,
+    forms::{
+        Dialog, EditError, FieldValue, FormSaveCancelButton, InputBoolean, InputConsumable,
+        InputConsumptionType, InputNumber, InputString, InputTextArea, Saving, UnsavedChanges,
+        ValidationError, use_dirty, validate_comments, validate_consumption_type,
+        validate_dose_unit, validate_doses_per_day, validate_medication_consumable,
+        validate_medication_dose,
+    },
+    functions::medications::{create_medication, delete_medication, update_medication},
+    i18n::t,
+    models::{
+        ChangeMedication, Consumable, ConsumptionType, MaybeSet, Medication, MedicationAdherence,
+        MedicationId, NewMedication, UserId,
+    },
+};
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operation {
+    Create { user_id: UserId },
+    Update { medication: Medication },
+}
+
+#[derive(Debug, Clone)]
+struct Validate {
+    consumable: Memo<Result<Consumable, ValidationError>>,
+    dose: Memo<Result<BigDecimal, ValidationError>>,
+    dose_unit: Memo<Result<String, ValidationError>>,
+    route: Memo<Result<ConsumptionType, ValidationError>>,
+    doses_per_day: Memo<Result<Option<i32>, ValidationError>>,
+    comments: Memo<Result<Option<String>, ValidationError>>,
+}
+
+async fn do_save(
+    op: &Operation,
+    validate: &Validate,
+    active: bool,
+) -> Result<Medication, EditError> {
+    let consumable = validate.consumable.read().clone()?;
+    let dose = validate.dose.read().clone()?;
+    let dose_unit = validate.dose_unit.read().clone()?;
+    let route = validate.route.read().clone()?;
+    let doses_per_day = validate.doses_per_day.read().clone()?;
+    let comments = validate.comments.read().clone()?;
+
+    match op {
+        Operation::Create { user_id } => {
+            let updates = NewMedication {
+                user_id: *user_id,
+                consumable_id: consumable.id,
+                dose,
+                dose_unit,
+                route,
+                doses_per_day,
+                active,
+                comments,
+            };
+            create_medication(updates).await.map_err(EditError::Server)
+        }
+        Operation::Update { medication } => {
+            let changes = ChangeMedication {
+                user_id: MaybeSet::NoChange,
+                consumable_id: MaybeSet::Set(consumable.id),
+                dose: MaybeSet::Set(dose),
+                dose_unit: MaybeSet::Set(dose_unit),
+                route: MaybeSet::Set(route),
+                doses_per_day: MaybeSet::Set(doses_per_day),
+                active: MaybeSet::Set(active),
+                comments: MaybeSet::Set(comments),
+            };
+            update_medication(medication.id, changes)
+                .await
+                .map_err(EditError::Server)
+        }
+    }
+}
+
+#[component]
+pub fn MedicationUpdate(
+    op: Operation,
+    on_cancel: Callback,
+    on_save: Callback<Medication>,
+) -> Element {
+    let mut consumable = use_signal(|| match &op {
+        Operation::Create { .. } => None,
+        Operation::Update { medication } => Some(medication.consumable.clone()),
+    });
+    let dose = use_signal(|| match &op {
+        Operation::Create { .. } => String::new(),
+        Operation::Update { medication } => medication.dose.as_raw(),
+    });
+    let dose_unit = use_signal(|| match &op {
+        Operation::Create { .. } => String::new(),
+        Operation::Update { medication } => medication.dose_unit.as_raw(),
+    });
+    let mut route = use_signal(|| match &op {
+        Operation::Create { .. } => None,
+        Operation::Update { medication } => Some(medication.route),
+    });
+    let doses_per_day = use_signal(|| match &op {
+        Operation::Create { .. } => String::new(),
+        Operation::Update { medication } => medication.doses_per_day.as_raw(),
+    });
+    let active = use_signal(|| match &op {
+        Operation::Create { .. } => true,
+        Operation::Update { medication } => medication.active,
+    });
+    let comments = use_signal(|| match &op {
+        Operation::Create { .. } => String::new(),
+        Operation::Update { medication } => medication.comments.as_raw(),
+    });
+    let create_form = use_signal(|| false);
+
+    let validate = Validate {
+        consumable: use_memo(move || validate_medication_consumable(consumable())),
+        dose: use_memo(move || validate_medication_dose(&dose())),
+        dose_unit: use_memo(move || validate_dose_unit(&dose_unit())),
+        route: use_memo(move || validate_consumption_type(route())),
+        doses_per_day: use_memo(move || validate_doses_per_day(&doses_per_day())),
+        comments: use_memo(move || validate_comments(&comments())),
+    };
+
+    let dirty = use_dirty(move || {
+        (
+            consumable(),
+            dose(),
+            dose_unit(),
+            route(),
+            doses_per_day(),
+            active(),
+            comments(),
+        )
+    });
+
+    let mut saving = use_signal(|| Saving::No);
+
+    // disable form while waiting for response
+    let disabled = use_memo(move || saving.read().is_saving());
+    let disabled_save = use_memo(move || {
+        validate.consumable.read().is_err()
+            || validate.dose.read().is_err()
+            || validate.dose_unit.read().is_err()
+            || validate.route.read().is_err()
+            || validate.doses_per_day.read().is_err()
+            || validate.comments.read().is_err()
+            || disabled()
+    });
+
+    let op_clone = op.clone();
+    let validate_clone = validate.clone();
+    let on_save = use_callback(move |()| {
+        let op = op_clone.clone();
+        let validate = validate_clone.clone();
+        spawn(async move {
+            saving.set(Saving::Yes);
+
+            let result = do_save(&op, &validate, active()).await;
+
+            match result {
+                Ok(medication) => {
+                    saving.set(Saving::Finished(Ok(())));
+                    dirty.reset();
+                    on_save(medication);
+                }
+                Err(err) => saving.set(Saving::Finished(Err(err))),
+            }
+        });
+    });
+
+    // Default the route from the consumable when it has one.
+    let on_consumable_change = use_callback(move |value: Option<Consumable>| {
+        if route().is_none()
+            && let Some(consumption_type) = value.as_ref().and_then(|c| c.consumption_type)
+        {
+            route.set(Some(consumption_type));
+        }
+        consumable.set(value);
+    });
+
+    rsx! {
+        h3 { class: "text-lg font-bold",
+            match &op {
+                Operation::Create { .. } => t("Create Medication").to_string(),
+                Operation::Update { medication } => format!("{} {}", t("Edit Medication"), medication.name()),
+            }
+            UnsavedChanges { dirty: dirty.is_dirty() }
+        }
+        p { class: "py-4", "Press ESC key or click the button below to close" }
+        form {
+            novalidate: true,
+            action: "javascript:void(0)",
+            method: "dialog",
+            onkeyup: move |event| {
+                if event.key() == Key::Escape {
+                    on_cancel(());
+                }
+            },
+            InputConsumable {
+                id: "consumable",
+                label: "Drug",
+                value: consumable,
+                on_create: move |value| on_consumable_change(Some(value)),
+                on_change: on_consumable_change,
+                create_form,
+                disabled,
+            }
+            if !create_form() {
+                InputNumber {
+                    id: "dose",
+                    label: "Dose",
+                    value: dose,
+                    validate: validate.dose,
+                    disabled,
+                }
+                InputString {
+                    id: "dose_unit",
+                    label: "Dose Unit",
+                    value: dose_unit,
+                    validate: validate.dose_unit,
+                    disabled,
+                }
+                InputConsumptionType {
+                    id: "route",
+                    label: "Route",
+                    value: route,
+                    validate: validate.route,
+                    disabled,
+                }
+                InputNumber {
+                    id: "doses_per_day",
+                    label: "Doses per day (empty if taken as required)",
+                    value: doses_per_day,
+                    validate: validate.doses_per_day,
+                    disabled,
+                }
+                InputBoolean {
+                    id: "active",
+                    label: "Active",
+                    value: active,
+                    disabled,
+                }
+                InputTextArea {
+                    id: "comments",
+                    label: "Comments",
+                    value: comments,
+                    validate: validate.comments,
+                    disabled,
+                }
+                FormSaveCancelButton {
+                    disabled: disabled_save,
+                    on_save: move |()| on_save(()),
+                    on_cancel: move |_| on_cancel(()),
+                    title: match &op {
+                        Operation::Create { .. } => t("Create"),
+                        Operation::Update { .. } => t("Save"),
+                    },
+                    saving,
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn MedicationDelete(
+    medication: Medication,
+    on_cancel: Callback,
+    on_delete: Callback<Medication>,
+) -> Element {
+    let mut saving = use_signal(|| Saving::No);
+
+    let disabled = use_memo(move || saving.read().is_saving());
+
+    let medication_clone = medication.clone();
+    let on_save = use_callback(move |()| {
+        let medication = medication_clone.clone();
+        spawn(async move {
+            saving.set(Saving::Yes);
+
+            match delete_medication(medication.id).await {
+                Ok(_) => {
+                    saving.set(Saving::Finished(Ok(())));
+                    on_delete(medication.clone());
+                }
+                Err(err) => saving.set(Saving::Finished(Err(EditError::Server(err)))),
+            }
+        });
+    });
+
+    rsx! {
+        h3 { class: "text-lg font-bold",
+            "Delete medication "
+            {medication.name()}
+        }
+        p { class: "py-4", "Press ESC key or click the button below to close" }
+        MedicationDetails { medication: medication.clone() }
+        p { class: "py-4",
+            "Medications with recorded doses cannot be deleted, mark them inactive instead."
+        }
+        form {
+            novalidate: true,
+            action: "javascript:void(0)",
+            method: "dialog",
+            onkeyup: move |event| {
+                if event.key() == Key::Escape {
+                    on_cancel(());
+                }
+            },
+            FormSaveCancelButton {
+                disabled,
+                on_save: move |()| on_save(()),
+                on_cancel: move |_| on_cancel(()),
+                title: "Delete",
+                saving,
+            }
+        }
+    }
+}
+
+const MEDICATION_SVG: Asset = asset!("/assets/medication.svg");
+
+#[component]
+pub fn MedicationIcon() -> Element {
+    let alt = medication_title();
+    rsx! {
+        img { alt, title: alt, src: MEDICATION_SVG }
+    }
+}
+
+#[component]
+pub fn medication_title() -> &'static str {
+    "Medication"
+}
+
+#[component]
+pub fn MedicationLabel(medication: Medication) -> Element {
+    rsx! {
+        span {
+            {medication.name()}
+            " "
+            {medication.dose_string()}
+            " ("
+            {medication.route.as_title()}
+            ")"
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ActiveDialog {
+    Change(Operation),
+    Delete(Medication),
+    Idle,
+}
+
+#[component]
+pub fn MedicationDialog(
+    dialog: ActiveDialog,
+    on_close: Callback<()>,
+    on_change: Callback<Medication>,
+    on_delete: Callback<Medication>,
+) -> Element {
+    match dialog {
+        ActiveDialog::Change(op) => {
+            rsx! {
+                Dialog {
+                    MedicationUpdate { op, on_cancel: on_close, on_save: on_change }
+                }
+            }
+        }
+        ActiveDialog::Delete(medication) => {
+            rsx! {
+                Dialog {
+                    MedicationDelete { medication, on_cancel: on_close, on_delete }
+                }
+            }
+        }
+        ActiveDialog::Idle => {
+            rsx! {}
+        }
+    }
+}
+
+#[component]
+pub fn MedicationDetails(medication: Medication) -> Element {
+    rsx! {
+        div {
+            MedicationLabel { medication: medication.clone() }
+        }
+        div {
+            match medication.doses_per_day {
+                Some(doses) => format!("{doses} per day"),
+                None => "As required".to_string(),
+            }
+        }
+        if !medication.active {
+            div { class: "text-warning", "Inactive" }
+        }
+        if let Some(comments) = &medication.comments {
+            Markdown { content: comments.to_string() }
+        }
+    }
+}
+
+#[component]
+pub fn MedicationAdherenceList(adherence: Vec<MedicationAdherence>) -> Element {
+    rsx! {
+        if adherence.is_empty() {
+            p { "No active medications." }
+        }
+        ul {
+            for item in adherence {
+                li {
+                    class: if item.is_complete() { "text-success" } else { "text-warning" },
+                    MedicationLabel { medication: item.medication.clone() }
+                    ": "
+                    match item.scheduled() {
+                        Some(scheduled) => format!("{} of {} doses taken", item.taken, scheduled),
+                        None => format!("{} doses taken (as required)", item.taken),
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ListDialogReferenceError {
+    #[error("Invalid integer")]
+    ParseIntError(#[from] ParseIntError),
+
+    #[error("Invalid reference")]
+    ReferenceError,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum ListDialogReference {
+    Create,
+    Update {
+        medication_id: MedicationId,
+    },
+    Delete {
+        medication_id: MedicationId,
+    },
+    #[default]
+    Idle,
+}
+
+impl ToQueryArgument for ListDialogReference {
+    fn display_query_argument(
+        &self,
+        query_name: &str,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(f, "{}={}", query_name, self.to_string())
+    }
+}
+
+impl FromStr for ListDialogReference {
+    type Err = ListDialogReferenceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let split = s.split("-").collect::<Vec<_>>();
+        match split[..] {
+            ["create"] => Self::Create,
+            ["update", id] => {
+                let medication_id = MedicationId::new(id.parse()?);
+                Self::Update { medication_id }
+            }
+            ["delete", id] => {
+                let medication_id = MedicationId::new(id.parse()?);
+                Self::Delete { medication_id }
+            }
+            [""] | [] => Self::Idle,
+            _ => return Err(ListDialogReferenceError::ReferenceError),
+        }
+        .pipe(Ok)
+    }
+}
+
+#[allow(clippy::to_string_trait_impl)]
+impl ToString for ListDialogReference {
+    fn to_string(&self) -> String {
+        match self {
+            ListDialogReference::Create => "create".to_string(),
+            ListDialogReference::Update { medication_id } => format!("update-{medication_id}"),
+            ListDialogReference::Delete { medication_id } => format!("delete-{medication_id}"),
+            ListDialogReference::Idle => String::new(),
+        }
+    }
+}
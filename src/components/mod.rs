@@ -1,15 +1,21 @@
 pub mod buttons;
 pub mod consumables;
 pub mod consumptions;
+pub mod entry_templates;
 pub mod events;
 pub mod exercises;
+pub mod fluid_adjustments;
 pub mod health_metrics;
+pub mod medication_doses;
+pub mod medications;
 pub mod navbar;
 pub mod notes;
 pub mod poos;
 pub mod refluxs;
+pub mod stats;
 pub mod symptoms;
 pub mod timeline;
+pub mod undo;
 pub mod users;
 pub mod wee_urges;
 pub mod wees;
@@ -21,7 +27,7 @@ use dioxus::prelude::*;
 #[component]
 pub fn StrIcon(title: &'static str, icon: Element) -> Element {
     rsx! {
-        div { class: "text-sm w-10 dark:invert inline-block", {icon} }
+        div { class: "text-sm w-10 dark:invert inline-block", title, {icon} }
         span { class: "text-sm my-auto text-left", {title} }
     }
 }
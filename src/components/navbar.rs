@@ -1,10 +1,14 @@
 use crate::{
     Route,
-    components::{consumables, timeline},
-    dt::get_date_for_dt,
-    use_user,
+    components::{
+        consumables, timeline,
+        undo::{UndoButton, use_undo_log_provider},
+    },
+    dt::get_today,
+    functions::users::update_preferences,
+    models::{ChangePreferences, MaybeSet, SortDirection, Theme},
+    reload_user, use_timezone, use_user,
 };
-use chrono::Utc;
 use dioxus::prelude::*;
 use dioxus_router::{Link, Outlet, use_route};
 
@@ -33,18 +37,56 @@ pub fn MenuItem(route: Route, title: String, mut show_menu: Signal<bool>) -> Ele
     }
 }
 
+/// Cycle the logged in user's theme between the system setting, light and
+/// dark.
+#[component]
+fn ThemeToggle(theme: Option<Theme>) -> Element {
+    let mut saving = use_signal(|| false);
+    let (icon, title) = match theme {
+        None => ("◐", "Theme: system setting"),
+        Some(Theme::Light) => ("☀", "Theme: light"),
+        Some(Theme::Dark) => ("☾", "Theme: dark"),
+    };
+
+    rsx! {
+        button {
+            r#type: "button",
+            class: "btn btn-ghost btn-sm",
+            title,
+            "aria-label": title,
+            disabled: saving(),
+            onclick: move |_| {
+                spawn(async move {
+                    saving.set(true);
+                    let changes = ChangePreferences {
+                        theme: MaybeSet::Set(Theme::next(theme)),
+                        ..Default::default()
+                    };
+                    if update_preferences(changes).await.is_ok() {
+                        reload_user();
+                    }
+                    saving.set(false);
+                });
+            },
+            "{icon}"
+        }
+    }
+}
+
 #[component]
 pub fn Navbar() -> Element {
     let mut show_menu = use_signal(|| false);
     let user_result = use_user();
     let user = user_result.as_ref().ok().and_then(|x| x.as_ref());
 
-    let date = get_date_for_dt(Utc::now());
+    let date = get_today(use_timezone());
+    let current: Route = use_route();
+    use_undo_log_provider(current.clone());
 
     let menu_class = if show_menu() { "" } else { "hidden" };
 
     rsx! {
-        nav { class: "bg-white border-gray-200 dark:bg-gray-900 dark:border-gray-700",
+        nav { class: "bg-white border-gray-200 dark:bg-gray-900 dark:border-gray-700 print:hidden",
             div { class: "flex flex-wrap items-center justify-between mx-auto p-4",
                 Link {
                     to: Route::Home {},
@@ -54,6 +96,10 @@ pub fn Navbar() -> Element {
                         "Penguin Nurse"
                     }
                 }
+                UndoButton {}
+                if let Some(user) = user {
+                    ThemeToggle { theme: user.theme }
+                }
                 button {
                     "data-collapse-toggle": "navbar-multi-level",
                     "aria-controls": "navbar-multi-level",
@@ -87,10 +133,19 @@ pub fn Navbar() -> Element {
                             route: Route::TimelineList {
                                 date,
                                 dialog: timeline::DialogReference::Idle,
+                                sort: SortDirection::default(),
+                                focus: timeline::EntryFocus::default(),
                             },
                             title: "Today",
                             show_menu,
                         }
+                        MenuItem {
+                            route: Route::EntrySearch {
+                                query: String::new(),
+                            },
+                            title: "Search",
+                            show_menu,
+                        }
                         MenuItem {
                             route: Route::ConsumableList {
                                 dialog: consumables::ListDialogReference::Idle,
@@ -98,16 +153,30 @@ pub fn Navbar() -> Element {
                             title: "Consumables",
                             show_menu,
                         }
+                        MenuItem {
+                            route: Route::MedicationList {
+                                dialog: crate::components::medications::ListDialogReference::Idle,
+                            },
+                            title: "Medications",
+                            show_menu,
+                        }
                         if let Some(user) = user {
                             if user.is_admin {
                                 MenuItem {
                                     route: Route::UserList {
                                         dialog: crate::components::users::ListDialogReference::Idle,
+                                        page: 0,
+                                        search: String::new(),
                                     },
                                     title: "Users",
                                     show_menu,
                                 }
                             }
+                            MenuItem {
+                                route: Route::Preferences {},
+                                title: "Preferences",
+                                show_menu,
+                            }
                             MenuItem {
                                 route: Route::Logout {},
                                 title: "Logout",
@@ -115,7 +184,9 @@ pub fn Navbar() -> Element {
                             }
                         } else {
                             MenuItem {
-                                route: Route::Login {},
+                                route: Route::Login {
+                                    next: current.to_string(),
+                                },
                                 title: "Login",
                                 show_menu,
                             }
@@ -131,7 +202,7 @@ pub fn Navbar() -> Element {
 
         Outlet::<Route> {}
 
-        footer { class: "footer bg-base-200 text-base-content p-10 mt-20",
+        footer { class: "footer bg-base-200 text-base-content p-10 mt-20 print:hidden",
             aside {
                 div {
                     "version: "
@@ -4,21 +4,28 @@ use dioxus::prelude::*;
 use crate::{
     components::{
         events::{EventDateTimeShort, Markdown},
-        times::time_delta_to_string,
+        times::time_delta_to_string_rounded,
     },
     forms::{
         Dialog, EditError, FieldValue, FormSaveCancelButton, InputDateTime, InputTextArea, Saving,
         ValidationError, validate_comments, validate_fixed_offset_date_time,
     },
     functions::notes::{create_note, delete_note, update_note},
+    i18n::t,
     models::{ChangeNote, MaybeSet, NewNote, Note, UserId},
+    use_duration_rounding, use_timezone,
 };
 use classes::classes;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Operation {
-    Create { user_id: UserId },
-    Update { note: Note },
+    Create {
+        user_id: UserId,
+        template: Option<NewNote>,
+    },
+    Update {
+        note: Note,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -32,7 +39,7 @@ async fn do_save(op: &Operation, validate: &Validate) -> Result<Note, EditError>
     let comments = validate.comments.read().clone()?;
 
     match op {
-        Operation::Create { user_id } => {
+        Operation::Create { user_id, .. } => {
             let updates = NewNote {
                 user_id: *user_id,
                 time,
@@ -61,12 +68,17 @@ pub fn NoteUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Note>) -
     });
 
     let comments = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(note),
+            ..
+        } => note.comments.as_raw(),
         Operation::Create { .. } => String::new(),
         Operation::Update { note } => note.comments.as_raw(),
     });
 
+    let timezone = use_timezone();
     let validate = Validate {
-        time: use_memo(move || validate_fixed_offset_date_time(&time())),
+        time: use_memo(move || validate_fixed_offset_date_time(&time(), timezone)),
         comments: use_memo(move || validate_comments(&comments())),
     };
 
@@ -101,8 +113,8 @@ pub fn NoteUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Note>) -
     rsx! {
         h3 { class: "text-lg font-bold",
             match &op {
-                Operation::Create { .. } => "Create Note".to_string(),
-                Operation::Update { note } => format!("Edit Note {}", note.name()),
+                Operation::Create { .. } => t("Create Note").to_string(),
+                Operation::Update { note } => format!("{} {}", t("Edit Note"), note.name()),
             }
         }
         p { class: "py-4", "Press ESC key or click the button below to close" }
@@ -134,8 +146,8 @@ pub fn NoteUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Note>) -
                 on_save: move |()| on_save(()),
                 on_cancel: move |_| on_cancel(()),
                 title: match &op {
-                    Operation::Create { .. } => "Create",
-                    Operation::Update { .. } => "Save",
+                    Operation::Create { .. } => t("Create"),
+                    Operation::Update { .. } => t("Save"),
                 },
                 saving,
             }
@@ -199,7 +211,7 @@ pub fn note_icon() -> Element {
     let alt = note_title();
     let icon = NOTE_SVG;
     rsx! {
-        img { alt, src: icon }
+        img { alt, title: alt, src: icon }
     }
 }
 
@@ -210,7 +222,7 @@ pub fn note_title() -> &'static str {
 
 #[component]
 pub fn note_duration(duration: chrono::TimeDelta) -> Element {
-    let text = time_delta_to_string(duration);
+    let text = time_delta_to_string_rounded(duration, use_duration_rounding());
 
     rsx! {
         span { {text} }
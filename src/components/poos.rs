@@ -6,22 +6,33 @@ use palette::Hsv;
 use crate::{
     components::{
         events::{EventTime, Markdown, UrgencyLabel, event_colour},
-        times::time_delta_to_string,
+        times::time_delta_to_string_rounded,
     },
     forms::{
         Colour, Dialog, EditError, FieldValue, FormSaveCancelButton, InputColour, InputDateTime,
-        InputDuration, InputNumber, InputPooBristolType, InputTextArea, InputUrgency, Saving,
-        ValidationError, validate_bristol, validate_colour, validate_comments, validate_duration,
-        validate_fixed_offset_date_time, validate_poo_quantity, validate_urgency,
+        InputDuration, InputPooBristolType, InputPooQuantity, InputTextArea, InputUrgency, Saving,
+        UnsavedChanges, ValidationError, use_dirty, validate_bristol, validate_colour,
+        validate_comments, validate_duration, validate_fixed_offset_date_time,
+        validate_poo_quantity, validate_urgency,
     },
-    functions::poos::{create_poo, delete_poo, update_poo},
-    models::{Bristol, ChangePoo, MaybeSet, NewPoo, Poo, Urgency, UserId},
+    functions::poos::{create_poo, delete_poo, find_duplicate_poo, update_poo},
+    i18n::t,
+    models::{
+        Bristol, ChangePoo, ColourPaletteKind, EntryKind, MaybeSet, NewPoo, Poo, PooSize, Urgency,
+        UserId,
+    },
+    use_duration_rounding, use_timezone,
 };
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Operation {
-    Create { user_id: UserId },
-    Update { poo: Poo },
+    Create {
+        user_id: UserId,
+        template: Option<NewPoo>,
+    },
+    Update {
+        poo: Poo,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -35,7 +46,11 @@ struct Validate {
     comments: Memo<Result<Option<String>, ValidationError>>,
 }
 
-async fn do_save(op: &Operation, validate: &Validate) -> Result<Poo, EditError> {
+async fn do_save(
+    op: &Operation,
+    validate: &Validate,
+    allow_duplicate: bool,
+) -> Result<Poo, EditError> {
     let time = validate.time.read().clone()?;
     let duration = validate.duration.read().clone()?;
     let urgency = validate.urgency.read().clone()?;
@@ -45,7 +60,7 @@ async fn do_save(op: &Operation, validate: &Validate) -> Result<Poo, EditError>
     let comments = validate.comments.read().clone()?;
 
     match op {
-        Operation::Create { user_id } => {
+        Operation::Create { user_id, .. } => {
             let updates = NewPoo {
                 user_id: *user_id,
                 time,
@@ -56,6 +71,14 @@ async fn do_save(op: &Operation, validate: &Validate) -> Result<Poo, EditError>
                 colour,
                 comments,
             };
+            if !allow_duplicate
+                && find_duplicate_poo(updates.clone())
+                    .await
+                    .map_err(EditError::Server)?
+                    .is_some()
+            {
+                return Err(EditError::Duplicate);
+            }
             create_poo(updates).await.map_err(EditError::Server)
         }
         Operation::Update { poo } => {
@@ -81,22 +104,52 @@ pub fn PooUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Poo>) ->
         Operation::Update { poo } => poo.time.as_raw(),
     });
     let duration = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(poo),
+            ..
+        } => poo.duration.as_raw(),
         Operation::Create { .. } => String::new(),
         Operation::Update { poo } => poo.duration.as_raw(),
     });
     let urgency = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(poo),
+            ..
+        } => Some(poo.urgency),
         Operation::Create { .. } => None,
         Operation::Update { poo } => Some(poo.urgency),
     });
     let quantity = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(poo),
+            ..
+        } => poo.quantity.as_raw(),
         Operation::Create { .. } => String::new(),
         Operation::Update { poo } => poo.quantity.as_raw(),
     });
     let bristol = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(poo),
+            ..
+        } => Some(poo.bristol),
         Operation::Create { .. } => None,
         Operation::Update { poo } => Some(poo.bristol),
     });
     let colour = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(poo),
+            ..
+        } => {
+            if let Some(colour) = poo.colour {
+                (
+                    colour.hue.as_raw(),
+                    colour.saturation.as_raw(),
+                    colour.value.as_raw(),
+                )
+            } else {
+                (String::new(), String::new(), String::new())
+            }
+        }
         Operation::Create { .. } => (String::new(), String::new(), String::new()),
         Operation::Update { poo } => {
             if let Some(colour) = poo.colour {
@@ -111,14 +164,19 @@ pub fn PooUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Poo>) ->
         }
     });
     let comments = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(poo),
+            ..
+        } => poo.comments.as_ref().cloned().unwrap_or_default(),
         Operation::Create { .. } => "".to_string(),
         Operation::Update { poo } => poo.comments.as_ref().cloned().unwrap_or_default(),
     });
 
+    let timezone = use_timezone();
     let validate = {
         let validate_quantity = use_memo(move || validate_poo_quantity(&quantity()));
         Validate {
-            time: use_memo(move || validate_fixed_offset_date_time(&time())),
+            time: use_memo(move || validate_fixed_offset_date_time(&time(), timezone)),
             duration: use_memo(move || validate_duration(&duration())),
             urgency: use_memo(move || validate_urgency(urgency())),
             quantity: validate_quantity,
@@ -128,6 +186,18 @@ pub fn PooUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Poo>) ->
         }
     };
 
+    let dirty = use_dirty(move || {
+        (
+            time(),
+            duration(),
+            urgency(),
+            quantity(),
+            bristol(),
+            colour(),
+            comments(),
+        )
+    });
+
     let mut saving = use_signal(|| Saving::No);
 
     // disable form while waiting for response
@@ -149,12 +219,15 @@ pub fn PooUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Poo>) ->
         let op = op_clone.clone();
         let validate = validate_clone.clone();
         spawn(async move {
+            let allow_duplicate =
+                matches!(&*saving.peek(), Saving::Finished(Err(err)) if err.is_duplicate());
             saving.set(Saving::Yes);
 
-            let result = do_save(&op, &validate).await;
+            let result = do_save(&op, &validate, allow_duplicate).await;
             match result {
                 Ok(poo) => {
                     saving.set(Saving::Finished(Ok(())));
+                    dirty.reset();
                     on_save(poo);
                 }
                 Err(err) => saving.set(Saving::Finished(Err(err))),
@@ -165,9 +238,10 @@ pub fn PooUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Poo>) ->
     rsx! {
         h3 { class: "text-lg font-bold",
             match &op {
-                Operation::Create { .. } => "Create Poo".to_string(),
-                Operation::Update { poo } => format!("Edit Poo {}", poo.id),
+                Operation::Create { .. } => t("Create Poo").to_string(),
+                Operation::Update { poo } => format!("{} {}", t("Edit Poo"), poo.id),
             }
+            UnsavedChanges { dirty: dirty.is_dirty() }
         }
         p { class: "py-4", "Press ESC key or click the button below to close" }
         form {
@@ -193,6 +267,7 @@ pub fn PooUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Poo>) ->
                 start_time: validate.time,
                 validate: validate.duration,
                 disabled,
+                kind: EntryKind::Poo,
             }
             InputUrgency {
                 id: "urgency",
@@ -201,7 +276,7 @@ pub fn PooUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Poo>) ->
                 validate: validate.urgency,
                 disabled,
             }
-            InputNumber {
+            InputPooQuantity {
                 id: "quantity",
                 label: "Quantity",
                 value: quantity,
@@ -220,12 +295,7 @@ pub fn PooUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Poo>) ->
                 label: "Colour",
                 value: colour,
                 validate: validate.colour,
-                colours: vec![
-                    ("light".to_string(), Hsv::new(25.0, 1.0, 0.8)),
-                    ("normal".to_string(), Hsv::new(25.0, 1.0, 0.5)),
-                    ("dark".to_string(), Hsv::new(25.0, 1.0, 0.2)),
-                    ("red".to_string(), Hsv::new(0.0, 1.0, 1.0)),
-                ],
+                palette: ColourPaletteKind::Poo,
                 disabled,
             }
             Colour { colour }
@@ -242,8 +312,8 @@ pub fn PooUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Poo>) ->
                 on_save: move |()| on_save(()),
                 on_cancel: move |()| on_cancel(()),
                 title: match &op {
-                    Operation::Create { .. } => "Create",
-                    Operation::Update { .. } => "Save",
+                    Operation::Create { .. } => t("Create"),
+                    Operation::Update { .. } => t("Save"),
                 },
                 saving,
             }
@@ -276,7 +346,7 @@ pub fn PooDelete(poo: Poo, on_cancel: Callback, on_delete: Callback<Poo>) -> Ele
     rsx! {
         h3 { class: "text-lg font-bold",
             "Delete poo "
-            {poo.id.to_string()}
+            {poo.name()}
         }
         p { class: "py-4", "Press ESC key or click the button below to close" }
         PooSummary { poo: poo.clone() }
@@ -306,7 +376,7 @@ const POO_SVG: Asset = asset!("/assets/poo.svg");
 pub fn PooIcon() -> Element {
     let alt = poo_title();
     rsx! {
-        img { alt, src: POO_SVG }
+        img { alt, title: alt, src: POO_SVG }
     }
 }
 
@@ -316,7 +386,7 @@ pub fn poo_title() -> &'static str {
 
 #[component]
 pub fn PooDuration(duration: chrono::TimeDelta) -> Element {
-    let text = time_delta_to_string(duration);
+    let text = time_delta_to_string_rounded(duration, use_duration_rounding());
 
     let classes = if duration.num_seconds() == 0 {
         classes!["text-error"]
@@ -366,7 +436,11 @@ pub fn PooBristolIcon(bristol: Bristol) -> Element {
         Bristol::B7 => "7",
     };
     rsx! {
-        div { class: "text-sm w-10 dark:invert inline-block", {icon} }
+        div {
+            class: "text-sm w-10 dark:invert inline-block",
+            title: bristol.as_title(),
+            {icon}
+        }
     }
 }
 
@@ -381,7 +455,12 @@ pub fn PooQuantity(quantity: i32) -> Element {
     };
 
     rsx! {
-        span { class: classes, {quantity.to_string() + " out of 10"} }
+        span { class: classes,
+            {PooSize::from_quantity(quantity).as_title()}
+            " ("
+            {quantity.to_string()}
+            " out of 10)"
+        }
     }
 }
 
@@ -5,23 +5,30 @@ use crate::{
     components::{
         events::{EventDateTimeShort, Markdown},
         symptoms::SymptomDisplay,
-        times::time_delta_to_string,
+        times::time_delta_to_string_rounded,
     },
     forms::{
         Dialog, EditError, FieldValue, FormSaveCancelButton, InputDateTime, InputDuration,
-        InputSymptomIntensity, InputTextArea, Saving, ValidationError, validate_comments,
-        validate_duration, validate_fixed_offset_date_time, validate_location,
+        InputSymptomIntensity, InputTextArea, Saving, UnsavedChanges, ValidationError, use_dirty,
+        validate_comments, validate_duration, validate_fixed_offset_date_time, validate_location,
         validate_symptom_intensity,
     },
     functions::refluxs::{create_reflux, delete_reflux, update_reflux},
-    models::{ChangeReflux, MaybeSet, NewReflux, Reflux, UserId},
+    i18n::t,
+    models::{ChangeReflux, EntryKind, MaybeSet, NewReflux, Reflux, UserId},
+    use_duration_rounding, use_timezone,
 };
 use classes::classes;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Operation {
-    Create { user_id: UserId },
-    Update { reflux: Reflux },
+    Create {
+        user_id: UserId,
+        template: Option<NewReflux>,
+    },
+    Update {
+        reflux: Reflux,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -41,7 +48,7 @@ async fn do_save(op: &Operation, validate: &Validate) -> Result<Reflux, EditErro
     let comments = validate.comments.read().clone()?;
 
     match op {
-        Operation::Create { user_id } => {
+        Operation::Create { user_id, .. } => {
             let updates = NewReflux {
                 user_id: *user_id,
                 time,
@@ -76,33 +83,52 @@ pub fn RefluxUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Reflux
     });
 
     let duration = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(reflux),
+            ..
+        } => reflux.duration.as_raw(),
         Operation::Create { .. } => String::new(),
         Operation::Update { reflux } => reflux.duration.as_raw(),
     });
 
     let location = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(reflux),
+            ..
+        } => reflux.location.as_raw(),
         Operation::Create { .. } => String::new(),
         Operation::Update { reflux } => reflux.location.as_raw(),
     });
 
     let severity = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(reflux),
+            ..
+        } => reflux.severity.to_string(),
         Operation::Create { .. } => String::new(),
         Operation::Update { reflux } => reflux.severity.to_string(),
     });
 
     let comments = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(reflux),
+            ..
+        } => reflux.comments.as_raw(),
         Operation::Create { .. } => String::new(),
         Operation::Update { reflux } => reflux.comments.as_raw(),
     });
 
+    let timezone = use_timezone();
     let validate = Validate {
-        time: use_memo(move || validate_fixed_offset_date_time(&time())),
+        time: use_memo(move || validate_fixed_offset_date_time(&time(), timezone)),
         duration: use_memo(move || validate_duration(&duration())),
         location: use_memo(move || validate_location(&location())),
         severity: use_memo(move || validate_symptom_intensity(&severity())),
         comments: use_memo(move || validate_comments(&comments())),
     };
 
+    let dirty = use_dirty(move || (time(), duration(), location(), severity(), comments()));
+
     let mut saving = use_signal(|| Saving::No);
 
     // disable form while waiting for response
@@ -129,6 +155,7 @@ pub fn RefluxUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Reflux
             match result {
                 Ok(consumable) => {
                     saving.set(Saving::Finished(Ok(())));
+                    dirty.reset();
                     on_save(consumable);
                 }
                 Err(err) => saving.set(Saving::Finished(Err(err))),
@@ -139,9 +166,10 @@ pub fn RefluxUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Reflux
     rsx! {
         h3 { class: "text-lg font-bold",
             match &op {
-                Operation::Create { .. } => "Create Reflux".to_string(),
-                Operation::Update { reflux } => format!("Edit Reflux {}", reflux.name()),
+                Operation::Create { .. } => t("Create Reflux").to_string(),
+                Operation::Update { reflux } => format!("{} {}", t("Edit Reflux"), reflux.name()),
             }
+            UnsavedChanges { dirty: dirty.is_dirty() }
         }
         p { class: "py-4", "Press ESC key or click the button below to close" }
         form {
@@ -167,6 +195,7 @@ pub fn RefluxUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Reflux
                 start_time: validate.time,
                 validate: validate.duration,
                 disabled,
+                kind: EntryKind::Reflux,
             }
             InputTextArea {
                 id: "location",
@@ -194,8 +223,8 @@ pub fn RefluxUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Reflux
                 on_save: move |()| on_save(()),
                 on_cancel: move |_| on_cancel(()),
                 title: match &op {
-                    Operation::Create { .. } => "Create",
-                    Operation::Update { .. } => "Save",
+                    Operation::Create { .. } => t("Create"),
+                    Operation::Update { .. } => t("Save"),
                 },
                 saving,
             }
@@ -259,7 +288,7 @@ pub fn reflux_icon() -> Element {
     let alt = reflux_title();
     let icon = REFLUX_SVG;
     rsx! {
-        img { alt, src: icon }
+        img { alt, title: alt, src: icon }
     }
 }
 
@@ -270,7 +299,7 @@ pub fn reflux_title() -> &'static str {
 
 #[component]
 pub fn reflux_duration(duration: chrono::TimeDelta) -> Element {
-    let text = time_delta_to_string(duration);
+    let text = time_delta_to_string_rounded(duration, use_duration_rounding());
 
     rsx! {
         span { {text} }
@@ -0,0 +1,293 @@
+use std::ops::Deref;
+
+use bigdecimal::{BigDecimal, RoundingMode};
+use chrono::{TimeDelta, Utc};
+use dioxus::prelude::*;
+use dioxus_fullstack::ServerFnError;
+
+use crate::{
+    components::times::time_delta_to_string,
+    dt::get_today,
+    functions::stats::{
+        daily_nutrition, duration_stats, time_of_day_histogram, wee_urge_latency_stats,
+    },
+    models::{
+        DurationKind, DurationStats, EntryKind, NutritionTotals, UserId, WeeUrgeLatencyStats,
+    },
+    use_timezone,
+};
+
+const STATS_PERIODS: [i64; 3] = [7, 30, 90];
+
+fn optional_duration(duration: Option<TimeDelta>) -> String {
+    duration.map_or_else(|| "-".to_string(), time_delta_to_string)
+}
+
+#[component]
+pub fn DurationStatsPanel(user_id: UserId) -> Element {
+    let mut kind = use_signal(|| DurationKind::Wee);
+    let mut days = use_signal(|| STATS_PERIODS[0]);
+
+    let stats: Resource<Result<DurationStats, ServerFnError>> = use_resource(move || async move {
+        let end = Utc::now();
+        let start = end - TimeDelta::days(days());
+        duration_stats(user_id, kind(), start, end).await
+    });
+
+    rsx! {
+        h2 { class: "font-bold text-lg", "Duration Stats" }
+        div { class: "flex gap-2 mb-2",
+            select {
+                class: "select select-sm",
+                value: kind().as_id(),
+                onchange: move |e| {
+                    if let Ok(value) = e.value().parse() {
+                        kind.set(value);
+                    }
+                },
+                for value in DurationKind::all_values() {
+                    option { value: value.as_id(), {value.as_title()} }
+                }
+            }
+            select {
+                class: "select select-sm",
+                value: days().to_string(),
+                onchange: move |e| {
+                    if let Ok(value) = e.value().parse() {
+                        days.set(value);
+                    }
+                },
+                for period in STATS_PERIODS {
+                    option { value: period.to_string(), "Last {period} days" }
+                }
+            }
+        }
+        match stats.read().deref() {
+            Some(Err(err)) => rsx! {
+                div { class: "alert alert-error",
+                    "Error loading stats: "
+                    {err.to_string()}
+                }
+            },
+            Some(Ok(stats)) => rsx! {
+                table { class: "table table-sm w-auto",
+                    tbody {
+                        tr {
+                            td { "Entries" }
+                            td { "{stats.count} ({stats.timed_count} timed)" }
+                        }
+                        tr {
+                            td { "Total" }
+                            td { {time_delta_to_string(stats.total)} }
+                        }
+                        tr {
+                            td { "Mean" }
+                            td { {optional_duration(stats.mean)} }
+                        }
+                        tr {
+                            td { "Median" }
+                            td { {optional_duration(stats.median)} }
+                        }
+                        tr {
+                            td { "90th percentile" }
+                            td { {optional_duration(stats.p90)} }
+                        }
+                    }
+                }
+            },
+            None => rsx! {
+                p { class: "alert alert-info", "Loading..." }
+            },
+        }
+    }
+}
+
+#[component]
+pub fn WeeUrgeLatencyPanel(user_id: UserId) -> Element {
+    let mut days = use_signal(|| STATS_PERIODS[0]);
+
+    let stats: Resource<Result<WeeUrgeLatencyStats, ServerFnError>> =
+        use_resource(move || async move {
+            let end = Utc::now();
+            let start = end - TimeDelta::days(days());
+            wee_urge_latency_stats(user_id, start, end).await
+        });
+
+    rsx! {
+        h2 { class: "font-bold text-lg", "Urge to Wee" }
+        div { class: "flex gap-2 mb-2",
+            select {
+                class: "select select-sm",
+                value: days().to_string(),
+                onchange: move |e| {
+                    if let Ok(value) = e.value().parse() {
+                        days.set(value);
+                    }
+                },
+                for period in STATS_PERIODS {
+                    option { value: period.to_string(), "Last {period} days" }
+                }
+            }
+        }
+        match stats.read().deref() {
+            Some(Err(err)) => rsx! {
+                div { class: "alert alert-error",
+                    "Error loading stats: "
+                    {err.to_string()}
+                }
+            },
+            Some(Ok(stats)) => rsx! {
+                table { class: "table table-sm w-auto",
+                    tbody {
+                        tr {
+                            td { "Urges" }
+                            td { "{stats.urges} ({stats.linked} followed by a wee)" }
+                        }
+                        tr {
+                            td { "Mean wait" }
+                            td { {optional_duration(stats.mean)} }
+                        }
+                        tr {
+                            td { "Median wait" }
+                            td { {optional_duration(stats.median)} }
+                        }
+                    }
+                }
+            },
+            None => rsx! {
+                p { class: "alert alert-info", "Loading..." }
+            },
+        }
+    }
+}
+
+#[component]
+pub fn TimeOfDayPanel(user_id: UserId) -> Element {
+    let mut kind = use_signal(|| EntryKind::Consumption);
+    let mut days = use_signal(|| STATS_PERIODS[1]);
+
+    let hours: Resource<Result<[usize; 24], ServerFnError>> = use_resource(move || async move {
+        let end = Utc::now();
+        let start = end - TimeDelta::days(days());
+        time_of_day_histogram(user_id, kind(), start, end).await
+    });
+
+    rsx! {
+        h2 { class: "font-bold text-lg", "Time of Day" }
+        div { class: "flex gap-2 mb-2",
+            select {
+                class: "select select-sm",
+                value: kind().as_id(),
+                onchange: move |e| {
+                    if let Ok(value) = e.value().parse() {
+                        kind.set(value);
+                    }
+                },
+                for value in EntryKind::all_values() {
+                    option { value: value.as_id(), {value.as_title()} }
+                }
+            }
+            select {
+                class: "select select-sm",
+                value: days().to_string(),
+                onchange: move |e| {
+                    if let Ok(value) = e.value().parse() {
+                        days.set(value);
+                    }
+                },
+                for period in STATS_PERIODS {
+                    option { value: period.to_string(), "Last {period} days" }
+                }
+            }
+        }
+        match hours.read().deref() {
+            Some(Err(err)) => rsx! {
+                div { class: "alert alert-error",
+                    "Error loading stats: "
+                    {err.to_string()}
+                }
+            },
+            Some(Ok(hours)) => {
+                let max = hours.iter().copied().max().unwrap_or(0).max(1);
+                rsx! {
+                    div { class: "flex items-end gap-px h-24 max-w-xl",
+                        for (hour , count) in hours.iter().copied().enumerate() {
+                            div {
+                                key: "{hour}",
+                                class: "flex-1 bg-primary min-h-px",
+                                style: "height: {count * 100 / max}%",
+                                title: "{hour:02}:00 - {count}",
+                            }
+                        }
+                    }
+                    div { class: "flex justify-between max-w-xl text-xs opacity-70",
+                        span { "00" }
+                        span { "06" }
+                        span { "12" }
+                        span { "18" }
+                        span { "23" }
+                    }
+                }
+            }
+            None => rsx! {
+                p { class: "alert alert-info", "Loading..." }
+            },
+        }
+    }
+}
+
+fn rounded(value: &BigDecimal, scale: i64) -> String {
+    value
+        .with_scale_round(scale, RoundingMode::HalfUp)
+        .to_string()
+}
+
+#[component]
+pub fn NutritionPanel(user_id: UserId) -> Element {
+    let timezone = use_timezone();
+
+    let totals: Resource<Result<NutritionTotals, ServerFnError>> =
+        use_resource(move || async move { daily_nutrition(user_id, get_today(timezone)).await });
+
+    rsx! {
+        h2 { class: "font-bold text-lg", "Today's Nutrition" }
+        match totals.read().deref() {
+            Some(Err(err)) => rsx! {
+                div { class: "alert alert-error",
+                    "Error loading nutrition: "
+                    {err.to_string()}
+                }
+            },
+            Some(Ok(totals)) => rsx! {
+                table { class: "table table-sm w-auto",
+                    tbody {
+                        tr {
+                            td { "Calories" }
+                            td { "{rounded(&totals.nutrition.calories, 0)} kcal" }
+                        }
+                        tr {
+                            td { "Protein" }
+                            td { "{rounded(&totals.nutrition.protein, 1)} g" }
+                        }
+                        tr {
+                            td { "Fat" }
+                            td { "{rounded(&totals.nutrition.fat, 1)} g" }
+                        }
+                        tr {
+                            td { "Carbohydrate" }
+                            td { "{rounded(&totals.nutrition.carbohydrate, 1)} g" }
+                        }
+                    }
+                }
+                if totals.unknown_items > 0 {
+                    p { class: "text-warning text-sm",
+                        "{totals.unknown_items} items without nutrition data or a quantity were left out."
+                    }
+                }
+            },
+            None => rsx! {
+                p { class: "alert alert-info", "Loading..." }
+            },
+        }
+    }
+}
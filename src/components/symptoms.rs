@@ -1,25 +1,65 @@
-use chrono::{DateTime, FixedOffset, Local, Utc};
+use std::borrow::Cow;
+
+use chrono::{DateTime, FixedOffset, Local, TimeDelta, Utc};
 use derive_enum_all_values::AllValues;
 use dioxus::prelude::*;
 
 use crate::{
     components::events::{EventDateTimeShort, Markdown},
     forms::{
-        Dialog, EditError, FieldValue, FormSaveCancelButton, InputDateTime, InputString,
-        InputSymptomIntensity, InputTextArea, Saving, ValidationError, validate_comments,
+        Dialog, EditError, FieldError, FieldValue, FormErrorSummary, FormSaveCancelButton,
+        InputDateTime, InputNumber, InputString, InputSymptomIntensity, InputTextArea, Saving,
+        UnsavedChanges, ValidationError, use_dirty, validate_comments,
         validate_fixed_offset_date_time, validate_symptom_extra_details,
-        validate_symptom_intensity,
+        validate_symptom_intensity, validate_symptom_repeat_hours, validate_symptom_repeat_times,
+    },
+    functions::symptoms::{create_symptom, create_symptoms, delete_symptom, update_symptom},
+    i18n::{format_prefs, t},
+    models::{
+        ChangeSymptom, MaybeSet, NewSymptom, Symptom, UserId, format_datetime_long,
+        symptom_repeat_times,
     },
-    functions::symptoms::{create_symptom, delete_symptom, update_symptom},
-    models::{ChangeSymptom, MaybeSet, NewSymptom, Symptom, UserId},
+    use_timezone,
 };
 use classes::classes;
 
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, PartialEq)]
 pub enum Operation {
-    Create { user_id: UserId },
-    Update { symptom: Symptom },
+    Create {
+        user_id: UserId,
+        template: Option<NewSymptom>,
+    },
+    Update {
+        symptom: Symptom,
+    },
+}
+
+impl Operation {
+    /// The symptom to fill the form from: the one being edited, or the
+    /// template's fields for a new one.
+    fn initial_symptom(&self) -> Option<Cow<'_, Symptom>> {
+        match self {
+            Operation::Create {
+                template: Some(template),
+                ..
+            } => symptom_from_template(template).map(Cow::Owned),
+            Operation::Create { .. } => None,
+            Operation::Update { symptom } => Some(Cow::Borrowed(symptom)),
+        }
+    }
+}
+
+/// A template's fields as an unsaved symptom, so the same accessors can read
+/// them.
+fn symptom_from_template(template: &NewSymptom) -> Option<Symptom> {
+    let mut value = serde_json::to_value(template).ok()?;
+    let now = serde_json::to_value(Utc::now()).ok()?;
+    let fields = value.as_object_mut()?;
+    fields.insert("id".to_string(), 0.into());
+    fields.insert("created_at".to_string(), now.clone());
+    fields.insert("updated_at".to_string(), now);
+    serde_json::from_value(value).ok()
 }
 
 #[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, AllValues)]
@@ -93,23 +133,22 @@ pub struct SymptomInput {
 }
 
 fn build_form_inputs(op: &Operation) -> (Vec<SymptomInput>, Memo<bool>) {
+    let initial_symptom = op.initial_symptom();
     let inputs: Vec<_> = SYMPTOM_DEFS
         .iter()
         .map(|meta| {
-            let initial = match op {
-                Operation::Create { .. } => "0".to_string(),
-                Operation::Update { symptom } => (meta.accessor)(symptom).to_string(),
+            let initial = match initial_symptom.as_deref() {
+                None => "0".to_string(),
+                Some(symptom) => (meta.accessor)(symptom).to_string(),
             };
 
             let value = use_signal(|| initial);
             let validate = use_memo(move || validate_symptom_intensity(&value()));
 
             let extra = meta.extra.as_ref().map(|extra_meta| {
-                let initial = match op {
-                    Operation::Create { .. } => "".to_string(),
-                    Operation::Update { symptom } => {
-                        (extra_meta.accessor)(symptom).cloned().unwrap_or_default()
-                    }
+                let initial = match initial_symptom.as_deref() {
+                    None => "".to_string(),
+                    Some(symptom) => (extra_meta.accessor)(symptom).cloned().unwrap_or_default(),
                 };
                 let extra_value = use_signal(|| initial);
                 let extra_validate = use_memo({
@@ -117,7 +156,7 @@ fn build_form_inputs(op: &Operation) -> (Vec<SymptomInput>, Memo<bool>) {
                 });
                 SymptomExtraInput {
                     id: extra_meta.id,
-                    label: extra_meta.label,
+                    label: t(extra_meta.label),
                     value: extra_value,
                     validate: extra_validate,
                     meta: extra_meta,
@@ -126,7 +165,7 @@ fn build_form_inputs(op: &Operation) -> (Vec<SymptomInput>, Memo<bool>) {
 
             SymptomInput {
                 id: meta.id,
-                label: meta.label,
+                label: t(meta.label),
                 category: meta.category,
                 meta,
                 value,
@@ -138,8 +177,13 @@ fn build_form_inputs(op: &Operation) -> (Vec<SymptomInput>, Memo<bool>) {
 
     let has_errors = {
         let deps: Vec<Memo<_>> = inputs.iter().map(|i| i.validate).collect();
+        let extra_deps: Vec<Memo<_>> = inputs
+            .iter()
+            .filter_map(|i| i.extra.as_ref().map(|extra| extra.validate))
+            .collect();
         use_memo(move || {
-            deps.iter().any(|v| v().is_err()) // true if any field is invalid
+            // true if any field is invalid
+            deps.iter().any(|v| v().is_err()) || extra_deps.iter().any(|v| v().is_err())
         })
     };
 
@@ -169,6 +213,22 @@ fn inputs_to_new_symptom(
     Ok(s)
 }
 
+fn symptom_to_new_symptom(symptom: &Symptom, time: DateTime<FixedOffset>) -> NewSymptom {
+    let mut s = NewSymptom {
+        comments: symptom.comments.clone(),
+        ..NewSymptom::default(symptom.user_id, time)
+    };
+
+    for meta in SYMPTOM_DEFS {
+        (meta.set_new)(&mut s, (meta.accessor)(symptom));
+        if let Some(extra) = &meta.extra {
+            (extra.set_new)(&mut s, (extra.accessor)(symptom));
+        }
+    }
+
+    s
+}
+
 fn inputs_to_change_symptom(
     inputs: &[SymptomInput],
     time: DateTime<FixedOffset>,
@@ -534,6 +594,64 @@ pub struct SymptomField<'a> {
     // pub category: SymptomCategory,
 }
 
+/// Quote a CSV field if it contains a separator, quote or line break.
+#[cfg(feature = "server")]
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// One row per symptom record with a column per symptom, for spreadsheets.
+///
+/// Times are shown in `timezone`, or the offset the symptom was recorded
+/// with if there is none. Missing extra details are left empty.
+#[cfg(feature = "server")]
+pub fn symptoms_to_csv(symptoms: &[Symptom], timezone: Option<chrono_tz::Tz>) -> String {
+    let mut header = vec!["time"];
+    for meta in SYMPTOM_DEFS {
+        header.push(meta.id);
+        if let Some(extra) = &meta.extra {
+            header.push(extra.id);
+        }
+    }
+    header.push("comments");
+
+    let mut csv = header.join(",") + "\r\n";
+    for symptom in symptoms {
+        let time = match timezone {
+            Some(timezone) => symptom.time.with_timezone(&timezone).naive_local(),
+            None => symptom.time.naive_local(),
+        };
+        let mut row = vec![time.format("%Y-%m-%d %H:%M:%S").to_string()];
+        for meta in SYMPTOM_DEFS {
+            row.push((meta.accessor)(symptom).to_string());
+            if let Some(extra) = &meta.extra {
+                row.push((extra.accessor)(symptom).map_or_else(String::new, |v| csv_field(v)));
+            }
+        }
+        row.push(
+            symptom
+                .comments
+                .as_deref()
+                .map_or_else(String::new, csv_field),
+        );
+        csv += &(row.join(",") + "\r\n");
+    }
+    csv
+}
+
+/// Is this symptom present, either with an intensity or extra details?
+pub fn symptom_has_value(meta: &SymptomMeta, symptom: &Symptom) -> bool {
+    let extra = meta
+        .extra
+        .as_ref()
+        .and_then(|extra| (extra.accessor)(symptom));
+    (meta.accessor)(symptom) > 0 || extra.is_some()
+}
+
 pub fn collect_symptom_fields<'a>(
     symptom: &'a Symptom,
     category: SymptomCategory,
@@ -544,16 +662,13 @@ pub fn collect_symptom_fields<'a>(
         .filter_map(|meta| {
             let intensity = (meta.accessor)(symptom);
             let extra = meta.extra.as_ref().map(|e| SymptomExtraField {
-                label: e.label,
+                label: t(e.label),
                 value: (e.accessor)(symptom),
             });
 
-            let has_value =
-                intensity > 0 || extra.as_ref().map(|s| s.value.is_some()).unwrap_or(false);
-
-            if has_value {
+            if symptom_has_value(meta, symptom) {
                 Some(SymptomField {
-                    label: meta.label,
+                    label: t(meta.label),
                     intensity,
                     extra,
                     // category,
@@ -580,7 +695,7 @@ async fn do_save(
     let comments = validate.comments.read().clone()?;
 
     match op {
-        Operation::Create { user_id } => {
+        Operation::Create { user_id, .. } => {
             let updates = inputs_to_new_symptom(input, *user_id, time, comments)
                 .map_err(EditError::Validation)?;
             create_symptom(updates).await.map_err(EditError::Server)
@@ -602,24 +717,101 @@ pub fn SymptomUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Sympt
         Operation::Update { symptom } => symptom.time.as_raw(),
     });
     let comments = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(symptom),
+            ..
+        } => symptom.comments.as_raw(),
         Operation::Create { .. } => String::new(),
         Operation::Update { symptom } => symptom.comments.as_raw(),
     });
     let (inputs, has_errors) = build_form_inputs(&op);
+    let mut repeating = use_signal(|| false);
+
+    // Symptoms present when the dialog opened, so fields don't vanish while
+    // they are being edited.
+    let active_ids: Vec<&'static str> = match op.initial_symptom() {
+        None => Vec::new(),
+        Some(symptom) => SYMPTOM_DEFS
+            .iter()
+            .filter(|meta| symptom_has_value(meta, &symptom))
+            .map(|meta| meta.id)
+            .collect(),
+    };
+    let has_active = !active_ids.is_empty();
+    let mut only_active = use_signal(|| has_active);
 
+    let timezone = use_timezone();
     let validate = {
         Validate {
-            time: use_memo(move || validate_fixed_offset_date_time(&time())),
+            time: use_memo(move || validate_fixed_offset_date_time(&time(), timezone)),
             comments: use_memo(move || validate_comments(&comments())),
         }
     };
 
+    let dirty = {
+        let inputs = inputs.clone();
+        use_dirty(move || {
+            let values: Vec<_> = inputs
+                .iter()
+                .map(|input| {
+                    (
+                        input.value.read().clone(),
+                        input.extra.as_ref().map(|extra| extra.value.read().clone()),
+                    )
+                })
+                .collect();
+            (time(), comments(), values)
+        })
+    };
+
     let mut saving = use_signal(|| Saving::No);
 
     // disable form while waiting for response
     let disabled = use_memo(move || saving.read().is_saving());
     let disabled_save = use_memo(move || has_errors() || disabled());
 
+    let errors = {
+        let inputs = inputs.clone();
+        let validate = validate.clone();
+        use_memo(move || {
+            let mut errors = Vec::new();
+            if let Err(err) = validate.time.read().as_ref() {
+                errors.push(FieldError {
+                    id: "time",
+                    label: "Time",
+                    message: err.to_string(),
+                });
+            }
+            if let Err(err) = validate.comments.read().as_ref() {
+                errors.push(FieldError {
+                    id: "comments",
+                    label: "Comments",
+                    message: err.to_string(),
+                });
+            }
+            for input in &inputs {
+                if let Err(err) = input.validate.read().as_ref() {
+                    errors.push(FieldError {
+                        id: input.id,
+                        label: input.label,
+                        message: err.to_string(),
+                    });
+                }
+                if let Some(extra) = &input.extra
+                    && let Err(err) = extra.validate.read().as_ref()
+                {
+                    errors.push(FieldError {
+                        id: extra.id,
+                        label: extra.label,
+                        message: err.to_string(),
+                    });
+                }
+            }
+            errors
+        })
+    };
+
+    let on_saved = on_save;
     let op_clone = op.clone();
     let validate_clone = validate.clone();
     let inputs_clone = inputs.clone();
@@ -635,6 +827,7 @@ pub fn SymptomUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Sympt
             match result {
                 Ok(symptom) => {
                     saving.set(Saving::Finished(Ok(())));
+                    dirty.reset();
                     on_save(symptom);
                 }
                 Err(err) => saving.set(Saving::Finished(Err(err))),
@@ -642,12 +835,24 @@ pub fn SymptomUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Sympt
         });
     });
 
+    if let (true, Operation::Update { symptom }) = (repeating(), &op) {
+        let original = symptom.clone();
+        return rsx! {
+            SymptomRepeat {
+                symptom: symptom.clone(),
+                on_cancel: move |()| repeating.set(false),
+                on_save: move |_symptoms| on_saved(original.clone()),
+            }
+        };
+    }
+
     rsx! {
         h3 { class: "text-lg font-bold",
             match &op {
-                Operation::Create { .. } => "Create Symptom".to_string(),
-                Operation::Update { symptom } => format!("Edit Symptom {}", symptom.id),
+                Operation::Create { .. } => t("Create Symptom").to_string(),
+                Operation::Update { symptom } => format!("{} {}", t("Edit Symptom"), symptom.id),
             }
+            UnsavedChanges { dirty: dirty.is_dirty() }
         }
         p { class: "py-4", "Press ESC key or click the button below to close" }
         form {
@@ -659,6 +864,13 @@ pub fn SymptomUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Sympt
                     on_cancel(());
                 }
             },
+            if has_errors() {
+                FormErrorSummary {
+                    errors,
+                    // The field may be hidden with the inactive symptoms.
+                    on_select: move |_id| only_active.set(false),
+                }
+            }
             InputDateTime {
                 id: "time",
                 label: "Time",
@@ -673,13 +885,29 @@ pub fn SymptomUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Sympt
                 validate: validate.comments,
                 disabled,
             }
+            if has_active {
+                label { class: "label mb-5",
+                    input {
+                        r#type: "checkbox",
+                        class: "toggle",
+                        checked: only_active(),
+                        disabled: disabled(),
+                        onchange: move |e| only_active.set(e.checked()),
+                    }
+                    "Show only active symptoms"
+                }
+            }
             for category in SymptomCategory::all_values() {
                 {
-                    let fields: Vec<_> = inputs.iter().filter(|i| i.category == *category).collect();
+                    let fields: Vec<_> = inputs
+                        .iter()
+                        .filter(|i| i.category == *category)
+                        .filter(|i| !only_active() || active_ids.contains(&i.id))
+                        .collect();
                     rsx! {
                         if !fields.is_empty() {
                             fieldset { class: "fieldset border-2 rounded-md p-4 mb-4",
-                                legend { class: "fieldset-legend px-2", "{category}" }
+                                legend { class: "fieldset-legend px-2", {t(category.as_title())} }
                                 for field in fields {
                                     InputSymptomIntensity {
                                         id: field.id,
@@ -703,13 +931,22 @@ pub fn SymptomUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Sympt
                     }
                 }
             }
+            if let Operation::Update { .. } = &op {
+                button {
+                    r#type: "button",
+                    class: "btn btn-secondary mb-5",
+                    disabled: disabled(),
+                    onclick: move |_| repeating.set(true),
+                    "Repeat at other times"
+                }
+            }
             FormSaveCancelButton {
                 disabled: disabled_save,
                 on_save: move |()| on_save(()),
                 on_cancel: move |()| on_cancel(()),
                 title: match &op {
-                    Operation::Create { .. } => "Create",
-                    Operation::Update { .. } => "Save",
+                    Operation::Create { .. } => t("Create"),
+                    Operation::Update { .. } => t("Save"),
                 },
                 saving,
             }
@@ -717,6 +954,150 @@ pub fn SymptomUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Sympt
     }
 }
 
+async fn do_repeat(
+    symptom: &Symptom,
+    times: Memo<Result<Vec<DateTime<FixedOffset>>, ValidationError>>,
+) -> Result<Vec<Symptom>, EditError> {
+    let times = times.read().clone()?;
+    let symptoms = times
+        .into_iter()
+        .map(|time| symptom_to_new_symptom(symptom, time))
+        .collect();
+    create_symptoms(symptoms).await.map_err(EditError::Server)
+}
+
+#[component]
+fn SymptomRepeat(
+    symptom: Symptom,
+    on_cancel: Callback,
+    on_save: Callback<Vec<Symptom>>,
+) -> Element {
+    let start = use_signal(|| (symptom.time + TimeDelta::hours(4)).as_raw());
+    let end = use_signal(|| (symptom.time + TimeDelta::hours(24)).as_raw());
+    let every = use_signal(|| "4".to_string());
+    let mut times = use_signal(String::new);
+    let mut interval_error = use_signal(|| None::<String>);
+
+    let timezone = use_timezone();
+    let validate_start = use_memo(move || validate_fixed_offset_date_time(&start(), timezone));
+    let validate_end = use_memo(move || validate_fixed_offset_date_time(&end(), timezone));
+    let validate_every = use_memo(move || validate_symptom_repeat_hours(&every()));
+    let validate_times = use_memo(move || validate_symptom_repeat_times(&times(), timezone));
+
+    let mut saving = use_signal(|| Saving::No);
+
+    // disable form while waiting for response
+    let disabled = use_memo(move || saving.read().is_saving());
+    let disabled_fill = use_memo(move || {
+        validate_start.read().is_err()
+            || validate_end.read().is_err()
+            || validate_every.read().is_err()
+            || disabled()
+    });
+    let disabled_save = use_memo(move || validate_times.read().is_err() || disabled());
+
+    let fill_times = move |_| {
+        let (Ok(start), Ok(end), Ok(every)) = (validate_start(), validate_end(), validate_every())
+        else {
+            return;
+        };
+        match symptom_repeat_times(start, end, TimeDelta::hours(every.into())) {
+            Ok(list) => {
+                interval_error.set(None);
+                times.set(
+                    list.iter()
+                        .map(|time| time.as_raw())
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                );
+            }
+            Err(err) => interval_error.set(Some(err)),
+        }
+    };
+
+    let symptom_clone = symptom.clone();
+    let on_save = use_callback(move |()| {
+        let symptom = symptom_clone.clone();
+        spawn(async move {
+            saving.set(Saving::Yes);
+
+            let result = do_repeat(&symptom, validate_times).await;
+
+            match result {
+                Ok(symptoms) => {
+                    saving.set(Saving::Finished(Ok(())));
+                    on_save(symptoms);
+                }
+                Err(err) => saving.set(Saving::Finished(Err(err))),
+            }
+        });
+    });
+
+    rsx! {
+        h3 { class: "text-lg font-bold",
+            {format!("{} {}", t("Repeat Symptom"), symptom.id)}
+        }
+        p { class: "py-4",
+            "Copies the saved symptom record, including comments, to each time below."
+        }
+        form {
+            novalidate: true,
+            action: "javascript:void(0)",
+            method: "dialog",
+            onkeyup: move |event| {
+                if event.key() == Key::Escape {
+                    on_cancel(());
+                }
+            },
+            InputDateTime {
+                id: "repeat_start",
+                label: "From",
+                value: start,
+                validate: validate_start,
+                disabled,
+            }
+            InputDateTime {
+                id: "repeat_end",
+                label: "To",
+                value: end,
+                validate: validate_end,
+                disabled,
+            }
+            InputNumber {
+                id: "repeat_every",
+                label: "Every (hours)",
+                value: every,
+                validate: validate_every,
+                disabled,
+            }
+            button {
+                r#type: "button",
+                class: "btn btn-secondary mb-5",
+                disabled: disabled_fill(),
+                onclick: fill_times,
+                "Fill in times"
+            }
+            if let Some(err) = interval_error() {
+                div { class: "text-red-500 mb-5", "{err}" }
+            }
+            InputTextArea {
+                id: "repeat_times",
+                label: "Times (one per line)",
+                value: times,
+                validate: validate_times,
+                disabled,
+            }
+            FormSaveCancelButton {
+                disabled: disabled_save,
+                on_save: move |()| on_save(()),
+                on_cancel: move |()| on_cancel(()),
+                title: "Create copies",
+                saving,
+            }
+        }
+    }
+}
+
 #[component]
 pub fn SymptomDelete(
     symptom: Symptom,
@@ -746,7 +1127,7 @@ pub fn SymptomDelete(
     rsx! {
         h3 { class: "text-lg font-bold",
             "Delete symptom "
-            {symptom.id.to_string()}
+            {symptom.name()}
         }
         p { class: "py-4", "Press ESC key or click the button below to close" }
         SymptomSummary { symptom: symptom.clone() }
@@ -776,7 +1157,7 @@ const SYMPTOM_SVG: Asset = asset!("/assets/symptom.svg");
 pub fn symptom_icon() -> Element {
     let alt = symptom_title();
     rsx! {
-        img { alt, src: SYMPTOM_SVG }
+        img { alt, title: alt, src: SYMPTOM_SVG }
     }
 }
 
@@ -906,14 +1287,14 @@ pub fn SymptomDetails(symptom: Symptom) -> Element {
     //         div { class: "inline-block ml-2", {desc} }
     //     }),
     rsx! {
-        h3 { class: "text-lg font-bold", {symptom.time.format("%Y-%m-%d %H:%M:%S %:z").to_string()} }
+        h3 { class: "text-lg font-bold", {format_datetime_long(&symptom.time, &format_prefs())} }
         for category in SymptomCategory::all_values() {
             {
                 let fields = collect_symptom_fields(&symptom, *category);
                 rsx! {
                     if !fields.is_empty() {
                         fieldset { class: "fieldset border-2 rounded-md px-4 pb-4 mb-4",
-                            legend { class: "fieldset-legend px-2", "{category}" }
+                            legend { class: "fieldset-legend px-2", {t(category.as_title())} }
                             for field in fields {
                                 SymptomDisplay {
                                     name: field.label.to_string(),
@@ -943,3 +1324,48 @@ pub fn SymptomDetails(symptom: Symptom) -> Element {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "server")]
+    fn csv_field_quotes_only_when_needed() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("left, lower"), "\"left, lower\"");
+        assert_eq!(csv_field("a \"sharp\" pain"), "\"a \"\"sharp\"\" pain\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    #[cfg(feature = "server")]
+    fn symptoms_csv_header_has_a_column_per_field() {
+        let csv = symptoms_to_csv(&[], None);
+        let columns: Vec<&str> = csv.trim_end().split(',').collect();
+
+        let extras = SYMPTOM_DEFS
+            .iter()
+            .filter(|meta| meta.extra.is_some())
+            .count();
+        assert_eq!(columns.len(), SYMPTOM_DEFS.len() + extras + 2);
+        assert_eq!(columns.first(), Some(&"time"));
+        assert_eq!(columns.last(), Some(&"comments"));
+        assert!(columns.contains(&"abdominal_pain_location"));
+    }
+
+    #[test]
+    fn symptom_from_template_keeps_fields() {
+        let time = DateTime::parse_from_rfc3339("2024-01-01T12:00:00+11:00").unwrap();
+        let template = NewSymptom {
+            fever: 3,
+            comments: Some("evening".to_string()),
+            ..NewSymptom::default(UserId::new(1), time)
+        };
+
+        let symptom = symptom_from_template(&template).unwrap();
+        assert_eq!(symptom.fever, 3);
+        assert_eq!(symptom.comments.as_deref(), Some("evening"));
+        assert_eq!(symptom_to_new_symptom(&symptom, time), template);
+    }
+}
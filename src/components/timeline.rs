@@ -1,4 +1,4 @@
-use std::{num::ParseIntError, str::FromStr};
+use std::{convert::Infallible, num::ParseIntError, str::FromStr};
 
 use dioxus::prelude::*;
 use dioxus_router::ToQueryArgument;
@@ -6,16 +6,21 @@ use tap::Pipe;
 use thiserror::Error;
 
 use crate::{
-    components::{consumptions::ConsumptionDialog, poos::PooDialog, wees::WeeDialog},
+    components::{
+        consumptions::ConsumptionDialog, poos::PooDialog, undo::use_undo_log, wees::WeeDialog,
+    },
     models::{
-        Consumable, ConsumableId, Consumption, ConsumptionId, Entry, EntryData, Exercise,
-        ExerciseId, HealthMetric, HealthMetricId, Note, NoteId, Poo, PooId, Reflux, RefluxId,
-        Symptom, SymptomId, UserId, Wee, WeeId, WeeUrge, WeeUrgeId,
+        Consumable, ConsumableId, Consumption, ConsumptionId, ConsumptionWithItems, Entry,
+        EntryData, EntryId, EntryTemplateData, EntryTemplateId, Exercise, ExerciseId,
+        FluidAdjustment, FluidAdjustmentId, HealthMetric, HealthMetricId, MedicationDose,
+        MedicationDoseId, MedicationDoseWithMedication, Note, NoteId, Poo, PooId, Reflux, RefluxId,
+        SortDirection, Symptom, SymptomId, UserId, Wee, WeeId, WeeUrge, WeeUrgeId,
     },
 };
 
 use super::{
-    consumptions, exercises, health_metrics, notes, poos, refluxs, symptoms, wee_urges, wees,
+    consumptions, exercises, fluid_adjustments, health_metrics, medication_doses, notes, poos,
+    refluxs, symptoms, wee_urges, wees,
 };
 
 #[derive(Debug, Clone, PartialEq)]
@@ -29,16 +34,95 @@ pub enum ActiveDialog {
     Symptom(symptoms::ActiveDialog),
     Reflux(refluxs::ActiveDialog),
     Note(notes::ActiveDialog),
+    FluidAdjustment(fluid_adjustments::ActiveDialog),
+    Medication(medication_doses::ActiveDialog),
     Idle,
 }
 
-#[derive(Error, Debug)]
+impl ActiveDialog {
+    /// A create dialog for `user_id`, filled in from a template.
+    pub fn create_from_template(user_id: UserId, data: EntryTemplateData) -> Self {
+        match data {
+            EntryTemplateData::Poo(poo) => {
+                ActiveDialog::Poo(poos::ActiveDialog::Change(poos::Operation::Create {
+                    user_id,
+                    template: Some(poo),
+                }))
+            }
+            EntryTemplateData::Wee(wee) => {
+                ActiveDialog::Wee(wees::ActiveDialog::Change(wees::Operation::Create {
+                    user_id,
+                    template: Some(wee),
+                }))
+            }
+            EntryTemplateData::WeeUrge(wee_urge) => ActiveDialog::WeeUrge(
+                wee_urges::ActiveDialog::Change(wee_urges::Operation::Create {
+                    user_id,
+                    template: Some(wee_urge),
+                }),
+            ),
+            EntryTemplateData::Consumption(consumption) => ActiveDialog::Consumption(
+                consumptions::ActiveDialog::UpdateBasic(consumptions::Operation::Create {
+                    user_id,
+                    template: Some(consumption),
+                }),
+            ),
+            EntryTemplateData::Exercise(exercise) => ActiveDialog::Exercise(
+                exercises::ActiveDialog::Change(exercises::Operation::Create {
+                    user_id,
+                    template: Some(exercise),
+                }),
+            ),
+            EntryTemplateData::HealthMetric(health_metric) => ActiveDialog::HealthMetric(
+                health_metrics::ActiveDialog::Change(health_metrics::Operation::Create {
+                    user_id,
+                    template: Some(health_metric),
+                }),
+            ),
+            EntryTemplateData::Symptom(symptom) => ActiveDialog::Symptom(
+                symptoms::ActiveDialog::Change(symptoms::Operation::Create {
+                    user_id,
+                    template: Some(symptom),
+                }),
+            ),
+            EntryTemplateData::Reflux(reflux) => {
+                ActiveDialog::Reflux(refluxs::ActiveDialog::Change(refluxs::Operation::Create {
+                    user_id,
+                    template: Some(reflux),
+                }))
+            }
+            EntryTemplateData::Note(note) => {
+                ActiveDialog::Note(notes::ActiveDialog::Change(notes::Operation::Create {
+                    user_id,
+                    template: Some(note),
+                }))
+            }
+            EntryTemplateData::FluidAdjustment(fluid_adjustment) => ActiveDialog::FluidAdjustment(
+                fluid_adjustments::ActiveDialog::Change(fluid_adjustments::Operation::Create {
+                    user_id,
+                    template: Some(fluid_adjustment),
+                }),
+            ),
+            EntryTemplateData::Medication(medication_dose) => ActiveDialog::Medication(
+                medication_doses::ActiveDialog::Change(medication_doses::Operation::Create {
+                    user_id,
+                    template: Some(medication_dose),
+                }),
+            ),
+        }
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
 pub enum DialogReferenceError {
-    #[error("Invalid integer")]
-    ParseIntError(#[from] ParseIntError),
+    #[error("Invalid id in dialog reference {reference:?}: {source}")]
+    InvalidId {
+        reference: String,
+        source: ParseIntError,
+    },
 
-    #[error("Invalid reference")]
-    ReferenceError,
+    #[error("Unknown dialog reference {0:?}")]
+    UnknownReference(String),
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
@@ -135,6 +219,27 @@ pub enum DialogReference {
     DeleteNote {
         note_id: NoteId,
     },
+    CreateFluidAdjustment {
+        user_id: UserId,
+    },
+    UpdateFluidAdjustment {
+        fluid_adjustment_id: FluidAdjustmentId,
+    },
+    DeleteFluidAdjustment {
+        fluid_adjustment_id: FluidAdjustmentId,
+    },
+    CreateMedication {
+        user_id: UserId,
+    },
+    UpdateMedication {
+        medication_dose_id: MedicationDoseId,
+    },
+    DeleteMedication {
+        medication_dose_id: MedicationDoseId,
+    },
+    CreateFromTemplate {
+        entry_template_id: EntryTemplateId,
+    },
     #[default]
     Idle,
 }
@@ -163,6 +268,14 @@ impl DialogReference {
                 reflux_id: reflux.id,
             },
             EntryData::Note(note) => DialogReference::UpdateNote { note_id: note.id },
+            EntryData::FluidAdjustment(fluid_adjustment) => {
+                DialogReference::UpdateFluidAdjustment {
+                    fluid_adjustment_id: fluid_adjustment.id,
+                }
+            }
+            EntryData::Medication(medication_dose) => DialogReference::UpdateMedication {
+                medication_dose_id: medication_dose.dose.id,
+            },
         }
     }
 
@@ -189,6 +302,14 @@ impl DialogReference {
                 reflux_id: reflux.id,
             },
             EntryData::Note(note) => DialogReference::DeleteNote { note_id: note.id },
+            EntryData::FluidAdjustment(fluid_adjustment) => {
+                DialogReference::DeleteFluidAdjustment {
+                    fluid_adjustment_id: fluid_adjustment.id,
+                }
+            }
+            EntryData::Medication(medication_dose) => DialogReference::DeleteMedication {
+                medication_dose_id: medication_dose.dose.id,
+            },
         }
     }
 }
@@ -203,10 +324,58 @@ impl ToQueryArgument for DialogReference {
     }
 }
 
+impl ToQueryArgument for SortDirection {
+    fn display_query_argument(
+        &self,
+        query_name: &str,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(f, "{}={}", query_name, self.as_str())
+    }
+}
+
+/// Entry to select and scroll to when the timeline is opened.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EntryFocus(pub Option<EntryId>);
+
+impl ToQueryArgument for EntryFocus {
+    fn display_query_argument(
+        &self,
+        query_name: &str,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        let value = self.0.map(|id| id.as_str()).unwrap_or_default();
+        write!(f, "{}={}", query_name, value)
+    }
+}
+
+impl FromStr for EntryFocus {
+    type Err = Infallible;
+
+    /// Invalid ids are ignored rather than failing the whole route.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.parse().ok()))
+    }
+}
+
 impl FromStr for DialogReference {
     type Err = DialogReferenceError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Self::parse(s) {
+            Ok(Some(reference)) => Ok(reference),
+            Ok(None) => Err(DialogReferenceError::UnknownReference(s.to_string())),
+            Err(source) => Err(DialogReferenceError::InvalidId {
+                reference: s.to_string(),
+                source,
+            }),
+        }
+    }
+}
+
+impl DialogReference {
+    /// Parse a reference, returning `None` if it is not recognised.
+    fn parse(s: &str) -> Result<Option<Self>, ParseIntError> {
         let split = s.split("-").collect::<Vec<_>>();
         match split[..] {
             ["wee", "create", id] => {
@@ -347,9 +516,42 @@ impl FromStr for DialogReference {
                 let note_id = id.parse()?;
                 Self::DeleteNote { note_id }
             }
+            ["fluid_adjustment", "create", id] => {
+                let user_id = UserId::new(id.parse()?);
+                Self::CreateFluidAdjustment { user_id }
+            }
+            ["fluid_adjustment", "update", id] => {
+                let fluid_adjustment_id = FluidAdjustmentId::new(id.parse()?);
+                Self::UpdateFluidAdjustment {
+                    fluid_adjustment_id,
+                }
+            }
+            ["medication", "create", id] => {
+                let user_id = UserId::new(id.parse()?);
+                Self::CreateMedication { user_id }
+            }
+            ["medication", "update", id] => {
+                let medication_dose_id = MedicationDoseId::new(id.parse()?);
+                Self::UpdateMedication { medication_dose_id }
+            }
+            ["medication", "delete", id] => {
+                let medication_dose_id = MedicationDoseId::new(id.parse()?);
+                Self::DeleteMedication { medication_dose_id }
+            }
+            ["fluid_adjustment", "delete", id] => {
+                let fluid_adjustment_id = FluidAdjustmentId::new(id.parse()?);
+                Self::DeleteFluidAdjustment {
+                    fluid_adjustment_id,
+                }
+            }
+            ["template", "create", id] => {
+                let entry_template_id = EntryTemplateId::new(id.parse()?);
+                Self::CreateFromTemplate { entry_template_id }
+            }
             [""] | [] => Self::Idle,
-            _ => return Err(DialogReferenceError::ReferenceError),
+            _ => return Ok(None),
         }
+        .pipe(Some)
         .pipe(Ok)
     }
 }
@@ -430,6 +632,27 @@ impl ToString for DialogReference {
             DialogReference::CreateNote { user_id } => format!("note-create-{user_id}"),
             DialogReference::UpdateNote { note_id } => format!("note-update-{note_id}"),
             DialogReference::DeleteNote { note_id } => format!("note-delete-{note_id}"),
+            DialogReference::CreateFluidAdjustment { user_id } => {
+                format!("fluid_adjustment-create-{user_id}")
+            }
+            DialogReference::UpdateFluidAdjustment {
+                fluid_adjustment_id,
+            } => format!("fluid_adjustment-update-{fluid_adjustment_id}"),
+            DialogReference::DeleteFluidAdjustment {
+                fluid_adjustment_id,
+            } => format!("fluid_adjustment-delete-{fluid_adjustment_id}"),
+            DialogReference::CreateMedication { user_id } => {
+                format!("medication-create-{user_id}")
+            }
+            DialogReference::UpdateMedication { medication_dose_id } => {
+                format!("medication-update-{medication_dose_id}")
+            }
+            DialogReference::DeleteMedication { medication_dose_id } => {
+                format!("medication-delete-{medication_dose_id}")
+            }
+            DialogReference::CreateFromTemplate { entry_template_id } => {
+                format!("template-create-{entry_template_id}")
+            }
             DialogReference::Idle => String::new(),
         }
     }
@@ -446,20 +669,30 @@ pub fn TimelineDialog(
     show_consumption_ingredient_update_basic: Callback<(Consumption, Consumable)>,
     show_consumption_ingredient_update_ingredients: Callback<(Consumption, Consumable)>,
 ) -> Element {
+    let undo_log = use_undo_log();
+
     match dialog() {
         ActiveDialog::Wee(wee_dialog) => {
+            let original = match &wee_dialog {
+                wees::ActiveDialog::Change(wees::Operation::Update { wee }) => {
+                    Some(Entry::from(wee.clone()))
+                }
+                _ => None,
+            };
             rsx! {
                 WeeDialog {
                     dialog: wee_dialog,
                     on_close,
                     on_change: move |wee: Wee| {
+                        undo_log.saved(original.clone(), EntryId::Wee(wee.id));
                         replace_dialog(DialogReference::UpdateWee {
                             wee_id: wee.id,
                         });
                         on_change(());
                         on_close(());
                     },
-                    on_delete: move |_wee| {
+                    on_delete: move |wee: Wee| {
+                        undo_log.deleted(wee);
                         on_change(());
                         on_close(());
                     },
@@ -467,18 +700,26 @@ pub fn TimelineDialog(
             }
         }
         ActiveDialog::WeeUrge(wee_urge_dialog) => {
+            let original = match &wee_urge_dialog {
+                wee_urges::ActiveDialog::Change(wee_urges::Operation::Update { wee_urge }) => {
+                    Some(Entry::from(wee_urge.clone()))
+                }
+                _ => None,
+            };
             rsx! {
                 wee_urges::WeeUrgeDialog {
                     dialog: wee_urge_dialog,
                     on_close,
                     on_change: move |wee_urge: WeeUrge| {
+                        undo_log.saved(original.clone(), EntryId::WeeUrge(wee_urge.id));
                         replace_dialog(DialogReference::UpdateWeeUrge {
                             wee_urge_id: wee_urge.id,
                         });
                         on_change(());
                         on_close(());
                     },
-                    on_delete: move |_wee_urge| {
+                    on_delete: move |wee_urge: WeeUrge| {
+                        undo_log.deleted(wee_urge);
                         on_change(());
                         on_close(());
                     },
@@ -486,18 +727,26 @@ pub fn TimelineDialog(
             }
         }
         ActiveDialog::Poo(poo_dialog) => {
+            let original = match &poo_dialog {
+                poos::ActiveDialog::Change(poos::Operation::Update { poo }) => {
+                    Some(Entry::from(poo.clone()))
+                }
+                _ => None,
+            };
             rsx! {
                 PooDialog {
                     dialog: poo_dialog,
                     on_close,
                     on_change: move |poo: Poo| {
+                        undo_log.saved(original.clone(), EntryId::Poo(poo.id));
                         replace_dialog(DialogReference::UpdatePoo {
                             poo_id: poo.id,
                         });
                         on_change(());
                         on_close(());
                     },
-                    on_delete: move |_poo| {
+                    on_delete: move |poo: Poo| {
+                        undo_log.deleted(poo);
                         on_change(());
                         on_close(());
                     },
@@ -505,6 +754,15 @@ pub fn TimelineDialog(
             }
         }
         ActiveDialog::Consumption(consumption_dialog) => {
+            let original = match &consumption_dialog {
+                consumptions::ActiveDialog::UpdateBasic(consumptions::Operation::Update {
+                    consumption,
+                }) => Some(Entry::from(ConsumptionWithItems {
+                    consumption: consumption.clone(),
+                    items: Vec::new(),
+                })),
+                _ => None,
+            };
             rsx! {
                 ConsumptionDialog {
                     dialog: consumption_dialog,
@@ -513,6 +771,7 @@ pub fn TimelineDialog(
                     show_ingredient_update_basic: show_consumption_ingredient_update_basic,
                     show_ingredient_update_ingredients: show_consumption_ingredient_update_ingredients,
                     on_change: move |consumption: Consumption| {
+                        undo_log.saved(original.clone(), EntryId::Consumption(consumption.id));
                         replace_dialog(DialogReference::UpdateBasic {
                             consumption_id: consumption.id,
                         });
@@ -521,7 +780,8 @@ pub fn TimelineDialog(
                     on_change_ingredients: move |_consumption| {
                         on_change(());
                     },
-                    on_delete: move |_consumption| {
+                    on_delete: move |consumption: ConsumptionWithItems| {
+                        undo_log.deleted(consumption);
                         on_change(());
                     },
                     on_close,
@@ -529,18 +789,26 @@ pub fn TimelineDialog(
             }
         }
         ActiveDialog::Exercise(exercise_dialog) => {
+            let original = match &exercise_dialog {
+                exercises::ActiveDialog::Change(exercises::Operation::Update { exercise }) => {
+                    Some(Entry::from(exercise.clone()))
+                }
+                _ => None,
+            };
             rsx! {
                 exercises::ExerciseDialog {
                     dialog: exercise_dialog,
                     on_close,
                     on_change: move |exercise: Exercise| {
+                        undo_log.saved(original.clone(), EntryId::Exercise(exercise.id));
                         replace_dialog(DialogReference::UpdateExercise {
                             exercise_id: exercise.id,
                         });
                         on_change(());
                         on_close(());
                     },
-                    on_delete: move |_exercise| {
+                    on_delete: move |exercise: Exercise| {
+                        undo_log.deleted(exercise);
                         on_change(());
                         on_close(());
                     },
@@ -548,18 +816,26 @@ pub fn TimelineDialog(
             }
         }
         ActiveDialog::HealthMetric(health_metric_dialog) => {
+            let original = match &health_metric_dialog {
+                health_metrics::ActiveDialog::Change(health_metrics::Operation::Update {
+                    health_metric,
+                }) => Some(Entry::from(health_metric.clone())),
+                _ => None,
+            };
             rsx! {
                 health_metrics::HealthMetricDialog {
                     dialog: health_metric_dialog,
                     on_close,
                     on_change: move |health_metric: HealthMetric| {
+                        undo_log.saved(original.clone(), EntryId::HealthMetric(health_metric.id));
                         replace_dialog(DialogReference::UpdateHealthMetric {
                             health_metric_id: health_metric.id,
                         });
                         on_change(());
                         on_close(());
                     },
-                    on_delete: move |_health_metric| {
+                    on_delete: move |health_metric: HealthMetric| {
+                        undo_log.deleted(health_metric);
                         on_change(());
                         on_close(());
                     },
@@ -567,18 +843,26 @@ pub fn TimelineDialog(
             }
         }
         ActiveDialog::Symptom(symptom_dialog) => {
+            let original = match &symptom_dialog {
+                symptoms::ActiveDialog::Change(symptoms::Operation::Update { symptom }) => {
+                    Some(Entry::from(symptom.clone()))
+                }
+                _ => None,
+            };
             rsx! {
                 symptoms::SymptomDialog {
                     dialog: symptom_dialog,
                     on_close,
                     on_change: move |symptom: Symptom| {
+                        undo_log.saved(original.clone(), EntryId::Symptom(symptom.id));
                         replace_dialog(DialogReference::UpdateSymptom {
                             symptom_id: symptom.id,
                         });
                         on_change(());
                         on_close(());
                     },
-                    on_delete: move |_symptom| {
+                    on_delete: move |symptom: Symptom| {
+                        undo_log.deleted(symptom);
                         on_change(());
                         on_close(());
                     },
@@ -586,18 +870,26 @@ pub fn TimelineDialog(
             }
         }
         ActiveDialog::Reflux(reflux_dialog) => {
+            let original = match &reflux_dialog {
+                refluxs::ActiveDialog::Change(refluxs::Operation::Update { reflux }) => {
+                    Some(Entry::from(reflux.clone()))
+                }
+                _ => None,
+            };
             rsx! {
                 refluxs::RefluxDialog {
                     dialog: reflux_dialog,
                     on_close,
                     on_change: move |reflux: Reflux| {
+                        undo_log.saved(original.clone(), EntryId::Reflux(reflux.id));
                         replace_dialog(DialogReference::UpdateReflux {
                             reflux_id: reflux.id,
                         });
                         on_change(());
                         on_close(());
                     },
-                    on_delete: move |_reflux| {
+                    on_delete: move |reflux: Reflux| {
+                        undo_log.deleted(reflux);
                         on_change(());
                         on_close(());
                     },
@@ -605,18 +897,80 @@ pub fn TimelineDialog(
             }
         }
         ActiveDialog::Note(note_dialog) => {
+            let original = match &note_dialog {
+                notes::ActiveDialog::Change(notes::Operation::Update { note }) => {
+                    Some(Entry::from(note.clone()))
+                }
+                _ => None,
+            };
             rsx! {
                 notes::NoteDialog {
                     dialog: note_dialog,
                     on_close,
                     on_change: move |note: Note| {
+                        undo_log.saved(original.clone(), EntryId::Note(note.id));
                         replace_dialog(DialogReference::UpdateNote {
                             note_id: note.id,
                         });
                         on_change(());
                         on_close(());
                     },
-                    on_delete: move |_note| {
+                    on_delete: move |note: Note| {
+                        undo_log.deleted(note);
+                        on_change(());
+                        on_close(());
+                    },
+                }
+            }
+        }
+        ActiveDialog::FluidAdjustment(fluid_adjustment_dialog) => {
+            let original = match &fluid_adjustment_dialog {
+                fluid_adjustments::ActiveDialog::Change(fluid_adjustments::Operation::Update {
+                    fluid_adjustment,
+                }) => Some(Entry::from(fluid_adjustment.clone())),
+                _ => None,
+            };
+            rsx! {
+                fluid_adjustments::FluidAdjustmentDialog {
+                    dialog: fluid_adjustment_dialog,
+                    on_close,
+                    on_change: move |fluid_adjustment: FluidAdjustment| {
+                        undo_log.saved(original.clone(), EntryId::FluidAdjustment(fluid_adjustment.id));
+                        replace_dialog(DialogReference::UpdateFluidAdjustment {
+                            fluid_adjustment_id: fluid_adjustment.id,
+                        });
+                        on_change(());
+                        on_close(());
+                    },
+                    on_delete: move |fluid_adjustment: FluidAdjustment| {
+                        undo_log.deleted(fluid_adjustment);
+                        on_change(());
+                        on_close(());
+                    },
+                }
+            }
+        }
+        ActiveDialog::Medication(medication_dialog) => {
+            let original = match &medication_dialog {
+                medication_doses::ActiveDialog::Change(medication_doses::Operation::Update {
+                    medication_dose,
+                }) => Some(Entry::from(medication_dose.clone())),
+                _ => None,
+            };
+            rsx! {
+                medication_doses::MedicationDoseDialog {
+                    dialog: medication_dialog,
+                    on_close,
+                    on_change: move |medication_dose: MedicationDose| {
+                        undo_log.saved(original.clone(), EntryId::Medication(medication_dose.id));
+                        replace_dialog(DialogReference::UpdateMedication {
+                            medication_dose_id: medication_dose.id,
+                        });
+                        on_change(());
+                        on_close(());
+                    },
+                    on_delete: move |medication_dose: MedicationDoseWithMedication| {
+                        undo_log.deleted(medication_dose);
                         on_change(());
                         on_close(());
                     },
@@ -628,3 +982,145 @@ pub fn TimelineDialog(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all_references() -> Vec<DialogReference> {
+        let user_id = UserId::new(1);
+        vec![
+            DialogReference::CreateWee { user_id },
+            DialogReference::UpdateWee {
+                wee_id: WeeId::new(2),
+            },
+            DialogReference::DeleteWee {
+                wee_id: WeeId::new(3),
+            },
+            DialogReference::CreateWeeUrge { user_id },
+            DialogReference::UpdateWeeUrge {
+                wee_urge_id: WeeUrgeId::new(4),
+            },
+            DialogReference::DeleteWeeUrge {
+                wee_urge_id: WeeUrgeId::new(5),
+            },
+            DialogReference::CreatePoo { user_id },
+            DialogReference::UpdatePoo {
+                poo_id: PooId::new(6),
+            },
+            DialogReference::DeletePoo {
+                poo_id: PooId::new(7),
+            },
+            DialogReference::CreateConsumption { user_id },
+            DialogReference::UpdateBasic {
+                consumption_id: ConsumptionId::new(8),
+            },
+            DialogReference::UpdateIngredients {
+                consumption_id: ConsumptionId::new(9),
+            },
+            DialogReference::IngredientUpdateBasic {
+                parent_id: ConsumptionId::new(10),
+                consumable_id: ConsumableId::new(11),
+            },
+            DialogReference::IngredientUpdateIngredients {
+                parent_id: ConsumptionId::new(12),
+                consumable_id: ConsumableId::new(13),
+            },
+            DialogReference::DeleteConsumption {
+                consumption_id: ConsumptionId::new(14),
+            },
+            DialogReference::CreateExercise { user_id },
+            DialogReference::UpdateExercise {
+                exercise_id: ExerciseId::new(15),
+            },
+            DialogReference::DeleteExercise {
+                exercise_id: ExerciseId::new(16),
+            },
+            DialogReference::CreateHealthMetric { user_id },
+            DialogReference::UpdateHealthMetric {
+                health_metric_id: HealthMetricId::new(17),
+            },
+            DialogReference::DeleteHealthMetric {
+                health_metric_id: HealthMetricId::new(18),
+            },
+            DialogReference::CreateSymptom { user_id },
+            DialogReference::UpdateSymptom {
+                symptom_id: SymptomId::new(19),
+            },
+            DialogReference::DeleteSymptom {
+                symptom_id: SymptomId::new(20),
+            },
+            DialogReference::CreateReflux { user_id },
+            DialogReference::UpdateReflux {
+                reflux_id: RefluxId::new(21),
+            },
+            DialogReference::DeleteReflux {
+                reflux_id: RefluxId::new(22),
+            },
+            DialogReference::CreateNote { user_id },
+            DialogReference::UpdateNote {
+                note_id: NoteId::new(23),
+            },
+            DialogReference::DeleteNote {
+                note_id: NoteId::new(24),
+            },
+            DialogReference::CreateFluidAdjustment { user_id },
+            DialogReference::UpdateFluidAdjustment {
+                fluid_adjustment_id: FluidAdjustmentId::new(25),
+            },
+            DialogReference::DeleteFluidAdjustment {
+                fluid_adjustment_id: FluidAdjustmentId::new(26),
+            },
+            DialogReference::CreateMedication { user_id },
+            DialogReference::UpdateMedication {
+                medication_dose_id: MedicationDoseId::new(27),
+            },
+            DialogReference::DeleteMedication {
+                medication_dose_id: MedicationDoseId::new(28),
+            },
+            DialogReference::CreateFromTemplate {
+                entry_template_id: EntryTemplateId::new(29),
+            },
+            DialogReference::Idle,
+        ]
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for reference in all_references() {
+            assert_eq!(
+                DialogReference::from_str(&reference.to_string()),
+                Ok(reference.clone()),
+                "round trip failed for {reference:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_references_are_distinct() {
+        let strings = all_references()
+            .iter()
+            .map(|reference| reference.to_string())
+            .collect::<std::collections::HashSet<_>>();
+        assert_eq!(strings.len(), all_references().len());
+    }
+
+    #[test]
+    fn test_unknown_reference() {
+        assert_eq!(
+            DialogReference::from_str("wee-frobnicate-1"),
+            Err(DialogReferenceError::UnknownReference(
+                "wee-frobnicate-1".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_invalid_id() {
+        let err = DialogReference::from_str("wee-update-abc").unwrap_err();
+        assert!(matches!(
+            err,
+            DialogReferenceError::InvalidId { ref reference, .. } if reference == "wee-update-abc"
+        ));
+    }
+}
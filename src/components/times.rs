@@ -26,6 +26,27 @@ pub fn time_delta_to_string(duration: chrono::TimeDelta) -> String {
     }
 }
 
+/// Like [`time_delta_to_string`], but durations at least `round_after` long
+/// are rounded to the nearest minute, as the seconds are just noise for long
+/// events. Durations under a minute always keep their seconds.
+pub fn time_delta_to_string_rounded(
+    duration: chrono::TimeDelta,
+    round_after: chrono::TimeDelta,
+) -> String {
+    let seconds = duration.num_seconds();
+    if seconds.abs() < 60 || seconds.abs() < round_after.num_seconds() {
+        return time_delta_to_string(duration);
+    }
+
+    let minutes = (seconds.abs() + 30) / 60 * seconds.signum();
+    if minutes.abs() < 60 {
+        let negative_string = if minutes < 0 { "negative " } else { "" };
+        format!("{negative_string}{} minutes", minutes.abs())
+    } else {
+        time_delta_to_string(chrono::TimeDelta::minutes(minutes))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -74,4 +95,45 @@ mod tests {
             "negative 10 days + 0 hours"
         );
     }
+    #[test]
+    fn test_time_delta_to_string_rounded() {
+        let hour = chrono::TimeDelta::hours(1);
+        assert_eq!(
+            time_delta_to_string_rounded(chrono::TimeDelta::seconds(10), hour),
+            "10 seconds"
+        );
+        assert_eq!(
+            time_delta_to_string_rounded(chrono::TimeDelta::seconds(10 * 60 + 40), hour),
+            "10 minutes + 40 seconds"
+        );
+        assert_eq!(
+            time_delta_to_string_rounded(chrono::TimeDelta::seconds(3600 + 5 * 60 + 40), hour),
+            "1 hours + 6 minutes"
+        );
+        assert_eq!(
+            time_delta_to_string_rounded(chrono::TimeDelta::seconds(-(3600 + 5 * 60 + 40)), hour),
+            "negative 1 hours + 6 minutes"
+        );
+    }
+
+    #[test]
+    fn test_time_delta_to_string_rounded_with_lower_threshold() {
+        let minutes = chrono::TimeDelta::minutes(5);
+        assert_eq!(
+            time_delta_to_string_rounded(chrono::TimeDelta::seconds(4 * 60 + 40), minutes),
+            "4 minutes + 40 seconds"
+        );
+        assert_eq!(
+            time_delta_to_string_rounded(chrono::TimeDelta::seconds(10 * 60 + 40), minutes),
+            "11 minutes"
+        );
+        assert_eq!(
+            time_delta_to_string_rounded(chrono::TimeDelta::seconds(59 * 60 + 40), minutes),
+            "1 hours + 0 minutes"
+        );
+        assert_eq!(
+            time_delta_to_string_rounded(chrono::TimeDelta::seconds(40), chrono::TimeDelta::zero()),
+            "40 seconds"
+        );
+    }
 }
@@ -0,0 +1,283 @@
+use std::mem::{Discriminant, discriminant};
+
+use dioxus::prelude::*;
+use dioxus_fullstack::ServerFnError;
+
+use crate::{
+    Route,
+    functions::{
+        consumptions::{
+            create_consumption, create_consumption_consumable, delete_consumption,
+            update_consumption, update_consumption_consumable,
+        },
+        exercises::{create_exercise, delete_exercise, update_exercise},
+        fluid_adjustments::{
+            create_fluid_adjustment, delete_fluid_adjustment, update_fluid_adjustment,
+        },
+        health_metrics::{create_health_metric, delete_health_metric, update_health_metric},
+        medication_doses::{
+            create_medication_dose, delete_medication_dose, update_medication_dose,
+        },
+        notes::{create_note, delete_note, update_note},
+        poos::{create_poo, delete_poo, update_poo},
+        refluxs::{create_reflux, delete_reflux, update_reflux},
+        symptoms::{create_symptom, delete_symptom, update_symptom},
+        wee_urges::{create_wee_urge, delete_wee_urge, update_wee_urge},
+        wees::{create_wee, delete_wee, update_wee},
+    },
+    models::{
+        ChangeConsumptionConsumable, ConsumptionConsumableId, ConsumptionWithItems, Entry,
+        EntryData, EntryId, MaybeSet, NewConsumptionConsumable, UndoAction, change_request_from,
+        new_request_from,
+    },
+};
+
+/// The last change made on the current page, so it can be undone from the
+/// navbar. Leaving the page forgets it.
+#[derive(Clone, Copy, PartialEq)]
+pub struct UndoLog {
+    action: Signal<Option<UndoAction>>,
+    page: Signal<Option<Discriminant<Route>>>,
+    /// Bumped after every undo, so pages showing entries know to reload.
+    undone: Signal<u32>,
+}
+
+impl UndoLog {
+    pub fn action(&self) -> Option<UndoAction> {
+        self.action.read().clone()
+    }
+
+    /// Subscribe to undos, for resources that need to reload afterwards.
+    pub fn track(&self) {
+        self.undone.read();
+    }
+
+    /// Record a save, an update if there was an `original` to go back to.
+    pub fn saved(mut self, original: Option<Entry>, id: EntryId) {
+        let action = match original {
+            Some(original) => UndoAction::Updated(Box::new(original)),
+            None => UndoAction::Created(id),
+        };
+        self.action.set(Some(action));
+    }
+
+    pub fn deleted(mut self, entry: impl Into<Entry>) {
+        self.action
+            .set(Some(UndoAction::Deleted(Box::new(entry.into()))));
+    }
+
+    fn visit(mut self, route: &Route) {
+        let page = discriminant(route);
+        if *self.page.peek() != Some(page) {
+            self.page.set(Some(page));
+            self.action.set(None);
+        }
+    }
+}
+
+pub fn use_undo_log() -> UndoLog {
+    use_context()
+}
+
+/// Provide the undo log for the pages below, forgetting the last change
+/// whenever the user moves to a different page.
+pub fn use_undo_log_provider(route: Route) -> UndoLog {
+    let undo_log = use_context_provider(|| UndoLog {
+        action: Signal::new(None),
+        page: Signal::new(None),
+        undone: Signal::new(0),
+    });
+    use_effect(use_reactive!(|route| undo_log.visit(&route)));
+    undo_log
+}
+
+fn convert_error(err: serde_json::Error) -> ServerFnError {
+    ServerFnError::new(format!("Cannot restore entry: {err}"))
+}
+
+async fn delete_entry(id: EntryId) -> Result<(), ServerFnError> {
+    match id {
+        EntryId::Wee(id) => delete_wee(id).await,
+        EntryId::WeeUrge(id) => delete_wee_urge(id).await,
+        EntryId::Poo(id) => delete_poo(id).await,
+        EntryId::Consumption(id) => delete_consumption(id).await,
+        EntryId::Exercise(id) => delete_exercise(id).await,
+        EntryId::HealthMetric(id) => delete_health_metric(id).await,
+        EntryId::Symptom(id) => delete_symptom(id).await,
+        EntryId::Reflux(id) => delete_reflux(id).await,
+        EntryId::Note(id) => delete_note(id).await,
+        EntryId::FluidAdjustment(id) => delete_fluid_adjustment(id).await,
+        EntryId::Medication(id) => delete_medication_dose(id).await,
+    }
+}
+
+/// Put an edited entry back how it was. For consumptions only the basic
+/// details are restored, ingredients are edited separately.
+async fn restore_entry(data: EntryData) -> Result<(), ServerFnError> {
+    match data {
+        EntryData::Wee(wee) => {
+            let changes = change_request_from(&wee).map_err(convert_error)?;
+            update_wee(wee.id, changes).await?;
+        }
+        EntryData::WeeUrge(wee_urge) => {
+            let changes = change_request_from(&wee_urge).map_err(convert_error)?;
+            update_wee_urge(wee_urge.id, changes).await?;
+        }
+        EntryData::Poo(poo) => {
+            let changes = change_request_from(&poo).map_err(convert_error)?;
+            update_poo(poo.id, changes).await?;
+        }
+        EntryData::Consumption(consumption) => {
+            let consumption = consumption.consumption;
+            let changes = change_request_from(&consumption).map_err(convert_error)?;
+            update_consumption(consumption.id, changes).await?;
+        }
+        EntryData::Exercise(exercise) => {
+            let changes = change_request_from(&exercise).map_err(convert_error)?;
+            update_exercise(exercise.id, changes).await?;
+        }
+        EntryData::HealthMetric(health_metric) => {
+            let changes = change_request_from(&health_metric).map_err(convert_error)?;
+            update_health_metric(health_metric.id, changes).await?;
+        }
+        EntryData::Symptom(symptom) => {
+            let changes = change_request_from(&symptom).map_err(convert_error)?;
+            update_symptom(symptom.id, changes).await?;
+        }
+        EntryData::Reflux(reflux) => {
+            let changes = change_request_from(&reflux).map_err(convert_error)?;
+            update_reflux(reflux.id, changes).await?;
+        }
+        EntryData::Note(note) => {
+            let changes = change_request_from(&note).map_err(convert_error)?;
+            update_note(note.id, changes).await?;
+        }
+        EntryData::FluidAdjustment(fluid_adjustment) => {
+            let changes = change_request_from(&fluid_adjustment).map_err(convert_error)?;
+            update_fluid_adjustment(fluid_adjustment.id, changes).await?;
+        }
+        EntryData::Medication(medication_dose) => {
+            let dose = medication_dose.dose;
+            let changes = change_request_from(&dose).map_err(convert_error)?;
+            update_medication_dose(dose.id, changes).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Create a consumption again along with its ingredients.
+async fn recreate_consumption(consumption: ConsumptionWithItems) -> Result<(), ServerFnError> {
+    let new_consumption = new_request_from(&consumption.consumption).map_err(convert_error)?;
+    let created = create_consumption(new_consumption).await?;
+
+    for item in consumption.items {
+        let id = ConsumptionConsumableId::new(created.id, item.consumable.id);
+        create_consumption_consumable(NewConsumptionConsumable {
+            id,
+            quantity: item.nested.quantity,
+            liquid_mls: item.nested.liquid_mls,
+            comments: item.nested.comments,
+        })
+        .await?;
+
+        if item.nested.portion != 1 {
+            let changes = ChangeConsumptionConsumable {
+                portion: MaybeSet::Set(item.nested.portion),
+                ..Default::default()
+            };
+            update_consumption_consumable(id, changes).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Create a deleted entry again. It gets a new id.
+async fn recreate_entry(data: EntryData) -> Result<(), ServerFnError> {
+    match data {
+        EntryData::Wee(wee) => {
+            create_wee(new_request_from(&wee).map_err(convert_error)?).await?;
+        }
+        EntryData::WeeUrge(wee_urge) => {
+            create_wee_urge(new_request_from(&wee_urge).map_err(convert_error)?).await?;
+        }
+        EntryData::Poo(poo) => {
+            create_poo(new_request_from(&poo).map_err(convert_error)?).await?;
+        }
+        EntryData::Consumption(consumption) => {
+            recreate_consumption(consumption).await?;
+        }
+        EntryData::Exercise(exercise) => {
+            create_exercise(new_request_from(&exercise).map_err(convert_error)?).await?;
+        }
+        EntryData::HealthMetric(health_metric) => {
+            create_health_metric(new_request_from(&health_metric).map_err(convert_error)?).await?;
+        }
+        EntryData::Symptom(symptom) => {
+            create_symptom(new_request_from(&symptom).map_err(convert_error)?).await?;
+        }
+        EntryData::Reflux(reflux) => {
+            create_reflux(new_request_from(&reflux).map_err(convert_error)?).await?;
+        }
+        EntryData::Note(note) => {
+            create_note(new_request_from(&note).map_err(convert_error)?).await?;
+        }
+        EntryData::FluidAdjustment(fluid_adjustment) => {
+            create_fluid_adjustment(new_request_from(&fluid_adjustment).map_err(convert_error)?)
+                .await?;
+        }
+        EntryData::Medication(medication_dose) => {
+            create_medication_dose(new_request_from(&medication_dose.dose).map_err(convert_error)?)
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+async fn undo(action: UndoAction) -> Result<(), ServerFnError> {
+    match action {
+        UndoAction::Created(id) => delete_entry(id).await,
+        UndoAction::Updated(entry) => restore_entry(entry.data).await,
+        UndoAction::Deleted(entry) => recreate_entry(entry.data).await,
+    }
+}
+
+#[component]
+pub fn UndoButton() -> Element {
+    let mut undo_log = use_undo_log();
+    let mut undoing = use_signal(|| false);
+    let mut error = use_signal(|| None::<ServerFnError>);
+
+    let Some(action) = undo_log.action() else {
+        return rsx! {};
+    };
+    let title = action.title();
+
+    rsx! {
+        div { class: "flex items-center gap-2",
+            button {
+                r#type: "button",
+                class: "btn btn-sm btn-outline",
+                disabled: undoing(),
+                onclick: move |_| {
+                    let action = action.clone();
+                    spawn(async move {
+                        undoing.set(true);
+                        match undo(action).await {
+                            Ok(()) => {
+                                error.set(None);
+                                undo_log.action.set(None);
+                                undo_log.undone += 1;
+                            }
+                            Err(err) => error.set(Some(err)),
+                        }
+                        undoing.set(false);
+                    });
+                },
+                {title}
+            }
+            if let Some(err) = error() {
+                span { class: "text-sm text-red-600 dark:text-red-400", "Undo failed: {err}" }
+            }
+        }
+    }
+}
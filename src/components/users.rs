@@ -1,5 +1,7 @@
 use std::{num::ParseIntError, str::FromStr, sync::Arc};
 
+use chrono::{DateTime, Local, Utc};
+
 use dioxus::prelude::*;
 use dioxus_router::ToQueryArgument;
 use tap::Pipe;
@@ -8,13 +10,29 @@ use thiserror::Error;
 use crate::{
     forms::{
         Dialog, EditError, FieldValue, FormSaveCancelButton, InputBoolean, InputPassword,
-        InputString, Saving, ValidationError, validate_1st_password, validate_2nd_password,
-        validate_email, validate_full_name, validate_username,
+        InputString, Saving, UnsavedChanges, ValidationError, use_dirty, validate_1st_password,
+        validate_2nd_password, validate_email, validate_full_name, validate_username,
     },
     functions::users::{create_user, delete_user, update_user},
-    models::{ChangeUser, MaybeSet, NewUser, User},
+    i18n::{format_prefs, t},
+    models::{ChangeUser, MaybeSet, NewUser, User, UserId, format_datetime},
 };
 
+#[component]
+pub fn LastLogin(last_login_at: Option<DateTime<Utc>>) -> Element {
+    match last_login_at {
+        Some(last_login_at) => {
+            let last_login_at = last_login_at.with_timezone(&Local).fixed_offset();
+            rsx! {
+                {format_datetime(&last_login_at, &format_prefs())}
+            }
+        }
+        None => rsx! {
+            span { class: "text-warning", "Never logged in" }
+        },
+    }
+}
+
 #[derive(Debug, Clone)]
 struct ValidateSaveNewUser {
     username: Memo<Result<String, ValidationError>>,
@@ -50,6 +68,7 @@ struct ValidateUpdateExistingUser {
     email: Memo<Result<String, ValidationError>>,
     full_name: Memo<Result<String, ValidationError>>,
     is_admin: Memo<Result<bool, ValidationError>>,
+    is_active: Memo<Result<bool, ValidationError>>,
 }
 
 async fn do_update_existing_user(
@@ -60,6 +79,7 @@ async fn do_update_existing_user(
     let email = validate.email.read().clone()?;
     let full_name = validate.full_name.read().clone()?;
     let is_admin = validate.is_admin.read().clone()?;
+    let is_active = validate.is_active.read().clone()?;
 
     let changes = ChangeUser {
         username: MaybeSet::Set(username),
@@ -67,6 +87,7 @@ async fn do_update_existing_user(
         full_name: MaybeSet::Set(full_name),
         oidc_id: MaybeSet::NoChange,
         is_admin: MaybeSet::Set(is_admin),
+        is_active: MaybeSet::Set(is_active),
     };
     update_user(user.id, changes, None)
         .await
@@ -86,14 +107,7 @@ async fn do_change_password(
     let password = validate.password.read().clone()?;
     let _password_confirm = validate.password_confirm.read().clone()?;
 
-    let changes = ChangeUser {
-        username: MaybeSet::NoChange,
-        email: MaybeSet::NoChange,
-        full_name: MaybeSet::NoChange,
-        oidc_id: MaybeSet::NoChange,
-        is_admin: MaybeSet::NoChange,
-    };
-    update_user(user.id, changes, Some(password))
+    update_user(user.id, ChangeUser::default(), Some(password))
         .await
         .map_err(EditError::Server)
 }
@@ -121,6 +135,17 @@ pub fn UserCreate(on_cancel: Callback, on_save: Callback<User>) -> Element {
         }
     };
 
+    let dirty = use_dirty(move || {
+        (
+            username(),
+            email(),
+            full_name(),
+            password(),
+            password_confirm(),
+            is_admin(),
+        )
+    });
+
     let mut saving = use_signal(|| Saving::No);
 
     // disable form while waiting for response
@@ -145,6 +170,7 @@ pub fn UserCreate(on_cancel: Callback, on_save: Callback<User>) -> Element {
             match result {
                 Ok(user) => {
                     saving.set(Saving::Finished(Ok(())));
+                    dirty.reset();
                     on_save(user);
                 }
                 Err(err) => saving.set(Saving::Finished(Err(err))),
@@ -155,7 +181,10 @@ pub fn UserCreate(on_cancel: Callback, on_save: Callback<User>) -> Element {
     rsx! {
 
         Dialog {
-            h3 { class: "text-lg font-bold", "Create User" }
+            h3 { class: "text-lg font-bold",
+                {t("Create User")}
+                UnsavedChanges { dirty: dirty.is_dirty() }
+            }
             p { class: "py-4", "Press ESC key or click the button below to close" }
             form {
                 novalidate: true,
@@ -211,7 +240,7 @@ pub fn UserCreate(on_cancel: Callback, on_save: Callback<User>) -> Element {
                     disabled: disabled_save,
                     on_save: move |()| on_save(()),
                     on_cancel: move |_| on_cancel(()),
-                    title: "Create",
+                    title: t("Create"),
                     saving,
                 }
             }
@@ -227,14 +256,18 @@ pub fn UserUpdate(user: User, on_cancel: Callback, on_save: Callback<User>) -> E
     let email = use_signal(|| user.email.as_raw());
     let full_name = use_signal(|| user.full_name.as_raw());
     let is_admin = use_signal(|| user.is_admin);
+    let is_active = use_signal(|| user.is_active);
 
     let validate = ValidateUpdateExistingUser {
         username: use_memo(move || validate_username(&username())),
         email: use_memo(move || validate_email(&email())),
         full_name: use_memo(move || validate_full_name(&full_name())),
         is_admin: use_memo(move || Ok(is_admin())),
+        is_active: use_memo(move || Ok(is_active())),
     };
 
+    let dirty = use_dirty(move || (username(), email(), full_name(), is_admin(), is_active()));
+
     let mut saving = use_signal(|| Saving::No);
 
     // disable form while waiting for response
@@ -260,6 +293,7 @@ pub fn UserUpdate(user: User, on_cancel: Callback, on_save: Callback<User>) -> E
             match result {
                 Ok(user) => {
                     saving.set(Saving::Finished(Ok(())));
+                    dirty.reset();
                     on_save(user);
                 }
                 Err(err) => saving.set(Saving::Finished(Err(err))),
@@ -270,8 +304,14 @@ pub fn UserUpdate(user: User, on_cancel: Callback, on_save: Callback<User>) -> E
     rsx! {
         Dialog {
             h3 { class: "text-lg font-bold",
-                "Edit User: "
+                {t("Edit User")}
+                ": "
                 {&*user.username}
+                UnsavedChanges { dirty: dirty.is_dirty() }
+            }
+            p { class: "pt-4",
+                "Last login: "
+                LastLogin { last_login_at: user.last_login_at }
             }
             p { class: "py-4", "Press ESC key or click the button below to close" }
             form {
@@ -310,6 +350,12 @@ pub fn UserUpdate(user: User, on_cancel: Callback, on_save: Callback<User>) -> E
                     value: is_admin,
                     disabled,
                 }
+                InputBoolean {
+                    id: "is_active",
+                    label: "Is Active",
+                    value: is_active,
+                    disabled,
+                }
                 FormSaveCancelButton {
                     disabled: disabled_save,
                     on_save: move |()| on_save(()),
@@ -339,6 +385,8 @@ pub fn UserUpdatePassword(user: User, on_cancel: Callback, on_save: Callback<Use
         }
     };
 
+    let dirty = use_dirty(move || (password(), password_confirm()));
+
     let mut saving = use_signal(|| Saving::No);
 
     // disable form while waiting for response
@@ -359,6 +407,7 @@ pub fn UserUpdatePassword(user: User, on_cancel: Callback, on_save: Callback<Use
             match result {
                 Ok(user) => {
                     saving.set(Saving::Finished(Ok(())));
+                    dirty.reset();
                     on_save(user);
                 }
                 Err(err) => saving.set(Saving::Finished(Err(err))),
@@ -371,6 +420,7 @@ pub fn UserUpdatePassword(user: User, on_cancel: Callback, on_save: Callback<Use
             h3 { class: "text-lg font-bold",
                 "Change password for "
                 {&*user.username}
+                UnsavedChanges { dirty: dirty.is_dirty() }
             }
             p { class: "py-4", "Press ESC key or click the button below to close" }
             form {
@@ -481,15 +531,15 @@ pub enum ListDialogReferenceError {
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub enum ListDialogReference {
     Create,
-    // Update {
-    //     user_id: UserId,
-    // },
-    // Password {
-    //     user_id: UserId,
-    // },
-    // Delete {
-    //     user_id: UserId,
-    // },
+    Update {
+        user_id: UserId,
+    },
+    Password {
+        user_id: UserId,
+    },
+    Delete {
+        user_id: UserId,
+    },
     #[default]
     Idle,
 }
@@ -511,18 +561,18 @@ impl FromStr for ListDialogReference {
         let split = s.split("-").collect::<Vec<_>>();
         match split[..] {
             ["create"] => Self::Create,
-            // ["update", id] => {
-            //     let user_id = UserId::new(id.parse()?);
-            //     Self::Update { user_id }
-            // }
-            // ["password", id] => {
-            //     let user_id = UserId::new(id.parse()?);
-            //     Self::Password { user_id }
-            // }
-            // ["delete", id] => {
-            //     let user_id = UserId::new(id.parse()?);
-            //     Self::Delete { user_id }
-            // }
+            ["update", id] => {
+                let user_id = UserId::new(id.parse()?);
+                Self::Update { user_id }
+            }
+            ["password", id] => {
+                let user_id = UserId::new(id.parse()?);
+                Self::Password { user_id }
+            }
+            ["delete", id] => {
+                let user_id = UserId::new(id.parse()?);
+                Self::Delete { user_id }
+            }
             [""] | [] => Self::Idle,
             _ => return Err(ListDialogReferenceError::ReferenceError),
         }
@@ -535,11 +585,9 @@ impl ToString for ListDialogReference {
     fn to_string(&self) -> String {
         match self {
             ListDialogReference::Create => "create".to_string(),
-            // ListDialogReference::Update { user_id } => format!("update-{user_id}"),
-            // ListDialogReference::Password { user_id } => {
-            //     format!("password-{user_id}")
-            // }
-            // ListDialogReference::Delete { user_id } => format!("delete-{user_id}"),
+            ListDialogReference::Update { user_id } => format!("update-{user_id}"),
+            ListDialogReference::Password { user_id } => format!("password-{user_id}"),
+            ListDialogReference::Delete { user_id } => format!("delete-{user_id}"),
             ListDialogReference::Idle => String::new(),
         }
     }
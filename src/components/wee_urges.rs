@@ -5,17 +5,24 @@ use crate::{
     components::events::{EventDateTimeShort, Markdown, UrgencyLabel},
     forms::{
         Dialog, EditError, FieldValue, FormSaveCancelButton, InputDateTime, InputTextArea,
-        InputUrgency, Saving, ValidationError, validate_comments, validate_fixed_offset_date_time,
-        validate_urgency,
+        InputUrgency, Saving, UnsavedChanges, ValidationError, use_dirty, validate_comments,
+        validate_fixed_offset_date_time, validate_urgency,
     },
     functions::wee_urges::{create_wee_urge, delete_wee_urge, update_wee_urge},
+    i18n::t,
     models::{ChangeWeeUrge, MaybeSet, NewWeeUrge, Urgency, UserId, WeeUrge},
+    use_timezone,
 };
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Operation {
-    Create { user_id: UserId },
-    Update { wee_urge: WeeUrge },
+    Create {
+        user_id: UserId,
+        template: Option<NewWeeUrge>,
+    },
+    Update {
+        wee_urge: WeeUrge,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -31,7 +38,7 @@ async fn do_save(op: &Operation, validate: &Validate) -> Result<WeeUrge, EditErr
     let comments = validate.comments.read().clone()?;
 
     match op {
-        Operation::Create { user_id } => {
+        Operation::Create { user_id, .. } => {
             let updates = NewWeeUrge {
                 user_id: *user_id,
                 time,
@@ -61,20 +68,31 @@ pub fn WeeUrgeUpdate(op: Operation, on_cancel: Callback, on_save: Callback<WeeUr
         Operation::Update { wee_urge } => wee_urge.time.as_raw(),
     });
     let urgency = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(wee_urge),
+            ..
+        } => Some(wee_urge.urgency),
         Operation::Create { .. } => None,
         Operation::Update { wee_urge } => Some(wee_urge.urgency),
     });
     let comments = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(wee_urge),
+            ..
+        } => wee_urge.comments.as_raw(),
         Operation::Create { .. } => String::new(),
         Operation::Update { wee_urge } => wee_urge.comments.as_raw(),
     });
 
+    let timezone = use_timezone();
     let validate = Validate {
-        time: use_memo(move || validate_fixed_offset_date_time(&time())),
+        time: use_memo(move || validate_fixed_offset_date_time(&time(), timezone)),
         urgency: use_memo(move || validate_urgency(urgency())),
         comments: use_memo(move || validate_comments(&comments())),
     };
 
+    let dirty = use_dirty(move || (time(), urgency(), comments()));
+
     let mut saving = use_signal(|| Saving::No);
 
     // disable form while waiting for response
@@ -99,6 +117,7 @@ pub fn WeeUrgeUpdate(op: Operation, on_cancel: Callback, on_save: Callback<WeeUr
             match result {
                 Ok(wee_urge) => {
                     saving.set(Saving::Finished(Ok(())));
+                    dirty.reset();
                     on_save(wee_urge);
                 }
                 Err(err) => saving.set(Saving::Finished(Err(err))),
@@ -109,9 +128,10 @@ pub fn WeeUrgeUpdate(op: Operation, on_cancel: Callback, on_save: Callback<WeeUr
     rsx! {
         h3 { class: "text-lg font-bold",
             match &op {
-                Operation::Create { .. } => "Create Wee Urge".to_string(),
-                Operation::Update { wee_urge } => format!("Edit Wee Urge {}", wee_urge.id),
+                Operation::Create { .. } => t("Create Wee Urge").to_string(),
+                Operation::Update { wee_urge } => format!("{} {}", t("Edit Wee Urge"), wee_urge.id),
             }
+            UnsavedChanges { dirty: dirty.is_dirty() }
         }
         p { class: "py-4", "Press ESC key or click the button below to close" }
         form {
@@ -150,8 +170,8 @@ pub fn WeeUrgeUpdate(op: Operation, on_cancel: Callback, on_save: Callback<WeeUr
                 on_save: move |()| on_save(()),
                 on_cancel: move |()| on_cancel(()),
                 title: match &op {
-                    Operation::Create { .. } => "Create",
-                    Operation::Update { .. } => "Save",
+                    Operation::Create { .. } => t("Create"),
+                    Operation::Update { .. } => t("Save"),
                 },
                 saving,
             }
@@ -187,10 +207,11 @@ pub fn WeeUrgeDelete(
 
     rsx! {
         h3 { class: "text-lg font-bold",
-            "Delete wee_urge "
-            {wee_urge.id.to_string()}
+            "Delete wee urge "
+            {wee_urge.name()}
         }
         p { class: "py-4", "Press ESC key or click the button below to close" }
+        WeeUrgeSummary { wee_urge: wee_urge.clone() }
         form {
             novalidate: true,
             action: "javascript:void(0)",
@@ -217,7 +238,7 @@ const WEE_URGENCY_SVG: Asset = asset!("/assets/wee_urge.svg");
 pub fn WeeUrgeIcon() -> Element {
     let alt = wee_urge_title();
     rsx! {
-        img { alt, src: WEE_URGENCY_SVG }
+        img { alt, title: alt, src: WEE_URGENCY_SVG }
     }
 }
 
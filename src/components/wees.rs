@@ -7,23 +7,36 @@ use crate::{
     components::{
         events::{EventDateTimeShort, Markdown, UrgencyLabel, event_colour},
         symptoms::{SymptomDisplay, SymptomIntensity},
-        times::time_delta_to_string,
+        times::{time_delta_to_string, time_delta_to_string_rounded},
     },
     forms::{
-        Colour, Dialog, EditError, FieldValue, FormSaveCancelButton, InputColour, InputDateTime,
-        InputDuration, InputNumber, InputSymptomIntensity, InputTextArea, InputUrgency, Saving,
-        ValidationError, validate_colour, validate_comments, validate_duration,
-        validate_fixed_offset_date_time, validate_symptom_intensity, validate_urgency,
-        validate_wee_millilitres,
+        Colour, Dialog, EditError, FieldValue, FieldWarning, FormSaveCancelButton, InputColour,
+        InputDateTime, InputDuration, InputNumber, InputSymptomIntensity, InputTextArea,
+        InputUrgency, InputVolumePresets, Saving, UnsavedChanges, ValidationError, use_dirty,
+        validate_colour, validate_comments, validate_duration, validate_fixed_offset_date_time,
+        validate_symptom_intensity, validate_urgency, validate_wee_millilitres,
     },
-    functions::wees::{create_wee, delete_wee, update_wee},
-    models::{ChangeWee, MaybeSet, NewWee, Urgency, UserId, Wee},
+    functions::{
+        wee_urges::get_unlinked_wee_urges,
+        wees::{create_wee, delete_wee, find_duplicate_wee, update_wee},
+    },
+    i18n::t,
+    models::{
+        ChangeWee, ColourPaletteKind, EntryKind, MaybeSet, NewWee, Urgency, UserId, Wee,
+        WeeCoherenceThresholds, WeeId, WeeUrgeId, wee_coherence_warning,
+    },
+    use_duration_rounding, use_timezone,
 };
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Operation {
-    Create { user_id: UserId },
-    Update { wee: Wee },
+    Create {
+        user_id: UserId,
+        template: Option<NewWee>,
+    },
+    Update {
+        wee: Wee,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -37,7 +50,12 @@ struct Validate {
     comments: Memo<Result<Option<String>, ValidationError>>,
 }
 
-async fn do_save(op: &Operation, validate: &Validate) -> Result<Wee, EditError> {
+async fn do_save(
+    op: &Operation,
+    validate: &Validate,
+    wee_urge_id: Option<WeeUrgeId>,
+    allow_duplicate: bool,
+) -> Result<Wee, EditError> {
     let time = validate.time.read().clone()?;
     let duration = validate.duration.read().clone()?;
     let urgency = validate.urgency.read().clone()?;
@@ -47,7 +65,7 @@ async fn do_save(op: &Operation, validate: &Validate) -> Result<Wee, EditError>
     let comments = validate.comments.read().clone()?;
 
     match op {
-        Operation::Create { user_id } => {
+        Operation::Create { user_id, .. } => {
             let updates = NewWee {
                 user_id: *user_id,
                 time,
@@ -57,7 +75,16 @@ async fn do_save(op: &Operation, validate: &Validate) -> Result<Wee, EditError>
                 mls,
                 colour,
                 comments,
+                wee_urge_id,
             };
+            if !allow_duplicate
+                && find_duplicate_wee(updates.clone())
+                    .await
+                    .map_err(EditError::Server)?
+                    .is_some()
+            {
+                return Err(EditError::Duplicate);
+            }
             create_wee(updates).await.map_err(EditError::Server)
         }
         Operation::Update { wee } => {
@@ -70,6 +97,7 @@ async fn do_save(op: &Operation, validate: &Validate) -> Result<Wee, EditError>
                 mls: MaybeSet::Set(mls),
                 colour: MaybeSet::Set(colour),
                 comments: MaybeSet::Set(comments),
+                wee_urge_id: MaybeSet::Set(wee_urge_id),
             };
             update_wee(wee.id, changes).await.map_err(EditError::Server)
         }
@@ -83,22 +111,52 @@ pub fn WeeUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Wee>) ->
         Operation::Update { wee } => wee.time.as_raw(),
     });
     let duration = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(wee),
+            ..
+        } => wee.duration.as_raw(),
         Operation::Create { .. } => String::new(),
         Operation::Update { wee } => wee.duration.as_raw(),
     });
     let urgency = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(wee),
+            ..
+        } => Some(wee.urgency),
         Operation::Create { .. } => None,
         Operation::Update { wee } => Some(wee.urgency),
     });
     let leakage = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(wee),
+            ..
+        } => wee.leakage.as_raw(),
         Operation::Create { .. } => String::new(),
         Operation::Update { wee } => wee.leakage.as_raw(),
     });
     let mls = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(wee),
+            ..
+        } => wee.mls.as_raw(),
         Operation::Create { .. } => String::new(),
         Operation::Update { wee } => wee.mls.as_raw(),
     });
     let colour = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(wee),
+            ..
+        } => {
+            if let Some(colour) = wee.colour {
+                (
+                    colour.hue.into_inner().to_string(),
+                    colour.saturation.to_string(),
+                    colour.value.to_string(),
+                )
+            } else {
+                (String::new(), String::new(), String::new())
+            }
+        }
         Operation::Create { .. } => (String::new(), String::new(), String::new()),
         Operation::Update { wee } => {
             if let Some(colour) = wee.colour {
@@ -113,14 +171,23 @@ pub fn WeeUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Wee>) ->
         }
     });
     let comments = use_signal(|| match &op {
+        Operation::Create {
+            template: Some(wee),
+            ..
+        } => wee.comments.as_raw(),
         Operation::Create { .. } => String::new(),
         Operation::Update { wee } => wee.comments.as_raw(),
     });
+    let wee_urge_id = use_signal(|| match &op {
+        Operation::Create { .. } => None,
+        Operation::Update { wee } => wee.wee_urge_id,
+    });
 
+    let timezone = use_timezone();
     let validate = {
         let validate_mls = use_memo(move || validate_wee_millilitres(&mls()));
         Validate {
-            time: use_memo(move || validate_fixed_offset_date_time(&time())),
+            time: use_memo(move || validate_fixed_offset_date_time(&time(), timezone)),
             duration: use_memo(move || validate_duration(&duration())),
             urgency: use_memo(move || validate_urgency(urgency())),
             leakage: use_memo(move || validate_symptom_intensity(&leakage())),
@@ -130,6 +197,25 @@ pub fn WeeUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Wee>) ->
         }
     };
 
+    let coherence_warning = use_memo(move || {
+        let mls = validate.mls.read().clone().ok()?;
+        let urgency = validate.urgency.read().clone().ok()?;
+        wee_coherence_warning(mls, urgency, &WeeCoherenceThresholds::default())
+    });
+
+    let dirty = use_dirty(move || {
+        (
+            time(),
+            duration(),
+            urgency(),
+            leakage(),
+            mls(),
+            colour(),
+            comments(),
+            wee_urge_id(),
+        )
+    });
+
     let mut saving = use_signal(|| Saving::No);
 
     // disable form while waiting for response
@@ -151,13 +237,16 @@ pub fn WeeUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Wee>) ->
         let op = op_clone.clone();
         let validate = validate_clone.clone();
         spawn(async move {
+            let allow_duplicate =
+                matches!(&*saving.peek(), Saving::Finished(Err(err)) if err.is_duplicate());
             saving.set(Saving::Yes);
 
-            let result = do_save(&op, &validate).await;
+            let result = do_save(&op, &validate, wee_urge_id(), allow_duplicate).await;
 
             match result {
                 Ok(wee) => {
                     saving.set(Saving::Finished(Ok(())));
+                    dirty.reset();
                     on_save(wee);
                 }
                 Err(err) => saving.set(Saving::Finished(Err(err))),
@@ -168,9 +257,10 @@ pub fn WeeUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Wee>) ->
     rsx! {
         h3 { class: "text-lg font-bold",
             match &op {
-                Operation::Create { .. } => "Create Wee".to_string(),
-                Operation::Update { wee } => format!("Edit Wee {}", wee.id),
+                Operation::Create { .. } => t("Create Wee").to_string(),
+                Operation::Update { wee } => format!("{} {}", t("Edit Wee"), wee.id),
             }
+            UnsavedChanges { dirty: dirty.is_dirty() }
         }
         p { class: "py-4", "Press ESC key or click the button below to close" }
         form {
@@ -196,6 +286,7 @@ pub fn WeeUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Wee>) ->
                 start_time: validate.time,
                 validate: validate.duration,
                 disabled,
+                kind: EntryKind::Wee,
             }
             InputUrgency {
                 id: "urgency",
@@ -204,6 +295,19 @@ pub fn WeeUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Wee>) ->
                 validate: validate.urgency,
                 disabled,
             }
+            WeeUrgeLink {
+                user_id: match &op {
+                    Operation::Create { user_id, .. } => *user_id,
+                    Operation::Update { wee } => wee.user_id,
+                },
+                wee_id: match &op {
+                    Operation::Create { .. } => None,
+                    Operation::Update { wee } => Some(wee.id),
+                },
+                wee_time: validate.time,
+                value: wee_urge_id,
+                disabled,
+            }
             InputSymptomIntensity {
                 id: "leakage",
                 label: "Leakage",
@@ -217,19 +321,16 @@ pub fn WeeUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Wee>) ->
                 value: mls,
                 validate: validate.mls,
                 disabled,
+                step: bigdecimal::BigDecimal::from(10),
             }
+            InputVolumePresets { value: mls, disabled }
+            FieldWarning { warning: coherence_warning, disabled }
             InputColour {
                 id: "colour",
                 label: "Colour",
                 value: colour,
                 validate: validate.colour,
-                colours: vec![
-                    ("extra light".to_string(), Hsv::new(44.0, 1.0, 0.8)),
-                    ("light".to_string(), Hsv::new(42.0, 1.0, 0.8)),
-                    ("normal".to_string(), Hsv::new(40.0, 1.0, 0.8)),
-                    ("dark".to_string(), Hsv::new(38.0, 1.0, 0.8)),
-                    ("extra dark".to_string(), Hsv::new(36.0, 1.0, 0.8)),
-                ],
+                palette: ColourPaletteKind::Wee,
                 disabled,
             }
             Colour { colour }
@@ -246,8 +347,8 @@ pub fn WeeUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Wee>) ->
                 on_save: move |()| on_save(()),
                 on_cancel: move |()| on_cancel(()),
                 title: match &op {
-                    Operation::Create { .. } => "Create",
-                    Operation::Update { .. } => "Save",
+                    Operation::Create { .. } => t("Create"),
+                    Operation::Update { .. } => t("Save"),
                 },
                 saving,
             }
@@ -255,6 +356,68 @@ pub fn WeeUpdate(op: Operation, on_cancel: Callback, on_save: Callback<Wee>) ->
     }
 }
 
+#[component]
+fn WeeUrgeLink(
+    user_id: UserId,
+    wee_id: Option<WeeId>,
+    wee_time: Memo<Result<DateTime<FixedOffset>, ValidationError>>,
+    mut value: Signal<Option<WeeUrgeId>>,
+    disabled: Memo<bool>,
+) -> Element {
+    let urges = use_resource(move || async move {
+        let Ok(time) = wee_time() else {
+            return Ok(Vec::new());
+        };
+        get_unlinked_wee_urges(user_id, time.with_timezone(&Utc), wee_id).await
+    });
+
+    rsx! {
+        div { class: "mb-5",
+            label { class: "block mb-2 text-sm font-medium text-gray-900 dark:text-white",
+                "Followed urge"
+            }
+            match &*urges.read() {
+                Some(Ok(urges)) => rsx! {
+                    div { class: "flex flex-wrap gap-2",
+                        button {
+                            r#type: "button",
+                            class: "btn btn-secondary",
+                            class: if value().is_none() { "btn-active" },
+                            disabled: disabled(),
+                            onclick: move |_e| value.set(None),
+                            "Not linked"
+                        }
+                        for urge in urges.iter().cloned() {
+                            button {
+                                key: "{urge.id}",
+                                r#type: "button",
+                                class: "btn btn-secondary",
+                                class: if value() == Some(urge.id) { "btn-active" },
+                                disabled: disabled(),
+                                onclick: move |_e| value.set(Some(urge.id)),
+                                EventDateTimeShort { time: urge.time }
+                                UrgencyLabel { urgency: urge.urgency }
+                                if let Ok(time) = wee_time() {
+                                    {format!("{} before", time_delta_to_string(time - urge.time))}
+                                }
+                            }
+                        }
+                    }
+                    if urges.is_empty() {
+                        p { class: "text-sm opacity-70", "No recent urges to link." }
+                    }
+                },
+                Some(Err(err)) => rsx! {
+                    div { class: "alert alert-error", "Error loading urges: {err}" }
+                },
+                None => rsx! {
+                    p { "Loading urges..." }
+                },
+            }
+        }
+    }
+}
+
 #[component]
 pub fn WeeDelete(wee: Wee, on_cancel: Callback, on_delete: Callback<Wee>) -> Element {
     let mut saving = use_signal(|| Saving::No);
@@ -280,7 +443,7 @@ pub fn WeeDelete(wee: Wee, on_cancel: Callback, on_delete: Callback<Wee>) -> Ele
     rsx! {
         h3 { class: "text-lg font-bold",
             "Delete wee "
-            {wee.id.to_string()}
+            {wee.name()}
         }
         p { class: "py-4", "Press ESC key or click the button below to close" }
         WeeSummary { wee: wee.clone() }
@@ -310,7 +473,7 @@ const WEE_SVG: Asset = asset!("/assets/wee.svg");
 pub fn WeeIcon() -> Element {
     let alt = wee_title();
     rsx! {
-        img { alt, src: WEE_SVG }
+        img { alt, title: alt, src: WEE_SVG }
     }
 }
 
@@ -320,7 +483,7 @@ pub fn wee_title() -> &'static str {
 
 #[component]
 pub fn WeeDuration(duration: chrono::TimeDelta) -> Element {
-    let text = time_delta_to_string(duration);
+    let text = time_delta_to_string_rounded(duration, use_duration_rounding());
 
     let classes = if duration.num_seconds() == 0 {
         classes!["text-error"]
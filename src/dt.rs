@@ -1,12 +1,35 @@
-use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
 use dioxus_fullstack::ServerFnError;
-use tap::Pipe;
 use tracing::error;
 
 const DAY_TIME: NaiveTime = NaiveTime::from_hms_opt(6, 30, 0).unwrap();
 
+fn local_to_utc(local: NaiveDateTime, timezone: Option<Tz>) -> Option<DateTime<Utc>> {
+    match timezone {
+        Some(timezone) => timezone
+            .from_local_datetime(&local)
+            .single()
+            .map(|x| x.with_timezone(&Utc)),
+        None => Local
+            .from_local_datetime(&local)
+            .single()
+            .map(|x| x.with_timezone(&Utc)),
+    }
+}
+
+fn utc_to_local(dt: DateTime<Utc>, timezone: Option<Tz>) -> NaiveDateTime {
+    match timezone {
+        Some(timezone) => dt.with_timezone(&timezone).naive_local(),
+        None => dt.with_timezone(&Local).naive_local(),
+    }
+}
+
+/// Start and end of the timeline day, in the user's timezone or the
+/// device's if they have not set one.
 pub fn get_utc_times_for_date(
     date: NaiveDate,
+    timezone: Option<Tz>,
 ) -> Result<(DateTime<Utc>, DateTime<Utc>), ServerFnError> {
     let today = date;
     let tomorrow = today.succ_opt().ok_or_else(|| {
@@ -14,32 +37,24 @@ pub fn get_utc_times_for_date(
         ServerFnError::new("Failed to get tomorrow's date".to_string())
     })?;
 
-    let start = today
-        .and_time(DAY_TIME)
-        .pipe(|x| Local.from_local_datetime(&x));
-    let end = tomorrow
-        .and_time(DAY_TIME)
-        .pipe(|x| Local.from_local_datetime(&x));
-
-    let start = start.single().ok_or_else(|| {
+    let start = local_to_utc(today.and_time(DAY_TIME), timezone).ok_or_else(|| {
         error!("Failed to convert start time to UTC for date: {:?}", today);
         ServerFnError::new("Failed to convert start time".to_string())
     })?;
 
-    let end = end.single().ok_or_else(|| {
+    let end = local_to_utc(tomorrow.and_time(DAY_TIME), timezone).ok_or_else(|| {
         error!("Failed to convert end time to UTC for date: {:?}", tomorrow);
         ServerFnError::new("Failed to convert end time".to_string())
     })?;
 
-    let start = start.with_timezone(&Utc);
-    let end = end.with_timezone(&Utc);
-
     Ok((start, end))
 }
 
-pub fn get_date_for_dt(entry_date: DateTime<Utc>) -> NaiveDate {
-    let local_date_time = entry_date.with_timezone(&Local);
-    let local_date = local_date_time.date_naive();
+/// The timeline day a time falls on, in the user's timezone or the device's
+/// if they have not set one.
+pub fn get_date_for_dt(entry_date: DateTime<Utc>, timezone: Option<Tz>) -> NaiveDate {
+    let local_date_time = utc_to_local(entry_date, timezone);
+    let local_date = local_date_time.date();
 
     if local_date_time.time() < DAY_TIME {
         local_date.pred_opt().unwrap_or(local_date)
@@ -48,6 +63,54 @@ pub fn get_date_for_dt(entry_date: DateTime<Utc>) -> NaiveDate {
     }
 }
 
+/// The timeline day it is now for the user.
+pub fn get_today(timezone: Option<Tz>) -> NaiveDate {
+    get_date_for_dt(Utc::now(), timezone)
+}
+
 pub fn display_date(entry_date: NaiveDate) -> String {
     entry_date.format("%A, %-d %B, %C%y").to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn date_for_dt_uses_timezone() {
+        // 22:00 UTC is the next morning in Melbourne but still the same
+        // evening in New York.
+        let dt = utc("2025-03-10T22:00:00Z");
+        assert_eq!(
+            get_date_for_dt(dt, Some(chrono_tz::Australia::Melbourne)),
+            NaiveDate::from_ymd_opt(2025, 3, 11).unwrap()
+        );
+        assert_eq!(
+            get_date_for_dt(dt, Some(chrono_tz::America::New_York)),
+            NaiveDate::from_ymd_opt(2025, 3, 10).unwrap()
+        );
+    }
+
+    #[test]
+    fn date_for_dt_before_day_start_is_previous_day() {
+        // 05:00 in Melbourne is before the day starts.
+        let dt = utc("2025-03-10T18:00:00Z");
+        assert_eq!(
+            get_date_for_dt(dt, Some(chrono_tz::Australia::Melbourne)),
+            NaiveDate::from_ymd_opt(2025, 3, 10).unwrap()
+        );
+    }
+
+    #[test]
+    fn utc_times_for_date_uses_timezone() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 11).unwrap();
+        let (start, end) =
+            get_utc_times_for_date(date, Some(chrono_tz::Australia::Melbourne)).unwrap();
+        assert_eq!(start, utc("2025-03-10T19:30:00Z"));
+        assert_eq!(end, utc("2025-03-11T19:30:00Z"));
+    }
+}
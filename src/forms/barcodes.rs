@@ -1,6 +1,6 @@
 use dioxus::prelude::*;
 
-use crate::components::buttons::ActionButton;
+use crate::{components::buttons::ActionButton, forms::validate_barcode};
 
 const ZBAR_WASM: Asset = asset!("/assets/zbar.wasm");
 
@@ -105,17 +105,23 @@ fn BarcodeInput(on_set: Callback<String>) -> Element {
     }
 }
 
+/// Scan a barcode into `barcode`, normalized if it is valid. `on_scan` is
+/// called with each scanned barcode.
 #[component]
-pub fn Barcode(barcode: Signal<String>) -> Element {
+pub fn Barcode(barcode: Signal<String>, on_scan: Option<Callback<String>>) -> Element {
     let mut show = use_signal(|| false);
 
     rsx! {
         if show() {
             h1 { "Please scan barcode" }
             BarcodeInput {
-                on_set: move |value| {
-                    barcode.set(value);
+                on_set: move |value: String| {
+                    let value = validate_barcode(&value).ok().flatten().unwrap_or(value);
+                    barcode.set(value.clone());
                     show.set(false);
+                    if let Some(on_scan) = on_scan {
+                        on_scan(value);
+                    }
                 },
             }
             ActionButton { on_click: move |_| show.set(false), "cancel" }
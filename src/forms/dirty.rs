@@ -0,0 +1,51 @@
+#![allow(non_snake_case)]
+use dioxus::prelude::*;
+
+use crate::i18n::t;
+
+/// Whether a form's values differ from the ones it started with.
+pub struct Dirty<T: 'static> {
+    initial: Signal<T>,
+    current: Memo<T>,
+}
+
+impl<T> Clone for Dirty<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Dirty<T> {}
+
+impl<T: Clone + PartialEq + 'static> Dirty<T> {
+    pub fn is_dirty(&self) -> bool {
+        *self.initial.read() != *self.current.read()
+    }
+
+    /// Treat the current values as the saved ones.
+    pub fn reset(mut self) {
+        let current = self.current.peek().clone();
+        self.initial.set(current);
+    }
+}
+
+/// Track changes to a form. `current` reads every field's signal, the values
+/// it returns on the first render are the initial ones.
+pub fn use_dirty<T: Clone + PartialEq + 'static>(current: impl FnMut() -> T + 'static) -> Dirty<T> {
+    let current = use_memo(current);
+    let initial = use_signal(|| current.peek().clone());
+    Dirty { initial, current }
+}
+
+/// Marker for a dialog title while the form has changes that are not saved.
+#[component]
+pub fn UnsavedChanges(dirty: bool) -> Element {
+    rsx! {
+        if dirty {
+            span { class: "ml-2 text-sm font-normal text-warning",
+                "• "
+                {t("unsaved changes")}
+            }
+        }
+    }
+}
@@ -20,4 +20,14 @@ pub enum EditError {
 
     #[error(transparent)]
     Validation(#[from] ValidationError),
+
+    /// Saving again confirms it.
+    #[error("Looks like a duplicate of an entry saved moments ago, save again to keep both")]
+    Duplicate,
+}
+
+impl EditError {
+    pub fn is_duplicate(&self) -> bool {
+        matches!(self, EditError::Duplicate)
+    }
 }
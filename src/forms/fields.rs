@@ -1,30 +1,36 @@
 #![allow(non_snake_case)]
-use chrono::{DateTime, FixedOffset, Local, TimeDelta, Utc};
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, TimeDelta, Utc};
 use classes::classes;
-use dioxus::{prelude::*, signals::Signal};
+use dioxus::{core::Task, prelude::*, signals::Signal};
 use dioxus_fullstack::ServerFnError;
+use gloo_timers::future::sleep;
 use palette::{Hsv, IntoColor, Srgb};
-use std::{ops::Deref, rc::Rc};
-use tap::Pipe;
+use std::{ops::Deref, rc::Rc, str::FromStr, time::Duration};
 
 use crate::{
     components::{
         ElementIcon,
         buttons::{ActionButton, CreateButton},
         consumables::{self, ConsumableLabel, ConsumableUnitIcon, ConsumableUpdate},
-        consumptions::ConsumptionTypeIcon,
+        consumptions::{ConsumptionRouteIcon, ConsumptionTypeIcon},
         events::{UrgencyIcon, UrgencyLabel},
         exercises::{ExerciseRpeIcon, ExerciseRpeLabel, ExerciseTypeIcon},
+        medications::{MedicationIcon, MedicationLabel},
         poos::PooBristolIcon,
     },
     forms::{
         Barcode, validate_colour_hue, validate_colour_saturation, validate_colour_value,
         values::FieldLabel,
     },
-    functions::consumables::search_consumables,
+    functions::{colour_palettes::get_colour_presets, consumables::search_consumables},
+    i18n::t,
     models::{
-        Bristol, Consumable, ConsumableUnit, ConsumptionType, ExerciseRpe, ExerciseType, Urgency,
+        Bristol, ColourPaletteKind, Consumable, ConsumableUnit, ConsumptionRoute, ConsumptionType,
+        DateFormat, DistanceUnit, EntryKind, ExerciseRpe, ExerciseType, GlucoseUnit, LandingDate,
+        Locale, Medication, PooSize, Theme, Urgency,
     },
+    use_consumption_types, use_user,
 };
 
 use super::FieldValue;
@@ -110,11 +116,76 @@ pub fn FieldMessage<D: 'static + Clone + PartialEq>(
 ) -> Element {
     rsx! {
         if disabled() {
-            div { class: "text-gray-300", "Inactive" }
+            div { class: "text-gray-300", {t("Inactive")} }
         } else if let Err(err) = validate() {
             div { class: "text-red-500", "{err}" }
         } else {
-            div { class: "text-green-500", "Looks good!" }
+            div { class: "text-green-500", {t("Looks good!")} }
+        }
+    }
+}
+
+/// Advisory message that does not stop the form being saved.
+#[component]
+pub fn FieldWarning(warning: Memo<Option<String>>, disabled: Memo<bool>) -> Element {
+    rsx! {
+        if let (false, Some(warning)) = (disabled(), warning()) {
+            div { class: "text-warning mb-5", "{warning}" }
+        }
+    }
+}
+
+/// A field that is stopping the form from being saved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldError {
+    /// The `id` of the field's input.
+    pub id: &'static str,
+    pub label: &'static str,
+    pub message: String,
+}
+
+/// Banner listing the invalid fields, for forms too long to see them all at
+/// once. Clicking a field scrolls to it and focuses it, after `on_select` has
+/// had a chance to make sure it is shown.
+#[component]
+pub fn FormErrorSummary(
+    errors: Memo<Vec<FieldError>>,
+    on_select: Option<Callback<&'static str>>,
+) -> Element {
+    let mut focus = use_signal(|| None::<&'static str>);
+
+    use_effect(move || {
+        if let Some(id) = focus() {
+            document::eval(&format!(
+                "const field = document.getElementById({id:?}); field?.scrollIntoView({{ block: 'center' }}); field?.focus();"
+            ));
+        }
+    });
+
+    rsx! {
+        if !errors().is_empty() {
+            div { class: "alert alert-error mb-5 flex-col items-start", role: "alert",
+                div { {t("Fix these fields before saving:")} }
+                ul { class: "list-disc list-inside",
+                    for error in errors() {
+                        li { key: "{error.id}",
+                            a {
+                                href: "#{error.id}",
+                                class: "link",
+                                onclick: move |event| {
+                                    event.prevent_default();
+                                    if let Some(on_select) = on_select {
+                                        on_select(error.id);
+                                    }
+                                    focus.set(Some(error.id));
+                                },
+                                "{error.label}"
+                            }
+                            ": {error.message}"
+                        }
+                    }
+                }
+            }
         }
     }
 }
@@ -374,6 +445,7 @@ pub fn InputString<D: 'static + Clone + PartialEq>(
     value: Signal<String>,
     validate: Memo<Result<D, ValidationError>>,
     disabled: Memo<bool>,
+    on_blur: Option<Callback<()>>,
 ) -> Element {
     rsx! {
         div { class: "mb-5",
@@ -388,12 +460,63 @@ pub fn InputString<D: 'static + Clone + PartialEq>(
                 oninput: move |e| {
                     value.set(e.value());
                 },
+                onblur: move |_| {
+                    if let Some(on_blur) = on_blur {
+                        on_blur(());
+                    }
+                },
             }
             FieldMessage { validate, disabled }
         }
     }
 }
 
+fn step_value(value: &str, step: &BigDecimal) -> String {
+    let current = BigDecimal::from_str(value.trim()).unwrap_or_default();
+    let new_value = current + step;
+    if new_value < 0 {
+        "0".to_string()
+    } else {
+        new_value.to_string()
+    }
+}
+
+#[component]
+fn StepButton(label: &'static str, disabled: Memo<bool>, on_step: Callback<()>) -> Element {
+    let mut repeat: Signal<Option<Task>> = use_signal(|| None);
+
+    let mut stop = move || {
+        if let Some(task) = repeat.write().take() {
+            task.cancel();
+        }
+    };
+
+    rsx! {
+        button {
+            r#type: "button",
+            class: "btn btn-secondary",
+            disabled: disabled(),
+            onpointerdown: move |_e| {
+                stop();
+                on_step(());
+                // Keep stepping while the button is held down.
+                let task = spawn(async move {
+                    sleep(Duration::from_millis(500)).await;
+                    loop {
+                        on_step(());
+                        sleep(Duration::from_millis(100)).await;
+                    }
+                });
+                repeat.set(Some(task));
+            },
+            onpointerup: move |_e| stop(),
+            onpointerleave: move |_e| stop(),
+            onpointercancel: move |_e| stop(),
+            "{label}"
+        }
+    }
+}
+
 #[component]
 pub fn InputNumber<D: 'static + Clone + PartialEq>(
     id: &'static str,
@@ -401,45 +524,201 @@ pub fn InputNumber<D: 'static + Clone + PartialEq>(
     value: Signal<String>,
     validate: Memo<Result<D, ValidationError>>,
     disabled: Memo<bool>,
+    step: Option<BigDecimal>,
+    suffix: Option<String>,
 ) -> Element {
+    let input = rsx! {
+        input {
+            r#type: "text",
+            class: get_input_classes(validate().is_ok(), disabled()),
+            id,
+            r#type: "number",
+            pattern: "[0-9]*",
+            inputmode: "numeric",
+            placeholder: "Enter input",
+            value: "{value()}",
+            disabled,
+            oninput: move |e| {
+                value.set(e.value());
+            },
+        }
+    };
+
     rsx! {
         div { class: "mb-5",
             label { r#for: id, class: get_label_classes(), "{label}" }
-            input {
-                r#type: "text",
-                class: get_input_classes(validate().is_ok(), disabled()),
-                id,
-                r#type: "number",
-                pattern: "[0-9]*",
-                inputmode: "numeric",
-                placeholder: "Enter input",
-                value: "{value()}",
-                disabled,
-                oninput: move |e| {
-                    value.set(e.value());
-                },
+            if step.is_some() || suffix.is_some() {
+                div { class: "flex gap-2",
+                    if let Some(step) = step.clone() {
+                        StepButton {
+                            label: "-",
+                            disabled,
+                            on_step: move |()| {
+                                let new_value = step_value(&value.peek(), &-step.clone());
+                                value.set(new_value);
+                            },
+                        }
+                    }
+                    {input}
+                    if let Some(suffix) = suffix {
+                        span { class: "self-center", "{suffix}" }
+                    }
+                    if let Some(step) = step {
+                        StepButton {
+                            label: "+",
+                            disabled,
+                            on_step: move |()| {
+                                let new_value = step_value(&value.peek(), &step);
+                                value.set(new_value);
+                            },
+                        }
+                    }
+                }
+            } else {
+                {input}
             }
             FieldMessage { validate, disabled }
         }
     }
 }
 
+/// An amount of a consumable, shown with its unit and stepped by an amount
+/// that suits the unit. Millilitre amounts also offer the user's volume
+/// presets. `validate` should check the value against `unit`.
 #[component]
-pub fn InputSymptomIntensity(
+pub fn InputQuantity(
+    id: &'static str,
+    label: String,
+    unit: ConsumableUnit,
+    value: Signal<String>,
+    validate: Memo<Result<Option<BigDecimal>, ValidationError>>,
+    disabled: Memo<bool>,
+) -> Element {
+    let suffix = unit.postfix();
+
+    rsx! {
+        InputNumber {
+            id,
+            label,
+            value,
+            validate,
+            disabled,
+            step: unit.step(),
+            suffix: (!suffix.is_empty()).then(|| suffix.to_string()),
+        }
+        if unit == ConsumableUnit::Millilitres {
+            InputVolumePresets { value, disabled }
+        }
+    }
+}
+
+#[component]
+pub fn InputVolumePresets(value: Signal<String>, disabled: Memo<bool>) -> Element {
+    let presets = use_user()
+        .ok()
+        .flatten()
+        .map(|user| user.volume_presets.clone())
+        .unwrap_or_default();
+
+    rsx! {
+        if !disabled() && !presets.is_empty() {
+            div { class: "flex flex-wrap gap-2 mb-5",
+                for preset in presets {
+                    button {
+                        r#type: "button",
+                        class: "btn btn-secondary",
+                        class: if value() == preset.to_string() { "btn-active" },
+                        onclick: move |_e| value.set(preset.to_string()),
+                        "{preset}ml"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn InputPooQuantity(
     id: &'static str,
     label: &'static str,
     value: Signal<String>,
     validate: Memo<Result<i32, ValidationError>>,
     disabled: Memo<bool>,
 ) -> Element {
+    let current = validate().ok().map(PooSize::from_quantity);
+
     rsx! {
         InputNumber {
             id,
-            label: label.to_string() + " (0-10)",
+            label,
             value,
             validate,
             disabled,
         }
+        if !disabled() {
+            div { class: "flex flex-wrap gap-2 mb-5",
+                for size in PooSize::all_values().iter().copied() {
+                    button {
+                        r#type: "button",
+                        class: "btn btn-secondary",
+                        class: if current == Some(size) { "btn-active" },
+                        onclick: move |_e| value.set(size.quantity().to_string()),
+                        {size.as_title()}
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn InputSymptomIntensity(
+    id: &'static str,
+    label: &'static str,
+    value: Signal<String>,
+    validate: Memo<Result<i32, ValidationError>>,
+    disabled: Memo<bool>,
+) -> Element {
+    // An invalid value leaves the slider at zero until it is fixed.
+    let current = validate().unwrap_or(0);
+
+    rsx! {
+        div { class: "mb-5",
+            label { r#for: id, class: get_label_classes(), "{label} (0-10)" }
+            div { class: "flex items-center gap-4",
+                input {
+                    r#type: "range",
+                    class: "range range-sm flex-1",
+                    id: "{id}_slider",
+                    "aria-label": label,
+                    min: "0",
+                    max: "10",
+                    step: "1",
+                    value: "{current}",
+                    disabled,
+                    oninput: move |e| {
+                        value.set(e.value());
+                    },
+                }
+                div { class: "w-20",
+                    input {
+                        class: get_input_classes(validate().is_ok(), disabled()),
+                        id,
+                        r#type: "number",
+                        pattern: "[0-9]*",
+                        inputmode: "numeric",
+                        min: "0",
+                        max: "10",
+                        value: "{value()}",
+                        disabled,
+                        oninput: move |e| {
+                            value.set(e.value());
+                        },
+                    }
+                }
+            }
+            FieldMessage { validate, disabled }
+        }
     }
 }
 
@@ -471,7 +750,7 @@ pub fn InputPassword<D: 'static + Clone + Eq + PartialEq>(
 }
 
 #[component]
-pub fn InputTextArea<D: 'static + Clone + Eq + PartialEq>(
+pub fn InputTextArea<D: 'static + Clone + PartialEq>(
     id: &'static str,
     label: &'static str,
     value: Signal<String>,
@@ -517,6 +796,11 @@ pub fn InputDateTime(
                 oninput: move |e| {
                     value.set(e.value());
                 },
+                onblur: move |_| {
+                    if let Ok(dt) = validate() {
+                        value.set(dt.as_raw());
+                    }
+                },
             }
             ActionButton {
                 on_click: move |_e| {
@@ -529,6 +813,32 @@ pub fn InputDateTime(
     }
 }
 
+#[component]
+pub fn InputDate(
+    id: &'static str,
+    label: &'static str,
+    value: Signal<String>,
+    validate: Memo<Result<NaiveDate, ValidationError>>,
+    disabled: Memo<bool>,
+) -> Element {
+    rsx! {
+        div { class: "mb-5",
+            label { r#for: id, class: get_label_classes(), "{label}" }
+            input {
+                r#type: "date",
+                class: get_input_classes(validate().is_ok(), disabled()),
+                id,
+                value: "{value()}",
+                disabled,
+                oninput: move |e| {
+                    value.set(e.value());
+                },
+            }
+            FieldMessage { validate, disabled }
+        }
+    }
+}
+
 #[component]
 pub fn InputOptionDateTimeUtc(
     id: &'static str,
@@ -570,7 +880,28 @@ pub fn InputDuration(
     start_time: Memo<Result<DateTime<FixedOffset>, ValidationError>>,
     validate: Memo<Result<TimeDelta, ValidationError>>,
     disabled: Memo<bool>,
+    kind: EntryKind,
 ) -> Element {
+    let warning = use_memo(move || {
+        validate()
+            .ok()
+            .and_then(|duration| kind.duration_warning(duration))
+    });
+
+    // Elapsed time is worked out from wall clock timestamps, so the
+    // stopwatch stays correct if ticks are missed while the page is in the
+    // background.
+    let mut stopwatch_started = use_signal(|| None::<DateTime<Utc>>);
+    let mut stopwatch_now = use_signal(Utc::now);
+    use_future(move || async move {
+        loop {
+            sleep(Duration::from_secs(1)).await;
+            if stopwatch_started.peek().is_some() {
+                stopwatch_now.set(Utc::now());
+            }
+        }
+    });
+
     rsx! {
         div { class: "mb-5",
             label { r#for: id, class: get_label_classes(), "{label}" }
@@ -597,7 +928,33 @@ pub fn InputDuration(
                     "Stop"
                 }
             }
+            if let Some(started) = stopwatch_started() {
+                span { class: "font-mono mx-2", {(stopwatch_now() - started).as_raw()} }
+                ActionButton {
+                    on_click: move |_e| {
+                        value.set((Utc::now() - started).as_raw());
+                        stopwatch_started.set(None);
+                    },
+                    "Stop stopwatch"
+                }
+                ActionButton {
+                    on_click: move |_e| {
+                        stopwatch_started.set(None);
+                    },
+                    "Cancel"
+                }
+            } else {
+                ActionButton {
+                    on_click: move |_e| {
+                        let now = Utc::now();
+                        stopwatch_now.set(now);
+                        stopwatch_started.set(Some(now));
+                    },
+                    "Start stopwatch"
+                }
+            }
             FieldMessage { validate, disabled }
+            FieldWarning { warning, disabled }
         }
     }
 }
@@ -694,6 +1051,323 @@ pub fn InputConsumptionType(
     }
 }
 
+/// Like [`InputConsumptionType`], with the user's own types after the built
+/// in ones.
+#[component]
+pub fn InputConsumptionRoute(
+    id: &'static str,
+    label: &'static str,
+    value: Signal<Option<ConsumptionRoute>>,
+    validate: Memo<Result<ConsumptionRoute, ValidationError>>,
+    disabled: Memo<bool>,
+) -> Element {
+    let custom_types = use_consumption_types();
+    let options = ConsumptionType::all_values()
+        .iter()
+        .map(|consumption_type| ConsumptionRoute::BuiltIn(*consumption_type))
+        .chain(custom_types.into_iter().map(ConsumptionRoute::Custom))
+        .map(|route| {
+            let label = route.as_title();
+            InputOption {
+                id: route.as_id(),
+                icon: rsx! {
+                    ConsumptionRouteIcon { route: route.clone() }
+                },
+                value: Some(route),
+                title: label.clone(),
+                label: rsx! { "{label}" },
+            }
+        })
+        .collect::<Vec<_>>();
+
+    rsx! {
+        InputSelect {
+            id,
+            label,
+            validate,
+            value,
+            disabled,
+            options,
+        }
+    }
+}
+
+#[component]
+pub fn InputMedication(
+    id: &'static str,
+    label: &'static str,
+    value: Signal<Option<Medication>>,
+    validate: Memo<Result<Medication, ValidationError>>,
+    disabled: Memo<bool>,
+    medications: Vec<Medication>,
+) -> Element {
+    let options = medications
+        .into_iter()
+        .map(|medication| {
+            let id = medication.id.to_string();
+            let icon = rsx! {
+                MedicationIcon {}
+            };
+            let title = medication.name();
+            let label = rsx! {
+                MedicationLabel { medication: medication.clone() }
+            };
+            InputOption {
+                id,
+                value: Some(medication),
+                icon,
+                title,
+                label,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    rsx! {
+        InputSelect {
+            id,
+            label,
+            validate,
+            value,
+            disabled,
+            options,
+        }
+    }
+}
+
+#[component]
+pub fn InputDateFormat(
+    id: &'static str,
+    label: &'static str,
+    value: Signal<Option<DateFormat>>,
+    validate: Memo<Result<Option<DateFormat>, ValidationError>>,
+    disabled: Memo<bool>,
+) -> Element {
+    let options = std::iter::once(InputOption {
+        id: "language".to_string(),
+        value: None,
+        icon: rsx! {},
+        title: "Language default".to_string(),
+        label: rsx! { "Language default" },
+    })
+    .chain(DateFormat::all_values().iter().map(|date_format| {
+        let label = date_format.as_title();
+        InputOption {
+            id: date_format.as_id().to_string(),
+            value: Some(*date_format),
+            icon: rsx! {},
+            title: label.to_string(),
+            label: rsx! { "{label}" },
+        }
+    }))
+    .collect::<Vec<_>>();
+
+    rsx! {
+        InputSelect {
+            id,
+            label,
+            validate,
+            value,
+            disabled,
+            options,
+        }
+    }
+}
+
+#[component]
+pub fn InputDistanceUnit(
+    id: &'static str,
+    label: &'static str,
+    value: Signal<Option<DistanceUnit>>,
+    validate: Memo<Result<Option<DistanceUnit>, ValidationError>>,
+    disabled: Memo<bool>,
+) -> Element {
+    let options = std::iter::once(InputOption {
+        id: "default".to_string(),
+        value: None,
+        icon: rsx! {},
+        title: "Default (kilometres)".to_string(),
+        label: rsx! { "Default (kilometres)" },
+    })
+    .chain(DistanceUnit::all_values().iter().map(|distance_unit| {
+        let label = distance_unit.as_title();
+        InputOption {
+            id: distance_unit.as_id().to_string(),
+            value: Some(*distance_unit),
+            icon: rsx! {},
+            title: label.to_string(),
+            label: rsx! { "{label}" },
+        }
+    }))
+    .collect::<Vec<_>>();
+
+    rsx! {
+        InputSelect {
+            id,
+            label,
+            validate,
+            value,
+            disabled,
+            options,
+        }
+    }
+}
+
+#[component]
+pub fn InputGlucoseUnit(
+    id: &'static str,
+    label: &'static str,
+    value: Signal<Option<GlucoseUnit>>,
+    validate: Memo<Result<Option<GlucoseUnit>, ValidationError>>,
+    disabled: Memo<bool>,
+) -> Element {
+    let options = std::iter::once(InputOption {
+        id: "default".to_string(),
+        value: None,
+        icon: rsx! {},
+        title: "Default (mmol/L)".to_string(),
+        label: rsx! { "Default (mmol/L)" },
+    })
+    .chain(GlucoseUnit::all_values().iter().map(|glucose_unit| {
+        let label = glucose_unit.as_title();
+        InputOption {
+            id: glucose_unit.as_id().to_string(),
+            value: Some(*glucose_unit),
+            icon: rsx! {},
+            title: label.to_string(),
+            label: rsx! { "{label}" },
+        }
+    }))
+    .collect::<Vec<_>>();
+
+    rsx! {
+        InputSelect {
+            id,
+            label,
+            validate,
+            value,
+            disabled,
+            options,
+        }
+    }
+}
+
+#[component]
+pub fn InputTheme(
+    id: &'static str,
+    label: &'static str,
+    value: Signal<Option<Theme>>,
+    validate: Memo<Result<Option<Theme>, ValidationError>>,
+    disabled: Memo<bool>,
+) -> Element {
+    let options = std::iter::once(InputOption {
+        id: "default".to_string(),
+        value: None,
+        icon: rsx! {},
+        title: "Default (system setting)".to_string(),
+        label: rsx! { "Default (system setting)" },
+    })
+    .chain(Theme::all_values().iter().map(|theme| {
+        let label = theme.as_title();
+        InputOption {
+            id: theme.as_id().to_string(),
+            value: Some(*theme),
+            icon: rsx! {},
+            title: label.to_string(),
+            label: rsx! { "{label}" },
+        }
+    }))
+    .collect::<Vec<_>>();
+
+    rsx! {
+        InputSelect {
+            id,
+            label,
+            validate,
+            value,
+            disabled,
+            options,
+        }
+    }
+}
+
+#[component]
+pub fn InputLandingDate(
+    id: &'static str,
+    label: &'static str,
+    value: Signal<Option<LandingDate>>,
+    validate: Memo<Result<Option<LandingDate>, ValidationError>>,
+    disabled: Memo<bool>,
+) -> Element {
+    let options = std::iter::once(InputOption {
+        id: "today".to_string(),
+        value: None,
+        icon: rsx! {},
+        title: "Today".to_string(),
+        label: rsx! { "Today" },
+    })
+    .chain(LandingDate::all_values().iter().map(|landing_date| {
+        let label = landing_date.as_title();
+        InputOption {
+            id: landing_date.as_id().to_string(),
+            value: Some(*landing_date),
+            icon: rsx! {},
+            title: label.to_string(),
+            label: rsx! { "{label}" },
+        }
+    }))
+    .collect::<Vec<_>>();
+
+    rsx! {
+        InputSelect {
+            id,
+            label,
+            validate,
+            value,
+            disabled,
+            options,
+        }
+    }
+}
+
+#[component]
+pub fn InputLocale(
+    id: &'static str,
+    label: &'static str,
+    value: Signal<Option<Locale>>,
+    validate: Memo<Result<Option<Locale>, ValidationError>>,
+    disabled: Memo<bool>,
+) -> Element {
+    let options = std::iter::once(InputOption {
+        id: "browser".to_string(),
+        value: None,
+        icon: rsx! {},
+        title: "Browser default".to_string(),
+        label: rsx! { "Browser default" },
+    })
+    .chain(Locale::all_values().iter().map(|locale| {
+        let label = locale.as_title();
+        InputOption {
+            id: locale.as_id().to_string(),
+            value: Some(*locale),
+            icon: rsx! {},
+            title: label.to_string(),
+            label: rsx! { "{label}" },
+        }
+    }))
+    .collect::<Vec<_>>();
+
+    rsx! {
+        InputSelect {
+            id,
+            label,
+            validate,
+            value,
+            disabled,
+            options,
+        }
+    }
+}
+
 #[component]
 pub fn InputConsumptionTypeMaybe(
     id: &'static str,
@@ -1017,11 +1691,18 @@ pub fn InputColour(
     label: &'static str,
     value: Signal<(String, String, String)>,
     validate: Memo<Result<Option<Hsv>, ValidationError>>,
-    colours: Vec<(String, Hsv)>,
+    palette: ColourPaletteKind,
     disabled: Memo<bool>,
 ) -> Element {
     let (hue, saturation, brightness) = value();
 
+    // Show the defaults until the user's own palette has loaded.
+    let presets = use_resource(move || async move { get_colour_presets(palette).await });
+    let colours = match presets.read().deref() {
+        Some(Ok(colours)) => colours.clone(),
+        _ => palette.default_colours(),
+    };
+
     let hue_id = format!("{}-hue", id);
     let saturation_id = format!("{}-saturation", id);
     let value_id = format!("{}-value", id);
@@ -1114,10 +1795,10 @@ pub fn InputColour(
         }
 
         div {
-            for (button_name , button_colour) in colours {
+            for preset in colours {
                 ColourButton {
-                    colour: button_colour,
-                    name: button_name,
+                    colour: preset.hsv(),
+                    name: preset.name.clone(),
                     on_click: move |c: Hsv| {
                         value
                             .set((
@@ -1126,7 +1807,7 @@ pub fn InputColour(
                                 c.value.to_string(),
                             ))
                     },
-                    selected: Some(button_colour) == colour,
+                    selected: Some(preset.hsv()) == colour,
                 }
             }
         }
@@ -1134,6 +1815,9 @@ pub fn InputColour(
     }
 }
 
+/// Consumables to pick from, and whether more matched than were returned.
+type ConsumableOptions = (Vec<PullDownMenuItem<Consumable>>, bool);
+
 #[component]
 pub fn InputConsumable(
     id: &'static str,
@@ -1154,24 +1838,28 @@ pub fn InputConsumable(
         }
     });
 
-    let list: Resource<Result<Vec<PullDownMenuItem<Consumable>>, ServerFnError>> =
+    let list: Resource<Result<ConsumableOptions, ServerFnError>> =
         use_resource(move || async move {
             let query = search();
             if let Some(query) = query
                 && !query.is_empty()
             {
-                search_consumables(query, false, false)
-                    .await
-                    .unwrap()
+                let results = search_consumables(query.clone(), false, false).await?;
+                let options = results
+                    .consumables
                     .into_iter()
                     .map(|consumable| {
                         let id = consumable.id.to_string();
                         let icon = rsx! {
-                            consumables::ConsumableIcon {}
+                            consumables::ConsumableIcon { consumable: consumable.clone() }
                         };
                         let label = rsx! {
                             div {
-                                ConsumableLabel { consumable: consumable.clone() }
+                                ConsumableLabel {
+                                    consumable: consumable.clone(),
+                                    query: query.clone(),
+                                    compact: true,
+                                }
                             }
                         };
                         PullDownMenuItem {
@@ -1181,15 +1869,20 @@ pub fn InputConsumable(
                             icon,
                         }
                     })
-                    .collect::<Vec<_>>()
-                    .pipe(Ok)
+                    .collect::<Vec<_>>();
+                Ok((options, results.truncated))
             } else {
-                Ok(Vec::new())
+                Ok((Vec::new(), false))
             }
         });
 
-    let filtered_options =
-        use_memo(move || list().unwrap_or_else(|| Ok(Vec::new())).unwrap_or_default());
+    let filtered_options = use_memo(move || {
+        list()
+            .and_then(Result::ok)
+            .map(|(options, _truncated)| options)
+            .unwrap_or_default()
+    });
+    let truncated = use_memo(move || matches!(list(), Some(Ok((_, true)))));
 
     let validate = use_memo(move || Ok(value()));
 
@@ -1203,6 +1896,11 @@ pub fn InputConsumable(
                     create_form.set(false);
                     on_create(consumable);
                 },
+                on_open_existing: move |consumable: Consumable| {
+                    value.set(Some(consumable.clone()));
+                    create_form.set(false);
+                    on_change(Some(consumable));
+                },
             }
         } else {
             if let Some(Err(err)) = list.read().deref() {
@@ -1237,6 +1935,9 @@ pub fn InputConsumable(
                 search,
                 on_change: Some(on_change),
             }
+            if truncated() {
+                consumables::SearchTruncatedHint {}
+            }
             div { class: "gap-2",
                 CreateButton { on_click: move |_e| create_form.set(true), "Create" }
                 Barcode { barcode }
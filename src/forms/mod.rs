@@ -2,6 +2,7 @@ mod barcodes;
 mod buttons;
 mod colours;
 mod dialog;
+mod dirty;
 mod errors;
 mod fields;
 mod saving;
@@ -14,12 +15,16 @@ pub use buttons::{
 };
 pub use colours::Colour;
 pub use dialog::Dialog;
+pub use dirty::{UnsavedChanges, use_dirty};
 pub use errors::{EditError, ValidationError};
 pub use fields::{
-    InputBoolean, InputColour, InputConsumable, InputConsumableUnitType, InputConsumptionType,
-    InputConsumptionTypeMaybe, InputDateTime, InputDuration, InputExerciseCalories,
-    InputExerciseRpe, InputExerciseType, InputNumber, InputOptionDateTimeUtc, InputPassword,
-    InputPooBristolType, InputString, InputSymptomIntensity, InputTextArea, InputUrgency,
+    ColourButton, FieldError, FieldWarning, FormErrorSummary, InputBoolean, InputColour,
+    InputConsumable, InputConsumableUnitType, InputConsumptionRoute, InputConsumptionType,
+    InputConsumptionTypeMaybe, InputDate, InputDateFormat, InputDateTime, InputDistanceUnit,
+    InputDuration, InputExerciseCalories, InputExerciseRpe, InputExerciseType, InputGlucoseUnit,
+    InputLandingDate, InputLocale, InputMedication, InputNumber, InputOptionDateTimeUtc,
+    InputPassword, InputPooBristolType, InputPooQuantity, InputQuantity, InputString,
+    InputSymptomIntensity, InputTextArea, InputTheme, InputUrgency, InputVolumePresets,
 };
 pub use saving::MyForm;
 pub use saving::Saving;
@@ -28,14 +33,23 @@ pub use validation::{
     validate_brand, validate_bristol, validate_colour, validate_colour_hue,
     validate_colour_saturation, validate_colour_value, validate_comments,
     validate_consumable_millilitres, validate_consumable_quantity, validate_consumable_unit,
-    validate_consumption_type, validate_consumption_type_maybe, validate_diastolic_bp,
-    validate_distance, validate_duration, validate_email, validate_exercise_calories,
-    validate_exercise_rpe, validate_exercise_type, validate_fixed_offset_date_time,
-    validate_full_name, validate_height, validate_location, validate_maybe_date_time,
-    validate_name, validate_password, validate_poo_quantity, validate_pulse,
-    validate_symptom_extra_details, validate_symptom_intensity, validate_systolic_bp,
-    validate_urgency, validate_username, validate_waist_circumference, validate_wee_millilitres,
-    validate_weight,
+    validate_consumption_portion, validate_consumption_route, validate_consumption_type,
+    validate_consumption_type_icon, validate_consumption_type_maybe, validate_date,
+    validate_date_format, validate_destroyed, validate_diastolic_bp, validate_distance,
+    validate_distance_unit, validate_dose_unit, validate_doses_per_day, validate_duration,
+    validate_duration_rounding_minutes, validate_email, validate_exercise_calories,
+    validate_exercise_heart_rate, validate_exercise_max_heart_rate, validate_exercise_rpe,
+    validate_exercise_type, validate_fixed_offset_date_time, validate_fluid_adjustment_millilitres,
+    validate_full_name, validate_glucose_unit, validate_group_window_minutes, validate_height,
+    validate_ingredient_millilitres, validate_landing_date, validate_locale, validate_location,
+    validate_max_heart_rate, validate_maybe_date_time, validate_medication,
+    validate_medication_consumable, validate_medication_dose, validate_name,
+    validate_nutrition_value, validate_palette_colours, validate_password, validate_poo_quantity,
+    validate_pulse, validate_share_link_days, validate_symptom_extra_details,
+    validate_symptom_intensity, validate_symptom_repeat_hours, validate_symptom_repeat_times,
+    validate_systolic_bp, validate_theme, validate_timezone, validate_urgency, validate_username,
+    validate_volume_presets, validate_waist_circumference, validate_webhook_url,
+    validate_wee_millilitres, validate_weight,
 };
 
 mod values;
@@ -1,9 +1,18 @@
 use bigdecimal::BigDecimal;
-use chrono::{DateTime, FixedOffset, TimeDelta, Utc};
+use chrono::{
+    DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, TimeZone, Utc,
+};
+use chrono_tz::Tz;
 use palette::Hsv;
 use tap::Pipe;
 
-use crate::models::{Bristol, ConsumableUnit, ConsumptionType, ExerciseRpe, ExerciseType, Urgency};
+use crate::models::{
+    Bristol, Consumable, ConsumableUnit, ConsumptionRoute, ConsumptionType, DateFormat,
+    DistanceUnit, ExerciseRpe, ExerciseType, GlucoseUnit, LandingDate, Locale, MAX_SYMPTOM_REPEATS,
+    Medication, PaletteColour, Theme, Urgency, check_consumable_lifetime,
+    check_consumption_type_icon, check_fluid_adjustment_mls, check_symptom_extra_details,
+    check_webhook_url, normalize_barcode, normalize_email, parse_palette_colours,
+};
 
 use super::{FieldValue, errors::ValidationError};
 
@@ -22,7 +31,9 @@ pub fn validate_brand(str: &str) -> Result<Option<String>, ValidationError> {
 }
 
 pub fn validate_barcode(str: &str) -> Result<Option<String>, ValidationError> {
-    validate_field_value(str)
+    validate_field_value::<Option<String>>(str)?
+        .map(|barcode| normalize_barcode(&barcode).map_err(ValidationError))
+        .transpose()
 }
 
 pub fn validate_username(str: &str) -> Result<String, ValidationError> {
@@ -35,10 +46,7 @@ pub fn validate_full_name(str: &str) -> Result<String, ValidationError> {
 
 pub fn validate_email(str: &str) -> Result<String, ValidationError> {
     let str = validate_field_value::<String>(str)?;
-    if !str.contains('@') {
-        return Err(ValidationError("Email should contain @".to_string()));
-    }
-    Ok(str)
+    normalize_email(&str).map_err(ValidationError)
 }
 
 pub fn validate_password(str: &str) -> Result<String, ValidationError> {
@@ -80,23 +88,109 @@ pub fn validate_location(str: &str) -> Result<Option<String>, ValidationError> {
 }
 
 pub fn validate_distance(str: &str) -> Result<Option<bigdecimal::BigDecimal>, ValidationError> {
-    validate_field_value(str)
+    let Some(distance) =
+        validate_in_range_maybe(str, BigDecimal::from(0), BigDecimal::from(10_000))?
+    else {
+        return Ok(None);
+    };
+    let (_, scale) = distance.normalized().as_bigint_and_exponent();
+    if scale > 3 {
+        return Err(ValidationError(
+            "Distance must not have more than 3 decimal places".to_string(),
+        ));
+    }
+    Ok(Some(distance))
 }
 
 // pub fn validate_utc_date_time(str: &str) -> Result<chrono::DateTime<Utc>, ValidationError> {
 //     validate_field_value(str)
 // }
 
+/// Accepts RFC3339, which keeps its own offset, or "YYYY-MM-DD HH:MM" and
+/// "HH:MM" (today) in the user's timezone, or the browser's if they have not
+/// set one.
 pub fn validate_fixed_offset_date_time(
     str: &str,
+    timezone: Option<Tz>,
 ) -> Result<chrono::DateTime<FixedOffset>, ValidationError> {
+    match timezone {
+        Some(timezone) => {
+            let today = Utc::now().with_timezone(&timezone).date_naive();
+            parse_fixed_offset_date_time(str, &timezone, today)
+        }
+        None => parse_fixed_offset_date_time(str, &Local, Local::now().date_naive()),
+    }
+}
+
+const DATE_TIME_FORMATS: [&str; 4] = [
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%dT%H:%M:%S",
+];
+
+const TIME_FORMATS: [&str; 2] = ["%H:%M", "%H:%M:%S"];
+
+fn parse_fixed_offset_date_time<T: TimeZone>(
+    str: &str,
+    timezone: &T,
+    today: NaiveDate,
+) -> Result<DateTime<FixedOffset>, ValidationError> {
+    let err = match validate_field_value(str) {
+        Ok(dt) => return Ok(dt),
+        Err(err) => err,
+    };
+
+    let str = str.trim();
+    let naive = DATE_TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(str, format).ok())
+        .or_else(|| {
+            TIME_FORMATS
+                .iter()
+                .find_map(|format| NaiveTime::parse_from_str(str, format).ok())
+                .map(|time| today.and_time(time))
+        })
+        .ok_or(err)?;
+
+    timezone
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.fixed_offset())
+        .ok_or_else(|| ValidationError(format!("{naive} does not exist in this timezone")))
+}
+
+pub fn validate_date(str: &str) -> Result<NaiveDate, ValidationError> {
     validate_field_value(str)
 }
 
+pub fn validate_share_link_days(str: &str) -> Result<i32, ValidationError> {
+    validate_in_range(str, 1, 90)
+}
+
+pub fn validate_palette_colours(str: &str) -> Result<Vec<PaletteColour>, ValidationError> {
+    parse_palette_colours(str).map_err(ValidationError)
+}
+
+pub fn validate_webhook_url(str: &str) -> Result<String, ValidationError> {
+    check_webhook_url(str).map_err(ValidationError)
+}
+
 pub fn validate_maybe_date_time(str: &str) -> Result<Option<DateTime<Utc>>, ValidationError> {
     validate_field_value(str)
 }
 
+pub fn validate_destroyed(
+    created: &Result<Option<DateTime<Utc>>, ValidationError>,
+    destroyed: &str,
+) -> Result<Option<DateTime<Utc>>, ValidationError> {
+    let destroyed = validate_maybe_date_time(destroyed)?;
+    if let Ok(created) = created {
+        check_consumable_lifetime(*created, destroyed).map_err(ValidationError)?;
+    }
+    Ok(destroyed)
+}
+
 pub fn validate_duration(str: &str) -> Result<TimeDelta, ValidationError> {
     validate_field_value(str)
 }
@@ -105,10 +199,57 @@ pub fn validate_wee_millilitres(str: &str) -> Result<i32, ValidationError> {
     validate_in_range_exclusive(str, 0, 10_000)
 }
 
+pub fn validate_fluid_adjustment_millilitres(str: &str) -> Result<i32, ValidationError> {
+    validate_field_value::<i32>(str)?
+        .pipe(check_fluid_adjustment_mls)
+        .map_err(ValidationError)
+}
+
+pub fn validate_volume_presets(str: &str) -> Result<Vec<i32>, ValidationError> {
+    str.split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| validate_in_range_exclusive(value, 0, 10_000))
+        .collect()
+}
+
+pub fn validate_group_window_minutes(str: &str) -> Result<Option<i32>, ValidationError> {
+    validate_in_range_maybe(str, 1, 240)
+}
+
+pub fn validate_duration_rounding_minutes(str: &str) -> Result<Option<i32>, ValidationError> {
+    validate_in_range_maybe(str, 1, 1440)
+}
+
 pub fn validate_consumable_quantity(
     str: &str,
+    unit: ConsumableUnit,
 ) -> Result<Option<bigdecimal::BigDecimal>, ValidationError> {
-    validate_in_range_maybe_exclusive(str, BigDecimal::from(0), BigDecimal::from(10_000))
+    let quantity =
+        validate_in_range_maybe_exclusive(str, BigDecimal::from(0), BigDecimal::from(10_000))?;
+    if unit.is_count()
+        && let Some(quantity) = &quantity
+        && !quantity.is_integer()
+    {
+        return Err(ValidationError("Count must be a whole number".to_string()));
+    }
+    Ok(quantity)
+}
+
+/// Liquid in an ingredient, which only makes sense for units that can hold
+/// liquid.
+pub fn validate_ingredient_millilitres(
+    str: &str,
+    unit: ConsumableUnit,
+) -> Result<Option<bigdecimal::BigDecimal>, ValidationError> {
+    let liquid_mls = validate_consumable_millilitres(str)?;
+    if !unit.has_liquid() && liquid_mls.is_some() {
+        return Err(ValidationError(format!(
+            "{} ingredients do not hold liquid",
+            unit.as_title()
+        )));
+    }
+    Ok(liquid_mls)
 }
 
 pub fn validate_consumable_millilitres(
@@ -117,6 +258,17 @@ pub fn validate_consumable_millilitres(
     validate_in_range_maybe_exclusive(str, BigDecimal::from(0), BigDecimal::from(10_000))
 }
 
+/// Calories or grams of a macronutrient per nutrition basis amount.
+pub fn validate_nutrition_value(
+    str: &str,
+) -> Result<Option<bigdecimal::BigDecimal>, ValidationError> {
+    validate_in_range_maybe(str, BigDecimal::from(0), BigDecimal::from(10_000))
+}
+
+pub fn validate_consumption_portion(str: &str) -> Result<bigdecimal::BigDecimal, ValidationError> {
+    validate_in_range(str, BigDecimal::new(1.into(), 2), BigDecimal::from(10))
+}
+
 pub fn validate_consumable_unit(
     unit: Option<ConsumableUnit>,
 ) -> Result<ConsumableUnit, ValidationError> {
@@ -129,12 +281,90 @@ pub fn validate_consumption_type(
     consumption_type.ok_or_else(|| ValidationError("Consumption type is required".to_string()))
 }
 
+pub fn validate_consumption_route(
+    route: Option<ConsumptionRoute>,
+) -> Result<ConsumptionRoute, ValidationError> {
+    route.ok_or_else(|| ValidationError("Consumption type is required".to_string()))
+}
+
+pub fn validate_consumption_type_icon(str: &str) -> Result<String, ValidationError> {
+    check_consumption_type_icon(str).map_err(ValidationError)
+}
+
 pub fn validate_consumption_type_maybe(
     consumption_type: Option<ConsumptionType>,
 ) -> Result<Option<ConsumptionType>, ValidationError> {
     Ok(consumption_type)
 }
 
+pub fn validate_date_format(
+    date_format: Option<DateFormat>,
+) -> Result<Option<DateFormat>, ValidationError> {
+    Ok(date_format)
+}
+
+pub fn validate_landing_date(
+    landing_date: Option<LandingDate>,
+) -> Result<Option<LandingDate>, ValidationError> {
+    Ok(landing_date)
+}
+
+pub fn validate_distance_unit(
+    distance_unit: Option<DistanceUnit>,
+) -> Result<Option<DistanceUnit>, ValidationError> {
+    Ok(distance_unit)
+}
+
+pub fn validate_glucose_unit(
+    glucose_unit: Option<GlucoseUnit>,
+) -> Result<Option<GlucoseUnit>, ValidationError> {
+    Ok(glucose_unit)
+}
+
+pub fn validate_theme(theme: Option<Theme>) -> Result<Option<Theme>, ValidationError> {
+    Ok(theme)
+}
+
+pub fn validate_timezone(str: &str) -> Result<Option<chrono_tz::Tz>, ValidationError> {
+    let str = str.trim();
+    if str.is_empty() {
+        return Ok(None);
+    }
+    str.parse()
+        .map(Some)
+        .map_err(|_| ValidationError(format!("Unknown timezone {str}")))
+}
+
+pub fn validate_locale(locale: Option<Locale>) -> Result<Option<Locale>, ValidationError> {
+    Ok(locale)
+}
+
+pub fn validate_medication(medication: Option<Medication>) -> Result<Medication, ValidationError> {
+    medication.ok_or_else(|| ValidationError("Medication is required".to_string()))
+}
+
+pub fn validate_medication_consumable(
+    consumable: Option<Consumable>,
+) -> Result<Consumable, ValidationError> {
+    consumable.ok_or_else(|| ValidationError("Drug is required".to_string()))
+}
+
+pub fn validate_medication_dose(str: &str) -> Result<BigDecimal, ValidationError> {
+    let dose = validate_in_range_exclusive(str, BigDecimal::from(0), BigDecimal::from(100_000))?;
+    if dose == 0 {
+        return Err(ValidationError("Dose must be greater than 0".to_string()));
+    }
+    Ok(dose)
+}
+
+pub fn validate_dose_unit(str: &str) -> Result<String, ValidationError> {
+    validate_field_value(str)
+}
+
+pub fn validate_doses_per_day(str: &str) -> Result<Option<i32>, ValidationError> {
+    validate_in_range_maybe(str, 1, 24)
+}
+
 pub fn validate_exercise_type(
     exercise_type: Option<ExerciseType>,
 ) -> Result<ExerciseType, ValidationError> {
@@ -274,10 +504,26 @@ pub fn validate_pulse(str: &str) -> Result<Option<i32>, ValidationError> {
     validate_in_range_maybe(str, 30, 220)
 }
 
+/// Validate a blood glucose reading entered in `unit`, returning it in mmol/L.
 pub fn validate_blood_glucose(
     str: &str,
+    unit: GlucoseUnit,
 ) -> Result<Option<bigdecimal::BigDecimal>, ValidationError> {
-    validate_in_range_maybe(str, BigDecimal::from(0), BigDecimal::from(50))
+    let Some(value) = validate_in_range_maybe(str, BigDecimal::from(0), unit.max())? else {
+        return Ok(None);
+    };
+    let (_, scale) = value.normalized().as_bigint_and_exponent();
+    if scale > unit.scale() {
+        let message = match unit.scale() {
+            0 => format!("Blood glucose in {} must be a whole number", unit.as_unit()),
+            places => format!(
+                "Blood glucose in {} must not have more than {places} decimal place",
+                unit.as_unit()
+            ),
+        };
+        return Err(ValidationError(message));
+    }
+    Ok(Some(unit.convert_to_mmol(&value)))
 }
 
 pub fn validate_systolic_bp(str: &str) -> Result<Option<i32>, ValidationError> {
@@ -306,6 +552,27 @@ pub fn validate_exercise_calories(str: &str) -> Result<Option<i32>, ValidationEr
     validate_in_range_maybe(str, 0, 10_000)
 }
 
+pub fn validate_exercise_heart_rate(str: &str) -> Result<Option<i32>, ValidationError> {
+    validate_in_range_maybe(str, 30, 250)
+}
+
+pub fn validate_exercise_max_heart_rate(
+    str: &str,
+    avg_heart_rate: Option<i32>,
+) -> Result<Option<i32>, ValidationError> {
+    let max_heart_rate = validate_exercise_heart_rate(str)?;
+    match (avg_heart_rate, max_heart_rate) {
+        (Some(avg), Some(max)) if max < avg => Err(ValidationError(
+            "Max heart rate must not be less than the average".to_string(),
+        )),
+        _ => Ok(max_heart_rate),
+    }
+}
+
+pub fn validate_max_heart_rate(str: &str) -> Result<Option<i32>, ValidationError> {
+    validate_in_range_maybe(str, 100, 250)
+}
+
 pub fn validate_exercise_rpe(
     rpe: &Option<ExerciseRpe>,
 ) -> Result<Option<ExerciseRpe>, ValidationError> {
@@ -325,13 +592,111 @@ pub fn validate_symptom_extra_details(
         .as_ref()
         .map_err(|_rr| ValidationError("Fix symptom intensity first".to_string()))?;
 
-    match (symptom_intensity, &extra_details) {
-        (0, Some(_)) => Err(ValidationError(
-            "Extra details must be empty if symptom intensity is 0".to_string(),
-        )),
-        (x, None) if x > 0 => Err(ValidationError(
-            "Extra details must be set if symptom intensity is greater than 0".to_string(),
-        )),
-        _ => Ok(extra_details),
+    check_symptom_extra_details(symptom_intensity, extra_details.as_deref())
+        .map_err(|err| ValidationError(err.to_string()))?;
+    Ok(extra_details)
+}
+
+pub fn validate_symptom_repeat_hours(str: &str) -> Result<i32, ValidationError> {
+    validate_in_range(str, 1, 24)
+}
+
+/// One time per line, blank lines are ignored.
+pub fn validate_symptom_repeat_times(
+    str: &str,
+    timezone: Option<Tz>,
+) -> Result<Vec<DateTime<FixedOffset>>, ValidationError> {
+    let times = str
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            validate_fixed_offset_date_time(line, timezone)
+                .map_err(|err| ValidationError(format!("{line}: {err}")))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if times.is_empty() {
+        return Err(ValidationError("At least one time is required".to_string()));
+    }
+    if times.len() > MAX_SYMPTOM_REPEATS {
+        return Err(ValidationError(format!(
+            "Cannot repeat more than {MAX_SYMPTOM_REPEATS} times"
+        )));
+    }
+    Ok(times)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(str: &str) -> Result<DateTime<FixedOffset>, ValidationError> {
+        let timezone = FixedOffset::east_opt(10 * 3600).unwrap();
+        let today = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        parse_fixed_offset_date_time(str, &timezone, today)
+    }
+
+    fn rfc3339(str: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(str).unwrap()
+    }
+
+    #[test]
+    fn rfc3339_keeps_its_own_offset() {
+        assert_eq!(
+            parse("2024-05-30T08:15:00-04:00").unwrap(),
+            rfc3339("2024-05-30T08:15:00-04:00")
+        );
+    }
+
+    #[test]
+    fn date_and_time_use_timezone_offset() {
+        assert_eq!(
+            parse("2024-05-30 14:30").unwrap(),
+            rfc3339("2024-05-30T14:30:00+10:00")
+        );
+        assert_eq!(
+            parse(" 2024-05-30T14:30:15 ").unwrap(),
+            rfc3339("2024-05-30T14:30:15+10:00")
+        );
+    }
+
+    #[test]
+    fn time_only_is_today_in_timezone() {
+        assert_eq!(
+            parse("14:30").unwrap(),
+            rfc3339("2024-06-01T14:30:00+10:00")
+        );
+    }
+
+    #[test]
+    fn rejects_empty_and_invalid_input() {
+        assert!(parse("").is_err());
+        assert!(parse("yesterday").is_err());
+        assert!(parse("2024-05-30 25:00").is_err());
+    }
+
+    #[test]
+    fn rejects_time_missing_in_timezone() {
+        // Clocks went forward at 02:00 in Melbourne on this day.
+        let today = NaiveDate::from_ymd_opt(2024, 10, 6).unwrap();
+        assert!(
+            parse_fixed_offset_date_time("02:30", &chrono_tz::Australia::Melbourne, today).is_err()
+        );
+    }
+
+    #[test]
+    fn date_and_time_use_user_timezone_not_browser() {
+        // Winter in New York is UTC-5, whatever timezone the test runs in.
+        assert_eq!(
+            validate_fixed_offset_date_time("2024-01-15 09:00", Some(chrono_tz::America::New_York))
+                .unwrap(),
+            rfc3339("2024-01-15T09:00:00-05:00")
+        );
+        assert_eq!(
+            validate_fixed_offset_date_time("2024-07-15 09:00", Some(chrono_tz::America::New_York))
+                .unwrap(),
+            rfc3339("2024-07-15T09:00:00-04:00")
+        );
     }
 }
@@ -1,16 +1,19 @@
-use chrono::{DateTime, FixedOffset, Local, TimeDelta, Utc};
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, TimeDelta, Utc};
 use dioxus::prelude::*;
 use palette::RgbHue;
 use thiserror::Error;
 
 use crate::components::consumables::{ConsumableIcon, ConsumableLabel, ConsumableUnitIcon};
-use crate::components::consumptions::ConsumptionTypeIcon;
+use crate::components::consumptions::{ConsumptionRouteIcon, ConsumptionTypeIcon};
 use crate::components::events::{UrgencyIcon, UrgencyLabel};
 use crate::components::exercises::{ExerciseRpeIcon, ExerciseRpeLabel, ExerciseTypeIcon};
+use crate::components::medications::{MedicationIcon, MedicationLabel};
 use crate::components::poos::PooBristolIcon;
 use crate::components::{ElementIcon, StrIcon};
 use crate::models::{
-    Bristol, Consumable, ConsumableUnit, ConsumptionType, ExerciseRpe, ExerciseType, Urgency,
+    Bristol, Consumable, ConsumableUnit, ConsumptionRoute, ConsumptionType, DateFormat,
+    DistanceUnit, ExerciseRpe, ExerciseType, GlucoseUnit, LandingDate, Locale, Medication, Theme,
+    Urgency,
 };
 
 #[derive(Error, Debug)]
@@ -111,6 +114,26 @@ impl FieldValue for DateTime<FixedOffset> {
     }
 }
 
+impl FieldValue for NaiveDate {
+    type RawValue = String;
+    type DerefValue = str;
+
+    fn as_raw(&self) -> String {
+        self.format("%Y-%m-%d").to_string()
+    }
+
+    fn from_raw(value: &str) -> Result<Self, FieldValueError> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(FieldValueError::RequiredValue);
+        }
+        match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            Ok(date) => Ok(date),
+            Err(_) => Err(FieldValueError::InvalidValue),
+        }
+    }
+}
+
 impl FieldValue for TimeDelta {
     type RawValue = String;
     type DerefValue = str;
@@ -289,6 +312,62 @@ impl FieldLabel for ConsumptionType {
     }
 }
 
+impl FieldLabel for ConsumptionRoute {
+    fn as_label(&self) -> Element {
+        let label = self.as_title();
+        rsx! {
+            ElementIcon {
+                title: rsx! { "{label}" },
+                icon: rsx! {
+                    ConsumptionRouteIcon { route: self.clone() }
+                },
+            }
+        }
+    }
+}
+
+impl FieldLabel for DateFormat {
+    fn as_label(&self) -> Element {
+        let label = self.as_title();
+        rsx! { "{label}" }
+    }
+}
+
+impl FieldLabel for DistanceUnit {
+    fn as_label(&self) -> Element {
+        let label = self.as_title();
+        rsx! { "{label}" }
+    }
+}
+
+impl FieldLabel for GlucoseUnit {
+    fn as_label(&self) -> Element {
+        let label = self.as_title();
+        rsx! { "{label}" }
+    }
+}
+
+impl FieldLabel for Theme {
+    fn as_label(&self) -> Element {
+        let label = self.as_title();
+        rsx! { "{label}" }
+    }
+}
+
+impl FieldLabel for LandingDate {
+    fn as_label(&self) -> Element {
+        let label = self.as_title();
+        rsx! { "{label}" }
+    }
+}
+
+impl FieldLabel for Locale {
+    fn as_label(&self) -> Element {
+        let label = self.as_title();
+        rsx! { "{label}" }
+    }
+}
+
 impl FieldLabel for ConsumableUnit {
     fn as_label(&self) -> Element {
         let label = self.as_title();
@@ -366,10 +445,25 @@ impl FieldLabel for Consumable {
         rsx! {
             ElementIcon {
                 title: rsx! {
-                    ConsumableLabel { consumable: self.clone() }
+                    ConsumableLabel { consumable: self.clone(), compact: true }
+                },
+                icon: rsx! {
+                    ConsumableIcon { consumable: self.clone() }
+                },
+            }
+        }
+    }
+}
+
+impl FieldLabel for Medication {
+    fn as_label(&self) -> Element {
+        rsx! {
+            ElementIcon {
+                title: rsx! {
+                    MedicationLabel { medication: self.clone() }
                 },
                 icon: rsx! {
-                    ConsumableIcon {}
+                    MedicationIcon {}
                 },
             }
         }
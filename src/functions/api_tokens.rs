@@ -0,0 +1,73 @@
+use crate::models::{self, ApiTokenId, UserId};
+use dioxus::prelude::*;
+use dioxus_fullstack::{ServerFnError, server};
+
+#[cfg(feature = "server")]
+use super::common::{AppError, get_database_connection, get_user_id};
+
+#[server]
+pub async fn get_api_tokens(user_id: UserId) -> Result<Vec<models::ApiToken>, ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    if user_id != logged_in_user_id {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+
+    let mut conn = get_database_connection().await?;
+    crate::server::database::models::api_tokens::get_api_tokens_for_user(
+        &mut conn,
+        user_id.as_inner(),
+    )
+    .await
+    .map(|x| x.into_iter().map(|y| y.into()).collect())
+    .map_err(AppError::from)
+    .map_err(ServerFnError::from)
+}
+
+/// Create a personal access token. The secret is returned once and only its
+/// hash is stored.
+#[server]
+pub async fn create_api_token(
+    api_token: models::NewApiToken,
+) -> Result<models::CreatedApiToken, ServerFnError> {
+    use crate::server::database::models::api_tokens;
+    use crate::server::tokens::{generate_token, hash_token};
+
+    let logged_in_user_id = get_user_id().await?;
+    if api_token.user_id != logged_in_user_id {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+
+    let secret = generate_token()
+        .map(|token| format!("pn_{token}"))
+        .map_err(|err| ServerFnError::new(format!("Failed to generate token: {err}")))?;
+    let token_hash = hash_token(&secret);
+
+    let mut conn = get_database_connection().await?;
+    let new_api_token = api_tokens::NewApiToken::from_front_end(&token_hash, &api_token);
+
+    let token = api_tokens::create_api_token(&mut conn, &new_api_token)
+        .await
+        .map_err(AppError::from)?
+        .into();
+
+    Ok(models::CreatedApiToken { token, secret })
+}
+
+#[server]
+pub async fn delete_api_token(id: ApiTokenId) -> Result<(), ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    let mut conn = get_database_connection().await?;
+
+    crate::server::database::models::api_tokens::delete_api_token(
+        &mut conn,
+        id.as_inner(),
+        logged_in_user_id.as_inner(),
+    )
+    .await
+    .map_err(AppError::from)
+    .map_err(ServerFnError::from)
+}
@@ -0,0 +1,142 @@
+use crate::models::{self, ColourPaletteId, ColourPaletteKind, UserId};
+use dioxus::prelude::*;
+use dioxus_fullstack::{ServerFnError, server};
+
+#[cfg(feature = "server")]
+use super::common::{AppError, get_database_connection, get_user_id};
+
+#[server]
+pub async fn get_colour_palettes(
+    user_id: UserId,
+) -> Result<Vec<models::ColourPalette>, ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    if user_id != logged_in_user_id {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+
+    let mut conn = get_database_connection().await?;
+    crate::server::database::models::colour_palettes::get_colour_palettes_for_user(
+        &mut conn,
+        user_id.as_inner(),
+    )
+    .await
+    .map(|x| x.into_iter().map(|y| y.into()).collect())
+    .map_err(AppError::from)
+    .map_err(ServerFnError::from)
+}
+
+/// The preset colours to offer for `kind`, from the logged in user's active
+/// palette or the defaults.
+#[server]
+pub async fn get_colour_presets(
+    kind: ColourPaletteKind,
+) -> Result<Vec<models::PaletteColour>, ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+
+    let mut conn = get_database_connection().await?;
+    let palette = crate::server::database::models::colour_palettes::get_active_colour_palette(
+        &mut conn,
+        logged_in_user_id.as_inner(),
+        kind,
+    )
+    .await
+    .map_err(AppError::from)?;
+
+    let colours = palette
+        .map(|palette| palette.colours())
+        .filter(|colours| !colours.is_empty())
+        .unwrap_or_else(|| kind.default_colours());
+    Ok(colours)
+}
+
+#[server]
+pub async fn create_colour_palette(
+    palette: models::NewColourPalette,
+) -> Result<models::ColourPalette, ServerFnError> {
+    use crate::server::database::models::colour_palettes;
+
+    let logged_in_user_id = get_user_id().await?;
+    if palette.user_id != logged_in_user_id {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+    if palette.colours.is_empty() {
+        return Err(ServerFnError::new("Palette must have at least one colour"));
+    }
+
+    let mut conn = get_database_connection().await?;
+    let new_palette = colour_palettes::NewColourPalette::from_front_end(&palette);
+
+    colour_palettes::create_colour_palette(&mut conn, &new_palette)
+        .await
+        .map(|x| x.into())
+        .map_err(AppError::from)
+        .map_err(ServerFnError::from)
+}
+
+#[server]
+pub async fn update_colour_palette(
+    id: ColourPaletteId,
+    palette: models::ChangeColourPalette,
+) -> Result<models::ColourPalette, ServerFnError> {
+    use crate::server::database::models::colour_palettes;
+
+    let logged_in_user_id = get_user_id().await?;
+    if let models::MaybeSet::Set(colours) = &palette.colours
+        && colours.is_empty()
+    {
+        return Err(ServerFnError::new("Palette must have at least one colour"));
+    }
+
+    let mut conn = get_database_connection().await?;
+    let updates = colour_palettes::ChangeColourPalette::from_front_end(&palette);
+
+    colour_palettes::update_colour_palette(
+        &mut conn,
+        id.as_inner(),
+        logged_in_user_id.as_inner(),
+        &updates,
+    )
+    .await
+    .map(|x| x.into())
+    .map_err(AppError::from)
+    .map_err(ServerFnError::from)
+}
+
+/// Use palette `id` for `kind`, or the default colours if `id` is `None`.
+#[server]
+pub async fn set_active_colour_palette(
+    kind: ColourPaletteKind,
+    id: Option<ColourPaletteId>,
+) -> Result<(), ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    let mut conn = get_database_connection().await?;
+
+    crate::server::database::models::colour_palettes::set_active_colour_palette(
+        &mut conn,
+        logged_in_user_id.as_inner(),
+        kind,
+        id.map(|id| id.as_inner()),
+    )
+    .await
+    .map_err(AppError::from)
+    .map_err(ServerFnError::from)
+}
+
+#[server]
+pub async fn delete_colour_palette(id: ColourPaletteId) -> Result<(), ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    let mut conn = get_database_connection().await?;
+
+    crate::server::database::models::colour_palettes::delete_colour_palette(
+        &mut conn,
+        id.as_inner(),
+        logged_in_user_id.as_inner(),
+    )
+    .await
+    .map_err(AppError::from)
+    .map_err(ServerFnError::from)
+}
@@ -3,13 +3,17 @@ use diesel_async::pooled_connection::PoolError;
 use dioxus::prelude::*;
 use dioxus_fullstack::FullstackContext;
 use dioxus_fullstack::ServerFnError;
+use serde::Serialize;
 use tap::Pipe;
 use thiserror::Error;
+use tracing::error;
 
-use crate::models::UserId;
+use crate::models::{EntryId, UserId, WebhookAction};
 use crate::server::auth::Session;
 use crate::server::database::connection::DatabaseConnection;
 use crate::server::database::connection::DatabasePool;
+use crate::server::timeline_cache::TimelineCache;
+use crate::server::webhooks::{WebhookEvent, WebhookQueue};
 
 #[derive(Error, Debug)]
 pub enum AppError {
@@ -51,3 +55,74 @@ pub async fn assert_is_admin() -> Result<(), ServerFnError> {
         .ok_or(ServerFnError::new("Not Admin".to_string()))?;
     Ok(())
 }
+
+/// Forget the user's cached timeline days after something on them changed.
+pub async fn invalidate_timeline(user_id: UserId) {
+    if let Some(cache) = get_timeline_cache().await {
+        cache.invalidate_user(user_id.as_inner());
+    }
+}
+
+/// Forget every user's cached timeline days, after a change to something
+/// shared between users such as a consumable.
+pub async fn invalidate_all_timelines() {
+    if let Some(cache) = get_timeline_cache().await {
+        cache.clear();
+    }
+}
+
+pub async fn get_timeline_cache() -> Option<TimelineCache> {
+    let cache: Result<Extension<TimelineCache>, _> = FullstackContext::extract().await;
+    match cache {
+        Ok(Extension(cache)) => Some(cache),
+        Err(_) => {
+            error!("Timeline cache is not available");
+            None
+        }
+    }
+}
+
+/// Tell the user's webhooks that an entry was created or updated, and drop
+/// their cached timeline.
+pub async fn notify_entry_saved<T: Serialize>(
+    user_id: UserId,
+    action: WebhookAction,
+    entry_id: EntryId,
+    entry: &T,
+) {
+    invalidate_timeline(user_id).await;
+    match serde_json::to_value(entry) {
+        Ok(data) => queue_webhook_event(user_id, action, entry_id, Some(data)).await,
+        Err(err) => error!(
+            "Failed to serialize {} for webhooks: {err}",
+            entry_id.as_str()
+        ),
+    }
+}
+
+/// Tell the user's webhooks that an entry was deleted, and drop their
+/// cached timeline.
+pub async fn notify_entry_deleted(user_id: UserId, entry_id: EntryId) {
+    invalidate_timeline(user_id).await;
+    queue_webhook_event(user_id, WebhookAction::Deleted, entry_id, None).await;
+}
+
+async fn queue_webhook_event(
+    user_id: UserId,
+    action: WebhookAction,
+    entry_id: EntryId,
+    data: Option<serde_json::Value>,
+) {
+    let queue: Result<Extension<WebhookQueue>, _> = FullstackContext::extract().await;
+    let Ok(Extension(queue)) = queue else {
+        error!("Webhook queue is not available");
+        return;
+    };
+    queue.push(WebhookEvent {
+        user_id,
+        action,
+        entry_id,
+        occurred_at: chrono::Utc::now(),
+        data,
+    });
+}
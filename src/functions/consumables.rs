@@ -3,7 +3,7 @@ use dioxus::prelude::*;
 use dioxus_fullstack::{ServerFnError, server};
 
 #[cfg(feature = "server")]
-use super::common::{AppError, get_database_connection, get_user_id};
+use super::common::{AppError, get_database_connection, get_user_id, invalidate_all_timelines};
 
 #[cfg(feature = "server")]
 use tap::Pipe;
@@ -58,18 +58,23 @@ pub async fn search_consumables(
     query: String,
     include_only_created: bool,
     include_destroyed: bool,
-) -> Result<Vec<models::Consumable>, ServerFnError> {
+) -> Result<models::ConsumableSearchResults, ServerFnError> {
     let _logged_in_user_id = get_user_id().await?;
 
+    let limit = models::CONSUMABLE_SEARCH_LIMIT;
     let mut conn = get_database_connection().await?;
     crate::server::database::models::consumables::search_consumables(
         &mut conn,
         &query,
         include_only_created,
         include_destroyed,
+        limit as i64 + 1,
     )
     .await
-    .map(|x| x.into_iter().map(|y| y.into()).collect())
+    .map(|x| {
+        let consumables = x.into_iter().map(|y| y.into()).collect();
+        models::ConsumableSearchResults::new(consumables, limit)
+    })
     .map_err(AppError::from)
     .map_err(ServerFnError::from)
 }
@@ -127,6 +132,113 @@ pub async fn get_consumable_by_id(
         .map_err(ServerFnError::from)
 }
 
+#[server]
+pub async fn get_consumable_by_barcode(
+    barcode: String,
+) -> Result<Option<models::Consumable>, ServerFnError> {
+    let _logged_in_user_id = get_user_id().await?;
+
+    let mut conn = get_database_connection().await?;
+
+    crate::server::database::models::consumables::get_consumable_by_barcode(&mut conn, &barcode)
+        .await
+        .map(|x| x.map(|y| y.into()))
+        .map_err(AppError::from)
+        .map_err(ServerFnError::from)
+}
+
+/// Consumables that already have this name and brand. Duplicates are allowed,
+/// so this is only used to warn about them.
+#[server]
+pub async fn get_consumables_with_name(
+    name: String,
+    brand: Option<String>,
+) -> Result<Vec<models::Consumable>, ServerFnError> {
+    let _logged_in_user_id = get_user_id().await?;
+
+    let mut conn = get_database_connection().await?;
+
+    crate::server::database::models::consumables::get_consumables_by_name_and_brand(
+        &mut conn,
+        name.trim(),
+        brand.as_deref().map(str::trim),
+        models::DUPLICATE_NAME_LIMIT,
+    )
+    .await
+    .map(|x| x.into_iter().map(|y| y.into()).collect())
+    .map_err(AppError::from)
+    .map_err(ServerFnError::from)
+}
+
+/// Product details for a barcode from the configured product database, `None`
+/// if there is no product database or it does not know the barcode.
+#[server]
+pub async fn lookup_barcode(
+    barcode: String,
+) -> Result<Option<models::BarcodeProduct>, ServerFnError> {
+    use crate::server::barcodes::BarcodeLookup;
+    use axum::Extension;
+
+    let _logged_in_user_id = get_user_id().await?;
+    let barcode = models::normalize_barcode(&barcode).map_err(ServerFnError::new)?;
+
+    let Extension(lookup): Extension<BarcodeLookup> = FullstackContext::extract().await?;
+    lookup.lookup(&barcode).await.map_err(|err| {
+        tracing::error!("Error looking up barcode {barcode}: {err}");
+        ServerFnError::new("Barcode lookup failed")
+    })
+}
+
+/// Barcodes are stored normalized so the uniqueness check can't be bypassed
+/// by clients that send them with spaces or dashes.
+#[cfg(feature = "server")]
+fn normalize_optional_barcode(barcode: Option<&str>) -> Result<Option<String>, ServerFnError> {
+    barcode
+        .map(|barcode| models::normalize_barcode(barcode).map_err(ServerFnError::new))
+        .transpose()
+}
+
+#[cfg(feature = "server")]
+async fn check_barcode_unique(
+    conn: &mut crate::server::database::connection::DatabaseConnection,
+    barcode: Option<&str>,
+    id: Option<ConsumableId>,
+) -> Result<(), ServerFnError> {
+    let Some(barcode) = barcode else {
+        return Ok(());
+    };
+
+    let existing =
+        crate::server::database::models::consumables::get_consumable_by_barcode(conn, barcode)
+            .await
+            .map_err(AppError::from)?;
+
+    match existing {
+        Some(existing) if Some(ConsumableId::new(existing.id)) != id => Err(ServerFnError::new(
+            format!("Barcode {barcode} is already used by {}", existing.name),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// The unique index catches a barcode saved by someone else since
+/// `check_barcode_unique`, report it the same way.
+#[cfg(feature = "server")]
+fn barcode_used_error(err: diesel::result::Error, barcode: Option<&str>) -> ServerFnError {
+    use diesel::result::{DatabaseErrorKind, Error};
+
+    match (&err, barcode) {
+        (Error::DatabaseError(DatabaseErrorKind::UniqueViolation, info), Some(barcode))
+            if info.constraint_name() == Some("consumables_barcode_unique") =>
+        {
+            ServerFnError::new(format!(
+                "Barcode {barcode} is already used by another consumable"
+            ))
+        }
+        _ => AppError::from(err).into(),
+    }
+}
+
 #[server]
 pub async fn create_consumable(
     consumable: models::NewConsumable,
@@ -135,14 +247,18 @@ pub async fn create_consumable(
 
     let _logged_in_user_id = get_user_id().await?;
 
+    let mut consumable = consumable;
+    consumable.barcode = normalize_optional_barcode(consumable.barcode.as_deref())?;
+
     let mut conn = get_database_connection().await?;
+    check_barcode_unique(&mut conn, consumable.barcode.as_deref(), None).await?;
+
     let new_consumable = consumables::NewConsumable::from_front_end(&consumable);
 
     crate::server::database::models::consumables::create_consumable(&mut conn, &new_consumable)
         .await
         .map(|x| x.into())
-        .map_err(AppError::from)
-        .map_err(ServerFnError::from)
+        .map_err(|err| barcode_used_error(err, consumable.barcode.as_deref()))
 }
 
 #[server]
@@ -152,19 +268,30 @@ pub async fn update_consumable(
 ) -> Result<models::Consumable, ServerFnError> {
     let _logged_in_user_id = get_user_id().await?;
 
+    let mut consumable = consumable;
     let mut conn = get_database_connection().await?;
+    if let models::MaybeSet::Set(barcode) = &mut consumable.barcode {
+        *barcode = normalize_optional_barcode(barcode.as_deref())?;
+        check_barcode_unique(&mut conn, barcode.as_deref(), Some(id)).await?;
+    }
+
     let updates =
         crate::server::database::models::consumables::ChangeConsumable::from_front_end(&consumable);
 
-    crate::server::database::models::consumables::update_consumable(
-        &mut conn,
-        id.as_inner(),
-        &updates,
-    )
-    .await
-    .map(|x| x.into())
-    .map_err(AppError::from)
-    .map_err(ServerFnError::from)
+    let consumable: models::Consumable =
+        crate::server::database::models::consumables::update_consumable(
+            &mut conn,
+            id.as_inner(),
+            &updates,
+        )
+        .await
+        .map(|x| x.into())
+        .map_err(|err| {
+            let barcode = consumable.barcode.map_inner_deref().into_option().flatten();
+            barcode_used_error(err, barcode)
+        })?;
+    invalidate_all_timelines().await;
+    Ok(consumable)
 }
 
 #[server]
@@ -174,8 +301,9 @@ pub async fn delete_consumable(id: ConsumableId) -> Result<(), ServerFnError> {
 
     crate::server::database::models::consumables::delete_consumable(&mut conn, id.as_inner())
         .await
-        .map_err(AppError::from)
-        .map_err(ServerFnError::from)
+        .map_err(AppError::from)?;
+    invalidate_all_timelines().await;
+    Ok(())
 }
 
 #[server]
@@ -190,14 +318,16 @@ pub async fn create_nested_consumable(
     let new_nested_consumable =
         nested_consumables::NewNestedConsumable::from_front_end(&consumable);
 
-    crate::server::database::models::nested_consumables::create_nested_consumable(
-        &mut conn,
-        &new_nested_consumable,
-    )
-    .await
-    .map(|x| x.into())
-    .map_err(AppError::from)
-    .map_err(ServerFnError::from)
+    let nested: models::NestedConsumable =
+        crate::server::database::models::nested_consumables::create_nested_consumable(
+            &mut conn,
+            &new_nested_consumable,
+        )
+        .await
+        .map(|x| x.into())
+        .map_err(AppError::from)?;
+    invalidate_all_timelines().await;
+    Ok(nested)
 }
 
 #[server]
@@ -212,8 +342,9 @@ pub async fn delete_nested_consumable(id: models::NestedConsumableId) -> Result<
         consumable_id.as_inner(),
     )
     .await
-    .map_err(AppError::from)
-    .map_err(ServerFnError::from)
+    .map_err(AppError::from)?;
+    invalidate_all_timelines().await;
+    Ok(())
 }
 
 #[server]
@@ -230,14 +361,19 @@ pub async fn update_nested_consumable(
         );
     let (parent_id, consumable_id) = id.as_inner();
 
-    crate::server::database::models::nested_consumables::update_nested_consumable(
-        &mut conn,
-        parent_id.as_inner(),
-        consumable_id.as_inner(),
-        &updates,
-    )
-    .await
-    .map(|x| x.into())
-    .map_err(AppError::from)
-    .map_err(ServerFnError::from)
+    let nested: models::NestedConsumable =
+        crate::server::database::models::nested_consumables::update_nested_consumable(
+            &mut conn,
+            parent_id.as_inner(),
+            consumable_id.as_inner(),
+            &updates,
+        )
+        .await
+        .map(|x| x.into())
+        .map_err(|err| {
+            let barcode = consumable.barcode.map_inner_deref().into_option().flatten();
+            barcode_used_error(err, barcode)
+        })?;
+    invalidate_all_timelines().await;
+    Ok(nested)
 }
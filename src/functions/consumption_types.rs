@@ -0,0 +1,80 @@
+use crate::models::{self, CustomConsumptionTypeId, UserId};
+use dioxus::prelude::*;
+use dioxus_fullstack::{ServerFnError, server};
+
+#[cfg(feature = "server")]
+use super::common::{AppError, get_database_connection, get_user_id, invalidate_timeline};
+
+#[server]
+pub async fn get_consumption_types(
+    user_id: UserId,
+) -> Result<Vec<models::CustomConsumptionType>, ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    if user_id != logged_in_user_id {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+
+    let mut conn = get_database_connection().await?;
+    crate::server::database::models::consumption_types::get_consumption_types_for_user(
+        &mut conn,
+        user_id.as_inner(),
+    )
+    .await
+    .map(|x| x.into_iter().map(|y| y.into()).collect())
+    .map_err(AppError::from)
+    .map_err(ServerFnError::from)
+}
+
+#[server]
+pub async fn create_consumption_type(
+    consumption_type: models::NewCustomConsumptionType,
+) -> Result<models::CustomConsumptionType, ServerFnError> {
+    use crate::server::database::models::consumption_types;
+
+    let logged_in_user_id = get_user_id().await?;
+    if consumption_type.user_id != logged_in_user_id {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+
+    let name = consumption_type.name.trim().to_string();
+    if name.is_empty() {
+        return Err(ServerFnError::new("Name is required"));
+    }
+    let icon =
+        models::check_consumption_type_icon(&consumption_type.icon).map_err(ServerFnError::new)?;
+    let consumption_type = models::NewCustomConsumptionType {
+        name,
+        icon,
+        ..consumption_type
+    };
+
+    let mut conn = get_database_connection().await?;
+    let new = consumption_types::NewCustomConsumptionType::from_front_end(&consumption_type);
+
+    consumption_types::create_consumption_type(&mut conn, &new)
+        .await
+        .map(|x| x.into())
+        .map_err(AppError::from)
+        .map_err(ServerFnError::from)
+}
+
+/// Consumptions using the type fall back to its base type.
+#[server]
+pub async fn delete_consumption_type(id: CustomConsumptionTypeId) -> Result<(), ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    let mut conn = get_database_connection().await?;
+
+    crate::server::database::models::consumption_types::delete_consumption_type(
+        &mut conn,
+        id.as_inner(),
+        logged_in_user_id.as_inner(),
+    )
+    .await
+    .map_err(AppError::from)?;
+    invalidate_timeline(logged_in_user_id).await;
+    Ok(())
+}
@@ -7,13 +7,20 @@ use crate::models::{self, ConsumableId, ConsumptionId, UserId};
 use crate::models::{ConsumptionWithItems, MaybeSet};
 
 #[cfg(feature = "server")]
-use super::common::{AppError, get_database_connection, get_user_id};
+use crate::models::{EntryId, WebhookAction};
+
+#[cfg(feature = "server")]
+use super::common::{
+    AppError, get_database_connection, get_user_id, invalidate_timeline, notify_entry_deleted,
+    notify_entry_saved,
+};
 
 #[server]
 pub async fn get_consumptions_for_time_range(
     user_id: UserId,
     start: chrono::DateTime<chrono::Utc>,
     end: chrono::DateTime<chrono::Utc>,
+    options: models::RangeOptions,
 ) -> Result<Vec<models::ConsumptionWithItems>, ServerFnError> {
     pub fn items_to_front_end(
         items: Vec<(
@@ -45,6 +52,7 @@ pub async fn get_consumptions_for_time_range(
         user_id.as_inner(),
         start,
         end,
+        options,
     )
     .await
     .map(|x| {
@@ -96,6 +104,28 @@ pub async fn get_parent_consumables(
     .map_err(ServerFnError::from)
 }
 
+/// The most recent consumptions, newest first, that include the consumable.
+#[server]
+pub async fn get_consumptions_for_consumable(
+    consumable_id: ConsumableId,
+    limit: i64,
+) -> Result<Vec<(models::Consumption, models::ConsumptionConsumable)>, ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+
+    let limit = limit.clamp(1, 200);
+    let mut conn = get_database_connection().await?;
+    crate::server::database::models::consumptions::get_consumptions_for_consumable(
+        &mut conn,
+        logged_in_user_id.as_inner(),
+        consumable_id.as_inner(),
+        limit,
+    )
+    .await
+    .map(|x| x.into_iter().map(|(a, b)| (a.into(), b.into())).collect())
+    .map_err(AppError::from)
+    .map_err(ServerFnError::from)
+}
+
 #[server]
 pub async fn get_consumption_by_id(
     id: ConsumptionId,
@@ -121,16 +151,27 @@ pub async fn create_consumption(
 ) -> Result<models::Consumption, ServerFnError> {
     use crate::server::database::models::consumptions;
 
-    let _logged_in_user_id = get_user_id().await?;
+    let logged_in_user_id = get_user_id().await?;
 
     let mut conn = get_database_connection().await?;
     let new_consumption = consumptions::NewConsumption::from_front_end(&consumption);
 
-    crate::server::database::models::consumptions::create_consumption(&mut conn, &new_consumption)
+    let consumption: models::Consumption =
+        crate::server::database::models::consumptions::create_consumption(
+            &mut conn,
+            &new_consumption,
+        )
         .await
         .map(|x| x.into())
-        .map_err(AppError::from)
-        .map_err(ServerFnError::from)
+        .map_err(AppError::from)?;
+    notify_entry_saved(
+        logged_in_user_id,
+        WebhookAction::Created,
+        EntryId::Consumption(consumption.id),
+        &consumption,
+    )
+    .await;
+    Ok(consumption)
 }
 
 #[server]
@@ -153,15 +194,23 @@ pub async fn update_consumption(
         &consumption,
     );
 
-    crate::server::database::models::consumptions::update_consumption(
-        &mut conn,
-        id.as_inner(),
-        &updates,
+    let consumption: models::Consumption =
+        crate::server::database::models::consumptions::update_consumption(
+            &mut conn,
+            id.as_inner(),
+            &updates,
+        )
+        .await
+        .map(|x| x.into())
+        .map_err(AppError::from)?;
+    notify_entry_saved(
+        logged_in_user_id,
+        WebhookAction::Updated,
+        EntryId::Consumption(consumption.id),
+        &consumption,
     )
-    .await
-    .map(|x| x.into())
-    .map_err(AppError::from)
-    .map_err(ServerFnError::from)
+    .await;
+    Ok(consumption)
 }
 
 #[server]
@@ -175,8 +224,9 @@ pub async fn delete_consumption(id: ConsumptionId) -> Result<(), ServerFnError>
         logged_in_user_id.as_inner(),
     )
     .await
-    .map_err(AppError::from)
-    .map_err(ServerFnError::from)
+    .map_err(AppError::from)?;
+    notify_entry_deleted(logged_in_user_id, EntryId::Consumption(id)).await;
+    Ok(())
 }
 
 #[server]
@@ -185,27 +235,111 @@ pub async fn create_consumption_consumable(
 ) -> Result<models::ConsumptionConsumable, ServerFnError> {
     use crate::server::database::models::consumption_consumables;
 
-    let _logged_in_user_id = get_user_id().await?;
+    let logged_in_user_id = get_user_id().await?;
 
     let mut conn = get_database_connection().await?;
     let new_consumptions_consumable =
         consumption_consumables::NewConsumptionConsumable::from_front_end(&consumable);
 
-    crate::server::database::models::consumption_consumables::create_consumption_consumable(
+    let consumable: models::ConsumptionConsumable =
+        crate::server::database::models::consumption_consumables::create_consumption_consumable(
+            &mut conn,
+            &new_consumptions_consumable,
+        )
+        .await
+        .map(|x| x.into())
+        .map_err(AppError::from)?;
+    invalidate_timeline(logged_in_user_id).await;
+    Ok(consumable)
+}
+
+#[server]
+pub async fn create_consumption_consumables(
+    parent_id: ConsumptionId,
+    child_ids: Vec<models::ConsumableId>,
+) -> Result<Vec<models::ConsumptionConsumable>, ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+
+    let mut conn = get_database_connection().await?;
+
+    let parent = crate::server::database::models::consumptions::get_consumption_by_id(
         &mut conn,
-        &new_consumptions_consumable,
+        parent_id.as_inner(),
+        logged_in_user_id.as_inner(),
     )
     .await
-    .map(|x| x.into())
-    .map_err(AppError::from)
-    .map_err(ServerFnError::from)
+    .map_err(AppError::from)?;
+
+    if parent.is_none() {
+        return Err(ServerFnError::new("Consumption not found"));
+    }
+
+    let child_ids = child_ids.iter().map(|id| id.as_inner()).collect::<Vec<_>>();
+
+    let consumables: Vec<models::ConsumptionConsumable> =
+        crate::server::database::models::consumption_consumables::create_consumption_consumables(
+            &mut conn,
+            parent_id.as_inner(),
+            &child_ids,
+        )
+        .await
+        .map(|x| x.into_iter().map(|y| y.into()).collect())
+        .map_err(AppError::from)?;
+    invalidate_timeline(logged_in_user_id).await;
+    Ok(consumables)
+}
+
+/// Move ingredients logged against the wrong consumption to another one.
+#[server]
+pub async fn move_consumption_items(
+    from_id: ConsumptionId,
+    to_id: ConsumptionId,
+    consumable_ids: Vec<ConsumableId>,
+) -> Result<(), ServerFnError> {
+    use crate::server::database::models::consumptions::get_consumption_by_id;
+
+    let logged_in_user_id = get_user_id().await?;
+
+    if from_id == to_id {
+        return Err(ServerFnError::new(
+            "Cannot move ingredients to the same consumption",
+        ));
+    }
+
+    let mut conn = get_database_connection().await?;
+
+    for id in [from_id, to_id] {
+        let consumption =
+            get_consumption_by_id(&mut conn, id.as_inner(), logged_in_user_id.as_inner())
+                .await
+                .map_err(AppError::from)?;
+        if consumption.is_none() {
+            return Err(ServerFnError::new("Consumption not found"));
+        }
+    }
+
+    let consumable_ids = consumable_ids
+        .iter()
+        .map(|id| id.as_inner())
+        .collect::<Vec<_>>();
+
+    crate::server::database::models::consumption_consumables::move_consumption_consumables(
+        &mut conn,
+        from_id.as_inner(),
+        to_id.as_inner(),
+        &consumable_ids,
+    )
+    .await
+    .map_err(AppError::from)?;
+    invalidate_timeline(logged_in_user_id).await;
+    Ok(())
 }
 
 #[server]
 pub async fn delete_consumption_consumable(
     id: models::ConsumptionConsumableId,
 ) -> Result<(), ServerFnError> {
-    let _logged_in_user_id = get_user_id().await?;
+    let logged_in_user_id = get_user_id().await?;
     let mut conn = get_database_connection().await?;
     let (parent_id, consumable_id) = id.as_inner();
 
@@ -215,8 +349,9 @@ pub async fn delete_consumption_consumable(
         consumable_id.as_inner(),
     )
     .await
-    .map_err(AppError::from)
-    .map_err(ServerFnError::from)
+    .map_err(AppError::from)?;
+    invalidate_timeline(logged_in_user_id).await;
+    Ok(())
 }
 
 #[server]
@@ -224,7 +359,7 @@ pub async fn update_consumption_consumable(
     id: models::ConsumptionConsumableId,
     consumable: models::ChangeConsumptionConsumable,
 ) -> Result<models::ConsumptionConsumable, ServerFnError> {
-    let _logged_in_user_id = get_user_id().await?;
+    let logged_in_user_id = get_user_id().await?;
 
     let mut conn = get_database_connection().await?;
     let updates =
@@ -233,14 +368,16 @@ pub async fn update_consumption_consumable(
         );
     let (parent_id, consumable_id) = id.as_inner();
 
-    crate::server::database::models::consumption_consumables::update_consumption_consumable(
-        &mut conn,
-        parent_id.as_inner(),
-        consumable_id.as_inner(),
-        &updates,
-    )
-    .await
-    .map(|x| x.into())
-    .map_err(AppError::from)
-    .map_err(ServerFnError::from)
+    let consumable: models::ConsumptionConsumable =
+        crate::server::database::models::consumption_consumables::update_consumption_consumable(
+            &mut conn,
+            parent_id.as_inner(),
+            consumable_id.as_inner(),
+            &updates,
+        )
+        .await
+        .map(|x| x.into())
+        .map_err(AppError::from)?;
+    invalidate_timeline(logged_in_user_id).await;
+    Ok(consumable)
 }
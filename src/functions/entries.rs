@@ -0,0 +1,215 @@
+use chrono::{DateTime, Utc};
+use dioxus::prelude::*;
+use dioxus_fullstack::{ServerFnError, server};
+
+use crate::models::{self, EntryId, UserId};
+
+#[cfg(feature = "server")]
+use crate::models::ConsumptionWithItems;
+
+#[cfg(feature = "server")]
+use super::common::{AppError, get_database_connection, get_timeline_cache, get_user_id};
+
+#[cfg(feature = "server")]
+use super::{
+    consumptions::{get_child_consumables, get_consumption_by_id},
+    exercises::get_exercise_by_id,
+    fluid_adjustments::get_fluid_adjustment_by_id,
+    health_metrics::get_health_metric_by_id,
+    medication_doses::get_medication_dose_by_id,
+    notes::get_note_by_id,
+    poos::get_poo_by_id,
+    refluxs::get_reflux_by_id,
+    symptoms::get_symptom_by_id,
+    wee_urges::get_wee_urge_by_id,
+    wees::get_wee_by_id,
+};
+
+/// Fetch any kind of timeline entry. Each lookup checks the entry belongs to
+/// the logged in user.
+#[server]
+pub async fn get_entry_by_id(entry_id: EntryId) -> Result<Option<models::Entry>, ServerFnError> {
+    let entry = match entry_id {
+        EntryId::Wee(id) => get_wee_by_id(id).await?.map(Into::into),
+        EntryId::WeeUrge(id) => get_wee_urge_by_id(id).await?.map(Into::into),
+        EntryId::Poo(id) => get_poo_by_id(id).await?.map(Into::into),
+        EntryId::Consumption(id) => match get_consumption_by_id(id).await? {
+            Some(consumption) => {
+                let items = get_child_consumables(consumption.id).await?;
+                Some(ConsumptionWithItems::new(consumption, items).into())
+            }
+            None => None,
+        },
+        EntryId::Exercise(id) => get_exercise_by_id(id).await?.map(Into::into),
+        EntryId::HealthMetric(id) => get_health_metric_by_id(id).await?.map(Into::into),
+        EntryId::Symptom(id) => get_symptom_by_id(id).await?.map(Into::into),
+        EntryId::Reflux(id) => get_reflux_by_id(id).await?.map(Into::into),
+        EntryId::Note(id) => get_note_by_id(id).await?.map(Into::into),
+        EntryId::FluidAdjustment(id) => get_fluid_adjustment_by_id(id).await?.map(Into::into),
+        EntryId::Medication(id) => get_medication_dose_by_id(id).await?.map(Into::into),
+    };
+    Ok(entry)
+}
+
+/// Longest day a timeline can be asked for, allowing for the clocks going
+/// back.
+#[cfg(feature = "server")]
+const MAX_TIMELINE_DAY: chrono::TimeDelta = chrono::TimeDelta::hours(25);
+
+/// Every entry on the user's timeline for a day, as serialized JSON of a
+/// `Timeline` sorted oldest first. Days are cached on the server until
+/// something on them changes.
+///
+/// The day runs from `start` to `end`, worked out by the client so users
+/// without a timezone get the day of their browser rather than the server.
+/// Anything longer than a day is rejected.
+#[server]
+pub async fn timeline_json(
+    user_id: UserId,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<String, ServerFnError> {
+    use std::sync::Arc;
+
+    let logged_in_user_id = get_user_id().await?;
+    if user_id != logged_in_user_id {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+    if end <= start || end - start > MAX_TIMELINE_DAY {
+        return Err(ServerFnError::new("Timeline must cover a single day"));
+    }
+
+    let cache = get_timeline_cache().await;
+    if let Some(json) = cache
+        .as_ref()
+        .and_then(|cache| cache.get(user_id.as_inner(), (start, end)))
+    {
+        return Ok(json.to_string());
+    }
+    let generation = cache.as_ref().map(|cache| cache.generation());
+
+    let mut conn = get_database_connection().await?;
+    let timeline = crate::server::reports::load_timeline(&mut conn, user_id.as_inner(), start, end)
+        .await
+        .map_err(AppError::from)?;
+    let json: Arc<str> = serde_json::to_string(&timeline)
+        .map_err(|err| ServerFnError::new(format!("Failed to serialize timeline: {err}")))?
+        .into();
+
+    if let (Some(cache), Some(generation)) = (cache, generation) {
+        cache.insert(user_id.as_inner(), (start, end), generation, json.clone());
+    }
+    Ok(json.to_string())
+}
+
+/// Count the entries of every kind in each `(start, end)` window, so the
+/// timeline can point at nearby days that have something recorded.
+#[server]
+pub async fn get_entry_counts_for_range(
+    user_id: UserId,
+    windows: Vec<(DateTime<Utc>, DateTime<Utc>)>,
+) -> Result<Vec<i64>, ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    if user_id != logged_in_user_id {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+
+    let mut conn = get_database_connection().await?;
+    crate::server::database::models::stats::get_entry_counts_for_range(
+        &mut conn,
+        user_id.as_inner(),
+        &windows,
+    )
+    .await
+    .map_err(AppError::from)
+    .map_err(ServerFnError::from)
+}
+
+/// Entries of any kind whose comments mention `query`, newest first.
+#[server]
+pub async fn search_entries_by_comment(
+    user_id: UserId,
+    query: String,
+) -> Result<Vec<models::Entry>, ServerFnError> {
+    const SEARCH_LIMIT: i64 = 100;
+
+    let logged_in_user_id = get_user_id().await?;
+    if user_id != logged_in_user_id {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let ids = {
+        let mut conn = get_database_connection().await?;
+        crate::server::database::models::entries::search_entry_ids_by_comment(
+            &mut conn,
+            user_id.as_inner(),
+            query,
+            SEARCH_LIMIT,
+        )
+        .await
+        .map_err(AppError::from)?
+    };
+
+    get_entries_by_ids(ids).await
+}
+
+/// Fetch the entries for `ids`, keeping their order and skipping any that
+/// have since been deleted.
+#[cfg(feature = "server")]
+async fn get_entries_by_ids(ids: Vec<EntryId>) -> Result<Vec<models::Entry>, ServerFnError> {
+    let mut entries = Vec::with_capacity(ids.len());
+    for id in ids {
+        if let Some(entry) = get_entry_by_id(id).await? {
+            entries.push(entry);
+        }
+    }
+    Ok(entries)
+}
+
+/// A chunk of up to `limit` entries of any kind that come after `before` in
+/// the timeline, newest first, for scrolling back through the timeline. With
+/// no cursor the chunk starts at the newest entry.
+#[server]
+pub async fn get_timeline_before(
+    user_id: UserId,
+    before: Option<models::EntryCursor>,
+    limit: i64,
+) -> Result<models::EntryPage, ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    if user_id != logged_in_user_id {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+
+    let limit = limit.clamp(1, 200);
+    let cursors = {
+        let mut conn = get_database_connection().await?;
+        crate::server::database::models::entries::get_entry_cursors_after(
+            &mut conn,
+            user_id.as_inner(),
+            before,
+            limit,
+        )
+        .await
+        .map_err(AppError::from)?
+    };
+
+    // The next page starts after the last entry found, even if it has since
+    // been deleted.
+    let full = i64::try_from(cursors.len()).unwrap_or(i64::MAX) >= limit;
+    let next = if full { cursors.last().copied() } else { None };
+    let entries = get_entries_by_ids(cursors.into_iter().map(|cursor| cursor.id).collect()).await?;
+    Ok(models::EntryPage { entries, next })
+}
@@ -0,0 +1,83 @@
+use crate::models::{self, EntryTemplateId};
+use dioxus::prelude::*;
+use dioxus_fullstack::{ServerFnError, server};
+
+#[cfg(feature = "server")]
+use super::common::{AppError, get_database_connection, get_user_id};
+
+#[server]
+pub async fn get_entry_templates() -> Result<Vec<models::EntryTemplate>, ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    let mut conn = get_database_connection().await?;
+
+    crate::server::database::models::entry_templates::get_entry_templates_for_user(
+        &mut conn,
+        logged_in_user_id.as_inner(),
+    )
+    .await
+    .map(|x| x.into_iter().filter_map(|y| y.into_front_end()).collect())
+    .map_err(AppError::from)
+    .map_err(ServerFnError::from)
+}
+
+#[server]
+pub async fn create_entry_template(
+    template: models::NewEntryTemplate,
+) -> Result<models::EntryTemplate, ServerFnError> {
+    use crate::server::database::models::entry_templates;
+
+    let logged_in_user_id = get_user_id().await?;
+    if template.user_id != logged_in_user_id {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+    if template.name.trim().is_empty() {
+        return Err(ServerFnError::new("Template name cannot be empty"));
+    }
+
+    let mut conn = get_database_connection().await?;
+    let new_template = entry_templates::NewEntryTemplate::from_front_end(&template)
+        .map_err(|err| ServerFnError::new(err.to_string()))?;
+
+    let template = entry_templates::create_entry_template(&mut conn, &new_template)
+        .await
+        .map_err(AppError::from)?;
+    template
+        .into_front_end()
+        .ok_or(ServerFnError::new("Template could not be read back"))
+}
+
+/// The template's fields, to fill in a create dialog.
+#[server]
+pub async fn get_entry_template_data(
+    id: EntryTemplateId,
+) -> Result<models::EntryTemplateData, ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    let mut conn = get_database_connection().await?;
+
+    crate::server::database::models::entry_templates::get_entry_template_by_id(
+        &mut conn,
+        id.as_inner(),
+        logged_in_user_id.as_inner(),
+    )
+    .await
+    .map_err(AppError::from)?
+    .and_then(|template| template.data())
+    .ok_or(ServerFnError::new("Template not found"))
+}
+
+#[server]
+pub async fn delete_entry_template(id: EntryTemplateId) -> Result<(), ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    let mut conn = get_database_connection().await?;
+
+    crate::server::database::models::entry_templates::delete_entry_template(
+        &mut conn,
+        id.as_inner(),
+        logged_in_user_id.as_inner(),
+    )
+    .await
+    .map_err(AppError::from)
+    .map_err(ServerFnError::from)
+}
@@ -7,13 +7,19 @@ use dioxus_fullstack::{ServerFnError, server};
 use crate::models::MaybeSet;
 
 #[cfg(feature = "server")]
-use super::common::{AppError, get_database_connection, get_user_id};
+use crate::models::{EntryId, WebhookAction};
+
+#[cfg(feature = "server")]
+use super::common::{
+    AppError, get_database_connection, get_user_id, notify_entry_deleted, notify_entry_saved,
+};
 
 #[server]
 pub async fn get_exercises_for_time_range(
     user_id: UserId,
     start: DateTime<Utc>,
     end: DateTime<Utc>,
+    options: models::RangeOptions,
 ) -> Result<Vec<models::Exercise>, ServerFnError> {
     let logged_in_user_id = get_user_id().await?;
     if user_id != logged_in_user_id {
@@ -28,6 +34,7 @@ pub async fn get_exercises_for_time_range(
         user_id.as_inner(),
         start,
         end,
+        options,
     )
     .await
     .map(|x| x.into_iter().map(|y| y.into()).collect())
@@ -68,11 +75,19 @@ pub async fn create_exercise(
     let mut conn = get_database_connection().await?;
     let new_exercise = exercises::NewExercise::from_front_end(&exercise);
 
-    crate::server::database::models::exercises::create_exercise(&mut conn, &new_exercise)
-        .await
-        .map(|x| x.into())
-        .map_err(AppError::from)
-        .map_err(ServerFnError::from)
+    let exercise: models::Exercise =
+        crate::server::database::models::exercises::create_exercise(&mut conn, &new_exercise)
+            .await
+            .map(|x| x.into())
+            .map_err(AppError::from)?;
+    notify_entry_saved(
+        logged_in_user_id,
+        WebhookAction::Created,
+        EntryId::Exercise(exercise.id),
+        &exercise,
+    )
+    .await;
+    Ok(exercise)
 }
 
 #[server]
@@ -94,11 +109,22 @@ pub async fn update_exercise(
     let updates =
         crate::server::database::models::exercises::ChangeExercise::from_front_end(&exercise);
 
-    crate::server::database::models::exercises::update_exercise(&mut conn, id.as_inner(), &updates)
-        .await
-        .map(|x| x.into())
-        .map_err(AppError::from)
-        .map_err(ServerFnError::from)
+    let exercise: models::Exercise = crate::server::database::models::exercises::update_exercise(
+        &mut conn,
+        id.as_inner(),
+        &updates,
+    )
+    .await
+    .map(|x| x.into())
+    .map_err(AppError::from)?;
+    notify_entry_saved(
+        logged_in_user_id,
+        WebhookAction::Updated,
+        EntryId::Exercise(exercise.id),
+        &exercise,
+    )
+    .await;
+    Ok(exercise)
 }
 
 #[server]
@@ -112,6 +138,7 @@ pub async fn delete_exercise(id: ExerciseId) -> Result<(), ServerFnError> {
         logged_in_user_id.as_inner(),
     )
     .await
-    .map_err(AppError::from)
-    .map_err(ServerFnError::from)
+    .map_err(AppError::from)?;
+    notify_entry_deleted(logged_in_user_id, EntryId::Exercise(id)).await;
+    Ok(())
 }
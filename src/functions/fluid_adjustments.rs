@@ -0,0 +1,154 @@
+use crate::models::{self, FluidAdjustmentId, UserId};
+use chrono::{DateTime, Utc};
+use dioxus::prelude::*;
+use dioxus_fullstack::{ServerFnError, server};
+
+#[cfg(feature = "server")]
+use crate::models::{MaybeSet, check_fluid_adjustment_mls};
+
+#[cfg(feature = "server")]
+use crate::models::{EntryId, WebhookAction};
+
+#[cfg(feature = "server")]
+use super::common::{
+    AppError, get_database_connection, get_user_id, notify_entry_deleted, notify_entry_saved,
+};
+
+#[server]
+pub async fn get_fluid_adjustments_for_time_range(
+    user_id: UserId,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    options: models::RangeOptions,
+) -> Result<Vec<models::FluidAdjustment>, ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    if user_id != logged_in_user_id {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+
+    let mut conn = get_database_connection().await?;
+    crate::server::database::models::fluid_adjustments::get_fluid_adjustments_for_time_range(
+        &mut conn,
+        user_id.as_inner(),
+        start,
+        end,
+        options,
+    )
+    .await
+    .map(|x| x.into_iter().map(|y| y.into()).collect())
+    .map_err(AppError::from)
+    .map_err(ServerFnError::from)
+}
+
+#[server]
+pub async fn get_fluid_adjustment_by_id(
+    id: FluidAdjustmentId,
+) -> Result<Option<models::FluidAdjustment>, ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    let mut conn = get_database_connection().await?;
+
+    crate::server::database::models::fluid_adjustments::get_fluid_adjustment_by_id(
+        &mut conn,
+        id.as_inner(),
+        logged_in_user_id.as_inner(),
+    )
+    .await
+    .map(|x| x.map(|y| y.into()))
+    .map_err(AppError::from)
+    .map_err(ServerFnError::from)
+}
+
+#[server]
+pub async fn create_fluid_adjustment(
+    adjustment: models::NewFluidAdjustment,
+) -> Result<models::FluidAdjustment, ServerFnError> {
+    use crate::server::database::models::fluid_adjustments;
+
+    let logged_in_user_id = get_user_id().await?;
+
+    if adjustment.user_id != logged_in_user_id {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+
+    check_fluid_adjustment_mls(adjustment.mls).map_err(ServerFnError::new)?;
+
+    let mut conn = get_database_connection().await?;
+    let new_adjustment = fluid_adjustments::NewFluidAdjustment::from_front_end(&adjustment);
+
+    let fluid_adjustment: models::FluidAdjustment =
+        fluid_adjustments::create_fluid_adjustment(&mut conn, &new_adjustment)
+            .await
+            .map(|x| x.into())
+            .map_err(AppError::from)?;
+    notify_entry_saved(
+        logged_in_user_id,
+        WebhookAction::Created,
+        EntryId::FluidAdjustment(fluid_adjustment.id),
+        &fluid_adjustment,
+    )
+    .await;
+    Ok(fluid_adjustment)
+}
+
+#[server]
+pub async fn update_fluid_adjustment(
+    id: FluidAdjustmentId,
+    adjustment: models::ChangeFluidAdjustment,
+) -> Result<models::FluidAdjustment, ServerFnError> {
+    use crate::server::database::models::fluid_adjustments;
+
+    let logged_in_user_id = get_user_id().await?;
+
+    if let MaybeSet::Set(req_user_id) = adjustment.user_id
+        && logged_in_user_id != req_user_id
+    {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+
+    if let MaybeSet::Set(mls) = adjustment.mls {
+        check_fluid_adjustment_mls(mls).map_err(ServerFnError::new)?;
+    }
+
+    let mut conn = get_database_connection().await?;
+    let updates = fluid_adjustments::ChangeFluidAdjustment::from_front_end(&adjustment);
+
+    let fluid_adjustment: models::FluidAdjustment = fluid_adjustments::update_fluid_adjustment(
+        &mut conn,
+        id.as_inner(),
+        logged_in_user_id.as_inner(),
+        &updates,
+    )
+    .await
+    .map(|x| x.into())
+    .map_err(AppError::from)?;
+    notify_entry_saved(
+        logged_in_user_id,
+        WebhookAction::Updated,
+        EntryId::FluidAdjustment(fluid_adjustment.id),
+        &fluid_adjustment,
+    )
+    .await;
+    Ok(fluid_adjustment)
+}
+
+#[server]
+pub async fn delete_fluid_adjustment(id: FluidAdjustmentId) -> Result<(), ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    let mut conn = get_database_connection().await?;
+
+    crate::server::database::models::fluid_adjustments::delete_fluid_adjustment(
+        &mut conn,
+        id.as_inner(),
+        logged_in_user_id.as_inner(),
+    )
+    .await
+    .map_err(AppError::from)?;
+    notify_entry_deleted(logged_in_user_id, EntryId::FluidAdjustment(id)).await;
+    Ok(())
+}
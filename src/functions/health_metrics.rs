@@ -7,13 +7,19 @@ use dioxus_fullstack::{ServerFnError, server};
 use crate::models::MaybeSet;
 
 #[cfg(feature = "server")]
-use super::common::{AppError, get_database_connection, get_user_id};
+use crate::models::{EntryId, WebhookAction};
+
+#[cfg(feature = "server")]
+use super::common::{
+    AppError, get_database_connection, get_user_id, notify_entry_deleted, notify_entry_saved,
+};
 
 #[server]
 pub async fn get_health_metrics_for_time_range(
     user_id: UserId,
     start: DateTime<Utc>,
     end: DateTime<Utc>,
+    options: models::RangeOptions,
 ) -> Result<Vec<models::HealthMetric>, ServerFnError> {
     let logged_in_user_id = get_user_id().await?;
     if user_id != logged_in_user_id {
@@ -28,6 +34,7 @@ pub async fn get_health_metrics_for_time_range(
         user_id.as_inner(),
         start,
         end,
+        options,
     )
     .await
     .map(|x| x.into_iter().map(|y| y.into()).collect())
@@ -70,14 +77,22 @@ pub async fn create_health_metric(
     let mut conn = get_database_connection().await?;
     let new_health_metric = health_metrics::NewHealthMetric::from_front_end(&health_metric);
 
-    crate::server::database::models::health_metrics::create_health_metric(
-        &mut conn,
-        &new_health_metric,
+    let health_metric: models::HealthMetric =
+        crate::server::database::models::health_metrics::create_health_metric(
+            &mut conn,
+            &new_health_metric,
+        )
+        .await
+        .map(|x| x.into())
+        .map_err(AppError::from)?;
+    notify_entry_saved(
+        logged_in_user_id,
+        WebhookAction::Created,
+        EntryId::HealthMetric(health_metric.id),
+        &health_metric,
     )
-    .await
-    .map(|x| x.into())
-    .map_err(AppError::from)
-    .map_err(ServerFnError::from)
+    .await;
+    Ok(health_metric)
 }
 
 #[server]
@@ -101,15 +116,23 @@ pub async fn update_health_metric(
             &health_metric,
         );
 
-    crate::server::database::models::health_metrics::update_health_metric(
-        &mut conn,
-        id.as_inner(),
-        &updates,
+    let health_metric: models::HealthMetric =
+        crate::server::database::models::health_metrics::update_health_metric(
+            &mut conn,
+            id.as_inner(),
+            &updates,
+        )
+        .await
+        .map(|x| x.into())
+        .map_err(AppError::from)?;
+    notify_entry_saved(
+        logged_in_user_id,
+        WebhookAction::Updated,
+        EntryId::HealthMetric(health_metric.id),
+        &health_metric,
     )
-    .await
-    .map(|x| x.into())
-    .map_err(AppError::from)
-    .map_err(ServerFnError::from)
+    .await;
+    Ok(health_metric)
 }
 
 #[server]
@@ -123,6 +146,7 @@ pub async fn delete_health_metric(id: HealthMetricId) -> Result<(), ServerFnErro
         logged_in_user_id.as_inner(),
     )
     .await
-    .map_err(AppError::from)
-    .map_err(ServerFnError::from)
+    .map_err(AppError::from)?;
+    notify_entry_deleted(logged_in_user_id, EntryId::HealthMetric(id)).await;
+    Ok(())
 }
@@ -0,0 +1,171 @@
+use crate::models::{self, MedicationDoseId, UserId};
+use chrono::{DateTime, Utc};
+use dioxus::prelude::*;
+use dioxus_fullstack::{ServerFnError, server};
+
+#[cfg(feature = "server")]
+use crate::models::MaybeSet;
+
+#[cfg(feature = "server")]
+use crate::models::{EntryId, WebhookAction};
+
+#[cfg(feature = "server")]
+use super::common::{
+    AppError, get_database_connection, get_user_id, notify_entry_deleted, notify_entry_saved,
+};
+
+#[cfg(feature = "server")]
+async fn check_medication_owner(
+    conn: &mut crate::server::database::connection::DatabaseConnection,
+    medication_id: models::MedicationId,
+    user_id: UserId,
+) -> Result<(), ServerFnError> {
+    crate::server::database::models::medications::get_medication_by_id(
+        conn,
+        medication_id.as_inner(),
+        user_id.as_inner(),
+    )
+    .await
+    .map_err(AppError::from)?
+    .ok_or(ServerFnError::new("Cannot find medication"))?;
+    Ok(())
+}
+
+#[server]
+pub async fn get_medication_doses_for_time_range(
+    user_id: UserId,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    options: models::RangeOptions,
+) -> Result<Vec<models::MedicationDoseWithMedication>, ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    if user_id != logged_in_user_id {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+
+    let mut conn = get_database_connection().await?;
+    crate::server::database::models::medication_doses::get_medication_doses_for_time_range(
+        &mut conn,
+        user_id.as_inner(),
+        start,
+        end,
+        options,
+    )
+    .await
+    .map(|x| x.into_iter().map(|y| y.into()).collect())
+    .map_err(AppError::from)
+    .map_err(ServerFnError::from)
+}
+
+#[server]
+pub async fn get_medication_dose_by_id(
+    id: MedicationDoseId,
+) -> Result<Option<models::MedicationDoseWithMedication>, ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    let mut conn = get_database_connection().await?;
+
+    crate::server::database::models::medication_doses::get_medication_dose_by_id(
+        &mut conn,
+        id.as_inner(),
+        logged_in_user_id.as_inner(),
+    )
+    .await
+    .map(|x| x.map(|y| y.into()))
+    .map_err(AppError::from)
+    .map_err(ServerFnError::from)
+}
+
+#[server]
+pub async fn create_medication_dose(
+    dose: models::NewMedicationDose,
+) -> Result<models::MedicationDose, ServerFnError> {
+    use crate::server::database::models::medication_doses;
+
+    let logged_in_user_id = get_user_id().await?;
+
+    if dose.user_id != logged_in_user_id {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+
+    let mut conn = get_database_connection().await?;
+    check_medication_owner(&mut conn, dose.medication_id, logged_in_user_id).await?;
+
+    let new_dose = medication_doses::NewMedicationDose::from_front_end(&dose);
+
+    let dose: models::MedicationDose =
+        medication_doses::create_medication_dose(&mut conn, &new_dose)
+            .await
+            .map(|x| x.into())
+            .map_err(AppError::from)?;
+    notify_entry_saved(
+        logged_in_user_id,
+        WebhookAction::Created,
+        EntryId::Medication(dose.id),
+        &dose,
+    )
+    .await;
+    Ok(dose)
+}
+
+#[server]
+pub async fn update_medication_dose(
+    id: MedicationDoseId,
+    dose: models::ChangeMedicationDose,
+) -> Result<models::MedicationDose, ServerFnError> {
+    use crate::server::database::models::medication_doses;
+
+    let logged_in_user_id = get_user_id().await?;
+
+    if let MaybeSet::Set(req_user_id) = dose.user_id
+        && logged_in_user_id != req_user_id
+    {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+
+    let mut conn = get_database_connection().await?;
+    if let MaybeSet::Set(medication_id) = dose.medication_id {
+        check_medication_owner(&mut conn, medication_id, logged_in_user_id).await?;
+    }
+
+    let updates = medication_doses::ChangeMedicationDose::from_front_end(&dose);
+
+    let dose: models::MedicationDose = medication_doses::update_medication_dose(
+        &mut conn,
+        id.as_inner(),
+        logged_in_user_id.as_inner(),
+        &updates,
+    )
+    .await
+    .map(|x| x.into())
+    .map_err(AppError::from)?;
+    notify_entry_saved(
+        logged_in_user_id,
+        WebhookAction::Updated,
+        EntryId::Medication(dose.id),
+        &dose,
+    )
+    .await;
+    Ok(dose)
+}
+
+#[server]
+pub async fn delete_medication_dose(id: MedicationDoseId) -> Result<(), ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    let mut conn = get_database_connection().await?;
+
+    crate::server::database::models::medication_doses::delete_medication_dose(
+        &mut conn,
+        id.as_inner(),
+        logged_in_user_id.as_inner(),
+    )
+    .await
+    .map_err(AppError::from)?;
+    notify_entry_deleted(logged_in_user_id, EntryId::Medication(id)).await;
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use crate::models::{self, MedicationId};
+use dioxus::prelude::*;
+use dioxus_fullstack::{ServerFnError, server};
+
+#[cfg(feature = "server")]
+use crate::models::MaybeSet;
+
+#[cfg(feature = "server")]
+use super::common::{AppError, get_database_connection, get_user_id, invalidate_timeline};
+
+#[server]
+pub async fn get_medications(
+    include_inactive: bool,
+) -> Result<Vec<models::Medication>, ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    let mut conn = get_database_connection().await?;
+
+    crate::server::database::models::medications::get_medications(
+        &mut conn,
+        logged_in_user_id.as_inner(),
+        include_inactive,
+    )
+    .await
+    .map(|x| x.into_iter().map(|y| y.into()).collect())
+    .map_err(AppError::from)
+    .map_err(ServerFnError::from)
+}
+
+#[server]
+pub async fn get_medication_by_id(
+    id: MedicationId,
+) -> Result<Option<models::Medication>, ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    let mut conn = get_database_connection().await?;
+
+    crate::server::database::models::medications::get_medication_by_id(
+        &mut conn,
+        id.as_inner(),
+        logged_in_user_id.as_inner(),
+    )
+    .await
+    .map(|x| x.map(|y| y.into()))
+    .map_err(AppError::from)
+    .map_err(ServerFnError::from)
+}
+
+#[server]
+pub async fn create_medication(
+    medication: models::NewMedication,
+) -> Result<models::Medication, ServerFnError> {
+    use crate::server::database::models::medications;
+
+    let logged_in_user_id = get_user_id().await?;
+
+    if medication.user_id != logged_in_user_id {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+
+    let mut conn = get_database_connection().await?;
+    let new_medication = medications::NewMedication::from_front_end(&medication);
+
+    let created = medications::create_medication(&mut conn, &new_medication)
+        .await
+        .map_err(AppError::from)?;
+
+    medications::get_medication_by_id(&mut conn, created.id, logged_in_user_id.as_inner())
+        .await
+        .map_err(AppError::from)?
+        .ok_or(ServerFnError::new("Cannot find medication"))
+        .map(|x| x.into())
+}
+
+#[server]
+pub async fn update_medication(
+    id: MedicationId,
+    medication: models::ChangeMedication,
+) -> Result<models::Medication, ServerFnError> {
+    use crate::server::database::models::medications;
+
+    let logged_in_user_id = get_user_id().await?;
+
+    if let MaybeSet::Set(req_user_id) = medication.user_id
+        && logged_in_user_id != req_user_id
+    {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+
+    let mut conn = get_database_connection().await?;
+    let updates = medications::ChangeMedication::from_front_end(&medication);
+
+    medications::update_medication(
+        &mut conn,
+        id.as_inner(),
+        logged_in_user_id.as_inner(),
+        &updates,
+    )
+    .await
+    .map_err(AppError::from)?;
+    invalidate_timeline(logged_in_user_id).await;
+
+    medications::get_medication_by_id(&mut conn, id.as_inner(), logged_in_user_id.as_inner())
+        .await
+        .map_err(AppError::from)?
+        .ok_or(ServerFnError::new("Cannot find medication"))
+        .map(|x| x.into())
+}
+
+#[server]
+pub async fn delete_medication(id: MedicationId) -> Result<(), ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    let mut conn = get_database_connection().await?;
+
+    crate::server::database::models::medications::delete_medication(
+        &mut conn,
+        id.as_inner(),
+        logged_in_user_id.as_inner(),
+    )
+    .await
+    .map_err(AppError::from)?;
+    invalidate_timeline(logged_in_user_id).await;
+    Ok(())
+}
@@ -7,13 +7,19 @@ use dioxus_fullstack::{ServerFnError, server};
 use crate::models::MaybeSet;
 
 #[cfg(feature = "server")]
-use super::common::{AppError, get_database_connection, get_user_id};
+use crate::models::{EntryId, WebhookAction};
+
+#[cfg(feature = "server")]
+use super::common::{
+    AppError, get_database_connection, get_user_id, notify_entry_deleted, notify_entry_saved,
+};
 
 #[server]
 pub async fn get_notes_for_time_range(
     user_id: UserId,
     start: DateTime<Utc>,
     end: DateTime<Utc>,
+    options: models::RangeOptions,
 ) -> Result<Vec<models::Note>, ServerFnError> {
     let logged_in_user_id = get_user_id().await?;
     if user_id != logged_in_user_id {
@@ -28,6 +34,7 @@ pub async fn get_notes_for_time_range(
         user_id.as_inner(),
         start,
         end,
+        options,
     )
     .await
     .map(|x| x.into_iter().map(|y| y.into()).collect())
@@ -66,11 +73,19 @@ pub async fn create_note(note: models::NewNote) -> Result<models::Note, ServerFn
     let mut conn = get_database_connection().await?;
     let new_note = notes::NewNote::from_front_end(&note);
 
-    crate::server::database::models::notes::create_note(&mut conn, &new_note)
-        .await
-        .map(|x| x.into())
-        .map_err(AppError::from)
-        .map_err(ServerFnError::from)
+    let note: models::Note =
+        crate::server::database::models::notes::create_note(&mut conn, &new_note)
+            .await
+            .map(|x| x.into())
+            .map_err(AppError::from)?;
+    notify_entry_saved(
+        logged_in_user_id,
+        WebhookAction::Created,
+        EntryId::Note(note.id),
+        &note,
+    )
+    .await;
+    Ok(note)
 }
 
 #[server]
@@ -91,11 +106,19 @@ pub async fn update_note(
     let mut conn = get_database_connection().await?;
     let updates = crate::server::database::models::notes::ChangeNote::from_front_end(&note);
 
-    crate::server::database::models::notes::update_note(&mut conn, id.as_inner(), &updates)
-        .await
-        .map(|x| x.into())
-        .map_err(AppError::from)
-        .map_err(ServerFnError::from)
+    let note: models::Note =
+        crate::server::database::models::notes::update_note(&mut conn, id.as_inner(), &updates)
+            .await
+            .map(|x| x.into())
+            .map_err(AppError::from)?;
+    notify_entry_saved(
+        logged_in_user_id,
+        WebhookAction::Updated,
+        EntryId::Note(note.id),
+        &note,
+    )
+    .await;
+    Ok(note)
 }
 
 #[server]
@@ -109,6 +132,7 @@ pub async fn delete_note(id: NoteId) -> Result<(), ServerFnError> {
         logged_in_user_id.as_inner(),
     )
     .await
-    .map_err(AppError::from)
-    .map_err(ServerFnError::from)
+    .map_err(AppError::from)?;
+    notify_entry_deleted(logged_in_user_id, EntryId::Note(id)).await;
+    Ok(())
 }
@@ -7,13 +7,19 @@ use dioxus_fullstack::{ServerFnError, server};
 use crate::models::MaybeSet;
 
 #[cfg(feature = "server")]
-use super::common::{AppError, get_database_connection, get_user_id};
+use crate::models::{EntryId, WebhookAction};
+
+#[cfg(feature = "server")]
+use super::common::{
+    AppError, get_database_connection, get_user_id, notify_entry_deleted, notify_entry_saved,
+};
 
 #[server]
 pub async fn get_poos_for_time_range(
     user_id: UserId,
     start: DateTime<Utc>,
     end: DateTime<Utc>,
+    options: models::RangeOptions,
 ) -> Result<Vec<models::Poo>, ServerFnError> {
     use dioxus_fullstack::ServerFnError;
 
@@ -31,6 +37,7 @@ pub async fn get_poos_for_time_range(
         logged_in_user_id.as_inner(),
         start,
         end,
+        options,
     )
     .await
     .map(|x| x.into_iter().map(|y| y.into()).collect())
@@ -54,6 +61,33 @@ pub async fn get_poo_by_id(id: PooId) -> Result<Option<models::Poo>, ServerFnErr
     .map_err(ServerFnError::from)
 }
 
+/// A poo saved moments ago that this one looks like an accidental copy of.
+#[server]
+pub async fn find_duplicate_poo(poo: models::NewPoo) -> Result<Option<models::Poo>, ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+
+    if poo.user_id != logged_in_user_id {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+
+    let now = Utc::now();
+    let mut conn = get_database_connection().await?;
+    let recent = crate::server::database::models::poos::get_poos_created_since(
+        &mut conn,
+        logged_in_user_id.as_inner(),
+        now - models::duplicate_entry_window(),
+    )
+    .await
+    .map_err(AppError::from)?;
+
+    Ok(recent
+        .into_iter()
+        .map(models::Poo::from)
+        .find(|existing| poo.is_duplicate_of(existing, now)))
+}
+
 #[server]
 pub async fn create_poo(poo: models::NewPoo) -> Result<models::Poo, ServerFnError> {
     use crate::server::database::models::poos;
@@ -69,11 +103,18 @@ pub async fn create_poo(poo: models::NewPoo) -> Result<models::Poo, ServerFnErro
     let mut conn = get_database_connection().await?;
     let new_poo = poos::NewPoo::from_front_end(&poo);
 
-    crate::server::database::models::poos::create_poo(&mut conn, new_poo)
+    let poo: models::Poo = crate::server::database::models::poos::create_poo(&mut conn, new_poo)
         .await
         .map(|x| x.into())
-        .map_err(AppError::from)
-        .map_err(ServerFnError::from)
+        .map_err(AppError::from)?;
+    notify_entry_saved(
+        logged_in_user_id,
+        WebhookAction::Created,
+        EntryId::Poo(poo.id),
+        &poo,
+    )
+    .await;
+    Ok(poo)
 }
 
 #[server]
@@ -91,11 +132,19 @@ pub async fn update_poo(id: PooId, poo: models::ChangePoo) -> Result<models::Poo
     let mut conn = get_database_connection().await?;
     let updates = crate::server::database::models::poos::ChangePoo::from_front_end(&poo);
 
-    crate::server::database::models::poos::update_poo(&mut conn, id.as_inner(), updates)
-        .await
-        .map(|x| x.into())
-        .map_err(AppError::from)
-        .map_err(ServerFnError::from)
+    let poo: models::Poo =
+        crate::server::database::models::poos::update_poo(&mut conn, id.as_inner(), updates)
+            .await
+            .map(|x| x.into())
+            .map_err(AppError::from)?;
+    notify_entry_saved(
+        logged_in_user_id,
+        WebhookAction::Updated,
+        EntryId::Poo(poo.id),
+        &poo,
+    )
+    .await;
+    Ok(poo)
 }
 
 #[server]
@@ -109,6 +158,7 @@ pub async fn delete_poo(id: PooId) -> Result<(), ServerFnError> {
         logged_in_user_id.as_inner(),
     )
     .await
-    .map_err(AppError::from)
-    .map_err(ServerFnError::from)
+    .map_err(AppError::from)?;
+    notify_entry_deleted(logged_in_user_id, EntryId::Poo(id)).await;
+    Ok(())
 }
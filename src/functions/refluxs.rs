@@ -7,13 +7,19 @@ use dioxus_fullstack::{ServerFnError, server};
 use crate::models::MaybeSet;
 
 #[cfg(feature = "server")]
-use super::common::{AppError, get_database_connection, get_user_id};
+use crate::models::{EntryId, WebhookAction};
+
+#[cfg(feature = "server")]
+use super::common::{
+    AppError, get_database_connection, get_user_id, notify_entry_deleted, notify_entry_saved,
+};
 
 #[server]
 pub async fn get_refluxs_for_time_range(
     user_id: UserId,
     start: DateTime<Utc>,
     end: DateTime<Utc>,
+    options: models::RangeOptions,
 ) -> Result<Vec<models::Reflux>, ServerFnError> {
     use dioxus_fullstack::ServerFnError;
 
@@ -30,6 +36,7 @@ pub async fn get_refluxs_for_time_range(
         user_id.as_inner(),
         start,
         end,
+        options,
     )
     .await
     .map(|x| x.into_iter().map(|y| y.into()).collect())
@@ -68,11 +75,19 @@ pub async fn create_reflux(reflux: models::NewReflux) -> Result<models::Reflux,
     let mut conn = get_database_connection().await?;
     let new_reflux = refluxs::NewReflux::from_front_end(&reflux);
 
-    crate::server::database::models::refluxs::create_reflux(&mut conn, &new_reflux)
-        .await
-        .map(|x| x.into())
-        .map_err(AppError::from)
-        .map_err(ServerFnError::from)
+    let reflux: models::Reflux =
+        crate::server::database::models::refluxs::create_reflux(&mut conn, &new_reflux)
+            .await
+            .map(|x| x.into())
+            .map_err(AppError::from)?;
+    notify_entry_saved(
+        logged_in_user_id,
+        WebhookAction::Created,
+        EntryId::Reflux(reflux.id),
+        &reflux,
+    )
+    .await;
+    Ok(reflux)
 }
 
 #[server]
@@ -93,11 +108,19 @@ pub async fn update_reflux(
     let mut conn = get_database_connection().await?;
     let updates = crate::server::database::models::refluxs::ChangeReflux::from_front_end(&reflux);
 
-    crate::server::database::models::refluxs::update_reflux(&mut conn, id.as_inner(), &updates)
-        .await
-        .map(|x| x.into())
-        .map_err(AppError::from)
-        .map_err(ServerFnError::from)
+    let reflux: models::Reflux =
+        crate::server::database::models::refluxs::update_reflux(&mut conn, id.as_inner(), &updates)
+            .await
+            .map(|x| x.into())
+            .map_err(AppError::from)?;
+    notify_entry_saved(
+        logged_in_user_id,
+        WebhookAction::Updated,
+        EntryId::Reflux(reflux.id),
+        &reflux,
+    )
+    .await;
+    Ok(reflux)
 }
 
 #[server]
@@ -111,6 +134,7 @@ pub async fn delete_reflux(id: RefluxId) -> Result<(), ServerFnError> {
         logged_in_user_id.as_inner(),
     )
     .await
-    .map_err(AppError::from)
-    .map_err(ServerFnError::from)
+    .map_err(AppError::from)?;
+    notify_entry_deleted(logged_in_user_id, EntryId::Reflux(id)).await;
+    Ok(())
 }
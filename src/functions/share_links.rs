@@ -0,0 +1,133 @@
+use crate::models::{self, ShareLinkId, UserId};
+use dioxus::prelude::*;
+use dioxus_fullstack::{ServerFnError, server};
+
+#[cfg(feature = "server")]
+use super::common::{AppError, get_database_connection, get_user_id};
+
+/// Longest range of time a single share link may cover.
+#[cfg(feature = "server")]
+const MAX_SHARE_RANGE_DAYS: i64 = 93;
+
+/// Longest time a share link may stay valid for.
+#[cfg(feature = "server")]
+const MAX_SHARE_LIFETIME_DAYS: i64 = 90;
+
+#[server]
+pub async fn get_share_links(user_id: UserId) -> Result<Vec<models::ShareLink>, ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    if user_id != logged_in_user_id {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+
+    let mut conn = get_database_connection().await?;
+    crate::server::database::models::share_links::get_share_links_for_user(
+        &mut conn,
+        user_id.as_inner(),
+    )
+    .await
+    .map(|x| x.into_iter().map(|y| y.into()).collect())
+    .map_err(AppError::from)
+    .map_err(ServerFnError::from)
+}
+
+/// Create a share link. The token is returned once and only its hash is
+/// stored.
+#[server]
+pub async fn create_share_link(
+    share_link: models::NewShareLink,
+) -> Result<models::CreatedShareLink, ServerFnError> {
+    use crate::server::database::models::share_links;
+    use crate::server::tokens::{generate_token, hash_token};
+    use chrono::{TimeDelta, Utc};
+
+    let logged_in_user_id = get_user_id().await?;
+    if share_link.user_id != logged_in_user_id {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+
+    if share_link.end <= share_link.start {
+        return Err(ServerFnError::new("End must be after start"));
+    }
+    if share_link.end - share_link.start > TimeDelta::days(MAX_SHARE_RANGE_DAYS) {
+        return Err(ServerFnError::new(format!(
+            "Shared range cannot be longer than {MAX_SHARE_RANGE_DAYS} days"
+        )));
+    }
+    let now = Utc::now();
+    if share_link.expires_at <= now {
+        return Err(ServerFnError::new("Expiry must be in the future"));
+    }
+    if share_link.expires_at - now > TimeDelta::days(MAX_SHARE_LIFETIME_DAYS) {
+        return Err(ServerFnError::new(format!(
+            "Share links cannot last longer than {MAX_SHARE_LIFETIME_DAYS} days"
+        )));
+    }
+
+    let token = generate_token()
+        .map_err(|err| ServerFnError::new(format!("Failed to generate token: {err}")))?;
+    let token_hash = hash_token(&token);
+
+    let mut conn = get_database_connection().await?;
+    let new_share_link = share_links::NewShareLink::from_front_end(&token_hash, &share_link);
+
+    let share_link = share_links::create_share_link(&mut conn, &new_share_link)
+        .await
+        .map_err(AppError::from)?
+        .into();
+
+    Ok(models::CreatedShareLink { share_link, token })
+}
+
+#[server]
+pub async fn delete_share_link(id: ShareLinkId) -> Result<(), ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    let mut conn = get_database_connection().await?;
+
+    crate::server::database::models::share_links::delete_share_link(
+        &mut conn,
+        id.as_inner(),
+        logged_in_user_id.as_inner(),
+    )
+    .await
+    .map_err(AppError::from)
+    .map_err(ServerFnError::from)
+}
+
+/// Fetch the timeline a share link points at. This does not require a login;
+/// the token is the only credential, so it only ever exposes the range it was
+/// minted for.
+#[server]
+pub async fn get_shared_timeline(token: String) -> Result<models::SharedTimeline, ServerFnError> {
+    use crate::server::database::models::share_links;
+    use crate::server::reports::load_timeline;
+    use crate::server::tokens::hash_token;
+    use chrono::Utc;
+
+    let mut conn = get_database_connection().await?;
+    let share_link =
+        share_links::get_valid_share_link_by_token_hash(&mut conn, &hash_token(&token), Utc::now())
+            .await
+            .map_err(AppError::from)?
+            .ok_or(ServerFnError::new("This link is invalid or has expired"))?;
+
+    let timeline = load_timeline(
+        &mut conn,
+        share_link.user_id,
+        share_link.start_time,
+        share_link.end_time,
+    )
+    .await
+    .map_err(AppError::from)?;
+
+    Ok(models::SharedTimeline {
+        start: share_link.start_time,
+        end: share_link.end_time,
+        expires_at: share_link.expires_at,
+        entries: timeline.iter().cloned().collect(),
+    })
+}
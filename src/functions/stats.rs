@@ -0,0 +1,213 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
+use dioxus::prelude::*;
+use dioxus_fullstack::{ServerFnError, server};
+
+use crate::models::{self, ConsumableId, DurationKind, EntryKind, UserId};
+
+#[cfg(feature = "server")]
+use super::common::{AppError, get_database_connection, get_user_id};
+
+#[server]
+pub async fn duration_stats(
+    user_id: UserId,
+    kind: DurationKind,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<models::DurationStats, ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    if user_id != logged_in_user_id {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+
+    let mut conn = get_database_connection().await?;
+    crate::server::database::models::stats::get_duration_stats(
+        &mut conn,
+        user_id.as_inner(),
+        kind,
+        start,
+        end,
+    )
+    .await
+    .map(|x| x.into())
+    .map_err(AppError::from)
+    .map_err(ServerFnError::from)
+}
+
+#[server]
+pub async fn wee_urge_latency_stats(
+    user_id: UserId,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<models::WeeUrgeLatencyStats, ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    if user_id != logged_in_user_id {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+
+    let mut conn = get_database_connection().await?;
+    crate::server::database::models::stats::get_wee_urge_latency_stats(
+        &mut conn,
+        user_id.as_inner(),
+        start,
+        end,
+    )
+    .await
+    .map(|x| x.into())
+    .map_err(AppError::from)
+    .map_err(ServerFnError::from)
+}
+
+/// The day, as shown on the timeline, of the user's most recent entry.
+#[server]
+pub async fn last_entry_date(
+    user_id: UserId,
+    timezone: Option<Tz>,
+) -> Result<Option<NaiveDate>, ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    if user_id != logged_in_user_id {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+
+    let mut conn = get_database_connection().await?;
+    crate::server::database::models::stats::get_last_entry_time(&mut conn, user_id.as_inner())
+        .await
+        .map(|x| x.map(|time| crate::dt::get_date_for_dt(time, timezone)))
+        .map_err(AppError::from)
+        .map_err(ServerFnError::from)
+}
+
+/// How many entries of a kind happened in each hour of the day, in the
+/// user's timezone.
+#[server]
+pub async fn time_of_day_histogram(
+    user_id: UserId,
+    kind: EntryKind,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<[usize; 24], ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    if user_id != logged_in_user_id {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+
+    let mut conn = get_database_connection().await?;
+    let timezone =
+        crate::server::database::models::users::get_user_by_id(&mut conn, user_id.as_inner())
+            .await
+            .map_err(AppError::from)?
+            .and_then(|user| user.timezone)
+            .and_then(|timezone| timezone.parse::<Tz>().ok());
+
+    crate::server::database::models::stats::get_entry_times(
+        &mut conn,
+        user_id.as_inner(),
+        kind,
+        start,
+        end,
+    )
+    .await
+    .map(|times| models::hour_of_day_histogram(&times, timezone))
+    .map_err(AppError::from)
+    .map_err(ServerFnError::from)
+}
+
+/// Calories and macronutrients taken in on a day, in the user's timezone.
+#[server]
+pub async fn daily_nutrition(
+    user_id: UserId,
+    date: NaiveDate,
+) -> Result<models::NutritionTotals, ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    if user_id != logged_in_user_id {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+
+    let mut conn = get_database_connection().await?;
+    let timezone =
+        crate::server::database::models::users::get_user_by_id(&mut conn, user_id.as_inner())
+            .await
+            .map_err(AppError::from)?
+            .and_then(|user| user.timezone)
+            .and_then(|timezone| timezone.parse::<Tz>().ok());
+    let (start, end) = crate::dt::get_utc_times_for_date(date, timezone)?;
+
+    crate::server::database::models::stats::get_nutrition_totals(
+        &mut conn,
+        user_id.as_inner(),
+        start,
+        end,
+    )
+    .await
+    .map_err(AppError::from)
+    .map_err(ServerFnError::from)
+}
+
+/// How much of a consumable the user had each day from `start` to `end`
+/// inclusive, in the user's timezone.
+#[server]
+pub async fn consumable_totals(
+    user_id: UserId,
+    consumable_id: ConsumableId,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<models::ConsumableTotals, ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    if user_id != logged_in_user_id {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+
+    if end < start {
+        return Err(ServerFnError::new("End date must not be before start date"));
+    }
+    if (end - start).num_days() >= models::MAX_CONSUMABLE_TOTALS_DAYS {
+        return Err(ServerFnError::new(format!(
+            "Range must be at most {} days",
+            models::MAX_CONSUMABLE_TOTALS_DAYS
+        )));
+    }
+
+    let mut conn = get_database_connection().await?;
+    let timezone =
+        crate::server::database::models::users::get_user_by_id(&mut conn, user_id.as_inner())
+            .await
+            .map_err(AppError::from)?
+            .and_then(|user| user.timezone)
+            .and_then(|timezone| timezone.parse::<Tz>().ok());
+    let (start_time, _) = crate::dt::get_utc_times_for_date(start, timezone)?;
+    let (_, end_time) = crate::dt::get_utc_times_for_date(end, timezone)?;
+
+    let items: Vec<(models::Consumption, models::ConsumptionConsumable)> =
+        crate::server::database::models::consumptions::get_consumptions_for_consumable_in_range(
+            &mut conn,
+            user_id.as_inner(),
+            consumable_id.as_inner(),
+            start_time,
+            end_time,
+        )
+        .await
+        .map_err(AppError::from)?
+        .into_iter()
+        .map(|(consumption, item)| (consumption.into(), item.into()))
+        .collect();
+
+    Ok(models::ConsumableTotals::new(
+        start,
+        end,
+        &items,
+        timezone,
+        Utc::now(),
+    ))
+}
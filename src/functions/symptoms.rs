@@ -7,13 +7,19 @@ use dioxus_fullstack::{ServerFnError, server};
 use crate::models::MaybeSet;
 
 #[cfg(feature = "server")]
-use super::common::{AppError, get_database_connection, get_user_id};
+use crate::models::{EntryId, WebhookAction};
+
+#[cfg(feature = "server")]
+use super::common::{
+    AppError, get_database_connection, get_user_id, notify_entry_deleted, notify_entry_saved,
+};
 
 #[server]
 pub async fn get_symptoms_for_time_range(
     user_id: UserId,
     start: DateTime<Utc>,
     end: DateTime<Utc>,
+    options: models::RangeOptions,
 ) -> Result<Vec<models::Symptom>, ServerFnError> {
     let logged_in_user_id = get_user_id().await?;
     if user_id != logged_in_user_id {
@@ -28,6 +34,7 @@ pub async fn get_symptoms_for_time_range(
         user_id.as_inner(),
         start,
         end,
+        options,
     )
     .await
     .map(|x| x.into_iter().map(|y| y.into()).collect())
@@ -35,6 +42,50 @@ pub async fn get_symptoms_for_time_range(
     .map_err(ServerFnError::from)
 }
 
+/// Symptoms between two times as CSV, with times in the user's timezone.
+#[server]
+pub async fn export_symptoms_csv(
+    user_id: UserId,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<String, ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    if user_id != logged_in_user_id {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+
+    let mut conn = get_database_connection().await?;
+    let timezone =
+        crate::server::database::models::users::get_user_by_id(&mut conn, user_id.as_inner())
+            .await
+            .map_err(AppError::from)?
+            .and_then(|user| user.timezone)
+            .and_then(|timezone| timezone.parse::<chrono_tz::Tz>().ok());
+
+    let symptoms: Vec<models::Symptom> =
+        crate::server::database::models::symptoms::get_symptoms_for_time_range(
+            &mut conn,
+            user_id.as_inner(),
+            start,
+            end,
+            models::RangeOptions {
+                sort: Some(models::SortDirection::Ascending),
+                limit: None,
+            },
+        )
+        .await
+        .map_err(AppError::from)?
+        .into_iter()
+        .map(|symptom| symptom.into())
+        .collect();
+
+    Ok(crate::components::symptoms::symptoms_to_csv(
+        &symptoms, timezone,
+    ))
+}
+
 #[server]
 pub async fn get_symptom_by_id(id: SymptomId) -> Result<Option<models::Symptom>, ServerFnError> {
     let logged_in_user_id = get_user_id().await?;
@@ -63,14 +114,77 @@ pub async fn create_symptom(symptom: models::NewSymptom) -> Result<models::Sympt
         ));
     }
 
+    symptom
+        .check_extra_details()
+        .map_err(|err| ServerFnError::new(err.to_string()))?;
+
     let mut conn = get_database_connection().await?;
     let new_symptom = symptoms::NewSymptom::from_front_end(&symptom);
 
-    crate::server::database::models::symptoms::create_symptom(&mut conn, &new_symptom)
-        .await
-        .map(|x| x.into())
-        .map_err(AppError::from)
-        .map_err(ServerFnError::from)
+    let symptom: models::Symptom =
+        crate::server::database::models::symptoms::create_symptom(&mut conn, &new_symptom)
+            .await
+            .map(|x| x.into())
+            .map_err(AppError::from)?;
+    notify_entry_saved(
+        logged_in_user_id,
+        WebhookAction::Created,
+        EntryId::Symptom(symptom.id),
+        &symptom,
+    )
+    .await;
+    Ok(symptom)
+}
+
+/// Create several symptom records at once, such as copies of one at other times.
+#[server]
+pub async fn create_symptoms(
+    symptoms: Vec<models::NewSymptom>,
+) -> Result<Vec<models::Symptom>, ServerFnError> {
+    use crate::server::database::models::symptoms;
+
+    let logged_in_user_id = get_user_id().await?;
+
+    if symptoms.len() > models::MAX_SYMPTOM_REPEATS {
+        return Err(ServerFnError::new(format!(
+            "Cannot create more than {} symptoms at once",
+            models::MAX_SYMPTOM_REPEATS
+        )));
+    }
+
+    for symptom in &symptoms {
+        if symptom.user_id != logged_in_user_id {
+            return Err(ServerFnError::new(
+                "User ID does not match the logged in user",
+            ));
+        }
+
+        symptom
+            .check_extra_details()
+            .map_err(|err| ServerFnError::new(err.to_string()))?;
+    }
+
+    let mut conn = get_database_connection().await?;
+    let new_symptoms = symptoms
+        .iter()
+        .map(symptoms::NewSymptom::from_front_end)
+        .collect::<Vec<_>>();
+
+    let symptoms: Vec<models::Symptom> =
+        crate::server::database::models::symptoms::create_symptoms(&mut conn, &new_symptoms)
+            .await
+            .map(|x| x.into_iter().map(|y| y.into()).collect())
+            .map_err(AppError::from)?;
+    for symptom in &symptoms {
+        notify_entry_saved(
+            logged_in_user_id,
+            WebhookAction::Created,
+            EntryId::Symptom(symptom.id),
+            symptom,
+        )
+        .await;
+    }
+    Ok(symptoms)
 }
 
 #[server]
@@ -92,11 +206,22 @@ pub async fn update_symptom(
     let updates =
         crate::server::database::models::symptoms::ChangeSymptom::from_front_end(&symptom);
 
-    crate::server::database::models::symptoms::update_symptom(&mut conn, id.as_inner(), &updates)
-        .await
-        .map(|x| x.into())
-        .map_err(AppError::from)
-        .map_err(ServerFnError::from)
+    let symptom: models::Symptom = crate::server::database::models::symptoms::update_symptom(
+        &mut conn,
+        id.as_inner(),
+        &updates,
+    )
+    .await
+    .map(|x| x.into())
+    .map_err(AppError::from)?;
+    notify_entry_saved(
+        logged_in_user_id,
+        WebhookAction::Updated,
+        EntryId::Symptom(symptom.id),
+        &symptom,
+    )
+    .await;
+    Ok(symptom)
 }
 
 #[server]
@@ -110,6 +235,7 @@ pub async fn delete_symptom(id: SymptomId) -> Result<(), ServerFnError> {
         logged_in_user_id.as_inner(),
     )
     .await
-    .map_err(AppError::from)
-    .map_err(ServerFnError::from)
+    .map_err(AppError::from)?;
+    notify_entry_deleted(logged_in_user_id, EntryId::Symptom(id)).await;
+    Ok(())
 }
@@ -4,16 +4,32 @@ use dioxus_fullstack::{ServerFnError, server};
 use crate::models::{self, UserId};
 
 #[cfg(feature = "server")]
-use super::common::{AppError, assert_is_admin, get_database_connection};
+use super::common::{
+    AppError, assert_is_admin, get_database_connection, get_user_id, invalidate_timeline,
+};
+
+/// Largest page of users that may be requested at once.
+#[cfg(feature = "server")]
+pub const MAX_USERS_PAGE_SIZE: i64 = 100;
 
 #[server]
-pub async fn get_users() -> Result<Vec<models::User>, ServerFnError> {
+pub async fn get_users_paged(
+    offset: i64,
+    limit: i64,
+    query: String,
+) -> Result<models::UserPage, ServerFnError> {
     assert_is_admin().await?;
     let mut conn = get_database_connection().await?;
 
-    crate::server::database::models::users::get_users(&mut conn)
+    let offset = offset.max(0);
+    let limit = limit.clamp(1, MAX_USERS_PAGE_SIZE);
+
+    crate::server::database::models::users::get_users_paged(&mut conn, offset, limit, query.trim())
         .await
-        .map(|x| x.into_iter().map(|y| y.into()).collect())
+        .map(|(users, total)| models::UserPage {
+            users: users.into_iter().map(|y| y.into()).collect(),
+            total,
+        })
         .map_err(AppError::from)
         .map_err(ServerFnError::from)
 }
@@ -37,8 +53,22 @@ pub async fn create_user(user: models::NewUser) -> Result<models::User, ServerFn
     assert_is_admin().await?;
     let mut conn = get_database_connection().await?;
 
-    let hashed_password = password_auth::generate_hash(&user.password);
-    let new_user: server::NewUser = server::NewUser::from_front_end(&user, &hashed_password);
+    let user = models::NewUser {
+        email: models::normalize_email(&user.email).map_err(ServerFnError::new)?,
+        ..user
+    };
+    // Users without a password can only log in with SSO.
+    let hashed_password = match (user.password.is_empty(), &user.oidc_id) {
+        (false, _) => Some(password_auth::generate_hash(&user.password)),
+        (true, Some(_)) => None,
+        (true, None) => {
+            return Err(ServerFnError::new(
+                "A password is required for users that do not log in with SSO",
+            ));
+        }
+    };
+    let new_user: server::NewUser =
+        server::NewUser::from_front_end(&user, hashed_password.as_deref());
 
     crate::server::database::models::users::create_user(&mut conn, new_user)
         .await
@@ -58,16 +88,27 @@ pub async fn update_user(
     assert_is_admin().await?;
     let mut conn = get_database_connection().await?;
 
+    let mut user = user;
+    if let models::MaybeSet::Set(email) = &user.email {
+        user.email =
+            models::MaybeSet::Set(models::normalize_email(email).map_err(ServerFnError::new)?);
+    }
+    if password.as_deref().is_some_and(str::is_empty) {
+        return Err(ServerFnError::new("Password cannot be empty"));
+    }
     let hashed_password = password.as_ref().map(password_auth::generate_hash);
 
     let updates: server::UpdateUser =
         server::UpdateUser::from_front_end(&user, hashed_password.as_deref());
 
-    crate::server::database::models::users::update_user(&mut conn, id.as_inner(), updates)
-        .await
-        .map(|x| x.into())
-        .map_err(AppError::from)
-        .map_err(ServerFnError::from)
+    let user: models::User =
+        crate::server::database::models::users::update_user(&mut conn, id.as_inner(), updates)
+            .await
+            .map(|x| x.into())
+            .map_err(AppError::from)?;
+    // The timezone decides which entries fall on each day.
+    invalidate_timeline(id).await;
+    Ok(user)
 }
 
 #[server]
@@ -75,8 +116,65 @@ pub async fn delete_user(id: UserId) -> Result<(), ServerFnError> {
     assert_is_admin().await?;
     let mut conn = get_database_connection().await?;
 
-    crate::server::database::models::users::delete_user(&mut conn, id.as_inner())
+    crate::server::database::models::users::delete_user_and_data(&mut conn, id.as_inner())
+        .await
+        .map_err(AppError::from)?;
+    invalidate_timeline(id).await;
+    Ok(())
+}
+
+#[server]
+pub async fn update_preferences(
+    preferences: models::ChangePreferences,
+) -> Result<models::User, ServerFnError> {
+    use crate::server::database::models::users as server;
+
+    let logged_in_user_id = get_user_id().await?;
+    let mut conn = get_database_connection().await?;
+
+    let updates = server::UpdatePreferences::from_front_end(&preferences);
+
+    let user: models::User = crate::server::database::models::users::update_preferences(
+        &mut conn,
+        logged_in_user_id.as_inner(),
+        updates,
+    )
+    .await
+    .map(|x| x.into())
+    .map_err(AppError::from)?;
+    // The timezone decides which entries fall on each day.
+    invalidate_timeline(logged_in_user_id).await;
+    Ok(user)
+}
+
+/// Permanently delete the logged in user and everything they recorded, then
+/// end their session. The user must type their username to confirm. There is
+/// deliberately no id argument, so this can never act on another account.
+#[server]
+pub async fn delete_own_account(confirm_text: String) -> Result<(), ServerFnError> {
+    use crate::server::auth::Session;
+
+    let mut session: Session = FullstackContext::extract().await?;
+    let user = session
+        .user
+        .clone()
+        .ok_or(ServerFnError::new("Not Logged In".to_string()))?;
+
+    if confirm_text.trim() != user.username {
+        return Err(ServerFnError::new(
+            "Confirmation text does not match your username",
+        ));
+    }
+
+    let mut conn = get_database_connection().await?;
+    crate::server::database::models::users::delete_user_and_data(&mut conn, user.id)
         .await
         .map_err(AppError::from)
-        .map_err(ServerFnError::from)
+        .map_err(ServerFnError::from)?;
+
+    session
+        .logout()
+        .await
+        .map_err(|_| ServerFnError::new("Account deleted, but logging out failed"))?;
+    Ok(())
 }
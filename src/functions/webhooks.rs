@@ -0,0 +1,73 @@
+use crate::models::{self, UserId, WebhookId};
+use dioxus::prelude::*;
+use dioxus_fullstack::{ServerFnError, server};
+
+#[cfg(feature = "server")]
+use super::common::{AppError, get_database_connection, get_user_id};
+
+#[server]
+pub async fn get_webhooks(user_id: UserId) -> Result<Vec<models::Webhook>, ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    if user_id != logged_in_user_id {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+
+    let mut conn = get_database_connection().await?;
+    crate::server::database::models::webhooks::get_webhooks_for_user(&mut conn, user_id.as_inner())
+        .await
+        .map(|x| x.into_iter().map(|y| y.into()).collect())
+        .map_err(AppError::from)
+        .map_err(ServerFnError::from)
+}
+
+/// Register a webhook. The signing secret is returned once so it can be
+/// configured on the receiving end.
+#[server]
+pub async fn create_webhook(
+    webhook: models::NewWebhook,
+) -> Result<models::CreatedWebhook, ServerFnError> {
+    use crate::server::database::models::webhooks;
+    use crate::server::tokens::generate_token;
+
+    let logged_in_user_id = get_user_id().await?;
+    if webhook.user_id != logged_in_user_id {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+
+    let url = crate::server::webhooks::check_webhook_url(&webhook.url)
+        .await
+        .map_err(ServerFnError::new)?;
+    let webhook = models::NewWebhook { url, ..webhook };
+
+    let secret = generate_token()
+        .map_err(|err| ServerFnError::new(format!("Failed to generate secret: {err}")))?;
+
+    let mut conn = get_database_connection().await?;
+    let new_webhook = webhooks::NewWebhook::from_front_end(&secret, &webhook);
+
+    let webhook = webhooks::create_webhook(&mut conn, &new_webhook)
+        .await
+        .map_err(AppError::from)?
+        .into();
+
+    Ok(models::CreatedWebhook { webhook, secret })
+}
+
+#[server]
+pub async fn delete_webhook(id: WebhookId) -> Result<(), ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    let mut conn = get_database_connection().await?;
+
+    crate::server::database::models::webhooks::delete_webhook(
+        &mut conn,
+        id.as_inner(),
+        logged_in_user_id.as_inner(),
+    )
+    .await
+    .map_err(AppError::from)
+    .map_err(ServerFnError::from)
+}
@@ -7,13 +7,19 @@ use dioxus_fullstack::{ServerFnError, server};
 use crate::models::MaybeSet;
 
 #[cfg(feature = "server")]
-use super::common::{AppError, get_database_connection, get_user_id};
+use crate::models::{EntryId, WebhookAction};
+
+#[cfg(feature = "server")]
+use super::common::{
+    AppError, get_database_connection, get_user_id, notify_entry_deleted, notify_entry_saved,
+};
 
 #[server]
 pub async fn get_wee_urges_for_time_range(
     user_id: UserId,
     start: DateTime<Utc>,
     end: DateTime<Utc>,
+    options: models::RangeOptions,
 ) -> Result<Vec<models::WeeUrge>, ServerFnError> {
     let logged_in_user_id = get_user_id().await?;
     if user_id != logged_in_user_id {
@@ -28,6 +34,34 @@ pub async fn get_wee_urges_for_time_range(
         user_id.as_inner(),
         start,
         end,
+        options,
+    )
+    .await
+    .map(|x| x.into_iter().map(|y| y.into()).collect())
+    .map_err(AppError::from)
+    .map_err(ServerFnError::from)
+}
+
+#[server]
+pub async fn get_unlinked_wee_urges(
+    user_id: UserId,
+    wee_time: DateTime<Utc>,
+    exclude_wee_id: Option<models::WeeId>,
+) -> Result<Vec<models::WeeUrge>, ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+    if user_id != logged_in_user_id {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+
+    let mut conn = get_database_connection().await?;
+    crate::server::database::models::wee_urges::get_unlinked_wee_urges(
+        &mut conn,
+        user_id.as_inner(),
+        wee_time - models::wee_urge_link_window(),
+        wee_time + chrono::TimeDelta::seconds(1),
+        exclude_wee_id.map(|id| id.as_inner()),
     )
     .await
     .map(|x| x.into_iter().map(|y| y.into()).collect())
@@ -68,11 +102,19 @@ pub async fn create_wee_urge(
     let mut conn = get_database_connection().await?;
     let new_wee_urge = wee_urges::NewWeeUrge::from_front_end(&wee_urge);
 
-    crate::server::database::models::wee_urges::create_wee_urge(&mut conn, &new_wee_urge)
-        .await
-        .map(|x| x.into())
-        .map_err(AppError::from)
-        .map_err(ServerFnError::from)
+    let wee_urge: models::WeeUrge =
+        crate::server::database::models::wee_urges::create_wee_urge(&mut conn, &new_wee_urge)
+            .await
+            .map(|x| x.into())
+            .map_err(AppError::from)?;
+    notify_entry_saved(
+        logged_in_user_id,
+        WebhookAction::Created,
+        EntryId::WeeUrge(wee_urge.id),
+        &wee_urge,
+    )
+    .await;
+    Ok(wee_urge)
 }
 
 #[server]
@@ -94,11 +136,22 @@ pub async fn update_wee_urge(
     let updates =
         crate::server::database::models::wee_urges::ChangeWeeUrge::from_front_end(&wee_urge);
 
-    crate::server::database::models::wee_urges::update_wee_urge(&mut conn, id.as_inner(), &updates)
-        .await
-        .map(|x| x.into())
-        .map_err(AppError::from)
-        .map_err(ServerFnError::from)
+    let wee_urge: models::WeeUrge = crate::server::database::models::wee_urges::update_wee_urge(
+        &mut conn,
+        id.as_inner(),
+        &updates,
+    )
+    .await
+    .map(|x| x.into())
+    .map_err(AppError::from)?;
+    notify_entry_saved(
+        logged_in_user_id,
+        WebhookAction::Updated,
+        EntryId::WeeUrge(wee_urge.id),
+        &wee_urge,
+    )
+    .await;
+    Ok(wee_urge)
 }
 
 #[server]
@@ -112,6 +165,7 @@ pub async fn delete_wee_urge(id: WeeUrgeId) -> Result<(), ServerFnError> {
         logged_in_user_id.as_inner(),
     )
     .await
-    .map_err(AppError::from)
-    .map_err(ServerFnError::from)
+    .map_err(AppError::from)?;
+    notify_entry_deleted(logged_in_user_id, EntryId::WeeUrge(id)).await;
+    Ok(())
 }
@@ -7,13 +7,44 @@ use dioxus_fullstack::{ServerFnError, server};
 use crate::models::MaybeSet;
 
 #[cfg(feature = "server")]
-use super::common::{AppError, get_database_connection, get_user_id};
+use crate::models::{EntryId, WebhookAction};
+
+#[cfg(feature = "server")]
+use super::common::{
+    AppError, get_database_connection, get_user_id, notify_entry_deleted, notify_entry_saved,
+};
+
+#[cfg(feature = "server")]
+async fn check_wee_urge_link(
+    conn: &mut crate::server::database::connection::DatabaseConnection,
+    wee_urge_id: models::WeeUrgeId,
+    user_id: UserId,
+    wee_time: chrono::DateTime<chrono::FixedOffset>,
+) -> Result<(), ServerFnError> {
+    let wee_urge: models::WeeUrge = crate::server::database::models::wee_urges::get_wee_urge_by_id(
+        conn,
+        wee_urge_id.as_inner(),
+        user_id.as_inner(),
+    )
+    .await
+    .map_err(AppError::from)?
+    .ok_or(ServerFnError::new("Cannot find wee urge"))?
+    .into();
+
+    if !models::can_link_wee_urge(wee_urge.time, wee_time) {
+        return Err(ServerFnError::new(
+            "Wee urge must be shortly before the wee",
+        ));
+    }
+    Ok(())
+}
 
 #[server]
 pub async fn get_wees_for_time_range(
     user_id: UserId,
     start: DateTime<Utc>,
     end: DateTime<Utc>,
+    options: models::RangeOptions,
 ) -> Result<Vec<models::Wee>, ServerFnError> {
     let logged_in_user_id = get_user_id().await?;
     if user_id != logged_in_user_id {
@@ -28,6 +59,7 @@ pub async fn get_wees_for_time_range(
         user_id.as_inner(),
         start,
         end,
+        options,
     )
     .await
     .map(|x| x.into_iter().map(|y| y.into()).collect())
@@ -51,6 +83,33 @@ pub async fn get_wee_by_id(id: WeeId) -> Result<Option<models::Wee>, ServerFnErr
     .map_err(ServerFnError::from)
 }
 
+/// A wee saved moments ago that this one looks like an accidental copy of.
+#[server]
+pub async fn find_duplicate_wee(wee: models::NewWee) -> Result<Option<models::Wee>, ServerFnError> {
+    let logged_in_user_id = get_user_id().await?;
+
+    if wee.user_id != logged_in_user_id {
+        return Err(ServerFnError::new(
+            "User ID does not match the logged in user",
+        ));
+    }
+
+    let now = Utc::now();
+    let mut conn = get_database_connection().await?;
+    let recent = crate::server::database::models::wees::get_wees_created_since(
+        &mut conn,
+        logged_in_user_id.as_inner(),
+        now - models::duplicate_entry_window(),
+    )
+    .await
+    .map_err(AppError::from)?;
+
+    Ok(recent
+        .into_iter()
+        .map(models::Wee::from)
+        .find(|existing| wee.is_duplicate_of(existing, now)))
+}
+
 #[server]
 pub async fn create_wee(wee: models::NewWee) -> Result<models::Wee, ServerFnError> {
     use crate::server::database::models::wees;
@@ -64,13 +123,23 @@ pub async fn create_wee(wee: models::NewWee) -> Result<models::Wee, ServerFnErro
     }
 
     let mut conn = get_database_connection().await?;
+    if let Some(wee_urge_id) = wee.wee_urge_id {
+        check_wee_urge_link(&mut conn, wee_urge_id, logged_in_user_id, wee.time).await?;
+    }
     let new_wee = wees::NewWee::from_front_end(&wee);
 
-    crate::server::database::models::wees::create_wee(&mut conn, &new_wee)
+    let wee: models::Wee = crate::server::database::models::wees::create_wee(&mut conn, &new_wee)
         .await
         .map(|x| x.into())
-        .map_err(AppError::from)
-        .map_err(ServerFnError::from)
+        .map_err(AppError::from)?;
+    notify_entry_saved(
+        logged_in_user_id,
+        WebhookAction::Created,
+        EntryId::Wee(wee.id),
+        &wee,
+    )
+    .await;
+    Ok(wee)
 }
 
 #[server]
@@ -86,13 +155,39 @@ pub async fn update_wee(id: WeeId, wee: models::ChangeWee) -> Result<models::Wee
     }
 
     let mut conn = get_database_connection().await?;
+    if let MaybeSet::Set(Some(wee_urge_id)) = wee.wee_urge_id {
+        let wee_time = match wee.time {
+            MaybeSet::Set(time) => time,
+            MaybeSet::NoChange => {
+                let existing: models::Wee = crate::server::database::models::wees::get_wee_by_id(
+                    &mut conn,
+                    id.as_inner(),
+                    logged_in_user_id.as_inner(),
+                )
+                .await
+                .map_err(AppError::from)?
+                .ok_or(ServerFnError::new("Cannot find wee"))?
+                .into();
+                existing.time
+            }
+        };
+        check_wee_urge_link(&mut conn, wee_urge_id, logged_in_user_id, wee_time).await?;
+    }
     let updates = crate::server::database::models::wees::ChangeWee::from_front_end(&wee);
 
-    crate::server::database::models::wees::update_wee(&mut conn, id.as_inner(), &updates)
-        .await
-        .map(|x| x.into())
-        .map_err(AppError::from)
-        .map_err(ServerFnError::from)
+    let wee: models::Wee =
+        crate::server::database::models::wees::update_wee(&mut conn, id.as_inner(), &updates)
+            .await
+            .map(|x| x.into())
+            .map_err(AppError::from)?;
+    notify_entry_saved(
+        logged_in_user_id,
+        WebhookAction::Updated,
+        EntryId::Wee(wee.id),
+        &wee,
+    )
+    .await;
+    Ok(wee)
 }
 
 #[server]
@@ -106,6 +201,7 @@ pub async fn delete_wee(id: WeeId) -> Result<(), ServerFnError> {
         logged_in_user_id.as_inner(),
     )
     .await
-    .map_err(AppError::from)
-    .map_err(ServerFnError::from)
+    .map_err(AppError::from)?;
+    notify_entry_deleted(logged_in_user_id, EntryId::Wee(id)).await;
+    Ok(())
 }
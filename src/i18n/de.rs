@@ -0,0 +1,97 @@
+pub const STRINGS: &[(&str, &str)] = &[
+    // Form messages
+    ("Inactive", "Inaktiv"),
+    ("Looks good!", "Sieht gut aus!"),
+    ("unsaved changes", "ungespeicherte Änderungen"),
+    (
+        "Fix these fields before saving:",
+        "Bitte diese Felder vor dem Speichern korrigieren:",
+    ),
+    ("Create", "Erstellen"),
+    ("Save", "Speichern"),
+    // Timeline
+    ("Continues from previous day", "Fortsetzung vom Vortag"),
+    (
+        "Continues into next day",
+        "Wird am nächsten Tag fortgesetzt",
+    ),
+    // Form titles
+    ("Create Consumable", "Verbrauchsartikel erstellen"),
+    ("Edit Consumable", "Verbrauchsartikel bearbeiten"),
+    ("Create Consumption", "Verzehr erstellen"),
+    ("Edit Consumption", "Verzehr bearbeiten"),
+    ("Create Exercise", "Training erstellen"),
+    ("Edit Exercise", "Training bearbeiten"),
+    ("Create Fluid Adjustment", "Flüssigkeitskorrektur erstellen"),
+    ("Edit Fluid Adjustment", "Flüssigkeitskorrektur bearbeiten"),
+    ("Create HealthMetric", "Gesundheitswert erstellen"),
+    ("Edit HealthMetric", "Gesundheitswert bearbeiten"),
+    ("Create Medication", "Medikament erstellen"),
+    ("Edit Medication", "Medikament bearbeiten"),
+    ("Create Medication Dose", "Medikamentendosis erstellen"),
+    ("Edit Medication Dose", "Medikamentendosis bearbeiten"),
+    ("Create Note", "Notiz erstellen"),
+    ("Edit Note", "Notiz bearbeiten"),
+    ("Create Poo", "Stuhlgang erstellen"),
+    ("Edit Poo", "Stuhlgang bearbeiten"),
+    ("Create Reflux", "Reflux erstellen"),
+    ("Edit Reflux", "Reflux bearbeiten"),
+    ("Create Symptom", "Symptom erstellen"),
+    ("Edit Symptom", "Symptom bearbeiten"),
+    ("Create User", "Benutzer erstellen"),
+    ("Edit User", "Benutzer bearbeiten"),
+    ("Create Wee", "Wasserlassen erstellen"),
+    ("Edit Wee", "Wasserlassen bearbeiten"),
+    ("Create Wee Urge", "Harndrang erstellen"),
+    ("Edit Wee Urge", "Harndrang bearbeiten"),
+    // Symptom categories
+    ("General / Systemic", "Allgemein / Systemisch"),
+    ("Respiratory / ENT", "Atemwege / HNO"),
+    ("Digestive / GI", "Verdauung / Magen-Darm"),
+    ("Musculoskeletal", "Bewegungsapparat"),
+    ("Head / Face / Mouth", "Kopf / Gesicht / Mund"),
+    ("Cardiovascular", "Herz-Kreislauf"),
+    ("Neurological", "Neurologisch"),
+    ("Mental Health / Sleep", "Psyche / Schlaf"),
+    // Symptoms
+    ("Appetite Loss", "Appetitlosigkeit"),
+    ("Fever", "Fieber"),
+    ("Cough", "Husten"),
+    ("Sore Throat", "Halsschmerzen"),
+    ("Nasal Symptom", "Nasenbeschwerden"),
+    (
+        "Nasal Symptom Description",
+        "Beschreibung der Nasenbeschwerden",
+    ),
+    ("Sneezing", "Niesen"),
+    ("Heart Burn", "Sodbrennen"),
+    ("Abdominal Pain", "Bauchschmerzen"),
+    ("Abdominal Pain Location", "Ort der Bauchschmerzen"),
+    ("Diarrhea", "Durchfall"),
+    ("Constipation", "Verstopfung"),
+    ("Lower Back Pain", "Schmerzen im unteren Rücken"),
+    ("Upper Back Pain", "Schmerzen im oberen Rücken"),
+    ("Neck Pain", "Nackenschmerzen"),
+    ("Shoulder Pain", "Schulterschmerzen"),
+    ("Joint Pain", "Gelenkschmerzen"),
+    ("Headache", "Kopfschmerzen"),
+    ("Nausea", "Übelkeit"),
+    ("Dizziness", "Schwindel"),
+    ("Stomach Ache", "Magenschmerzen"),
+    ("Chest Pain", "Brustschmerzen"),
+    ("Shortness of Breath", "Atemnot"),
+    ("Fatigue", "Müdigkeit"),
+    ("Anxiety", "Angst"),
+    ("Depression", "Depression"),
+    ("Insomnia", "Schlaflosigkeit"),
+    ("Hand Pain", "Handschmerzen"),
+    ("Wrist Pain", "Handgelenkschmerzen"),
+    ("Foot Pain", "Fußschmerzen"),
+    ("Dental Pain", "Zahnschmerzen"),
+    ("Dental Pain Description", "Beschreibung der Zahnschmerzen"),
+    ("Eye Pain", "Augenschmerzen"),
+    ("Ear Pain", "Ohrenschmerzen"),
+    ("Feeling Hot", "Hitzegefühl"),
+    ("Feeling Cold", "Kältegefühl"),
+    ("Feeling Thirsty", "Durstgefühl"),
+];
@@ -0,0 +1,91 @@
+//! Lightweight translation of UI strings.
+//!
+//! Strings are looked up by their English text, so English needs no table
+//! and a missing translation falls back to English.
+
+use dioxus::prelude::*;
+
+use crate::models::{DateFormat, FormatPrefs, Locale};
+
+mod de;
+
+static LOCALE: GlobalSignal<Locale> = Signal::global(Locale::default);
+static DATE_FORMAT: GlobalSignal<Option<DateFormat>> = Signal::global(|| None);
+
+fn table(locale: Locale) -> &'static [(&'static str, &'static str)] {
+    match locale {
+        Locale::En => &[],
+        Locale::De => de::STRINGS,
+    }
+}
+
+fn lookup(locale: Locale, key: &'static str) -> &'static str {
+    table(locale)
+        .iter()
+        .find(|(english, _)| *english == key)
+        .map_or(key, |(_, translated)| translated)
+}
+
+/// Translate a UI string into the current locale.
+pub fn t(key: &'static str) -> &'static str {
+    lookup(*LOCALE.read(), key)
+}
+
+/// Date and number formatting for the current locale and preferences.
+pub fn format_prefs() -> FormatPrefs {
+    FormatPrefs::new(*LOCALE.read(), *DATE_FORMAT.read())
+}
+
+/// Use the user's locale preference, otherwise the browser's languages.
+pub fn use_init_locale(
+    user_locale: Memo<Option<Locale>>,
+    user_date_format: Memo<Option<DateFormat>>,
+) {
+    use_effect(move || {
+        *DATE_FORMAT.write() = user_date_format();
+    });
+    use_effect(move || {
+        if let Some(locale) = user_locale() {
+            *LOCALE.write() = locale;
+            return;
+        }
+        spawn(async move {
+            let mut eval = document::eval("dioxus.send(navigator.languages.join(','));");
+            if let Ok(languages) = eval.recv::<String>().await {
+                *LOCALE.write() = Locale::from_accept_language(&languages).unwrap_or_default();
+            }
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_english_is_identity() {
+        assert_eq!(lookup(Locale::En, "Looks good!"), "Looks good!");
+    }
+
+    #[test]
+    fn test_translated() {
+        assert_eq!(lookup(Locale::De, "Looks good!"), "Sieht gut aus!");
+    }
+
+    #[test]
+    fn test_missing_falls_back_to_english() {
+        assert_eq!(lookup(Locale::De, "No such string"), "No such string");
+    }
+
+    #[test]
+    fn test_no_duplicate_keys() {
+        for locale in Locale::all_values() {
+            let mut seen = HashSet::new();
+            for (key, _) in table(*locale) {
+                assert!(seen.insert(key), "duplicate key {key:?} for {locale:?}");
+            }
+        }
+    }
+}
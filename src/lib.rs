@@ -1,3 +1,4 @@
+pub mod dt;
 pub mod models;
 pub mod validation;
 
@@ -3,16 +3,24 @@ use std::sync::Arc;
 use chrono::NaiveDate;
 use dioxus::prelude::*;
 
-use components::{consumables, navbar::Navbar, timeline, users};
+use components::{consumables, medications, navbar::Navbar, timeline, users};
 use dioxus_fullstack::{ServerFnError, use_server_future};
 use dioxus_router::{Routable, Router};
-use models::{User, UserId};
-use views::{ConsumableList, Home, Login, Logout, TimelineList, UserDetail, UserList, get_user};
+use functions::consumption_types::get_consumption_types;
+use functions::entry_templates::get_entry_templates;
+use models::{
+    CustomConsumptionType, EntryTemplate, GlucoseUnit, OidcFailure, SortDirection, User, UserId,
+};
+use views::{
+    ConsumableList, EntrySearch, Home, Landing, Login, LoginError, Logout, MedicationList,
+    Preferences, SharedTimeline, TimelineList, TimelineScroll, UserDetail, UserList, get_user,
+};
 
 mod components;
 mod dt;
 mod forms;
 mod functions;
+mod i18n;
 mod models;
 mod version;
 mod views;
@@ -23,21 +31,35 @@ mod server;
 #[derive(Debug, Clone, Routable, PartialEq)]
 #[rustfmt::skip]
 enum Route {
-    #[route("/login")]
-    Login {},
+    #[route("/login?:next")]
+    Login { next: String },
+    #[route("/login/error?:reason")]
+    LoginError { reason: OidcFailure },
     #[route("/logout")]
     Logout {},
+    #[route("/shared/:token")]
+    SharedTimeline { token: String },
     #[layout(Navbar)]
     #[route("/")]
     Home {  },
-    #[route("/:date?:dialog")]
-    TimelineList { date: NaiveDate, dialog: timeline::DialogReference},
-    #[route("/users?:dialog")]
-    UserList { dialog: users::ListDialogReference },
+    #[route("/landing")]
+    Landing {},
+    #[route("/:date?:dialog&:sort&:focus")]
+    TimelineList { date: NaiveDate, dialog: timeline::DialogReference, sort: SortDirection, focus: timeline::EntryFocus },
+    #[route("/scroll")]
+    TimelineScroll {},
+    #[route("/search?:query")]
+    EntrySearch { query: String },
+    #[route("/users?:dialog&:page&:search")]
+    UserList { dialog: users::ListDialogReference, page: usize, search: String },
     #[route("/users/:user_id?:dialog")]
     UserDetail { user_id: UserId, dialog: users::DetailsDialogReference },
     #[route("/consumables?:dialog")]
     ConsumableList {dialog: consumables::ListDialogReference },
+    #[route("/medications?:dialog")]
+    MedicationList { dialog: medications::ListDialogReference },
+    #[route("/preferences")]
+    Preferences {},
     #[route("/:..segments")]
     NotFound { segments: Vec<String> },
 }
@@ -60,6 +82,50 @@ fn reload_user() {
     user_resource.restart();
 }
 
+/// The logged in user's timezone, if they have set one.
+fn use_timezone() -> Option<chrono_tz::Tz> {
+    use_user().ok().flatten().and_then(|user| user.timezone)
+}
+
+/// Durations at least this long are shown rounded to the nearest minute.
+fn use_duration_rounding() -> chrono::TimeDelta {
+    const DEFAULT_DURATION_ROUNDING_MINUTES: i32 = 60;
+
+    let minutes = use_user()
+        .ok()
+        .flatten()
+        .and_then(|user| user.duration_rounding_minutes)
+        .unwrap_or(DEFAULT_DURATION_ROUNDING_MINUTES);
+    chrono::TimeDelta::minutes(minutes.into())
+}
+
+/// The unit the logged in user reads blood glucose in.
+fn use_glucose_unit() -> GlucoseUnit {
+    use_user()
+        .ok()
+        .flatten()
+        .and_then(|user| user.glucose_unit)
+        .unwrap_or(GlucoseUnit::MmolPerLitre)
+}
+
+/// The logged in user's own consumption types, empty until they load.
+fn use_consumption_types() -> Vec<CustomConsumptionType> {
+    let resource: Resource<Result<Vec<CustomConsumptionType>, ServerFnError>> = use_context();
+    match &*resource.read() {
+        Some(Ok(consumption_types)) => consumption_types.clone(),
+        Some(Err(_)) | None => Vec::new(),
+    }
+}
+
+/// The logged in user's entry templates, empty until they load.
+fn use_entry_templates() -> Vec<EntryTemplate> {
+    let resource: Resource<Result<Vec<EntryTemplate>, ServerFnError>> = use_context();
+    match &*resource.read() {
+        Some(Ok(templates)) => templates.clone(),
+        Some(Err(_)) | None => Vec::new(),
+    }
+}
+
 fn use_user() -> Result<Option<Arc<User>>, ServerFnError> {
     let user_resource: Resource<Result<Option<Arc<User>>, ServerFnError>> = use_context();
     let user_result: &Option<Result<Option<Arc<User>>, ServerFnError>> = &user_resource.read();
@@ -79,6 +145,61 @@ fn App() -> Element {
 
     use_context_provider(|| user_resource);
 
+    let consumption_types_resource = use_resource(move || async move {
+        let user_id = user_resource
+            .read()
+            .as_ref()
+            .and_then(|result| result.as_ref().ok())
+            .and_then(|user| user.as_ref())
+            .map(|user| user.id);
+        match user_id {
+            Some(user_id) => get_consumption_types(user_id).await,
+            None => Ok(Vec::new()),
+        }
+    });
+    use_context_provider(|| consumption_types_resource);
+
+    let entry_templates_resource = use_resource(move || async move {
+        let logged_in = user_resource
+            .read()
+            .as_ref()
+            .and_then(|result| result.as_ref().ok())
+            .is_some_and(|user| user.is_some());
+        if logged_in {
+            get_entry_templates().await
+        } else {
+            Ok(Vec::new())
+        }
+    });
+    use_context_provider(|| entry_templates_resource);
+
+    let user_locale = use_memo(move || {
+        user_resource
+            .read()
+            .as_ref()
+            .and_then(|result| result.as_ref().ok())
+            .and_then(|user| user.as_ref())
+            .and_then(|user| user.locale)
+    });
+    let user_date_format = use_memo(move || {
+        user_resource
+            .read()
+            .as_ref()
+            .and_then(|result| result.as_ref().ok())
+            .and_then(|user| user.as_ref())
+            .and_then(|user| user.date_format)
+    });
+    i18n::use_init_locale(user_locale, user_date_format);
+    let user_theme = use_memo(move || {
+        user_resource
+            .read()
+            .as_ref()
+            .and_then(|result| result.as_ref().ok())
+            .and_then(|user| user.as_ref())
+            .and_then(|user| user.theme)
+    });
+    let theme_class = user_theme().map(|theme| theme.as_id()).unwrap_or_default();
+
     rsx! {
         // Global app resources
         document::Link { rel: "icon", r#type: "image/svg+xml", href: FAVICON_SVG }
@@ -89,7 +210,12 @@ fn App() -> Element {
             src: asset!("/assets/bundle.js", JsAssetOptions::new().with_minify(false)),
         }
 
-        Router::<Route> {}
+        // Rendered on the server too, so the chosen theme shows without a flash.
+        div {
+            class: "min-h-screen {theme_class}",
+            "data-theme": user_theme().map(|theme| theme.as_id()),
+            Router::<Route> {}
+        }
     }
 }
 
@@ -0,0 +1,134 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use derive_enum_all_values::AllValues;
+use serde::{Deserialize, Serialize};
+
+use super::UserId;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ApiTokenId(i64);
+
+#[allow(dead_code)]
+impl ApiTokenId {
+    pub fn new(id: i64) -> Self {
+        Self(id)
+    }
+    pub fn as_inner(self) -> i64 {
+        self.0
+    }
+}
+
+impl FromStr for ApiTokenId {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.parse()?))
+    }
+}
+
+impl std::fmt::Display for ApiTokenId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// What an API token is allowed to do.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize, AllValues)]
+pub enum ApiScope {
+    Read,
+    ReadWrite,
+}
+
+impl ApiScope {
+    #[cfg(feature = "server")]
+    pub fn as_id(&self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::ReadWrite => "read_write",
+        }
+    }
+
+    pub fn as_title(&self) -> &'static str {
+        match self {
+            Self::Read => "Read only",
+            Self::ReadWrite => "Read and write",
+        }
+    }
+
+    /// Does a token with this scope allow an action that needs `required`?
+    #[cfg(feature = "server")]
+    pub fn allows(&self, required: ApiScope) -> bool {
+        match required {
+            Self::Read => true,
+            Self::ReadWrite => *self == Self::ReadWrite,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ApiScopeParseError;
+
+impl FromStr for ApiScope {
+    type Err = ApiScopeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read" => Ok(Self::Read),
+            "read_write" => Ok(Self::ReadWrite),
+            _ => Err(ApiScopeParseError),
+        }
+    }
+}
+
+/// A personal access token for the `/api/v1` endpoints. Only a hash of the
+/// secret is stored, so it cannot be shown again after creation.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ApiToken {
+    pub id: ApiTokenId,
+    pub user_id: UserId,
+    pub name: String,
+    pub scope: ApiScope,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NewApiToken {
+    pub user_id: UserId,
+    pub name: String,
+    pub scope: ApiScope,
+}
+
+/// A newly created token, together with the secret that is only available now.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CreatedApiToken {
+    pub token: ApiToken,
+    pub secret: String,
+}
+
+#[cfg(test)]
+#[cfg(feature = "server")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn api_scope_round_trips_through_id() {
+        for scope in ApiScope::all_values() {
+            assert_eq!(ApiScope::from_str(scope.as_id()).ok(), Some(*scope));
+        }
+    }
+
+    #[test]
+    fn read_write_allows_everything() {
+        assert!(ApiScope::ReadWrite.allows(ApiScope::Read));
+        assert!(ApiScope::ReadWrite.allows(ApiScope::ReadWrite));
+    }
+
+    #[test]
+    fn read_only_does_not_allow_writes() {
+        assert!(ApiScope::Read.allows(ApiScope::Read));
+        assert!(!ApiScope::Read.allows(ApiScope::ReadWrite));
+    }
+}
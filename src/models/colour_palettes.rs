@@ -0,0 +1,239 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use derive_enum_all_values::AllValues;
+use palette::Hsv;
+use serde::{Deserialize, Serialize};
+
+use crate::models::MaybeSet;
+
+use super::UserId;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ColourPaletteId(i64);
+
+#[allow(dead_code)]
+impl ColourPaletteId {
+    pub fn new(id: i64) -> Self {
+        Self(id)
+    }
+    pub fn as_inner(self) -> i64 {
+        self.0
+    }
+}
+
+impl FromStr for ColourPaletteId {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.parse()?))
+    }
+}
+
+impl std::fmt::Display for ColourPaletteId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Which colour input a palette is used for.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize, AllValues)]
+pub enum ColourPaletteKind {
+    Wee,
+    Poo,
+}
+
+impl ColourPaletteKind {
+    pub fn as_id(&self) -> &'static str {
+        match self {
+            Self::Wee => "wee",
+            Self::Poo => "poo",
+        }
+    }
+
+    pub fn as_title(&self) -> &'static str {
+        match self {
+            Self::Wee => "Wee",
+            Self::Poo => "Poo",
+        }
+    }
+
+    /// The preset colours used when the user has not chosen a palette.
+    pub fn default_colours(&self) -> Vec<PaletteColour> {
+        match self {
+            Self::Wee => vec![
+                PaletteColour::new("extra light", 44.0, 1.0, 0.8),
+                PaletteColour::new("light", 42.0, 1.0, 0.8),
+                PaletteColour::new("normal", 40.0, 1.0, 0.8),
+                PaletteColour::new("dark", 38.0, 1.0, 0.8),
+                PaletteColour::new("extra dark", 36.0, 1.0, 0.8),
+            ],
+            Self::Poo => vec![
+                PaletteColour::new("light", 25.0, 1.0, 0.8),
+                PaletteColour::new("normal", 25.0, 1.0, 0.5),
+                PaletteColour::new("dark", 25.0, 1.0, 0.2),
+                PaletteColour::new("red", 0.0, 1.0, 1.0),
+            ],
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ColourPaletteKindParseError;
+
+impl FromStr for ColourPaletteKind {
+    type Err = ColourPaletteKindParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "wee" => Ok(Self::Wee),
+            "poo" => Ok(Self::Poo),
+            _ => Err(ColourPaletteKindParseError),
+        }
+    }
+}
+
+/// A named preset colour.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PaletteColour {
+    pub name: String,
+    pub hue: f32,
+    pub saturation: f32,
+    pub value: f32,
+}
+
+impl PaletteColour {
+    pub fn new(name: &str, hue: f32, saturation: f32, value: f32) -> Self {
+        Self {
+            name: name.to_string(),
+            hue,
+            saturation,
+            value,
+        }
+    }
+
+    pub fn hsv(&self) -> Hsv {
+        Hsv::new(self.hue, self.saturation, self.value)
+    }
+}
+
+/// Parse palette colours written one per line as `name: hue, saturation, value`.
+pub fn parse_palette_colours(str: &str) -> Result<Vec<PaletteColour>, String> {
+    let colours = str
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(parse_palette_colour)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if colours.is_empty() {
+        Err("Palette must have at least one colour".to_string())
+    } else {
+        Ok(colours)
+    }
+}
+
+fn parse_palette_colour(line: &str) -> Result<PaletteColour, String> {
+    let invalid = || format!("Expected \"name: hue, saturation, value\" in \"{line}\"");
+
+    let (name, values) = line.rsplit_once(':').ok_or_else(invalid)?;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(invalid());
+    }
+
+    let values = values
+        .split(',')
+        .map(|value| value.trim().parse::<f32>().map_err(|_| invalid()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let [hue, saturation, value] = values[..] else {
+        return Err(invalid());
+    };
+
+    if !(-180.0..=360.0).contains(&hue) {
+        Err(format!("Hue for {name} must be between -180 and 360"))
+    } else if !(0.0..=1.0).contains(&saturation) || !(0.0..=1.0).contains(&value) {
+        Err(format!(
+            "Saturation and value for {name} must be between 0 and 1"
+        ))
+    } else {
+        Ok(PaletteColour::new(name, hue, saturation, value))
+    }
+}
+
+/// The inverse of [`parse_palette_colours`], for editing.
+pub fn format_palette_colours(colours: &[PaletteColour]) -> String {
+    colours
+        .iter()
+        .map(|colour| {
+            format!(
+                "{}: {}, {}, {}",
+                colour.name, colour.hue, colour.saturation, colour.value
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A user defined set of preset colours. The active palette of each kind
+/// replaces the default preset buttons.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ColourPalette {
+    pub id: ColourPaletteId,
+    pub user_id: UserId,
+    pub name: String,
+    pub kind: ColourPaletteKind,
+    pub colours: Vec<PaletteColour>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NewColourPalette {
+    pub user_id: UserId,
+    pub name: String,
+    pub kind: ColourPaletteKind,
+    pub colours: Vec<PaletteColour>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ChangeColourPalette {
+    pub name: MaybeSet<String>,
+    pub colours: MaybeSet<Vec<PaletteColour>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn palette_kind_round_trips_through_id() {
+        for kind in ColourPaletteKind::all_values() {
+            assert_eq!(ColourPaletteKind::from_str(kind.as_id()).ok(), Some(*kind));
+        }
+    }
+
+    #[test]
+    fn parse_palette_colours_round_trip() {
+        let colours = ColourPaletteKind::Wee.default_colours();
+        let text = format_palette_colours(&colours);
+        assert_eq!(parse_palette_colours(&text), Ok(colours));
+    }
+
+    #[test]
+    fn parse_palette_colours_skips_blank_lines() {
+        let colours = parse_palette_colours("\n  pale: 40, 0.5, 0.9  \n\n").unwrap();
+        assert_eq!(colours, vec![PaletteColour::new("pale", 40.0, 0.5, 0.9)]);
+    }
+
+    #[test]
+    fn parse_palette_colours_rejects_invalid() {
+        assert!(parse_palette_colours("").is_err());
+        assert!(parse_palette_colours("pale 40, 0.5, 0.9").is_err());
+        assert!(parse_palette_colours(": 40, 0.5, 0.9").is_err());
+        assert!(parse_palette_colours("pale: 40, 0.5").is_err());
+        assert!(parse_palette_colours("pale: 40, 1.5, 0.9").is_err());
+        assert!(parse_palette_colours("pale: 400, 0.5, 0.9").is_err());
+    }
+}
@@ -6,6 +6,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::models::MaybeSet;
 
+#[cfg(feature = "server")]
+use super::Nutrition;
 use super::{ConsumableItem, ConsumptionType};
 
 #[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq, AllValues)]
@@ -43,6 +45,54 @@ impl ConsumableUnit {
             Self::Number => "",
         }
     }
+
+    /// Whether the unit counts whole items rather than measuring an amount.
+    pub fn is_count(&self) -> bool {
+        matches!(self, Self::Number)
+    }
+
+    /// Whether an amount in this unit can hold liquid worth recording, such
+    /// as a drink or a can.
+    pub fn has_liquid(&self) -> bool {
+        matches!(self, Self::Millilitres | Self::Number)
+    }
+
+    /// The amount that nutritional values are given for: 100 g or ml, or a
+    /// single item or unit.
+    #[cfg(feature = "server")]
+    pub fn nutrition_basis(&self) -> bigdecimal::BigDecimal {
+        match self {
+            Self::Millilitres | Self::Grams => bigdecimal::BigDecimal::from(100),
+            Self::InternationalUnits | Self::Number => bigdecimal::BigDecimal::from(1),
+        }
+    }
+
+    pub fn nutrition_basis_label(&self) -> &'static str {
+        match self {
+            Self::Millilitres => "per 100 ml",
+            Self::Grams => "per 100 g",
+            Self::InternationalUnits => "per IU",
+            Self::Number => "per item",
+        }
+    }
+
+    /// Amount the steppers change a quantity in this unit by.
+    pub fn step(&self) -> bigdecimal::BigDecimal {
+        match self {
+            Self::Millilitres => bigdecimal::BigDecimal::from(10),
+            Self::Grams | Self::InternationalUnits | Self::Number => {
+                bigdecimal::BigDecimal::from(1)
+            }
+        }
+    }
+
+    pub fn quantity_label(&self) -> String {
+        if self.is_count() {
+            "Count".to_string()
+        } else {
+            "Quantity".to_string()
+        }
+    }
 }
 
 impl Display for ConsumableUnit {
@@ -56,7 +106,7 @@ impl Display for ConsumableUnit {
     }
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct ConsumableId(i64);
 
 impl ConsumableId {
@@ -96,6 +146,45 @@ pub struct Consumable {
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
     pub consumption_type: Option<ConsumptionType>,
+    /// Energy in kcal, per [`ConsumableUnit::nutrition_basis`].
+    pub calories: Option<bigdecimal::BigDecimal>,
+    /// Grams of protein, per [`ConsumableUnit::nutrition_basis`].
+    pub protein: Option<bigdecimal::BigDecimal>,
+    /// Grams of fat, per [`ConsumableUnit::nutrition_basis`].
+    pub fat: Option<bigdecimal::BigDecimal>,
+    /// Grams of carbohydrate, per [`ConsumableUnit::nutrition_basis`].
+    pub carbohydrate: Option<bigdecimal::BigDecimal>,
+    /// Key of the uploaded image, changed whenever a new one is uploaded.
+    pub image_key: Option<String>,
+}
+
+impl Consumable {
+    /// URL of the image, or of its thumbnail, if there is one.
+    pub fn image_url(&self, thumbnail: bool) -> Option<String> {
+        let suffix = if thumbnail { "-thumb" } else { "" };
+        self.image_key
+            .as_ref()
+            .map(|key| format!("/api/v1/images/{key}{suffix}.jpg"))
+    }
+
+    /// Nutritional values per basis amount, if any were entered. Values left
+    /// blank alongside others are taken as zero.
+    #[cfg(feature = "server")]
+    pub fn nutrition(&self) -> Option<Nutrition> {
+        if self.calories.is_none()
+            && self.protein.is_none()
+            && self.fat.is_none()
+            && self.carbohydrate.is_none()
+        {
+            return None;
+        }
+        Some(Nutrition {
+            calories: self.calories.clone().unwrap_or_default(),
+            protein: self.protein.clone().unwrap_or_default(),
+            fat: self.fat.clone().unwrap_or_default(),
+            carbohydrate: self.carbohydrate.clone().unwrap_or_default(),
+        })
+    }
 }
 
 #[cfg(feature = "server")]
@@ -105,12 +194,54 @@ impl ConsumableWithItems {
     }
 }
 
+/// Most consumables a search returns, so the picker stays quick to render.
+pub const CONSUMABLE_SEARCH_LIMIT: usize = 20;
+
+/// Most existing consumables listed when warning about a duplicate name.
+#[cfg(feature = "server")]
+pub const DUPLICATE_NAME_LIMIT: i64 = 5;
+
+/// Consumables matching a search, best matches first.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ConsumableSearchResults {
+    pub consumables: Vec<Consumable>,
+    /// More consumables matched than are listed.
+    pub truncated: bool,
+}
+
+#[cfg(feature = "server")]
+impl ConsumableSearchResults {
+    /// Keep the first `limit` consumables. Pass up to one more than `limit`
+    /// to find out if there were more.
+    pub fn new(mut consumables: Vec<Consumable>, limit: usize) -> Self {
+        let truncated = consumables.len() > limit;
+        consumables.truncate(limit);
+        Self {
+            consumables,
+            truncated,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct ConsumableWithItems {
     pub consumable: Consumable,
     pub items: Vec<ConsumableItem>,
 }
 
+/// Product details found by looking up a barcode, to fill in a new
+/// consumable. Anything the product database does not know is `None`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct BarcodeProduct {
+    pub name: Option<String>,
+    pub brand: Option<String>,
+    pub unit: Option<ConsumableUnit>,
+    pub calories: Option<bigdecimal::BigDecimal>,
+    pub protein: Option<bigdecimal::BigDecimal>,
+    pub fat: Option<bigdecimal::BigDecimal>,
+    pub carbohydrate: Option<bigdecimal::BigDecimal>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct NewConsumable {
     pub name: String,
@@ -122,9 +253,13 @@ pub struct NewConsumable {
     pub created: Option<DateTime<Utc>>,
     pub destroyed: Option<DateTime<Utc>>,
     pub consumption_type: Option<ConsumptionType>,
+    pub calories: Option<bigdecimal::BigDecimal>,
+    pub protein: Option<bigdecimal::BigDecimal>,
+    pub fat: Option<bigdecimal::BigDecimal>,
+    pub carbohydrate: Option<bigdecimal::BigDecimal>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct ChangeConsumable {
     pub name: MaybeSet<String>,
     pub brand: MaybeSet<Option<String>>,
@@ -135,4 +270,247 @@ pub struct ChangeConsumable {
     pub created: MaybeSet<Option<DateTime<Utc>>>,
     pub destroyed: MaybeSet<Option<DateTime<Utc>>>,
     pub consumption_type: MaybeSet<Option<ConsumptionType>>,
+    pub calories: MaybeSet<Option<bigdecimal::BigDecimal>>,
+    pub protein: MaybeSet<Option<bigdecimal::BigDecimal>>,
+    pub fat: MaybeSet<Option<bigdecimal::BigDecimal>>,
+    pub carbohydrate: MaybeSet<Option<bigdecimal::BigDecimal>>,
+}
+
+/// Validate an EAN-8, EAN-13 or UPC-A barcode and return its normalized form.
+///
+/// Whitespace and dashes are removed and UPC-A barcodes are padded to EAN-13, so the same
+/// product scanned as either symbology gives the same value.
+pub fn normalize_barcode(barcode: &str) -> Result<String, String> {
+    let digits: String = barcode
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .collect();
+
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err("Barcode should only contain digits".to_string());
+    }
+
+    let normalized = match digits.len() {
+        8 | 13 => digits,
+        12 => format!("0{digits}"),
+        len => {
+            return Err(format!(
+                "Barcode has {len} digits, expected 8 (EAN-8), 12 (UPC-A) or 13 (EAN-13)"
+            ));
+        }
+    };
+
+    let values: Vec<u32> = normalized.chars().filter_map(|c| c.to_digit(10)).collect();
+    let (check, data) = values.split_last().ok_or("Barcode is empty")?;
+
+    // Weights alternate 3, 1, 3, ... starting from the digit next to the check digit.
+    let sum: u32 = data
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, digit)| if i % 2 == 0 { digit * 3 } else { *digit })
+        .sum();
+    let expected = (10 - sum % 10) % 10;
+
+    if *check != expected {
+        return Err(format!(
+            "Barcode check digit is {check}, expected {expected}"
+        ));
+    }
+
+    Ok(normalized)
+}
+
+/// A consumable cannot be destroyed before it was created.
+pub fn check_consumable_lifetime(
+    created: Option<DateTime<Utc>>,
+    destroyed: Option<DateTime<Utc>>,
+) -> Result<(), String> {
+    match (created, destroyed) {
+        (Some(created), Some(destroyed)) if destroyed < created => {
+            Err("Destroyed must not be before created".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Maximum characters of comments shown for a consumable in a picker.
+pub const COMMENTS_PREVIEW_CHARS: usize = 60;
+
+/// A one line, plain text preview of markdown comments, or `None` if there
+/// is nothing to show. The flag is true if anything was left out.
+pub fn comments_preview(comments: &str, max_chars: usize) -> Option<(String, bool)> {
+    let mut lines = comments
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty());
+    let first = lines
+        .next()?
+        .trim_start_matches(['#', '>', '-', '*', ' '])
+        .trim();
+    let more_lines = lines.next().is_some();
+
+    if first.chars().count() > max_chars {
+        let truncated: String = first.chars().take(max_chars).collect();
+        Some((format!("{}…", truncated.trim_end()), true))
+    } else {
+        Some((first.to_string(), more_lines))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comments_preview_uses_first_line() {
+        assert_eq!(
+            comments_preview("# Heading\n\nMore text", 60),
+            Some(("Heading".to_string(), true))
+        );
+        assert_eq!(
+            comments_preview("- only item", 60),
+            Some(("only item".to_string(), false))
+        );
+        assert_eq!(comments_preview("  \n ", 60), None);
+    }
+
+    #[test]
+    fn comments_preview_truncates_long_lines() {
+        assert_eq!(
+            comments_preview("abcde fghij", 6),
+            Some(("abcde…".to_string(), true))
+        );
+    }
+
+    #[cfg(feature = "server")]
+    fn make_consumable(id: i64) -> Consumable {
+        Consumable {
+            id: ConsumableId::new(id),
+            name: format!("consumable-{id}"),
+            brand: None,
+            barcode: None,
+            is_organic: false,
+            unit: ConsumableUnit::Grams,
+            comments: None,
+            created: None,
+            destroyed: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            consumption_type: None,
+            calories: None,
+            protein: None,
+            fat: None,
+            carbohydrate: None,
+            image_key: None,
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "server")]
+    fn search_results_are_capped() {
+        let consumables = (1..=3).map(make_consumable).collect::<Vec<_>>();
+
+        let results = ConsumableSearchResults::new(consumables.clone(), 2);
+        assert_eq!(results.consumables, consumables[..2]);
+        assert!(results.truncated);
+
+        let results = ConsumableSearchResults::new(consumables.clone(), 3);
+        assert_eq!(results.consumables, consumables);
+        assert!(!results.truncated);
+    }
+
+    #[test]
+    fn destroyed_before_created_is_rejected() {
+        let created = DateTime::parse_from_rfc3339("2024-01-02T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let destroyed = created - chrono::TimeDelta::days(1);
+
+        assert!(check_consumable_lifetime(Some(created), Some(destroyed)).is_err());
+        assert_eq!(
+            check_consumable_lifetime(Some(destroyed), Some(created)),
+            Ok(())
+        );
+        assert_eq!(
+            check_consumable_lifetime(Some(created), Some(created)),
+            Ok(())
+        );
+        assert_eq!(check_consumable_lifetime(None, Some(destroyed)), Ok(()));
+        assert_eq!(check_consumable_lifetime(Some(created), None), Ok(()));
+    }
+
+    #[test]
+    fn quantity_label_follows_unit() {
+        assert_eq!(ConsumableUnit::Number.quantity_label(), "Count");
+        assert_eq!(ConsumableUnit::Grams.quantity_label(), "Quantity");
+    }
+
+    #[test]
+    fn step_follows_unit() {
+        assert_eq!(
+            ConsumableUnit::Millilitres.step(),
+            bigdecimal::BigDecimal::from(10)
+        );
+        assert_eq!(
+            ConsumableUnit::Grams.step(),
+            bigdecimal::BigDecimal::from(1)
+        );
+    }
+
+    #[test]
+    fn barcode_valid_ean13_is_unchanged() {
+        assert_eq!(
+            normalize_barcode("4006381333931"),
+            Ok("4006381333931".to_string())
+        );
+    }
+
+    #[test]
+    fn barcode_valid_ean8_is_unchanged() {
+        assert_eq!(normalize_barcode("96385074"), Ok("96385074".to_string()));
+    }
+
+    #[test]
+    fn barcode_upca_is_padded_to_ean13() {
+        assert_eq!(
+            normalize_barcode("036000291452"),
+            Ok("0036000291452".to_string())
+        );
+    }
+
+    #[test]
+    fn barcode_whitespace_is_removed() {
+        assert_eq!(
+            normalize_barcode(" 9 310072 000695 "),
+            Ok("9310072000695".to_string())
+        );
+    }
+
+    #[test]
+    fn barcode_dashes_are_removed() {
+        assert_eq!(
+            normalize_barcode("9-310072-000695"),
+            Ok("9310072000695".to_string())
+        );
+    }
+
+    #[test]
+    fn barcode_bad_check_digit_returns_error() {
+        assert!(normalize_barcode("4006381333932").is_err());
+        assert!(normalize_barcode("036000291453").is_err());
+        assert!(normalize_barcode("96385075").is_err());
+    }
+
+    #[test]
+    fn barcode_bad_length_returns_error() {
+        assert!(normalize_barcode("").is_err());
+        assert!(normalize_barcode("12345").is_err());
+        assert!(normalize_barcode("40063813339310").is_err());
+    }
+
+    #[test]
+    fn barcode_non_digits_returns_error() {
+        assert!(normalize_barcode("400638133393X").is_err());
+    }
 }
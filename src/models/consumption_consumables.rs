@@ -33,6 +33,9 @@ pub struct ConsumptionConsumable {
     pub id: ConsumptionConsumableId,
     pub quantity: Option<bigdecimal::BigDecimal>,
     pub liquid_mls: Option<bigdecimal::BigDecimal>,
+    /// Fraction of the recorded quantity that was actually consumed, e.g. 0.5
+    /// for half a meal.
+    pub portion: bigdecimal::BigDecimal,
     pub comments: Option<String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
@@ -48,6 +51,85 @@ impl ConsumptionItem {
     pub fn new(nested: ConsumptionConsumable, consumable: Consumable) -> Self {
         Self { nested, consumable }
     }
+
+    /// Whether a quantity was given; without one the item is left out of
+    /// rollups.
+    pub fn is_quantity_specified(&self) -> bool {
+        self.nested.quantity.is_some()
+    }
+
+    /// Liquid actually consumed, after applying the portion.
+    pub fn portion_liquid_mls(&self) -> Option<bigdecimal::BigDecimal> {
+        self.nested
+            .liquid_mls
+            .as_ref()
+            .map(|liquid_mls| apply_portion(liquid_mls, &self.nested.portion))
+    }
+}
+
+#[cfg(feature = "server")]
+impl ConsumptionConsumable {
+    /// The changes that fold `other`, a row for the same consumable, into
+    /// this one. Amounts are added together; if the portions differ they are
+    /// applied first so the total consumed stays the same.
+    pub fn merge(&self, other: &ConsumptionConsumable) -> ChangeConsumptionConsumable {
+        let (quantity, liquid_mls, portion) = if self.portion == other.portion {
+            (
+                add_optional(self.quantity.clone(), other.quantity.clone()),
+                add_optional(self.liquid_mls.clone(), other.liquid_mls.clone()),
+                self.portion.clone(),
+            )
+        } else {
+            let scale = |value: &Option<bigdecimal::BigDecimal>,
+                         portion: &bigdecimal::BigDecimal| {
+                value.as_ref().map(|value| apply_portion(value, portion))
+            };
+            (
+                add_optional(
+                    scale(&self.quantity, &self.portion),
+                    scale(&other.quantity, &other.portion),
+                ),
+                add_optional(
+                    scale(&self.liquid_mls, &self.portion),
+                    scale(&other.liquid_mls, &other.portion),
+                ),
+                bigdecimal::BigDecimal::from(1),
+            )
+        };
+
+        let comments = match (&self.comments, &other.comments) {
+            (Some(a), Some(b)) if a != b => Some(format!("{a}\n\n{b}")),
+            (Some(a), _) => Some(a.clone()),
+            (None, b) => b.clone(),
+        };
+
+        ChangeConsumptionConsumable {
+            quantity: MaybeSet::Set(quantity),
+            liquid_mls: MaybeSet::Set(liquid_mls),
+            portion: MaybeSet::Set(portion),
+            comments: MaybeSet::Set(comments),
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+fn add_optional(
+    a: Option<bigdecimal::BigDecimal>,
+    b: Option<bigdecimal::BigDecimal>,
+) -> Option<bigdecimal::BigDecimal> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a + b),
+        (a, None) => a,
+        (None, b) => b,
+    }
+}
+
+/// Scale a value recorded for the whole item by the portion consumed.
+fn apply_portion(
+    value: &bigdecimal::BigDecimal,
+    portion: &bigdecimal::BigDecimal,
+) -> bigdecimal::BigDecimal {
+    value * portion
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -58,9 +140,91 @@ pub struct NewConsumptionConsumable {
     pub comments: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct ChangeConsumptionConsumable {
     pub quantity: MaybeSet<Option<bigdecimal::BigDecimal>>,
     pub liquid_mls: MaybeSet<Option<bigdecimal::BigDecimal>>,
+    pub portion: MaybeSet<bigdecimal::BigDecimal>,
     pub comments: MaybeSet<Option<String>>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bigdecimal::BigDecimal;
+    use std::str::FromStr;
+
+    #[test]
+    fn apply_portion_with_whole_portion_is_unchanged() {
+        let value = BigDecimal::from(250);
+        assert_eq!(apply_portion(&value, &BigDecimal::from(1)), value);
+    }
+
+    #[test]
+    fn apply_portion_scales_value() {
+        let half = BigDecimal::from_str("0.5").unwrap();
+        assert_eq!(
+            apply_portion(&BigDecimal::from(250), &half),
+            BigDecimal::from(125)
+        );
+    }
+
+    #[cfg(feature = "server")]
+    fn make_nested(
+        quantity: Option<i64>,
+        liquid_mls: Option<i64>,
+        portion: &str,
+        comments: Option<&str>,
+    ) -> ConsumptionConsumable {
+        ConsumptionConsumable {
+            id: ConsumptionConsumableId::new(ConsumptionId::new(1), ConsumableId::new(2)),
+            quantity: quantity.map(BigDecimal::from),
+            liquid_mls: liquid_mls.map(BigDecimal::from),
+            portion: BigDecimal::from_str(portion).unwrap(),
+            comments: comments.map(str::to_string),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "server")]
+    fn merge_adds_amounts_with_same_portion() {
+        let target = make_nested(Some(2), None, "0.5", Some("lunch"));
+        let moved = make_nested(Some(3), Some(100), "0.5", Some("lunch"));
+        let changes = target.merge(&moved);
+        assert_eq!(changes.quantity, MaybeSet::Set(Some(BigDecimal::from(5))));
+        assert_eq!(
+            changes.liquid_mls,
+            MaybeSet::Set(Some(BigDecimal::from(100)))
+        );
+        assert_eq!(
+            changes.portion,
+            MaybeSet::Set(BigDecimal::from_str("0.5").unwrap())
+        );
+        assert_eq!(changes.comments, MaybeSet::Set(Some("lunch".to_string())));
+    }
+
+    #[test]
+    #[cfg(feature = "server")]
+    fn merge_applies_differing_portions() {
+        let target = make_nested(Some(4), None, "0.5", None);
+        let moved = make_nested(Some(3), None, "1", Some("seconds"));
+        let changes = target.merge(&moved);
+        assert_eq!(changes.quantity, MaybeSet::Set(Some(BigDecimal::from(5))));
+        assert_eq!(changes.liquid_mls, MaybeSet::Set(None));
+        assert_eq!(changes.portion, MaybeSet::Set(BigDecimal::from(1)));
+        assert_eq!(changes.comments, MaybeSet::Set(Some("seconds".to_string())));
+    }
+
+    #[test]
+    #[cfg(feature = "server")]
+    fn merge_keeps_both_comments() {
+        let target = make_nested(None, None, "1", Some("a"));
+        let moved = make_nested(None, None, "1", Some("b"));
+        assert_eq!(
+            target.merge(&moved).comments,
+            MaybeSet::Set(Some("a\n\nb".to_string()))
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{Consumption, ConsumptionType, UserId};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct CustomConsumptionTypeId(i64);
+
+#[allow(dead_code)]
+impl CustomConsumptionTypeId {
+    pub fn new(id: i64) -> Self {
+        Self(id)
+    }
+    pub fn as_inner(self) -> i64 {
+        self.0
+    }
+}
+
+impl FromStr for CustomConsumptionTypeId {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.parse()?))
+    }
+}
+
+impl std::fmt::Display for CustomConsumptionTypeId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A consumption type added by a user, for routes the built in types do not
+/// cover. It behaves like its base type everywhere the route matters, such
+/// as checking ingredients match.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct CustomConsumptionType {
+    pub id: CustomConsumptionTypeId,
+    pub user_id: UserId,
+    pub name: String,
+    /// A short piece of text, usually an emoji, shown in place of an image.
+    pub icon: String,
+    pub base_type: ConsumptionType,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NewCustomConsumptionType {
+    pub user_id: UserId,
+    pub name: String,
+    pub icon: String,
+    pub base_type: ConsumptionType,
+}
+
+/// Longest icon allowed for a custom consumption type, in characters.
+pub const MAX_CONSUMPTION_TYPE_ICON_CHARS: usize = 4;
+
+/// Icons are short, like an emoji or a couple of letters.
+pub fn check_consumption_type_icon(icon: &str) -> Result<String, String> {
+    let icon = icon.trim();
+    if icon.is_empty() {
+        Err("Icon is required".to_string())
+    } else if icon.chars().count() > MAX_CONSUMPTION_TYPE_ICON_CHARS {
+        Err(format!(
+            "Icon must be at most {MAX_CONSUMPTION_TYPE_ICON_CHARS} characters"
+        ))
+    } else {
+        Ok(icon.to_string())
+    }
+}
+
+/// How something was consumed: one of the built in types, or one of the
+/// user's own.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub enum ConsumptionRoute {
+    BuiltIn(ConsumptionType),
+    Custom(CustomConsumptionType),
+}
+
+impl ConsumptionRoute {
+    /// The route of a consumption, looking its custom type up in
+    /// `custom_types`. Falls back to the built in type if it is not there.
+    pub fn for_consumption(
+        consumption: &Consumption,
+        custom_types: &[CustomConsumptionType],
+    ) -> Self {
+        Self::for_type(
+            consumption.consumption_type,
+            consumption.custom_type_id,
+            custom_types,
+        )
+    }
+
+    /// The same as [`ConsumptionRoute::for_consumption`], from the type
+    /// fields alone.
+    pub fn for_type(
+        consumption_type: ConsumptionType,
+        custom_type_id: Option<CustomConsumptionTypeId>,
+        custom_types: &[CustomConsumptionType],
+    ) -> Self {
+        custom_type_id
+            .and_then(|id| custom_types.iter().find(|custom| custom.id == id))
+            .map(|custom| Self::Custom(custom.clone()))
+            .unwrap_or(Self::BuiltIn(consumption_type))
+    }
+
+    pub fn as_id(&self) -> String {
+        match self {
+            Self::BuiltIn(consumption_type) => consumption_type.as_id().to_string(),
+            Self::Custom(custom) => format!("custom-{}", custom.id),
+        }
+    }
+
+    pub fn as_title(&self) -> String {
+        match self {
+            Self::BuiltIn(consumption_type) => consumption_type.as_title().to_string(),
+            Self::Custom(custom) => custom.name.clone(),
+        }
+    }
+
+    /// The built in type stored with the consumption.
+    pub fn consumption_type(&self) -> ConsumptionType {
+        match self {
+            Self::BuiltIn(consumption_type) => *consumption_type,
+            Self::Custom(custom) => custom.base_type,
+        }
+    }
+
+    pub fn custom_type_id(&self) -> Option<CustomConsumptionTypeId> {
+        match self {
+            Self::BuiltIn(_) => None,
+            Self::Custom(custom) => Some(custom.id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ConsumptionId;
+
+    fn custom_type(id: i64) -> CustomConsumptionType {
+        CustomConsumptionType {
+            id: CustomConsumptionTypeId::new(id),
+            user_id: UserId::new(1),
+            name: "Sublingual".to_string(),
+            icon: "👅".to_string(),
+            base_type: ConsumptionType::Digest,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn consumption(custom_type_id: Option<i64>) -> Consumption {
+        Consumption {
+            id: ConsumptionId::new(1),
+            user_id: UserId::new(1),
+            time: "2025-01-01T12:00:00+11:00".parse().unwrap(),
+            duration: chrono::TimeDelta::minutes(1),
+            consumption_type: ConsumptionType::InhaleMouth,
+            custom_type_id: custom_type_id.map(CustomConsumptionTypeId::new),
+            liquid_mls: None,
+            comments: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            planned: false,
+        }
+    }
+
+    #[test]
+    fn route_uses_custom_type_when_known() {
+        let custom_types = vec![custom_type(2)];
+        let route = ConsumptionRoute::for_consumption(&consumption(Some(2)), &custom_types);
+        assert_eq!(
+            route.custom_type_id(),
+            Some(CustomConsumptionTypeId::new(2))
+        );
+        assert_eq!(route.as_title(), "Sublingual");
+        assert_eq!(route.consumption_type(), ConsumptionType::Digest);
+        assert_eq!(route.as_id(), "custom-2");
+    }
+
+    #[test]
+    fn route_falls_back_to_built_in() {
+        let custom_types = vec![custom_type(2)];
+        for id in [None, Some(3)] {
+            let route = ConsumptionRoute::for_consumption(&consumption(id), &custom_types);
+            assert_eq!(
+                route,
+                ConsumptionRoute::BuiltIn(ConsumptionType::InhaleMouth)
+            );
+            assert_eq!(route.custom_type_id(), None);
+        }
+    }
+
+    #[test]
+    fn icon_must_be_short() {
+        assert_eq!(check_consumption_type_icon(" 👅 "), Ok("👅".to_string()));
+        assert!(check_consumption_type_icon("").is_err());
+        assert!(check_consumption_type_icon("abcde").is_err());
+    }
+}
@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::models::{UserId, common::MaybeSet};
 
-use super::ConsumptionItem;
+use super::{ConsumptionConsumableId, ConsumptionItem, CustomConsumptionTypeId};
 
 #[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq, AllValues)]
 pub enum ConsumptionType {
@@ -42,7 +42,7 @@ impl ConsumptionType {
     }
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct ConsumptionId(i64);
 
 impl ConsumptionId {
@@ -75,16 +75,27 @@ pub struct Consumption {
     pub time: chrono::DateTime<chrono::FixedOffset>,
     pub duration: chrono::TimeDelta,
     pub consumption_type: ConsumptionType,
+    /// The user's own type, if they picked one; `consumption_type` then
+    /// holds its base type.
+    pub custom_type_id: Option<CustomConsumptionTypeId>,
     pub liquid_mls: Option<bigdecimal::BigDecimal>,
     pub comments: Option<String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    /// Planned in advance and not yet marked as done.
+    pub planned: bool,
 }
 
 impl Consumption {
     pub fn name(&self) -> String {
         self.time.with_timezone(&Local).time().to_string()
     }
+
+    /// A planned consumption that has not happened yet, so should be left out
+    /// of what was taken in.
+    pub fn is_pending(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.planned && self.time > now
+    }
 }
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct ConsumptionWithItems {
@@ -92,6 +103,116 @@ pub struct ConsumptionWithItems {
     pub items: Vec<ConsumptionItem>,
 }
 
+/// Whether something taken in was a drink, food, or both.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ConsumptionIntake {
+    Liquid,
+    Solid,
+    Mixed,
+}
+
+impl ConsumptionIntake {
+    pub fn as_title(&self) -> &'static str {
+        match self {
+            Self::Liquid => "Liquid",
+            Self::Solid => "Solid",
+            Self::Mixed => "Mixed",
+        }
+    }
+}
+
+impl ConsumptionWithItems {
+    /// Classify what was taken in, `None` if nothing was swallowed or injected.
+    ///
+    /// A consumption without any liquid millilitres is solid, even if it has
+    /// ingredients. One with liquid is mixed if any ingredient has no liquid.
+    pub fn intake(&self) -> Option<ConsumptionIntake> {
+        let consumption = &self.consumption;
+        if !matches!(
+            consumption.consumption_type,
+            ConsumptionType::Digest | ConsumptionType::Inject
+        ) {
+            return None;
+        }
+
+        let zero = bigdecimal::BigDecimal::from(0);
+        let has_liquid = consumption
+            .liquid_mls
+            .as_ref()
+            .is_some_and(|mls| *mls > zero);
+        let has_solid = self.items.iter().any(|item| {
+            item.nested
+                .liquid_mls
+                .as_ref()
+                .is_none_or(|mls| *mls <= zero)
+        });
+
+        let intake = match (has_liquid, has_solid) {
+            (false, _) => ConsumptionIntake::Solid,
+            (true, false) => ConsumptionIntake::Liquid,
+            (true, true) => ConsumptionIntake::Mixed,
+        };
+        Some(intake)
+    }
+}
+
+/// The liquid recorded against a consumption compared with the liquid of its
+/// ingredients, after portions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiquidBalance {
+    pub consumption_mls: bigdecimal::BigDecimal,
+    pub items_mls: bigdecimal::BigDecimal,
+}
+
+impl LiquidBalance {
+    pub fn new(consumption: &Consumption, items: &[ConsumptionItem]) -> Self {
+        Self {
+            consumption_mls: consumption.liquid_mls.clone().unwrap_or_default(),
+            items_mls: items
+                .iter()
+                .filter_map(|item| item.portion_liquid_mls())
+                .sum(),
+        }
+    }
+
+    pub fn is_balanced(&self) -> bool {
+        self.consumption_mls == self.items_mls
+    }
+}
+
+impl ConsumptionWithItems {
+    pub fn liquid_balance(&self) -> LiquidBalance {
+        LiquidBalance::new(&self.consumption, &self.items)
+    }
+
+    /// New liquid millilitres for each ingredient that has some, scaled so
+    /// they add up to the consumption's liquid, rounded to 2 decimal places.
+    ///
+    /// `None` if no ingredient has any liquid to scale.
+    pub fn distribute_liquid_mls(
+        &self,
+    ) -> Option<Vec<(ConsumptionConsumableId, bigdecimal::BigDecimal)>> {
+        let balance = self.liquid_balance();
+        if balance.items_mls <= 0 {
+            return None;
+        }
+
+        let distributed = self
+            .items
+            .iter()
+            .filter_map(|item| {
+                let liquid_mls = item.nested.liquid_mls.as_ref()?;
+                let scaled = liquid_mls * &balance.consumption_mls / &balance.items_mls;
+                let scaled = scaled
+                    .with_scale_round(2, bigdecimal::RoundingMode::HalfUp)
+                    .normalized();
+                Some((item.nested.id, scaled))
+            })
+            .collect();
+        Some(distributed)
+    }
+}
+
 #[cfg(feature = "server")]
 impl ConsumptionWithItems {
     pub fn new(consumption: Consumption, items: Vec<ConsumptionItem>) -> Self {
@@ -105,16 +226,218 @@ pub struct NewConsumption {
     pub time: chrono::DateTime<chrono::FixedOffset>,
     pub duration: chrono::TimeDelta,
     pub consumption_type: ConsumptionType,
+    pub custom_type_id: Option<CustomConsumptionTypeId>,
     pub liquid_mls: Option<bigdecimal::BigDecimal>,
     pub comments: Option<String>,
+    pub planned: bool,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct ChangeConsumption {
     pub user_id: MaybeSet<UserId>,
     pub time: MaybeSet<chrono::DateTime<chrono::FixedOffset>>,
     pub duration: MaybeSet<chrono::TimeDelta>,
     pub consumption_type: MaybeSet<ConsumptionType>,
+    pub custom_type_id: MaybeSet<Option<CustomConsumptionTypeId>>,
     pub liquid_mls: MaybeSet<Option<bigdecimal::BigDecimal>>,
     pub comments: MaybeSet<Option<String>>,
+    pub planned: MaybeSet<bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+    use chrono::Utc;
+
+    use super::*;
+    use crate::models::{
+        Consumable, ConsumableId, ConsumableUnit, ConsumptionConsumable, ConsumptionConsumableId,
+    };
+
+    fn make_consumption(
+        consumption_type: ConsumptionType,
+        liquid_mls: Option<BigDecimal>,
+        item_mls: Vec<Option<BigDecimal>>,
+    ) -> ConsumptionWithItems {
+        let id = ConsumptionId::new(1);
+        let items = item_mls
+            .into_iter()
+            .enumerate()
+            .map(|(n, liquid_mls)| {
+                let consumable_id = ConsumableId::new(n as i64);
+                ConsumptionItem {
+                    nested: ConsumptionConsumable {
+                        id: ConsumptionConsumableId::new(id, consumable_id),
+                        quantity: None,
+                        liquid_mls,
+                        portion: BigDecimal::from(1),
+                        comments: None,
+                        created_at: Utc::now(),
+                        updated_at: Utc::now(),
+                    },
+                    consumable: Consumable {
+                        id: consumable_id,
+                        name: format!("consumable-{n}"),
+                        brand: None,
+                        barcode: None,
+                        is_organic: false,
+                        unit: ConsumableUnit::Grams,
+                        comments: None,
+                        created: None,
+                        destroyed: None,
+                        created_at: Utc::now(),
+                        updated_at: Utc::now(),
+                        consumption_type: None,
+                        calories: None,
+                        protein: None,
+                        fat: None,
+                        carbohydrate: None,
+                        image_key: None,
+                    },
+                }
+            })
+            .collect();
+
+        ConsumptionWithItems {
+            consumption: Consumption {
+                id,
+                user_id: UserId::new(1),
+                time: chrono::DateTime::parse_from_rfc3339("2024-01-01T12:00:00+00:00").unwrap(),
+                duration: chrono::TimeDelta::seconds(60),
+                consumption_type,
+                custom_type_id: None,
+                liquid_mls,
+                comments: None,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+                planned: false,
+            },
+            items,
+        }
+    }
+
+    #[test]
+    fn drink_without_ingredients_is_liquid() {
+        let c = make_consumption(ConsumptionType::Digest, Some(BigDecimal::from(250)), vec![]);
+        assert_eq!(c.intake(), Some(ConsumptionIntake::Liquid));
+    }
+
+    #[test]
+    fn ingredients_without_liquid_are_solid() {
+        let c = make_consumption(ConsumptionType::Digest, None, vec![None, None]);
+        assert_eq!(c.intake(), Some(ConsumptionIntake::Solid));
+    }
+
+    #[test]
+    fn liquid_and_solid_ingredients_are_mixed() {
+        let c = make_consumption(
+            ConsumptionType::Digest,
+            Some(BigDecimal::from(200)),
+            vec![Some(BigDecimal::from(200)), None],
+        );
+        assert_eq!(c.intake(), Some(ConsumptionIntake::Mixed));
+    }
+
+    #[test]
+    fn liquid_ingredients_are_liquid() {
+        let c = make_consumption(
+            ConsumptionType::Digest,
+            Some(BigDecimal::from(300)),
+            vec![Some(BigDecimal::from(250)), Some(BigDecimal::from(50))],
+        );
+        assert_eq!(c.intake(), Some(ConsumptionIntake::Liquid));
+    }
+
+    #[test]
+    fn matching_liquid_is_balanced() {
+        let c = make_consumption(
+            ConsumptionType::Digest,
+            Some(BigDecimal::from(300)),
+            vec![
+                Some(BigDecimal::from(250)),
+                Some(BigDecimal::from(50)),
+                None,
+            ],
+        );
+        assert!(c.liquid_balance().is_balanced());
+    }
+
+    #[test]
+    fn mismatched_liquid_is_not_balanced() {
+        let c = make_consumption(
+            ConsumptionType::Digest,
+            None,
+            vec![Some(BigDecimal::from(250))],
+        );
+        let balance = c.liquid_balance();
+        assert!(!balance.is_balanced());
+        assert_eq!(balance.consumption_mls, BigDecimal::from(0));
+        assert_eq!(balance.items_mls, BigDecimal::from(250));
+    }
+
+    #[test]
+    fn distribute_scales_liquid_ingredients() {
+        let c = make_consumption(
+            ConsumptionType::Digest,
+            Some(BigDecimal::from(600)),
+            vec![
+                Some(BigDecimal::from(250)),
+                Some(BigDecimal::from(50)),
+                None,
+            ],
+        );
+        let distributed = c.distribute_liquid_mls().unwrap();
+        let mls: Vec<_> = distributed.into_iter().map(|(_, mls)| mls).collect();
+        assert_eq!(mls, vec![BigDecimal::from(500), BigDecimal::from(100)]);
+    }
+
+    #[test]
+    fn distribute_rounds_to_two_places() {
+        let c = make_consumption(
+            ConsumptionType::Digest,
+            Some(BigDecimal::from(100)),
+            vec![Some(BigDecimal::from(1)), Some(BigDecimal::from(2))],
+        );
+        let distributed = c.distribute_liquid_mls().unwrap();
+        let mls: Vec<_> = distributed.into_iter().map(|(_, mls)| mls).collect();
+        assert_eq!(
+            mls,
+            vec![
+                "33.33".parse::<BigDecimal>().unwrap(),
+                "66.67".parse::<BigDecimal>().unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    fn distribute_without_liquid_ingredients_is_none() {
+        let c = make_consumption(
+            ConsumptionType::Digest,
+            Some(BigDecimal::from(100)),
+            vec![None],
+        );
+        assert_eq!(c.distribute_liquid_mls(), None);
+    }
+
+    #[test]
+    fn planned_consumption_is_pending_until_its_time() {
+        let mut c = make_consumption(ConsumptionType::Digest, None, vec![]).consumption;
+        let before = c.time.to_utc() - chrono::TimeDelta::minutes(1);
+        let after = c.time.to_utc() + chrono::TimeDelta::minutes(1);
+        assert!(!c.is_pending(before));
+
+        c.planned = true;
+        assert!(c.is_pending(before));
+        assert!(!c.is_pending(after));
+    }
+
+    #[test]
+    fn not_swallowed_has_no_intake() {
+        let c = make_consumption(
+            ConsumptionType::InhaleNose,
+            Some(BigDecimal::from(10)),
+            vec![],
+        );
+        assert_eq!(c.intake(), None);
+    }
 }
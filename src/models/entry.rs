@@ -1,13 +1,19 @@
-use chrono::{DateTime, FixedOffset};
+use std::str::FromStr;
+
+use chrono::{DateTime, FixedOffset, TimeDelta, Utc};
+use derive_enum_all_values::AllValues;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 use crate::models::{
-    Exercise, ExerciseId, HealthMetric, HealthMetricId, Note, NoteId, Reflux, RefluxId, Symptom,
+    Exercise, ExerciseId, FluidAdjustment, FluidAdjustmentId, HealthMetric, HealthMetricId,
+    MedicationDoseId, MedicationDoseWithMedication, Note, NoteId, Reflux, RefluxId, Symptom,
     SymptomId, WeeUrge, WeeUrgeId,
 };
 
 use super::{ConsumptionId, ConsumptionWithItems, Poo, PooId, Wee, WeeId};
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub enum EntryId {
     Poo(PooId),
     Wee(WeeId),
@@ -18,31 +24,212 @@ pub enum EntryId {
     Symptom(SymptomId),
     Reflux(RefluxId),
     Note(NoteId),
+    FluidAdjustment(FluidAdjustmentId),
+    Medication(MedicationDoseId),
 }
 
 impl EntryId {
     pub fn as_str(&self) -> String {
+        format!("{}-{}", self.kind_prefix(), self.as_inner())
+    }
+
+    /// The part of `as_str` naming the kind of entry.
+    pub fn kind_prefix(&self) -> &'static str {
+        match self {
+            EntryId::Poo(_) => "poo",
+            EntryId::Wee(_) => "wee",
+            EntryId::WeeUrge(_) => "wee-urgency",
+            EntryId::Consumption(_) => "consumption",
+            EntryId::Exercise(_) => "exercise",
+            EntryId::HealthMetric(_) => "health-metric",
+            EntryId::Symptom(_) => "symptom",
+            EntryId::Reflux(_) => "reflux",
+            EntryId::Note(_) => "note",
+            EntryId::FluidAdjustment(_) => "fluid-adjustment",
+            EntryId::Medication(_) => "medication",
+        }
+    }
+
+    /// The id within the entry's own table.
+    pub fn as_inner(&self) -> i64 {
+        match self {
+            EntryId::Poo(id) => id.as_inner(),
+            EntryId::Wee(id) => id.as_inner(),
+            EntryId::WeeUrge(id) => id.as_inner(),
+            EntryId::Consumption(id) => id.as_inner(),
+            EntryId::Exercise(id) => id.as_inner(),
+            EntryId::HealthMetric(id) => id.as_inner(),
+            EntryId::Symptom(id) => id.as_inner(),
+            EntryId::Reflux(id) => id.as_inner(),
+            EntryId::Note(id) => id.as_inner(),
+            EntryId::FluidAdjustment(id) => id.as_inner(),
+            EntryId::Medication(id) => id.as_inner(),
+        }
+    }
+
+    pub fn kind(&self) -> EntryKind {
+        match self {
+            EntryId::Poo(_) => EntryKind::Poo,
+            EntryId::Wee(_) => EntryKind::Wee,
+            EntryId::WeeUrge(_) => EntryKind::WeeUrge,
+            EntryId::Consumption(_) => EntryKind::Consumption,
+            EntryId::Exercise(_) => EntryKind::Exercise,
+            EntryId::HealthMetric(_) => EntryKind::HealthMetric,
+            EntryId::Symptom(_) => EntryKind::Symptom,
+            EntryId::Reflux(_) => EntryKind::Reflux,
+            EntryId::Note(_) => EntryKind::Note,
+            EntryId::FluidAdjustment(_) => EntryKind::FluidAdjustment,
+            EntryId::Medication(_) => EntryKind::Medication,
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("Failed to parse entry id")]
+pub struct EntryIdParseError;
+
+impl FromStr for EntryId {
+    type Err = EntryIdParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, id) = s.rsplit_once('-').ok_or(EntryIdParseError)?;
+        let id = id.parse::<i64>().map_err(|_| EntryIdParseError)?;
+        match kind {
+            "poo" => Ok(EntryId::Poo(PooId::new(id))),
+            "wee" => Ok(EntryId::Wee(WeeId::new(id))),
+            "wee-urgency" => Ok(EntryId::WeeUrge(WeeUrgeId::new(id))),
+            "consumption" => Ok(EntryId::Consumption(ConsumptionId::new(id))),
+            "exercise" => Ok(EntryId::Exercise(ExerciseId::new(id))),
+            "health-metric" => Ok(EntryId::HealthMetric(HealthMetricId::new(id))),
+            "symptom" => Ok(EntryId::Symptom(SymptomId::new(id))),
+            "reflux" => Ok(EntryId::Reflux(RefluxId::new(id))),
+            "note" => Ok(EntryId::Note(NoteId::new(id))),
+            "fluid-adjustment" => Ok(EntryId::FluidAdjustment(FluidAdjustmentId::new(id))),
+            "medication" => Ok(EntryId::Medication(MedicationDoseId::new(id))),
+            _ => Err(EntryIdParseError),
+        }
+    }
+}
+
+/// The type of an entry, without its id or data.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, AllValues)]
+pub enum EntryKind {
+    Poo,
+    Wee,
+    WeeUrge,
+    Consumption,
+    Exercise,
+    HealthMetric,
+    Symptom,
+    Reflux,
+    Note,
+    FluidAdjustment,
+    Medication,
+}
+
+impl EntryKind {
+    /// Matches the prefix used by [`EntryId::as_str`].
+    pub fn as_id(&self) -> &'static str {
+        match self {
+            EntryKind::Poo => "poo",
+            EntryKind::Wee => "wee",
+            EntryKind::WeeUrge => "wee-urgency",
+            EntryKind::Consumption => "consumption",
+            EntryKind::Exercise => "exercise",
+            EntryKind::HealthMetric => "health-metric",
+            EntryKind::Symptom => "symptom",
+            EntryKind::Reflux => "reflux",
+            EntryKind::Note => "note",
+            EntryKind::FluidAdjustment => "fluid-adjustment",
+            EntryKind::Medication => "medication",
+        }
+    }
+
+    pub fn as_title(&self) -> &'static str {
         match self {
-            EntryId::Poo(id) => format!("poo-{}", id.as_inner()),
-            EntryId::Wee(id) => format!("wee-{}", id.as_inner()),
-            EntryId::WeeUrge(id) => format!("wee-urgency-{}", id.as_inner()),
-            EntryId::Consumption(id) => format!("consumption-{}", id.as_inner()),
-            EntryId::Exercise(id) => format!("exercise-{}", id.as_inner()),
-            EntryId::HealthMetric(id) => format!("health-metric-{}", id.as_inner()),
-            EntryId::Symptom(id) => format!("symptom-{}", id.as_inner()),
-            EntryId::Reflux(id) => format!("reflux-{}", id.as_inner()),
-            EntryId::Note(id) => format!("note-{}", id.as_inner()),
+            EntryKind::Poo => "Poo",
+            EntryKind::Wee => "Wee",
+            EntryKind::WeeUrge => "Wee urge",
+            EntryKind::Consumption => "Consumption",
+            EntryKind::Exercise => "Exercise",
+            EntryKind::HealthMetric => "Health metric",
+            EntryKind::Symptom => "Symptom",
+            EntryKind::Reflux => "Reflux",
+            EntryKind::Note => "Note",
+            EntryKind::FluidAdjustment => "Fluid adjustment",
+            EntryKind::Medication => "Medication",
         }
     }
+
+    /// Longest duration that is plausible for this kind of entry. Anything
+    /// longer is probably a timer that was never stopped.
+    pub fn plausible_max_duration(&self) -> Option<TimeDelta> {
+        match self {
+            EntryKind::Wee => Some(TimeDelta::minutes(10)),
+            EntryKind::Poo => Some(TimeDelta::minutes(60)),
+            EntryKind::Consumption => Some(TimeDelta::hours(2)),
+            EntryKind::Reflux => Some(TimeDelta::hours(6)),
+            EntryKind::Exercise => Some(TimeDelta::hours(8)),
+            EntryKind::WeeUrge
+            | EntryKind::HealthMetric
+            | EntryKind::Symptom
+            | EntryKind::Note
+            | EntryKind::FluidAdjustment
+            | EntryKind::Medication => None,
+        }
+    }
+
+    /// Advisory message if `duration` is implausibly long for this kind.
+    pub fn duration_warning(&self, duration: TimeDelta) -> Option<String> {
+        let max = self.plausible_max_duration()?;
+        (duration > max).then(|| {
+            format!(
+                "{} usually takes no more than {} minutes, was the timer left running?",
+                self.as_title(),
+                max.num_minutes()
+            )
+        })
+    }
+}
+
+/// An identical entry created this recently is probably an accidental
+/// double submission rather than a second event.
+#[cfg(feature = "server")]
+pub fn duplicate_entry_window() -> TimeDelta {
+    TimeDelta::seconds(10)
+}
+
+/// Written as [`EntryKind::as_id`], to be used in URLs.
+impl std::fmt::Display for EntryKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_id())
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("Failed to parse entry kind")]
+pub struct EntryKindParseError;
+
+impl FromStr for EntryKind {
+    type Err = EntryKindParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        EntryKind::all_values()
+            .iter()
+            .find(|kind| kind.as_id() == s)
+            .copied()
+            .ok_or(EntryKindParseError)
+    }
 }
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum Event {
     Start,
     // End,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum EntryData {
     Poo(Poo),
     Wee(Wee),
@@ -53,9 +240,71 @@ pub enum EntryData {
     Symptom(Symptom),
     Reflux(Reflux),
     Note(Note),
+    FluidAdjustment(FluidAdjustment),
+    Medication(MedicationDoseWithMedication),
 }
 
-#[derive(Debug, Clone, PartialEq)]
+impl EntryData {
+    pub fn kind(&self) -> EntryKind {
+        match self {
+            EntryData::Poo(_) => EntryKind::Poo,
+            EntryData::Wee(_) => EntryKind::Wee,
+            EntryData::WeeUrge(_) => EntryKind::WeeUrge,
+            EntryData::Consumption(_) => EntryKind::Consumption,
+            EntryData::Exercise(_) => EntryKind::Exercise,
+            EntryData::HealthMetric(_) => EntryKind::HealthMetric,
+            EntryData::Symptom(_) => EntryKind::Symptom,
+            EntryData::Reflux(_) => EntryKind::Reflux,
+            EntryData::Note(_) => EntryKind::Note,
+            EntryData::FluidAdjustment(_) => EntryKind::FluidAdjustment,
+            EntryData::Medication(_) => EntryKind::Medication,
+        }
+    }
+}
+
+/// Position of an entry in the timeline, for paging newest first.
+///
+/// Entries at the same time are ordered by kind and then id, so every entry
+/// has its own position. Fetching the entries after a cursor never skips or
+/// repeats entries, even if new entries are added between pages.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct EntryCursor {
+    pub time: DateTime<Utc>,
+    pub id: EntryId,
+}
+
+#[derive(Error, Debug)]
+#[error("Failed to parse entry cursor")]
+pub struct EntryCursorParseError;
+
+/// Written as `<microseconds since epoch>_<entry id>`, to be used in URLs.
+impl std::fmt::Display for EntryCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}_{}", self.time.timestamp_micros(), self.id.as_str())
+    }
+}
+
+impl FromStr for EntryCursor {
+    type Err = EntryCursorParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (time, id) = s.split_once('_').ok_or(EntryCursorParseError)?;
+        let time = time.parse::<i64>().map_err(|_| EntryCursorParseError)?;
+        let time = DateTime::from_timestamp_micros(time).ok_or(EntryCursorParseError)?;
+        let id = id.parse().map_err(|_| EntryCursorParseError)?;
+        Ok(Self { time, id })
+    }
+}
+
+/// A chunk of entries, newest first, and the cursor to fetch the next older
+/// chunk from, if there may be more.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntryPage {
+    pub entries: Vec<Entry>,
+    pub next: Option<EntryCursor>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Entry {
     pub event: Event,
     pub time: DateTime<FixedOffset>,
@@ -63,6 +312,10 @@ pub struct Entry {
 }
 
 impl Entry {
+    pub fn kind(&self) -> EntryKind {
+        self.data.kind()
+    }
+
     pub fn get_id(&self) -> EntryId {
         match &self.data {
             EntryData::Poo(poo) => EntryId::Poo(poo.id),
@@ -74,6 +327,401 @@ impl Entry {
             EntryData::Symptom(symptom) => EntryId::Symptom(symptom.id),
             EntryData::Reflux(reflux) => EntryId::Reflux(reflux.id),
             EntryData::Note(note) => EntryId::Note(note.id),
+            EntryData::FluidAdjustment(fluid_adjustment) => {
+                EntryId::FluidAdjustment(fluid_adjustment.id)
+            }
+            EntryData::Medication(medication_dose) => EntryId::Medication(medication_dose.dose.id),
         }
     }
+
+    pub fn comments(&self) -> Option<&str> {
+        match &self.data {
+            EntryData::Poo(poo) => poo.comments.as_deref(),
+            EntryData::Wee(wee) => wee.comments.as_deref(),
+            EntryData::WeeUrge(wee_urge) => wee_urge.comments.as_deref(),
+            EntryData::Consumption(consumption) => consumption.consumption.comments.as_deref(),
+            EntryData::Exercise(exercise) => exercise.comments.as_deref(),
+            EntryData::HealthMetric(health_metric) => health_metric.comments.as_deref(),
+            EntryData::Symptom(symptom) => symptom.comments.as_deref(),
+            EntryData::Reflux(reflux) => reflux.comments.as_deref(),
+            EntryData::Note(note) => note.comments.as_deref(),
+            EntryData::FluidAdjustment(fluid_adjustment) => fluid_adjustment.comments.as_deref(),
+            EntryData::Medication(medication_dose) => medication_dose.dose.comments.as_deref(),
+        }
+    }
+
+    /// How long the entry lasted, zero for entries that are instantaneous.
+    pub fn duration(&self) -> TimeDelta {
+        match &self.data {
+            EntryData::Poo(poo) => poo.duration,
+            EntryData::Wee(wee) => wee.duration,
+            EntryData::Consumption(consumption) => consumption.consumption.duration,
+            EntryData::Exercise(exercise) => exercise.duration,
+            EntryData::Reflux(reflux) => reflux.duration,
+            EntryData::WeeUrge(_)
+            | EntryData::HealthMetric(_)
+            | EntryData::Symptom(_)
+            | EntryData::Note(_)
+            | EntryData::FluidAdjustment(_)
+            | EntryData::Medication(_) => TimeDelta::zero(),
+        }
+    }
+
+    /// Whether the entry started before `start`, such as one shown on a day
+    /// because it carried on past midnight from the day before.
+    pub fn starts_before(&self, start: DateTime<Utc>) -> bool {
+        self.time.with_timezone(&Utc) < start
+    }
+
+    pub fn day_span(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> DaySpan {
+        DaySpan::new(self.time.with_timezone(&Utc), self.duration(), start, end)
+    }
+
+    /// When the entry's record was created and last updated.
+    fn audit_times(&self) -> (DateTime<Utc>, DateTime<Utc>) {
+        match &self.data {
+            EntryData::Poo(poo) => (poo.created_at, poo.updated_at),
+            EntryData::Wee(wee) => (wee.created_at, wee.updated_at),
+            EntryData::WeeUrge(wee_urge) => (wee_urge.created_at, wee_urge.updated_at),
+            EntryData::Consumption(consumption) => (
+                consumption.consumption.created_at,
+                consumption.consumption.updated_at,
+            ),
+            EntryData::Exercise(exercise) => (exercise.created_at, exercise.updated_at),
+            EntryData::HealthMetric(health_metric) => {
+                (health_metric.created_at, health_metric.updated_at)
+            }
+            EntryData::Symptom(symptom) => (symptom.created_at, symptom.updated_at),
+            EntryData::Reflux(reflux) => (reflux.created_at, reflux.updated_at),
+            EntryData::Note(note) => (note.created_at, note.updated_at),
+            EntryData::FluidAdjustment(fluid_adjustment) => {
+                (fluid_adjustment.created_at, fluid_adjustment.updated_at)
+            }
+            EntryData::Medication(medication_dose) => (
+                medication_dose.dose.created_at,
+                medication_dose.dose.updated_at,
+            ),
+        }
+    }
+
+    /// When the entry was last edited, if that was well after it was
+    /// recorded. See [`edited_after_the_fact`].
+    pub fn edited_at(&self) -> Option<DateTime<Utc>> {
+        let (created_at, updated_at) = self.audit_times();
+        edited_after_the_fact(self.time.with_timezone(&Utc), created_at, updated_at)
+    }
+}
+
+/// Edits made within this long of recording an entry are just corrections.
+const EDIT_GRACE: TimeDelta = TimeDelta::hours(1);
+
+/// The update time of a record that was changed more than [`EDIT_GRACE`]
+/// after both the event and its creation, so it may not reflect what was
+/// noted at the time.
+pub fn edited_after_the_fact(
+    time: DateTime<Utc>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let recorded = created_at.max(time);
+    (updated_at - recorded > EDIT_GRACE).then_some(updated_at)
+}
+
+/// Whether an entry shown for a day actually started on the previous day, or
+/// keeps going into the next day.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct DaySpan {
+    pub from_previous_day: bool,
+    pub into_next_day: bool,
+}
+
+impl DaySpan {
+    pub fn new(
+        time: DateTime<Utc>,
+        duration: TimeDelta,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            from_previous_day: time < start,
+            into_next_day: time + duration > end,
+        }
+    }
+}
+
+impl From<Wee> for Entry {
+    fn from(wee: Wee) -> Self {
+        Self {
+            event: Event::Start,
+            time: wee.time,
+            data: EntryData::Wee(wee),
+        }
+    }
+}
+
+impl From<WeeUrge> for Entry {
+    fn from(wee_urge: WeeUrge) -> Self {
+        Self {
+            event: Event::Start,
+            time: wee_urge.time,
+            data: EntryData::WeeUrge(wee_urge),
+        }
+    }
+}
+
+impl From<Poo> for Entry {
+    fn from(poo: Poo) -> Self {
+        Self {
+            event: Event::Start,
+            time: poo.time,
+            data: EntryData::Poo(poo),
+        }
+    }
+}
+
+impl From<ConsumptionWithItems> for Entry {
+    fn from(consumption: ConsumptionWithItems) -> Self {
+        Self {
+            event: Event::Start,
+            time: consumption.consumption.time,
+            data: EntryData::Consumption(consumption),
+        }
+    }
+}
+
+impl From<Exercise> for Entry {
+    fn from(exercise: Exercise) -> Self {
+        Self {
+            event: Event::Start,
+            time: exercise.time,
+            data: EntryData::Exercise(exercise),
+        }
+    }
+}
+
+impl From<HealthMetric> for Entry {
+    fn from(health_metric: HealthMetric) -> Self {
+        Self {
+            event: Event::Start,
+            time: health_metric.time,
+            data: EntryData::HealthMetric(health_metric),
+        }
+    }
+}
+
+impl From<Symptom> for Entry {
+    fn from(symptom: Symptom) -> Self {
+        Self {
+            event: Event::Start,
+            time: symptom.time,
+            data: EntryData::Symptom(symptom),
+        }
+    }
+}
+
+impl From<Reflux> for Entry {
+    fn from(reflux: Reflux) -> Self {
+        Self {
+            event: Event::Start,
+            time: reflux.time,
+            data: EntryData::Reflux(reflux),
+        }
+    }
+}
+
+impl From<Note> for Entry {
+    fn from(note: Note) -> Self {
+        Self {
+            event: Event::Start,
+            time: note.time,
+            data: EntryData::Note(note),
+        }
+    }
+}
+
+impl From<FluidAdjustment> for Entry {
+    fn from(fluid_adjustment: FluidAdjustment) -> Self {
+        Self {
+            event: Event::Start,
+            time: fluid_adjustment.time,
+            data: EntryData::FluidAdjustment(fluid_adjustment),
+        }
+    }
+}
+
+impl From<MedicationDoseWithMedication> for Entry {
+    fn from(medication_dose: MedicationDoseWithMedication) -> Self {
+        Self {
+            event: Event::Start,
+            time: medication_dose.dose.time,
+            data: EntryData::Medication(medication_dose),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entry_id_round_trip() {
+        let ids = [
+            EntryId::Poo(PooId::new(1)),
+            EntryId::Wee(WeeId::new(2)),
+            EntryId::WeeUrge(WeeUrgeId::new(3)),
+            EntryId::Consumption(ConsumptionId::new(4)),
+            EntryId::Exercise(ExerciseId::new(5)),
+            EntryId::HealthMetric(HealthMetricId::new(6)),
+            EntryId::Symptom(SymptomId::new(7)),
+            EntryId::Reflux(RefluxId::new(8)),
+            EntryId::Note(NoteId::new(9)),
+            EntryId::FluidAdjustment(FluidAdjustmentId::new(10)),
+            EntryId::Medication(MedicationDoseId::new(11)),
+        ];
+        for id in ids {
+            assert_eq!(id.as_str().parse::<EntryId>().ok(), Some(id));
+        }
+    }
+
+    #[test]
+    fn duration_warning_only_past_the_kind_maximum() {
+        assert_eq!(
+            EntryKind::Wee.duration_warning(TimeDelta::minutes(10)),
+            None
+        );
+        assert!(
+            EntryKind::Wee
+                .duration_warning(TimeDelta::minutes(11))
+                .is_some()
+        );
+        assert_eq!(
+            EntryKind::Exercise.duration_warning(TimeDelta::minutes(11)),
+            None
+        );
+        assert_eq!(EntryKind::Note.duration_warning(TimeDelta::days(2)), None);
+    }
+
+    #[test]
+    fn entry_kind_round_trip() {
+        for kind in EntryKind::all_values() {
+            assert_eq!(kind.as_id().parse::<EntryKind>().ok(), Some(*kind));
+        }
+        assert_eq!(
+            EntryId::WeeUrge(WeeUrgeId::new(3)).kind(),
+            EntryKind::WeeUrge
+        );
+        assert!("unknown".parse::<EntryKind>().is_err());
+    }
+
+    #[test]
+    fn entry_kind_displays_as_id() {
+        for kind in EntryKind::all_values() {
+            assert_eq!(kind.to_string(), kind.as_id());
+            assert_eq!(kind.to_string().parse::<EntryKind>().ok(), Some(*kind));
+        }
+    }
+
+    #[test]
+    fn entry_cursor_round_trip() {
+        let cursor = EntryCursor {
+            time: "2026-01-01T06:00:00.123456Z".parse().unwrap(),
+            id: EntryId::WeeUrge(WeeUrgeId::new(3)),
+        };
+        assert_eq!(cursor.to_string(), "1767247200123456_wee-urgency-3");
+        assert_eq!(cursor.to_string().parse::<EntryCursor>().ok(), Some(cursor));
+    }
+
+    #[test]
+    fn entry_cursor_invalid() {
+        assert!("".parse::<EntryCursor>().is_err());
+        assert!("123".parse::<EntryCursor>().is_err());
+        assert!("abc_wee-1".parse::<EntryCursor>().is_err());
+        assert!("123_unknown-1".parse::<EntryCursor>().is_err());
+    }
+
+    #[test]
+    fn entry_id_invalid() {
+        assert!("".parse::<EntryId>().is_err());
+        assert!("wee".parse::<EntryId>().is_err());
+        assert!("wee-abc".parse::<EntryId>().is_err());
+        assert!("unknown-1".parse::<EntryId>().is_err());
+    }
+
+    fn day() -> (DateTime<Utc>, DateTime<Utc>) {
+        let start = "2026-01-01T06:00:00Z".parse().unwrap();
+        let end = "2026-01-02T06:00:00Z".parse().unwrap();
+        (start, end)
+    }
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn edited_soon_after_is_not_flagged() {
+        let time = utc("2024-01-01T10:00:00Z");
+        let updated = utc("2024-01-01T10:30:00Z");
+        assert_eq!(edited_after_the_fact(time, time, updated), None);
+    }
+
+    #[test]
+    fn edited_long_after_is_flagged() {
+        let time = utc("2024-01-01T10:00:00Z");
+        let updated = utc("2024-01-02T09:00:00Z");
+        assert_eq!(edited_after_the_fact(time, time, updated), Some(updated));
+    }
+
+    #[test]
+    fn late_entry_without_edits_is_not_flagged() {
+        let time = utc("2024-01-01T10:00:00Z");
+        let created = utc("2024-01-03T10:00:00Z");
+        assert_eq!(edited_after_the_fact(time, created, created), None);
+    }
+
+    #[test]
+    fn day_span_within_day() {
+        let (start, end) = day();
+        let time = "2026-01-01T12:00:00Z".parse().unwrap();
+        assert_eq!(
+            DaySpan::new(time, TimeDelta::minutes(30), start, end),
+            DaySpan::default()
+        );
+    }
+
+    #[test]
+    fn day_span_into_next_day() {
+        let (start, end) = day();
+        let time = "2026-01-02T05:30:00Z".parse().unwrap();
+        let span = DaySpan::new(time, TimeDelta::minutes(90), start, end);
+        assert!(!span.from_previous_day);
+        assert!(span.into_next_day);
+    }
+
+    #[test]
+    fn day_span_from_previous_day() {
+        let (start, end) = day();
+        let time = "2026-01-01T05:30:00Z".parse().unwrap();
+        let span = DaySpan::new(time, TimeDelta::minutes(90), start, end);
+        assert!(span.from_previous_day);
+        assert!(!span.into_next_day);
+    }
+
+    #[test]
+    fn day_span_ending_exactly_at_boundary() {
+        let (start, end) = day();
+        let time = "2026-01-02T05:00:00Z".parse().unwrap();
+        assert_eq!(
+            DaySpan::new(time, TimeDelta::hours(1), start, end),
+            DaySpan::default()
+        );
+    }
+
+    #[test]
+    fn day_span_whole_day() {
+        let (start, end) = day();
+        let time = "2026-01-01T05:00:00Z".parse().unwrap();
+        let span = DaySpan::new(time, TimeDelta::hours(26), start, end);
+        assert!(span.from_previous_day);
+        assert!(span.into_next_day);
+    }
 }
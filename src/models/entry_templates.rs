@@ -0,0 +1,209 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Entry, EntryData, EntryKind, NewConsumption, NewExercise, NewFluidAdjustment, NewHealthMetric,
+    NewMedicationDose, NewNote, NewPoo, NewReflux, NewSymptom, NewWee, NewWeeUrge, UserId,
+    new_request_from,
+};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct EntryTemplateId(i64);
+
+#[allow(dead_code)]
+impl EntryTemplateId {
+    pub fn new(id: i64) -> Self {
+        Self(id)
+    }
+    pub fn as_inner(self) -> i64 {
+        self.0
+    }
+}
+
+impl FromStr for EntryTemplateId {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.parse()?))
+    }
+}
+
+impl std::fmt::Display for EntryTemplateId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// The pre-filled fields of a template, as the request that would create
+/// the entry. The time and user are not used, the create dialog starts at
+/// the current time for the timeline's user.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum EntryTemplateData {
+    Poo(NewPoo),
+    Wee(NewWee),
+    WeeUrge(NewWeeUrge),
+    Consumption(NewConsumption),
+    Exercise(NewExercise),
+    HealthMetric(NewHealthMetric),
+    Symptom(NewSymptom),
+    Reflux(NewReflux),
+    Note(NewNote),
+    FluidAdjustment(NewFluidAdjustment),
+    Medication(NewMedicationDose),
+}
+
+impl EntryTemplateData {
+    pub fn kind(&self) -> EntryKind {
+        match self {
+            EntryTemplateData::Poo(_) => EntryKind::Poo,
+            EntryTemplateData::Wee(_) => EntryKind::Wee,
+            EntryTemplateData::WeeUrge(_) => EntryKind::WeeUrge,
+            EntryTemplateData::Consumption(_) => EntryKind::Consumption,
+            EntryTemplateData::Exercise(_) => EntryKind::Exercise,
+            EntryTemplateData::HealthMetric(_) => EntryKind::HealthMetric,
+            EntryTemplateData::Symptom(_) => EntryKind::Symptom,
+            EntryTemplateData::Reflux(_) => EntryKind::Reflux,
+            EntryTemplateData::Note(_) => EntryKind::Note,
+            EntryTemplateData::FluidAdjustment(_) => EntryKind::FluidAdjustment,
+            EntryTemplateData::Medication(_) => EntryKind::Medication,
+        }
+    }
+
+    /// The stored fields, without the kind.
+    pub fn payload(&self) -> Result<serde_json::Value, serde_json::Error> {
+        match self {
+            EntryTemplateData::Poo(new) => serde_json::to_value(new),
+            EntryTemplateData::Wee(new) => serde_json::to_value(new),
+            EntryTemplateData::WeeUrge(new) => serde_json::to_value(new),
+            EntryTemplateData::Consumption(new) => serde_json::to_value(new),
+            EntryTemplateData::Exercise(new) => serde_json::to_value(new),
+            EntryTemplateData::HealthMetric(new) => serde_json::to_value(new),
+            EntryTemplateData::Symptom(new) => serde_json::to_value(new),
+            EntryTemplateData::Reflux(new) => serde_json::to_value(new),
+            EntryTemplateData::Note(new) => serde_json::to_value(new),
+            EntryTemplateData::FluidAdjustment(new) => serde_json::to_value(new),
+            EntryTemplateData::Medication(new) => serde_json::to_value(new),
+        }
+    }
+
+    /// The reverse of [`EntryTemplateData::payload`].
+    pub fn from_payload(
+        kind: EntryKind,
+        payload: serde_json::Value,
+    ) -> Result<Self, serde_json::Error> {
+        use serde_json::from_value;
+
+        let data = match kind {
+            EntryKind::Poo => EntryTemplateData::Poo(from_value(payload)?),
+            EntryKind::Wee => EntryTemplateData::Wee(from_value(payload)?),
+            EntryKind::WeeUrge => EntryTemplateData::WeeUrge(from_value(payload)?),
+            EntryKind::Consumption => EntryTemplateData::Consumption(from_value(payload)?),
+            EntryKind::Exercise => EntryTemplateData::Exercise(from_value(payload)?),
+            EntryKind::HealthMetric => EntryTemplateData::HealthMetric(from_value(payload)?),
+            EntryKind::Symptom => EntryTemplateData::Symptom(from_value(payload)?),
+            EntryKind::Reflux => EntryTemplateData::Reflux(from_value(payload)?),
+            EntryKind::Note => EntryTemplateData::Note(from_value(payload)?),
+            EntryKind::FluidAdjustment => EntryTemplateData::FluidAdjustment(from_value(payload)?),
+            EntryKind::Medication => EntryTemplateData::Medication(from_value(payload)?),
+        };
+        Ok(data)
+    }
+
+    /// The fields of a saved entry, to be used again later.
+    ///
+    /// Links to other entries, like the urge a wee followed, only make sense
+    /// for the original entry so are dropped. Consumption ingredients are not
+    /// kept either.
+    pub fn from_entry(entry: &Entry) -> Result<Self, serde_json::Error> {
+        let data = match &entry.data {
+            EntryData::Poo(poo) => EntryTemplateData::Poo(new_request_from(poo)?),
+            EntryData::Wee(wee) => EntryTemplateData::Wee(NewWee {
+                wee_urge_id: None,
+                ..new_request_from(wee)?
+            }),
+            EntryData::WeeUrge(wee_urge) => EntryTemplateData::WeeUrge(new_request_from(wee_urge)?),
+            EntryData::Consumption(consumption) => EntryTemplateData::Consumption(NewConsumption {
+                planned: false,
+                ..new_request_from(&consumption.consumption)?
+            }),
+            EntryData::Exercise(exercise) => {
+                EntryTemplateData::Exercise(new_request_from(exercise)?)
+            }
+            EntryData::HealthMetric(health_metric) => {
+                EntryTemplateData::HealthMetric(new_request_from(health_metric)?)
+            }
+            EntryData::Symptom(symptom) => EntryTemplateData::Symptom(new_request_from(symptom)?),
+            EntryData::Reflux(reflux) => EntryTemplateData::Reflux(new_request_from(reflux)?),
+            EntryData::Note(note) => EntryTemplateData::Note(new_request_from(note)?),
+            EntryData::FluidAdjustment(fluid_adjustment) => {
+                EntryTemplateData::FluidAdjustment(new_request_from(fluid_adjustment)?)
+            }
+            EntryData::Medication(medication_dose) => {
+                EntryTemplateData::Medication(new_request_from(&medication_dose.dose)?)
+            }
+        };
+        Ok(data)
+    }
+}
+
+/// A named, pre-filled entry of one kind, for things that are logged often.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EntryTemplate {
+    pub id: EntryTemplateId,
+    pub user_id: UserId,
+    pub name: String,
+    pub data: EntryTemplateData,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl EntryTemplate {
+    pub fn kind(&self) -> EntryKind {
+        self.data.kind()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NewEntryTemplate {
+    pub user_id: UserId,
+    pub name: String,
+    pub data: EntryTemplateData,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::fixtures::wee;
+
+    #[test]
+    fn from_entry_drops_linked_wee_urge() {
+        let data = EntryTemplateData::from_entry(&Entry::from(wee())).unwrap();
+        let EntryTemplateData::Wee(new) = &data else {
+            panic!("expected a wee template, got {data:?}");
+        };
+        assert_eq!(data.kind(), EntryKind::Wee);
+        assert_eq!(new.mls, 250);
+        assert_eq!(new.comments.as_deref(), Some("after coffee"));
+        assert_eq!(new.wee_urge_id, None);
+    }
+
+    #[test]
+    fn template_data_round_trips_through_payload() {
+        let data = EntryTemplateData::from_entry(&Entry::from(wee())).unwrap();
+        let payload = data.payload().unwrap();
+        assert_eq!(
+            EntryTemplateData::from_payload(data.kind(), payload).unwrap(),
+            data
+        );
+    }
+
+    #[test]
+    fn from_payload_rejects_wrong_kind() {
+        let data = EntryTemplateData::from_entry(&Entry::from(wee())).unwrap();
+        let payload = data.payload().unwrap();
+        assert!(EntryTemplateData::from_payload(EntryKind::Exercise, payload).is_err());
+    }
+}
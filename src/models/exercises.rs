@@ -1,3 +1,4 @@
+use bigdecimal::{BigDecimal, RoundingMode};
 use chrono::Local;
 use derive_enum_all_values::AllValues;
 use std::str::FromStr;
@@ -131,7 +132,130 @@ impl From<ExerciseRpe> for i32 {
     }
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+/// Unit used to display exercise distances. Distances are always stored in
+/// kilometres.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq, AllValues)]
+pub enum DistanceUnit {
+    Kilometres,
+    Miles,
+}
+
+impl DistanceUnit {
+    pub fn as_id(&self) -> &'static str {
+        match self {
+            Self::Kilometres => "km",
+            Self::Miles => "mi",
+        }
+    }
+
+    pub fn as_title(&self) -> &'static str {
+        match self {
+            Self::Kilometres => "Kilometres",
+            Self::Miles => "Miles",
+        }
+    }
+
+    /// Convert a distance in kilometres to this unit.
+    pub fn convert_km(&self, km: &BigDecimal) -> BigDecimal {
+        match self {
+            Self::Kilometres => km.clone(),
+            // 1 mile is exactly 1.609344 km.
+            Self::Miles => km / BigDecimal::new(1_609_344.into(), 6),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct DistanceUnitParseError;
+
+impl FromStr for DistanceUnit {
+    type Err = DistanceUnitParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "km" => Ok(Self::Kilometres),
+            "mi" => Ok(Self::Miles),
+            _ => Err(DistanceUnitParseError),
+        }
+    }
+}
+
+/// Format a distance stored in kilometres in the given unit, to at most two
+/// decimal places.
+pub fn format_distance(km: &BigDecimal, unit: DistanceUnit) -> String {
+    let value = unit
+        .convert_km(km)
+        .with_scale_round(2, RoundingMode::HalfUp)
+        .to_string();
+    let value = if value.contains('.') {
+        value.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &value
+    };
+    format!("{value} {}", unit.as_id())
+}
+
+/// Heart rate training zone, as a percentage band of maximum heart rate.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq, AllValues)]
+pub enum HeartRateZone {
+    Zone1,
+    Zone2,
+    Zone3,
+    Zone4,
+    Zone5,
+}
+
+impl HeartRateZone {
+    pub fn as_title(&self) -> &'static str {
+        match self {
+            Self::Zone1 => "Zone 1 (very light)",
+            Self::Zone2 => "Zone 2 (light)",
+            Self::Zone3 => "Zone 3 (moderate)",
+            Self::Zone4 => "Zone 4 (hard)",
+            Self::Zone5 => "Zone 5 (maximum)",
+        }
+    }
+
+    /// Lower bound of the zone, as a percentage of maximum heart rate.
+    pub fn min_percent(&self) -> i32 {
+        match self {
+            Self::Zone1 => 50,
+            Self::Zone2 => 60,
+            Self::Zone3 => 70,
+            Self::Zone4 => 80,
+            Self::Zone5 => 90,
+        }
+    }
+}
+
+/// Heart rate zones derived from a maximum heart rate.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct HeartRateZones {
+    pub max_heart_rate: i32,
+}
+
+impl HeartRateZones {
+    pub fn new(max_heart_rate: i32) -> Self {
+        Self { max_heart_rate }
+    }
+
+    /// Lowest heart rate, in bpm, that falls within the zone.
+    pub fn min_heart_rate(&self, zone: HeartRateZone) -> i32 {
+        (self.max_heart_rate * zone.min_percent() + 99) / 100
+    }
+
+    /// The zone a heart rate falls in, or None if it is below zone 1.
+    /// Anything above the maximum heart rate is still zone 5.
+    pub fn classify(&self, heart_rate: i32) -> Option<HeartRateZone> {
+        HeartRateZone::all_values()
+            .iter()
+            .rev()
+            .find(|zone| heart_rate >= self.min_heart_rate(**zone))
+            .copied()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct ExerciseId(i64);
 
 #[allow(dead_code)]
@@ -170,6 +294,8 @@ pub struct Exercise {
     pub rpe: Option<ExerciseRpe>,
     pub exercise_type: ExerciseType,
     pub comments: Option<String>,
+    pub avg_heart_rate: Option<i32>,
+    pub max_heart_rate: Option<i32>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
@@ -179,6 +305,13 @@ impl Exercise {
     pub fn name(&self) -> String {
         self.time.with_timezone(&Local).time().to_string()
     }
+
+    /// Zone of the average heart rate. The whole duration of the exercise
+    /// is counted as time in this zone.
+    pub fn heart_rate_zone(&self, zones: &HeartRateZones) -> Option<HeartRateZone> {
+        self.avg_heart_rate
+            .and_then(|heart_rate| zones.classify(heart_rate))
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -192,9 +325,11 @@ pub struct NewExercise {
     pub rpe: Option<ExerciseRpe>,
     pub exercise_type: ExerciseType,
     pub comments: Option<String>,
+    pub avg_heart_rate: Option<i32>,
+    pub max_heart_rate: Option<i32>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct ChangeExercise {
     pub user_id: MaybeSet<UserId>,
     pub time: MaybeSet<chrono::DateTime<chrono::FixedOffset>>,
@@ -205,4 +340,67 @@ pub struct ChangeExercise {
     pub rpe: MaybeSet<Option<ExerciseRpe>>,
     pub exercise_type: MaybeSet<ExerciseType>,
     pub comments: MaybeSet<Option<String>>,
+    pub avg_heart_rate: MaybeSet<Option<i32>>,
+    pub max_heart_rate: MaybeSet<Option<i32>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decimal(s: &str) -> BigDecimal {
+        BigDecimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn format_distance_km() {
+        assert_eq!(
+            format_distance(&decimal("5.12345"), DistanceUnit::Kilometres),
+            "5.12 km"
+        );
+        assert_eq!(
+            format_distance(&decimal("5.125"), DistanceUnit::Kilometres),
+            "5.13 km"
+        );
+        assert_eq!(
+            format_distance(&decimal("5.50"), DistanceUnit::Kilometres),
+            "5.5 km"
+        );
+        assert_eq!(
+            format_distance(&decimal("10"), DistanceUnit::Kilometres),
+            "10 km"
+        );
+    }
+
+    #[test]
+    fn format_distance_miles() {
+        assert_eq!(
+            format_distance(&decimal("1.609344"), DistanceUnit::Miles),
+            "1 mi"
+        );
+        assert_eq!(
+            format_distance(&decimal("42.195"), DistanceUnit::Miles),
+            "26.22 mi"
+        );
+    }
+
+    #[test]
+    fn zone_bounds_follow_max_heart_rate() {
+        let zones = HeartRateZones::new(190);
+        assert_eq!(zones.min_heart_rate(HeartRateZone::Zone1), 95);
+        assert_eq!(zones.min_heart_rate(HeartRateZone::Zone3), 133);
+        assert_eq!(zones.min_heart_rate(HeartRateZone::Zone5), 171);
+    }
+
+    #[test]
+    fn classify_heart_rate() {
+        let zones = HeartRateZones::new(200);
+        assert_eq!(zones.classify(99), None);
+        assert_eq!(zones.classify(100), Some(HeartRateZone::Zone1));
+        assert_eq!(zones.classify(139), Some(HeartRateZone::Zone2));
+        assert_eq!(zones.classify(140), Some(HeartRateZone::Zone3));
+        assert_eq!(zones.classify(165), Some(HeartRateZone::Zone4));
+        assert_eq!(zones.classify(180), Some(HeartRateZone::Zone5));
+        assert_eq!(zones.classify(210), Some(HeartRateZone::Zone5));
+    }
 }
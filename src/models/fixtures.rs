@@ -0,0 +1,22 @@
+use chrono::{DateTime, TimeDelta, Utc};
+
+use super::{Urgency, UserId, Wee, WeeId, WeeUrgeId};
+
+/// A saved wee with every field filled in.
+pub fn wee() -> Wee {
+    let time = DateTime::parse_from_rfc3339("2024-01-01T12:00:00+11:00").unwrap();
+    Wee {
+        id: WeeId::new(3),
+        user_id: UserId::new(1),
+        time,
+        duration: TimeDelta::seconds(30),
+        urgency: Urgency::U2,
+        leakage: 0,
+        mls: 250,
+        colour: None,
+        comments: Some("after coffee".to_string()),
+        wee_urge_id: Some(WeeUrgeId::new(7)),
+        created_at: time.with_timezone(&Utc),
+        updated_at: time.with_timezone(&Utc),
+    }
+}
@@ -0,0 +1,86 @@
+use chrono::Local;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::MaybeSet;
+
+use super::UserId;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct FluidAdjustmentId(i64);
+
+#[allow(dead_code)]
+impl FluidAdjustmentId {
+    pub fn new(id: i64) -> Self {
+        Self(id)
+    }
+    pub fn as_inner(self) -> i64 {
+        self.0
+    }
+}
+
+impl FromStr for FluidAdjustmentId {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.parse()?))
+    }
+}
+
+impl std::fmt::Display for FluidAdjustmentId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A manual correction to the fluid balance, positive values are counted as
+/// fluid in and negative values as fluid out.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FluidAdjustment {
+    pub id: FluidAdjustmentId,
+    pub user_id: UserId,
+    pub time: chrono::DateTime<chrono::FixedOffset>,
+    pub mls: i32,
+    pub comments: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[allow(dead_code)]
+impl FluidAdjustment {
+    pub fn name(&self) -> String {
+        self.time.with_timezone(&Local).time().to_string()
+    }
+}
+
+/// Largest adjustment (in either direction) that is accepted.
+pub const MAX_FLUID_ADJUSTMENT_MLS: i32 = 5_000;
+
+pub fn check_fluid_adjustment_mls(mls: i32) -> Result<i32, String> {
+    if mls == 0 {
+        Err("Adjustment cannot be zero".to_string())
+    } else if mls.abs() > MAX_FLUID_ADJUSTMENT_MLS {
+        Err(format!(
+            "Adjustment must be between -{MAX_FLUID_ADJUSTMENT_MLS} and {MAX_FLUID_ADJUSTMENT_MLS}"
+        ))
+    } else {
+        Ok(mls)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NewFluidAdjustment {
+    pub user_id: UserId,
+    pub time: chrono::DateTime<chrono::FixedOffset>,
+    pub mls: i32,
+    pub comments: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ChangeFluidAdjustment {
+    pub user_id: MaybeSet<UserId>,
+    pub time: MaybeSet<chrono::DateTime<chrono::FixedOffset>>,
+    pub mls: MaybeSet<i32>,
+    pub comments: MaybeSet<Option<String>>,
+}
@@ -1,4 +1,6 @@
+use bigdecimal::{BigDecimal, RoundingMode};
 use chrono::Local;
+use derive_enum_all_values::AllValues;
 use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
@@ -7,7 +9,7 @@ use crate::models::MaybeSet;
 
 use super::UserId;
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct HealthMetricId(i64);
 
 #[allow(dead_code)]
@@ -34,6 +36,84 @@ impl std::fmt::Display for HealthMetricId {
     }
 }
 
+/// Unit blood glucose is shown and entered in. Readings are always stored in
+/// mmol/L, to one decimal place.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq, AllValues)]
+pub enum GlucoseUnit {
+    MmolPerLitre,
+    MgPerDecilitre,
+}
+
+impl GlucoseUnit {
+    pub fn as_id(&self) -> &'static str {
+        match self {
+            Self::MmolPerLitre => "mmol_l",
+            Self::MgPerDecilitre => "mg_dl",
+        }
+    }
+
+    pub fn as_title(&self) -> &'static str {
+        match self {
+            Self::MmolPerLitre => "Millimoles per litre (mmol/L)",
+            Self::MgPerDecilitre => "Milligrams per decilitre (mg/dL)",
+        }
+    }
+
+    pub fn as_unit(&self) -> &'static str {
+        match self {
+            Self::MmolPerLitre => "mmol/L",
+            Self::MgPerDecilitre => "mg/dL",
+        }
+    }
+
+    /// Number of decimal places readings are shown and entered with.
+    pub fn scale(&self) -> i64 {
+        match self {
+            Self::MmolPerLitre => 1,
+            Self::MgPerDecilitre => 0,
+        }
+    }
+
+    /// Largest reading that can be stored, in this unit.
+    pub fn max(&self) -> BigDecimal {
+        self.convert_mmol(&BigDecimal::from(50))
+    }
+
+    fn factor(&self) -> BigDecimal {
+        match self {
+            Self::MmolPerLitre => BigDecimal::from(1),
+            // 1 mmol/L of glucose is approximately 18 mg/dL.
+            Self::MgPerDecilitre => BigDecimal::from(18),
+        }
+    }
+
+    /// Convert a reading in mmol/L to this unit, rounded to its precision.
+    pub fn convert_mmol(&self, mmol: &BigDecimal) -> BigDecimal {
+        (mmol * self.factor()).with_scale_round(self.scale(), RoundingMode::HalfUp)
+    }
+
+    /// Convert a reading in this unit to mmol/L, rounded to the stored
+    /// precision.
+    pub fn convert_to_mmol(&self, value: &BigDecimal) -> BigDecimal {
+        (value / self.factor()).with_scale_round(1, RoundingMode::HalfUp)
+    }
+}
+
+#[derive(Debug)]
+pub struct GlucoseUnitParseError;
+
+impl FromStr for GlucoseUnit {
+    type Err = GlucoseUnitParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mmol_l" => Ok(Self::MmolPerLitre),
+            "mg_dl" => Ok(Self::MgPerDecilitre),
+            _ => Err(GlucoseUnitParseError),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct HealthMetric {
     pub id: HealthMetricId,
@@ -72,7 +152,7 @@ pub struct NewHealthMetric {
     pub comments: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct ChangeHealthMetric {
     pub user_id: MaybeSet<UserId>,
     pub time: MaybeSet<chrono::DateTime<chrono::FixedOffset>>,
@@ -85,3 +165,39 @@ pub struct ChangeHealthMetric {
     pub waist_circumference: MaybeSet<Option<bigdecimal::BigDecimal>>,
     pub comments: MaybeSet<Option<String>>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decimal(s: &str) -> BigDecimal {
+        BigDecimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn glucose_unit_round_trips_ids() {
+        for unit in GlucoseUnit::all_values() {
+            assert_eq!(GlucoseUnit::from_str(unit.as_id()).unwrap(), *unit);
+        }
+    }
+
+    #[test]
+    fn glucose_from_mmol() {
+        let unit = GlucoseUnit::MmolPerLitre;
+        assert_eq!(unit.convert_mmol(&decimal("5.6")), decimal("5.6"));
+
+        let unit = GlucoseUnit::MgPerDecilitre;
+        assert_eq!(unit.convert_mmol(&decimal("5.6")), decimal("101"));
+        assert_eq!(unit.max(), decimal("900"));
+    }
+
+    #[test]
+    fn glucose_to_mmol() {
+        let unit = GlucoseUnit::MmolPerLitre;
+        assert_eq!(unit.convert_to_mmol(&decimal("7.25")), decimal("7.3"));
+
+        let unit = GlucoseUnit::MgPerDecilitre;
+        assert_eq!(unit.convert_to_mmol(&decimal("100")), decimal("5.6"));
+        assert_eq!(unit.convert_to_mmol(&decimal("900")), decimal("50.0"));
+    }
+}
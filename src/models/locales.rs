@@ -0,0 +1,231 @@
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use derive_enum_all_values::AllValues;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq, Default, AllValues)]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+}
+
+#[derive(Error, Debug)]
+#[error("Failed to parse locale")]
+pub struct LocaleParseError;
+
+impl Locale {
+    pub fn as_id(&self) -> &'static str {
+        match self {
+            Self::En => "en",
+            Self::De => "de",
+        }
+    }
+
+    pub fn as_title(&self) -> &'static str {
+        match self {
+            Self::En => "English",
+            Self::De => "Deutsch",
+        }
+    }
+
+    /// Pick the first supported locale from an `Accept-Language` style list,
+    /// e.g. `de-AT,de;q=0.9,en;q=0.8`. Quality values are ignored; browsers
+    /// already list languages in order of preference.
+    pub fn from_accept_language(value: &str) -> Option<Self> {
+        value.split(',').find_map(|language| {
+            let language = language.split(';').next()?.trim();
+            let primary = language.split(['-', '_']).next()?;
+            primary.to_lowercase().parse().ok()
+        })
+    }
+}
+
+impl FromStr for Locale {
+    type Err = LocaleParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "en" => Ok(Self::En),
+            "de" => Ok(Self::De),
+            _ => Err(LocaleParseError),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq, AllValues)]
+pub enum DateFormat {
+    Iso,
+    DayMonthYear,
+    DayMonthYearDotted,
+    MonthDayYear,
+}
+
+#[derive(Error, Debug)]
+#[error("Failed to parse date format")]
+pub struct DateFormatParseError;
+
+impl DateFormat {
+    pub fn as_id(&self) -> &'static str {
+        match self {
+            Self::Iso => "iso",
+            Self::DayMonthYear => "dmy",
+            Self::DayMonthYearDotted => "dmy_dotted",
+            Self::MonthDayYear => "mdy",
+        }
+    }
+
+    pub fn as_title(&self) -> &'static str {
+        match self {
+            Self::Iso => "2025-12-31",
+            Self::DayMonthYear => "31/12/2025",
+            Self::DayMonthYearDotted => "31.12.2025",
+            Self::MonthDayYear => "12/31/2025",
+        }
+    }
+
+    fn pattern(&self) -> &'static str {
+        match self {
+            Self::Iso => "%Y-%m-%d",
+            Self::DayMonthYear => "%d/%m/%Y",
+            Self::DayMonthYearDotted => "%d.%m.%Y",
+            Self::MonthDayYear => "%m/%d/%Y",
+        }
+    }
+}
+
+impl FromStr for DateFormat {
+    type Err = DateFormatParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "iso" => Ok(Self::Iso),
+            "dmy" => Ok(Self::DayMonthYear),
+            "dmy_dotted" => Ok(Self::DayMonthYearDotted),
+            "mdy" => Ok(Self::MonthDayYear),
+            _ => Err(DateFormatParseError),
+        }
+    }
+}
+
+/// How dates and numbers are displayed, from the user's locale and
+/// preferences.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct FormatPrefs {
+    pub date_format: DateFormat,
+    pub decimal_separator: char,
+}
+
+impl Default for FormatPrefs {
+    fn default() -> Self {
+        Self::new(Locale::default(), None)
+    }
+}
+
+impl FormatPrefs {
+    pub fn new(locale: Locale, date_format: Option<DateFormat>) -> Self {
+        let (default_date_format, decimal_separator) = match locale {
+            Locale::En => (DateFormat::Iso, '.'),
+            Locale::De => (DateFormat::DayMonthYearDotted, ','),
+        };
+        Self {
+            date_format: date_format.unwrap_or(default_date_format),
+            decimal_separator,
+        }
+    }
+
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        date.format(self.date_format.pattern()).to_string()
+    }
+
+    pub fn format_decimal(&self, value: &BigDecimal) -> String {
+        value
+            .to_string()
+            .replace('.', &self.decimal_separator.to_string())
+    }
+}
+
+/// Date and time to the minute, in the entry's own time zone.
+pub fn format_datetime(dt: &DateTime<FixedOffset>, prefs: &FormatPrefs) -> String {
+    format!(
+        "{} {}",
+        prefs.format_date(dt.date_naive()),
+        dt.format("%H:%M")
+    )
+}
+
+/// Date and time to the second, including the time zone offset.
+pub fn format_datetime_long(dt: &DateTime<FixedOffset>, prefs: &FormatPrefs) -> String {
+    format!(
+        "{} {}",
+        prefs.format_date(dt.date_naive()),
+        dt.format("%H:%M:%S %:z")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339("2025-12-31T08:05:09+11:00").unwrap()
+    }
+
+    #[test]
+    fn format_datetime_default_is_iso() {
+        let prefs = FormatPrefs::default();
+        assert_eq!(format_datetime(&example(), &prefs), "2025-12-31 08:05");
+        assert_eq!(
+            format_datetime_long(&example(), &prefs),
+            "2025-12-31 08:05:09 +11:00"
+        );
+    }
+
+    #[test]
+    fn format_datetime_follows_locale() {
+        let prefs = FormatPrefs::new(Locale::De, None);
+        assert_eq!(format_datetime(&example(), &prefs), "31.12.2025 08:05");
+    }
+
+    #[test]
+    fn format_datetime_preference_overrides_locale() {
+        let prefs = FormatPrefs::new(Locale::De, Some(DateFormat::MonthDayYear));
+        assert_eq!(format_datetime(&example(), &prefs), "12/31/2025 08:05");
+    }
+
+    #[test]
+    fn format_decimal_separator() {
+        let value = BigDecimal::from_str("12.5").unwrap();
+        assert_eq!(
+            FormatPrefs::new(Locale::En, None).format_decimal(&value),
+            "12.5"
+        );
+        assert_eq!(
+            FormatPrefs::new(Locale::De, None).format_decimal(&value),
+            "12,5"
+        );
+    }
+
+    #[test]
+    fn accept_language_picks_first_supported() {
+        assert_eq!(
+            Locale::from_accept_language("fr-FR,de;q=0.9,en;q=0.8"),
+            Some(Locale::De)
+        );
+    }
+
+    #[test]
+    fn accept_language_ignores_region() {
+        assert_eq!(Locale::from_accept_language("en-AU"), Some(Locale::En));
+        assert_eq!(Locale::from_accept_language("DE_de"), Some(Locale::De));
+    }
+
+    #[test]
+    fn accept_language_unsupported() {
+        assert_eq!(Locale::from_accept_language("fr,ja;q=0.5"), None);
+        assert_eq!(Locale::from_accept_language(""), None);
+    }
+}
@@ -0,0 +1,78 @@
+use chrono::Local;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::MaybeSet;
+
+use super::{Medication, MedicationId, UserId};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct MedicationDoseId(i64);
+
+#[allow(dead_code)]
+impl MedicationDoseId {
+    pub fn new(id: i64) -> Self {
+        Self(id)
+    }
+    pub fn as_inner(self) -> i64 {
+        self.0
+    }
+}
+
+impl FromStr for MedicationDoseId {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.parse()?))
+    }
+}
+
+impl std::fmt::Display for MedicationDoseId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MedicationDose {
+    pub id: MedicationDoseId,
+    pub user_id: UserId,
+    pub medication_id: MedicationId,
+    pub time: chrono::DateTime<chrono::FixedOffset>,
+    pub dose: bigdecimal::BigDecimal,
+    pub comments: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[allow(dead_code)]
+impl MedicationDose {
+    pub fn name(&self) -> String {
+        self.time.with_timezone(&Local).time().to_string()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MedicationDoseWithMedication {
+    pub dose: MedicationDose,
+    pub medication: Medication,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NewMedicationDose {
+    pub user_id: UserId,
+    pub medication_id: MedicationId,
+    pub time: chrono::DateTime<chrono::FixedOffset>,
+    pub dose: bigdecimal::BigDecimal,
+    pub comments: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ChangeMedicationDose {
+    pub user_id: MaybeSet<UserId>,
+    pub medication_id: MaybeSet<MedicationId>,
+    pub time: MaybeSet<chrono::DateTime<chrono::FixedOffset>>,
+    pub dose: MaybeSet<bigdecimal::BigDecimal>,
+    pub comments: MaybeSet<Option<String>>,
+}
@@ -0,0 +1,105 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::MaybeSet;
+
+use super::{Consumable, ConsumableId, ConsumptionType, UserId};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MedicationId(i64);
+
+#[allow(dead_code)]
+impl MedicationId {
+    pub fn new(id: i64) -> Self {
+        Self(id)
+    }
+    pub fn as_inner(self) -> i64 {
+        self.0
+    }
+}
+
+impl FromStr for MedicationId {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.parse()?))
+    }
+}
+
+impl std::fmt::Display for MedicationId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A medication the user takes, the drug itself is a consumable.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct Medication {
+    pub id: MedicationId,
+    pub user_id: UserId,
+    pub consumable: Consumable,
+    pub dose: bigdecimal::BigDecimal,
+    pub dose_unit: String,
+    pub route: ConsumptionType,
+    /// Number of scheduled doses per day, `None` if taken as required.
+    pub doses_per_day: Option<i32>,
+    pub active: bool,
+    pub comments: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl Medication {
+    pub fn name(&self) -> String {
+        self.consumable.name.clone()
+    }
+
+    pub fn dose_string(&self) -> String {
+        format!("{} {}", self.dose, self.dose_unit)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NewMedication {
+    pub user_id: UserId,
+    pub consumable_id: ConsumableId,
+    pub dose: bigdecimal::BigDecimal,
+    pub dose_unit: String,
+    pub route: ConsumptionType,
+    pub doses_per_day: Option<i32>,
+    pub active: bool,
+    pub comments: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ChangeMedication {
+    pub user_id: MaybeSet<UserId>,
+    pub consumable_id: MaybeSet<ConsumableId>,
+    pub dose: MaybeSet<bigdecimal::BigDecimal>,
+    pub dose_unit: MaybeSet<String>,
+    pub route: MaybeSet<ConsumptionType>,
+    pub doses_per_day: MaybeSet<Option<i32>>,
+    pub active: MaybeSet<bool>,
+    pub comments: MaybeSet<Option<String>>,
+}
+
+/// Doses taken compared to doses scheduled for a medication over one day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MedicationAdherence {
+    pub medication: Medication,
+    pub taken: usize,
+}
+
+impl MedicationAdherence {
+    pub fn scheduled(&self) -> Option<usize> {
+        self.medication
+            .doses_per_day
+            .map(|doses| usize::try_from(doses).unwrap_or_default())
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.scheduled()
+            .is_none_or(|scheduled| self.taken >= scheduled)
+    }
+}
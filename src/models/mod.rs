@@ -1,20 +1,48 @@
 mod users;
+pub use users::ChangePreferences;
 pub use users::ChangeUser;
+#[cfg(feature = "server")]
+pub use users::LANDING_URL;
+pub use users::LandingDate;
 pub use users::NewUser;
+pub use users::Theme;
 pub use users::User;
 pub use users::UserId;
+pub use users::UserPage;
+pub use users::normalize_email;
+pub use users::post_login_url;
+
+mod api_tokens;
+pub use api_tokens::ApiScope;
+pub use api_tokens::ApiToken;
+pub use api_tokens::ApiTokenId;
+pub use api_tokens::CreatedApiToken;
+pub use api_tokens::NewApiToken;
+
+mod oidc;
+pub use oidc::OidcFailure;
+pub use oidc::OidcProvider;
+
+mod locales;
+pub use locales::{DateFormat, FormatPrefs, Locale, format_datetime, format_datetime_long};
 
 mod wees;
 pub use wees::ChangeWee;
 pub use wees::NewWee;
 pub use wees::Wee;
+pub use wees::WeeCoherenceThresholds;
 pub use wees::WeeId;
+pub use wees::wee_coherence_warning;
 
 mod wee_urges;
 pub use wee_urges::ChangeWeeUrge;
 pub use wee_urges::NewWeeUrge;
 pub use wee_urges::WeeUrge;
 pub use wee_urges::WeeUrgeId;
+#[cfg(feature = "server")]
+pub use wee_urges::can_link_wee_urge;
+#[cfg(feature = "server")]
+pub use wee_urges::wee_urge_link_window;
 
 mod poos;
 pub use poos::Bristol;
@@ -22,23 +50,32 @@ pub use poos::ChangePoo;
 pub use poos::NewPoo;
 pub use poos::Poo;
 pub use poos::PooId;
+pub use poos::PooSize;
 
 mod exercises;
 pub use exercises::ChangeExercise;
+pub use exercises::DistanceUnit;
 pub use exercises::Exercise;
 pub use exercises::ExerciseId;
 pub use exercises::ExerciseRpe;
 pub use exercises::ExerciseType;
+pub use exercises::HeartRateZone;
+pub use exercises::HeartRateZones;
 pub use exercises::NewExercise;
+pub use exercises::format_distance;
 
 mod symptoms;
 pub use symptoms::ChangeSymptom;
+pub use symptoms::MAX_SYMPTOM_REPEATS;
 pub use symptoms::NewSymptom;
 pub use symptoms::Symptom;
 pub use symptoms::SymptomId;
+pub use symptoms::check_symptom_extra_details;
+pub use symptoms::symptom_repeat_times;
 
 mod health_metrics;
 pub use health_metrics::ChangeHealthMetric;
+pub use health_metrics::GlucoseUnit;
 pub use health_metrics::HealthMetric;
 pub use health_metrics::HealthMetricId;
 pub use health_metrics::NewHealthMetric;
@@ -55,12 +92,91 @@ pub use notes::NewNote;
 pub use notes::Note;
 pub use notes::NoteId;
 
+pub mod fluid_adjustments;
+pub use fluid_adjustments::ChangeFluidAdjustment;
+pub use fluid_adjustments::FluidAdjustment;
+pub use fluid_adjustments::FluidAdjustmentId;
+pub use fluid_adjustments::NewFluidAdjustment;
+pub use fluid_adjustments::check_fluid_adjustment_mls;
+
+pub mod medications;
+pub use medications::ChangeMedication;
+pub use medications::Medication;
+pub use medications::MedicationAdherence;
+pub use medications::MedicationId;
+pub use medications::NewMedication;
+
+pub mod medication_doses;
+pub use medication_doses::ChangeMedicationDose;
+pub use medication_doses::MedicationDose;
+pub use medication_doses::MedicationDoseId;
+pub use medication_doses::MedicationDoseWithMedication;
+pub use medication_doses::NewMedicationDose;
+
+mod share_links;
+pub use share_links::CreatedShareLink;
+pub use share_links::NewShareLink;
+pub use share_links::ShareLink;
+pub use share_links::ShareLinkId;
+pub use share_links::SharedTimeline;
+
+mod colour_palettes;
+pub use colour_palettes::ChangeColourPalette;
+pub use colour_palettes::ColourPalette;
+pub use colour_palettes::ColourPaletteId;
+pub use colour_palettes::ColourPaletteKind;
+pub use colour_palettes::NewColourPalette;
+pub use colour_palettes::PaletteColour;
+pub use colour_palettes::format_palette_colours;
+pub use colour_palettes::parse_palette_colours;
+
+mod webhooks;
+pub use webhooks::CreatedWebhook;
+pub use webhooks::NewWebhook;
+pub use webhooks::Webhook;
+#[cfg(feature = "server")]
+pub use webhooks::WebhookAction;
+pub use webhooks::WebhookId;
+pub use webhooks::check_webhook_url;
+#[cfg(feature = "server")]
+pub use webhooks::is_public_ip;
+
+mod stats;
+pub use stats::ConsumableTotals;
+pub use stats::DurationKind;
+pub use stats::DurationStats;
+#[cfg(feature = "server")]
+pub use stats::MAX_CONSUMABLE_TOTALS_DAYS;
+pub use stats::WeeUrgeLatencyStats;
+#[cfg(feature = "server")]
+pub use stats::hour_of_day_histogram;
+
 mod entry;
 pub use entry::Entry;
+pub use entry::EntryCursor;
 pub use entry::EntryData;
 pub use entry::EntryId;
+pub use entry::EntryKind;
+pub use entry::EntryPage;
+#[cfg(feature = "server")]
+pub use entry::duplicate_entry_window;
+
+mod undo;
+pub use undo::UndoAction;
+pub use undo::change_request_from;
+pub use undo::new_request_from;
+
+mod entry_templates;
+pub use entry_templates::EntryTemplate;
+pub use entry_templates::EntryTemplateData;
+pub use entry_templates::EntryTemplateId;
+pub use entry_templates::NewEntryTemplate;
 
 mod timeline;
+pub use timeline::EntryGroup;
+pub use timeline::FluidBalance;
+pub use timeline::RangeOptions;
+pub use timeline::SortDirection;
 pub use timeline::Timeline;
 
 mod common;
@@ -68,21 +184,39 @@ pub use common::MaybeSet;
 pub use common::Urgency;
 
 mod consumables;
+pub use consumables::BarcodeProduct;
+pub use consumables::COMMENTS_PREVIEW_CHARS;
+pub use consumables::CONSUMABLE_SEARCH_LIMIT;
 pub use consumables::ChangeConsumable;
 pub use consumables::Consumable;
 pub use consumables::ConsumableId;
+pub use consumables::ConsumableSearchResults;
 pub use consumables::ConsumableUnit;
 pub use consumables::ConsumableWithItems;
+#[cfg(feature = "server")]
+pub use consumables::DUPLICATE_NAME_LIMIT;
 pub use consumables::NewConsumable;
+pub use consumables::check_consumable_lifetime;
+pub use consumables::comments_preview;
+pub use consumables::normalize_barcode;
 
 mod consumptions;
 pub use consumptions::ChangeConsumption;
 pub use consumptions::Consumption;
 pub use consumptions::ConsumptionId;
+pub use consumptions::ConsumptionIntake;
 pub use consumptions::ConsumptionType;
 pub use consumptions::ConsumptionWithItems;
+pub use consumptions::LiquidBalance;
 pub use consumptions::NewConsumption;
 
+mod consumption_types;
+pub use consumption_types::ConsumptionRoute;
+pub use consumption_types::CustomConsumptionType;
+pub use consumption_types::CustomConsumptionTypeId;
+pub use consumption_types::NewCustomConsumptionType;
+pub use consumption_types::check_consumption_type_icon;
+
 mod nested_consumables;
 pub use nested_consumables::ChangeNestedConsumable;
 pub use nested_consumables::ConsumableItem;
@@ -96,3 +230,10 @@ pub use consumption_consumables::ConsumptionConsumable;
 pub use consumption_consumables::ConsumptionConsumableId;
 pub use consumption_consumables::ConsumptionItem;
 pub use consumption_consumables::NewConsumptionConsumable;
+
+mod nutrition;
+pub use nutrition::Nutrition;
+pub use nutrition::NutritionTotals;
+
+#[cfg(test)]
+mod fixtures;
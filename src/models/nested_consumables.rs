@@ -53,6 +53,12 @@ impl ConsumableItem {
     pub fn new(nested: NestedConsumable, consumable: Consumable) -> Self {
         Self { nested, consumable }
     }
+
+    /// Whether a quantity was given; without one the ingredient is left out
+    /// of rollups.
+    pub fn is_quantity_specified(&self) -> bool {
+        self.nested.quantity.is_some()
+    }
 }
 
 #[allow(dead_code)]
@@ -65,7 +71,7 @@ pub struct NewNestedConsumable {
 }
 
 #[allow(dead_code)]
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct ChangeNestedConsumable {
     pub quantity: MaybeSet<Option<bigdecimal::BigDecimal>>,
     pub liquid_mls: MaybeSet<Option<bigdecimal::BigDecimal>>,
@@ -0,0 +1,287 @@
+#[cfg(feature = "server")]
+use std::collections::HashMap;
+use std::ops::AddAssign;
+
+use bigdecimal::BigDecimal;
+#[cfg(feature = "server")]
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "server")]
+use super::{Consumable, ConsumableId, ConsumableItem, ConsumptionWithItems};
+
+/// Energy in kcal and macronutrients in grams.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Nutrition {
+    pub calories: BigDecimal,
+    pub protein: BigDecimal,
+    pub fat: BigDecimal,
+    pub carbohydrate: BigDecimal,
+}
+
+#[cfg(feature = "server")]
+impl Nutrition {
+    fn scale(&self, factor: &BigDecimal) -> Self {
+        Self {
+            calories: &self.calories * factor,
+            protein: &self.protein * factor,
+            fat: &self.fat * factor,
+            carbohydrate: &self.carbohydrate * factor,
+        }
+    }
+}
+
+impl AddAssign for Nutrition {
+    fn add_assign(&mut self, other: Self) {
+        self.calories += other.calories;
+        self.protein += other.protein;
+        self.fat += other.fat;
+        self.carbohydrate += other.carbohydrate;
+    }
+}
+
+/// What was taken in over a period.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct NutritionTotals {
+    pub nutrition: Nutrition,
+    /// Items left out of the totals because there was no nutrition data or
+    /// no quantity to scale it by.
+    pub unknown_items: usize,
+}
+
+#[cfg(feature = "server")]
+impl NutritionTotals {
+    /// Sum nutrition across consumptions, recursing into the ingredients of
+    /// consumables that have no nutrition data of their own.
+    ///
+    /// `ingredients` maps each consumable to its nested consumables. A
+    /// consumable's ingredients make up one of it for counted units, or the
+    /// whole batch otherwise, so only the portion is applied to a batch.
+    /// Planned consumptions that have not happened yet are skipped.
+    pub fn new(
+        consumptions: &[ConsumptionWithItems],
+        ingredients: &HashMap<ConsumableId, Vec<ConsumableItem>>,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let mut totals = Self::default();
+        for consumption in consumptions {
+            if consumption.consumption.is_pending(now) {
+                continue;
+            }
+            for item in &consumption.items {
+                totals.add_consumable(
+                    &item.consumable,
+                    item.nested.quantity.as_ref(),
+                    &item.nested.portion,
+                    ingredients,
+                    &mut Vec::new(),
+                );
+            }
+        }
+        totals
+    }
+
+    fn add_consumable(
+        &mut self,
+        consumable: &Consumable,
+        quantity: Option<&BigDecimal>,
+        scale: &BigDecimal,
+        ingredients: &HashMap<ConsumableId, Vec<ConsumableItem>>,
+        path: &mut Vec<ConsumableId>,
+    ) {
+        if let Some(nutrition) = consumable.nutrition() {
+            match quantity {
+                Some(quantity) => {
+                    let factor = quantity / consumable.unit.nutrition_basis() * scale;
+                    self.nutrition += nutrition.scale(&factor);
+                }
+                None => self.unknown_items += 1,
+            }
+            return;
+        }
+
+        // Guard against a consumable that ends up containing itself.
+        let children = ingredients
+            .get(&consumable.id)
+            .filter(|children| !children.is_empty() && !path.contains(&consumable.id));
+        let Some(children) = children else {
+            self.unknown_items += 1;
+            return;
+        };
+
+        let scale = match (consumable.unit.is_count(), quantity) {
+            (true, Some(quantity)) => quantity * scale,
+            _ => scale.clone(),
+        };
+        path.push(consumable.id);
+        for child in children {
+            self.add_consumable(
+                &child.consumable,
+                child.nested.quantity.as_ref(),
+                &scale,
+                ingredients,
+                path,
+            );
+        }
+        path.pop();
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "server")]
+mod tests {
+    use super::*;
+    use crate::models::{
+        ConsumableUnit, Consumption, ConsumptionConsumable, ConsumptionConsumableId, ConsumptionId,
+        ConsumptionItem, ConsumptionType, NestedConsumable, NestedConsumableId, UserId,
+    };
+
+    fn make_consumable(id: i64, unit: ConsumableUnit, calories: Option<i64>) -> Consumable {
+        Consumable {
+            id: ConsumableId::new(id),
+            name: format!("consumable-{id}"),
+            brand: None,
+            barcode: None,
+            is_organic: false,
+            unit,
+            comments: None,
+            created: None,
+            destroyed: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            consumption_type: None,
+            calories: calories.map(BigDecimal::from),
+            protein: None,
+            fat: None,
+            carbohydrate: None,
+            image_key: None,
+        }
+    }
+
+    fn make_consumption(items: Vec<(Consumable, Option<i64>, &str)>) -> ConsumptionWithItems {
+        let id = ConsumptionId::new(1);
+        let items = items
+            .into_iter()
+            .map(|(consumable, quantity, portion)| ConsumptionItem {
+                nested: ConsumptionConsumable {
+                    id: ConsumptionConsumableId::new(id, consumable.id),
+                    quantity: quantity.map(BigDecimal::from),
+                    liquid_mls: None,
+                    portion: portion.parse().unwrap(),
+                    comments: None,
+                    created_at: Utc::now(),
+                    updated_at: Utc::now(),
+                },
+                consumable,
+            })
+            .collect();
+
+        ConsumptionWithItems {
+            consumption: Consumption {
+                id,
+                user_id: UserId::new(1),
+                time: DateTime::parse_from_rfc3339("2024-01-01T12:00:00+00:00").unwrap(),
+                duration: chrono::TimeDelta::seconds(60),
+                consumption_type: ConsumptionType::Digest,
+                custom_type_id: None,
+                liquid_mls: None,
+                comments: None,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+                planned: false,
+            },
+            items,
+        }
+    }
+
+    fn make_ingredient(parent: &Consumable, child: Consumable, quantity: i64) -> ConsumableItem {
+        ConsumableItem::new(
+            NestedConsumable {
+                id: NestedConsumableId::new(parent.id, child.id),
+                quantity: Some(BigDecimal::from(quantity)),
+                liquid_mls: None,
+                comments: None,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            },
+            child,
+        )
+    }
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2024-01-02T00:00:00+00:00")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn scales_by_quantity_and_portion() {
+        let apple = make_consumable(1, ConsumableUnit::Grams, Some(50));
+        let consumptions = [make_consumption(vec![(apple, Some(200), "0.5")])];
+
+        let totals = NutritionTotals::new(&consumptions, &HashMap::new(), now());
+
+        assert_eq!(totals.nutrition.calories, BigDecimal::from(50));
+        assert_eq!(totals.unknown_items, 0);
+    }
+
+    #[test]
+    fn counts_items_without_data_or_quantity_as_unknown() {
+        let apple = make_consumable(1, ConsumableUnit::Grams, Some(50));
+        let mystery = make_consumable(2, ConsumableUnit::Grams, None);
+        let consumptions = [make_consumption(vec![
+            (apple, None, "1"),
+            (mystery, Some(100), "1"),
+        ])];
+
+        let totals = NutritionTotals::new(&consumptions, &HashMap::new(), now());
+
+        assert_eq!(totals.nutrition, Nutrition::default());
+        assert_eq!(totals.unknown_items, 2);
+    }
+
+    #[test]
+    fn recurses_into_ingredients_per_item() {
+        let sandwich = make_consumable(1, ConsumableUnit::Number, None);
+        let bread = make_consumable(2, ConsumableUnit::Grams, Some(250));
+        let cheese = make_consumable(3, ConsumableUnit::Grams, None);
+        let ingredients = HashMap::from([(
+            sandwich.id,
+            vec![
+                make_ingredient(&sandwich, bread, 80),
+                make_ingredient(&sandwich, cheese, 20),
+            ],
+        )]);
+        let consumptions = [make_consumption(vec![(sandwich, Some(2), "1")])];
+
+        let totals = NutritionTotals::new(&consumptions, &ingredients, now());
+
+        assert_eq!(totals.nutrition.calories, BigDecimal::from(400));
+        assert_eq!(totals.unknown_items, 1);
+    }
+
+    #[test]
+    fn stops_at_a_consumable_that_contains_itself() {
+        let soup = make_consumable(1, ConsumableUnit::Millilitres, None);
+        let ingredients =
+            HashMap::from([(soup.id, vec![make_ingredient(&soup, soup.clone(), 100)])]);
+        let consumptions = [make_consumption(vec![(soup, Some(300), "1")])];
+
+        let totals = NutritionTotals::new(&consumptions, &ingredients, now());
+
+        assert_eq!(totals.unknown_items, 1);
+    }
+
+    #[test]
+    fn skips_pending_planned_consumptions() {
+        let apple = make_consumable(1, ConsumableUnit::Grams, Some(50));
+        let mut consumption = make_consumption(vec![(apple, Some(100), "1")]);
+        consumption.consumption.planned = true;
+        consumption.consumption.time =
+            DateTime::parse_from_rfc3339("2024-01-03T12:00:00+00:00").unwrap();
+
+        let totals = NutritionTotals::new(&[consumption], &HashMap::new(), now());
+
+        assert_eq!(totals, NutritionTotals::default());
+    }
+}
@@ -0,0 +1,113 @@
+use std::{convert::Infallible, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// An OIDC provider the user can log in with.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OidcProvider {
+    pub id: String,
+    pub name: String,
+}
+
+/// Why logging in with the OIDC provider failed, as explained to the user.
+///
+/// The details stay in the server logs; this only says enough for the user
+/// to know whether there is anything they can do about it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OidcFailure {
+    /// The user or the provider refused to grant access.
+    AccessDenied,
+    /// The account exists but has been disabled.
+    AccountDisabled,
+    /// The provider did not share a name or valid email address.
+    MissingDetails,
+    /// The login took too long or was completed twice.
+    Expired,
+    #[default]
+    Server,
+}
+
+impl OidcFailure {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OidcFailure::AccessDenied => "access_denied",
+            OidcFailure::AccountDisabled => "account_disabled",
+            OidcFailure::MissingDetails => "missing_details",
+            OidcFailure::Expired => "expired",
+            OidcFailure::Server => "server",
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            OidcFailure::AccessDenied => "Access was denied",
+            OidcFailure::AccountDisabled => "Your account is disabled",
+            OidcFailure::MissingDetails => "Some details are missing",
+            OidcFailure::Expired => "Your login expired",
+            OidcFailure::Server => "Something went wrong",
+        }
+    }
+
+    pub fn message(&self) -> &'static str {
+        match self {
+            OidcFailure::AccessDenied => {
+                "The login provider did not allow access. If you cancelled by mistake, try again."
+            }
+            OidcFailure::AccountDisabled => {
+                "Ask an administrator to enable your account before logging in."
+            }
+            OidcFailure::MissingDetails => {
+                "The login provider did not share your name and email address. Allow access to them and try again."
+            }
+            OidcFailure::Expired => "The login was not completed in time. Please try again.",
+            OidcFailure::Server => {
+                "We could not log you in because of a problem on our side. Try again later."
+            }
+        }
+    }
+
+    /// Whether trying again might help.
+    pub fn can_retry(&self) -> bool {
+        !matches!(self, OidcFailure::AccountDisabled)
+    }
+}
+
+impl FromStr for OidcFailure {
+    type Err = Infallible;
+
+    /// Unknown reasons are shown as a server error rather than failing the
+    /// whole route.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let failure = match s {
+            "access_denied" => OidcFailure::AccessDenied,
+            "account_disabled" => OidcFailure::AccountDisabled,
+            "missing_details" => OidcFailure::MissingDetails,
+            "expired" => OidcFailure::Expired,
+            _ => OidcFailure::Server,
+        };
+        Ok(failure)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_str() {
+        for failure in [
+            OidcFailure::AccessDenied,
+            OidcFailure::AccountDisabled,
+            OidcFailure::MissingDetails,
+            OidcFailure::Expired,
+            OidcFailure::Server,
+        ] {
+            assert_eq!(failure.as_str().parse(), Ok(failure));
+        }
+    }
+
+    #[test]
+    fn unknown_reason_is_a_server_error() {
+        assert_eq!("bogus".parse(), Ok(OidcFailure::Server));
+    }
+}
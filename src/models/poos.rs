@@ -7,8 +7,10 @@ use thiserror::Error;
 use crate::models::{MaybeSet, common::Urgency};
 
 use super::UserId;
+#[cfg(feature = "server")]
+use super::duplicate_entry_window;
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct PooId(i64);
 
 impl PooId {
@@ -113,6 +115,46 @@ impl Bristol {
     }
 }
 
+/// Human labels for ranges of the 0 to 10 poo quantity scale. The number is
+/// still what gets stored.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq, AllValues)]
+pub enum PooSize {
+    Nothing,
+    Small,
+    Medium,
+    Large,
+}
+
+impl PooSize {
+    pub fn from_quantity(quantity: i32) -> Self {
+        match quantity {
+            ..=0 => PooSize::Nothing,
+            1..=3 => PooSize::Small,
+            4..=6 => PooSize::Medium,
+            _ => PooSize::Large,
+        }
+    }
+
+    /// A typical quantity for this size, used when picking a size directly.
+    pub fn quantity(&self) -> i32 {
+        match self {
+            PooSize::Nothing => 0,
+            PooSize::Small => 2,
+            PooSize::Medium => 5,
+            PooSize::Large => 8,
+        }
+    }
+
+    pub fn as_title(&self) -> &'static str {
+        match self {
+            PooSize::Nothing => "Nothing",
+            PooSize::Small => "Small",
+            PooSize::Medium => "Medium",
+            PooSize::Large => "Large",
+        }
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Poo {
@@ -129,6 +171,12 @@ pub struct Poo {
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
+impl Poo {
+    pub fn name(&self) -> String {
+        self.time.with_timezone(&chrono::Local).time().to_string()
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct NewPoo {
@@ -142,8 +190,24 @@ pub struct NewPoo {
     pub comments: Option<String>,
 }
 
+impl NewPoo {
+    /// Does this look like an accidental resubmission of an existing poo?
+    #[cfg(feature = "server")]
+    pub fn is_duplicate_of(&self, poo: &Poo, now: chrono::DateTime<chrono::Utc>) -> bool {
+        now - poo.created_at <= duplicate_entry_window()
+            && self.user_id == poo.user_id
+            && self.time == poo.time
+            && self.duration == poo.duration
+            && self.urgency == poo.urgency
+            && self.quantity == poo.quantity
+            && self.bristol == poo.bristol
+            && self.colour == poo.colour
+            && self.comments == poo.comments
+    }
+}
+
 #[allow(dead_code)]
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct ChangePoo {
     pub user_id: MaybeSet<UserId>,
     pub time: MaybeSet<chrono::DateTime<chrono::FixedOffset>>,
@@ -154,3 +218,26 @@ pub struct ChangePoo {
     pub colour: MaybeSet<Option<palette::Hsv>>,
     pub comments: MaybeSet<Option<String>>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn poo_size_from_quantity() {
+        assert_eq!(PooSize::from_quantity(0), PooSize::Nothing);
+        assert_eq!(PooSize::from_quantity(1), PooSize::Small);
+        assert_eq!(PooSize::from_quantity(3), PooSize::Small);
+        assert_eq!(PooSize::from_quantity(4), PooSize::Medium);
+        assert_eq!(PooSize::from_quantity(6), PooSize::Medium);
+        assert_eq!(PooSize::from_quantity(7), PooSize::Large);
+        assert_eq!(PooSize::from_quantity(10), PooSize::Large);
+    }
+
+    #[test]
+    fn poo_size_quantity_is_within_size() {
+        for size in PooSize::all_values() {
+            assert_eq!(PooSize::from_quantity(size.quantity()), *size);
+        }
+    }
+}
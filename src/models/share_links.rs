@@ -0,0 +1,84 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{Entry, UserId};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ShareLinkId(i64);
+
+#[allow(dead_code)]
+impl ShareLinkId {
+    pub fn new(id: i64) -> Self {
+        Self(id)
+    }
+    pub fn as_inner(self) -> i64 {
+        self.0
+    }
+}
+
+impl FromStr for ShareLinkId {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.parse()?))
+    }
+}
+
+impl std::fmt::Display for ShareLinkId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A token that lets anyone holding it view one user's timeline for a fixed
+/// range of time, until it expires or is revoked. Only a hash of the token
+/// is stored, so the link cannot be shown again after creation.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ShareLink {
+    pub id: ShareLinkId,
+    pub user_id: UserId,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ShareLink {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at <= now
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NewShareLink {
+    pub user_id: UserId,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// A newly created share link, together with the token that is only
+/// available now.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CreatedShareLink {
+    pub share_link: ShareLink,
+    pub token: String,
+}
+
+impl CreatedShareLink {
+    pub fn path(&self) -> String {
+        format!("/shared/{}", self.token)
+    }
+}
+
+/// The read only view of a timeline given to the holder of a share link.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SharedTimeline {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub entries: Vec<Entry>,
+}
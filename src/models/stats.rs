@@ -0,0 +1,331 @@
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+#[cfg(feature = "server")]
+use chrono::{DateTime, FixedOffset, Timelike, Utc};
+use chrono::{NaiveDate, TimeDelta};
+#[cfg(feature = "server")]
+use chrono_tz::Tz;
+use derive_enum_all_values::AllValues;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "server")]
+use super::{Consumption, ConsumptionConsumable};
+
+/// Kinds of timeline entry that record a duration.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq, AllValues)]
+pub enum DurationKind {
+    Wee,
+    Poo,
+    Consumption,
+    Exercise,
+    Reflux,
+}
+
+impl DurationKind {
+    pub fn as_id(&self) -> &'static str {
+        match self {
+            Self::Wee => "wee",
+            Self::Poo => "poo",
+            Self::Consumption => "consumption",
+            Self::Exercise => "exercise",
+            Self::Reflux => "reflux",
+        }
+    }
+
+    pub fn as_title(&self) -> &'static str {
+        match self {
+            Self::Wee => "Wee",
+            Self::Poo => "Poo",
+            Self::Consumption => "Consumption",
+            Self::Exercise => "Exercise",
+            Self::Reflux => "Reflux",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct DurationKindParseError;
+
+impl FromStr for DurationKind {
+    type Err = DurationKindParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "wee" => Ok(Self::Wee),
+            "poo" => Ok(Self::Poo),
+            "consumption" => Ok(Self::Consumption),
+            "exercise" => Ok(Self::Exercise),
+            "reflux" => Ok(Self::Reflux),
+            _ => Err(DurationKindParseError),
+        }
+    }
+}
+
+/// Summary of entry durations over a period.
+///
+/// Entries with a zero duration were not timed, so they are included in
+/// `count` but left out of `timed_count` and the averages.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DurationStats {
+    pub count: i64,
+    pub timed_count: i64,
+    pub total: TimeDelta,
+    pub mean: Option<TimeDelta>,
+    pub median: Option<TimeDelta>,
+    pub p90: Option<TimeDelta>,
+}
+
+/// How long after an urge the linked wee happened, over a period.
+///
+/// Urges that were never followed by a linked wee count towards `urges` only.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WeeUrgeLatencyStats {
+    pub urges: i64,
+    pub linked: i64,
+    pub mean: Option<TimeDelta>,
+    pub median: Option<TimeDelta>,
+}
+
+/// Number of entries that happened in each hour of the day.
+///
+/// Hours are in the user's timezone, so an entry logged while travelling is
+/// counted in the hour it was at home. Without a timezone each entry's own
+/// recorded offset is used instead.
+#[cfg(feature = "server")]
+pub fn hour_of_day_histogram(times: &[DateTime<FixedOffset>], timezone: Option<Tz>) -> [usize; 24] {
+    let mut hours = [0; 24];
+    for time in times {
+        let hour = match timezone {
+            Some(timezone) => time.with_timezone(&timezone).hour(),
+            None => time.hour(),
+        };
+        hours[hour as usize] += 1;
+    }
+    hours
+}
+
+/// Longest range of days a consumable's totals can be asked for.
+#[cfg(feature = "server")]
+pub const MAX_CONSUMABLE_TOTALS_DAYS: i64 = 366;
+
+/// How much of a consumable was consumed on one day.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ConsumableDayTotal {
+    pub date: NaiveDate,
+    pub quantity: BigDecimal,
+    pub liquid_mls: BigDecimal,
+}
+
+/// How much of a consumable was consumed over a range of days, with the
+/// portion of each consumption applied.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ConsumableTotals {
+    pub consumptions: usize,
+    pub quantity: BigDecimal,
+    pub liquid_mls: BigDecimal,
+    /// Every day from the start to the end of the range, including those
+    /// with nothing consumed.
+    pub days: Vec<ConsumableDayTotal>,
+}
+
+#[cfg(feature = "server")]
+impl ConsumableTotals {
+    /// Totals for the days `start` to `end` inclusive. Days are in the
+    /// user's timezone and planned consumptions still to come are left out.
+    pub fn new(
+        start: NaiveDate,
+        end: NaiveDate,
+        items: &[(Consumption, ConsumptionConsumable)],
+        timezone: Option<Tz>,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let mut days: Vec<ConsumableDayTotal> = start
+            .iter_days()
+            .take_while(|date| *date <= end)
+            .map(|date| ConsumableDayTotal {
+                date,
+                quantity: BigDecimal::from(0),
+                liquid_mls: BigDecimal::from(0),
+            })
+            .collect();
+
+        let mut consumptions = 0;
+        for (consumption, item) in items {
+            if consumption.is_pending(now) {
+                continue;
+            }
+            let date = crate::dt::get_date_for_dt(consumption.time.to_utc(), timezone);
+            let Some(day) = days.iter_mut().find(|day| day.date == date) else {
+                continue;
+            };
+            consumptions += 1;
+            if let Some(quantity) = &item.quantity {
+                day.quantity += quantity * &item.portion;
+            }
+            if let Some(liquid_mls) = &item.liquid_mls {
+                day.liquid_mls += liquid_mls * &item.portion;
+            }
+        }
+
+        Self {
+            consumptions,
+            quantity: days.iter().map(|day| &day.quantity).sum(),
+            liquid_mls: days.iter().map(|day| &day.liquid_mls).sum(),
+            days,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "server")]
+    use crate::models::{
+        ConsumableId, ConsumptionConsumableId, ConsumptionId, ConsumptionType, UserId,
+    };
+    #[cfg(feature = "server")]
+    use std::str::FromStr;
+
+    #[cfg(feature = "server")]
+    fn consumed(
+        time: &str,
+        quantity: Option<i64>,
+        liquid_mls: Option<i64>,
+        portion: &str,
+    ) -> (Consumption, ConsumptionConsumable) {
+        let id = ConsumptionId::new(1);
+        let consumption = Consumption {
+            id,
+            user_id: UserId::new(1),
+            time: DateTime::parse_from_rfc3339(time).unwrap(),
+            duration: TimeDelta::minutes(5),
+            consumption_type: ConsumptionType::Digest,
+            custom_type_id: None,
+            liquid_mls: None,
+            comments: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            planned: false,
+        };
+        let item = ConsumptionConsumable {
+            id: ConsumptionConsumableId::new(id, ConsumableId::new(2)),
+            quantity: quantity.map(BigDecimal::from),
+            liquid_mls: liquid_mls.map(BigDecimal::from),
+            portion: BigDecimal::from_str(portion).unwrap(),
+            comments: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        (consumption, item)
+    }
+
+    #[cfg(feature = "server")]
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::from_str(s).unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "server")]
+    fn consumable_totals_sum_by_day_with_portions() {
+        let items = [
+            consumed("2024-01-01T09:00:00+00:00", Some(1), Some(250), "1"),
+            consumed("2024-01-01T15:00:00+00:00", Some(2), Some(250), "0.5"),
+            consumed("2024-01-03T09:00:00+00:00", None, Some(100), "1"),
+        ];
+        let totals = ConsumableTotals::new(
+            date("2024-01-01"),
+            date("2024-01-03"),
+            &items,
+            Some(chrono_tz::UTC),
+            Utc::now(),
+        );
+        assert_eq!(totals.consumptions, 3);
+        assert_eq!(totals.quantity, BigDecimal::from(2));
+        assert_eq!(totals.liquid_mls, BigDecimal::from(475));
+        assert_eq!(totals.days.len(), 3);
+        assert_eq!(totals.days[0].quantity, BigDecimal::from(2));
+        assert_eq!(totals.days[1].liquid_mls, BigDecimal::from(0));
+        assert_eq!(totals.days[2].liquid_mls, BigDecimal::from(100));
+    }
+
+    #[test]
+    #[cfg(feature = "server")]
+    fn consumable_totals_use_timezone_for_days() {
+        // 2024-01-01 20:00 UTC is the morning of 2 January in Melbourne.
+        let items = [consumed("2024-01-01T20:00:00+00:00", Some(1), None, "1")];
+        let totals = ConsumableTotals::new(
+            date("2024-01-01"),
+            date("2024-01-02"),
+            &items,
+            Some(chrono_tz::Australia::Melbourne),
+            Utc::now(),
+        );
+        assert_eq!(totals.days[0].quantity, BigDecimal::from(0));
+        assert_eq!(totals.days[1].quantity, BigDecimal::from(1));
+    }
+
+    #[test]
+    #[cfg(feature = "server")]
+    fn consumable_totals_skip_pending_and_out_of_range() {
+        let (mut planned, item) = consumed("2024-01-01T12:00:00+00:00", Some(5), None, "1");
+        planned.planned = true;
+        let items = [
+            (planned, item),
+            consumed("2024-01-05T12:00:00+00:00", Some(7), None, "1"),
+        ];
+        let now = DateTime::parse_from_rfc3339("2024-01-01T06:00:00+00:00")
+            .unwrap()
+            .to_utc();
+        let totals = ConsumableTotals::new(
+            date("2024-01-01"),
+            date("2024-01-02"),
+            &items,
+            Some(chrono_tz::UTC),
+            now,
+        );
+        assert_eq!(totals.consumptions, 0);
+        assert_eq!(totals.quantity, BigDecimal::from(0));
+    }
+
+    #[cfg(feature = "server")]
+    fn time(s: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(s).unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "server")]
+    fn histogram_uses_timezone() {
+        let times = [
+            time("2024-01-01T13:30:00+00:00"),
+            time("2024-01-01T14:10:00+00:00"),
+            time("2024-01-01T23:59:00+00:00"),
+        ];
+        let hours = hour_of_day_histogram(&times, Some(chrono_tz::Australia::Melbourne));
+        // Melbourne is UTC+11 in January.
+        assert_eq!(hours[0], 1);
+        assert_eq!(hours[1], 1);
+        assert_eq!(hours[10], 1);
+        assert_eq!(hours.iter().sum::<usize>(), 3);
+    }
+
+    #[test]
+    #[cfg(feature = "server")]
+    fn histogram_without_timezone_uses_entry_offset() {
+        let times = [
+            time("2024-01-01T22:30:00+10:00"),
+            time("2024-01-01T22:45:00-05:00"),
+        ];
+        let hours = hour_of_day_histogram(&times, None);
+        assert_eq!(hours[22], 2);
+        assert_eq!(hours.iter().sum::<usize>(), 2);
+    }
+
+    #[test]
+    fn duration_kind_round_trip() {
+        for kind in DurationKind::all_values() {
+            assert_eq!(kind.as_id().parse::<DurationKind>().ok(), Some(*kind));
+        }
+        assert!("note".parse::<DurationKind>().is_err());
+    }
+}
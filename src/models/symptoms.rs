@@ -2,12 +2,13 @@ use chrono::Local;
 use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 use crate::models::MaybeSet;
 
 use super::UserId;
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct SymptomId(i64);
 
 #[allow(dead_code)]
@@ -86,6 +87,62 @@ impl Symptom {
     pub fn name(&self) -> String {
         self.time.with_timezone(&Local).time().to_string()
     }
+
+    /// Each symptom's title and intensity, in the order shown in the form.
+    pub fn intensities(&self) -> [(&'static str, i32); 34] {
+        [
+            ("Appetite Loss", self.appetite_loss),
+            ("Fever", self.fever),
+            ("Cough", self.cough),
+            ("Sore Throat", self.sore_throat),
+            ("Nasal Symptom", self.nasal_symptom),
+            ("Sneezing", self.sneezing),
+            ("Heart Burn", self.heart_burn),
+            ("Abdominal Pain", self.abdominal_pain),
+            ("Diarrhea", self.diarrhea),
+            ("Constipation", self.constipation),
+            ("Lower Back Pain", self.lower_back_pain),
+            ("Upper Back Pain", self.upper_back_pain),
+            ("Neck Pain", self.neck_pain),
+            ("Shoulder Pain", self.shoulder_pain),
+            ("Joint Pain", self.joint_pain),
+            ("Headache", self.headache),
+            ("Nausea", self.nausea),
+            ("Dizziness", self.dizziness),
+            ("Stomach Ache", self.stomach_ache),
+            ("Chest Pain", self.chest_pain),
+            ("Shortness of Breath", self.shortness_of_breath),
+            ("Fatigue", self.fatigue),
+            ("Anxiety", self.anxiety),
+            ("Depression", self.depression),
+            ("Insomnia", self.insomnia),
+            ("Hand Pain", self.hand_pain),
+            ("Wrist Pain", self.wrist_pain),
+            ("Foot Pain", self.foot_pain),
+            ("Dental Pain", self.dental_pain),
+            ("Eye Pain", self.eye_pain),
+            ("Ear Pain", self.ear_pain),
+            ("Feeling Hot", self.feeling_hot),
+            ("Feeling Cold", self.feeling_cold),
+            ("Feeling Thirsty", self.feeling_thirsty),
+        ]
+    }
+}
+
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+#[error("Extra details must be empty if symptom intensity is 0")]
+pub struct SymptomExtraDetailsError;
+
+/// Extra details describe a symptom that is present, so they need a nonzero
+/// intensity. A present symptom may still be recorded without them.
+pub fn check_symptom_extra_details(
+    intensity: i32,
+    extra_details: Option<&str>,
+) -> Result<(), SymptomExtraDetailsError> {
+    match (intensity, extra_details) {
+        (0, Some(_)) => Err(SymptomExtraDetailsError),
+        _ => Ok(()),
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -133,6 +190,17 @@ pub struct NewSymptom {
 }
 
 impl NewSymptom {
+    /// Check every symptom that carries extra details.
+    #[cfg(feature = "server")]
+    pub fn check_extra_details(&self) -> Result<(), SymptomExtraDetailsError> {
+        check_symptom_extra_details(
+            self.nasal_symptom,
+            self.nasal_symptom_description.as_deref(),
+        )?;
+        check_symptom_extra_details(self.abdominal_pain, self.abdominal_pain_location.as_deref())?;
+        check_symptom_extra_details(self.dental_pain, self.dental_pain_description.as_deref())
+    }
+
     pub fn default(user_id: UserId, time: chrono::DateTime<chrono::FixedOffset>) -> Self {
         Self {
             user_id,
@@ -179,6 +247,36 @@ impl NewSymptom {
     }
 }
 
+/// Most copies that can be made of a symptom record at once.
+pub const MAX_SYMPTOM_REPEATS: usize = 50;
+
+/// Times from `start` to `end` inclusive, `every` apart.
+pub fn symptom_repeat_times(
+    start: chrono::DateTime<chrono::FixedOffset>,
+    end: chrono::DateTime<chrono::FixedOffset>,
+    every: chrono::TimeDelta,
+) -> Result<Vec<chrono::DateTime<chrono::FixedOffset>>, String> {
+    if every <= chrono::TimeDelta::zero() {
+        return Err("Interval must be positive".to_string());
+    }
+    if end < start {
+        return Err("End must not be before start".to_string());
+    }
+
+    let mut times = Vec::new();
+    let mut time = start;
+    while time <= end {
+        if times.len() >= MAX_SYMPTOM_REPEATS {
+            return Err(format!(
+                "Cannot repeat more than {MAX_SYMPTOM_REPEATS} times"
+            ));
+        }
+        times.push(time);
+        time += every;
+    }
+    Ok(times)
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct ChangeSymptom {
     pub user_id: MaybeSet<UserId>,
@@ -222,3 +320,73 @@ pub struct ChangeSymptom {
     pub feeling_thirsty: MaybeSet<i32>,
     pub comments: MaybeSet<Option<String>>,
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::DateTime;
+
+    use super::*;
+
+    #[cfg(feature = "server")]
+    fn new_symptom() -> NewSymptom {
+        let time = DateTime::parse_from_rfc3339("2025-01-01T12:00:00+11:00").unwrap();
+        NewSymptom::default(UserId::new(1), time)
+    }
+
+    #[test]
+    #[cfg(feature = "server")]
+    fn nasal_symptom_description_requires_intensity() {
+        let mut symptom = new_symptom();
+        symptom.nasal_symptom_description = Some("Runny".to_string());
+        assert_eq!(symptom.check_extra_details(), Err(SymptomExtraDetailsError));
+
+        symptom.nasal_symptom = 2;
+        assert_eq!(symptom.check_extra_details(), Ok(()));
+    }
+
+    #[test]
+    #[cfg(feature = "server")]
+    fn nasal_symptom_without_description_is_allowed() {
+        let mut symptom = new_symptom();
+        symptom.nasal_symptom = 10;
+        assert_eq!(symptom.check_extra_details(), Ok(()));
+    }
+
+    #[test]
+    #[cfg(feature = "server")]
+    fn abdominal_pain_location_requires_intensity() {
+        let mut symptom = new_symptom();
+        symptom.abdominal_pain_location = Some("Lower left".to_string());
+        assert_eq!(symptom.check_extra_details(), Err(SymptomExtraDetailsError));
+
+        symptom.abdominal_pain = 5;
+        assert_eq!(symptom.check_extra_details(), Ok(()));
+    }
+
+    #[test]
+    fn repeat_times_includes_both_ends() {
+        let start = DateTime::parse_from_rfc3339("2025-01-01T08:00:00+11:00").unwrap();
+        let end = DateTime::parse_from_rfc3339("2025-01-01T20:00:00+11:00").unwrap();
+        let times = symptom_repeat_times(start, end, chrono::TimeDelta::hours(4)).unwrap();
+        assert_eq!(times.len(), 4);
+        assert_eq!(times[0], start);
+        assert_eq!(times[3], end);
+    }
+
+    #[test]
+    fn repeat_times_rejects_bad_ranges() {
+        let start = DateTime::parse_from_rfc3339("2025-01-01T08:00:00+11:00").unwrap();
+        let end = start + chrono::TimeDelta::days(30);
+        assert!(symptom_repeat_times(start, end, chrono::TimeDelta::zero()).is_err());
+        assert!(symptom_repeat_times(end, start, chrono::TimeDelta::hours(4)).is_err());
+        assert!(symptom_repeat_times(start, end, chrono::TimeDelta::hours(1)).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "server")]
+    fn abdominal_pain_without_location_is_allowed() {
+        let mut symptom = new_symptom();
+        symptom.abdominal_pain = 10;
+        assert_eq!(symptom.check_extra_details(), Ok(()));
+    }
+}
@@ -1,11 +1,149 @@
+use std::{cmp::Ordering, mem::discriminant, str::FromStr};
+
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::models::{
+    ConsumptionIntake, Medication, MedicationAdherence, MedicationDoseWithMedication,
+};
+#[cfg(feature = "server")]
 use crate::models::{Exercise, HealthMetric, Symptom, WeeUrge};
 
+#[cfg(feature = "server")]
 use super::ConsumptionWithItems;
-use super::entry::{Entry, EntryData, Event};
+use super::entry::{Entry, EntryData};
+#[cfg(feature = "server")]
 use super::poos::Poo;
+#[cfg(feature = "server")]
 use super::wees::Wee;
 
-#[derive(Default)]
+/// Totals of fluid recorded in a timeline, in millilitres.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FluidBalance {
+    pub input: BigDecimal,
+    pub output: BigDecimal,
+    pub adjustment: BigDecimal,
+    /// Number of solid only consumptions, which add nothing to the input.
+    pub solids: usize,
+}
+
+impl FluidBalance {
+    pub fn net(&self) -> BigDecimal {
+        &self.input - &self.output + &self.adjustment
+    }
+
+    /// Add the fluid recorded by an entry. Planned consumptions that have
+    /// not happened yet by `now` are left out, as are entries that started
+    /// before `start` and so count towards an earlier day.
+    fn add_entry(&mut self, entry: &Entry, start: DateTime<Utc>, now: DateTime<Utc>) {
+        if entry.starts_before(start) {
+            return;
+        }
+        match &entry.data {
+            EntryData::Consumption(consumption) if consumption.consumption.is_pending(now) => {}
+            EntryData::Consumption(consumption) => match consumption.intake() {
+                Some(ConsumptionIntake::Liquid | ConsumptionIntake::Mixed) => {
+                    if let Some(liquid_mls) = &consumption.consumption.liquid_mls {
+                        self.input += liquid_mls;
+                    }
+                }
+                Some(ConsumptionIntake::Solid) => self.solids += 1,
+                None => {}
+            },
+            EntryData::Wee(wee) => self.output += BigDecimal::from(wee.mls),
+            EntryData::FluidAdjustment(fluid_adjustment) => {
+                self.adjustment += BigDecimal::from(fluid_adjustment.mls)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Order in which timeline entries are displayed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortDirection {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+#[derive(Error, Debug)]
+#[error("Failed to parse sort direction")]
+pub struct SortDirectionParseError;
+
+impl SortDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortDirection::Ascending => "asc",
+            SortDirection::Descending => "desc",
+        }
+    }
+
+    pub fn reverse(self) -> Self {
+        match self {
+            SortDirection::Ascending => SortDirection::Descending,
+            SortDirection::Descending => SortDirection::Ascending,
+        }
+    }
+}
+
+impl FromStr for SortDirection {
+    type Err = SortDirectionParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "asc" => Ok(SortDirection::Ascending),
+            "desc" => Ok(SortDirection::Descending),
+            _ => Err(SortDirectionParseError),
+        }
+    }
+}
+
+/// How the database should return the entries of one kind in a time range.
+///
+/// The default returns every entry in no particular order, for the day view
+/// which sorts the whole timeline itself. Longer ranges can ask for the
+/// entries in time order and stop after `limit` of them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RangeOptions {
+    pub sort: Option<SortDirection>,
+    pub limit: Option<i64>,
+}
+
+/// A run of consecutive timeline entries of the same kind.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntryGroup(Vec<Entry>);
+
+impl EntryGroup {
+    pub fn first(&self) -> &Entry {
+        &self.0[0]
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Entry> {
+        self.0.iter()
+    }
+
+    fn accepts(&self, entry: &Entry, window: TimeDelta) -> bool {
+        let Some(last) = self.0.last() else {
+            return false;
+        };
+        discriminant(&last.data) == discriminant(&entry.data)
+            && (entry.time - last.time).abs() <= window
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
 pub struct Timeline(Vec<Entry>);
 
 impl Timeline {
@@ -13,134 +151,208 @@ impl Timeline {
         Self::default()
     }
 
+    #[cfg(feature = "server")]
     pub fn add_wees(&mut self, wees: Vec<Wee>) {
         for wee in wees {
             self.add_wee(wee);
         }
     }
 
+    #[cfg(feature = "server")]
     pub fn add_wee(&mut self, wee: Wee) {
-        self.0.push(Entry {
-            event: Event::Start,
-            time: wee.time,
-            data: EntryData::Wee(wee),
-        });
+        self.0.push(wee.into());
     }
 
+    #[cfg(feature = "server")]
     pub fn add_wee_urges(&mut self, wee_urges: Vec<WeeUrge>) {
         for wee_urge in wee_urges {
             self.add_wee_urge(wee_urge);
         }
     }
 
+    #[cfg(feature = "server")]
     pub fn add_wee_urge(&mut self, wee_urge: WeeUrge) {
-        self.0.push(Entry {
-            event: Event::Start,
-            time: wee_urge.time,
-            data: EntryData::WeeUrge(wee_urge),
-        });
+        self.0.push(wee_urge.into());
     }
 
+    #[cfg(feature = "server")]
     pub fn add_poos(&mut self, poos: Vec<Poo>) {
         for poo in poos {
             self.add_poo(poo);
         }
     }
 
+    #[cfg(feature = "server")]
     pub fn add_poo(&mut self, poo: Poo) {
-        self.0.push(Entry {
-            event: Event::Start,
-            time: poo.time,
-            data: EntryData::Poo(poo),
-        });
-    }
-
-    pub fn add_consumptions(&mut self, consumptions: Vec<ConsumptionWithItems>) {
-        for consumption in consumptions {
-            self.add_consumption(consumption);
-        }
+        self.0.push(poo.into());
     }
 
+    #[cfg(feature = "server")]
     pub fn add_consumption(&mut self, consumption: ConsumptionWithItems) {
-        self.0.push(Entry {
-            event: Event::Start,
-            time: consumption.consumption.time,
-            data: EntryData::Consumption(consumption),
-        });
+        self.0.push(consumption.into());
     }
 
+    #[cfg(feature = "server")]
     pub fn add_exercises(&mut self, exercises: Vec<Exercise>) {
         for exercise in exercises {
             self.add_exercise(exercise);
         }
     }
 
+    #[cfg(feature = "server")]
     pub fn add_exercise(&mut self, exercise: Exercise) {
-        self.0.push(Entry {
-            event: Event::Start,
-            time: exercise.time,
-            data: EntryData::Exercise(exercise),
-        });
+        self.0.push(exercise.into());
     }
 
+    #[cfg(feature = "server")]
     pub fn add_health_metrics(&mut self, health_metrics: Vec<HealthMetric>) {
         for health_metric in health_metrics {
             self.add_health_metric(health_metric);
         }
     }
 
+    #[cfg(feature = "server")]
     pub fn add_health_metric(&mut self, health_metric: HealthMetric) {
-        self.0.push(Entry {
-            event: Event::Start,
-            time: health_metric.time,
-            data: EntryData::HealthMetric(health_metric),
-        });
+        self.0.push(health_metric.into());
     }
 
+    #[cfg(feature = "server")]
     pub fn add_symptoms(&mut self, symptoms: Vec<Symptom>) {
         for symptom in symptoms {
             self.add_symptom(symptom);
         }
     }
 
+    #[cfg(feature = "server")]
     pub fn add_symptom(&mut self, symptom: Symptom) {
-        self.0.push(Entry {
-            event: Event::Start,
-            time: symptom.time,
-            data: EntryData::Symptom(symptom),
-        });
+        self.0.push(symptom.into());
     }
 
+    #[cfg(feature = "server")]
     pub fn add_refluxs(&mut self, refluxs: Vec<crate::models::Reflux>) {
         for reflux in refluxs {
             self.add_reflux(reflux);
         }
     }
 
+    #[cfg(feature = "server")]
     pub fn add_reflux(&mut self, reflux: crate::models::Reflux) {
-        self.0.push(Entry {
-            event: Event::Start,
-            time: reflux.time,
-            data: EntryData::Reflux(reflux),
-        });
+        self.0.push(reflux.into());
     }
 
+    #[cfg(feature = "server")]
     pub fn add_notes(&mut self, notes: Vec<crate::models::Note>) {
         for note in notes {
             self.add_note(note);
         }
     }
 
+    #[cfg(feature = "server")]
     pub fn add_note(&mut self, note: crate::models::Note) {
-        self.0.push(Entry {
-            event: Event::Start,
-            time: note.time,
-            data: EntryData::Note(note),
-        });
+        self.0.push(note.into());
+    }
+
+    #[cfg(feature = "server")]
+    pub fn add_fluid_adjustments(
+        &mut self,
+        fluid_adjustments: Vec<crate::models::FluidAdjustment>,
+    ) {
+        for fluid_adjustment in fluid_adjustments {
+            self.add_fluid_adjustment(fluid_adjustment);
+        }
+    }
+
+    #[cfg(feature = "server")]
+    pub fn add_fluid_adjustment(&mut self, fluid_adjustment: crate::models::FluidAdjustment) {
+        self.0.push(fluid_adjustment.into());
+    }
+
+    pub fn add_medication_doses(&mut self, medication_doses: Vec<MedicationDoseWithMedication>) {
+        for medication_dose in medication_doses {
+            self.add_medication_dose(medication_dose);
+        }
     }
 
+    pub fn add_medication_dose(&mut self, medication_dose: MedicationDoseWithMedication) {
+        self.0.push(medication_dose.into());
+    }
+
+    #[cfg(feature = "server")]
     pub fn sort(&mut self) {
-        self.0.sort_by(|a, b| a.time.cmp(&b.time));
+        self.sort_by_direction(SortDirection::Ascending);
+    }
+
+    /// Sort by time, breaking ties on the entry id so that entries at the
+    /// same time keep a consistent order in both directions.
+    pub fn sort_by_direction(&mut self, direction: SortDirection) {
+        self.0.sort_by(|a, b| {
+            let ordering = compare_entries(a, b);
+            match direction {
+                SortDirection::Ascending => ordering,
+                SortDirection::Descending => ordering.reverse(),
+            }
+        });
+    }
+
+    /// The fluid balance from `start`. Entries that started earlier are left
+    /// out, as are planned consumptions that have not happened yet.
+    pub fn fluid_balance(&self, start: DateTime<Utc>) -> FluidBalance {
+        let mut balance = FluidBalance::default();
+        let now = Utc::now();
+        for entry in &self.0 {
+            balance.add_entry(entry, start, now);
+        }
+        balance
+    }
+
+    /// Each entry, in the timeline's order, paired with the fluid balance from
+    /// `start` up to and including it. The balance builds up in time order
+    /// however the timeline is sorted, and entries without fluid carry it
+    /// forward.
+    pub fn with_running_balance(&self, start: DateTime<Utc>) -> Vec<(&Entry, FluidBalance)> {
+        let now = Utc::now();
+        let mut order: Vec<usize> = (0..self.0.len()).collect();
+        order.sort_by(|&a, &b| compare_entries(&self.0[a], &self.0[b]));
+
+        let mut balances = vec![FluidBalance::default(); self.0.len()];
+        let mut balance = FluidBalance::default();
+        for index in order {
+            balance.add_entry(&self.0[index], start, now);
+            balances[index] = balance.clone();
+        }
+        self.0.iter().zip(balances).collect()
+    }
+
+    pub fn medication_adherence(&self, medications: Vec<Medication>) -> Vec<MedicationAdherence> {
+        medications
+            .into_iter()
+            .map(|medication| {
+                let taken = self
+                    .0
+                    .iter()
+                    .filter(|entry| {
+                        matches!(&entry.data, EntryData::Medication(medication_dose)
+                            if medication_dose.dose.medication_id == medication.id)
+                    })
+                    .count();
+                MedicationAdherence { medication, taken }
+            })
+            .collect()
+    }
+
+    /// Group consecutive entries of the same kind that are no more than
+    /// `window` apart. With no window every entry is in its own group.
+    pub fn grouped_entries(&self, window: Option<TimeDelta>) -> Vec<EntryGroup> {
+        let mut groups: Vec<EntryGroup> = Vec::new();
+        for entry in &self.0 {
+            match (groups.last_mut(), window) {
+                (Some(group), Some(window)) if group.accepts(entry, window) => {
+                    group.0.push(entry.clone());
+                }
+                _ => groups.push(EntryGroup(vec![entry.clone()])),
+            }
+        }
+        groups
     }
 
     pub fn iter(&self) -> std::slice::Iter<'_, Entry> {
@@ -152,6 +364,14 @@ impl Timeline {
     }
 }
 
+fn compare_entries(a: &Entry, b: &Entry) -> Ordering {
+    a.time.cmp(&b.time).then_with(|| compare_entry_ids(a, b))
+}
+
+fn compare_entry_ids(a: &Entry, b: &Entry) -> Ordering {
+    a.get_id().as_str().cmp(&b.get_id().as_str())
+}
+
 // impl IntoIterator for Timeline {
 //     type Item = Entry;
 //     type IntoIter = std::vec::IntoIter<Self::Item>;
@@ -160,3 +380,184 @@ impl Timeline {
 //         self.0.into_iter()
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::entry::DaySpan;
+    use crate::models::{
+        Consumption, ConsumptionId, ConsumptionType, ConsumptionWithItems, FluidAdjustment,
+        FluidAdjustmentId, Note, NoteId, Urgency, UserId, Wee, WeeId,
+    };
+
+    fn time(hour: u32) -> chrono::DateTime<chrono::FixedOffset> {
+        chrono::DateTime::parse_from_rfc3339(&format!("2025-01-01T{hour:02}:00:00+11:00")).unwrap()
+    }
+
+    fn wee(id: i64, hour: u32, mls: i32) -> Wee {
+        Wee {
+            id: WeeId::new(id),
+            user_id: UserId::new(1),
+            time: time(hour),
+            duration: TimeDelta::seconds(30),
+            urgency: Urgency::U2,
+            leakage: 0,
+            mls,
+            colour: None,
+            comments: None,
+            wee_urge_id: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn fluid_adjustment(id: i64, hour: u32, mls: i32) -> FluidAdjustment {
+        FluidAdjustment {
+            id: FluidAdjustmentId::new(id),
+            user_id: UserId::new(1),
+            time: time(hour),
+            mls,
+            comments: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn note(id: i64, hour: u32) -> Note {
+        Note {
+            id: NoteId::new(id),
+            user_id: UserId::new(1),
+            time: time(hour),
+            comments: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn timeline(direction: SortDirection) -> Timeline {
+        let mut timeline = Timeline(vec![
+            fluid_adjustment(1, 8, 500).into(),
+            wee(1, 9, 200).into(),
+            note(1, 10).into(),
+            wee(2, 11, 100).into(),
+        ]);
+        timeline.sort_by_direction(direction);
+        timeline
+    }
+
+    fn day_start() -> DateTime<Utc> {
+        time(6).with_timezone(&Utc)
+    }
+
+    fn nets(timeline: &Timeline) -> Vec<BigDecimal> {
+        timeline
+            .with_running_balance(day_start())
+            .into_iter()
+            .map(|(_, balance)| balance.net())
+            .collect()
+    }
+
+    #[test]
+    fn running_balance_accumulates_in_time_order() {
+        let timeline = timeline(SortDirection::Ascending);
+        assert_eq!(
+            nets(&timeline),
+            [500, 300, 300, 200].map(BigDecimal::from).to_vec()
+        );
+    }
+
+    #[test]
+    fn running_balance_follows_descending_order() {
+        let timeline = timeline(SortDirection::Descending);
+        assert_eq!(
+            nets(&timeline),
+            [200, 300, 300, 500].map(BigDecimal::from).to_vec()
+        );
+        let (_, last) = timeline.with_running_balance(day_start()).remove(0);
+        assert_eq!(last, timeline.fluid_balance(day_start()));
+    }
+
+    fn drink(id: i64, hour: u32, duration: TimeDelta, mls: i32) -> ConsumptionWithItems {
+        ConsumptionWithItems {
+            consumption: Consumption {
+                id: ConsumptionId::new(id),
+                user_id: UserId::new(1),
+                time: time(hour),
+                duration,
+                consumption_type: ConsumptionType::Digest,
+                custom_type_id: None,
+                liquid_mls: Some(BigDecimal::from(mls)),
+                comments: None,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+                planned: false,
+            },
+            items: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn balance_leaves_out_entries_from_previous_day() {
+        // Started before the day began and finished after, so it is shown on
+        // both days but only counts towards the first.
+        let mut timeline = timeline(SortDirection::Ascending);
+        timeline.0.extend([
+            Entry::from(drink(1, 5, TimeDelta::hours(2), 400)),
+            Entry::from(drink(2, 7, TimeDelta::minutes(5), 100)),
+        ]);
+        timeline.sort_by_direction(SortDirection::Ascending);
+
+        assert_eq!(
+            nets(&timeline),
+            [0, 100, 600, 400, 400, 300].map(BigDecimal::from).to_vec()
+        );
+        assert_eq!(
+            timeline.fluid_balance(day_start()).input,
+            BigDecimal::from(100)
+        );
+
+        // The day before counts it.
+        let previous_day_start = day_start() - TimeDelta::days(1);
+        assert_eq!(
+            timeline.fluid_balance(previous_day_start).input,
+            BigDecimal::from(500)
+        );
+    }
+
+    #[test]
+    fn entry_spanning_midnight_is_shown_on_both_days_but_counted_once() {
+        // 23:30 for two hours, so it finishes at 01:30 the next day.
+        let mut drink = drink(1, 23, TimeDelta::hours(2), 300);
+        drink.consumption.time += TimeDelta::minutes(30);
+        let timeline = Timeline(vec![Entry::from(drink)]);
+        let entry = &timeline.0[0];
+
+        let first_day = time(0).with_timezone(&Utc);
+        let second_day = first_day + TimeDelta::days(1);
+        let third_day = second_day + TimeDelta::days(1);
+
+        assert_eq!(
+            entry.day_span(first_day, second_day),
+            DaySpan {
+                from_previous_day: false,
+                into_next_day: true,
+            }
+        );
+        assert_eq!(
+            entry.day_span(second_day, third_day),
+            DaySpan {
+                from_previous_day: true,
+                into_next_day: false,
+            }
+        );
+
+        assert_eq!(
+            timeline.fluid_balance(first_day).input,
+            BigDecimal::from(300)
+        );
+        assert_eq!(
+            timeline.fluid_balance(second_day).input,
+            BigDecimal::from(0)
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+
+use super::{Entry, EntryId};
+
+/// The most recent change to an entry, with enough detail to reverse it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum UndoAction {
+    /// The entry was created, undo by deleting it.
+    Created(EntryId),
+    /// The entry was edited, undo by putting back how it was before.
+    Updated(Box<Entry>),
+    /// The entry was deleted, undo by creating it again.
+    Deleted(Box<Entry>),
+}
+
+impl UndoAction {
+    pub fn entry_id(&self) -> EntryId {
+        match self {
+            UndoAction::Created(id) => *id,
+            UndoAction::Updated(entry) | UndoAction::Deleted(entry) => entry.get_id(),
+        }
+    }
+
+    pub fn title(&self) -> String {
+        let kind = self.entry_id().kind().as_title().to_lowercase();
+        match self {
+            UndoAction::Created(_) => format!("Undo adding {kind}"),
+            UndoAction::Updated(_) => format!("Undo changes to {kind}"),
+            UndoAction::Deleted(_) => format!("Undo deleting {kind}"),
+        }
+    }
+}
+
+/// A `New*` request that creates a saved entry again.
+///
+/// The request types use the same field names as the entries they create, so
+/// this copies the shared fields and drops the rest, like the id.
+pub fn new_request_from<T: DeserializeOwned>(
+    saved: &impl Serialize,
+) -> Result<T, serde_json::Error> {
+    serde_json::to_value(saved).and_then(serde_json::from_value)
+}
+
+/// A `Change*` request that sets every field back to the saved entry.
+pub fn change_request_from<T: DeserializeOwned>(
+    saved: &impl Serialize,
+) -> Result<T, serde_json::Error> {
+    let mut value = serde_json::to_value(saved)?;
+    if let Value::Object(fields) = &mut value {
+        for field in fields.values_mut() {
+            *field = json!({ "Set": field.take() });
+        }
+    }
+    serde_json::from_value(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ChangeWee, MaybeSet, NewWee, fixtures::wee};
+
+    #[test]
+    fn new_request_copies_saved_fields() {
+        let wee = wee();
+        let request: NewWee = new_request_from(&wee).unwrap();
+        assert_eq!(
+            request,
+            NewWee {
+                user_id: wee.user_id,
+                time: wee.time,
+                duration: wee.duration,
+                urgency: wee.urgency,
+                leakage: wee.leakage,
+                mls: wee.mls,
+                colour: wee.colour,
+                comments: wee.comments.clone(),
+                wee_urge_id: wee.wee_urge_id,
+            }
+        );
+    }
+
+    #[test]
+    fn change_request_sets_every_field() {
+        let wee = wee();
+        let request: ChangeWee = change_request_from(&wee).unwrap();
+        assert_eq!(
+            request,
+            ChangeWee {
+                user_id: MaybeSet::Set(wee.user_id),
+                time: MaybeSet::Set(wee.time),
+                duration: MaybeSet::Set(wee.duration),
+                urgency: MaybeSet::Set(wee.urgency),
+                leakage: MaybeSet::Set(wee.leakage),
+                mls: MaybeSet::Set(wee.mls),
+                colour: MaybeSet::Set(wee.colour),
+                comments: MaybeSet::Set(wee.comments.clone()),
+                wee_urge_id: MaybeSet::Set(wee.wee_urge_id),
+            }
+        );
+    }
+
+    #[test]
+    fn title_names_the_change() {
+        let wee = wee();
+        assert_eq!(
+            UndoAction::Created(EntryId::Wee(wee.id)).title(),
+            "Undo adding wee"
+        );
+        assert_eq!(
+            UndoAction::Deleted(Box::new(wee.into())).title(),
+            "Undo deleting wee"
+        );
+    }
+}
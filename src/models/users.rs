@@ -1,7 +1,8 @@
 use std::str::FromStr;
 
-use crate::models::MaybeSet;
+use crate::models::{DateFormat, DistanceUnit, GlucoseUnit, Locale, MaybeSet};
 
+use derive_enum_all_values::AllValues;
 use serde::{Deserialize, Serialize};
 
 // Types from database::models that frontend requires. This excludes secrets such as the users password.
@@ -32,6 +33,93 @@ impl std::fmt::Display for UserId {
     }
 }
 
+/// Which day the timeline opens on. Not having a preference means today.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize, AllValues)]
+pub enum LandingDate {
+    Yesterday,
+    LastEntry,
+}
+
+impl LandingDate {
+    pub fn as_id(&self) -> &'static str {
+        match self {
+            Self::Yesterday => "yesterday",
+            Self::LastEntry => "last_entry",
+        }
+    }
+
+    pub fn as_title(&self) -> &'static str {
+        match self {
+            Self::Yesterday => "Yesterday",
+            Self::LastEntry => "Last day with entries",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct LandingDateParseError;
+
+impl FromStr for LandingDate {
+    type Err = LandingDateParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "yesterday" => Ok(Self::Yesterday),
+            "last_entry" => Ok(Self::LastEntry),
+            _ => Err(LandingDateParseError),
+        }
+    }
+}
+
+/// Colour theme the app is shown in. Not having a preference follows the
+/// system setting.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize, AllValues)]
+pub enum Theme {
+    Light,
+    Dark,
+}
+
+impl Theme {
+    pub fn as_id(&self) -> &'static str {
+        match self {
+            Self::Light => "light",
+            Self::Dark => "dark",
+        }
+    }
+
+    pub fn as_title(&self) -> &'static str {
+        match self {
+            Self::Light => "Light",
+            Self::Dark => "Dark",
+        }
+    }
+
+    /// The preference after this one when cycling through system, light and
+    /// dark.
+    pub fn next(theme: Option<Theme>) -> Option<Theme> {
+        match theme {
+            None => Some(Self::Light),
+            Some(Self::Light) => Some(Self::Dark),
+            Some(Self::Dark) => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ThemeParseError;
+
+impl FromStr for Theme {
+    type Err = ThemeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "light" => Ok(Self::Light),
+            "dark" => Ok(Self::Dark),
+            _ => Err(ThemeParseError),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
 pub struct User {
     pub id: UserId,
@@ -42,6 +130,33 @@ pub struct User {
     pub is_admin: bool,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub volume_presets: Vec<i32>,
+    pub group_window_minutes: Option<i32>,
+    pub locale: Option<Locale>,
+    pub date_format: Option<DateFormat>,
+    pub last_login_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub is_active: bool,
+    pub landing_date: Option<LandingDate>,
+    /// Timezone used to decide which day it is, or the device's if unset.
+    pub timezone: Option<chrono_tz::Tz>,
+    /// Maximum heart rate, used to work out exercise heart rate zones.
+    pub max_heart_rate: Option<i32>,
+    /// Unit distances are shown in, or kilometres if unset.
+    pub distance_unit: Option<DistanceUnit>,
+    /// Durations at least this many minutes long are shown rounded to the
+    /// nearest minute, or an hour if unset.
+    pub duration_rounding_minutes: Option<i32>,
+    /// Unit blood glucose is shown and entered in, or mmol/L if unset.
+    pub glucose_unit: Option<GlucoseUnit>,
+    /// Colour theme, or the system setting if unset.
+    pub theme: Option<Theme>,
+}
+
+/// A page of users, with the total number of users matching the search.
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct UserPage {
+    pub users: Vec<User>,
+    pub total: i64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -54,7 +169,7 @@ pub struct NewUser {
     pub is_admin: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct ChangeUser {
     pub username: MaybeSet<String>,
     // pub password: MaybeSet<String>,
@@ -62,4 +177,152 @@ pub struct ChangeUser {
     pub oidc_id: MaybeSet<Option<String>>,
     pub email: MaybeSet<String>,
     pub is_admin: MaybeSet<bool>,
+    pub is_active: MaybeSet<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ChangePreferences {
+    pub volume_presets: MaybeSet<Vec<i32>>,
+    pub group_window_minutes: MaybeSet<Option<i32>>,
+    pub locale: MaybeSet<Option<Locale>>,
+    pub date_format: MaybeSet<Option<DateFormat>>,
+    pub landing_date: MaybeSet<Option<LandingDate>>,
+    pub timezone: MaybeSet<Option<chrono_tz::Tz>>,
+    pub max_heart_rate: MaybeSet<Option<i32>>,
+    pub distance_unit: MaybeSet<Option<DistanceUnit>>,
+    pub duration_rounding_minutes: MaybeSet<Option<i32>>,
+    pub glucose_unit: MaybeSet<Option<GlucoseUnit>>,
+    pub theme: MaybeSet<Option<Theme>>,
+}
+
+/// Characters allowed in the local part of an email address, besides
+/// letters and digits.
+const EMAIL_LOCAL_SPECIAL: &str = ".!#$%&'*+/=?^_`{|}~-";
+
+/// Validate an email address and return it in lower case, so addresses that
+/// only differ in case are treated as the same.
+///
+/// This accepts the common `local@domain.tld` form, including plus addressing
+/// such as `name+tag@example.com`, but not quoted local parts or IP address
+/// domains.
+pub fn normalize_email(email: &str) -> Result<String, String> {
+    let email = email.trim().to_lowercase();
+
+    let (local, domain) = email
+        .split_once('@')
+        .ok_or("Email should contain @".to_string())?;
+
+    if local.is_empty() || local.len() > 64 {
+        return Err("Email should have 1 to 64 characters before the @".to_string());
+    }
+    if !local
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || EMAIL_LOCAL_SPECIAL.contains(c))
+    {
+        return Err("Email contains invalid characters before the @".to_string());
+    }
+    if local.starts_with('.') || local.ends_with('.') || local.contains("..") {
+        return Err("Email has misplaced dots before the @".to_string());
+    }
+
+    let labels: Vec<&str> = domain.split('.').collect();
+    if labels.len() < 2 || domain.len() > 253 {
+        return Err("Email domain should look like example.com".to_string());
+    }
+    for label in &labels {
+        if label.is_empty()
+            || label.len() > 63
+            || label.starts_with('-')
+            || label.ends_with('-')
+            || !label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        {
+            return Err(format!("Email domain {domain} is invalid"));
+        }
+    }
+
+    Ok(email)
+}
+
+/// Page that opens the timeline on the user's landing date.
+pub const LANDING_URL: &str = "/landing";
+
+/// Where to go after logging in: back to `next` if it is a page on this site,
+/// otherwise the landing page.
+pub fn post_login_url(next: &str) -> &str {
+    let is_local_path =
+        next.starts_with('/') && !next.starts_with("//") && !next.starts_with("/\\");
+    if is_local_path && next != "/" && !next.starts_with("/login") {
+        next
+    } else {
+        LANDING_URL
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn theme_cycles_back_to_system() {
+        assert_eq!(Theme::next(None), Some(Theme::Light));
+        assert_eq!(Theme::next(Some(Theme::Light)), Some(Theme::Dark));
+        assert_eq!(Theme::next(Some(Theme::Dark)), None);
+        for theme in Theme::all_values() {
+            assert_eq!(theme.as_id().parse::<Theme>().ok(), Some(*theme));
+        }
+    }
+
+    #[test]
+    fn post_login_returns_to_local_pages() {
+        assert_eq!(post_login_url("/consumables"), "/consumables");
+        assert_eq!(
+            post_login_url("/2024-01-01?sort=Ascending"),
+            "/2024-01-01?sort=Ascending"
+        );
+    }
+
+    #[test]
+    fn post_login_lands_on_the_timeline_otherwise() {
+        assert_eq!(post_login_url(""), LANDING_URL);
+        assert_eq!(post_login_url("/"), LANDING_URL);
+        assert_eq!(post_login_url("/login"), LANDING_URL);
+        assert_eq!(post_login_url("//evil.example"), LANDING_URL);
+        assert_eq!(post_login_url("/\\evil.example"), LANDING_URL);
+        assert_eq!(post_login_url("https://evil.example/"), LANDING_URL);
+    }
+
+    #[test]
+    fn normalize_email_lower_cases() {
+        assert_eq!(
+            normalize_email(" Alice.Smith@Example.COM ").as_deref(),
+            Ok("alice.smith@example.com")
+        );
+    }
+
+    #[test]
+    fn normalize_email_accepts_plus_addressing() {
+        assert_eq!(
+            normalize_email("Bob+Nurse@Mail.Example.org").as_deref(),
+            Ok("bob+nurse@mail.example.org")
+        );
+    }
+
+    #[test]
+    fn normalize_email_rejects_invalid() {
+        for email in [
+            "",
+            "bob",
+            "@example.com",
+            "bob@",
+            "bob@localhost",
+            "bob@@example.com",
+            "bob@example..com",
+            "bob@-example.com",
+            "bob smith@example.com",
+            ".bob@example.com",
+            "bob..smith@example.com",
+        ] {
+            assert!(normalize_email(email).is_err(), "{email} should be invalid");
+        }
+    }
 }
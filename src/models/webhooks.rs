@@ -0,0 +1,255 @@
+use std::net::{IpAddr, Ipv4Addr};
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{EntryKind, UserId};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct WebhookId(i64);
+
+#[allow(dead_code)]
+impl WebhookId {
+    pub fn new(id: i64) -> Self {
+        Self(id)
+    }
+    pub fn as_inner(self) -> i64 {
+        self.0
+    }
+}
+
+impl FromStr for WebhookId {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.parse()?))
+    }
+}
+
+impl std::fmt::Display for WebhookId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// What happened to the entry a webhook is being told about.
+#[cfg(feature = "server")]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookAction {
+    Created,
+    Updated,
+    Deleted,
+}
+
+#[cfg(feature = "server")]
+impl WebhookAction {
+    pub fn as_id(&self) -> &'static str {
+        match self {
+            Self::Created => "created",
+            Self::Updated => "updated",
+            Self::Deleted => "deleted",
+        }
+    }
+}
+
+/// A URL that is sent a signed POST request whenever an entry changes.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Webhook {
+    pub id: WebhookId,
+    pub user_id: UserId,
+    pub url: String,
+    /// The kinds of entry that trigger the webhook, empty for all kinds.
+    pub kinds: Vec<EntryKind>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[cfg(feature = "server")]
+impl Webhook {
+    pub fn wants(&self, kind: EntryKind) -> bool {
+        self.is_active && (self.kinds.is_empty() || self.kinds.contains(&kind))
+    }
+}
+
+/// Webhooks must be an absolute https URL for a public host, as the server
+/// posts to them. Plain http is allowed in development builds.
+pub fn check_webhook_url(url: &str) -> Result<String, String> {
+    check_url(url, cfg!(debug_assertions))
+}
+
+fn check_url(url: &str, allow_http: bool) -> Result<String, String> {
+    let url = url.trim();
+    let rest = match (url.strip_prefix("https://"), url.strip_prefix("http://")) {
+        (Some(rest), _) => rest,
+        (None, Some(rest)) if allow_http => rest,
+        (None, Some(_)) => return Err("URL must start with https://".to_string()),
+        (None, None) => return Err("URL must start with https:// or http://".to_string()),
+    };
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = url_host(authority);
+    if host.is_empty() {
+        Err("URL must include a host".to_string())
+    } else if url.chars().any(char::is_whitespace) {
+        Err("URL cannot contain spaces".to_string())
+    } else if is_internal_host(host) {
+        Err("URL must be for a public host".to_string())
+    } else {
+        Ok(url.to_string())
+    }
+}
+
+/// The host of a URL authority, without any user info, port or IPv6 brackets.
+fn url_host(authority: &str) -> &str {
+    let host_port = authority.rsplit('@').next().unwrap_or_default();
+    match host_port.strip_prefix('[') {
+        Some(ipv6) => ipv6.split(']').next().unwrap_or_default(),
+        None => host_port.split(':').next().unwrap_or_default(),
+    }
+}
+
+fn is_internal_host(host: &str) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    match host.parse::<IpAddr>() {
+        Ok(ip) => !is_public_ip(ip),
+        Err(_) => {
+            host == "localhost" || host.ends_with(".localhost") || host.ends_with(".internal")
+        }
+    }
+}
+
+/// Whether the server may send requests to `ip`.
+///
+/// Loopback, private, link-local (including the cloud metadata service at
+/// 169.254.169.254), shared and multicast addresses are all internal.
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ipv4(ip),
+            None => {
+                let first = ip.segments()[0];
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    // Unique local, fc00::/7.
+                    || first & 0xfe00 == 0xfc00
+                    // Link-local, fe80::/10.
+                    || first & 0xffc0 == 0xfe80)
+            }
+        },
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || a == 0
+        // Shared address space used by carrier-grade NAT, 100.64.0.0/10.
+        || (a == 100 && b & 0xc0 == 64))
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NewWebhook {
+    pub user_id: UserId,
+    pub url: String,
+    pub kinds: Vec<EntryKind>,
+}
+
+/// A newly created webhook, together with the signing secret that is only
+/// available now.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CreatedWebhook {
+    pub webhook: Webhook,
+    pub secret: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "server")]
+    fn webhook(kinds: Vec<EntryKind>, is_active: bool) -> Webhook {
+        Webhook {
+            id: WebhookId::new(1),
+            user_id: UserId::new(1),
+            url: "https://example.com/hook".to_string(),
+            kinds,
+            is_active,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "server")]
+    fn empty_kinds_wants_everything() {
+        let webhook = webhook(vec![], true);
+        assert!(webhook.wants(EntryKind::Wee));
+        assert!(webhook.wants(EntryKind::Medication));
+    }
+
+    #[test]
+    #[cfg(feature = "server")]
+    fn kinds_filter_entries() {
+        let webhook = webhook(vec![EntryKind::Poo], true);
+        assert!(webhook.wants(EntryKind::Poo));
+        assert!(!webhook.wants(EntryKind::Wee));
+    }
+
+    #[test]
+    fn webhook_url_must_be_https() {
+        assert_eq!(
+            check_url(" https://example.com/hook ", false),
+            Ok("https://example.com/hook".to_string())
+        );
+        assert!(check_url("http://example.com/hook", false).is_err());
+        assert!(check_url("http://example.com/hook", true).is_ok());
+        assert!(check_url("ftp://example.com", true).is_err());
+        assert!(check_url("https:///hook", false).is_err());
+        assert!(check_url("https://example.com/a hook", false).is_err());
+    }
+
+    #[test]
+    fn webhook_url_rejects_internal_hosts() {
+        for url in [
+            "http://localhost:8080",
+            "https://LOCALHOST/hook",
+            "https://app.localhost/hook",
+            "https://metadata.google.internal/computeMetadata/v1/",
+            "https://127.0.0.1/hook",
+            "https://user@10.1.2.3:8443/hook",
+            "https://192.168.1.1/hook",
+            "https://172.16.0.1/hook",
+            "http://169.254.169.254/latest/meta-data/",
+            "https://100.64.0.1/hook",
+            "https://0.0.0.0/hook",
+            "https://[::1]:8080/hook",
+            "https://[fd00:ec2::254]/hook",
+            "https://[fe80::1]/hook",
+            "https://[::ffff:127.0.0.1]/hook",
+        ] {
+            assert!(check_url(url, true).is_err(), "{url} should be rejected");
+        }
+    }
+
+    #[test]
+    fn webhook_url_allows_public_addresses() {
+        assert!(check_url("https://93.184.215.14/hook", false).is_ok());
+        assert!(check_url("https://[2606:2800:21f:cb07::1]:8443/hook", false).is_ok());
+        assert!(check_url("https://hooks.example.com:8443/hook?a=1", false).is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "server")]
+    fn inactive_wants_nothing() {
+        assert!(!webhook(vec![], false).wants(EntryKind::Poo));
+    }
+}
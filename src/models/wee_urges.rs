@@ -6,7 +6,7 @@ use crate::models::{MaybeSet, Urgency};
 
 use super::UserId;
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct WeeUrgeId(i64);
 
 #[allow(dead_code)]
@@ -45,6 +45,12 @@ pub struct WeeUrge {
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
+impl WeeUrge {
+    pub fn name(&self) -> String {
+        self.time.with_timezone(&chrono::Local).time().to_string()
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct NewWeeUrge {
@@ -55,10 +61,58 @@ pub struct NewWeeUrge {
 }
 
 #[allow(dead_code)]
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct ChangeWeeUrge {
     pub user_id: MaybeSet<UserId>,
     pub time: MaybeSet<chrono::DateTime<chrono::FixedOffset>>,
     pub urgency: MaybeSet<Urgency>,
     pub comments: MaybeSet<Option<String>>,
 }
+
+/// How long before a wee an urge can be and still be linked to it.
+#[cfg(feature = "server")]
+pub fn wee_urge_link_window() -> chrono::TimeDelta {
+    chrono::TimeDelta::hours(3)
+}
+
+/// Can an urge at `urge_time` be linked to a wee at `wee_time`?
+#[cfg(feature = "server")]
+pub fn can_link_wee_urge(
+    urge_time: chrono::DateTime<chrono::FixedOffset>,
+    wee_time: chrono::DateTime<chrono::FixedOffset>,
+) -> bool {
+    urge_time <= wee_time && wee_time - urge_time <= wee_urge_link_window()
+}
+
+#[cfg(test)]
+#[cfg(feature = "server")]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, TimeDelta};
+
+    fn time(s: &str) -> DateTime<chrono::FixedOffset> {
+        DateTime::parse_from_rfc3339(s).unwrap()
+    }
+
+    #[test]
+    fn test_can_link_wee_urge() {
+        let wee_time = time("2025-01-01T12:00:00+11:00");
+        assert!(can_link_wee_urge(wee_time, wee_time));
+        assert!(can_link_wee_urge(
+            wee_time - TimeDelta::minutes(20),
+            wee_time
+        ));
+        assert!(can_link_wee_urge(
+            wee_time - wee_urge_link_window(),
+            wee_time
+        ));
+        assert!(!can_link_wee_urge(
+            wee_time - wee_urge_link_window() - TimeDelta::minutes(1),
+            wee_time
+        ));
+        assert!(!can_link_wee_urge(
+            wee_time + TimeDelta::minutes(1),
+            wee_time
+        ));
+    }
+}
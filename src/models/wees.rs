@@ -4,9 +4,11 @@ use serde::{Deserialize, Serialize};
 
 use crate::models::{MaybeSet, common::Urgency};
 
-use super::UserId;
+#[cfg(feature = "server")]
+use super::duplicate_entry_window;
+use super::{UserId, WeeUrgeId};
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct WeeId(i64);
 
 impl WeeId {
@@ -44,10 +46,18 @@ pub struct Wee {
     pub mls: i32,
     pub colour: Option<palette::Hsv>,
     pub comments: Option<String>,
+    /// The urge this wee followed, if it was linked to one.
+    pub wee_urge_id: Option<WeeUrgeId>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
+impl Wee {
+    pub fn name(&self) -> String {
+        self.time.with_timezone(&chrono::Local).time().to_string()
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct NewWee {
@@ -59,10 +69,27 @@ pub struct NewWee {
     pub mls: i32,
     pub colour: Option<palette::Hsv>,
     pub comments: Option<String>,
+    pub wee_urge_id: Option<WeeUrgeId>,
+}
+
+impl NewWee {
+    /// Does this look like an accidental resubmission of an existing wee?
+    #[cfg(feature = "server")]
+    pub fn is_duplicate_of(&self, wee: &Wee, now: chrono::DateTime<chrono::Utc>) -> bool {
+        now - wee.created_at <= duplicate_entry_window()
+            && self.user_id == wee.user_id
+            && self.time == wee.time
+            && self.duration == wee.duration
+            && self.urgency == wee.urgency
+            && self.leakage == wee.leakage
+            && self.mls == wee.mls
+            && self.colour == wee.colour
+            && self.comments == wee.comments
+    }
 }
 
 #[allow(dead_code)]
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct ChangeWee {
     pub user_id: MaybeSet<UserId>,
     pub time: MaybeSet<chrono::DateTime<chrono::FixedOffset>>,
@@ -72,4 +99,122 @@ pub struct ChangeWee {
     pub mls: MaybeSet<i32>,
     pub colour: MaybeSet<Option<palette::Hsv>>,
     pub comments: MaybeSet<Option<String>>,
+    pub wee_urge_id: MaybeSet<Option<WeeUrgeId>>,
+}
+
+/// Limits used to spot wees whose volume and urgency disagree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeeCoherenceThresholds {
+    /// Volumes at or above this are unlikely with little or no urgency.
+    pub high_mls: i32,
+    /// Volumes at or below this are unlikely with extreme urgency.
+    pub low_mls: i32,
+}
+
+impl Default for WeeCoherenceThresholds {
+    fn default() -> Self {
+        Self {
+            high_mls: 800,
+            low_mls: 20,
+        }
+    }
+}
+
+/// Advisory message when the volume and urgency of a wee look inconsistent.
+///
+/// This never blocks saving, it only catches likely typing mistakes.
+pub fn wee_coherence_warning(
+    mls: i32,
+    urgency: Urgency,
+    thresholds: &WeeCoherenceThresholds,
+) -> Option<String> {
+    match urgency {
+        Urgency::U0 | Urgency::U1 if mls >= thresholds.high_mls => Some(format!(
+            "{mls}ml is a lot with {}, is that right?",
+            urgency.as_title().to_lowercase()
+        )),
+        Urgency::U5 if mls > 0 && mls <= thresholds.low_mls => Some(format!(
+            "{mls}ml is very little with {}, is that right?",
+            urgency.as_title().to_lowercase()
+        )),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wee_coherence_warning() {
+        let thresholds = WeeCoherenceThresholds::default();
+        assert!(wee_coherence_warning(900, Urgency::U0, &thresholds).is_some());
+        assert!(wee_coherence_warning(900, Urgency::U1, &thresholds).is_some());
+        assert!(wee_coherence_warning(900, Urgency::U3, &thresholds).is_none());
+        assert!(wee_coherence_warning(300, Urgency::U0, &thresholds).is_none());
+        assert!(wee_coherence_warning(10, Urgency::U5, &thresholds).is_some());
+        assert!(wee_coherence_warning(0, Urgency::U5, &thresholds).is_none());
+        assert!(wee_coherence_warning(300, Urgency::U5, &thresholds).is_none());
+    }
+
+    #[cfg(feature = "server")]
+    fn new_wee() -> NewWee {
+        NewWee {
+            user_id: UserId::new(1),
+            time: chrono::DateTime::parse_from_rfc3339("2025-01-01T12:00:00+11:00").unwrap(),
+            duration: chrono::Duration::seconds(30),
+            urgency: Urgency::U2,
+            leakage: 0,
+            mls: 300,
+            colour: None,
+            comments: None,
+            wee_urge_id: None,
+        }
+    }
+
+    #[cfg(feature = "server")]
+    fn saved_wee(new_wee: &NewWee, created_at: chrono::DateTime<chrono::Utc>) -> Wee {
+        Wee {
+            id: WeeId::new(1),
+            user_id: new_wee.user_id,
+            time: new_wee.time,
+            duration: new_wee.duration,
+            urgency: new_wee.urgency,
+            leakage: new_wee.leakage,
+            mls: new_wee.mls,
+            colour: new_wee.colour,
+            comments: new_wee.comments.clone(),
+            wee_urge_id: new_wee.wee_urge_id,
+            created_at,
+            updated_at: created_at,
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "server")]
+    fn test_wee_is_duplicate_of() {
+        let now = chrono::Utc::now();
+        let wee = new_wee();
+        let saved = saved_wee(&wee, now - chrono::TimeDelta::seconds(2));
+        assert!(wee.is_duplicate_of(&saved, now));
+
+        let old = saved_wee(&wee, now - chrono::TimeDelta::minutes(5));
+        assert!(!wee.is_duplicate_of(&old, now));
+
+        let different = NewWee {
+            mls: 250,
+            ..wee.clone()
+        };
+        assert!(!different.is_duplicate_of(&saved, now));
+    }
+
+    #[test]
+    fn test_wee_coherence_custom_thresholds() {
+        let thresholds = WeeCoherenceThresholds {
+            high_mls: 400,
+            low_mls: 50,
+        };
+        assert!(wee_coherence_warning(450, Urgency::U0, &thresholds).is_some());
+        assert!(wee_coherence_warning(40, Urgency::U5, &thresholds).is_some());
+    }
 }
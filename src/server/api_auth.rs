@@ -0,0 +1,97 @@
+//! Authentication for the `/api/v1` endpoints.
+//!
+//! Requests may either carry an `Authorization: Bearer <token>` header with a
+//! personal access token, or fall back to the cookie session of a logged in
+//! browser. Session users have full access; tokens are limited to their scope.
+
+use axum::{
+    extract::FromRequestParts,
+    http::{StatusCode, header, request::Parts},
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+
+use crate::models::ApiScope;
+
+use super::auth::Session;
+use super::database::connection::DatabasePool;
+use super::database::models::{api_tokens::authenticate_api_token, users::User};
+use super::tokens::hash_token;
+
+pub struct ApiUser {
+    pub user: User,
+    pub scope: ApiScope,
+}
+
+impl ApiUser {
+    /// Reject the request unless the caller's scope allows `required`.
+    pub fn require(&self, required: ApiScope) -> Result<(), Response> {
+        if self.scope.allows(required) {
+            Ok(())
+        } else {
+            Err((
+                StatusCode::FORBIDDEN,
+                "Token does not have the required scope",
+            )
+                .into_response())
+        }
+    }
+}
+
+fn unauthorized(message: &'static str) -> Response {
+    (StatusCode::UNAUTHORIZED, message).into_response()
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for ApiUser {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Some(authorization) = parts.headers.get(header::AUTHORIZATION) else {
+            let session = parts
+                .extensions
+                .get::<Session>()
+                .ok_or_else(|| unauthorized("Not Logged In"))?;
+            let user = session
+                .user
+                .clone()
+                .ok_or_else(|| unauthorized("Not Logged In"))?;
+            return Ok(ApiUser {
+                user,
+                scope: ApiScope::ReadWrite,
+            });
+        };
+
+        let token = authorization
+            .to_str()
+            .ok()
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim)
+            .ok_or_else(|| unauthorized("Invalid Authorization header"))?;
+
+        let pool = parts
+            .extensions
+            .get::<DatabasePool>()
+            .cloned()
+            .ok_or_else(|| {
+                tracing::error!("DatabasePool extension missing");
+                (StatusCode::INTERNAL_SERVER_ERROR, "Error").into_response()
+            })?;
+
+        let mut conn = pool.get().await.map_err(|err| {
+            tracing::error!("Error getting database connection: {err:?}");
+            (StatusCode::INTERNAL_SERVER_ERROR, "Error").into_response()
+        })?;
+
+        match authenticate_api_token(&mut conn, &hash_token(token), Utc::now()).await {
+            Ok(Some((api_token, user))) => Ok(ApiUser {
+                scope: api_token.scope(),
+                user,
+            }),
+            Ok(None) => Err(unauthorized("Invalid token")),
+            Err(err) => {
+                tracing::error!("Error checking api token: {err:?}");
+                Err((StatusCode::INTERNAL_SERVER_ERROR, "Error").into_response())
+            }
+        }
+    }
+}
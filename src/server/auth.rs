@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::sync::{Arc, LazyLock};
 
 use axum::{
     body::Body,
@@ -17,7 +17,7 @@ use tokio::task;
 use super::database::{
     self,
     connection::DatabasePool,
-    models::users::{User, get_user_by_id, get_user_by_username},
+    models::users::{User, get_user_by_id, get_user_by_username, record_login},
 };
 
 // This allows us to extract the authentication fields from forms. We use this
@@ -47,6 +47,31 @@ pub enum Error {
 
     #[error(transparent)]
     TaskJoin(#[from] task::JoinError),
+
+    #[error("Account is disabled")]
+    AccountDisabled,
+
+    #[error("Invalid credentials")]
+    InvalidCredentials,
+}
+
+/// Checked when there is no hash to check, so that logging in takes as long
+/// whether or not the account exists and has a password.
+static DUMMY_HASH: LazyLock<String> = LazyLock::new(|| password_auth::generate_hash("no password"));
+
+/// Whether `password` matches the stored hash. Accounts without a password
+/// can only log in with SSO, so nothing matches them, not even an empty
+/// password.
+fn password_matches(password: &str, hash: Option<&str>) -> bool {
+    match hash {
+        Some(hash) if !hash.is_empty() && !password.is_empty() => {
+            verify_password(password, hash).is_ok()
+        }
+        _ => {
+            let _ = verify_password(password, &DUMMY_HASH);
+            false
+        }
+    }
 }
 
 impl AuthnBackend for Backend {
@@ -70,14 +95,28 @@ impl AuthnBackend for Backend {
             .map_err(database::connection::Error::from)
             .map_err(Error::Database)?;
 
+        let no_password = user.as_ref().is_some_and(|user| user.password.is_none());
+
         // Verifying the password is blocking and potentially slow, so we'll do so via
         // `spawn_blocking`.
-        task::spawn_blocking(|| {
+        let user = task::spawn_blocking(move || {
             // We're using password-based authentication--this works by comparing our form
             // input with an argon2 password hash.
-            Ok(user.filter(|user| verify_password(creds.password, &user.password).is_ok()))
+            user.filter(|user| password_matches(&creds.password, user.password.as_deref()))
         })
-        .await?
+        .await?;
+
+        match user {
+            Some(user) if !user.is_active => Err(Error::AccountDisabled),
+            Some(user) => record_login(&mut conn, user.id)
+                .await
+                .map_err(database::connection::Error::from)
+                .map_err(Error::Database)?
+                .pipe(Some)
+                .pipe(Ok),
+            None if no_password => Err(Error::InvalidCredentials),
+            None => Ok(None),
+        }
     }
 
     async fn get_user(&self, user_id: &UserId<Self>) -> Result<Option<Self::User>, Self::Error> {
@@ -88,10 +127,12 @@ impl AuthnBackend for Backend {
             .map_err(database::connection::Error::from)
             .map_err(Error::Database)?;
 
+        // Disabling an account also ends any existing sessions.
         get_user_by_id(&mut conn, *user_id)
             .await
             .map_err(database::connection::Error::from)
             .map_err(Error::Database)?
+            .filter(|user| user.is_active)
             .pipe(Ok)
     }
 }
@@ -154,6 +195,50 @@ impl<S: std::marker::Sync + std::marker::Send> axum::extract::FromRequestParts<S
     }
 }
 
+/// How long after logging in a session is ended regardless of activity, if
+/// at all.
+#[derive(Clone, Copy, Debug)]
+pub struct SessionMaxLifetime(pub Option<time::Duration>);
+
+/// Whether cookies are only sent over HTTPS, the same as for the session
+/// cookie.
+#[derive(Clone, Copy, Debug)]
+pub struct SecureCookies(pub bool);
+
+const LOGGED_IN_AT_KEY: &str = "penguin_nurse.logged_in_at";
+
+/// Log the user out once they logged in longer than `max_lifetime` ago.
+async fn enforce_max_lifetime(auth_session: &mut AuthSession, max_lifetime: time::Duration) {
+    let now = chrono::Utc::now().timestamp();
+    let logged_in_at = auth_session
+        .session
+        .get::<i64>(LOGGED_IN_AT_KEY)
+        .await
+        .ok()
+        .flatten();
+
+    let result = match (&auth_session.user, logged_in_at) {
+        (None, None) => Ok(()),
+        (None, Some(_)) => auth_session
+            .session
+            .remove::<i64>(LOGGED_IN_AT_KEY)
+            .await
+            .map(|_| ()),
+        (Some(_), None) => auth_session.session.insert(LOGGED_IN_AT_KEY, now).await,
+        (Some(_), Some(logged_in_at)) if now - logged_in_at > max_lifetime.whole_seconds() => {
+            if let Err(err) = auth_session.logout().await {
+                tracing::error!("Error ending expired session: {err:?}");
+            }
+            Ok(())
+        }
+        (Some(_), Some(_)) => Ok(()),
+    };
+
+    if let Err(err) = result {
+        tracing::error!("Error tracking session lifetime: {err:?}");
+    }
+}
+
 pub async fn session_middleware(req: Request<Body>, next: Next) -> Response<Body> {
     let manager: Arc<AuthManager<(), Backend>> = req
         .extensions()
@@ -165,8 +250,8 @@ pub async fn session_middleware(req: Request<Body>, next: Next) -> Response<Body
     let (mut parts, body) = req.into_parts();
 
     // Step 3: Extract AuthSession from the parts
-    let session = match AuthSession::from_request_parts(&mut parts, &manager).await {
-        Ok(s) => Session(s),
+    let mut auth_session = match AuthSession::from_request_parts(&mut parts, &manager).await {
+        Ok(s) => s,
         Err(_) => {
             return Response::builder()
                 .status(401)
@@ -175,6 +260,13 @@ pub async fn session_middleware(req: Request<Body>, next: Next) -> Response<Body
         }
     };
 
+    if let Some(SessionMaxLifetime(Some(max_lifetime))) =
+        parts.extensions.get::<SessionMaxLifetime>().copied()
+    {
+        enforce_max_lifetime(&mut auth_session, max_lifetime).await;
+    }
+    let session = Session(auth_session);
+
     // Step 4: Reassemble request and insert session
     let mut req = Request::from_parts(parts, body);
     req.extensions_mut().insert(session);
@@ -182,3 +274,24 @@ pub async fn session_middleware(req: Request<Body>, next: Next) -> Response<Body
     // Step 5: Pass to next middleware / handler
     next.run(req).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn password_matches_hash() {
+        let hash = password_auth::generate_hash("secret");
+        assert!(password_matches("secret", Some(&hash)));
+        assert!(!password_matches("wrong", Some(&hash)));
+    }
+
+    #[test]
+    fn empty_password_is_rejected() {
+        let hash = password_auth::generate_hash("");
+        assert!(!password_matches("", Some(&hash)));
+        assert!(!password_matches("", Some("")));
+        assert!(!password_matches("", None));
+        assert!(!password_matches("secret", None));
+    }
+}
@@ -0,0 +1,157 @@
+//! Looking up product details for a scanned barcode.
+//!
+//! Lookups are off unless `BARCODE_LOOKUP_URL` is set to the address of an
+//! Open Food Facts style API, with `{barcode}` where the barcode goes, such
+//! as `https://world.openfoodfacts.org/api/v2/product/{barcode}.json`.
+
+use std::{str::FromStr, time::Duration};
+
+use bigdecimal::BigDecimal;
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::models::{BarcodeProduct, ConsumableUnit};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Request failed: {0}")]
+    Request(#[from] reqwest::Error),
+
+    #[error("Invalid response: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+#[derive(Clone, Debug)]
+pub struct BarcodeLookup {
+    url: Option<String>,
+    client: reqwest::Client,
+}
+
+/// A nutriment per 100 g or ml, to one decimal place.
+fn nutriment(nutriments: &Value, name: &str) -> Option<BigDecimal> {
+    let value = nutriments.get(format!("{name}_100g"))?;
+    let value = match value {
+        Value::Number(number) => BigDecimal::from_str(&number.to_string()).ok()?,
+        Value::String(string) => BigDecimal::from_str(string.trim()).ok()?,
+        _ => return None,
+    };
+    Some(value.round(1))
+}
+
+fn non_empty(value: Option<&Value>) -> Option<String> {
+    let value = value?.as_str()?.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// The product in a lookup response, `None` if it was not found.
+fn parse_product(response: &Value) -> Option<BarcodeProduct> {
+    if response.get("status").and_then(Value::as_i64) != Some(1) {
+        return None;
+    }
+    let product = response.get("product")?;
+    let nutriments = product.get("nutriments").unwrap_or(&Value::Null);
+
+    let unit = match product.get("nutrition_data_per").and_then(Value::as_str) {
+        Some("100g") => Some(ConsumableUnit::Grams),
+        Some("100ml") => Some(ConsumableUnit::Millilitres),
+        _ => None,
+    };
+    // Only the first of a comma separated list of brands.
+    let brand = product
+        .get("brands")
+        .and_then(Value::as_str)
+        .and_then(|brands| brands.split(',').next())
+        .map(str::trim)
+        .filter(|brand| !brand.is_empty())
+        .map(str::to_string);
+
+    Some(BarcodeProduct {
+        name: non_empty(product.get("product_name")),
+        brand,
+        unit,
+        calories: nutriment(nutriments, "energy-kcal"),
+        protein: nutriment(nutriments, "proteins"),
+        fat: nutriment(nutriments, "fat"),
+        carbohydrate: nutriment(nutriments, "carbohydrates"),
+    })
+}
+
+impl BarcodeLookup {
+    pub fn from_env() -> Self {
+        let url = std::env::var("BARCODE_LOOKUP_URL")
+            .ok()
+            .filter(|url| !url.is_empty());
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        Self { url, client }
+    }
+
+    /// Look up a normalized barcode, `None` if lookups are off or the product
+    /// is unknown.
+    pub async fn lookup(&self, barcode: &str) -> Result<Option<BarcodeProduct>, Error> {
+        let Some(url) = &self.url else {
+            return Ok(None);
+        };
+        let url = url.replace("{barcode}", barcode);
+        let response = self.client.get(url).send().await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let body = response.error_for_status()?.bytes().await?;
+        let response: Value = serde_json::from_slice(&body)?;
+        Ok(parse_product(&response))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn parses_found_products() {
+        let response = json!({
+            "status": 1,
+            "product": {
+                "product_name": " Oat Milk ",
+                "brands": "Oatly, Oatly AB",
+                "nutrition_data_per": "100ml",
+                "nutriments": {
+                    "energy-kcal_100g": 46,
+                    "proteins_100g": 1.04,
+                    "fat_100g": "1.5",
+                    "carbohydrates_100g": 6.7,
+                },
+            },
+        });
+        assert_eq!(
+            parse_product(&response),
+            Some(BarcodeProduct {
+                name: Some("Oat Milk".to_string()),
+                brand: Some("Oatly".to_string()),
+                unit: Some(ConsumableUnit::Millilitres),
+                calories: Some(BigDecimal::from(46)),
+                protein: Some(BigDecimal::from_str("1.0").unwrap()),
+                fat: Some(BigDecimal::from_str("1.5").unwrap()),
+                carbohydrate: Some(BigDecimal::from_str("6.7").unwrap()),
+            })
+        );
+    }
+
+    #[test]
+    fn missing_details_are_none() {
+        let response = json!({ "status": 1, "product": { "product_name": "" } });
+        assert_eq!(parse_product(&response), Some(BarcodeProduct::default()));
+    }
+
+    #[test]
+    fn unknown_products_are_not_found() {
+        let response = json!({ "status": 0, "status_verbose": "product not found" });
+        assert_eq!(parse_product(&response), None);
+    }
+}
@@ -0,0 +1,151 @@
+use std::env;
+
+use axum::{
+    extract::Request,
+    http::{HeaderMap, Method, StatusCode, header},
+    middleware::{Next, from_fn},
+    response::{IntoResponse, Response},
+};
+use tap::Pipe;
+use thiserror::Error;
+use url::Url;
+
+#[derive(Debug, Error)]
+enum Error {
+    #[error("Missing Origin and Referer headers")]
+    MissingOrigin,
+    #[error("Invalid origin {0}")]
+    InvalidOrigin(String),
+    #[error("Cross site request from {0} rejected")]
+    CrossSite(String),
+}
+
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        tracing::warn!("csrf check failed: {}", self);
+        (StatusCode::FORBIDDEN, self.to_string()).into_response()
+    }
+}
+
+/// Reject state changing requests that did not originate from this site.
+///
+/// The `Origin` header (or the `Referer` header if `Origin` is missing) of
+/// every non-GET request must match the origin of `BASE_URL`. If `BASE_URL`
+/// is not set the request `Host` header is used instead.
+///
+/// API requests with a bearer token are not checked, the cookie session is
+/// not used for them so there is nothing for another site to forge.
+pub fn add_csrf_middleware<S: Clone + Send + Sync + 'static>(
+    app: axum::Router<S>,
+) -> axum::Router<S> {
+    let expected = env::var("BASE_URL").ok().map(|base_url| {
+        Url::parse(&base_url)
+            .expect("BASE_URL must be a valid URL")
+            .origin()
+            .ascii_serialization()
+    });
+
+    app.layer(from_fn(move |request: Request, next: Next| {
+        let expected = expected.clone();
+        async move { check(expected.as_deref(), request, next).await }
+    }))
+}
+
+fn is_safe(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+/// Whether an API request authenticates with a token, see
+/// [`super::api_auth::ApiUser`].
+fn uses_bearer_token(path: &str, headers: &HeaderMap) -> bool {
+    path.starts_with("/api/v1/")
+        && headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("Bearer "))
+}
+
+fn get_request_origin(headers: &HeaderMap) -> Result<String, Error> {
+    let value = headers
+        .get(header::ORIGIN)
+        .or_else(|| headers.get(header::REFERER))
+        .ok_or(Error::MissingOrigin)?;
+
+    let value = value
+        .to_str()
+        .map_err(|_| Error::InvalidOrigin(format!("{value:?}")))?;
+
+    Url::parse(value)
+        .map_err(|_| Error::InvalidOrigin(value.to_string()))?
+        .origin()
+        .ascii_serialization()
+        .pipe(Ok)
+}
+
+fn is_same_host(origin: &str, headers: &HeaderMap) -> bool {
+    let Some(host) = headers.get(header::HOST).and_then(|h| h.to_str().ok()) else {
+        return false;
+    };
+
+    origin
+        .split_once("://")
+        .is_some_and(|(_, origin_host)| origin_host.eq_ignore_ascii_case(host))
+}
+
+async fn check(expected: Option<&str>, request: Request, next: Next) -> Result<Response, Error> {
+    if is_safe(request.method()) || uses_bearer_token(request.uri().path(), request.headers()) {
+        return next.run(request).await.pipe(Ok);
+    }
+
+    let headers = request.headers();
+    let origin = get_request_origin(headers)?;
+
+    let allowed = match expected {
+        Some(expected) => origin == expected,
+        None => is_same_host(&origin, headers),
+    };
+
+    if !allowed {
+        return Err(Error::CrossSite(origin));
+    }
+
+    next.run(request).await.pipe(Ok)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(authorization: Option<&str>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(authorization) = authorization {
+            headers.insert(header::AUTHORIZATION, authorization.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn api_requests_with_bearer_token_are_not_checked() {
+        assert!(uses_bearer_token(
+            "/api/v1/consumables/1/image",
+            &headers(Some("Bearer abc"))
+        ));
+    }
+
+    #[test]
+    fn other_requests_are_checked() {
+        assert!(!uses_bearer_token(
+            "/api/v1/consumables/1/image",
+            &headers(None)
+        ));
+        assert!(!uses_bearer_token(
+            "/api/v1/consumables/1/image",
+            &headers(Some("Basic abc"))
+        ));
+        // Server functions use the session even with a token.
+        assert!(!uses_bearer_token(
+            "/api/update_consumable",
+            &headers(Some("Bearer abc"))
+        ));
+    }
+}
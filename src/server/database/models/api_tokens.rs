@@ -0,0 +1,140 @@
+use diesel::prelude::*;
+use diesel::{ExpressionMethods, QueryDsl, Queryable, Selectable};
+use diesel_async::RunQueryDsl;
+
+use chrono::DateTime;
+use chrono::Utc;
+use tracing::error;
+
+use crate::models;
+use crate::server::database::{connection::DatabaseConnection, schema};
+
+use super::users::User;
+
+#[derive(Queryable, Selectable, Debug, Clone, Identifiable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(table_name = schema::api_tokens)]
+pub struct ApiToken {
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String,
+    pub token_hash: String,
+    pub scope: String,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ApiToken {
+    /// The token's scope. Unknown values are treated as read only.
+    pub fn scope(&self) -> models::ApiScope {
+        self.scope.parse().unwrap_or_else(|_| {
+            error!("Invalid scope {} for api token {}", self.scope, self.id);
+            models::ApiScope::Read
+        })
+    }
+}
+
+impl From<ApiToken> for crate::models::ApiToken {
+    fn from(api_token: ApiToken) -> Self {
+        Self {
+            id: models::ApiTokenId::new(api_token.id),
+            user_id: models::UserId::new(api_token.user_id),
+            scope: api_token.scope(),
+            name: api_token.name,
+            last_used_at: api_token.last_used_at,
+            created_at: api_token.created_at,
+            updated_at: api_token.updated_at,
+        }
+    }
+}
+
+pub async fn get_api_tokens_for_user(
+    conn: &mut DatabaseConnection,
+    user_id: i64,
+) -> Result<Vec<ApiToken>, diesel::result::Error> {
+    use schema::api_tokens::created_at as q_created_at;
+    use schema::api_tokens::table;
+    use schema::api_tokens::user_id as q_user_id;
+
+    table
+        .select(ApiToken::as_select())
+        .filter(q_user_id.eq(user_id))
+        .order_by(q_created_at.desc())
+        .load(conn)
+        .await
+}
+
+/// Find the active user a token belongs to, recording that it was used.
+pub async fn authenticate_api_token(
+    conn: &mut DatabaseConnection,
+    token_hash: &str,
+    now: DateTime<Utc>,
+) -> Result<Option<(ApiToken, User)>, diesel::result::Error> {
+    use schema::api_tokens::dsl as q;
+
+    let result = schema::api_tokens::table
+        .inner_join(schema::users::table)
+        .filter(q::token_hash.eq(token_hash))
+        .filter(schema::users::is_active.eq(true))
+        .select((ApiToken::as_select(), User::as_select()))
+        .get_result::<(ApiToken, User)>(conn)
+        .await
+        .optional()?;
+
+    if let Some((api_token, _)) = &result {
+        diesel::update(schema::api_tokens::table.filter(q::id.eq(api_token.id)))
+            .set(q::last_used_at.eq(now))
+            .execute(conn)
+            .await?;
+    }
+
+    Ok(result)
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(table_name = schema::api_tokens)]
+pub struct NewApiToken<'a> {
+    pub user_id: i64,
+    pub name: &'a str,
+    pub token_hash: &'a str,
+    pub scope: &'a str,
+}
+
+impl<'a> NewApiToken<'a> {
+    pub fn from_front_end(token_hash: &'a str, api_token: &'a crate::models::NewApiToken) -> Self {
+        Self {
+            user_id: api_token.user_id.as_inner(),
+            name: &api_token.name,
+            token_hash,
+            scope: api_token.scope.as_id(),
+        }
+    }
+}
+
+pub async fn create_api_token(
+    conn: &mut DatabaseConnection,
+    new: &NewApiToken<'_>,
+) -> Result<ApiToken, diesel::result::Error> {
+    diesel::insert_into(schema::api_tokens::table)
+        .values(new)
+        .returning(ApiToken::as_returning())
+        .get_result(conn)
+        .await
+}
+
+pub async fn delete_api_token(
+    conn: &mut DatabaseConnection,
+    id: i64,
+    user_id: i64,
+) -> Result<(), diesel::result::Error> {
+    use schema::api_tokens::id as q_id;
+    use schema::api_tokens::table;
+    use schema::api_tokens::user_id as q_user_id;
+
+    diesel::delete(table.filter(q_id.eq(id)).filter(q_user_id.eq(user_id)))
+        .execute(conn)
+        .await?;
+    Ok(())
+}
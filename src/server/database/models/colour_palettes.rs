@@ -0,0 +1,223 @@
+use diesel::prelude::*;
+use diesel::{ExpressionMethods, QueryDsl, Queryable, Selectable};
+use diesel_async::RunQueryDsl;
+
+use chrono::DateTime;
+use chrono::Utc;
+use tracing::error;
+
+use crate::models;
+use crate::server::database::{connection::DatabaseConnection, schema};
+
+#[derive(Queryable, Selectable, Debug, Clone, Identifiable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(table_name = schema::colour_palettes)]
+pub struct ColourPalette {
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String,
+    pub kind: String,
+    pub colours: serde_json::Value,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ColourPalette {
+    /// The palette's kind. Unknown values are treated as wee palettes.
+    pub fn kind(&self) -> models::ColourPaletteKind {
+        self.kind.parse().unwrap_or_else(|_| {
+            error!("Invalid kind {} for colour palette {}", self.kind, self.id);
+            models::ColourPaletteKind::Wee
+        })
+    }
+
+    /// The palette's colours. Invalid JSON gives an empty palette.
+    pub fn colours(&self) -> Vec<models::PaletteColour> {
+        serde_json::from_value(self.colours.clone()).unwrap_or_else(|err| {
+            error!("Invalid colours for colour palette {}: {err}", self.id);
+            Vec::new()
+        })
+    }
+}
+
+impl From<ColourPalette> for crate::models::ColourPalette {
+    fn from(palette: ColourPalette) -> Self {
+        Self {
+            id: models::ColourPaletteId::new(palette.id),
+            user_id: models::UserId::new(palette.user_id),
+            kind: palette.kind(),
+            colours: palette.colours(),
+            name: palette.name,
+            is_active: palette.is_active,
+            created_at: palette.created_at,
+            updated_at: palette.updated_at,
+        }
+    }
+}
+
+pub async fn get_colour_palettes_for_user(
+    conn: &mut DatabaseConnection,
+    user_id: i64,
+) -> Result<Vec<ColourPalette>, diesel::result::Error> {
+    use schema::colour_palettes::name as q_name;
+    use schema::colour_palettes::table;
+    use schema::colour_palettes::user_id as q_user_id;
+
+    table
+        .select(ColourPalette::as_select())
+        .filter(q_user_id.eq(user_id))
+        .order_by(q_name.asc())
+        .load(conn)
+        .await
+}
+
+pub async fn get_active_colour_palette(
+    conn: &mut DatabaseConnection,
+    user_id: i64,
+    kind: models::ColourPaletteKind,
+) -> Result<Option<ColourPalette>, diesel::result::Error> {
+    use schema::colour_palettes::dsl as q;
+
+    schema::colour_palettes::table
+        .select(ColourPalette::as_select())
+        .filter(q::user_id.eq(user_id))
+        .filter(q::kind.eq(kind.as_id()))
+        .filter(q::is_active.eq(true))
+        .get_result(conn)
+        .await
+        .optional()
+}
+
+fn colours_to_json(colours: &[models::PaletteColour]) -> serde_json::Value {
+    serde_json::to_value(colours).unwrap_or_default()
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(table_name = schema::colour_palettes)]
+pub struct NewColourPalette<'a> {
+    pub user_id: i64,
+    pub name: &'a str,
+    pub kind: &'a str,
+    pub colours: serde_json::Value,
+}
+
+impl<'a> NewColourPalette<'a> {
+    pub fn from_front_end(palette: &'a crate::models::NewColourPalette) -> Self {
+        Self {
+            user_id: palette.user_id.as_inner(),
+            name: &palette.name,
+            kind: palette.kind.as_id(),
+            colours: colours_to_json(&palette.colours),
+        }
+    }
+}
+
+pub async fn create_colour_palette(
+    conn: &mut DatabaseConnection,
+    new: &NewColourPalette<'_>,
+) -> Result<ColourPalette, diesel::result::Error> {
+    diesel::insert_into(schema::colour_palettes::table)
+        .values(new)
+        .returning(ColourPalette::as_returning())
+        .get_result(conn)
+        .await
+}
+
+#[derive(AsChangeset, Debug, Clone)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(table_name = schema::colour_palettes)]
+pub struct ChangeColourPalette<'a> {
+    pub name: Option<&'a str>,
+    pub colours: Option<serde_json::Value>,
+}
+
+impl<'a> ChangeColourPalette<'a> {
+    pub fn from_front_end(palette: &'a crate::models::ChangeColourPalette) -> Self {
+        Self {
+            name: palette.name.as_deref().into_option(),
+            colours: palette
+                .colours
+                .as_ref()
+                .map(|colours| colours_to_json(colours.as_slice()))
+                .into_option(),
+        }
+    }
+}
+
+pub async fn update_colour_palette(
+    conn: &mut DatabaseConnection,
+    id: i64,
+    user_id: i64,
+    update: &ChangeColourPalette<'_>,
+) -> Result<ColourPalette, diesel::result::Error> {
+    use schema::colour_palettes::dsl as q;
+
+    diesel::update(
+        schema::colour_palettes::table
+            .filter(q::id.eq(id))
+            .filter(q::user_id.eq(user_id)),
+    )
+    .set(update)
+    .returning(ColourPalette::as_returning())
+    .get_result(conn)
+    .await
+}
+
+/// Make `id` the active palette for its kind, or go back to the default
+/// colours for `kind` if `id` is `None`.
+pub async fn set_active_colour_palette(
+    conn: &mut DatabaseConnection,
+    user_id: i64,
+    kind: models::ColourPaletteKind,
+    id: Option<i64>,
+) -> Result<(), diesel::result::Error> {
+    use diesel_async::AsyncConnection;
+    use diesel_async::scoped_futures::ScopedFutureExt;
+    use schema::colour_palettes::dsl as q;
+
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        async move {
+            diesel::update(
+                schema::colour_palettes::table
+                    .filter(q::user_id.eq(user_id))
+                    .filter(q::kind.eq(kind.as_id()))
+                    .filter(q::is_active.eq(true)),
+            )
+            .set(q::is_active.eq(false))
+            .execute(conn)
+            .await?;
+
+            if let Some(id) = id {
+                diesel::update(
+                    schema::colour_palettes::table
+                        .filter(q::id.eq(id))
+                        .filter(q::user_id.eq(user_id))
+                        .filter(q::kind.eq(kind.as_id())),
+                )
+                .set(q::is_active.eq(true))
+                .execute(conn)
+                .await?;
+            }
+            Ok(())
+        }
+        .scope_boxed()
+    })
+    .await
+}
+
+pub async fn delete_colour_palette(
+    conn: &mut DatabaseConnection,
+    id: i64,
+    user_id: i64,
+) -> Result<(), diesel::result::Error> {
+    use schema::colour_palettes::id as q_id;
+    use schema::colour_palettes::table;
+    use schema::colour_palettes::user_id as q_user_id;
+
+    diesel::delete(table.filter(q_id.eq(id)).filter(q_user_id.eq(user_id)))
+        .execute(conn)
+        .await?;
+    Ok(())
+}
@@ -8,7 +8,7 @@ use chrono::DateTime;
 use chrono::Utc;
 use tap::Pipe;
 
-use crate::models;
+use crate::models::{self, normalize_barcode};
 use crate::server::database::{connection::DatabaseConnection, schema};
 
 use super::consumptions::ConsumptionType;
@@ -62,6 +62,11 @@ pub struct Consumable {
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
     pub consumption_type: Option<ConsumptionType>,
+    pub calories: Option<bigdecimal::BigDecimal>,
+    pub protein: Option<bigdecimal::BigDecimal>,
+    pub fat: Option<bigdecimal::BigDecimal>,
+    pub carbohydrate: Option<bigdecimal::BigDecimal>,
+    pub image_key: Option<String>,
 }
 
 impl From<Consumable> for crate::models::Consumable {
@@ -79,6 +84,11 @@ impl From<Consumable> for crate::models::Consumable {
             updated_at: consumable.updated_at,
             comments: consumable.comments,
             consumption_type: consumable.consumption_type.map(|x| x.into()),
+            calories: consumable.calories,
+            protein: consumable.protein,
+            fat: consumable.fat,
+            carbohydrate: consumable.carbohydrate,
+            image_key: consumable.image_key,
         }
     }
 }
@@ -94,8 +104,9 @@ pub async fn search_consumables_with_nested(
     use crate::server::database::schema::nested_consumables::dsl as q_nested;
     use crate::server::database::schema::nested_consumables::table as nested_table;
 
+    let limit = models::CONSUMABLE_SEARCH_LIMIT as i64;
     let consumables =
-        search_consumables(conn, search, include_only_created, include_destroyed).await?;
+        search_consumables(conn, search, include_only_created, include_destroyed, limit).await?;
 
     let nested: Vec<(NestedConsumable, Consumable)> = nested_table
         .filter(q_nested::parent_id.eq_any(consumables.iter().map(|x| x.id)))
@@ -120,24 +131,44 @@ pub async fn search_consumables_with_nested(
     Ok(result)
 }
 
+/// Search by name, brand or barcode, best matches first: an exact barcode,
+/// then an exact name, then names starting with the search, then the rest.
 pub async fn search_consumables(
     conn: &mut DatabaseConnection,
     search: &str,
     include_only_created: bool,
     include_destroyed: bool,
+    limit: i64,
 ) -> Result<Vec<Consumable>, diesel::result::Error> {
     use crate::server::database::schema::consumables::dsl as q;
     use crate::server::database::schema::consumables::table;
+    use diesel::dsl::sql;
+    use diesel::sql_types::{Integer, Text};
+
+    let barcode = normalize_barcode(search).unwrap_or_else(|_| search.to_string());
+
+    let rank = sql::<Integer>("CASE WHEN barcode = ")
+        .bind::<Text, _>(barcode.clone())
+        .sql(" THEN 0 WHEN name ILIKE ")
+        .bind::<Text, _>(search.to_string())
+        .sql(" THEN 1 WHEN name ILIKE ")
+        .bind::<Text, _>(format!("{search}%"))
+        .sql(" THEN 2 ELSE 3 END");
 
     table
         .select(Consumable::as_select())
         .filter(
             q::name.ilike(format!("%{}%", search)).or(q::brand
                 .ilike(format!("%{}%", search))
-                .or(q::barcode.eq(search))),
+                .or(q::barcode.eq(barcode))),
         )
-        .order((q::created.desc(), q::destroyed.desc(), q::name.asc()))
-        .limit(10)
+        .order((
+            rank.asc(),
+            q::created.desc(),
+            q::destroyed.desc(),
+            q::name.asc(),
+        ))
+        .limit(limit)
         .into_boxed()
         .pipe(|x| {
             if include_only_created {
@@ -157,6 +188,39 @@ pub async fn search_consumables(
         .await
 }
 
+/// Consumables with the same name and brand, ignoring case. Both are
+/// matched literally, not as patterns.
+pub async fn get_consumables_by_name_and_brand(
+    conn: &mut DatabaseConnection,
+    name: &str,
+    brand: Option<&str>,
+    limit: i64,
+) -> Result<Vec<Consumable>, diesel::result::Error> {
+    use crate::server::database::schema::consumables::dsl as q;
+    use crate::server::database::schema::consumables::table;
+
+    fn escape_like(value: &str) -> String {
+        value
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_")
+    }
+
+    let query = table
+        .select(Consumable::as_select())
+        .filter(q::name.ilike(escape_like(name)))
+        .order(q::name.asc())
+        .limit(limit)
+        .into_boxed();
+
+    let query = match brand {
+        Some(brand) => query.filter(q::brand.ilike(escape_like(brand))),
+        None => query.filter(q::brand.is_null()),
+    };
+
+    query.get_results(conn).await
+}
+
 pub async fn get_consumable_by_id(
     conn: &mut DatabaseConnection,
     id: i64,
@@ -172,6 +236,21 @@ pub async fn get_consumable_by_id(
         .optional()
 }
 
+pub async fn get_consumable_by_barcode(
+    conn: &mut DatabaseConnection,
+    barcode: &str,
+) -> Result<Option<Consumable>, diesel::result::Error> {
+    use crate::server::database::schema::consumables::barcode as q_barcode;
+    use crate::server::database::schema::consumables::table;
+
+    table
+        .select(Consumable::as_select())
+        .filter(q_barcode.eq(barcode))
+        .get_result(conn)
+        .await
+        .optional()
+}
+
 #[derive(Insertable, Debug, Clone)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 #[diesel(table_name = schema::consumables)]
@@ -185,6 +264,10 @@ pub struct NewConsumable<'a> {
     pub created: Option<DateTime<Utc>>,
     pub destroyed: Option<DateTime<Utc>>,
     pub consumption_type: Option<ConsumptionType>,
+    pub calories: Option<&'a bigdecimal::BigDecimal>,
+    pub protein: Option<&'a bigdecimal::BigDecimal>,
+    pub fat: Option<&'a bigdecimal::BigDecimal>,
+    pub carbohydrate: Option<&'a bigdecimal::BigDecimal>,
 }
 
 impl<'a> NewConsumable<'a> {
@@ -199,6 +282,10 @@ impl<'a> NewConsumable<'a> {
             created: consumable.created.as_ref().copied(),
             destroyed: consumable.destroyed.as_ref().copied(),
             consumption_type: consumable.consumption_type.map(|x| x.into()),
+            calories: consumable.calories.as_ref(),
+            protein: consumable.protein.as_ref(),
+            fat: consumable.fat.as_ref(),
+            carbohydrate: consumable.carbohydrate.as_ref(),
         }
     }
 }
@@ -227,6 +314,10 @@ pub struct ChangeConsumable<'a> {
     pub created: Option<Option<DateTime<Utc>>>,
     pub destroyed: Option<Option<DateTime<Utc>>>,
     pub consumption_type: Option<Option<ConsumptionType>>,
+    pub calories: Option<Option<&'a bigdecimal::BigDecimal>>,
+    pub protein: Option<Option<&'a bigdecimal::BigDecimal>>,
+    pub fat: Option<Option<&'a bigdecimal::BigDecimal>>,
+    pub carbohydrate: Option<Option<&'a bigdecimal::BigDecimal>>,
 }
 
 impl<'a> ChangeConsumable<'a> {
@@ -241,6 +332,10 @@ impl<'a> ChangeConsumable<'a> {
             created: consumable.created.into_option(),
             destroyed: consumable.destroyed.into_option(),
             consumption_type: consumable.consumption_type.map_inner_into().into_option(),
+            calories: consumable.calories.as_inner_ref().into_option(),
+            protein: consumable.protein.as_inner_ref().into_option(),
+            fat: consumable.fat.as_inner_ref().into_option(),
+            carbohydrate: consumable.carbohydrate.as_inner_ref().into_option(),
         }
     }
 }
@@ -257,6 +352,21 @@ pub async fn update_consumable(
         .await
 }
 
+/// Set or clear the consumable's image, returning the updated consumable.
+pub async fn set_consumable_image(
+    conn: &mut DatabaseConnection,
+    id: i64,
+    image_key: Option<&str>,
+) -> Result<Consumable, diesel::result::Error> {
+    use schema::consumables::dsl as q;
+
+    diesel::update(schema::consumables::table.filter(q::id.eq(id)))
+        .set(q::image_key.eq(image_key))
+        .returning(Consumable::as_returning())
+        .get_result(conn)
+        .await
+}
+
 pub async fn delete_consumable(
     conn: &mut DatabaseConnection,
     id: i64,
@@ -269,3 +379,42 @@ pub async fn delete_consumable(
         .await?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MaybeSet;
+
+    #[test]
+    fn change_consumable_leaves_untouched_fields_alone() {
+        let change = crate::models::ChangeConsumable {
+            name: MaybeSet::Set("Apple".to_string()),
+            ..Default::default()
+        };
+        let updates = ChangeConsumable::from_front_end(&change);
+
+        assert_eq!(updates.name, Some("Apple"));
+        assert_eq!(updates.brand, None);
+        assert_eq!(updates.barcode, None);
+        assert_eq!(updates.is_organic, None);
+        assert!(updates.unit.is_none());
+        assert_eq!(updates.comments, None);
+        assert_eq!(updates.created, None);
+        assert_eq!(updates.destroyed, None);
+        assert!(updates.consumption_type.is_none());
+    }
+
+    #[test]
+    fn change_consumable_can_clear_nullable_fields() {
+        let change = crate::models::ChangeConsumable {
+            brand: MaybeSet::Set(None),
+            destroyed: MaybeSet::Set(None),
+            ..Default::default()
+        };
+        let updates = ChangeConsumable::from_front_end(&change);
+
+        assert_eq!(updates.name, None);
+        assert_eq!(updates.brand, Some(None));
+        assert_eq!(updates.destroyed, Some(None));
+    }
+}
@@ -22,6 +22,7 @@ pub struct ConsumptionConsumable {
     pub comments: Option<String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub portion: bigdecimal::BigDecimal,
 }
 
 impl From<ConsumptionConsumable> for crate::models::ConsumptionConsumable {
@@ -33,6 +34,7 @@ impl From<ConsumptionConsumable> for crate::models::ConsumptionConsumable {
             ),
             quantity: consumption_consumable.quantity,
             liquid_mls: consumption_consumable.liquid_mls,
+            portion: consumption_consumable.portion,
             comments: consumption_consumable.comments,
             created_at: consumption_consumable.created_at,
             updated_at: consumption_consumable.updated_at,
@@ -129,12 +131,37 @@ pub async fn create_consumption_consumable(
         .await
 }
 
+pub async fn create_consumption_consumables(
+    conn: &mut DatabaseConnection,
+    parent_id: i64,
+    consumable_ids: &[i64],
+) -> Result<Vec<ConsumptionConsumable>, diesel::result::Error> {
+    let values = consumable_ids
+        .iter()
+        .map(|consumable_id| NewConsumptionConsumable {
+            parent_id,
+            consumable_id: *consumable_id,
+            quantity: None,
+            liquid_mls: None,
+            comments: None,
+        })
+        .collect::<Vec<_>>();
+
+    diesel::insert_into(schema::consumption_consumables::table)
+        .values(&values)
+        .on_conflict_do_nothing()
+        .returning(ConsumptionConsumable::as_returning())
+        .get_results(conn)
+        .await
+}
+
 #[derive(AsChangeset, Debug, Clone)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 #[diesel(table_name = schema::consumption_consumables)]
 pub struct ChangeConsumptionConsumable<'a> {
     pub quantity: Option<Option<&'a bigdecimal::BigDecimal>>,
     pub liquid_mls: Option<Option<&'a bigdecimal::BigDecimal>>,
+    pub portion: Option<&'a bigdecimal::BigDecimal>,
     pub comments: Option<Option<&'a str>>,
 }
 
@@ -148,6 +175,7 @@ impl<'a> ChangeConsumptionConsumable<'a> {
                 .liquid_mls
                 .as_inner_ref()
                 .into_option(),
+            portion: consumption_consumable.portion.as_ref().into_option(),
             comments: consumption_consumable
                 .comments
                 .map_inner_deref()
@@ -191,3 +219,73 @@ pub async fn delete_consumption_consumable(
     .await?;
     Ok(())
 }
+
+/// Move the consumables from one consumption to another. A consumable the
+/// target already has is merged into the existing row.
+pub async fn move_consumption_consumables(
+    conn: &mut DatabaseConnection,
+    from_id: i64,
+    to_id: i64,
+    consumable_ids: &[i64],
+) -> Result<(), diesel::result::Error> {
+    use diesel_async::AsyncConnection;
+    use diesel_async::scoped_futures::ScopedFutureExt;
+    use schema::consumption_consumables::dsl as q;
+    use schema::consumption_consumables::table;
+
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        async move {
+            let moving: Vec<ConsumptionConsumable> = table
+                .select(ConsumptionConsumable::as_select())
+                .filter(q::parent_id.eq(from_id))
+                .filter(q::consumable_id.eq_any(consumable_ids))
+                .load(conn)
+                .await?;
+
+            let existing: Vec<ConsumptionConsumable> = table
+                .select(ConsumptionConsumable::as_select())
+                .filter(q::parent_id.eq(to_id))
+                .filter(q::consumable_id.eq_any(consumable_ids))
+                .load(conn)
+                .await?;
+
+            for item in moving {
+                let consumable_id = item.consumable_id;
+                match existing.iter().find(|e| e.consumable_id == consumable_id) {
+                    Some(target) => {
+                        let target: models::ConsumptionConsumable = target.clone().into();
+                        let changes = target.merge(&item.into());
+                        diesel::update(
+                            table
+                                .filter(q::parent_id.eq(to_id))
+                                .filter(q::consumable_id.eq(consumable_id)),
+                        )
+                        .set(ChangeConsumptionConsumable::from_front_end(&changes))
+                        .execute(conn)
+                        .await?;
+                        diesel::delete(
+                            table
+                                .filter(q::parent_id.eq(from_id))
+                                .filter(q::consumable_id.eq(consumable_id)),
+                        )
+                        .execute(conn)
+                        .await?;
+                    }
+                    None => {
+                        diesel::update(
+                            table
+                                .filter(q::parent_id.eq(from_id))
+                                .filter(q::consumable_id.eq(consumable_id)),
+                        )
+                        .set(q::parent_id.eq(to_id))
+                        .execute(conn)
+                        .await?;
+                    }
+                }
+            }
+            Ok(())
+        }
+        .scope_boxed()
+    })
+    .await
+}
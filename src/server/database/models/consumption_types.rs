@@ -0,0 +1,100 @@
+use diesel::prelude::*;
+use diesel::{ExpressionMethods, QueryDsl, Queryable, Selectable};
+use diesel_async::RunQueryDsl;
+
+use chrono::DateTime;
+use chrono::Utc;
+
+use crate::models;
+use crate::server::database::models::consumptions::ConsumptionType;
+use crate::server::database::{connection::DatabaseConnection, schema};
+
+#[derive(Queryable, Selectable, Debug, Clone, Identifiable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(table_name = schema::consumption_types)]
+pub struct CustomConsumptionType {
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String,
+    pub icon: String,
+    pub base_type: ConsumptionType,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<CustomConsumptionType> for crate::models::CustomConsumptionType {
+    fn from(custom: CustomConsumptionType) -> Self {
+        Self {
+            id: models::CustomConsumptionTypeId::new(custom.id),
+            user_id: models::UserId::new(custom.user_id),
+            name: custom.name,
+            icon: custom.icon,
+            base_type: custom.base_type.into(),
+            created_at: custom.created_at,
+            updated_at: custom.updated_at,
+        }
+    }
+}
+
+pub async fn get_consumption_types_for_user(
+    conn: &mut DatabaseConnection,
+    user_id: i64,
+) -> Result<Vec<CustomConsumptionType>, diesel::result::Error> {
+    use schema::consumption_types::name as q_name;
+    use schema::consumption_types::table;
+    use schema::consumption_types::user_id as q_user_id;
+
+    table
+        .select(CustomConsumptionType::as_select())
+        .filter(q_user_id.eq(user_id))
+        .order_by(q_name.asc())
+        .load(conn)
+        .await
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(table_name = schema::consumption_types)]
+pub struct NewCustomConsumptionType<'a> {
+    pub user_id: i64,
+    pub name: &'a str,
+    pub icon: &'a str,
+    pub base_type: ConsumptionType,
+}
+
+impl<'a> NewCustomConsumptionType<'a> {
+    pub fn from_front_end(custom: &'a crate::models::NewCustomConsumptionType) -> Self {
+        Self {
+            user_id: custom.user_id.as_inner(),
+            name: &custom.name,
+            icon: &custom.icon,
+            base_type: custom.base_type.into(),
+        }
+    }
+}
+
+pub async fn create_consumption_type(
+    conn: &mut DatabaseConnection,
+    new: &NewCustomConsumptionType<'_>,
+) -> Result<CustomConsumptionType, diesel::result::Error> {
+    diesel::insert_into(schema::consumption_types::table)
+        .values(new)
+        .returning(CustomConsumptionType::as_returning())
+        .get_result(conn)
+        .await
+}
+
+pub async fn delete_consumption_type(
+    conn: &mut DatabaseConnection,
+    id: i64,
+    user_id: i64,
+) -> Result<(), diesel::result::Error> {
+    use schema::consumption_types::id as q_id;
+    use schema::consumption_types::table;
+    use schema::consumption_types::user_id as q_user_id;
+
+    diesel::delete(table.filter(q_id.eq(id)).filter(q_user_id.eq(user_id)))
+        .execute(conn)
+        .await?;
+    Ok(())
+}
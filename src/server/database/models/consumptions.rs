@@ -62,6 +62,8 @@ pub struct Consumption {
     pub updated_at: chrono::DateTime<chrono::Utc>,
     pub utc_offset: i32,
     pub consumption_type: ConsumptionType,
+    pub planned: bool,
+    pub custom_type_id: Option<i64>,
 }
 
 const DEFAULT_TIMEZONE: chrono::FixedOffset = chrono::FixedOffset::east_opt(0).unwrap();
@@ -82,6 +84,10 @@ impl From<Consumption> for crate::models::Consumption {
             created_at: consumption.created_at,
             updated_at: consumption.updated_at,
             consumption_type: consumption.consumption_type.into(),
+            custom_type_id: consumption
+                .custom_type_id
+                .map(models::CustomConsumptionTypeId::new),
+            planned: consumption.planned,
         }
     }
 }
@@ -91,19 +97,33 @@ pub async fn get_consumptions_for_time_range(
     user_id: i64,
     start: chrono::DateTime<chrono::Utc>,
     end: chrono::DateTime<chrono::Utc>,
+    options: models::RangeOptions,
 ) -> Result<Vec<(Consumption, Vec<(ConsumptionConsumable, Consumable)>)>, diesel::result::Error> {
     let consumptions: Vec<Consumption> = {
+        use crate::server::database::schema::consumptions::duration as q_duration;
+        use crate::server::database::schema::consumptions::id as q_id;
         use crate::server::database::schema::consumptions::table;
         use crate::server::database::schema::consumptions::time as q_time;
         use crate::server::database::schema::consumptions::user_id as q_user_id;
 
-        table
+        let mut query = table
             .select(Consumption::as_select())
             .filter(q_user_id.eq(user_id))
-            .filter(q_time.ge(start))
+            // Include entries that started earlier but are still running.
+            .filter(q_time.ge(start).or((q_time + q_duration).gt(start)))
             .filter(q_time.lt(end))
-            .load(conn)
-            .await?
+            .into_boxed();
+
+        query = match options.sort {
+            Some(models::SortDirection::Ascending) => query.order((q_time.asc(), q_id.asc())),
+            Some(models::SortDirection::Descending) => query.order((q_time.desc(), q_id.desc())),
+            None => query,
+        };
+        if let Some(limit) = options.limit {
+            query = query.limit(limit);
+        }
+
+        query.load(conn).await?
     };
 
     let nested: Vec<(ConsumptionConsumable, Consumable)> =
@@ -140,6 +160,56 @@ pub async fn get_consumption_by_id(
         .optional()
 }
 
+/// The most recent consumptions by the user that include the consumable,
+/// newest first.
+pub async fn get_consumptions_for_consumable(
+    conn: &mut DatabaseConnection,
+    user_id: i64,
+    consumable_id: i64,
+    limit: i64,
+) -> Result<Vec<(Consumption, ConsumptionConsumable)>, diesel::result::Error> {
+    use crate::server::database::schema::consumption_consumables::consumable_id as q_consumable_id;
+    use crate::server::database::schema::consumptions::table;
+    use crate::server::database::schema::consumptions::time as q_time;
+    use crate::server::database::schema::consumptions::user_id as q_user_id;
+
+    table
+        .inner_join(schema::consumption_consumables::table)
+        .select((Consumption::as_select(), ConsumptionConsumable::as_select()))
+        .filter(q_user_id.eq(user_id))
+        .filter(q_consumable_id.eq(consumable_id))
+        .order(q_time.desc())
+        .limit(limit)
+        .load(conn)
+        .await
+}
+
+/// The user's consumptions that include the consumable and started between
+/// `start` and `end`, oldest first.
+pub async fn get_consumptions_for_consumable_in_range(
+    conn: &mut DatabaseConnection,
+    user_id: i64,
+    consumable_id: i64,
+    start: chrono::DateTime<chrono::Utc>,
+    end: chrono::DateTime<chrono::Utc>,
+) -> Result<Vec<(Consumption, ConsumptionConsumable)>, diesel::result::Error> {
+    use crate::server::database::schema::consumption_consumables::consumable_id as q_consumable_id;
+    use crate::server::database::schema::consumptions::table;
+    use crate::server::database::schema::consumptions::time as q_time;
+    use crate::server::database::schema::consumptions::user_id as q_user_id;
+
+    table
+        .inner_join(schema::consumption_consumables::table)
+        .select((Consumption::as_select(), ConsumptionConsumable::as_select()))
+        .filter(q_user_id.eq(user_id))
+        .filter(q_consumable_id.eq(consumable_id))
+        .filter(q_time.ge(start))
+        .filter(q_time.lt(end))
+        .order(q_time.asc())
+        .load(conn)
+        .await
+}
+
 #[derive(Insertable, Debug, Clone)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 #[diesel(table_name = schema::consumptions)]
@@ -149,8 +219,10 @@ pub struct NewConsumption<'a> {
     pub utc_offset: i32,
     pub duration: TimeDelta,
     pub consumption_type: ConsumptionType,
+    pub custom_type_id: Option<i64>,
     pub liquid_mls: Option<&'a bigdecimal::BigDecimal>,
     pub comments: Option<&'a str>,
+    pub planned: bool,
 }
 
 impl<'a> NewConsumption<'a> {
@@ -161,8 +233,10 @@ impl<'a> NewConsumption<'a> {
             utc_offset: consumption.time.offset().local_minus_utc(),
             duration: consumption.duration,
             consumption_type: consumption.consumption_type.into(),
+            custom_type_id: consumption.custom_type_id.map(|id| id.as_inner()),
             liquid_mls: consumption.liquid_mls.as_ref(),
             comments: consumption.comments.as_deref(),
+            planned: consumption.planned,
         }
     }
 }
@@ -186,8 +260,10 @@ pub struct ChangeConsumption<'a> {
     pub utc_offset: Option<i32>,
     pub duration: Option<TimeDelta>,
     pub consumption_type: Option<ConsumptionType>,
+    pub custom_type_id: Option<Option<i64>>,
     pub liquid_mls: Option<Option<&'a bigdecimal::BigDecimal>>,
     pub comments: Option<Option<&'a str>>,
+    pub planned: Option<bool>,
 }
 
 impl<'a> ChangeConsumption<'a> {
@@ -203,8 +279,13 @@ impl<'a> ChangeConsumption<'a> {
                 .into_option(),
             duration: consumption.duration.into_option(),
             consumption_type: consumption.consumption_type.map_into().into_option(),
+            custom_type_id: consumption
+                .custom_type_id
+                .map(|id| id.map(|id| id.as_inner()))
+                .into_option(),
             liquid_mls: consumption.liquid_mls.as_inner_ref().into_option(),
             comments: consumption.comments.map_inner_deref().into_option(),
+            planned: consumption.planned.into_option(),
         }
     }
 }
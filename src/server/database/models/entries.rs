@@ -0,0 +1,111 @@
+use diesel::QueryableByName;
+use diesel::sql_types::{BigInt, Text, Timestamptz};
+use diesel_async::RunQueryDsl;
+
+use crate::models::{EntryCursor, EntryId};
+use crate::server::database::connection::DatabaseConnection;
+
+#[derive(QueryableByName, Debug)]
+struct EntryMatch {
+    #[diesel(sql_type = Text)]
+    kind: String,
+    #[diesel(sql_type = BigInt)]
+    id: i64,
+    #[diesel(sql_type = Timestamptz)]
+    time: chrono::DateTime<chrono::Utc>,
+}
+
+/// Tables holding timeline entries, with the kind prefix used by `EntryId`.
+const ENTRY_KINDS: [(&str, &str); 11] = [
+    ("wees", "wee"),
+    ("wee_urges", "wee-urgency"),
+    ("poos", "poo"),
+    ("consumptions", "consumption"),
+    ("exercises", "exercise"),
+    ("health_metrics", "health-metric"),
+    ("symptoms", "symptom"),
+    ("refluxs", "reflux"),
+    ("notes", "note"),
+    ("fluid_adjustments", "fluid-adjustment"),
+    ("medication_doses", "medication"),
+];
+
+/// Query the kind, id and time of entries in every table matching `filter`
+/// and then `outer_filter`, in `EntryCursor` order. `$1` is the user id.
+fn entry_ids_query(filter: &str, outer_filter: &str, limit: &str) -> String {
+    let selects = ENTRY_KINDS
+        .iter()
+        .map(|(table, kind)| {
+            format!(
+                "SELECT '{kind}' AS kind, id, time FROM {table} WHERE user_id = $1 AND {filter}"
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" UNION ALL ");
+    format!(
+        "SELECT kind, id, time FROM ({selects}) AS entries WHERE {outer_filter} \
+        ORDER BY time DESC, kind DESC, id DESC LIMIT {limit}"
+    )
+}
+
+fn into_entry_cursors(rows: Vec<EntryMatch>) -> Vec<EntryCursor> {
+    rows.into_iter()
+        .filter_map(|row| {
+            let id = format!("{}-{}", row.kind, row.id).parse().ok()?;
+            Some(EntryCursor { time: row.time, id })
+        })
+        .collect()
+}
+
+/// Ids of entries of any kind whose comments contain `search`, newest first.
+pub async fn search_entry_ids_by_comment(
+    conn: &mut DatabaseConnection,
+    user_id: i64,
+    search: &str,
+    limit: i64,
+) -> Result<Vec<EntryId>, diesel::result::Error> {
+    let rows: Vec<EntryMatch> =
+        diesel::sql_query(entry_ids_query("comments ILIKE $2", "TRUE", "$3"))
+            .bind::<BigInt, _>(user_id)
+            .bind::<Text, _>(format!("%{search}%"))
+            .bind::<BigInt, _>(limit)
+            .load(conn)
+            .await?;
+
+    Ok(into_entry_cursors(rows)
+        .into_iter()
+        .map(|cursor| cursor.id)
+        .collect())
+}
+
+/// Positions of up to `limit` entries of any kind that come after `after`,
+/// or the newest entries if there is no cursor.
+pub async fn get_entry_cursors_after(
+    conn: &mut DatabaseConnection,
+    user_id: i64,
+    after: Option<EntryCursor>,
+    limit: i64,
+) -> Result<Vec<EntryCursor>, diesel::result::Error> {
+    let rows: Vec<EntryMatch> = match after {
+        Some(after) => {
+            let query = entry_ids_query("time <= $2", "(time, kind, id) < ($2, $3, $4)", "$5");
+            diesel::sql_query(query)
+                .bind::<BigInt, _>(user_id)
+                .bind::<Timestamptz, _>(after.time)
+                .bind::<Text, _>(after.id.kind_prefix())
+                .bind::<BigInt, _>(after.id.as_inner())
+                .bind::<BigInt, _>(limit)
+                .load(conn)
+                .await?
+        }
+        None => {
+            diesel::sql_query(entry_ids_query("TRUE", "TRUE", "$2"))
+                .bind::<BigInt, _>(user_id)
+                .bind::<BigInt, _>(limit)
+                .load(conn)
+                .await?
+        }
+    };
+
+    Ok(into_entry_cursors(rows))
+}
@@ -0,0 +1,129 @@
+use diesel::prelude::*;
+use diesel::{ExpressionMethods, QueryDsl, Queryable, Selectable};
+use diesel_async::RunQueryDsl;
+
+use chrono::DateTime;
+use chrono::Utc;
+use tracing::error;
+
+use crate::models;
+use crate::server::database::{connection::DatabaseConnection, schema};
+
+#[derive(Queryable, Selectable, Debug, Clone, Identifiable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(table_name = schema::templates)]
+pub struct EntryTemplate {
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String,
+    pub kind: String,
+    pub payload: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl EntryTemplate {
+    /// The template's fields, or `None` if they can't be read back.
+    pub fn data(&self) -> Option<models::EntryTemplateData> {
+        let Ok(kind) = self.kind.parse::<models::EntryKind>() else {
+            error!("Invalid kind {} for template {}", self.kind, self.id);
+            return None;
+        };
+        models::EntryTemplateData::from_payload(kind, self.payload.clone())
+            .inspect_err(|err| error!("Invalid payload for template {}: {err}", self.id))
+            .ok()
+    }
+
+    pub fn into_front_end(self) -> Option<models::EntryTemplate> {
+        let data = self.data()?;
+        Some(models::EntryTemplate {
+            id: models::EntryTemplateId::new(self.id),
+            user_id: models::UserId::new(self.user_id),
+            name: self.name,
+            data,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        })
+    }
+}
+
+pub async fn get_entry_templates_for_user(
+    conn: &mut DatabaseConnection,
+    user_id: i64,
+) -> Result<Vec<EntryTemplate>, diesel::result::Error> {
+    use schema::templates::dsl as q;
+
+    schema::templates::table
+        .select(EntryTemplate::as_select())
+        .filter(q::user_id.eq(user_id))
+        .order_by((q::kind.asc(), q::name.asc()))
+        .load(conn)
+        .await
+}
+
+pub async fn get_entry_template_by_id(
+    conn: &mut DatabaseConnection,
+    id: i64,
+    user_id: i64,
+) -> Result<Option<EntryTemplate>, diesel::result::Error> {
+    use schema::templates::dsl as q;
+
+    schema::templates::table
+        .select(EntryTemplate::as_select())
+        .filter(q::id.eq(id))
+        .filter(q::user_id.eq(user_id))
+        .get_result(conn)
+        .await
+        .optional()
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(table_name = schema::templates)]
+pub struct NewEntryTemplate<'a> {
+    pub user_id: i64,
+    pub name: &'a str,
+    pub kind: &'a str,
+    pub payload: serde_json::Value,
+}
+
+impl<'a> NewEntryTemplate<'a> {
+    pub fn from_front_end(
+        template: &'a crate::models::NewEntryTemplate,
+    ) -> Result<Self, serde_json::Error> {
+        Ok(Self {
+            user_id: template.user_id.as_inner(),
+            name: &template.name,
+            kind: template.data.kind().as_id(),
+            payload: template.data.payload()?,
+        })
+    }
+}
+
+pub async fn create_entry_template(
+    conn: &mut DatabaseConnection,
+    new: &NewEntryTemplate<'_>,
+) -> Result<EntryTemplate, diesel::result::Error> {
+    diesel::insert_into(schema::templates::table)
+        .values(new)
+        .returning(EntryTemplate::as_returning())
+        .get_result(conn)
+        .await
+}
+
+pub async fn delete_entry_template(
+    conn: &mut DatabaseConnection,
+    id: i64,
+    user_id: i64,
+) -> Result<(), diesel::result::Error> {
+    use schema::templates::dsl as q;
+
+    diesel::delete(
+        schema::templates::table
+            .filter(q::id.eq(id))
+            .filter(q::user_id.eq(user_id)),
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
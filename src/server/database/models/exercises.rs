@@ -69,6 +69,8 @@ pub struct Exercise {
     pub comments: Option<String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub avg_heart_rate: Option<i32>,
+    pub max_heart_rate: Option<i32>,
 }
 
 const DEFAULT_TIMEZONE: chrono::FixedOffset = chrono::FixedOffset::east_opt(0).unwrap();
@@ -94,6 +96,8 @@ impl From<Exercise> for crate::models::Exercise {
             created_at: exercise.created_at,
             updated_at: exercise.updated_at,
             exercise_type: exercise.exercise_type.into(),
+            avg_heart_rate: exercise.avg_heart_rate,
+            max_heart_rate: exercise.max_heart_rate,
         }
     }
 }
@@ -103,19 +107,32 @@ pub async fn get_exercises_for_time_range(
     user_id: i64,
     start: chrono::DateTime<chrono::Utc>,
     end: chrono::DateTime<chrono::Utc>,
+    options: models::RangeOptions,
 ) -> Result<Vec<Exercise>, diesel::result::Error> {
-    // use crate::server::database::schema::exercises::duration as q_duration;
+    use crate::server::database::schema::exercises::duration as q_duration;
+    use crate::server::database::schema::exercises::id as q_id;
     use crate::server::database::schema::exercises::table;
     use crate::server::database::schema::exercises::time as q_time;
     use crate::server::database::schema::exercises::user_id as q_user_id;
 
-    table
+    let mut query = table
         .select(Exercise::as_select())
         .filter(q_user_id.eq(user_id))
-        .filter(q_time.ge(start))
+        // Include entries that started earlier but are still running.
+        .filter(q_time.ge(start).or((q_time + q_duration).gt(start)))
         .filter(q_time.lt(end))
-        .load(conn)
-        .await
+        .into_boxed();
+
+    query = match options.sort {
+        Some(models::SortDirection::Ascending) => query.order((q_time.asc(), q_id.asc())),
+        Some(models::SortDirection::Descending) => query.order((q_time.desc(), q_id.desc())),
+        None => query,
+    };
+    if let Some(limit) = options.limit {
+        query = query.limit(limit);
+    }
+
+    query.load(conn).await
 }
 
 pub async fn get_exercise_by_id(
@@ -150,6 +167,8 @@ pub struct NewExercise<'a> {
     pub rpe: Option<i32>,
     pub exercise_type: ExerciseType,
     pub comments: Option<&'a str>,
+    pub avg_heart_rate: Option<i32>,
+    pub max_heart_rate: Option<i32>,
 }
 
 impl<'a> NewExercise<'a> {
@@ -165,6 +184,8 @@ impl<'a> NewExercise<'a> {
             rpe: exercise.rpe.map(|rpe| rpe.into()),
             exercise_type: exercise.exercise_type.into(),
             comments: exercise.comments.as_deref(),
+            avg_heart_rate: exercise.avg_heart_rate,
+            max_heart_rate: exercise.max_heart_rate,
         }
     }
 }
@@ -193,6 +214,8 @@ pub struct ChangeExercise<'a> {
     pub rpe: Option<Option<i32>>,
     pub exercise_type: Option<ExerciseType>,
     pub comments: Option<Option<&'a str>>,
+    pub avg_heart_rate: Option<Option<i32>>,
+    pub max_heart_rate: Option<Option<i32>>,
 }
 
 impl<'a> ChangeExercise<'a> {
@@ -213,6 +236,8 @@ impl<'a> ChangeExercise<'a> {
             rpe: exercise.rpe.map_inner_into().into_option(),
             exercise_type: exercise.exercise_type.map_into().into_option(),
             comments: exercise.comments.map_inner_deref().into_option(),
+            avg_heart_rate: exercise.avg_heart_rate.into_option(),
+            max_heart_rate: exercise.max_heart_rate.into_option(),
         }
     }
 }
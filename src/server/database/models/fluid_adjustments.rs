@@ -0,0 +1,186 @@
+use diesel::prelude::*;
+use diesel::{ExpressionMethods, QueryDsl, Queryable, Selectable};
+use diesel_async::RunQueryDsl;
+
+use chrono::DateTime;
+use chrono::Utc;
+
+use crate::models;
+use crate::server::database::{connection::DatabaseConnection, schema};
+
+#[allow(dead_code)]
+#[derive(Queryable, Selectable, Debug, Clone, Identifiable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(table_name = schema::fluid_adjustments)]
+pub struct FluidAdjustment {
+    pub id: i64,
+    pub user_id: i64,
+    pub time: DateTime<Utc>,
+    pub utc_offset: i32,
+    pub mls: i32,
+    pub comments: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+const DEFAULT_TIMEZONE: chrono::FixedOffset = chrono::FixedOffset::east_opt(0).unwrap();
+
+impl From<FluidAdjustment> for crate::models::FluidAdjustment {
+    fn from(adjustment: FluidAdjustment) -> Self {
+        let timezone =
+            chrono::FixedOffset::east_opt(adjustment.utc_offset).unwrap_or(DEFAULT_TIMEZONE);
+        let time = adjustment.time.with_timezone(&timezone);
+
+        Self {
+            id: models::FluidAdjustmentId::new(adjustment.id),
+            user_id: models::UserId::new(adjustment.user_id),
+            time,
+            mls: adjustment.mls,
+            comments: adjustment.comments,
+            created_at: adjustment.created_at,
+            updated_at: adjustment.updated_at,
+        }
+    }
+}
+
+pub async fn get_fluid_adjustments_for_time_range(
+    conn: &mut DatabaseConnection,
+    user_id: i64,
+    start: chrono::DateTime<chrono::Utc>,
+    end: chrono::DateTime<chrono::Utc>,
+    options: models::RangeOptions,
+) -> Result<Vec<FluidAdjustment>, diesel::result::Error> {
+    use crate::server::database::schema::fluid_adjustments::id as q_id;
+    use crate::server::database::schema::fluid_adjustments::table;
+    use crate::server::database::schema::fluid_adjustments::time as q_time;
+    use crate::server::database::schema::fluid_adjustments::user_id as q_user_id;
+
+    let mut query = table
+        .select(FluidAdjustment::as_select())
+        .filter(q_user_id.eq(user_id))
+        .filter(q_time.ge(start))
+        .filter(q_time.lt(end))
+        .into_boxed();
+
+    query = match options.sort {
+        Some(models::SortDirection::Ascending) => query.order((q_time.asc(), q_id.asc())),
+        Some(models::SortDirection::Descending) => query.order((q_time.desc(), q_id.desc())),
+        None => query,
+    };
+    if let Some(limit) = options.limit {
+        query = query.limit(limit);
+    }
+
+    query.load(conn).await
+}
+
+pub async fn get_fluid_adjustment_by_id(
+    conn: &mut DatabaseConnection,
+    id: i64,
+    user_id: i64,
+) -> Result<Option<FluidAdjustment>, diesel::result::Error> {
+    use crate::server::database::schema::fluid_adjustments::id as q_id;
+    use crate::server::database::schema::fluid_adjustments::table;
+    use crate::server::database::schema::fluid_adjustments::user_id as q_user_id;
+
+    table
+        .select(FluidAdjustment::as_select())
+        .filter(q_id.eq(id))
+        .filter(q_user_id.eq(user_id))
+        .get_result(conn)
+        .await
+        .optional()
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(table_name = schema::fluid_adjustments)]
+pub struct NewFluidAdjustment<'a> {
+    pub user_id: i64,
+    pub time: DateTime<Utc>,
+    pub utc_offset: i32,
+    pub mls: i32,
+    pub comments: Option<&'a str>,
+}
+
+impl<'a> NewFluidAdjustment<'a> {
+    pub fn from_front_end(adjustment: &'a crate::models::NewFluidAdjustment) -> Self {
+        Self {
+            user_id: adjustment.user_id.as_inner(),
+            time: adjustment.time.with_timezone(&Utc),
+            utc_offset: adjustment.time.offset().local_minus_utc(),
+            mls: adjustment.mls,
+            comments: adjustment.comments.as_deref(),
+        }
+    }
+}
+
+pub async fn create_fluid_adjustment(
+    conn: &mut DatabaseConnection,
+    update: &NewFluidAdjustment<'_>,
+) -> Result<FluidAdjustment, diesel::result::Error> {
+    diesel::insert_into(schema::fluid_adjustments::table)
+        .values(update)
+        .returning(FluidAdjustment::as_returning())
+        .get_result(conn)
+        .await
+}
+
+#[derive(AsChangeset, Debug, Clone)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(table_name = schema::fluid_adjustments)]
+pub struct ChangeFluidAdjustment<'a> {
+    pub time: Option<DateTime<Utc>>,
+    pub utc_offset: Option<i32>,
+    pub mls: Option<i32>,
+    pub comments: Option<Option<&'a str>>,
+}
+
+impl<'a> ChangeFluidAdjustment<'a> {
+    pub fn from_front_end(adjustment: &'a crate::models::ChangeFluidAdjustment) -> Self {
+        Self {
+            time: adjustment
+                .time
+                .map(|time| time.with_timezone(&Utc))
+                .into_option(),
+            utc_offset: adjustment
+                .time
+                .map(|time| time.offset().local_minus_utc())
+                .into_option(),
+            mls: adjustment.mls.into_option(),
+            comments: adjustment.comments.map_inner_deref().into_option(),
+        }
+    }
+}
+
+pub async fn update_fluid_adjustment(
+    conn: &mut DatabaseConnection,
+    id: i64,
+    user_id: i64,
+    update: &ChangeFluidAdjustment<'_>,
+) -> Result<FluidAdjustment, diesel::result::Error> {
+    use schema::fluid_adjustments::id as q_id;
+    use schema::fluid_adjustments::table;
+    use schema::fluid_adjustments::user_id as q_user_id;
+
+    diesel::update(table.filter(q_id.eq(id)).filter(q_user_id.eq(user_id)))
+        .set(update)
+        .returning(FluidAdjustment::as_returning())
+        .get_result(conn)
+        .await
+}
+
+pub async fn delete_fluid_adjustment(
+    conn: &mut DatabaseConnection,
+    id: i64,
+    user_id: i64,
+) -> Result<(), diesel::result::Error> {
+    use schema::fluid_adjustments::id as q_id;
+    use schema::fluid_adjustments::table;
+    use schema::fluid_adjustments::user_id as q_user_id;
+
+    diesel::delete(table.filter(q_id.eq(id)).filter(q_user_id.eq(user_id)))
+        .execute(conn)
+        .await?;
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use crate::models::{HealthMetricId, UserId};
+use crate::models::{HealthMetricId, RangeOptions, SortDirection, UserId};
 use crate::server::database::{connection::DatabaseConnection, schema};
 use chrono::Utc;
 use diesel::prelude::*;
@@ -57,19 +57,31 @@ pub async fn get_health_metrics_for_time_range(
     user_id: i64,
     start: chrono::DateTime<chrono::Utc>,
     end: chrono::DateTime<chrono::Utc>,
+    options: RangeOptions,
 ) -> Result<Vec<HealthMetric>, diesel::result::Error> {
     // use crate::server::database::schema::health_metrics::duration as q_duration;
+    use crate::server::database::schema::health_metrics::id as q_id;
     use crate::server::database::schema::health_metrics::table;
     use crate::server::database::schema::health_metrics::time as q_time;
     use crate::server::database::schema::health_metrics::user_id as q_user_id;
 
-    table
+    let mut query = table
         .select(HealthMetric::as_select())
         .filter(q_user_id.eq(user_id))
         .filter(q_time.ge(start))
         .filter(q_time.lt(end))
-        .load(conn)
-        .await
+        .into_boxed();
+
+    query = match options.sort {
+        Some(SortDirection::Ascending) => query.order((q_time.asc(), q_id.asc())),
+        Some(SortDirection::Descending) => query.order((q_time.desc(), q_id.desc())),
+        None => query,
+    };
+    if let Some(limit) = options.limit {
+        query = query.limit(limit);
+    }
+
+    query.load(conn).await
 }
 
 pub async fn get_health_metric_by_id(
@@ -0,0 +1,212 @@
+use diesel::prelude::*;
+use diesel::{ExpressionMethods, QueryDsl, Queryable, Selectable};
+use diesel_async::RunQueryDsl;
+
+use chrono::DateTime;
+use chrono::Utc;
+
+use crate::models;
+use crate::server::database::{connection::DatabaseConnection, schema};
+
+use super::consumables::Consumable;
+use super::medications::Medication;
+
+#[allow(dead_code)]
+#[derive(Queryable, Selectable, Debug, Clone, Identifiable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(table_name = schema::medication_doses)]
+pub struct MedicationDose {
+    pub id: i64,
+    pub user_id: i64,
+    pub medication_id: i64,
+    pub time: DateTime<Utc>,
+    pub utc_offset: i32,
+    pub dose: bigdecimal::BigDecimal,
+    pub comments: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+const DEFAULT_TIMEZONE: chrono::FixedOffset = chrono::FixedOffset::east_opt(0).unwrap();
+
+impl From<MedicationDose> for crate::models::MedicationDose {
+    fn from(dose: MedicationDose) -> Self {
+        let timezone = chrono::FixedOffset::east_opt(dose.utc_offset).unwrap_or(DEFAULT_TIMEZONE);
+        let time = dose.time.with_timezone(&timezone);
+
+        Self {
+            id: models::MedicationDoseId::new(dose.id),
+            user_id: models::UserId::new(dose.user_id),
+            medication_id: models::MedicationId::new(dose.medication_id),
+            time,
+            dose: dose.dose,
+            comments: dose.comments,
+            created_at: dose.created_at,
+            updated_at: dose.updated_at,
+        }
+    }
+}
+
+impl From<(MedicationDose, Medication, Consumable)>
+    for crate::models::MedicationDoseWithMedication
+{
+    fn from((dose, medication, consumable): (MedicationDose, Medication, Consumable)) -> Self {
+        Self {
+            dose: dose.into(),
+            medication: (medication, consumable).into(),
+        }
+    }
+}
+
+pub async fn get_medication_doses_for_time_range(
+    conn: &mut DatabaseConnection,
+    user_id: i64,
+    start: chrono::DateTime<chrono::Utc>,
+    end: chrono::DateTime<chrono::Utc>,
+    options: models::RangeOptions,
+) -> Result<Vec<(MedicationDose, Medication, Consumable)>, diesel::result::Error> {
+    use schema::medication_doses::id as q_id;
+    use schema::medication_doses::table;
+    use schema::medication_doses::time as q_time;
+    use schema::medication_doses::user_id as q_user_id;
+
+    let mut query = table
+        .inner_join(schema::medications::table.inner_join(schema::consumables::table))
+        .select((
+            MedicationDose::as_select(),
+            Medication::as_select(),
+            Consumable::as_select(),
+        ))
+        .filter(q_user_id.eq(user_id))
+        .filter(q_time.ge(start))
+        .filter(q_time.lt(end))
+        .into_boxed();
+
+    query = match options.sort {
+        Some(models::SortDirection::Ascending) => query.order((q_time.asc(), q_id.asc())),
+        Some(models::SortDirection::Descending) => query.order((q_time.desc(), q_id.desc())),
+        None => query,
+    };
+    if let Some(limit) = options.limit {
+        query = query.limit(limit);
+    }
+
+    query.load(conn).await
+}
+
+pub async fn get_medication_dose_by_id(
+    conn: &mut DatabaseConnection,
+    id: i64,
+    user_id: i64,
+) -> Result<Option<(MedicationDose, Medication, Consumable)>, diesel::result::Error> {
+    use schema::medication_doses::id as q_id;
+    use schema::medication_doses::table;
+    use schema::medication_doses::user_id as q_user_id;
+
+    table
+        .inner_join(schema::medications::table.inner_join(schema::consumables::table))
+        .select((
+            MedicationDose::as_select(),
+            Medication::as_select(),
+            Consumable::as_select(),
+        ))
+        .filter(q_id.eq(id))
+        .filter(q_user_id.eq(user_id))
+        .get_result(conn)
+        .await
+        .optional()
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(table_name = schema::medication_doses)]
+pub struct NewMedicationDose<'a> {
+    pub user_id: i64,
+    pub medication_id: i64,
+    pub time: DateTime<Utc>,
+    pub utc_offset: i32,
+    pub dose: &'a bigdecimal::BigDecimal,
+    pub comments: Option<&'a str>,
+}
+
+impl<'a> NewMedicationDose<'a> {
+    pub fn from_front_end(dose: &'a crate::models::NewMedicationDose) -> Self {
+        Self {
+            user_id: dose.user_id.as_inner(),
+            medication_id: dose.medication_id.as_inner(),
+            time: dose.time.with_timezone(&Utc),
+            utc_offset: dose.time.offset().local_minus_utc(),
+            dose: &dose.dose,
+            comments: dose.comments.as_deref(),
+        }
+    }
+}
+
+pub async fn create_medication_dose(
+    conn: &mut DatabaseConnection,
+    update: &NewMedicationDose<'_>,
+) -> Result<MedicationDose, diesel::result::Error> {
+    diesel::insert_into(schema::medication_doses::table)
+        .values(update)
+        .returning(MedicationDose::as_returning())
+        .get_result(conn)
+        .await
+}
+
+#[derive(AsChangeset, Debug, Clone)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(table_name = schema::medication_doses)]
+pub struct ChangeMedicationDose<'a> {
+    pub medication_id: Option<i64>,
+    pub time: Option<DateTime<Utc>>,
+    pub utc_offset: Option<i32>,
+    pub dose: Option<&'a bigdecimal::BigDecimal>,
+    pub comments: Option<Option<&'a str>>,
+}
+
+impl<'a> ChangeMedicationDose<'a> {
+    pub fn from_front_end(dose: &'a crate::models::ChangeMedicationDose) -> Self {
+        Self {
+            medication_id: dose.medication_id.map(|x| x.as_inner()).into_option(),
+            time: dose.time.map(|time| time.with_timezone(&Utc)).into_option(),
+            utc_offset: dose
+                .time
+                .map(|time| time.offset().local_minus_utc())
+                .into_option(),
+            dose: dose.dose.as_ref().into_option(),
+            comments: dose.comments.map_inner_deref().into_option(),
+        }
+    }
+}
+
+pub async fn update_medication_dose(
+    conn: &mut DatabaseConnection,
+    id: i64,
+    user_id: i64,
+    update: &ChangeMedicationDose<'_>,
+) -> Result<MedicationDose, diesel::result::Error> {
+    use schema::medication_doses::id as q_id;
+    use schema::medication_doses::table;
+    use schema::medication_doses::user_id as q_user_id;
+
+    diesel::update(table.filter(q_id.eq(id)).filter(q_user_id.eq(user_id)))
+        .set(update)
+        .returning(MedicationDose::as_returning())
+        .get_result(conn)
+        .await
+}
+
+pub async fn delete_medication_dose(
+    conn: &mut DatabaseConnection,
+    id: i64,
+    user_id: i64,
+) -> Result<(), diesel::result::Error> {
+    use schema::medication_doses::id as q_id;
+    use schema::medication_doses::table;
+    use schema::medication_doses::user_id as q_user_id;
+
+    diesel::delete(table.filter(q_id.eq(id)).filter(q_user_id.eq(user_id)))
+        .execute(conn)
+        .await?;
+    Ok(())
+}
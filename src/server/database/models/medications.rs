@@ -0,0 +1,187 @@
+use diesel::prelude::*;
+use diesel::{ExpressionMethods, QueryDsl, Queryable, Selectable};
+use diesel_async::RunQueryDsl;
+
+use crate::models;
+use crate::server::database::{connection::DatabaseConnection, schema};
+
+use super::consumables::Consumable;
+use super::consumptions::ConsumptionType;
+
+#[allow(dead_code)]
+#[derive(Queryable, Selectable, Debug, Clone, Identifiable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(table_name = schema::medications)]
+pub struct Medication {
+    pub id: i64,
+    pub user_id: i64,
+    pub consumable_id: i64,
+    pub dose: bigdecimal::BigDecimal,
+    pub dose_unit: String,
+    pub route: ConsumptionType,
+    pub doses_per_day: Option<i32>,
+    pub active: bool,
+    pub comments: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<(Medication, Consumable)> for crate::models::Medication {
+    fn from((medication, consumable): (Medication, Consumable)) -> Self {
+        Self {
+            id: models::MedicationId::new(medication.id),
+            user_id: models::UserId::new(medication.user_id),
+            consumable: consumable.into(),
+            dose: medication.dose,
+            dose_unit: medication.dose_unit,
+            route: medication.route.into(),
+            doses_per_day: medication.doses_per_day,
+            active: medication.active,
+            comments: medication.comments,
+            created_at: medication.created_at,
+            updated_at: medication.updated_at,
+        }
+    }
+}
+
+pub async fn get_medications(
+    conn: &mut DatabaseConnection,
+    user_id: i64,
+    include_inactive: bool,
+) -> Result<Vec<(Medication, Consumable)>, diesel::result::Error> {
+    use schema::consumables::name as q_name;
+    use schema::medications::active as q_active;
+    use schema::medications::table;
+    use schema::medications::user_id as q_user_id;
+
+    let mut query = table
+        .inner_join(schema::consumables::table)
+        .select((Medication::as_select(), Consumable::as_select()))
+        .filter(q_user_id.eq(user_id))
+        .order(q_name.asc())
+        .into_boxed();
+
+    if !include_inactive {
+        query = query.filter(q_active.eq(true));
+    }
+
+    query.load(conn).await
+}
+
+pub async fn get_medication_by_id(
+    conn: &mut DatabaseConnection,
+    id: i64,
+    user_id: i64,
+) -> Result<Option<(Medication, Consumable)>, diesel::result::Error> {
+    use schema::medications::id as q_id;
+    use schema::medications::table;
+    use schema::medications::user_id as q_user_id;
+
+    table
+        .inner_join(schema::consumables::table)
+        .select((Medication::as_select(), Consumable::as_select()))
+        .filter(q_id.eq(id))
+        .filter(q_user_id.eq(user_id))
+        .get_result(conn)
+        .await
+        .optional()
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(table_name = schema::medications)]
+pub struct NewMedication<'a> {
+    pub user_id: i64,
+    pub consumable_id: i64,
+    pub dose: &'a bigdecimal::BigDecimal,
+    pub dose_unit: &'a str,
+    pub route: ConsumptionType,
+    pub doses_per_day: Option<i32>,
+    pub active: bool,
+    pub comments: Option<&'a str>,
+}
+
+impl<'a> NewMedication<'a> {
+    pub fn from_front_end(medication: &'a crate::models::NewMedication) -> Self {
+        Self {
+            user_id: medication.user_id.as_inner(),
+            consumable_id: medication.consumable_id.as_inner(),
+            dose: &medication.dose,
+            dose_unit: &medication.dose_unit,
+            route: medication.route.into(),
+            doses_per_day: medication.doses_per_day,
+            active: medication.active,
+            comments: medication.comments.as_deref(),
+        }
+    }
+}
+
+pub async fn create_medication(
+    conn: &mut DatabaseConnection,
+    update: &NewMedication<'_>,
+) -> Result<Medication, diesel::result::Error> {
+    diesel::insert_into(schema::medications::table)
+        .values(update)
+        .returning(Medication::as_returning())
+        .get_result(conn)
+        .await
+}
+
+#[derive(AsChangeset, Debug, Clone)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(table_name = schema::medications)]
+pub struct ChangeMedication<'a> {
+    pub consumable_id: Option<i64>,
+    pub dose: Option<&'a bigdecimal::BigDecimal>,
+    pub dose_unit: Option<&'a str>,
+    pub route: Option<ConsumptionType>,
+    pub doses_per_day: Option<Option<i32>>,
+    pub active: Option<bool>,
+    pub comments: Option<Option<&'a str>>,
+}
+
+impl<'a> ChangeMedication<'a> {
+    pub fn from_front_end(medication: &'a crate::models::ChangeMedication) -> Self {
+        Self {
+            consumable_id: medication.consumable_id.map(|x| x.as_inner()).into_option(),
+            dose: medication.dose.as_ref().into_option(),
+            dose_unit: medication.dose_unit.as_deref().into_option(),
+            route: medication.route.map_into().into_option(),
+            doses_per_day: medication.doses_per_day.into_option(),
+            active: medication.active.into_option(),
+            comments: medication.comments.map_inner_deref().into_option(),
+        }
+    }
+}
+
+pub async fn update_medication(
+    conn: &mut DatabaseConnection,
+    id: i64,
+    user_id: i64,
+    update: &ChangeMedication<'_>,
+) -> Result<Medication, diesel::result::Error> {
+    use schema::medications::id as q_id;
+    use schema::medications::table;
+    use schema::medications::user_id as q_user_id;
+
+    diesel::update(table.filter(q_id.eq(id)).filter(q_user_id.eq(user_id)))
+        .set(update)
+        .returning(Medication::as_returning())
+        .get_result(conn)
+        .await
+}
+
+pub async fn delete_medication(
+    conn: &mut DatabaseConnection,
+    id: i64,
+    user_id: i64,
+) -> Result<(), diesel::result::Error> {
+    use schema::medications::id as q_id;
+    use schema::medications::table;
+    use schema::medications::user_id as q_user_id;
+
+    diesel::delete(table.filter(q_id.eq(id)).filter(q_user_id.eq(user_id)))
+        .execute(conn)
+        .await?;
+    Ok(())
+}
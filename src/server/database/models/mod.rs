@@ -1,14 +1,25 @@
+pub mod api_tokens;
+pub mod colour_palettes;
 pub mod consumables;
 pub mod consumption_consumables;
+pub mod consumption_types;
 pub mod consumptions;
+pub mod entries;
+pub mod entry_templates;
 pub mod exercises;
+pub mod fluid_adjustments;
 pub mod health_metrics;
+pub mod medication_doses;
+pub mod medications;
 pub mod nested_consumables;
 pub mod notes;
 pub mod poos;
 pub mod refluxs;
 pub mod session;
+pub mod share_links;
+pub mod stats;
 pub mod symptoms;
 pub mod users;
+pub mod webhooks;
 pub mod wee_urges;
 pub mod wees;
@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use diesel::prelude::*;
 use diesel::{ExpressionMethods, QueryDsl, Queryable, Selectable};
 use diesel_async::RunQueryDsl;
@@ -71,6 +73,42 @@ pub async fn get_parent_consumables(
     Ok(nested_consumables)
 }
 
+/// Ingredients of the given consumables, then of those ingredients in turn,
+/// until there are none left.
+pub async fn get_ingredients_recursive(
+    conn: &mut DatabaseConnection,
+    consumable_ids: Vec<i64>,
+) -> Result<Vec<(NestedConsumable, Consumable)>, diesel::result::Error> {
+    use schema::nested_consumables::dsl as q;
+    use schema::nested_consumables::table;
+
+    let mut seen = HashSet::new();
+    let mut pending = consumable_ids;
+    let mut result = Vec::new();
+
+    while !pending.is_empty() {
+        seen.extend(pending.iter().copied());
+
+        let level = table
+            .filter(q::parent_id.eq_any(pending.iter().copied()))
+            .inner_join(schema::consumables::table.on(schema::consumables::id.eq(q::consumable_id)))
+            .select((NestedConsumable::as_select(), Consumable::as_select()))
+            .get_results::<(NestedConsumable, Consumable)>(conn)
+            .await?;
+
+        pending = level
+            .iter()
+            .map(|(nested, _)| nested.consumable_id)
+            .filter(|id| !seen.contains(id))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        result.extend(level);
+    }
+
+    Ok(result)
+}
+
 // pub async fn get_nested_consumable_by_id(
 //     conn: &mut DatabaseConnection,
 //     parent_id: i64,
@@ -45,19 +45,31 @@ pub async fn get_notes_for_time_range(
     user_id: i64,
     start: chrono::DateTime<chrono::Utc>,
     end: chrono::DateTime<chrono::Utc>,
+    options: models::RangeOptions,
 ) -> Result<Vec<Note>, diesel::result::Error> {
     // use crate::server::database::schema::notes::duration as q_duration;
+    use crate::server::database::schema::notes::id as q_id;
     use crate::server::database::schema::notes::table;
     use crate::server::database::schema::notes::time as q_time;
     use crate::server::database::schema::notes::user_id as q_user_id;
 
-    table
+    let mut query = table
         .select(Note::as_select())
         .filter(q_user_id.eq(user_id))
         .filter(q_time.ge(start))
         .filter(q_time.lt(end))
-        .load(conn)
-        .await
+        .into_boxed();
+
+    query = match options.sort {
+        Some(models::SortDirection::Ascending) => query.order((q_time.asc(), q_id.asc())),
+        Some(models::SortDirection::Descending) => query.order((q_time.desc(), q_id.desc())),
+        None => query,
+    };
+    if let Some(limit) = options.limit {
+        query = query.limit(limit);
+    }
+
+    query.load(conn).await
 }
 
 pub async fn get_note_by_id(
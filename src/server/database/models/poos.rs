@@ -3,7 +3,7 @@ use diesel::prelude::*;
 use diesel_async::RunQueryDsl;
 
 use crate::{
-    models::{PooId, UserId},
+    models::{PooId, RangeOptions, SortDirection, UserId},
     server::database::{connection::DatabaseConnection, schema},
 };
 
@@ -63,17 +63,47 @@ pub async fn get_poos_for_time_range(
     user_id: i64,
     start: chrono::DateTime<chrono::Utc>,
     end: chrono::DateTime<chrono::Utc>,
+    options: RangeOptions,
 ) -> Result<Vec<Poo>, diesel::result::Error> {
-    // use crate::server::database::schema::wees::duration as q_duration;
+    use crate::server::database::schema::poos::duration as q_duration;
+    use crate::server::database::schema::poos::id as q_id;
     use crate::server::database::schema::poos::table;
     use crate::server::database::schema::poos::time as q_time;
     use crate::server::database::schema::poos::user_id as q_user_id;
 
-    table
+    let mut query = table
         .select(Poo::as_select())
         .filter(q_user_id.eq(user_id))
-        .filter(q_time.ge(start))
+        // Include entries that started earlier but are still running.
+        .filter(q_time.ge(start).or((q_time + q_duration).gt(start)))
         .filter(q_time.lt(end))
+        .into_boxed();
+
+    query = match options.sort {
+        Some(SortDirection::Ascending) => query.order((q_time.asc(), q_id.asc())),
+        Some(SortDirection::Descending) => query.order((q_time.desc(), q_id.desc())),
+        None => query,
+    };
+    if let Some(limit) = options.limit {
+        query = query.limit(limit);
+    }
+
+    query.load(conn).await
+}
+
+pub async fn get_poos_created_since(
+    conn: &mut DatabaseConnection,
+    user_id: i64,
+    since: chrono::DateTime<chrono::Utc>,
+) -> Result<Vec<Poo>, diesel::result::Error> {
+    use crate::server::database::schema::poos::created_at as q_created_at;
+    use crate::server::database::schema::poos::table;
+    use crate::server::database::schema::poos::user_id as q_user_id;
+
+    table
+        .select(Poo::as_select())
+        .filter(q_user_id.eq(user_id))
+        .filter(q_created_at.ge(since))
         .load(conn)
         .await
 }
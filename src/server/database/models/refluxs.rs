@@ -51,19 +51,32 @@ pub async fn get_refluxs_for_time_range(
     user_id: i64,
     start: chrono::DateTime<chrono::Utc>,
     end: chrono::DateTime<chrono::Utc>,
+    options: models::RangeOptions,
 ) -> Result<Vec<Reflux>, diesel::result::Error> {
-    // use crate::server::database::schema::refluxs::duration as q_duration;
+    use crate::server::database::schema::refluxs::duration as q_duration;
+    use crate::server::database::schema::refluxs::id as q_id;
     use crate::server::database::schema::refluxs::table;
     use crate::server::database::schema::refluxs::time as q_time;
     use crate::server::database::schema::refluxs::user_id as q_user_id;
 
-    table
+    let mut query = table
         .select(Reflux::as_select())
         .filter(q_user_id.eq(user_id))
-        .filter(q_time.ge(start))
+        // Include entries that started earlier but are still running.
+        .filter(q_time.ge(start).or((q_time + q_duration).gt(start)))
         .filter(q_time.lt(end))
-        .load(conn)
-        .await
+        .into_boxed();
+
+    query = match options.sort {
+        Some(models::SortDirection::Ascending) => query.order((q_time.asc(), q_id.asc())),
+        Some(models::SortDirection::Descending) => query.order((q_time.desc(), q_id.desc())),
+        None => query,
+    };
+    if let Some(limit) = options.limit {
+        query = query.limit(limit);
+    }
+
+    query.load(conn).await
 }
 
 pub async fn get_reflux_by_id(
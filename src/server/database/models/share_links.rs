@@ -0,0 +1,122 @@
+use diesel::prelude::*;
+use diesel::{ExpressionMethods, QueryDsl, Queryable, Selectable};
+use diesel_async::RunQueryDsl;
+
+use chrono::DateTime;
+use chrono::Utc;
+
+use crate::models;
+use crate::server::database::{connection::DatabaseConnection, schema};
+
+#[derive(Queryable, Selectable, Debug, Clone, Identifiable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(table_name = schema::share_links)]
+pub struct ShareLink {
+    pub id: i64,
+    pub token_hash: String,
+    pub user_id: i64,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<ShareLink> for crate::models::ShareLink {
+    fn from(share_link: ShareLink) -> Self {
+        Self {
+            id: models::ShareLinkId::new(share_link.id),
+            user_id: models::UserId::new(share_link.user_id),
+            start: share_link.start_time,
+            end: share_link.end_time,
+            expires_at: share_link.expires_at,
+            created_at: share_link.created_at,
+            updated_at: share_link.updated_at,
+        }
+    }
+}
+
+pub async fn get_share_links_for_user(
+    conn: &mut DatabaseConnection,
+    user_id: i64,
+) -> Result<Vec<ShareLink>, diesel::result::Error> {
+    use schema::share_links::created_at as q_created_at;
+    use schema::share_links::table;
+    use schema::share_links::user_id as q_user_id;
+
+    table
+        .select(ShareLink::as_select())
+        .filter(q_user_id.eq(user_id))
+        .order_by(q_created_at.desc())
+        .load(conn)
+        .await
+}
+
+/// Look up a share link by the hash of its token, ignoring links that have
+/// expired.
+pub async fn get_valid_share_link_by_token_hash(
+    conn: &mut DatabaseConnection,
+    token_hash: &str,
+    now: DateTime<Utc>,
+) -> Result<Option<ShareLink>, diesel::result::Error> {
+    use schema::share_links::expires_at as q_expires_at;
+    use schema::share_links::table;
+    use schema::share_links::token_hash as q_token_hash;
+
+    table
+        .select(ShareLink::as_select())
+        .filter(q_token_hash.eq(token_hash))
+        .filter(q_expires_at.gt(now))
+        .get_result(conn)
+        .await
+        .optional()
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(table_name = schema::share_links)]
+pub struct NewShareLink<'a> {
+    pub token_hash: &'a str,
+    pub user_id: i64,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl<'a> NewShareLink<'a> {
+    pub fn from_front_end(token_hash: &'a str, share_link: &crate::models::NewShareLink) -> Self {
+        Self {
+            token_hash,
+            user_id: share_link.user_id.as_inner(),
+            start_time: share_link.start,
+            end_time: share_link.end,
+            expires_at: share_link.expires_at,
+        }
+    }
+}
+
+pub async fn create_share_link(
+    conn: &mut DatabaseConnection,
+    new: &NewShareLink<'_>,
+) -> Result<ShareLink, diesel::result::Error> {
+    diesel::insert_into(schema::share_links::table)
+        .values(new)
+        .returning(ShareLink::as_returning())
+        .get_result(conn)
+        .await
+}
+
+pub async fn delete_share_link(
+    conn: &mut DatabaseConnection,
+    id: i64,
+    user_id: i64,
+) -> Result<(), diesel::result::Error> {
+    use schema::share_links::id as q_id;
+    use schema::share_links::table;
+    use schema::share_links::user_id as q_user_id;
+
+    diesel::delete(table.filter(q_id.eq(id)).filter(q_user_id.eq(user_id)))
+        .execute(conn)
+        .await?;
+    Ok(())
+}
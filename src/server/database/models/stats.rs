@@ -0,0 +1,321 @@
+use std::collections::HashMap;
+
+use chrono::TimeDelta;
+use diesel::QueryableByName;
+use diesel::sql_types::{BigInt, Integer, Interval, Nullable, Timestamptz};
+use diesel_async::RunQueryDsl;
+
+use crate::models::{self, DurationKind, EntryKind};
+use crate::server::database::connection::DatabaseConnection;
+
+use super::{consumptions, nested_consumables};
+
+#[derive(QueryableByName, Debug)]
+struct LastEntryTime {
+    #[diesel(sql_type = Nullable<Timestamptz>)]
+    time: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(QueryableByName, Debug)]
+struct EntryTime {
+    #[diesel(sql_type = Timestamptz)]
+    time: chrono::DateTime<chrono::Utc>,
+    #[diesel(sql_type = Integer)]
+    utc_offset: i32,
+}
+
+#[derive(QueryableByName, Debug)]
+struct EntryCount {
+    #[diesel(sql_type = BigInt)]
+    count: i64,
+}
+
+#[derive(QueryableByName, Debug)]
+pub struct DurationStats {
+    #[diesel(sql_type = BigInt)]
+    pub count: i64,
+    #[diesel(sql_type = BigInt)]
+    pub timed_count: i64,
+    #[diesel(sql_type = Interval)]
+    pub total: TimeDelta,
+    #[diesel(sql_type = Nullable<Interval>)]
+    pub mean: Option<TimeDelta>,
+    #[diesel(sql_type = Nullable<Interval>)]
+    pub median: Option<TimeDelta>,
+    #[diesel(sql_type = Nullable<Interval>)]
+    pub p90: Option<TimeDelta>,
+}
+
+impl From<DurationStats> for crate::models::DurationStats {
+    fn from(stats: DurationStats) -> Self {
+        Self {
+            count: stats.count,
+            timed_count: stats.timed_count,
+            total: stats.total,
+            mean: stats.mean,
+            median: stats.median,
+            p90: stats.p90,
+        }
+    }
+}
+
+#[derive(QueryableByName, Debug)]
+pub struct WeeUrgeLatencyStats {
+    #[diesel(sql_type = BigInt)]
+    pub urges: i64,
+    #[diesel(sql_type = BigInt)]
+    pub linked: i64,
+    #[diesel(sql_type = Nullable<Interval>)]
+    pub mean: Option<TimeDelta>,
+    #[diesel(sql_type = Nullable<Interval>)]
+    pub median: Option<TimeDelta>,
+}
+
+impl From<WeeUrgeLatencyStats> for crate::models::WeeUrgeLatencyStats {
+    fn from(stats: WeeUrgeLatencyStats) -> Self {
+        Self {
+            urges: stats.urges,
+            linked: stats.linked,
+            mean: stats.mean,
+            median: stats.median,
+        }
+    }
+}
+
+fn table_name(kind: DurationKind) -> &'static str {
+    match kind {
+        DurationKind::Wee => "wees",
+        DurationKind::Poo => "poos",
+        DurationKind::Consumption => "consumptions",
+        DurationKind::Exercise => "exercises",
+        DurationKind::Reflux => "refluxs",
+    }
+}
+
+pub async fn get_duration_stats(
+    conn: &mut DatabaseConnection,
+    user_id: i64,
+    kind: DurationKind,
+    start: chrono::DateTime<chrono::Utc>,
+    end: chrono::DateTime<chrono::Utc>,
+) -> Result<DurationStats, diesel::result::Error> {
+    // Zero durations mean the entry was not timed, including them would drag
+    // the averages and percentiles towards zero.
+    let query = format!(
+        "SELECT
+            COUNT(*) AS count,
+            COUNT(*) FILTER (WHERE duration > INTERVAL '0') AS timed_count,
+            COALESCE(SUM(duration), INTERVAL '0') AS total,
+            AVG(duration) FILTER (WHERE duration > INTERVAL '0') AS mean,
+            percentile_cont(0.5) WITHIN GROUP (ORDER BY duration)
+                FILTER (WHERE duration > INTERVAL '0') AS median,
+            percentile_cont(0.9) WITHIN GROUP (ORDER BY duration)
+                FILTER (WHERE duration > INTERVAL '0') AS p90
+        FROM {}
+        WHERE user_id = $1 AND time >= $2 AND time < $3",
+        table_name(kind)
+    );
+
+    diesel::sql_query(query)
+        .bind::<BigInt, _>(user_id)
+        .bind::<Timestamptz, _>(start)
+        .bind::<Timestamptz, _>(end)
+        .get_result(conn)
+        .await
+}
+
+/// Time from each urge in the period to the wee that was linked to it.
+pub async fn get_wee_urge_latency_stats(
+    conn: &mut DatabaseConnection,
+    user_id: i64,
+    start: chrono::DateTime<chrono::Utc>,
+    end: chrono::DateTime<chrono::Utc>,
+) -> Result<WeeUrgeLatencyStats, diesel::result::Error> {
+    let query = "SELECT
+            COUNT(*) AS urges,
+            COUNT(wees.id) AS linked,
+            AVG(wees.time - wee_urges.time) AS mean,
+            percentile_cont(0.5) WITHIN GROUP (ORDER BY wees.time - wee_urges.time)
+                FILTER (WHERE wees.id IS NOT NULL) AS median
+        FROM wee_urges
+        LEFT JOIN wees ON wees.wee_urge_id = wee_urges.id
+        WHERE wee_urges.user_id = $1 AND wee_urges.time >= $2 AND wee_urges.time < $3";
+
+    diesel::sql_query(query)
+        .bind::<BigInt, _>(user_id)
+        .bind::<Timestamptz, _>(start)
+        .bind::<Timestamptz, _>(end)
+        .get_result(conn)
+        .await
+}
+
+fn entry_table_name(kind: EntryKind) -> &'static str {
+    match kind {
+        EntryKind::Poo => "poos",
+        EntryKind::Wee => "wees",
+        EntryKind::WeeUrge => "wee_urges",
+        EntryKind::Consumption => "consumptions",
+        EntryKind::Exercise => "exercises",
+        EntryKind::HealthMetric => "health_metrics",
+        EntryKind::Symptom => "symptoms",
+        EntryKind::Reflux => "refluxs",
+        EntryKind::Note => "notes",
+        EntryKind::FluidAdjustment => "fluid_adjustments",
+        EntryKind::Medication => "medication_doses",
+    }
+}
+
+/// Times of the user's entries of one kind, in the offset each was recorded
+/// with.
+pub async fn get_entry_times(
+    conn: &mut DatabaseConnection,
+    user_id: i64,
+    kind: EntryKind,
+    start: chrono::DateTime<chrono::Utc>,
+    end: chrono::DateTime<chrono::Utc>,
+) -> Result<Vec<chrono::DateTime<chrono::FixedOffset>>, diesel::result::Error> {
+    let query = format!(
+        "SELECT time, utc_offset FROM {} WHERE user_id = $1 AND time >= $2 AND time < $3",
+        entry_table_name(kind)
+    );
+
+    let rows: Vec<EntryTime> = diesel::sql_query(query)
+        .bind::<BigInt, _>(user_id)
+        .bind::<Timestamptz, _>(start)
+        .bind::<Timestamptz, _>(end)
+        .load(conn)
+        .await?;
+
+    let times = rows
+        .into_iter()
+        .map(|row| {
+            let offset = chrono::FixedOffset::east_opt(row.utc_offset)
+                .unwrap_or(chrono::FixedOffset::east_opt(0).unwrap());
+            row.time.with_timezone(&offset)
+        })
+        .collect();
+    Ok(times)
+}
+
+/// Tables holding timeline entries, all of which have `user_id` and `time`.
+const ENTRY_TABLES: [&str; 11] = [
+    "wees",
+    "wee_urges",
+    "poos",
+    "consumptions",
+    "exercises",
+    "health_metrics",
+    "symptoms",
+    "refluxs",
+    "notes",
+    "fluid_adjustments",
+    "medication_doses",
+];
+
+/// Time of the user's most recent entry of any kind.
+pub async fn get_last_entry_time(
+    conn: &mut DatabaseConnection,
+    user_id: i64,
+) -> Result<Option<chrono::DateTime<chrono::Utc>>, diesel::result::Error> {
+    let selects = ENTRY_TABLES
+        .iter()
+        .map(|table| format!("SELECT MAX(time) AS time FROM {table} WHERE user_id = $1"))
+        .collect::<Vec<_>>()
+        .join(" UNION ALL ");
+    let query = format!("SELECT MAX(time) AS time FROM ({selects}) AS entries");
+
+    diesel::sql_query(query)
+        .bind::<BigInt, _>(user_id)
+        .get_result::<LastEntryTime>(conn)
+        .await
+        .map(|row| row.time)
+}
+
+/// Number of entries of any kind in each of the given time windows.
+pub async fn get_entry_counts_for_range(
+    conn: &mut DatabaseConnection,
+    user_id: i64,
+    windows: &[(chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>)],
+) -> Result<Vec<i64>, diesel::result::Error> {
+    let selects = ENTRY_TABLES
+        .iter()
+        .map(|table| {
+            format!(
+                "SELECT COUNT(*) AS count FROM {table} WHERE user_id = $1 AND time >= $2 AND time < $3"
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" UNION ALL ");
+    let query =
+        format!("SELECT COALESCE(SUM(count), 0)::BIGINT AS count FROM ({selects}) AS entries");
+
+    let mut counts = Vec::with_capacity(windows.len());
+    for &(start, end) in windows {
+        let row: EntryCount = diesel::sql_query(&query)
+            .bind::<BigInt, _>(user_id)
+            .bind::<Timestamptz, _>(start)
+            .bind::<Timestamptz, _>(end)
+            .get_result(conn)
+            .await?;
+        counts.push(row.count);
+    }
+    Ok(counts)
+}
+
+/// Nutrition taken in by consumptions that started between `start` and
+/// `end`, including the ingredients of nested consumables.
+pub async fn get_nutrition_totals(
+    conn: &mut DatabaseConnection,
+    user_id: i64,
+    start: chrono::DateTime<chrono::Utc>,
+    end: chrono::DateTime<chrono::Utc>,
+) -> Result<models::NutritionTotals, diesel::result::Error> {
+    let consumptions: Vec<models::ConsumptionWithItems> =
+        consumptions::get_consumptions_for_time_range(
+            conn,
+            user_id,
+            start,
+            end,
+            models::RangeOptions::default(),
+        )
+        .await?
+        .into_iter()
+        // Leave out consumptions still running from an earlier period.
+        .filter(|(consumption, _)| consumption.time >= start)
+        .map(|(consumption, items)| {
+            let items = items
+                .into_iter()
+                .map(|(nested, consumable)| {
+                    models::ConsumptionItem::new(nested.into(), consumable.into())
+                })
+                .collect();
+            models::ConsumptionWithItems::new(consumption.into(), items)
+        })
+        .collect();
+
+    let consumable_ids = consumptions
+        .iter()
+        .flat_map(|consumption| &consumption.items)
+        .map(|item| item.consumable.id.as_inner())
+        .collect();
+
+    let mut ingredients: HashMap<models::ConsumableId, Vec<models::ConsumableItem>> =
+        HashMap::new();
+    for (nested, consumable) in
+        nested_consumables::get_ingredients_recursive(conn, consumable_ids).await?
+    {
+        ingredients
+            .entry(models::ConsumableId::new(nested.parent_id))
+            .or_default()
+            .push(models::ConsumableItem::new(
+                nested.into(),
+                consumable.into(),
+            ));
+    }
+
+    Ok(models::NutritionTotals::new(
+        &consumptions,
+        &ingredients,
+        chrono::Utc::now(),
+    ))
+}
@@ -1,4 +1,4 @@
-use crate::models::{SymptomId, UserId};
+use crate::models::{RangeOptions, SortDirection, SymptomId, UserId};
 use crate::server::database::{connection::DatabaseConnection, schema};
 use chrono::Utc;
 use diesel::prelude::*;
@@ -117,19 +117,31 @@ pub async fn get_symptoms_for_time_range(
     user_id: i64,
     start: chrono::DateTime<chrono::Utc>,
     end: chrono::DateTime<chrono::Utc>,
+    options: RangeOptions,
 ) -> Result<Vec<Symptom>, diesel::result::Error> {
     // use crate::server::database::schema::symptoms::duration as q_duration;
+    use crate::server::database::schema::symptoms::id as q_id;
     use crate::server::database::schema::symptoms::table;
     use crate::server::database::schema::symptoms::time as q_time;
     use crate::server::database::schema::symptoms::user_id as q_user_id;
 
-    table
+    let mut query = table
         .select(Symptom::as_select())
         .filter(q_user_id.eq(user_id))
         .filter(q_time.ge(start))
         .filter(q_time.lt(end))
-        .load(conn)
-        .await
+        .into_boxed();
+
+    query = match options.sort {
+        Some(SortDirection::Ascending) => query.order((q_time.asc(), q_id.asc())),
+        Some(SortDirection::Descending) => query.order((q_time.desc(), q_id.desc())),
+        None => query,
+    };
+    if let Some(limit) = options.limit {
+        query = query.limit(limit);
+    }
+
+    query.load(conn).await
 }
 
 pub async fn get_symptom_by_id(
@@ -256,6 +268,17 @@ pub async fn create_symptom(
         .await
 }
 
+pub async fn create_symptoms(
+    conn: &mut DatabaseConnection,
+    updates: &[NewSymptom<'_>],
+) -> Result<Vec<Symptom>, diesel::result::Error> {
+    diesel::insert_into(schema::symptoms::table)
+        .values(updates)
+        .returning(Symptom::as_returning())
+        .get_results(conn)
+        .await
+}
+
 #[derive(AsChangeset, Debug, Clone)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 #[diesel(table_name = schema::symptoms)]
@@ -40,13 +40,27 @@ pub struct UserGroup {
 pub struct User {
     pub id: i64,
     pub username: String,
-    pub password: String,
+    /// Hashed password, or None for accounts that only log in with SSO.
+    pub password: Option<String>,
     pub full_name: String,
     pub oidc_id: Option<String>,
     pub email: String,
     pub is_admin: bool,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub volume_presets: Vec<i32>,
+    pub group_window_minutes: Option<i32>,
+    pub locale: Option<String>,
+    pub date_format: Option<String>,
+    pub last_login_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub is_active: bool,
+    pub landing_date: Option<String>,
+    pub timezone: Option<String>,
+    pub max_heart_rate: Option<i32>,
+    pub distance_unit: Option<String>,
+    pub duration_rounding_minutes: Option<i32>,
+    pub glucose_unit: Option<String>,
+    pub theme: Option<String>,
 }
 
 impl AuthUser for User {
@@ -57,7 +71,7 @@ impl AuthUser for User {
     }
 
     fn session_auth_hash(&self) -> &[u8] {
-        self.password.as_bytes()
+        self.password.as_deref().unwrap_or_default().as_bytes()
     }
 }
 
@@ -72,6 +86,27 @@ impl From<User> for crate::models::User {
             is_admin: user.is_admin,
             created_at: user.created_at,
             updated_at: user.updated_at,
+            volume_presets: user.volume_presets,
+            group_window_minutes: user.group_window_minutes,
+            locale: user.locale.and_then(|locale| locale.parse().ok()),
+            date_format: user
+                .date_format
+                .and_then(|date_format| date_format.parse().ok()),
+            last_login_at: user.last_login_at,
+            is_active: user.is_active,
+            landing_date: user
+                .landing_date
+                .and_then(|landing_date| landing_date.parse().ok()),
+            timezone: user.timezone.and_then(|timezone| timezone.parse().ok()),
+            max_heart_rate: user.max_heart_rate,
+            distance_unit: user
+                .distance_unit
+                .and_then(|distance_unit| distance_unit.parse().ok()),
+            duration_rounding_minutes: user.duration_rounding_minutes,
+            glucose_unit: user
+                .glucose_unit
+                .and_then(|glucose_unit| glucose_unit.parse().ok()),
+            theme: user.theme.and_then(|theme| theme.parse().ok()),
         }
     }
 }
@@ -81,7 +116,7 @@ impl From<User> for crate::models::User {
 #[diesel(table_name = schema::users)]
 pub struct NewUser<'a> {
     pub username: &'a str,
-    pub password: &'a str,
+    pub password: Option<&'a str>,
     pub full_name: &'a str,
     pub oidc_id: Option<&'a str>,
     pub email: &'a str,
@@ -89,7 +124,10 @@ pub struct NewUser<'a> {
 }
 
 impl<'a> NewUser<'a> {
-    pub fn from_front_end(user: &'a crate::models::NewUser, hashed_password: &'a str) -> Self {
+    pub fn from_front_end(
+        user: &'a crate::models::NewUser,
+        hashed_password: Option<&'a str>,
+    ) -> Self {
         Self {
             username: &user.username,
             password: hashed_password,
@@ -111,6 +149,7 @@ pub struct UpdateUser<'a> {
     pub oidc_id: Option<Option<&'a str>>,
     pub email: Option<&'a str>,
     pub is_admin: Option<bool>,
+    pub is_active: Option<bool>,
 }
 
 impl<'a> UpdateUser<'a> {
@@ -125,6 +164,63 @@ impl<'a> UpdateUser<'a> {
             oidc_id: user.oidc_id.map_inner_deref().into_option(),
             email: user.email.as_deref().into_option(),
             is_admin: user.is_admin.into_option(),
+            is_active: user.is_active.into_option(),
+        }
+    }
+}
+
+#[derive(AsChangeset, Debug, Clone)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(table_name = schema::users)]
+pub struct UpdatePreferences<'a> {
+    pub volume_presets: Option<&'a [i32]>,
+    pub group_window_minutes: Option<Option<i32>>,
+    pub locale: Option<Option<&'a str>>,
+    pub date_format: Option<Option<&'a str>>,
+    pub landing_date: Option<Option<&'a str>>,
+    pub timezone: Option<Option<&'a str>>,
+    pub max_heart_rate: Option<Option<i32>>,
+    pub distance_unit: Option<Option<&'a str>>,
+    pub duration_rounding_minutes: Option<Option<i32>>,
+    pub glucose_unit: Option<Option<&'a str>>,
+    pub theme: Option<Option<&'a str>>,
+}
+
+impl<'a> UpdatePreferences<'a> {
+    pub fn from_front_end(preferences: &'a crate::models::ChangePreferences) -> Self {
+        Self {
+            volume_presets: preferences.volume_presets.as_deref().into_option(),
+            group_window_minutes: preferences.group_window_minutes.into_option(),
+            locale: preferences
+                .locale
+                .map(|locale| locale.map(|locale| locale.as_id()))
+                .into_option(),
+            date_format: preferences
+                .date_format
+                .map(|date_format| date_format.map(|date_format| date_format.as_id()))
+                .into_option(),
+            landing_date: preferences
+                .landing_date
+                .map(|landing_date| landing_date.map(|landing_date| landing_date.as_id()))
+                .into_option(),
+            timezone: preferences
+                .timezone
+                .map(|timezone| timezone.map(|timezone| timezone.name()))
+                .into_option(),
+            max_heart_rate: preferences.max_heart_rate.into_option(),
+            distance_unit: preferences
+                .distance_unit
+                .map(|distance_unit| distance_unit.map(|distance_unit| distance_unit.as_id()))
+                .into_option(),
+            duration_rounding_minutes: preferences.duration_rounding_minutes.into_option(),
+            glucose_unit: preferences
+                .glucose_unit
+                .map(|glucose_unit| glucose_unit.map(|glucose_unit| glucose_unit.as_id()))
+                .into_option(),
+            theme: preferences
+                .theme
+                .map(|theme| theme.map(|theme| theme.as_id()))
+                .into_option(),
         }
     }
 }
@@ -177,6 +273,8 @@ pub async fn get_user_by_oidc_id(
         .pipe(Ok)
 }
 
+/// Find the only user with this email. Stored emails are lower case, so the
+/// lookup is case insensitive.
 pub async fn get_user_by_email(
     conn: &mut DatabaseConnection,
     email: &str,
@@ -184,6 +282,8 @@ pub async fn get_user_by_email(
     use schema::users::email as q_email;
     use schema::users::table;
 
+    let email = email.trim().to_lowercase();
+
     table
         .select(User::as_select())
         .filter(q_email.eq(email))
@@ -204,6 +304,39 @@ pub async fn get_users(conn: &mut DatabaseConnection) -> Result<Vec<User>, diese
     table.select(User::as_select()).load(conn).await
 }
 
+/// One page of users matching a search on username, email or full name,
+/// ordered by username, along with the total number of matches.
+pub async fn get_users_paged(
+    conn: &mut DatabaseConnection,
+    offset: i64,
+    limit: i64,
+    search: &str,
+) -> Result<(Vec<User>, i64), diesel::result::Error> {
+    use schema::users::dsl as q;
+    use schema::users::table;
+
+    let pattern = format!("%{search}%");
+    let matches = || {
+        table.into_boxed::<diesel::pg::Pg>().filter(
+            q::username
+                .ilike(pattern.clone())
+                .or(q::email.ilike(pattern.clone()))
+                .or(q::full_name.ilike(pattern.clone())),
+        )
+    };
+
+    let total = matches().count().get_result(conn).await?;
+    let users = matches()
+        .select(User::as_select())
+        .order(q::username.asc())
+        .offset(offset)
+        .limit(limit)
+        .load(conn)
+        .await?;
+
+    Ok((users, total))
+}
+
 pub async fn create_user(
     conn: &mut DatabaseConnection,
     updates: NewUser<'_>,
@@ -217,6 +350,21 @@ pub async fn create_user(
         .await
 }
 
+pub async fn record_login(
+    conn: &mut DatabaseConnection,
+    id: i64,
+) -> Result<User, diesel::result::Error> {
+    use schema::users::dsl as q;
+    use schema::users::table;
+
+    diesel::update(table)
+        .filter(q::id.eq(id))
+        .set(q::last_login_at.eq(chrono::Utc::now()))
+        .returning(User::as_returning())
+        .get_result(conn)
+        .await
+}
+
 pub async fn update_user(
     conn: &mut DatabaseConnection,
     id: i64,
@@ -233,17 +381,117 @@ pub async fn update_user(
         .await
 }
 
-pub async fn delete_user(
+pub async fn update_preferences(
     conn: &mut DatabaseConnection,
     id: i64,
-) -> Result<(), diesel::result::Error> {
+    updates: UpdatePreferences<'_>,
+) -> Result<User, diesel::result::Error> {
     use schema::users::id as q_id;
     use schema::users::table;
 
-    table
+    diesel::update(table)
         .filter(q_id.eq(id))
-        .pipe(diesel::delete)
-        .execute(conn)
+        .set(&updates)
+        .returning(User::as_returning())
+        .get_result(conn)
         .await
-        .pipe(|_| Ok(()))
+}
+
+/// Delete a user together with everything they recorded, in a single
+/// transaction. Consumables are shared between users and are kept.
+pub async fn delete_user_and_data(
+    conn: &mut DatabaseConnection,
+    id: i64,
+) -> Result<(), diesel::result::Error> {
+    use diesel_async::AsyncConnection;
+    use diesel_async::scoped_futures::ScopedFutureExt;
+
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        async move {
+            // Rows in consumption_consumables go with their consumption.
+            diesel::delete(schema::consumptions::table)
+                .filter(schema::consumptions::user_id.eq(id))
+                .execute(conn)
+                .await?;
+            diesel::delete(schema::consumption_types::table)
+                .filter(schema::consumption_types::user_id.eq(id))
+                .execute(conn)
+                .await?;
+            diesel::delete(schema::medication_doses::table)
+                .filter(schema::medication_doses::user_id.eq(id))
+                .execute(conn)
+                .await?;
+            diesel::delete(schema::medications::table)
+                .filter(schema::medications::user_id.eq(id))
+                .execute(conn)
+                .await?;
+            diesel::delete(schema::exercises::table)
+                .filter(schema::exercises::user_id.eq(id))
+                .execute(conn)
+                .await?;
+            diesel::delete(schema::fluid_adjustments::table)
+                .filter(schema::fluid_adjustments::user_id.eq(id))
+                .execute(conn)
+                .await?;
+            diesel::delete(schema::health_metrics::table)
+                .filter(schema::health_metrics::user_id.eq(id))
+                .execute(conn)
+                .await?;
+            diesel::delete(schema::notes::table)
+                .filter(schema::notes::user_id.eq(id))
+                .execute(conn)
+                .await?;
+            diesel::delete(schema::poos::table)
+                .filter(schema::poos::user_id.eq(id))
+                .execute(conn)
+                .await?;
+            diesel::delete(schema::refluxs::table)
+                .filter(schema::refluxs::user_id.eq(id))
+                .execute(conn)
+                .await?;
+            diesel::delete(schema::api_tokens::table)
+                .filter(schema::api_tokens::user_id.eq(id))
+                .execute(conn)
+                .await?;
+            diesel::delete(schema::colour_palettes::table)
+                .filter(schema::colour_palettes::user_id.eq(id))
+                .execute(conn)
+                .await?;
+            diesel::delete(schema::templates::table)
+                .filter(schema::templates::user_id.eq(id))
+                .execute(conn)
+                .await?;
+            diesel::delete(schema::share_links::table)
+                .filter(schema::share_links::user_id.eq(id))
+                .execute(conn)
+                .await?;
+            diesel::delete(schema::webhooks::table)
+                .filter(schema::webhooks::user_id.eq(id))
+                .execute(conn)
+                .await?;
+            diesel::delete(schema::symptoms::table)
+                .filter(schema::symptoms::user_id.eq(id))
+                .execute(conn)
+                .await?;
+            diesel::delete(schema::wee_urges::table)
+                .filter(schema::wee_urges::user_id.eq(id))
+                .execute(conn)
+                .await?;
+            diesel::delete(schema::wees::table)
+                .filter(schema::wees::user_id.eq(id))
+                .execute(conn)
+                .await?;
+            diesel::delete(schema::user_groups::table)
+                .filter(schema::user_groups::user_id.eq(id))
+                .execute(conn)
+                .await?;
+            diesel::delete(schema::users::table)
+                .filter(schema::users::id.eq(id))
+                .execute(conn)
+                .await?;
+            Ok(())
+        }
+        .scope_boxed()
+    })
+    .await
 }
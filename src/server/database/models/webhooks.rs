@@ -0,0 +1,121 @@
+use diesel::prelude::*;
+use diesel::{ExpressionMethods, QueryDsl, Queryable, Selectable};
+use diesel_async::RunQueryDsl;
+
+use chrono::DateTime;
+use chrono::Utc;
+use tracing::error;
+
+use crate::models;
+use crate::server::database::{connection::DatabaseConnection, schema};
+
+#[derive(Queryable, Selectable, Debug, Clone, Identifiable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(table_name = schema::webhooks)]
+pub struct Webhook {
+    pub id: i64,
+    pub user_id: i64,
+    pub url: String,
+    pub secret: String,
+    pub kinds: Vec<String>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Webhook {
+    /// The kinds of entry that trigger the webhook. Unknown values are skipped.
+    pub fn kinds(&self) -> Vec<models::EntryKind> {
+        self.kinds
+            .iter()
+            .filter_map(|kind| match kind.parse() {
+                Ok(kind) => Some(kind),
+                Err(_) => {
+                    error!("Invalid kind {kind} for webhook {}", self.id);
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+impl From<Webhook> for crate::models::Webhook {
+    fn from(webhook: Webhook) -> Self {
+        Self {
+            id: models::WebhookId::new(webhook.id),
+            user_id: models::UserId::new(webhook.user_id),
+            kinds: webhook.kinds(),
+            url: webhook.url,
+            is_active: webhook.is_active,
+            created_at: webhook.created_at,
+            updated_at: webhook.updated_at,
+        }
+    }
+}
+
+pub async fn get_webhooks_for_user(
+    conn: &mut DatabaseConnection,
+    user_id: i64,
+) -> Result<Vec<Webhook>, diesel::result::Error> {
+    use schema::webhooks::created_at as q_created_at;
+    use schema::webhooks::table;
+    use schema::webhooks::user_id as q_user_id;
+
+    table
+        .select(Webhook::as_select())
+        .filter(q_user_id.eq(user_id))
+        .order_by(q_created_at.desc())
+        .load(conn)
+        .await
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(table_name = schema::webhooks)]
+pub struct NewWebhook<'a> {
+    pub user_id: i64,
+    pub url: &'a str,
+    pub secret: &'a str,
+    pub kinds: Vec<String>,
+}
+
+impl<'a> NewWebhook<'a> {
+    pub fn from_front_end(secret: &'a str, webhook: &'a crate::models::NewWebhook) -> Self {
+        Self {
+            user_id: webhook.user_id.as_inner(),
+            url: &webhook.url,
+            secret,
+            kinds: webhook
+                .kinds
+                .iter()
+                .map(|kind| kind.as_id().to_string())
+                .collect(),
+        }
+    }
+}
+
+pub async fn create_webhook(
+    conn: &mut DatabaseConnection,
+    new: &NewWebhook<'_>,
+) -> Result<Webhook, diesel::result::Error> {
+    diesel::insert_into(schema::webhooks::table)
+        .values(new)
+        .returning(Webhook::as_returning())
+        .get_result(conn)
+        .await
+}
+
+pub async fn delete_webhook(
+    conn: &mut DatabaseConnection,
+    id: i64,
+    user_id: i64,
+) -> Result<(), diesel::result::Error> {
+    use schema::webhooks::id as q_id;
+    use schema::webhooks::table;
+    use schema::webhooks::user_id as q_user_id;
+
+    diesel::delete(table.filter(q_id.eq(id)).filter(q_user_id.eq(user_id)))
+        .execute(conn)
+        .await?;
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use crate::models::{UserId, WeeUrgeId};
+use crate::models::{RangeOptions, SortDirection, UserId, WeeUrgeId};
 use crate::server::database::{connection::DatabaseConnection, schema};
 use chrono::Utc;
 use diesel::prelude::*;
@@ -45,17 +45,63 @@ pub async fn get_wee_urges_for_time_range(
     user_id: i64,
     start: chrono::DateTime<chrono::Utc>,
     end: chrono::DateTime<chrono::Utc>,
+    options: RangeOptions,
 ) -> Result<Vec<WeeUrge>, diesel::result::Error> {
     // use crate::server::database::schema::wee_urges::duration as q_duration;
+    use crate::server::database::schema::wee_urges::id as q_id;
+    use crate::server::database::schema::wee_urges::table;
+    use crate::server::database::schema::wee_urges::time as q_time;
+    use crate::server::database::schema::wee_urges::user_id as q_user_id;
+
+    let mut query = table
+        .select(WeeUrge::as_select())
+        .filter(q_user_id.eq(user_id))
+        .filter(q_time.ge(start))
+        .filter(q_time.lt(end))
+        .into_boxed();
+
+    query = match options.sort {
+        Some(SortDirection::Ascending) => query.order((q_time.asc(), q_id.asc())),
+        Some(SortDirection::Descending) => query.order((q_time.desc(), q_id.desc())),
+        None => query,
+    };
+    if let Some(limit) = options.limit {
+        query = query.limit(limit);
+    }
+
+    query.load(conn).await
+}
+
+pub async fn get_unlinked_wee_urges(
+    conn: &mut DatabaseConnection,
+    user_id: i64,
+    start: chrono::DateTime<chrono::Utc>,
+    end: chrono::DateTime<chrono::Utc>,
+    exclude_wee_id: Option<i64>,
+) -> Result<Vec<WeeUrge>, diesel::result::Error> {
+    use crate::server::database::schema::wee_urges::id as q_id;
     use crate::server::database::schema::wee_urges::table;
     use crate::server::database::schema::wee_urges::time as q_time;
     use crate::server::database::schema::wee_urges::user_id as q_user_id;
+    use crate::server::database::schema::wees;
+
+    let mut linked = wees::table
+        .select(wees::wee_urge_id.assume_not_null())
+        .filter(wees::user_id.eq(user_id))
+        .filter(wees::wee_urge_id.is_not_null())
+        .into_boxed();
+
+    if let Some(exclude_wee_id) = exclude_wee_id {
+        linked = linked.filter(wees::id.ne(exclude_wee_id));
+    }
 
     table
         .select(WeeUrge::as_select())
         .filter(q_user_id.eq(user_id))
         .filter(q_time.ge(start))
         .filter(q_time.lt(end))
+        .filter(q_id.ne_all(linked))
+        .order(q_time.desc())
         .load(conn)
         .await
 }
@@ -1,4 +1,4 @@
-use crate::models::{UserId, WeeId};
+use crate::models::{RangeOptions, SortDirection, UserId, WeeId, WeeUrgeId};
 use crate::server::database::{connection::DatabaseConnection, schema};
 use chrono::Utc;
 use diesel::prelude::*;
@@ -24,6 +24,7 @@ pub struct Wee {
     pub updated_at: chrono::DateTime<chrono::Utc>,
     pub utc_offset: i32,
     pub leakage: i32,
+    pub wee_urge_id: Option<i64>,
 }
 
 const DEFAULT_TIMEZONE: chrono::FixedOffset = chrono::FixedOffset::east_opt(0).unwrap();
@@ -52,6 +53,7 @@ impl From<Wee> for crate::models::Wee {
             created_at: wee.created_at,
             updated_at: wee.updated_at,
             comments: wee.comments,
+            wee_urge_id: wee.wee_urge_id.map(WeeUrgeId::new),
         }
     }
 }
@@ -61,17 +63,47 @@ pub async fn get_wees_for_time_range(
     user_id: i64,
     start: chrono::DateTime<chrono::Utc>,
     end: chrono::DateTime<chrono::Utc>,
+    options: RangeOptions,
 ) -> Result<Vec<Wee>, diesel::result::Error> {
-    // use crate::server::database::schema::wees::duration as q_duration;
+    use crate::server::database::schema::wees::duration as q_duration;
+    use crate::server::database::schema::wees::id as q_id;
     use crate::server::database::schema::wees::table;
     use crate::server::database::schema::wees::time as q_time;
     use crate::server::database::schema::wees::user_id as q_user_id;
 
-    table
+    let mut query = table
         .select(Wee::as_select())
         .filter(q_user_id.eq(user_id))
-        .filter(q_time.ge(start))
+        // Include entries that started earlier but are still running.
+        .filter(q_time.ge(start).or((q_time + q_duration).gt(start)))
         .filter(q_time.lt(end))
+        .into_boxed();
+
+    query = match options.sort {
+        Some(SortDirection::Ascending) => query.order((q_time.asc(), q_id.asc())),
+        Some(SortDirection::Descending) => query.order((q_time.desc(), q_id.desc())),
+        None => query,
+    };
+    if let Some(limit) = options.limit {
+        query = query.limit(limit);
+    }
+
+    query.load(conn).await
+}
+
+pub async fn get_wees_created_since(
+    conn: &mut DatabaseConnection,
+    user_id: i64,
+    since: chrono::DateTime<chrono::Utc>,
+) -> Result<Vec<Wee>, diesel::result::Error> {
+    use crate::server::database::schema::wees::created_at as q_created_at;
+    use crate::server::database::schema::wees::table;
+    use crate::server::database::schema::wees::user_id as q_user_id;
+
+    table
+        .select(Wee::as_select())
+        .filter(q_user_id.eq(user_id))
+        .filter(q_created_at.ge(since))
         .load(conn)
         .await
 }
@@ -109,6 +141,7 @@ pub struct NewWee<'a> {
     colour_saturation: Option<f32>,
     colour_value: Option<f32>,
     comments: Option<&'a str>,
+    wee_urge_id: Option<i64>,
 }
 
 impl<'a> NewWee<'a> {
@@ -125,6 +158,7 @@ impl<'a> NewWee<'a> {
             colour_saturation: wee.colour.map(|colour| colour.saturation),
             colour_value: wee.colour.map(|colour| colour.value),
             comments: wee.comments.as_deref(),
+            wee_urge_id: wee.wee_urge_id.map(|id| id.as_inner()),
         }
     }
 }
@@ -154,6 +188,7 @@ pub struct ChangeWee<'a> {
     colour_saturation: Option<Option<f32>>,
     colour_value: Option<Option<f32>>,
     comments: Option<Option<&'a str>>,
+    wee_urge_id: Option<Option<i64>>,
 }
 
 impl<'a> ChangeWee<'a> {
@@ -181,6 +216,10 @@ impl<'a> ChangeWee<'a> {
                 .map(|x| x.map(|colour| colour.value))
                 .into_option(),
             comments: wee.comments.as_ref().map(|x| x.as_deref()).into_option(),
+            wee_urge_id: wee
+                .wee_urge_id
+                .map(|x| x.map(|id| id.as_inner()))
+                .into_option(),
         }
     }
 }
@@ -211,3 +250,45 @@ pub async fn delete_wee(
         .await?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MaybeSet;
+
+    #[test]
+    fn change_wee_leaves_untouched_fields_alone() {
+        let time = chrono::DateTime::parse_from_rfc3339("2026-01-01T10:00:00+10:00").unwrap();
+        let change = crate::models::ChangeWee {
+            time: MaybeSet::Set(time),
+            ..Default::default()
+        };
+        let updates = ChangeWee::from_front_end(&change);
+
+        assert_eq!(updates.time, Some(time.with_timezone(&Utc)));
+        assert_eq!(updates.utc_offset, Some(10 * 60 * 60));
+        assert_eq!(updates.duration, None);
+        assert_eq!(updates.urgency, None);
+        assert_eq!(updates.leakage, None);
+        assert_eq!(updates.mls, None);
+        assert_eq!(updates.colour_hue, None);
+        assert_eq!(updates.colour_saturation, None);
+        assert_eq!(updates.colour_value, None);
+        assert_eq!(updates.comments, None);
+        assert_eq!(updates.wee_urge_id, None);
+    }
+
+    #[test]
+    fn change_wee_can_clear_nullable_fields() {
+        let change = crate::models::ChangeWee {
+            colour: MaybeSet::Set(None),
+            comments: MaybeSet::Set(None),
+            ..Default::default()
+        };
+        let updates = ChangeWee::from_front_end(&change);
+
+        assert_eq!(updates.time, None);
+        assert_eq!(updates.colour_hue, Some(None));
+        assert_eq!(updates.comments, Some(None));
+    }
+}
@@ -14,6 +14,32 @@ pub mod sql_types {
     pub struct ExerciseType;
 }
 
+diesel::table! {
+    api_tokens (id) {
+        id -> Int8,
+        user_id -> Int8,
+        name -> Text,
+        token_hash -> Text,
+        scope -> Text,
+        last_used_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    colour_palettes (id) {
+        id -> Int8,
+        user_id -> Int8,
+        name -> Text,
+        kind -> Text,
+        colours -> Jsonb,
+        is_active -> Bool,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::ConsumableUnit;
@@ -32,6 +58,11 @@ diesel::table! {
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
         consumption_type -> Nullable<ConsumptionType>,
+        calories -> Nullable<Numeric>,
+        protein -> Nullable<Numeric>,
+        fat -> Nullable<Numeric>,
+        carbohydrate -> Nullable<Numeric>,
+        image_key -> Nullable<Text>,
     }
 }
 
@@ -44,6 +75,22 @@ diesel::table! {
         comments -> Nullable<Text>,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        portion -> Numeric,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::ConsumptionType;
+
+    consumption_types (id) {
+        id -> Int8,
+        user_id -> Int8,
+        name -> Text,
+        icon -> Text,
+        base_type -> ConsumptionType,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
     }
 }
 
@@ -62,6 +109,8 @@ diesel::table! {
         updated_at -> Timestamptz,
         utc_offset -> Int4,
         consumption_type -> ConsumptionType,
+        planned -> Bool,
+        custom_type_id -> Nullable<Int8>,
     }
 }
 
@@ -83,6 +132,21 @@ diesel::table! {
         comments -> Nullable<Text>,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        avg_heart_rate -> Nullable<Int4>,
+        max_heart_rate -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    fluid_adjustments (id) {
+        id -> Int8,
+        user_id -> Int8,
+        time -> Timestamptz,
+        utc_offset -> Int4,
+        mls -> Int4,
+        comments -> Nullable<Text>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
     }
 }
 
@@ -114,6 +178,39 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    medication_doses (id) {
+        id -> Int8,
+        user_id -> Int8,
+        medication_id -> Int8,
+        time -> Timestamptz,
+        utc_offset -> Int4,
+        dose -> Numeric,
+        comments -> Nullable<Text>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::ConsumptionType;
+
+    medications (id) {
+        id -> Int8,
+        user_id -> Int8,
+        consumable_id -> Int8,
+        dose -> Numeric,
+        dose_unit -> Text,
+        route -> ConsumptionType,
+        doses_per_day -> Nullable<Int4>,
+        active -> Bool,
+        comments -> Nullable<Text>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     nested_consumables (parent_id, consumable_id) {
         parent_id -> Int8,
@@ -180,6 +277,19 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    share_links (id) {
+        id -> Int8,
+        token_hash -> Text,
+        user_id -> Int8,
+        start_time -> Timestamptz,
+        end_time -> Timestamptz,
+        expires_at -> Timestamptz,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     symptoms (id) {
         id -> Int8,
@@ -229,6 +339,18 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    templates (id) {
+        id -> Int8,
+        user_id -> Int8,
+        name -> Text,
+        kind -> Text,
+        payload -> Jsonb,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     user_groups (user_id, group_id) {
         user_id -> Int8,
@@ -240,13 +362,39 @@ diesel::table! {
     users (id) {
         id -> Int8,
         username -> Text,
-        password -> Text,
+        password -> Nullable<Text>,
         full_name -> Text,
         oidc_id -> Nullable<Text>,
         email -> Text,
         is_admin -> Bool,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        volume_presets -> Array<Int4>,
+        group_window_minutes -> Nullable<Int4>,
+        locale -> Nullable<Text>,
+        date_format -> Nullable<Text>,
+        last_login_at -> Nullable<Timestamptz>,
+        is_active -> Bool,
+        landing_date -> Nullable<Text>,
+        timezone -> Nullable<Text>,
+        max_heart_rate -> Nullable<Int4>,
+        distance_unit -> Nullable<Text>,
+        duration_rounding_minutes -> Nullable<Int4>,
+        glucose_unit -> Nullable<Text>,
+        theme -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    webhooks (id) {
+        id -> Int8,
+        user_id -> Int8,
+        url -> Text,
+        secret -> Text,
+        kinds -> Array<Text>,
+        is_active -> Bool,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
     }
 }
 
@@ -279,38 +427,61 @@ diesel::table! {
         updated_at -> Timestamptz,
         utc_offset -> Int4,
         leakage -> Int4,
+        wee_urge_id -> Nullable<Int8>,
     }
 }
 
+diesel::joinable!(api_tokens -> users (user_id));
+diesel::joinable!(colour_palettes -> users (user_id));
 diesel::joinable!(consumption_consumables -> consumables (consumable_id));
 diesel::joinable!(consumption_consumables -> consumptions (parent_id));
+diesel::joinable!(consumption_types -> users (user_id));
+diesel::joinable!(consumptions -> consumption_types (custom_type_id));
 diesel::joinable!(consumptions -> users (user_id));
 diesel::joinable!(exercises -> users (user_id));
+diesel::joinable!(fluid_adjustments -> users (user_id));
 diesel::joinable!(health_metrics -> users (user_id));
+diesel::joinable!(medication_doses -> medications (medication_id));
+diesel::joinable!(medication_doses -> users (user_id));
+diesel::joinable!(medications -> consumables (consumable_id));
+diesel::joinable!(medications -> users (user_id));
 diesel::joinable!(notes -> users (user_id));
 diesel::joinable!(poos -> users (user_id));
 diesel::joinable!(refluxs -> users (user_id));
+diesel::joinable!(share_links -> users (user_id));
 diesel::joinable!(symptoms -> users (user_id));
+diesel::joinable!(templates -> users (user_id));
 diesel::joinable!(user_groups -> groups (group_id));
 diesel::joinable!(user_groups -> users (user_id));
+diesel::joinable!(webhooks -> users (user_id));
 diesel::joinable!(wee_urges -> users (user_id));
 diesel::joinable!(wees -> users (user_id));
+diesel::joinable!(wees -> wee_urges (wee_urge_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    api_tokens,
+    colour_palettes,
     consumables,
     consumption_consumables,
+    consumption_types,
     consumptions,
     exercises,
+    fluid_adjustments,
     groups,
     health_metrics,
+    medication_doses,
+    medications,
     nested_consumables,
     notes,
     poos,
     refluxs,
     session,
+    share_links,
     symptoms,
+    templates,
     user_groups,
     users,
+    webhooks,
     wee_urges,
     wees,
 );
@@ -1,6 +1,25 @@
-use axum::http::StatusCode;
-use axum::response::IntoResponse;
-use axum::{extract::WebSocketUpgrade, response::Response};
+use axum::body::Bytes;
+use axum::extract::rejection::BytesRejection;
+use axum::extract::{Path, Query};
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Redirect};
+use axum::{Extension, extract::WebSocketUpgrade, response::Response};
+use chrono::{Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::ApiScope;
+
+use super::api_auth::ApiUser;
+use super::database::connection::DatabasePool;
+use super::database::models::medications::get_medications;
+use super::database::models::stats::get_nutrition_totals;
+use super::images::{self, ImageStore};
+use super::reports::{load_timeline, render_report};
+use super::storage::Storage;
+use super::timeline_cache::TimelineCache;
+
+/// Longest date range, in days, that a single report may cover.
+const MAX_REPORT_DAYS: i64 = 366;
 
 // #[axum::debug_handler]
 pub async fn dioxus_handler(ws: WebSocketUpgrade) -> Response {
@@ -21,3 +40,368 @@ pub async fn health_check() -> Response {
     // }
     (StatusCode::OK, "OK").into_response()
 }
+
+#[derive(Deserialize)]
+pub struct ReportQuery {
+    start: NaiveDate,
+    end: NaiveDate,
+}
+
+/// Start of the given calendar day in the server's local time zone.
+fn start_of_day(date: NaiveDate) -> Option<chrono::DateTime<Utc>> {
+    Local
+        .from_local_datetime(&date.and_time(NaiveTime::MIN))
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+// PDF report of all entries between two dates inclusive
+pub async fn report_pdf(
+    api_user: ApiUser,
+    Extension(pool): Extension<DatabasePool>,
+    Query(query): Query<ReportQuery>,
+) -> Response {
+    if let Err(response) = api_user.require(ApiScope::Read) {
+        return response;
+    }
+    let user = &api_user.user;
+
+    if query.end < query.start || (query.end - query.start).num_days() >= MAX_REPORT_DAYS {
+        return (StatusCode::BAD_REQUEST, "Invalid date range").into_response();
+    }
+
+    let (Some(start), Some(end)) = (
+        start_of_day(query.start),
+        query.end.succ_opt().and_then(start_of_day),
+    ) else {
+        return (StatusCode::BAD_REQUEST, "Invalid date range").into_response();
+    };
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(err) => {
+            tracing::error!("Error getting database connection: {err:?}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Error").into_response();
+        }
+    };
+
+    let timeline = match load_timeline(&mut conn, user.id, start, end).await {
+        Ok(timeline) => timeline,
+        Err(err) => {
+            tracing::error!("Error loading report: {err:?}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Error").into_response();
+        }
+    };
+
+    let pdf = render_report(&timeline, query.start, query.end, start);
+    let disposition = format!(
+        "inline; filename=\"report-{}-{}.pdf\"",
+        query.start, query.end
+    );
+
+    (
+        [
+            (header::CONTENT_TYPE, "application/pdf".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        pdf,
+    )
+        .into_response()
+}
+
+/// Everything a user has recorded, for data portability.
+#[derive(Serialize)]
+struct Export {
+    exported_at: chrono::DateTime<Utc>,
+    user: crate::models::User,
+    entries: Vec<crate::models::Entry>,
+    medications: Vec<crate::models::Medication>,
+    /// Consumables referenced by the user's entries and medications.
+    /// Consumables are shared between users, so this is not a list of
+    /// consumables the user created.
+    consumables: Vec<crate::models::Consumable>,
+}
+
+// JSON export of all of the logged in user's data
+pub async fn export_json(api_user: ApiUser, Extension(pool): Extension<DatabasePool>) -> Response {
+    use crate::models::EntryData;
+
+    if let Err(response) = api_user.require(ApiScope::Read) {
+        return response;
+    }
+    let user = &api_user.user;
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(err) => {
+            tracing::error!("Error getting database connection: {err:?}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Error").into_response();
+        }
+    };
+
+    // The full range postgres can store comfortably, so nothing is missed.
+    let (Some(start), Some(end)) = (
+        NaiveDate::from_ymd_opt(1, 1, 1).and_then(start_of_day),
+        NaiveDate::from_ymd_opt(9999, 12, 31).and_then(start_of_day),
+    ) else {
+        return (StatusCode::INTERNAL_SERVER_ERROR, "Error").into_response();
+    };
+
+    let timeline = match load_timeline(&mut conn, user.id, start, end).await {
+        Ok(timeline) => timeline,
+        Err(err) => {
+            tracing::error!("Error loading export: {err:?}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Error").into_response();
+        }
+    };
+
+    let medications: Vec<crate::models::Medication> =
+        match get_medications(&mut conn, user.id, true).await {
+            Ok(medications) => medications.into_iter().map(Into::into).collect(),
+            Err(err) => {
+                tracing::error!("Error loading export: {err:?}");
+                return (StatusCode::INTERNAL_SERVER_ERROR, "Error").into_response();
+            }
+        };
+
+    let mut consumables = std::collections::BTreeMap::new();
+    for entry in timeline.iter() {
+        if let EntryData::Consumption(consumption) = &entry.data {
+            for item in &consumption.items {
+                consumables.insert(item.consumable.id.as_inner(), item.consumable.clone());
+            }
+        }
+    }
+    for medication in &medications {
+        consumables.insert(
+            medication.consumable.id.as_inner(),
+            medication.consumable.clone(),
+        );
+    }
+
+    let exported_at = Utc::now();
+    let export = Export {
+        exported_at,
+        user: user.clone().into(),
+        entries: timeline.iter().cloned().collect(),
+        medications,
+        consumables: consumables.into_values().collect(),
+    };
+
+    let body = match serde_json::to_vec_pretty(&export) {
+        Ok(body) => body,
+        Err(err) => {
+            tracing::error!("Error serializing export: {err:?}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Error").into_response();
+        }
+    };
+
+    let disposition = format!(
+        "attachment; filename=\"penguin-nurse-export-{}.json\"",
+        exported_at.format("%Y-%m-%d")
+    );
+
+    (
+        [
+            (header::CONTENT_TYPE, "application/json".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+#[derive(Deserialize)]
+pub struct NutritionQuery {
+    date: NaiveDate,
+}
+
+// Calories and macronutrients for a day in the user's time zone
+pub async fn nutrition_json(
+    api_user: ApiUser,
+    Extension(pool): Extension<DatabasePool>,
+    Query(query): Query<NutritionQuery>,
+) -> Response {
+    if let Err(response) = api_user.require(ApiScope::Read) {
+        return response;
+    }
+    let user = &api_user.user;
+
+    let timezone = user
+        .timezone
+        .as_deref()
+        .and_then(|timezone| timezone.parse::<chrono_tz::Tz>().ok());
+    let Ok((start, end)) = crate::dt::get_utc_times_for_date(query.date, timezone) else {
+        return (StatusCode::BAD_REQUEST, "Invalid date").into_response();
+    };
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(err) => {
+            tracing::error!("Error getting database connection: {err:?}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Error").into_response();
+        }
+    };
+
+    match get_nutrition_totals(&mut conn, user.id, start, end).await {
+        Ok(totals) => axum::Json(totals).into_response(),
+        Err(err) => {
+            tracing::error!("Error loading nutrition: {err:?}");
+            (StatusCode::INTERNAL_SERVER_ERROR, "Error").into_response()
+        }
+    }
+}
+
+/// Load a consumable, mapping failures to a response.
+async fn load_consumable(
+    conn: &mut super::database::connection::DatabaseConnection,
+    id: i64,
+) -> Result<super::database::models::consumables::Consumable, Response> {
+    use super::database::models::consumables::get_consumable_by_id;
+
+    match get_consumable_by_id(conn, id).await {
+        Ok(Some(consumable)) => Ok(consumable),
+        Ok(None) => Err((StatusCode::NOT_FOUND, "Consumable not found").into_response()),
+        Err(err) => {
+            tracing::error!("Error loading consumable: {err:?}");
+            Err((StatusCode::INTERNAL_SERVER_ERROR, "Error").into_response())
+        }
+    }
+}
+
+// Replace a consumable's image with the uploaded file
+pub async fn upload_consumable_image(
+    api_user: ApiUser,
+    Extension(pool): Extension<DatabasePool>,
+    Extension(images): Extension<ImageStore>,
+    Extension(storage): Extension<Storage>,
+    Extension(timeline_cache): Extension<TimelineCache>,
+    Path(id): Path<i64>,
+    body: Result<Bytes, BytesRejection>,
+) -> Response {
+    use super::database::models::consumables::set_consumable_image;
+
+    if let Err(response) = api_user.require(ApiScope::ReadWrite) {
+        return response;
+    }
+
+    let body = match storage.upload_body(body) {
+        Ok(body) => body,
+        Err(response) => return response,
+    };
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(err) => {
+            tracing::error!("Error getting database connection: {err:?}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Error").into_response();
+        }
+    };
+
+    let existing = match load_consumable(&mut conn, id).await {
+        Ok(consumable) => consumable,
+        Err(response) => return response,
+    };
+
+    let key = match images.save(body.to_vec()).await {
+        Ok(key) => key,
+        Err(err @ images::Error::Image(_)) => {
+            return (StatusCode::BAD_REQUEST, err.to_string()).into_response();
+        }
+        Err(err) => {
+            tracing::error!("Error saving image: {err:?}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Error").into_response();
+        }
+    };
+
+    match set_consumable_image(&mut conn, id, Some(&key)).await {
+        Ok(consumable) => {
+            timeline_cache.clear();
+            if let Some(old_key) = existing.image_key {
+                images.delete(&old_key).await;
+            }
+            axum::Json(crate::models::Consumable::from(consumable)).into_response()
+        }
+        Err(err) => {
+            tracing::error!("Error updating consumable image: {err:?}");
+            images.delete(&key).await;
+            (StatusCode::INTERNAL_SERVER_ERROR, "Error").into_response()
+        }
+    }
+}
+
+// Remove a consumable's image
+pub async fn delete_consumable_image(
+    api_user: ApiUser,
+    Extension(pool): Extension<DatabasePool>,
+    Extension(images): Extension<ImageStore>,
+    Extension(timeline_cache): Extension<TimelineCache>,
+    Path(id): Path<i64>,
+) -> Response {
+    use super::database::models::consumables::set_consumable_image;
+
+    if let Err(response) = api_user.require(ApiScope::ReadWrite) {
+        return response;
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(err) => {
+            tracing::error!("Error getting database connection: {err:?}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Error").into_response();
+        }
+    };
+
+    let existing = match load_consumable(&mut conn, id).await {
+        Ok(consumable) => consumable,
+        Err(response) => return response,
+    };
+
+    match set_consumable_image(&mut conn, id, None).await {
+        Ok(consumable) => {
+            timeline_cache.clear();
+            if let Some(old_key) = existing.image_key {
+                images.delete(&old_key).await;
+            }
+            axum::Json(crate::models::Consumable::from(consumable)).into_response()
+        }
+        Err(err) => {
+            tracing::error!("Error removing consumable image: {err:?}");
+            (StatusCode::INTERNAL_SERVER_ERROR, "Error").into_response()
+        }
+    }
+}
+
+// An uploaded image or thumbnail, named as in `Consumable::image_url`
+pub async fn get_image(
+    api_user: ApiUser,
+    Extension(images): Extension<ImageStore>,
+    Path(name): Path<String>,
+) -> Response {
+    if let Err(response) = api_user.require(ApiScope::Read) {
+        return response;
+    }
+
+    if let Some(url) = images.signed_url(&name) {
+        return Redirect::temporary(&url).into_response();
+    }
+
+    match images.read(&name).await {
+        Ok(Some(bytes)) => (
+            [
+                (header::CONTENT_TYPE, "image/jpeg"),
+                (
+                    header::CACHE_CONTROL,
+                    "private, max-age=31536000, immutable",
+                ),
+            ],
+            bytes,
+        )
+            .into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, "Image not found").into_response(),
+        Err(err) => {
+            tracing::error!("Error reading image: {err:?}");
+            (StatusCode::INTERNAL_SERVER_ERROR, "Error").into_response()
+        }
+    }
+}
@@ -0,0 +1,169 @@
+//! Uploaded consumable images.
+//!
+//! Each upload is resized into a display image and a small thumbnail, both
+//! stored as JPEG files under `images/` in the [`Storage`] under a random key.
+//! A new key is used for every upload so the files can be cached forever.
+
+use std::io::Cursor;
+
+use image::{DynamicImage, ImageFormat, imageops::FilterType};
+use thiserror::Error;
+
+use super::{
+    storage::{self, Storage},
+    tokens::generate_token,
+};
+
+/// Longest side of the display image, in pixels.
+const IMAGE_SIZE: u32 = 1024;
+
+/// Longest side of the thumbnail, in pixels.
+const THUMBNAIL_SIZE: u32 = 128;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Invalid image: {0}")]
+    Image(#[from] image::ImageError),
+
+    #[error("Storage error: {0}")]
+    Storage(#[from] storage::Error),
+
+    #[error("Random key error: {0}")]
+    Random(#[from] getrandom::Error),
+
+    #[error("Image processing failed: {0}")]
+    Task(#[from] tokio::task::JoinError),
+}
+
+#[derive(Clone, Debug)]
+pub struct ImageStore {
+    storage: Storage,
+}
+
+struct Resized {
+    image: Vec<u8>,
+    thumbnail: Vec<u8>,
+}
+
+fn encode_jpeg(image: &DynamicImage) -> Result<Vec<u8>, image::ImageError> {
+    // JPEG has no alpha channel.
+    let image = DynamicImage::ImageRgb8(image.to_rgb8());
+    let mut bytes = Cursor::new(Vec::new());
+    image.write_to(&mut bytes, ImageFormat::Jpeg)?;
+    Ok(bytes.into_inner())
+}
+
+fn resize(bytes: &[u8]) -> Result<Resized, image::ImageError> {
+    let original = image::load_from_memory(bytes)?;
+
+    let thumbnail = original.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE);
+    let image = if original.width() > IMAGE_SIZE || original.height() > IMAGE_SIZE {
+        original.resize(IMAGE_SIZE, IMAGE_SIZE, FilterType::Lanczos3)
+    } else {
+        original
+    };
+
+    Ok(Resized {
+        image: encode_jpeg(&image)?,
+        thumbnail: encode_jpeg(&thumbnail)?,
+    })
+}
+
+/// Whether `name` is a file this store could have written. Keys are URL safe
+/// base64, so this also keeps requests inside the image directory.
+fn is_valid_file_name(name: &str) -> bool {
+    let Some(stem) = name.strip_suffix(".jpg") else {
+        return false;
+    };
+    let key = stem.strip_suffix("-thumb").unwrap_or(stem);
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+impl ImageStore {
+    pub fn new(storage: Storage) -> Self {
+        Self { storage }
+    }
+
+    fn keys(key: &str) -> (String, String) {
+        (
+            format!("images/{key}.jpg"),
+            format!("images/{key}-thumb.jpg"),
+        )
+    }
+
+    /// Resize and store an uploaded image, returning its key.
+    pub async fn save(&self, bytes: Vec<u8>) -> Result<String, Error> {
+        let resized = tokio::task::spawn_blocking(move || resize(&bytes)).await??;
+
+        let key = generate_token()?;
+        let (image_key, thumbnail_key) = Self::keys(&key);
+        self.storage
+            .put(&image_key, resized.image, "image/jpeg")
+            .await?;
+        self.storage
+            .put(&thumbnail_key, resized.thumbnail, "image/jpeg")
+            .await?;
+        Ok(key)
+    }
+
+    /// Remove a stored image. Failures are logged, a stray file is harmless.
+    pub async fn delete(&self, key: &str) {
+        let (image_key, thumbnail_key) = Self::keys(key);
+        for key in [image_key, thumbnail_key] {
+            self.storage.delete(&key).await;
+        }
+    }
+
+    /// Read a stored file by name, `None` if it does not exist.
+    pub async fn read(&self, name: &str) -> Result<Option<Vec<u8>>, Error> {
+        if !is_valid_file_name(name) {
+            return Ok(None);
+        }
+        Ok(self.storage.get(&format!("images/{name}")).await?)
+    }
+
+    /// A URL the browser can fetch a stored file from directly, if the
+    /// storage backend supports it.
+    pub fn signed_url(&self, name: &str) -> Option<String> {
+        if !is_valid_file_name(name) {
+            return None;
+        }
+        self.storage.signed_url(&format!("images/{name}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_names_stay_in_the_image_directory() {
+        assert!(is_valid_file_name("abc_DEF-123.jpg"));
+        assert!(is_valid_file_name("abc_DEF-123-thumb.jpg"));
+        assert!(!is_valid_file_name("../secret.jpg"));
+        assert!(!is_valid_file_name("abc.png"));
+        assert!(!is_valid_file_name(".jpg"));
+    }
+
+    #[test]
+    fn resizes_large_images() {
+        let original = DynamicImage::new_rgba8(2048, 1024);
+        let mut bytes = Cursor::new(Vec::new());
+        original.write_to(&mut bytes, ImageFormat::Png).unwrap();
+
+        let resized = resize(bytes.get_ref()).unwrap();
+
+        let image = image::load_from_memory(&resized.image).unwrap();
+        assert_eq!((image.width(), image.height()), (1024, 512));
+        let thumbnail = image::load_from_memory(&resized.thumbnail).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (128, 64));
+    }
+
+    #[test]
+    fn rejects_non_images() {
+        assert!(resize(b"not an image").is_err());
+    }
+}
@@ -1,29 +1,150 @@
 use dioxus::prelude::*;
 
+mod api_auth;
 pub mod auth;
+pub mod barcodes;
+mod csrf;
 // pub mod context;
 pub mod database;
 mod handlers;
+mod images;
 mod oidc;
+mod pdf;
+pub mod reports;
+mod security_headers;
 mod session_store;
+mod storage;
+pub mod timeline_cache;
+pub mod tokens;
+pub mod webhooks;
 
-use axum::{Extension, routing::get};
-use handlers::{dioxus_handler, health_check};
+use axum::{
+    Extension,
+    extract::DefaultBodyLimit,
+    routing::{get, post},
+};
+use handlers::{
+    delete_consumable_image, dioxus_handler, export_json, get_image, health_check, nutrition_json,
+    report_pdf, upload_consumable_image,
+};
 use time::Duration;
 use tower_sessions::session_store::ExpiredDeletion;
 use tower_sessions::{Expiry, SessionManagerLayer, cookie::SameSite};
 
+pub use oidc::PendingLogins as OidcPendingLogins;
 pub use oidc::middleware::ClientState as OidcClientState;
 
+/// Session cookie settings, read from the environment.
+///
+/// Defaults to secure cookies with `SameSite=Strict`. Setting `DEV=true`
+/// switches the defaults to insecure cookies with `SameSite=Lax` for local
+/// development over plain HTTP. Each setting can be overridden individually:
+///
+/// - `SESSION_SECURE` - `true` or `false`.
+/// - `SESSION_SAME_SITE` - `strict`, `lax` or `none`.
+/// - `SESSION_INACTIVITY` - inactivity before the session expires, such as
+///   `30m`, `12h` or `7d`. Defaults to 7 days.
+/// - `SESSION_INACTIVITY_DAYS` - the same in days, used if `SESSION_INACTIVITY`
+///   is not set.
+/// - `SESSION_MAX_LIFETIME` - how long after logging in a user is logged out
+///   however active they are, in the same format. Unlimited if unset.
+///
+/// Invalid durations are logged and ignored.
+struct SessionConfig {
+    secure: bool,
+    same_site: SameSite,
+    inactivity_expiry: Duration,
+    max_lifetime: Option<Duration>,
+}
+
+impl SessionConfig {
+    fn from_env() -> Self {
+        let dev = env_bool("DEV").unwrap_or(false);
+
+        let secure = env_bool("SESSION_SECURE").unwrap_or(!dev);
+
+        let same_site = match std::env::var("SESSION_SAME_SITE") {
+            Ok(value) => match value.to_lowercase().as_str() {
+                "strict" => SameSite::Strict,
+                "lax" => SameSite::Lax,
+                "none" => SameSite::None,
+                _ => panic!("SESSION_SAME_SITE must be one of strict, lax or none"),
+            },
+            Err(_) if dev => SameSite::Lax,
+            Err(_) => SameSite::Strict,
+        };
+
+        let inactivity_expiry = env_session_duration("SESSION_INACTIVITY")
+            .or_else(|| env_session_duration("SESSION_INACTIVITY_DAYS"))
+            .unwrap_or(Duration::days(7));
+
+        let max_lifetime = env_session_duration("SESSION_MAX_LIFETIME");
+
+        if !secure {
+            tracing::warn!("session cookies are not marked secure");
+        }
+
+        Self {
+            secure,
+            same_site,
+            inactivity_expiry,
+            max_lifetime,
+        }
+    }
+}
+
+/// Parse a positive duration such as `30m`, `12h` or `7d`. A bare number is
+/// in days.
+fn parse_session_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let (number, unit) = match value.chars().last() {
+        Some(unit) if unit.is_ascii_alphabetic() => (&value[..value.len() - 1], unit),
+        _ => (value, 'd'),
+    };
+    let number: i64 = number.trim().parse().ok().filter(|number| *number > 0)?;
+    match unit.to_ascii_lowercase() {
+        'm' => Some(Duration::minutes(number)),
+        'h' => Some(Duration::hours(number)),
+        'd' => Some(Duration::days(number)),
+        _ => None,
+    }
+}
+
+fn env_session_duration(name: &str) -> Option<Duration> {
+    let value = std::env::var(name).ok()?;
+    let duration = parse_session_duration(&value);
+    if duration.is_none() {
+        tracing::warn!("ignoring {name}={value:?}, expected a duration such as 30m, 12h or 7d");
+    }
+    duration
+}
+
+fn env_bool(name: &str) -> Option<bool> {
+    let value = std::env::var(name).ok()?;
+    match value.to_lowercase().as_str() {
+        "1" | "true" | "yes" => Some(true),
+        "0" | "false" | "no" | "" => Some(false),
+        _ => panic!("{name} must be true or false"),
+    }
+}
+
 // The entry point for the server
 #[cfg(feature = "server")]
 pub fn init(app: fn() -> Element) {
     use axum_login::AuthManagerLayerBuilder;
+    use csrf::add_csrf_middleware;
     use oidc::middleware::add_oidc_middleware;
+    use security_headers::add_security_headers_middleware;
     use tap::Pipe;
 
     dioxus::serve(move || async move {
         let database = database::connection::init().await;
+        let webhook_queue = webhooks::WebhookQueue::start(database.clone());
+        let storage = storage::Storage::from_env();
+
+        let session_config = SessionConfig::from_env();
+        let session_max_lifetime = auth::SessionMaxLifetime(session_config.max_lifetime);
+        let secure_cookies = auth::SecureCookies(session_config.secure);
 
         let session_layer = {
             let session_store = session_store::PostgresStore::new(database.clone());
@@ -35,9 +156,9 @@ pub fn init(app: fn() -> Element) {
             );
 
             SessionManagerLayer::new(session_store)
-                .with_secure(false)
-                .with_expiry(Expiry::OnInactivity(Duration::days(7)))
-                .with_same_site(SameSite::Lax)
+                .with_secure(session_config.secure)
+                .with_expiry(Expiry::OnInactivity(session_config.inactivity_expiry))
+                .with_same_site(session_config.same_site)
                 .with_always_save(true)
         };
 
@@ -58,6 +179,8 @@ pub fn init(app: fn() -> Element) {
             (layer, manager)
         };
 
+        // Streaming stays disabled, the security headers middleware buffers
+        // each page to give the framework's scripts a nonce.
         let cfg = ServeConfig::new();
 
         axum::Router::new()
@@ -67,11 +190,52 @@ pub fn init(app: fn() -> Element) {
             .serve_dioxus_application(cfg, app)
             .route("/_health", get(health_check))
             .route("/_dioxus", get(dioxus_handler))
+            .route("/api/v1/report.pdf", get(report_pdf))
+            .route("/api/v1/export", get(export_json))
+            .route("/api/v1/nutrition", get(nutrition_json))
+            .route(
+                "/api/v1/consumables/{id}/image",
+                post(upload_consumable_image)
+                    .delete(delete_consumable_image)
+                    .layer(DefaultBodyLimit::max(storage.max_upload_bytes())),
+            )
+            .route("/api/v1/images/{name}", get(get_image))
             .pipe(add_oidc_middleware)
+            .pipe(add_csrf_middleware)
+            .pipe(|app| add_security_headers_middleware(app, storage.signed_url_origin()))
             .layer(axum::middleware::from_fn(auth::session_middleware))
             .layer(auth_layer)
+            .layer(Extension(session_max_lifetime))
+            .layer(Extension(secure_cookies))
             .layer(Extension(database))
+            .layer(Extension(images::ImageStore::new(storage.clone())))
+            .layer(Extension(storage))
+            .layer(Extension(barcodes::BarcodeLookup::from_env()))
+            .layer(Extension(webhook_queue))
+            .layer(Extension(timeline_cache::TimelineCache::new()))
             .layer(Extension(auth_manager))
             .pipe(Ok)
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_duration_units() {
+        assert_eq!(parse_session_duration("30m"), Some(Duration::minutes(30)));
+        assert_eq!(parse_session_duration("12H"), Some(Duration::hours(12)));
+        assert_eq!(parse_session_duration(" 7d "), Some(Duration::days(7)));
+        assert_eq!(parse_session_duration("3"), Some(Duration::days(3)));
+    }
+
+    #[test]
+    fn session_duration_rejects_invalid_values() {
+        assert_eq!(parse_session_duration(""), None);
+        assert_eq!(parse_session_duration("0"), None);
+        assert_eq!(parse_session_duration("-1d"), None);
+        assert_eq!(parse_session_duration("1w"), None);
+        assert_eq!(parse_session_duration("soon"), None);
+    }
+}
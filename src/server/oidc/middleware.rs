@@ -4,7 +4,7 @@ use arc_swap::ArcSwap;
 use axum::{
     Extension,
     extract::{Query, Request},
-    http::StatusCode,
+    http::header,
     middleware::{Next, from_fn},
     response::{IntoResponse, Redirect, Response},
 };
@@ -12,10 +12,11 @@ use tap::Pipe;
 use thiserror::Error;
 use url::Url;
 
+use crate::models::{OidcFailure, post_login_url};
 use crate::server::{
-    auth::{AuthError, AuthSession},
+    auth::{AuthError, AuthSession, SecureCookies},
     database::connection::DatabasePool,
-    oidc::{Client, Config},
+    oidc::{Client, Config, DEFAULT_PROVIDER_ID, state},
 };
 
 #[derive(Debug, Error)]
@@ -26,96 +27,241 @@ enum Error {
     Oidc(#[from] super::Error),
     #[error(transparent)]
     AxumLogin(#[from] AuthError),
+    #[error(transparent)]
+    State(#[from] state::Error),
+    #[error("Unknown OIDC provider {0}")]
+    UnknownProvider(String),
+    #[error("Login request is unknown or has expired")]
+    InvalidState,
+    #[error("Provider returned error: {0}")]
+    Provider(String),
+}
+
+impl Error {
+    fn failure(&self) -> OidcFailure {
+        match self {
+            Error::Provider(error) if error == "access_denied" => OidcFailure::AccessDenied,
+            Error::Oidc(super::Error::AccountDisabled) => OidcFailure::AccountDisabled,
+            Error::Oidc(super::Error::UserInfoMissing(_) | super::Error::UserInfoInvalid(_)) => {
+                OidcFailure::MissingDetails
+            }
+            Error::InvalidState | Error::Oidc(super::Error::RequestToken(_)) => {
+                OidcFailure::Expired
+            }
+            _ => OidcFailure::Server,
+        }
+    }
 }
 
 impl IntoResponse for Error {
+    /// Log the details and send the user to a page explaining what happened.
     fn into_response(self) -> Response {
-        (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()).into_response()
+        let failure = self.failure();
+        if failure == OidcFailure::Server {
+            tracing::error!("OIDC login failed: {self}");
+        } else {
+            tracing::warn!("OIDC login failed: {self}");
+        }
+        Redirect::to(&format!("/login/error?reason={}", failure.as_str())).into_response()
     }
 }
 
-pub type ClientState = Arc<ArcSwap<Option<Client>>>;
+/// A configured OIDC provider. The client is loaded in the background and is
+/// `None` until the provider's discovery document has been fetched.
+pub struct Provider {
+    pub id: String,
+    pub name: String,
+    pub client: ArcSwap<Option<Client>>,
+}
+
+pub type ClientState = Arc<Vec<Provider>>;
 
-pub fn add_oidc_middleware<S: Clone + Send + Sync + 'static>(
-    app: axum::Router<S>,
-) -> axum::Router<S> {
-    if env::var("OIDC_DISCOVERY_URL").is_err() {
-        return app;
+/// Split a comma separated list of provider ids, dropping blanks and
+/// duplicates.
+fn parse_provider_ids(value: &str) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::new();
+    for id in value.split(',').map(str::trim).filter(|id| !id.is_empty()) {
+        let id = id.to_lowercase();
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
     }
+    ids
+}
 
-    let base_url = env::var("BASE_URL").expect("BASE_URL must be set");
-    let issuer = env::var("OIDC_DISCOVERY_URL").expect("OIDC_DISCOVERY_URL must be set");
-    let client_id = env::var("OIDC_CLIENT_ID").expect("OIDC_CLIENT_ID must be set");
-    let client_secret = env::var("OIDC_CLIENT_SECRET").expect("OIDC_CLIENT_SECRET must be set");
-    let auth_scope = env::var("OIDC_AUTH_SCOPE").expect("OIDC_AUTH_SCOPE must be set");
+fn provider_config(id: &str, prefix: &str, redirect_uri: &Url) -> Config {
+    let var = |name: &str| {
+        let name = format!("{prefix}{name}");
+        env::var(&name).unwrap_or_else(|_| panic!("{name} must be set"))
+    };
 
+    Config {
+        id: id.to_string(),
+        name: env::var(format!("{prefix}NAME")).unwrap_or_else(|_| "OIDC".to_string()),
+        issuer: var("DISCOVERY_URL"),
+        client_id: var("CLIENT_ID"),
+        client_secret: var("CLIENT_SECRET"),
+        redirect_uri: redirect_uri.to_string(),
+        scopes: var("AUTH_SCOPE"),
+    }
+}
+
+/// Read the providers from the environment.
+///
+/// A single provider is configured with `OIDC_DISCOVERY_URL`,
+/// `OIDC_CLIENT_ID`, `OIDC_CLIENT_SECRET`, `OIDC_AUTH_SCOPE` and optionally
+/// `OIDC_NAME`. For more, list their ids in `OIDC_PROVIDERS` and prefix each
+/// variable with the upper case id, e.g. `OIDC_WORK_CLIENT_ID`.
+fn provider_configs() -> Vec<Config> {
+    let ids = env::var("OIDC_PROVIDERS")
+        .map(|value| parse_provider_ids(&value))
+        .unwrap_or_default();
+
+    if ids.is_empty() && env::var("OIDC_DISCOVERY_URL").is_err() {
+        return Vec::new();
+    }
+
+    let base_url = env::var("BASE_URL").expect("BASE_URL must be set");
     let redirect_uri = Url::parse(&base_url)
         .expect("BASE_URL must be a valid URL")
-        .join("/openid_connect_redirect_uri")
+        .join(state::CALLBACK_PATH)
         .expect("failed to join redirect_uri");
 
-    let oidc_config = Config {
-        issuer,
-        client_id,
-        client_secret,
-        redirect_uri: redirect_uri.to_string(),
-        scopes: auth_scope,
-    };
+    if ids.is_empty() {
+        return vec![provider_config(DEFAULT_PROVIDER_ID, "OIDC_", &redirect_uri)];
+    }
+
+    ids.iter()
+        .map(|id| {
+            let prefix = format!("OIDC_{}_", id.to_uppercase());
+            provider_config(id, &prefix, &redirect_uri)
+        })
+        .collect()
+}
 
-    let oidc_client = Arc::new(ArcSwap::new(Arc::new(None)));
-
-    {
-        let client = oidc_client.clone();
-        tokio::spawn(async move {
-            loop {
-                tracing::info!("refreshing oidc client");
-                let new_client = Client::new(&oidc_config).await;
-                match new_client {
-                    Ok(new_client) => {
-                        client.store(Arc::new(Some(new_client)));
-                        tokio::time::sleep(tokio::time::Duration::from_secs(10 * 60)).await;
-                    }
-                    Err(e) => {
-                        tracing::error!("failed to refresh oidc client: {}", e);
-                        tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
-                    }
+fn spawn_refresh(providers: ClientState, index: usize, config: Config) {
+    tokio::spawn(async move {
+        let provider = &providers[index];
+        loop {
+            tracing::info!("refreshing oidc client {}", provider.id);
+            let new_client = Client::new(&config).await;
+            match new_client {
+                Ok(new_client) => {
+                    provider.client.store(Arc::new(Some(new_client)));
+                    tokio::time::sleep(tokio::time::Duration::from_secs(10 * 60)).await;
+                }
+                Err(e) => {
+                    tracing::error!("failed to refresh oidc client {}: {}", provider.id, e);
+                    tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
                 }
             }
-        });
+        }
+    });
+}
+
+pub fn add_oidc_middleware<S: Clone + Send + Sync + 'static>(
+    app: axum::Router<S>,
+) -> axum::Router<S> {
+    let configs = provider_configs();
+    if configs.is_empty() {
+        return app;
+    }
+
+    let providers: ClientState = configs
+        .iter()
+        .map(|config| Provider {
+            id: config.id.clone(),
+            name: config.name.clone(),
+            client: ArcSwap::new(Arc::new(None)),
+        })
+        .collect::<Vec<_>>()
+        .pipe(Arc::new);
+
+    for (index, config) in configs.into_iter().enumerate() {
+        spawn_refresh(providers.clone(), index, config);
     }
 
     let layer = from_fn(auth);
 
-    app.layer(layer).layer(Extension(oidc_client))
+    app.layer(layer)
+        .layer(Extension(providers))
+        .layer(Extension(state::PendingLogins::new()))
+}
+
+/// Log in the user the provider redirected back, for the login `pending`
+/// that the redirect's state matched.
+async fn callback(
+    providers: &ClientState,
+    database_pool: &DatabasePool,
+    params: &HashMap<String, String>,
+    session: &mut AuthSession,
+    pending: Option<state::PendingLogin>,
+) -> Result<Redirect, Error> {
+    if let Some(error) = params.get("error") {
+        return Err(Error::Provider(error.clone()));
+    }
+
+    let code = params.get("code").cloned().unwrap_or_default();
+
+    let Some(pending) = pending else {
+        return Err(Error::InvalidState);
+    };
+
+    let Some(provider) = providers.iter().find(|p| p.id == pending.provider) else {
+        return Err(Error::UnknownProvider(pending.provider));
+    };
+    let oidc_client = provider.client.load();
+    let Some(oidc_client) = oidc_client.as_ref() else {
+        return Err(Error::NotInitialized);
+    };
+
+    let user = oidc_client.login(&code, database_pool).await?;
+    session.login(&user).await?;
+
+    // Checked again here as well as when the login started, so the user is
+    // never sent off site.
+    Ok(Redirect::to(post_login_url(&pending.return_url)))
 }
 
 // #[axum::debug_middleware]
 async fn auth(
-    Extension(oidc_client): Extension<ClientState>,
+    Extension(providers): Extension<ClientState>,
     Extension(database_pool): Extension<DatabasePool>,
+    Extension(SecureCookies(secure)): Extension<SecureCookies>,
+    Extension(pending_logins): Extension<state::PendingLogins>,
     Query(params): Query<HashMap<String, String>>,
     mut session: Extension<AuthSession>,
     request: Request,
     next: Next,
 ) -> Result<Response, Error> {
-    if request.uri().path() == "/openid_connect_redirect_uri" {
-        let code = params.get("code").cloned().unwrap_or_default();
+    if request.uri().path() == state::CALLBACK_PATH {
+        let state = params.get("state").map(String::as_str).unwrap_or_default();
+        let (pending, set_cookie) =
+            pending_logins.finish_login(request.headers(), state, secure)?;
 
-        let state = params
-            .get("state")
-            .cloned()
-            .unwrap_or_else(|| "/".to_string());
+        // The state is used up whatever happens, so always update the cookie.
+        let mut response = callback(&providers, &database_pool, &params, &mut session, pending)
+            .await
+            .into_response();
+        response
+            .headers_mut()
+            .append(header::SET_COOKIE, set_cookie);
+        return Ok(response);
+    }
 
-        let oidc_client = oidc_client.load();
-        let Some(oidc_client) = oidc_client.as_ref() else {
-            return Err(Error::NotInitialized);
-        };
+    next.run(request).await.pipe(Ok)
+}
 
-        let user = oidc_client.login(&code, &database_pool).await?;
-        session.login(&user).await?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        return Redirect::to(&state).into_response().pipe(Ok);
+    #[test]
+    fn provider_ids_are_trimmed_and_deduplicated() {
+        assert_eq!(
+            parse_provider_ids(" Google, work,,google "),
+            vec!["google".to_string(), "work".to_string()]
+        );
+        assert!(parse_provider_ids(" , ").is_empty());
     }
-
-    next.run(request).await.pipe(Ok)
 }
@@ -1,18 +1,28 @@
 mod claims;
 pub mod middleware;
+mod state;
+
+pub use state::PendingLogins;
 
 use openid::{Discovered, Options, error::ClientError};
 use thiserror::Error;
 use url::Url;
 
 use crate::server::database::models::users::{
-    create_user, get_user_by_email, get_user_by_oidc_id, update_user,
+    create_user, get_user_by_email, get_user_by_oidc_id, record_login, update_user,
 };
 
+use crate::models::normalize_email;
+
 use super::database::{self, connection::DatabasePool, models::users::User};
 
+/// Id of the provider configured with the unprefixed `OIDC_*` variables.
+const DEFAULT_PROVIDER_ID: &str = "default";
+
 #[derive(Debug, Clone)]
 pub struct Config {
+    pub id: String,
+    pub name: String,
     pub issuer: String,
     pub client_id: String,
     pub client_secret: String,
@@ -46,6 +56,10 @@ pub enum Error {
     #[error("No token")]
     NoToken,
 
+    // Account disabled error
+    #[error("Account is disabled")]
+    AccountDisabled,
+
     // Request token error
     #[error("Request token error: {0}")]
     RequestToken(ClientError),
@@ -66,6 +80,10 @@ pub enum Error {
     #[error("User info missing {0}")]
     UserInfoMissing(String),
 
+    // User info invalid error
+    #[error("User info invalid: {0}")]
+    UserInfoInvalid(String),
+
     // Database error
     #[error("Database error: {0}")]
     Database(#[from] database::connection::Error),
@@ -90,14 +108,36 @@ impl Client {
         Ok(client)
     }
 
-    pub fn get_auth_url(&self, origin_url: &str) -> String {
+    /// Start a login, returning the provider URL to send the user to and the
+    /// `Set-Cookie` header that ties the login to the browser. After logging
+    /// in they are sent on to `return_url`.
+    pub fn get_auth_url(
+        &self,
+        pending_logins: &PendingLogins,
+        headers: &axum::http::HeaderMap,
+        return_url: &str,
+        secure: bool,
+    ) -> Result<(String, axum::http::HeaderValue), state::Error> {
+        let (state, set_cookie) =
+            pending_logins.start_login(headers, &self.config.id, return_url, secure)?;
+
         let auth_url = self.oidc_client.auth_url(&Options {
             scope: Some(self.config.scopes.to_string()),
-            state: Some(origin_url.to_string()),
+            state: Some(state),
             ..Default::default()
         });
 
-        auth_url.into()
+        Ok((auth_url.into(), set_cookie))
+    }
+
+    /// The id stored against the user. Subjects are only unique within a
+    /// provider, so all but the default provider are namespaced by their id.
+    fn oidc_id(&self, subject: &str) -> String {
+        if self.config.id == DEFAULT_PROVIDER_ID {
+            subject.to_string()
+        } else {
+            format!("{}:{}", self.config.id, subject)
+        }
     }
 
     async fn login(&self, code: &str, pool: &DatabasePool) -> Result<User, Error> {
@@ -140,12 +180,15 @@ impl Client {
         let email = user_info
             .email
             .ok_or_else(|| Error::UserInfoMissing("email".into()))?;
+        let email = normalize_email(&email).map_err(Error::UserInfoInvalid)?;
 
         let is_admin = groups.contains(&"admin".to_string());
 
+        let oidc_id = self.oidc_id(&user_info.sub);
+
         let mut conn = pool.get().await.map_err(database::connection::Error::Bb8)?;
 
-        let user = get_user_by_oidc_id(&mut conn, &user_info.sub)
+        let user = get_user_by_oidc_id(&mut conn, &oidc_id)
             .await
             .map_err(database::connection::Error::Diesel)?;
 
@@ -157,12 +200,17 @@ impl Client {
                 .map_err(database::connection::Error::Diesel)?
         };
 
+        if user.as_ref().is_some_and(|user| !user.is_active) {
+            return Err(Error::AccountDisabled);
+        }
+
         let user = if let Some(user) = user {
             let updates = database::models::users::UpdateUser {
                 full_name: None,
-                oidc_id: Some(Some(user_info.sub.as_str())),
+                oidc_id: Some(Some(oidc_id.as_str())),
                 email: None,
                 is_admin: Some(is_admin),
+                is_active: None,
                 username: None,
                 password: None,
             };
@@ -173,17 +221,21 @@ impl Client {
         } else {
             let updates = database::models::users::NewUser {
                 full_name: name.as_str(),
-                oidc_id: Some(user_info.sub.as_str()),
+                oidc_id: Some(oidc_id.as_str()),
                 email: email.as_str(),
                 is_admin,
                 username: name.as_str(),
-                password: "",
+                password: None,
             };
             create_user(&mut conn, updates)
                 .await
                 .map_err(database::connection::Error::Diesel)?
         };
 
+        let user = record_login(&mut conn, user.id)
+            .await
+            .map_err(database::connection::Error::Diesel)?;
+
         Ok(user)
     }
 }
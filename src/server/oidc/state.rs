@@ -0,0 +1,405 @@
+//! Logins in progress with the OIDC provider.
+//!
+//! The `state` sent to the provider is a random token that protects the
+//! callback from CSRF. Where to go after logging in is kept on the server,
+//! keyed by that token, so it never round trips through the provider or the
+//! browser. It is checked with the same rules as a password login, so both
+//! end up in the same place.
+//!
+//! The browser is given a cookie listing the states it started, so a
+//! callback is only accepted from the browser that began the login. The
+//! cookie holds nothing else, so changing it gains nothing. It is separate
+//! from the session because the provider redirects back from another site,
+//! and the session cookie may be `SameSite=Strict`. It is `SameSite=Lax`,
+//! only sent to the callback and only lives as long as the login may take.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use axum::http::{HeaderMap, HeaderValue, header};
+use chrono::{DateTime, TimeDelta, Utc};
+use tower_sessions::cookie::{Cookie, SameSite, time};
+
+use crate::{models::post_login_url, server::tokens::generate_token};
+
+const PENDING_LOGINS_COOKIE: &str = "penguin_nurse_oidc";
+
+/// The only path the cookie is sent to.
+pub const CALLBACK_PATH: &str = "/openid_connect_redirect_uri";
+
+/// How long the user has to complete the login with the provider.
+const PENDING_LOGIN_LIFETIME: TimeDelta = TimeDelta::minutes(10);
+
+/// Most logins kept waiting at once. Anyone can start a login, so once this
+/// many are waiting the one closest to expiring is dropped.
+const MAX_PENDING_LOGINS: usize = 10_000;
+
+/// Most logins one browser's cookie remembers, for logins started in
+/// several tabs.
+const MAX_BROWSER_LOGINS: usize = 10;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Random state error: {0}")]
+    Random(#[from] getrandom::Error),
+
+    #[error("Cookie header error: {0}")]
+    Header(#[from] axum::http::header::InvalidHeaderValue),
+}
+
+/// A login waiting for the provider to redirect back.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingLogin {
+    /// Id of the provider the login was started with.
+    pub provider: String,
+    pub return_url: String,
+    expires_at: DateTime<Utc>,
+}
+
+type Logins = HashMap<String, PendingLogin>;
+
+/// Drop expired logins, and the one closest to expiring if there are still
+/// `max` or more, so there is room for another.
+fn make_room(pending: &mut Logins, now: DateTime<Utc>, max: usize) {
+    pending.retain(|_, login| login.expires_at > now);
+    if pending.len() >= max {
+        let first = pending
+            .iter()
+            .min_by_key(|(_, login)| login.expires_at)
+            .map(|(state, _)| state.clone());
+        if let Some(first) = first {
+            pending.remove(&first);
+        }
+    }
+}
+
+fn add_pending_login(
+    pending: &mut Logins,
+    state: String,
+    provider: &str,
+    return_url: &str,
+    now: DateTime<Utc>,
+) {
+    make_room(pending, now, MAX_PENDING_LOGINS);
+    pending.insert(
+        state,
+        PendingLogin {
+            provider: provider.to_string(),
+            return_url: post_login_url(return_url).to_string(),
+            expires_at: now + PENDING_LOGIN_LIFETIME,
+        },
+    );
+}
+
+/// Remove the login for `state`, returning it if it is still valid.
+fn take_pending_login(
+    pending: &mut Logins,
+    state: &str,
+    now: DateTime<Utc>,
+) -> Option<PendingLogin> {
+    pending.remove(state).filter(|login| login.expires_at > now)
+}
+
+/// The states in the request's cookie. A missing cookie has none.
+fn load(headers: &HeaderMap) -> Vec<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(Cookie::split_parse)
+        .filter_map(Result::ok)
+        .find(|cookie| cookie.name() == PENDING_LOGINS_COOKIE)
+        .map(|cookie| {
+            cookie
+                .value()
+                .split('.')
+                .filter(|state| !state.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The `Set-Cookie` header storing the newest of `states`, removing the
+/// cookie if there are none left.
+fn save(states: &[String], secure: bool) -> Result<HeaderValue, Error> {
+    let states = &states[states.len().saturating_sub(MAX_BROWSER_LOGINS)..];
+    let max_age = if states.is_empty() {
+        time::Duration::ZERO
+    } else {
+        time::Duration::seconds(PENDING_LOGIN_LIFETIME.num_seconds())
+    };
+
+    let cookie = Cookie::build((PENDING_LOGINS_COOKIE, states.join(".")))
+        .path(CALLBACK_PATH)
+        .http_only(true)
+        .secure(secure)
+        .same_site(SameSite::Lax)
+        .max_age(max_age)
+        .build();
+
+    Ok(HeaderValue::from_str(&cookie.to_string())?)
+}
+
+/// Logins waiting for the provider to redirect back, shared by every request.
+#[derive(Clone, Default)]
+pub struct PendingLogins(Arc<Mutex<Logins>>);
+
+impl PendingLogins {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a login, returning the state to send to the provider and the
+    /// `Set-Cookie` header to send back to the browser.
+    pub fn start_login(
+        &self,
+        headers: &HeaderMap,
+        provider: &str,
+        return_url: &str,
+        secure: bool,
+    ) -> Result<(String, HeaderValue), Error> {
+        let state = generate_token()?;
+        let mut pending = self.lock();
+        add_pending_login(
+            &mut pending,
+            state.clone(),
+            provider,
+            return_url,
+            Utc::now(),
+        );
+
+        let mut states: Vec<String> = load(headers)
+            .into_iter()
+            .filter(|state| pending.contains_key(state))
+            .collect();
+        states.push(state.clone());
+        Ok((state, save(&states, secure)?))
+    }
+
+    /// Finish a login, returning `None` if the state is unknown, expired or
+    /// was started in another browser, along with the `Set-Cookie` header to
+    /// send back to the browser.
+    pub fn finish_login(
+        &self,
+        headers: &HeaderMap,
+        state: &str,
+        secure: bool,
+    ) -> Result<(Option<PendingLogin>, HeaderValue), Error> {
+        let mut pending = self.lock();
+        let states = load(headers);
+        let login = if states.iter().any(|started| started == state) {
+            take_pending_login(&mut pending, state, Utc::now())
+        } else {
+            None
+        };
+
+        let states: Vec<String> = states
+            .into_iter()
+            .filter(|state| pending.contains_key(state))
+            .collect();
+        Ok((login, save(&states, secure)?))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Logins> {
+        // A panic while holding the lock cannot leave the map half updated,
+        // so carry on with whatever is in it.
+        self.0.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2024-01-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn return_url_must_be_local() {
+        use crate::models::LANDING_URL;
+
+        let mut pending = Logins::new();
+        for (state, return_url, expected) in [
+            ("local", "/consumables", "/consumables"),
+            ("protocol", "//evil.example", LANDING_URL),
+            ("backslash", "/\\evil.example", LANDING_URL),
+            ("absolute", "https://evil.example/", LANDING_URL),
+            ("empty", "", LANDING_URL),
+        ] {
+            add_pending_login(
+                &mut pending,
+                state.to_string(),
+                "default",
+                return_url,
+                now(),
+            );
+            let login = take_pending_login(&mut pending, state, now()).unwrap();
+            assert_eq!(login.return_url, expected);
+        }
+    }
+
+    #[test]
+    fn state_can_only_be_used_once() {
+        let mut pending = Logins::new();
+        add_pending_login(&mut pending, "abc".to_string(), "work", "/timeline", now());
+
+        let login = take_pending_login(&mut pending, "abc", now()).unwrap();
+        assert_eq!(login.provider, "work");
+        assert_eq!(login.return_url, "/timeline");
+        assert_eq!(take_pending_login(&mut pending, "abc", now()), None);
+    }
+
+    #[test]
+    fn unknown_and_expired_states_are_rejected() {
+        let mut pending = Logins::new();
+        add_pending_login(&mut pending, "abc".to_string(), "default", "/", now());
+
+        assert_eq!(take_pending_login(&mut pending, "xyz", now()), None);
+        let later = now() + PENDING_LOGIN_LIFETIME;
+        assert_eq!(take_pending_login(&mut pending, "abc", later), None);
+    }
+
+    #[test]
+    fn expired_logins_are_pruned() {
+        let mut pending = Logins::new();
+        add_pending_login(&mut pending, "old".to_string(), "default", "/", now());
+        let later = now() + PENDING_LOGIN_LIFETIME;
+        add_pending_login(&mut pending, "new".to_string(), "default", "/", later);
+
+        assert_eq!(pending.len(), 1);
+        assert!(pending.contains_key("new"));
+    }
+
+    #[test]
+    fn full_store_drops_the_login_closest_to_expiring() {
+        let mut pending = Logins::new();
+        for (minute, state) in ["first", "second", "third"].into_iter().enumerate() {
+            let started = now() + TimeDelta::minutes(minute as i64);
+            add_pending_login(&mut pending, state.to_string(), "default", "/", started);
+        }
+
+        make_room(&mut pending, now() + TimeDelta::minutes(3), 3);
+        assert_eq!(pending.len(), 2);
+        assert!(!pending.contains_key("first"));
+    }
+
+    /// The request the provider's redirect back makes, with the cookie set
+    /// when the login was started alongside the session cookie.
+    fn callback_headers(set_cookie: &HeaderValue) -> HeaderMap {
+        let cookie = Cookie::parse(set_cookie.to_str().unwrap()).unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::COOKIE,
+            format!("id=session; {}={}", cookie.name(), cookie.value())
+                .parse()
+                .unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn pending_login_cookie_is_lax_and_limited_to_callback() {
+        let logins = PendingLogins::new();
+        let (state, set_cookie) = logins
+            .start_login(&HeaderMap::new(), "default", "/timeline", true)
+            .unwrap();
+        let cookie = Cookie::parse(set_cookie.to_str().unwrap()).unwrap();
+
+        assert_eq!(cookie.name(), PENDING_LOGINS_COOKIE);
+        assert_eq!(cookie.value(), state);
+        assert_eq!(cookie.same_site(), Some(SameSite::Lax));
+        assert_eq!(cookie.http_only(), Some(true));
+        assert_eq!(cookie.secure(), Some(true));
+        assert_eq!(cookie.path(), Some(CALLBACK_PATH));
+        assert_eq!(cookie.max_age(), Some(time::Duration::minutes(10)));
+    }
+
+    #[test]
+    fn callback_finds_login_started_by_browser() {
+        let logins = PendingLogins::new();
+        let (state, set_cookie) = logins
+            .start_login(&HeaderMap::new(), "work", "/consumables", true)
+            .unwrap();
+
+        let (login, set_cookie) = logins
+            .finish_login(&callback_headers(&set_cookie), &state, true)
+            .unwrap();
+        let login = login.unwrap();
+        assert_eq!(login.provider, "work");
+        assert_eq!(login.return_url, "/consumables");
+
+        // The state was used up, so the cookie is removed.
+        let cookie = Cookie::parse(set_cookie.to_str().unwrap()).unwrap();
+        assert_eq!(cookie.max_age(), Some(time::Duration::ZERO));
+        let (login, _) = logins
+            .finish_login(&callback_headers(&set_cookie), &state, true)
+            .unwrap();
+        assert_eq!(login, None);
+    }
+
+    #[test]
+    fn callback_from_another_browser_is_rejected() {
+        let logins = PendingLogins::new();
+        let (state, set_cookie) = logins
+            .start_login(&HeaderMap::new(), "default", "/", true)
+            .unwrap();
+
+        let (login, _) = logins
+            .finish_login(&HeaderMap::new(), &state, true)
+            .unwrap();
+        assert_eq!(login, None);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::COOKIE,
+            format!("{PENDING_LOGINS_COOKIE}=garbage").parse().unwrap(),
+        );
+        let (login, _) = logins.finish_login(&headers, &state, true).unwrap();
+        assert_eq!(login, None);
+
+        // The browser that started it can still finish it.
+        let (login, _) = logins
+            .finish_login(&callback_headers(&set_cookie), &state, true)
+            .unwrap();
+        assert!(login.is_some());
+    }
+
+    #[test]
+    fn callback_for_state_unknown_to_server_is_rejected() {
+        let logins = PendingLogins::new();
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::COOKIE,
+            format!("{PENDING_LOGINS_COOKIE}=forged").parse().unwrap(),
+        );
+
+        let (login, set_cookie) = logins.finish_login(&headers, "forged", true).unwrap();
+        assert_eq!(login, None);
+        let cookie = Cookie::parse(set_cookie.to_str().unwrap()).unwrap();
+        assert_eq!(cookie.max_age(), Some(time::Duration::ZERO));
+    }
+
+    #[test]
+    fn logins_in_other_tabs_are_kept() {
+        let logins = PendingLogins::new();
+        let (first, set_cookie) = logins
+            .start_login(&HeaderMap::new(), "default", "/a", true)
+            .unwrap();
+        let (second, set_cookie) = logins
+            .start_login(&callback_headers(&set_cookie), "default", "/b", true)
+            .unwrap();
+
+        let (login, set_cookie) = logins
+            .finish_login(&callback_headers(&set_cookie), &second, true)
+            .unwrap();
+        assert_eq!(login.unwrap().return_url, "/b");
+        let (login, _) = logins
+            .finish_login(&callback_headers(&set_cookie), &first, true)
+            .unwrap();
+        assert_eq!(login.unwrap().return_url, "/a");
+    }
+}
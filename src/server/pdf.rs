@@ -0,0 +1,252 @@
+//! A minimal PDF writer for simple text reports.
+//!
+//! Only supports lines of text in the standard Helvetica fonts on A4 pages,
+//! which is enough for printable reports. Output is deterministic: the same
+//! input always produces the same bytes.
+
+use std::fmt::Write;
+
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 50.0;
+const BODY_SIZE: f32 = 10.0;
+const HEADING_SIZE: f32 = 14.0;
+const LINE_SPACING: f32 = 1.4;
+const MAX_LINE_CHARS: usize = 95;
+
+pub struct PdfWriter {
+    pages: Vec<String>,
+    current: String,
+    y: f32,
+}
+
+impl Default for PdfWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PdfWriter {
+    pub fn new() -> Self {
+        Self {
+            pages: Vec::new(),
+            current: String::new(),
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    pub fn heading(&mut self, text: &str) {
+        self.space();
+        self.write_line("F2", HEADING_SIZE, text);
+    }
+
+    pub fn line(&mut self, text: &str) {
+        for line in wrap(text, MAX_LINE_CHARS) {
+            self.write_line("F1", BODY_SIZE, &line);
+        }
+    }
+
+    pub fn space(&mut self) {
+        if !self.current.is_empty() {
+            self.y -= BODY_SIZE * LINE_SPACING;
+        }
+    }
+
+    fn write_line(&mut self, font: &str, size: f32, text: &str) {
+        let height = size * LINE_SPACING;
+        if self.y - height < MARGIN {
+            self.new_page();
+        }
+        self.y -= height;
+        let _ = writeln!(
+            self.current,
+            "BT /{font} {size} Tf {MARGIN} {y} Td ({text}) Tj ET",
+            y = self.y,
+            text = escape(text)
+        );
+    }
+
+    fn new_page(&mut self) {
+        self.pages.push(std::mem::take(&mut self.current));
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    pub fn finish(mut self) -> Vec<u8> {
+        if !self.current.is_empty() || self.pages.is_empty() {
+            self.new_page();
+        }
+
+        // Objects 1-4 are fixed, then each page has a page object followed
+        // by its content stream.
+        let num_pages = self.pages.len();
+        let page_ids = (0..num_pages).map(|i| 5 + i * 2).collect::<Vec<_>>();
+
+        let mut objects = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {num_pages} >>",
+                page_ids
+                    .iter()
+                    .map(|id| format!("{id} 0 R"))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+        ];
+
+        for (page, id) in self.pages.iter().zip(&page_ids) {
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+                 /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                id + 1
+            ));
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{page}endstream",
+                page.len()
+            ));
+        }
+
+        let mut out = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            let _ = write!(out, "{} 0 obj\n{object}\nendobj\n", i + 1);
+        }
+
+        let xref = out.len();
+        let _ = write!(out, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            let _ = writeln!(out, "{offset:010} 00000 n ");
+        }
+        let _ = write!(
+            out,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            objects.len() + 1
+        );
+
+        out.into_bytes()
+    }
+}
+
+/// Escape text for a PDF string literal, replacing anything outside of
+/// printable ASCII so that byte offsets stay correct.
+fn escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '(' | ')' => {
+                result.push('\\');
+                result.push(c);
+            }
+            ' '..='~' => result.push(c),
+            c if c.is_whitespace() => result.push(' '),
+            _ => result.push('?'),
+        }
+    }
+    result
+}
+
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        let word_len = word.chars().count();
+        let current_len = current.chars().count();
+        if current_len > 0 && current_len + 1 + word_len > width {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(f: impl FnOnce(&mut PdfWriter)) -> String {
+        let mut writer = PdfWriter::new();
+        f(&mut writer);
+        String::from_utf8(writer.finish()).unwrap()
+    }
+
+    #[test]
+    fn test_document_structure() {
+        let pdf = render(|writer| {
+            writer.heading("Report");
+            writer.line("Hello");
+        });
+        assert!(pdf.starts_with("%PDF-1.4\n"));
+        assert!(pdf.ends_with("%%EOF\n"));
+        assert!(pdf.contains("(Report) Tj"));
+        assert!(pdf.contains("(Hello) Tj"));
+        assert!(pdf.contains("/Count 1"));
+    }
+
+    #[test]
+    fn test_xref_offsets() {
+        let pdf = render(|writer| writer.line("Hello"));
+        let startxref = pdf
+            .lines()
+            .skip_while(|line| *line != "startxref")
+            .nth(1)
+            .unwrap()
+            .parse::<usize>()
+            .unwrap();
+        assert!(pdf[startxref..].starts_with("xref\n"));
+
+        let offsets = pdf[startxref..]
+            .lines()
+            .skip(3)
+            .take_while(|line| line.ends_with(" n "))
+            .map(|line| line[..10].parse::<usize>().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(offsets.len(), 6);
+        for (i, offset) in offsets.into_iter().enumerate() {
+            assert!(pdf[offset..].starts_with(&format!("{} 0 obj", i + 1)));
+        }
+    }
+
+    #[test]
+    fn test_page_break() {
+        let pdf = render(|writer| {
+            for i in 0..100 {
+                writer.line(&format!("Line {i}"));
+            }
+        });
+        assert!(pdf.contains("/Count 2"));
+        assert!(pdf.contains("(Line 99) Tj"));
+    }
+
+    #[test]
+    fn test_deterministic() {
+        let pdf_1 = render(|writer| writer.line("Hello"));
+        let pdf_2 = render(|writer| writer.line("Hello"));
+        assert_eq!(pdf_1, pdf_2);
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape(r"a (b) \c"), r"a \(b\) \\c");
+        assert_eq!(escape("caf\u{e9}\tx"), "caf? x");
+    }
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(wrap("", 10), vec![""]);
+        assert_eq!(wrap("one two three", 7), vec!["one two", "three"]);
+        assert_eq!(
+            wrap("averyveryverylongword", 5),
+            vec!["averyveryverylongword"]
+        );
+    }
+}
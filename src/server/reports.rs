@@ -0,0 +1,291 @@
+//! PDF summary reports for a range of dates.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::models::{
+    Consumable, ConsumptionConsumable, ConsumptionItem, ConsumptionWithItems, Entry, EntryData,
+    RangeOptions, Timeline,
+};
+use crate::server::database::{connection::DatabaseConnection, models};
+
+use super::pdf::PdfWriter;
+
+pub async fn load_timeline(
+    conn: &mut DatabaseConnection,
+    user_id: i64,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Timeline, diesel::result::Error> {
+    let mut timeline = Timeline::new();
+
+    let wees =
+        models::wees::get_wees_for_time_range(conn, user_id, start, end, RangeOptions::default())
+            .await?;
+    timeline.add_wees(wees.into_iter().map(Into::into).collect());
+
+    let wee_urges = models::wee_urges::get_wee_urges_for_time_range(
+        conn,
+        user_id,
+        start,
+        end,
+        RangeOptions::default(),
+    )
+    .await?;
+    timeline.add_wee_urges(wee_urges.into_iter().map(Into::into).collect());
+
+    let poos =
+        models::poos::get_poos_for_time_range(conn, user_id, start, end, RangeOptions::default())
+            .await?;
+    timeline.add_poos(poos.into_iter().map(Into::into).collect());
+
+    models::consumptions::get_consumptions_for_time_range(
+        conn,
+        user_id,
+        start,
+        end,
+        RangeOptions::default(),
+    )
+    .await?
+    .into_iter()
+    .for_each(|(consumption, items)| {
+        let items = items
+            .into_iter()
+            .map(|(nested, consumable)| {
+                ConsumptionItem::new(
+                    ConsumptionConsumable::from(nested),
+                    Consumable::from(consumable),
+                )
+            })
+            .collect();
+        timeline.add_consumption(ConsumptionWithItems::new(consumption.into(), items));
+    });
+
+    let exercises = models::exercises::get_exercises_for_time_range(
+        conn,
+        user_id,
+        start,
+        end,
+        RangeOptions::default(),
+    )
+    .await?;
+    timeline.add_exercises(exercises.into_iter().map(Into::into).collect());
+
+    let health_metrics = models::health_metrics::get_health_metrics_for_time_range(
+        conn,
+        user_id,
+        start,
+        end,
+        RangeOptions::default(),
+    )
+    .await?;
+    timeline.add_health_metrics(health_metrics.into_iter().map(Into::into).collect());
+
+    let symptoms = models::symptoms::get_symptoms_for_time_range(
+        conn,
+        user_id,
+        start,
+        end,
+        RangeOptions::default(),
+    )
+    .await?;
+    timeline.add_symptoms(symptoms.into_iter().map(Into::into).collect());
+
+    let refluxs = models::refluxs::get_refluxs_for_time_range(
+        conn,
+        user_id,
+        start,
+        end,
+        RangeOptions::default(),
+    )
+    .await?;
+    timeline.add_refluxs(refluxs.into_iter().map(Into::into).collect());
+
+    let notes =
+        models::notes::get_notes_for_time_range(conn, user_id, start, end, RangeOptions::default())
+            .await?;
+    timeline.add_notes(notes.into_iter().map(Into::into).collect());
+
+    let fluid_adjustments = models::fluid_adjustments::get_fluid_adjustments_for_time_range(
+        conn,
+        user_id,
+        start,
+        end,
+        RangeOptions::default(),
+    )
+    .await?;
+    timeline.add_fluid_adjustments(fluid_adjustments.into_iter().map(Into::into).collect());
+
+    let medication_doses = models::medication_doses::get_medication_doses_for_time_range(
+        conn,
+        user_id,
+        start,
+        end,
+        RangeOptions::default(),
+    )
+    .await?;
+    timeline.add_medication_doses(medication_doses.into_iter().map(Into::into).collect());
+
+    timeline.sort();
+    Ok(timeline)
+}
+
+fn entry_summary(entry: &Entry) -> String {
+    let details = match &entry.data {
+        EntryData::Wee(wee) => format!("{} ml, {}", wee.mls, wee.urgency.as_title()),
+        EntryData::WeeUrge(wee_urge) => wee_urge.urgency.as_title().to_string(),
+        EntryData::Poo(poo) => format!(
+            "Bristol {}, quantity {}, {}",
+            i32::from(poo.bristol),
+            poo.quantity,
+            poo.urgency.as_title()
+        ),
+        EntryData::Consumption(consumption) => {
+            let items = consumption
+                .items
+                .iter()
+                .map(|item| item.consumable.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!(
+                "{}: {items}",
+                consumption.consumption.consumption_type.as_title()
+            )
+        }
+        EntryData::Exercise(exercise) => format!(
+            "{}, {} minutes",
+            exercise.exercise_type.as_title(),
+            exercise.duration.num_minutes()
+        ),
+        EntryData::HealthMetric(health_metric) => {
+            let mut values = Vec::new();
+            if let Some(pulse) = health_metric.pulse {
+                values.push(format!("pulse {pulse}"));
+            }
+            if let (Some(systolic), Some(diastolic)) =
+                (health_metric.systolic_bp, health_metric.diastolic_bp)
+            {
+                values.push(format!("BP {systolic}/{diastolic}"));
+            }
+            if let Some(blood_glucose) = &health_metric.blood_glucose {
+                values.push(format!("glucose {blood_glucose} mmol/L"));
+            }
+            if let Some(weight) = &health_metric.weight {
+                values.push(format!("weight {weight} kg"));
+            }
+            values.join(", ")
+        }
+        EntryData::Symptom(symptom) => symptom
+            .intensities()
+            .into_iter()
+            .filter(|(_, intensity)| *intensity > 0)
+            .map(|(title, intensity)| format!("{title} {intensity}"))
+            .collect::<Vec<_>>()
+            .join(", "),
+        EntryData::Reflux(reflux) => format!("severity {}", reflux.severity),
+        EntryData::Note(_) => String::new(),
+        EntryData::FluidAdjustment(fluid_adjustment) => format!("{:+} ml", fluid_adjustment.mls),
+        EntryData::Medication(medication_dose) => format!(
+            "{} {}",
+            medication_dose.medication.name(),
+            medication_dose.medication.dose_string()
+        ),
+    };
+
+    let comments = match &entry.data {
+        EntryData::Wee(wee) => wee.comments.as_deref(),
+        EntryData::WeeUrge(wee_urge) => wee_urge.comments.as_deref(),
+        EntryData::Poo(poo) => poo.comments.as_deref(),
+        EntryData::Consumption(consumption) => consumption.consumption.comments.as_deref(),
+        EntryData::Exercise(exercise) => exercise.comments.as_deref(),
+        EntryData::HealthMetric(health_metric) => health_metric.comments.as_deref(),
+        EntryData::Symptom(symptom) => symptom.comments.as_deref(),
+        EntryData::Reflux(reflux) => reflux.comments.as_deref(),
+        EntryData::Note(note) => note.comments.as_deref(),
+        EntryData::FluidAdjustment(fluid_adjustment) => fluid_adjustment.comments.as_deref(),
+        EntryData::Medication(medication_dose) => medication_dose.dose.comments.as_deref(),
+    };
+
+    match (details.is_empty(), comments) {
+        (true, Some(comments)) => comments.to_string(),
+        (false, Some(comments)) => format!("{details} - {comments}"),
+        (_, None) => details,
+    }
+}
+
+/// Render a report for the given timeline. The timeline should already be
+/// sorted.
+///
+/// Entries that started before `from`, the start of the first day, are
+/// listed but only counted in the report for the day they started.
+pub fn render_report(
+    timeline: &Timeline,
+    start: NaiveDate,
+    end: NaiveDate,
+    from: DateTime<Utc>,
+) -> Vec<u8> {
+    let mut pdf = PdfWriter::new();
+    pdf.heading("Penguin Nurse Report");
+    pdf.line(&format!("From {start} to {end}"));
+
+    pdf.heading("Key Statistics");
+    let mut counts: BTreeMap<&'static str, usize> = BTreeMap::new();
+    for entry in timeline.iter().filter(|entry| !entry.starts_before(from)) {
+        *counts.entry(entry.kind().as_title()).or_default() += 1;
+    }
+    if counts.is_empty() {
+        pdf.line("No entries recorded.");
+    }
+    for (kind, count) in counts {
+        pdf.line(&format!("{kind}: {count}"));
+    }
+    let balance = timeline.fluid_balance(from);
+    pdf.line(&format!(
+        "Fluid in: {} ml, out: {} ml, adjustments: {} ml, net: {} ml",
+        balance.input.round(0),
+        balance.output.round(0),
+        balance.adjustment.round(0),
+        balance.net().round(0)
+    ));
+
+    pdf.heading("Symptom Trends");
+    let mut trends: Vec<(&'static str, usize, i32)> = Vec::new();
+    for entry in timeline.iter() {
+        let EntryData::Symptom(symptom) = &entry.data else {
+            continue;
+        };
+        for (title, intensity) in symptom.intensities() {
+            if intensity <= 0 {
+                continue;
+            }
+            match trends.iter_mut().find(|(t, _, _)| *t == title) {
+                Some((_, count, max)) => {
+                    *count += 1;
+                    *max = (*max).max(intensity);
+                }
+                None => trends.push((title, 1, intensity)),
+            }
+        }
+    }
+    if trends.is_empty() {
+        pdf.line("No symptoms recorded.");
+    }
+    for (title, count, max) in trends {
+        pdf.line(&format!(
+            "{title}: reported {count} times, maximum intensity {max}"
+        ));
+    }
+
+    pdf.heading("Entries");
+    for entry in timeline.iter() {
+        pdf.line(&format!(
+            "{} {}: {}",
+            entry.time.format("%Y-%m-%d %H:%M"),
+            entry.kind().as_title(),
+            entry_summary(entry)
+        ));
+    }
+
+    pdf.finish()
+}
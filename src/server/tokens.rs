@@ -0,0 +1,47 @@
+//! Random secrets handed out to clients, such as share links and API tokens.
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use sha2::{Digest, Sha256};
+
+/// Generate a random URL safe token with 256 bits of entropy.
+pub fn generate_token() -> Result<String, getrandom::Error> {
+    let mut bytes = [0u8; 32];
+    getrandom::fill(&mut bytes)?;
+    Ok(URL_SAFE_NO_PAD.encode(bytes))
+}
+
+/// Hash a token for storage. Tokens are random, so a fast hash is enough to
+/// stop a leaked database being used to authenticate.
+pub fn hash_token(token: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_tokens_are_unique() {
+        let first = generate_token().unwrap();
+        let second = generate_token().unwrap();
+        assert_eq!(first.len(), 43);
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn hash_is_stable_and_hides_token() {
+        let token = "secret-token";
+        assert_eq!(hash_token(token), hash_token(token));
+        assert_ne!(hash_token(token), token);
+        assert_ne!(hash_token(token), hash_token("other-token"));
+    }
+
+    #[test]
+    fn hash_matches_share_link_migration() {
+        // The share_link_token_hash migration hashes existing tokens in SQL.
+        assert_eq!(
+            hash_token("abc"),
+            "ungWv48Bz-pBQUDeXa4iI7ADYaOWF3qctBD_YfIAFa0"
+        );
+    }
+}
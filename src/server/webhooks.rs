@@ -0,0 +1,316 @@
+//! Delivery of entry changes to the webhooks users have registered.
+//!
+//! Server functions push events onto an in memory queue, so saving an entry
+//! never waits on a remote server. A background task looks up the matching
+//! webhooks and delivers each one independently, retrying with backoff.
+//! Both the queue and the number of deliveries in flight are limited, events
+//! are dropped with a warning when the queue is full.
+//!
+//! Webhook hosts are resolved by [`PublicResolver`], so a URL can't be used
+//! to reach the internal network even if its DNS changes after it was
+//! registered. Redirects are not followed for the same reason.
+
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, KeyInit, Mac};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use serde::Serialize;
+use sha2::Sha256;
+use tokio::sync::{Semaphore, mpsc};
+use tracing::{error, warn};
+
+use crate::models::{self, EntryId, UserId, WebhookAction};
+use crate::server::database::connection::DatabasePool;
+use crate::server::database::models::webhooks;
+
+/// Header containing the hex encoded HMAC-SHA256 of the request body.
+pub const SIGNATURE_HEADER: &str = "X-Penguin-Nurse-Signature";
+
+const MAX_ATTEMPTS: u32 = 5;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Events waiting for their webhooks to be looked up.
+const QUEUE_SIZE: usize = 1000;
+/// Deliveries, including their retries, in progress at once.
+const MAX_DELIVERIES: usize = 32;
+
+/// A change to an entry that webhooks may want to hear about.
+#[derive(Debug, Clone)]
+pub struct WebhookEvent {
+    pub user_id: UserId,
+    pub action: WebhookAction,
+    pub entry_id: EntryId,
+    pub occurred_at: DateTime<Utc>,
+    /// The entry after the change, `None` when it was deleted.
+    pub data: Option<serde_json::Value>,
+}
+
+#[derive(Serialize)]
+struct Payload<'a> {
+    action: WebhookAction,
+    kind: &'static str,
+    entry_id: String,
+    user_id: i64,
+    occurred_at: DateTime<Utc>,
+    data: &'a Option<serde_json::Value>,
+}
+
+impl<'a> From<&'a WebhookEvent> for Payload<'a> {
+    fn from(event: &'a WebhookEvent) -> Self {
+        Self {
+            action: event.action,
+            kind: event.entry_id.kind().as_id(),
+            entry_id: event.entry_id.as_str(),
+            user_id: event.user_id.as_inner(),
+            occurred_at: event.occurred_at,
+            data: &event.data,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct WebhookQueue(mpsc::Sender<WebhookEvent>);
+
+impl WebhookQueue {
+    /// Start the background task that delivers queued events.
+    pub fn start(database: DatabasePool) -> Self {
+        let (tx, rx) = mpsc::channel(QUEUE_SIZE);
+        tokio::spawn(run(database, rx));
+        Self(tx)
+    }
+
+    pub fn push(&self, event: WebhookEvent) {
+        match self.0.try_send(event) {
+            Ok(()) => {}
+            Err(mpsc::error::TrySendError::Full(event)) => {
+                warn!(
+                    "Webhook queue is full, dropping {} event for {}",
+                    event.action.as_id(),
+                    event.entry_id.as_str()
+                );
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => {
+                error!("Webhook queue is closed, dropping event");
+            }
+        }
+    }
+}
+
+/// Resolves hosts with the system resolver, failing if any address is not
+/// public.
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let addrs = resolve_public(&host, 0).await?;
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+async fn resolve_public(
+    host: &str,
+    port: u16,
+) -> Result<Vec<SocketAddr>, Box<dyn std::error::Error + Send + Sync>> {
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port)).await?.collect();
+    if addrs.is_empty() {
+        return Err(format!("{host} has no addresses").into());
+    }
+    if let Some(addr) = addrs.iter().find(|addr| !models::is_public_ip(addr.ip())) {
+        return Err(format!("{host} resolves to internal address {}", addr.ip()).into());
+    }
+    Ok(addrs)
+}
+
+/// The webhook URL as it will be requested, checking any address in it
+/// the way the URL parser reads it.
+fn parse_webhook_url(url: &str) -> Result<url::Url, String> {
+    let url = models::check_webhook_url(url)?;
+    let parsed = url::Url::parse(&url).map_err(|err| format!("Invalid URL: {err}"))?;
+    let ip = match parsed.host() {
+        Some(url::Host::Domain(_)) => return Ok(parsed),
+        Some(url::Host::Ipv4(ip)) => IpAddr::V4(ip),
+        Some(url::Host::Ipv6(ip)) => IpAddr::V6(ip),
+        None => return Err("URL must include a host".to_string()),
+    };
+    if models::is_public_ip(ip) {
+        Ok(parsed)
+    } else {
+        Err("URL must be for a public host".to_string())
+    }
+}
+
+/// Check a webhook URL is allowed and that its host only resolves to public
+/// addresses, so users find out when they register it.
+pub async fn check_webhook_url(url: &str) -> Result<String, String> {
+    let parsed = parse_webhook_url(url)?;
+    if let Some(url::Host::Domain(host)) = parsed.host() {
+        let port = parsed.port_or_known_default().unwrap_or_default();
+        resolve_public(host, port)
+            .await
+            .map_err(|err| format!("URL must be for a public host: {err}"))?;
+    }
+    Ok(url.trim().to_string())
+}
+
+fn build_client() -> reqwest::Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none())
+        .dns_resolver(Arc::new(PublicResolver))
+        .build()
+}
+
+async fn run(database: DatabasePool, mut rx: mpsc::Receiver<WebhookEvent>) {
+    let deliveries = Arc::new(Semaphore::new(MAX_DELIVERIES));
+    let client = match build_client() {
+        Ok(client) => client,
+        Err(err) => {
+            error!("Failed to create webhook client: {err}");
+            return;
+        }
+    };
+
+    while let Some(event) = rx.recv().await {
+        let hooks = match load_webhooks(&database, event.user_id).await {
+            Ok(hooks) => hooks,
+            Err(err) => {
+                error!("Failed to load webhooks for {}: {err}", event.user_id);
+                continue;
+            }
+        };
+
+        let body = match serde_json::to_vec(&Payload::from(&event)) {
+            Ok(body) => body,
+            Err(err) => {
+                error!("Failed to serialize webhook payload: {err}");
+                continue;
+            }
+        };
+
+        let kind = event.entry_id.kind();
+        for hook in hooks {
+            let secret = hook.secret.clone();
+            let hook: models::Webhook = hook.into();
+            if hook.wants(kind) {
+                // Waiting here leaves events in the queue until a delivery
+                // finishes.
+                let Ok(permit) = deliveries.clone().acquire_owned().await else {
+                    return;
+                };
+                let delivery = deliver(client.clone(), hook, secret, event.action, body.clone());
+                tokio::spawn(async move {
+                    delivery.await;
+                    drop(permit);
+                });
+            }
+        }
+    }
+}
+
+async fn load_webhooks(
+    database: &DatabasePool,
+    user_id: UserId,
+) -> Result<Vec<webhooks::Webhook>, String> {
+    let mut conn = database.get().await.map_err(|err| err.to_string())?;
+    webhooks::get_webhooks_for_user(&mut conn, user_id.as_inner())
+        .await
+        .map_err(|err| err.to_string())
+}
+
+async fn deliver(
+    client: reqwest::Client,
+    hook: models::Webhook,
+    secret: String,
+    action: WebhookAction,
+    body: Vec<u8>,
+) {
+    // Checked again as the rules may have changed since it was registered,
+    // and addresses in the URL itself are not looked up by the resolver.
+    let url = match parse_webhook_url(&hook.url) {
+        Ok(url) => url,
+        Err(err) => {
+            warn!("Webhook {} has a URL that is not allowed: {err}", hook.id);
+            return;
+        }
+    };
+
+    let signature = format!("sha256={}", sign(&secret, &body));
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        let result = client
+            .post(url.clone())
+            .header("Content-Type", "application/json")
+            .header("X-Penguin-Nurse-Event", action.as_id())
+            .header(SIGNATURE_HEADER, &signature)
+            .body(body.clone())
+            .send()
+            .await;
+
+        match result {
+            Ok(response) if response.status().is_success() => return,
+            Ok(response) => warn!(
+                "Webhook {} attempt {attempt} returned {}",
+                hook.id,
+                response.status()
+            ),
+            Err(err) => warn!("Webhook {} attempt {attempt} failed: {err}", hook.id),
+        }
+
+        if attempt < MAX_ATTEMPTS {
+            tokio::time::sleep(backoff(attempt)).await;
+        }
+    }
+
+    error!("Webhook {} failed after {MAX_ATTEMPTS} attempts", hook.id);
+}
+
+/// Delay before retrying after a failed attempt, doubling each time.
+fn backoff(attempt: u32) -> Duration {
+    Duration::from_secs(2u64.pow(attempt))
+}
+
+/// Hex encoded HMAC-SHA256 of `body`, so receivers can check where a request
+/// came from.
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = <Hmac<Sha256> as KeyInit>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(body);
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sign_matches_known_value() {
+        assert_eq!(
+            sign("key", b"The quick brown fox jumps over the lazy dog"),
+            "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+    }
+
+    #[test]
+    fn parse_url_rejects_other_spellings_of_internal_addresses() {
+        // The URL parser reads these as 127.0.0.1.
+        assert!(parse_webhook_url("http://2130706433/hook").is_err());
+        assert!(parse_webhook_url("http://0x7f.1/hook").is_err());
+        assert!(parse_webhook_url("https://example.com/hook").is_ok());
+    }
+
+    #[test]
+    fn backoff_doubles() {
+        assert_eq!(backoff(1), Duration::from_secs(2));
+        assert_eq!(backoff(2), Duration::from_secs(4));
+        assert_eq!(backoff(4), Duration::from_secs(16));
+    }
+}
@@ -1,5 +1,5 @@
 /// Validation/linting utilities for consumables and consumptions
-use crate::models::{Consumable, ConsumableItem, Consumption, ConsumptionItem};
+use crate::models::{Consumable, ConsumableItem, Consumption, ConsumptionItem, normalize_barcode};
 
 pub fn consumable_errors(
     consumable: &Consumable,
@@ -7,6 +7,18 @@ pub fn consumable_errors(
 ) -> Vec<String> {
     let mut errors = Vec::new();
 
+    if let Some(barcode) = &consumable.barcode {
+        match normalize_barcode(barcode) {
+            Ok(normalized) if normalized != *barcode => {
+                errors.push(format!(
+                    "Barcode {barcode} is not normalized, should be {normalized}"
+                ));
+            }
+            Ok(_) => {}
+            Err(err) => errors.push(format!("Barcode {barcode} is invalid: {err}")),
+        }
+    }
+
     if let Some(nested_consumables) = nested_consumables {
         for nc in nested_consumables {
             if let (Some(nc_type), Some(consumable_type)) =
@@ -44,8 +56,7 @@ pub fn consumption_errors(
         let expected_mls = consumption.liquid_mls.as_ref().unwrap_or(&zero);
         let total_nested_mls: bigdecimal::BigDecimal = consumption_consumables
             .iter()
-            .filter_map(|ci| ci.nested.liquid_mls.as_ref())
-            .cloned()
+            .filter_map(|ci| ci.portion_liquid_mls())
             .sum();
         if *expected_mls != total_nested_mls {
             errors.push(format!(
@@ -104,6 +115,11 @@ mod tests {
             created_at: Utc::now(),
             updated_at: Utc::now(),
             consumption_type,
+            calories: None,
+            protein: None,
+            fat: None,
+            carbohydrate: None,
+            image_key: None,
         }
     }
 
@@ -140,10 +156,12 @@ mod tests {
             time: chrono::DateTime::parse_from_rfc3339("2024-01-01T12:00:00+00:00").unwrap(),
             duration: chrono::TimeDelta::seconds(duration_secs),
             consumption_type,
+            custom_type_id: None,
             liquid_mls,
             comments: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            planned: false,
         }
     }
 
@@ -161,6 +179,7 @@ mod tests {
                 ),
                 quantity: None,
                 liquid_mls,
+                portion: BigDecimal::from(1),
                 comments: None,
                 created_at: Utc::now(),
                 updated_at: Utc::now(),
@@ -169,6 +188,22 @@ mod tests {
         )
     }
 
+    #[test]
+    fn consumable_invalid_barcode_returns_error() {
+        let mut consumable = make_consumable(1, None);
+        consumable.barcode = Some("4006381333932".to_string());
+        assert_eq!(consumable_errors(&consumable, None).len(), 1);
+    }
+
+    #[test]
+    fn consumable_unnormalized_barcode_returns_error() {
+        let mut consumable = make_consumable(1, None);
+        consumable.barcode = Some("036000291452".to_string());
+        let errors = consumable_errors(&consumable, None);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("0036000291452"));
+    }
+
     // ── consumable_errors ─────────────────────────────────────────────────────
 
     #[test]
@@ -299,4 +334,17 @@ mod tests {
         );
         assert!(consumption_errors(&c, Some(&vec![item])).is_empty());
     }
+
+    #[test]
+    fn consumption_liquid_mls_uses_ingredient_portion() {
+        let c = make_consumption(10, Some(BigDecimal::from(125)), ConsumptionType::Digest);
+        let mut item = make_consumption_item(
+            1,
+            1,
+            Some(BigDecimal::from(250)),
+            Some(ConsumptionType::Digest),
+        );
+        item.nested.portion = "0.5".parse().unwrap();
+        assert!(consumption_errors(&c, Some(&vec![item])).is_empty());
+    }
 }
@@ -4,13 +4,13 @@ use crate::{
         FormCancelButton, FormCloseButton, FormSubmitButton, InputPassword, InputString, MyForm,
         validate_password, validate_username,
     },
-    models::User,
+    models::{OidcFailure, OidcProvider, User, post_login_url},
     reload_user, use_user,
 };
 use dioxus::prelude::*;
 
 use dioxus_fullstack::{ServerFnError, server};
-use dioxus_router::{NavigationTarget, navigator};
+use dioxus_router::{NavigationTarget, ToQueryArgument, navigator};
 #[cfg(feature = "server")]
 use tracing::error;
 
@@ -40,9 +40,95 @@ pub fn LoginWindow(children: Element) -> Element {
     }
 }
 
+/// Where to go after logging in, see [`post_login_url`].
+fn post_login_route(next: &str) -> Route {
+    post_login_url(next).parse().unwrap_or(Route::Landing {})
+}
+
 #[component]
-pub fn Login() -> Element {
-    let is_oidc_enabled = use_resource(is_oidc_enabled);
+fn OidcLoginButton(
+    provider: String,
+    title: String,
+    /// Page to return to after logging in.
+    next: String,
+    on_error: EventHandler<ServerFnError>,
+) -> Element {
+    rsx! {
+        div { class: "flex items-center justify-center",
+            button {
+                r#type: "button",
+                class: "w-full btn btn-primary my-2",
+                onclick: move |_| {
+                    let provider = provider.clone();
+                    let next = next.clone();
+                    async move {
+                        match login_with_oidc(provider, next).await {
+                            Ok(url) => {
+                                let navigator = navigator();
+                                navigator.push(NavigationTarget::<Route>::External(url));
+                            }
+                            Err(err) => on_error(err),
+                        }
+                    }
+                },
+                {title}
+            }
+        }
+    }
+}
+
+impl ToQueryArgument for OidcFailure {
+    fn display_query_argument(
+        &self,
+        query_name: &str,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(f, "{}={}", query_name, self.as_str())
+    }
+}
+
+/// Shown when logging in with the OIDC provider fails.
+#[component]
+pub fn LoginError(reason: OidcFailure) -> Element {
+    let providers = use_resource(oidc_providers);
+    let mut error: Signal<Option<ServerFnError>> = use_signal(|| None);
+
+    rsx! {
+        LoginWindow {
+            h1 { class: "text-xl font-bold leading-tight tracking-tight text-gray-900 md:text-2xl dark:text-white",
+                {reason.title()}
+            }
+            p { class: "text-gray-500 dark:text-gray-300", {reason.message()} }
+            if let Some(err) = error() {
+                div { class: "alert alert-error", {err.to_string()} }
+            }
+            if reason.can_retry() {
+                if let Some(Ok(providers)) = providers() {
+                    for provider in providers.iter().cloned() {
+                        OidcLoginButton {
+                            key: "{provider.id}",
+                            title: if providers.len() == 1 { "Try again".to_string() } else { format!("Try again with {}", provider.name) },
+                            provider: provider.id,
+                            next: String::new(),
+                            on_error: move |err| error.set(Some(err)),
+                        }
+                    }
+                }
+            }
+            Link {
+                to: Route::Login {
+                    next: String::new(),
+                },
+                class: "text-sm font-medium text-primary-600 hover:underline dark:text-primary-500",
+                "Back to login"
+            }
+        }
+    }
+}
+
+#[component]
+pub fn Login(next: ReadSignal<Option<String>>) -> Element {
+    let providers = use_resource(oidc_providers);
 
     let username = use_signal(String::new);
     let password = use_signal(String::new);
@@ -65,7 +151,7 @@ pub fn Login() -> Element {
                 reload_user();
                 result.set(None);
                 let navigator = navigator();
-                navigator.push(Route::Home {});
+                navigator.push(post_login_route(&next().unwrap_or_default()));
             }
             Err(err) => {
                 result.set(Some(Err(err)));
@@ -87,9 +173,9 @@ pub fn Login() -> Element {
                             disabled: Memo::new(|| false),
                             on_save: move |_| {
                                 let navigator = navigator();
-                                navigator.push(Route::Home {});
+                                navigator.push(post_login_route(&next().unwrap_or_default()));
                             },
-                            title: "Home",
+                            title: "Continue",
                         }
                     }
                 }
@@ -171,23 +257,14 @@ pub fn Login() -> Element {
                                         title: "Sign in",
                                         on_save: move |_e| async move { on_save(()).await },
                                     }
-                                    if is_oidc_enabled().unwrap_or(Ok(false)).unwrap_or(false) {
-                                        div { class: "flex items-center justify-center",
-                                            button {
-                                                r#type: "button",
-                                                class: "w-full btn btn-primary my-2",
-                                                onclick: move |_| async move {
-                                                    let url = login_with_oidc().await;
-                                                    match url {
-                                                        Ok(url) => {
-                                                            result.set(None);
-                                                            let navigator = navigator();
-                                                            navigator.push(NavigationTarget::<Route>::External(url));
-                                                        }
-                                                        Err(err) => result.set(Some(Err(err))),
-                                                    }
-                                                },
-                                                "Sign in with OIDC"
+                                    if let Some(Ok(providers)) = providers() {
+                                        for provider in providers {
+                                            OidcLoginButton {
+                                                key: "{provider.id}",
+                                                title: "Sign in with {provider.name}",
+                                                provider: provider.id,
+                                                next: next().unwrap_or_default(),
+                                                on_error: move |err| result.set(Some(Err(err))),
                                             }
                                         }
                                     }
@@ -312,6 +389,14 @@ async fn login_with_password(username: String, password: String) -> Result<User,
             error!("Invalid credentials");
             return Err(ServerFnError::new("Invalid credentials"));
         }
+        Err(axum_login::Error::Backend(crate::server::auth::Error::AccountDisabled)) => {
+            error!("Login refused for disabled account");
+            return Err(ServerFnError::new("Account is disabled"));
+        }
+        Err(axum_login::Error::Backend(crate::server::auth::Error::InvalidCredentials)) => {
+            error!("Password login refused for account without a password");
+            return Err(ServerFnError::new("Invalid credentials"));
+        }
         Err(err) => {
             error!("Error authenticating user: {:?}", err);
             return Err(ServerFnError::new("Invalid server error"));
@@ -346,26 +431,55 @@ pub async fn get_user() -> Result<Option<User>, ServerFnError> {
     session.user.clone().map(|x| x.into()).pipe(Ok)
 }
 
+/// Providers that are ready to log in with. Fails if OIDC is not configured.
 #[server]
-pub async fn is_oidc_enabled() -> Result<bool, ServerFnError> {
+pub async fn oidc_providers() -> Result<Vec<OidcProvider>, ServerFnError> {
     use crate::server::OidcClientState;
     use axum::Extension;
 
-    let Extension(client): Extension<OidcClientState> = FullstackContext::extract().await?;
-    Ok(client.load().is_some())
+    let Extension(providers): Extension<OidcClientState> = FullstackContext::extract().await?;
+    providers
+        .iter()
+        .filter(|provider| provider.client.load().is_some())
+        .map(|provider| OidcProvider {
+            id: provider.id.clone(),
+            name: provider.name.clone(),
+        })
+        .collect::<Vec<_>>()
+        .pipe(Ok)
 }
 
 #[server]
-pub async fn login_with_oidc() -> Result<String, ServerFnError> {
-    use crate::server::OidcClientState;
-    use axum::Extension;
+pub async fn login_with_oidc(provider: String, next: String) -> Result<String, ServerFnError> {
+    use crate::server::auth::SecureCookies;
+    use crate::server::{OidcClientState, OidcPendingLogins};
+    use axum::{
+        Extension,
+        http::{HeaderMap, header},
+    };
 
-    let Extension(oidc_client): Extension<OidcClientState> = FullstackContext::extract().await?;
+    let Extension(providers): Extension<OidcClientState> = FullstackContext::extract().await?;
+    let Extension(pending_logins): Extension<OidcPendingLogins> =
+        FullstackContext::extract().await?;
+    let Extension(SecureCookies(secure)): Extension<SecureCookies> =
+        FullstackContext::extract().await?;
+    let headers: HeaderMap = FullstackContext::extract().await?;
 
-    let oidc_client = oidc_client.load();
+    let Some(provider) = providers.iter().find(|p| p.id == provider) else {
+        return Err(ServerFnError::new("Unknown OIDC provider"));
+    };
+    let oidc_client = provider.client.load();
     let Some(oidc_client) = oidc_client.as_ref() else {
         return Err(ServerFnError::new("OIDC not initialized"));
     };
-    let auth_url = oidc_client.get_auth_url("/");
+    let (auth_url, set_cookie) = oidc_client
+        .get_auth_url(&pending_logins, &headers, &next, secure)
+        .map_err(|e| {
+            error!("Error starting OIDC login: {:?}", e);
+            ServerFnError::new("Error starting OIDC login")
+        })?;
+    if let Some(context) = FullstackContext::current() {
+        context.add_response_header(header::SET_COOKIE, set_cookie);
+    }
     Ok(auth_url)
 }
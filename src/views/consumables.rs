@@ -1,6 +1,6 @@
 use std::ops::Deref;
 
-use chrono::Local;
+use chrono::{Local, Utc};
 use dioxus::prelude::*;
 use dioxus_fullstack::ServerFnError;
 use dioxus_router::navigator;
@@ -11,27 +11,87 @@ use crate::{
     components::{
         buttons::{ChangeButton, CreateButton},
         consumables::{
-            ActiveDialog, ConsumableDialog, ConsumableItemList, ListDialogReference, Operation,
-            OrganicIcon, consumable_errors,
+            ActiveDialog, ConsumableDialog, ConsumableItemList, ConsumableThumbnail, Highlighted,
+            ListDialogReference, Operation, OrganicIcon, consumable_errors,
         },
         events::Markdown,
     },
     forms::Barcode,
-    functions::consumables::{get_consumable_by_id, search_consumables_with_nested},
-    models::{Consumable, ConsumableId, ConsumableWithItems},
+    functions::consumables::{
+        get_consumable_by_id, search_consumables_with_nested, update_consumable,
+    },
+    models::{ChangeConsumable, Consumable, ConsumableId, ConsumableWithItems, MaybeSet},
     use_user,
 };
 
+/// Which timestamp a quick action stamps with the current time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stamp {
+    Opened,
+    Finished,
+}
+
+impl Stamp {
+    fn as_title(self) -> &'static str {
+        match self {
+            Stamp::Opened => "opened",
+            Stamp::Finished => "finished",
+        }
+    }
+
+    fn changes(self) -> ChangeConsumable {
+        let now = MaybeSet::Set(Some(Utc::now()));
+        match self {
+            Stamp::Opened => ChangeConsumable {
+                created: now,
+                ..Default::default()
+            },
+            Stamp::Finished => ChangeConsumable {
+                destroyed: now,
+                ..Default::default()
+            },
+        }
+    }
+}
+
 #[component]
 fn EntryRow(
     consumable_with_items: ConsumableWithItems,
     selected: Signal<Option<ConsumableId>>,
+    query: String,
+    on_change: Callback<Consumable>,
 ) -> Element {
     let consumable = consumable_with_items.consumable;
     let items = consumable_with_items.items;
     let errors = consumable_errors(&consumable, Some(&items));
 
     let id = consumable.id;
+    let has_created = consumable.created.is_some();
+    let has_destroyed = consumable.destroyed.is_some();
+
+    let mut confirm: Signal<Option<Stamp>> = use_signal(|| None);
+    let mut stamp_error: Signal<Option<ServerFnError>> = use_signal(|| None);
+
+    let mut on_stamp = move |stamp: Stamp| {
+        let already_set = match stamp {
+            Stamp::Opened => has_created,
+            Stamp::Finished => has_destroyed,
+        };
+        if already_set && confirm() != Some(stamp) {
+            confirm.set(Some(stamp));
+            return;
+        }
+        confirm.set(None);
+        spawn(async move {
+            match update_consumable(id, stamp.changes()).await {
+                Ok(consumable) => {
+                    stamp_error.set(None);
+                    on_change(consumable);
+                }
+                Err(err) => stamp_error.set(Some(err)),
+            }
+        });
+    };
 
     let navigator = navigator();
     rsx! {
@@ -39,14 +99,17 @@ fn EntryRow(
             class: "hover:bg-gray-500 border-blue-300 mt-2 mb-2 p-2 border-2 w-full sm:w-auto sm:border-none inline-block sm:table-row",
             onclick: move |_| { selected.set(Some(id)) },
             td { class: "block sm:table-cell border-blue-300 sm:border-t-2",
+                ConsumableThumbnail { consumable: consumable.clone() }
                 if consumable.is_organic {
                     OrganicIcon {}
                 }
-                {consumable.name}
+                Highlighted { text: consumable.name.clone(), query: query.clone() }
             }
             td { class: "block sm:table-cell border-blue-300 sm:border-t-2",
                 if let Some(brand) = &consumable.brand {
-                    div { {brand.clone()} }
+                    div {
+                        Highlighted { text: brand.clone(), query: query.clone() }
+                    }
                 }
             }
 
@@ -132,6 +195,35 @@ fn EntryRow(
                             },
                             "Delete"
                         }
+                        ChangeButton {
+                            on_click: move |_| on_stamp(Stamp::Opened),
+                            "Opened now"
+                        }
+                        ChangeButton {
+                            on_click: move |_| on_stamp(Stamp::Finished),
+                            "Finished now"
+                        }
+                    }
+                    if let Some(stamp) = confirm() {
+                        div { class: "alert alert-warning mt-2",
+                            "This consumable already has a {stamp.as_title()} time. Replace it with now?"
+                            div { class: "flex gap-2",
+                                ChangeButton {
+                                    on_click: move |_| on_stamp(stamp),
+                                    "Replace"
+                                }
+                                ChangeButton {
+                                    on_click: move |_| confirm.set(None),
+                                    "Cancel"
+                                }
+                            }
+                        }
+                    }
+                    if let Some(err) = stamp_error() {
+                        div { class: "alert alert-error mt-2",
+                            "Error updating consumable: "
+                            {err.to_string()}
+                        }
                     }
                 }
             }
@@ -213,6 +305,16 @@ pub fn ConsumableList(dialog: ReadSignal<Option<ListDialogReference>>) -> Elemen
             search_consumables_with_nested(query(), show_only_created(), show_destroyed()).await
         });
 
+    let on_stamped = move |consumable: Consumable| {
+        if let Some(Ok(rows)) = list.write().as_mut()
+            && let Some(row) = rows
+                .iter_mut()
+                .find(|row| row.consumable.id == consumable.id)
+        {
+            row.consumable = consumable;
+        }
+    };
+
     rsx! {
         div { class: "ml-2 mr-2",
             div { class: "mb-2",
@@ -302,6 +404,8 @@ pub fn ConsumableList(dialog: ReadSignal<Option<ListDialogReference>>) -> Elemen
                                     key: "{consumable.consumable.id.as_inner().to_string()}",
                                     consumable_with_items: consumable.clone(),
                                     selected,
+                                    query: query(),
+                                    on_change: on_stamped,
                                 }
                             }
                         }
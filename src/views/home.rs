@@ -1,14 +1,114 @@
-use chrono::Utc;
+use std::ops::Deref;
+
+use chrono::NaiveDate;
+use chrono_tz::Tz;
 use dioxus::prelude::*;
+use dioxus_fullstack::ServerFnError;
 use dioxus_router::navigator;
 
 use crate::{
     Route,
-    components::{buttons::NavButton, timeline::DialogReference},
-    dt::get_date_for_dt,
-    use_user,
+    components::{
+        buttons::NavButton,
+        medications::MedicationAdherenceList,
+        stats::{DurationStatsPanel, NutritionPanel, TimeOfDayPanel, WeeUrgeLatencyPanel},
+        timeline::{DialogReference, EntryFocus},
+    },
+    dt::{get_today, get_utc_times_for_date},
+    functions::{
+        medication_doses::get_medication_doses_for_time_range, medications::get_medications,
+        stats::last_entry_date,
+    },
+    models::{LandingDate, MedicationAdherence, RangeOptions, SortDirection, Timeline, UserId},
+    use_timezone, use_user,
 };
 
+#[component]
+fn TodaysMedications(user_id: UserId) -> Element {
+    let timezone = use_timezone();
+    let adherence: Resource<Result<Vec<MedicationAdherence>, ServerFnError>> =
+        use_resource(move || async move {
+            let (start, end) = get_utc_times_for_date(get_today(timezone), timezone)?;
+            let medications = get_medications(false).await?;
+
+            let mut timeline = Timeline::new();
+            let doses =
+                get_medication_doses_for_time_range(user_id, start, end, RangeOptions::default())
+                    .await?;
+            timeline.add_medication_doses(doses);
+
+            Ok(timeline.medication_adherence(medications))
+        });
+
+    rsx! {
+        h2 { class: "font-bold text-lg", "Today's Medications" }
+        match adherence.read().deref() {
+            Some(Err(err)) => rsx! {
+                div { class: "alert alert-error",
+                    "Error loading medications: "
+                    {err.to_string()}
+                }
+            },
+            Some(Ok(adherence)) => rsx! {
+                MedicationAdherenceList { adherence: adherence.clone() }
+            },
+            None => rsx! {
+                p { class: "alert alert-info", "Loading..." }
+            },
+        }
+    }
+}
+
+/// The day the timeline should open on for the user's landing date preference.
+async fn get_landing_date(
+    user_id: UserId,
+    landing_date: Option<LandingDate>,
+    timezone: Option<Tz>,
+) -> NaiveDate {
+    let today = get_today(timezone);
+    match landing_date {
+        None => today,
+        Some(LandingDate::Yesterday) => today.pred_opt().unwrap_or(today),
+        Some(LandingDate::LastEntry) => last_entry_date(user_id, timezone)
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or(today),
+    }
+}
+
+/// Where logging in ends up by default: the timeline for the user's landing
+/// date, worked out in their timezone.
+#[component]
+pub fn Landing() -> Element {
+    let navigator = navigator();
+    // Still loading while this is an error, for instance just after logging in.
+    let user = use_user().ok();
+
+    use_effect(use_reactive!(|user| {
+        let Some(user) = user else {
+            return;
+        };
+        let Some(user) = user else {
+            navigator.replace(Route::Home {});
+            return;
+        };
+        spawn(async move {
+            let date = get_landing_date(user.id, user.landing_date, user.timezone).await;
+            navigator.replace(Route::TimelineList {
+                date,
+                dialog: DialogReference::Idle,
+                sort: SortDirection::default(),
+                focus: EntryFocus::default(),
+            });
+        });
+    }));
+
+    rsx! {
+        p { class: "alert alert-info", "Loading..." }
+    }
+}
+
 #[component]
 pub fn Home() -> Element {
     let navigator = navigator();
@@ -23,21 +123,38 @@ pub fn Home() -> Element {
             if let Some(user) = user {
                 p { class: "text-green-300", "Welcome, {user.full_name}!" }
                 NavButton {
-                    on_click: move |_| {
-                        let new_date = get_date_for_dt(Utc::now());
-                        navigator
-                            .push(Route::TimelineList {
-                                date: new_date,
-                                dialog: DialogReference::Idle,
+                    on_click: {
+                        let user_id = user.id;
+                        let landing_date = user.landing_date;
+                        let timezone = user.timezone;
+                        move |_| {
+                            spawn(async move {
+                                let new_date = get_landing_date(user_id, landing_date, timezone)
+                                    .await;
+                                navigator
+                                    .push(Route::TimelineList {
+                                        date: new_date,
+                                        dialog: DialogReference::Idle,
+                                        sort: SortDirection::default(),
+                                        focus: EntryFocus::default(),
+                                    });
                             });
+                        }
                     },
-                    "Today"
+                    {user.landing_date.map_or("Today", |landing_date| landing_date.as_title())}
                 }
+                TodaysMedications { user_id: user.id }
+                NutritionPanel { user_id: user.id }
+                DurationStatsPanel { user_id: user.id }
+                WeeUrgeLatencyPanel { user_id: user.id }
+                TimeOfDayPanel { user_id: user.id }
             } else {
                 p { class: "text-red-600", "Please log in to continue." }
                 NavButton {
                     on_click: move |_| {
-                        navigator.push(Route::Login {});
+                        navigator.push(Route::Login {
+                            next: String::new(),
+                        });
                     },
                     "Login"
                 }
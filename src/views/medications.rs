@@ -0,0 +1,217 @@
+use std::ops::Deref;
+
+use dioxus::prelude::*;
+use dioxus_fullstack::ServerFnError;
+use dioxus_router::navigator;
+use tap::Pipe;
+
+use crate::{
+    Route,
+    components::{
+        buttons::{ChangeButton, CreateButton},
+        medications::{
+            ActiveDialog, ListDialogReference, MedicationDetails, MedicationDialog, Operation,
+        },
+    },
+    functions::medications::{get_medication_by_id, get_medications},
+    models::{Medication, MedicationId},
+    use_user,
+};
+
+#[component]
+fn EntryRow(medication: Medication, selected: Signal<Option<MedicationId>>) -> Element {
+    let id = medication.id;
+
+    let navigator = navigator();
+    rsx! {
+        tr {
+            class: "hover:bg-gray-500 border-blue-300 mt-2 mb-2 p-2 border-2 w-full sm:w-auto sm:border-none inline-block sm:table-row",
+            onclick: move |_| { selected.set(Some(id)) },
+            td { class: "block sm:table-cell border-blue-300 sm:border-t-2",
+                MedicationDetails { medication }
+            }
+        }
+
+        if selected() == Some(id) {
+            tr {
+                td { class: "block sm:table-cell",
+                    div { class: "flex gap-2",
+                        ChangeButton {
+                            on_click: move |_| {
+                                navigator
+                                    .push(Route::MedicationList {
+                                        dialog: ListDialogReference::Update {
+                                            medication_id: id,
+                                        },
+                                    });
+                            },
+                            "Edit"
+                        }
+                        ChangeButton {
+                            on_click: move |_| {
+                                navigator
+                                    .push(Route::MedicationList {
+                                        dialog: ListDialogReference::Delete {
+                                            medication_id: id,
+                                        },
+                                    });
+                            },
+                            "Delete"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn MedicationList(dialog: ReadSignal<Option<ListDialogReference>>) -> Element {
+    let user = use_user().ok().flatten();
+
+    let Some(user) = user.as_ref() else {
+        return rsx! {
+            p { class: "alert alert-error", "You are not logged in." }
+        };
+    };
+    let user_id = user.id;
+
+    let selected: Signal<Option<MedicationId>> = use_signal(|| None);
+    let mut show_inactive = use_signal(|| false);
+
+    let dialog: Resource<Result<ActiveDialog, ServerFnError>> = use_resource(move || async move {
+        let Some(dialog) = dialog() else {
+            return Ok(ActiveDialog::Idle);
+        };
+        match dialog {
+            ListDialogReference::Create => {
+                ActiveDialog::Change(Operation::Create { user_id }).pipe(Ok)
+            }
+            ListDialogReference::Update { medication_id } => {
+                let medication = get_medication_by_id(medication_id)
+                    .await?
+                    .ok_or(ServerFnError::new("Cannot find medication"))?;
+                ActiveDialog::Change(Operation::Update { medication }).pipe(Ok)
+            }
+            ListDialogReference::Delete { medication_id } => {
+                let medication = get_medication_by_id(medication_id)
+                    .await?
+                    .ok_or(ServerFnError::new("Cannot find medication"))?;
+                ActiveDialog::Delete(medication).pipe(Ok)
+            }
+            ListDialogReference::Idle => Ok(ActiveDialog::Idle),
+        }
+    });
+
+    let navigator = navigator();
+    let mut list: Resource<Result<Vec<Medication>, ServerFnError>> =
+        use_resource(move || async move { get_medications(show_inactive()).await });
+
+    rsx! {
+        div { class: "ml-2 mr-2",
+            div { class: "mb-2",
+                CreateButton {
+                    on_click: move |_| {
+                        navigator
+                            .push(Route::MedicationList {
+                                dialog: ListDialogReference::Create,
+                            });
+                    },
+                    "Create"
+                }
+            }
+
+            div {
+                label {
+                    r#for: "show_inactive",
+                    class: "block mb-2 text-sm font-medium text-gray-900 dark:text-white",
+                    "Show inactive"
+                }
+                input {
+                    r#type: "checkbox",
+                    class: "checkbox",
+                    checked: show_inactive(),
+                    oninput: move |e| {
+                        show_inactive.set(e.checked());
+                    },
+                }
+            }
+        }
+
+        match list.read().deref() {
+            Some(Err(err)) => rsx! {
+                div { class: "alert alert-error",
+                    "Error loading medications: "
+                    {err.to_string()}
+                }
+            },
+            Some(Ok(list)) if list.is_empty() => rsx! {
+                p { class: "alert alert-info", "No entries found." }
+            },
+            Some(Ok(list)) => rsx! {
+                div { class: "ml-2 mr-2 sm:ml-0 sm:mr-0",
+                    table { class: "block sm:table",
+                        thead { class: "hidden sm:table-header-group",
+                            tr {
+                                th { "Medication" }
+                            }
+                        }
+                        tbody { class: "block sm:table-row-group",
+                            for medication in list.iter() {
+                                EntryRow {
+                                    key: "{medication.id.as_inner().to_string()}",
+                                    medication: medication.clone(),
+                                    selected,
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            None => {
+                rsx! {
+                    p { class: "alert alert-info", "Loading..." }
+                }
+            }
+        }
+
+        match dialog.read().deref() {
+            Some(Err(err)) => rsx! {
+                div { class: "alert alert-error",
+                    "Error loading dialog: "
+                    {err.to_string()}
+                }
+            },
+            Some(Ok(dialog)) => rsx! {
+                MedicationDialog {
+                    dialog: dialog.clone(),
+                    on_change: move |_medication: Medication| {
+                        list.restart();
+                        navigator
+                            .push(Route::MedicationList {
+                                dialog: ListDialogReference::Idle,
+                            });
+                    },
+                    on_delete: move |_medication| {
+                        list.restart();
+                        navigator
+                            .push(Route::MedicationList {
+                                dialog: ListDialogReference::Idle,
+                            });
+                    },
+                    on_close: move |()| {
+                        navigator
+                            .push(Route::MedicationList {
+                                dialog: ListDialogReference::Idle,
+                            });
+                    },
+                }
+            },
+            None => {
+                rsx! {
+                    p { class: "alert alert-info", "Loading..." }
+                }
+            }
+        }
+    }
+}
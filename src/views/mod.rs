@@ -1,14 +1,29 @@
 mod home;
-pub use home::Home;
+pub use home::{Home, Landing};
 
 mod timeline;
 pub use timeline::TimelineList;
 
 mod auth;
-pub use auth::{Login, Logout, get_user};
+pub use auth::{Login, LoginError, Logout, get_user};
 
 mod users;
 pub use users::{UserDetail, UserList};
 
 mod consumables;
 pub use consumables::ConsumableList;
+
+mod medications;
+pub use medications::MedicationList;
+
+mod preferences;
+pub use preferences::Preferences;
+
+mod shared;
+pub use shared::SharedTimeline;
+
+mod search;
+pub use search::EntrySearch;
+
+mod scroll;
+pub use scroll::TimelineScroll;
@@ -0,0 +1,1644 @@
+use std::ops::Deref;
+use std::sync::Arc;
+
+use chrono::{NaiveDate, TimeDelta, Utc};
+use dioxus::prelude::*;
+use dioxus_fullstack::ServerFnError;
+use dioxus_router::navigator;
+
+use crate::{
+    Route,
+    dt::{display_date, get_date_for_dt, get_today, get_utc_times_for_date},
+    forms::{
+        ColourButton, EditError, FieldValue, FormSubmitButton, InputBoolean, InputConsumptionType,
+        InputDate, InputDateFormat, InputDistanceUnit, InputGlucoseUnit, InputLandingDate,
+        InputLocale, InputNumber, InputString, InputTextArea, InputTheme, MyForm, Saving,
+        ValidationError, validate_consumption_type, validate_consumption_type_icon, validate_date,
+        validate_date_format, validate_distance_unit, validate_duration_rounding_minutes,
+        validate_glucose_unit, validate_group_window_minutes, validate_landing_date,
+        validate_locale, validate_max_heart_rate, validate_name, validate_palette_colours,
+        validate_share_link_days, validate_theme, validate_timezone, validate_volume_presets,
+        validate_webhook_url,
+    },
+    functions::{
+        api_tokens::{create_api_token, delete_api_token, get_api_tokens},
+        colour_palettes::{
+            create_colour_palette, delete_colour_palette, get_colour_palettes,
+            set_active_colour_palette, update_colour_palette,
+        },
+        consumption_types::{create_consumption_type, delete_consumption_type},
+        entry_templates::delete_entry_template,
+        share_links::{create_share_link, delete_share_link, get_share_links},
+        symptoms::export_symptoms_csv,
+        users::{delete_own_account, update_preferences},
+        webhooks::{create_webhook, delete_webhook, get_webhooks},
+    },
+    models::{
+        ApiScope, ApiToken, ChangeColourPalette, ChangePreferences, ColourPalette, ColourPaletteId,
+        ColourPaletteKind, ConsumptionType, CreatedApiToken, CreatedShareLink, CreatedWebhook,
+        CustomConsumptionType, DateFormat, DistanceUnit, EntryKind, EntryTemplate, GlucoseUnit,
+        LandingDate, Locale, MaybeSet, NewApiToken, NewColourPalette, NewCustomConsumptionType,
+        NewShareLink, NewWebhook, PaletteColour, ShareLink, Theme, User, UserId, Webhook,
+        format_palette_colours,
+    },
+    reload_user, use_timezone, use_user,
+};
+
+#[derive(Debug, Clone)]
+struct Validate {
+    volume_presets: Memo<Result<Vec<i32>, ValidationError>>,
+    group_window_minutes: Memo<Result<Option<i32>, ValidationError>>,
+    locale: Memo<Result<Option<Locale>, ValidationError>>,
+    date_format: Memo<Result<Option<DateFormat>, ValidationError>>,
+    landing_date: Memo<Result<Option<LandingDate>, ValidationError>>,
+    timezone: Memo<Result<Option<chrono_tz::Tz>, ValidationError>>,
+    max_heart_rate: Memo<Result<Option<i32>, ValidationError>>,
+    distance_unit: Memo<Result<Option<DistanceUnit>, ValidationError>>,
+    duration_rounding_minutes: Memo<Result<Option<i32>, ValidationError>>,
+    glucose_unit: Memo<Result<Option<GlucoseUnit>, ValidationError>>,
+    theme: Memo<Result<Option<Theme>, ValidationError>>,
+}
+
+async fn do_save(validate: &Validate) -> Result<User, EditError> {
+    let volume_presets = validate.volume_presets.read().clone()?;
+    let group_window_minutes = validate.group_window_minutes.read().clone()?;
+    let locale = validate.locale.read().clone()?;
+    let date_format = validate.date_format.read().clone()?;
+    let landing_date = validate.landing_date.read().clone()?;
+    let timezone = validate.timezone.read().clone()?;
+    let max_heart_rate = validate.max_heart_rate.read().clone()?;
+    let distance_unit = validate.distance_unit.read().clone()?;
+    let duration_rounding_minutes = validate.duration_rounding_minutes.read().clone()?;
+    let glucose_unit = validate.glucose_unit.read().clone()?;
+    let theme = validate.theme.read().clone()?;
+
+    let changes = ChangePreferences {
+        volume_presets: MaybeSet::Set(volume_presets),
+        group_window_minutes: MaybeSet::Set(group_window_minutes),
+        locale: MaybeSet::Set(locale),
+        date_format: MaybeSet::Set(date_format),
+        landing_date: MaybeSet::Set(landing_date),
+        timezone: MaybeSet::Set(timezone),
+        max_heart_rate: MaybeSet::Set(max_heart_rate),
+        distance_unit: MaybeSet::Set(distance_unit),
+        duration_rounding_minutes: MaybeSet::Set(duration_rounding_minutes),
+        glucose_unit: MaybeSet::Set(glucose_unit),
+        theme: MaybeSet::Set(theme),
+    };
+    update_preferences(changes).await.map_err(EditError::Server)
+}
+
+#[component]
+fn PreferencesUpdate(user: Arc<User>) -> Element {
+    let volume_presets = use_signal(|| {
+        user.volume_presets
+            .iter()
+            .map(|preset| preset.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    });
+    let group_window_minutes = use_signal(|| {
+        user.group_window_minutes
+            .map(|minutes| minutes.to_string())
+            .unwrap_or_default()
+    });
+    let locale = use_signal(|| user.locale);
+    let date_format = use_signal(|| user.date_format);
+    let landing_date = use_signal(|| user.landing_date);
+    let timezone = use_signal(|| {
+        user.timezone
+            .map(|timezone| timezone.name().to_string())
+            .unwrap_or_default()
+    });
+    let max_heart_rate = use_signal(|| {
+        user.max_heart_rate
+            .map(|max_heart_rate| max_heart_rate.to_string())
+            .unwrap_or_default()
+    });
+    let distance_unit = use_signal(|| user.distance_unit);
+    let duration_rounding_minutes = use_signal(|| {
+        user.duration_rounding_minutes
+            .map(|minutes| minutes.to_string())
+            .unwrap_or_default()
+    });
+    let glucose_unit = use_signal(|| user.glucose_unit);
+    let theme = use_signal(|| user.theme);
+
+    let validate = Validate {
+        volume_presets: use_memo(move || validate_volume_presets(&volume_presets())),
+        group_window_minutes: use_memo(move || {
+            validate_group_window_minutes(&group_window_minutes())
+        }),
+        locale: use_memo(move || validate_locale(locale())),
+        date_format: use_memo(move || validate_date_format(date_format())),
+        landing_date: use_memo(move || validate_landing_date(landing_date())),
+        timezone: use_memo(move || validate_timezone(&timezone())),
+        max_heart_rate: use_memo(move || validate_max_heart_rate(&max_heart_rate())),
+        distance_unit: use_memo(move || validate_distance_unit(distance_unit())),
+        duration_rounding_minutes: use_memo(move || {
+            validate_duration_rounding_minutes(&duration_rounding_minutes())
+        }),
+        glucose_unit: use_memo(move || validate_glucose_unit(glucose_unit())),
+        theme: use_memo(move || validate_theme(theme())),
+    };
+
+    let mut saving = use_signal(|| Saving::No);
+
+    // disable form while waiting for response
+    let disabled = use_memo(move || saving.read().is_saving());
+    let disabled_save = use_memo(move || {
+        validate.volume_presets.read().is_err()
+            || validate.group_window_minutes.read().is_err()
+            || validate.locale.read().is_err()
+            || validate.date_format.read().is_err()
+            || validate.landing_date.read().is_err()
+            || validate.timezone.read().is_err()
+            || validate.max_heart_rate.read().is_err()
+            || validate.distance_unit.read().is_err()
+            || validate.duration_rounding_minutes.read().is_err()
+            || validate.glucose_unit.read().is_err()
+            || validate.theme.read().is_err()
+            || disabled()
+    });
+
+    let validate_clone = validate.clone();
+    let on_save = use_callback(move |()| {
+        let validate = validate_clone.clone();
+        spawn(async move {
+            saving.set(Saving::Yes);
+
+            match do_save(&validate).await {
+                Ok(_user) => {
+                    saving.set(Saving::Finished(Ok(())));
+                    reload_user();
+                }
+                Err(err) => saving.set(Saving::Finished(Err(err))),
+            }
+        });
+    });
+
+    rsx! {
+        MyForm {
+            InputString {
+                id: "volume_presets",
+                label: "Volume presets (ml, comma separated)",
+                value: volume_presets,
+                validate: validate.volume_presets,
+                disabled,
+            }
+            InputString {
+                id: "group_window_minutes",
+                label: "Group similar timeline entries within (minutes, blank to disable)",
+                value: group_window_minutes,
+                validate: validate.group_window_minutes,
+                disabled,
+            }
+            InputLocale {
+                id: "locale",
+                label: "Language",
+                value: locale,
+                validate: validate.locale,
+                disabled,
+            }
+            InputDateFormat {
+                id: "date_format",
+                label: "Date format",
+                value: date_format,
+                validate: validate.date_format,
+                disabled,
+            }
+            InputLandingDate {
+                id: "landing_date",
+                label: "Open the timeline on",
+                value: landing_date,
+                validate: validate.landing_date,
+                disabled,
+            }
+            InputString {
+                id: "timezone",
+                label: "Timezone (e.g. Australia/Melbourne, blank for this device's timezone)",
+                value: timezone,
+                validate: validate.timezone,
+                disabled,
+            }
+            InputString {
+                id: "max_heart_rate",
+                label: "Max heart rate (bpm, for exercise heart rate zones)",
+                value: max_heart_rate,
+                validate: validate.max_heart_rate,
+                disabled,
+            }
+            InputDistanceUnit {
+                id: "distance_unit",
+                label: "Distance unit",
+                value: distance_unit,
+                validate: validate.distance_unit,
+                disabled,
+            }
+            InputString {
+                id: "duration_rounding_minutes",
+                label: "Round durations to the nearest minute from (minutes, blank for 60)",
+                value: duration_rounding_minutes,
+                validate: validate.duration_rounding_minutes,
+                disabled,
+            }
+            InputGlucoseUnit {
+                id: "glucose_unit",
+                label: "Blood glucose unit",
+                value: glucose_unit,
+                validate: validate.glucose_unit,
+                disabled,
+            }
+            InputTheme {
+                id: "theme",
+                label: "Theme",
+                value: theme,
+                validate: validate.theme,
+                disabled,
+            }
+            match &*saving.read() {
+                Saving::Yes => rsx! {
+                    div { class: "alert alert-info", "Saving..." }
+                },
+                Saving::Finished(Ok(())) => rsx! {
+                    div { class: "alert alert-success", "Saved!" }
+                },
+                Saving::Finished(Err(err)) => rsx! {
+                    div { class: "alert alert-error",
+                        "Error: "
+                        {err.to_string()}
+                    }
+                },
+                Saving::No => rsx! {},
+            }
+            FormSubmitButton { disabled: disabled_save, title: "Save", on_save }
+        }
+    }
+}
+
+#[component]
+fn DeleteAccount(user: Arc<User>) -> Element {
+    let confirm_text = use_signal(String::new);
+
+    let username = user.username.clone();
+    let validate = use_memo(move || {
+        if confirm_text().trim() == username {
+            Ok(())
+        } else {
+            Err(ValidationError("Type your username to confirm".to_string()))
+        }
+    });
+
+    let mut saving = use_signal(|| Saving::No);
+
+    let disabled = use_memo(move || saving.read().is_saving());
+    let disabled_save = use_memo(move || validate.read().is_err() || disabled());
+
+    let on_save = use_callback(move |()| {
+        spawn(async move {
+            saving.set(Saving::Yes);
+
+            match delete_own_account(confirm_text()).await {
+                Ok(()) => {
+                    saving.set(Saving::Finished(Ok(())));
+                    reload_user();
+                    navigator().push(Route::Home {});
+                }
+                Err(err) => saving.set(Saving::Finished(Err(EditError::Server(err)))),
+            }
+        });
+    });
+
+    rsx! {
+        MyForm {
+            p { class: "mb-4",
+                "This permanently deletes your account and every entry you have recorded. "
+                "It cannot be undone."
+            }
+            InputString {
+                id: "delete_account_confirm",
+                label: "Type your username to confirm",
+                value: confirm_text,
+                validate,
+                disabled,
+            }
+            match &*saving.read() {
+                Saving::Yes => rsx! {
+                    div { class: "alert alert-info", "Deleting..." }
+                },
+                Saving::Finished(Ok(())) => rsx! {
+                    div { class: "alert alert-success", "Account deleted." }
+                },
+                Saving::Finished(Err(err)) => rsx! {
+                    div { class: "alert alert-error",
+                        "Error: "
+                        {err.to_string()}
+                    }
+                },
+                Saving::No => rsx! {},
+            }
+            FormSubmitButton {
+                disabled: disabled_save,
+                title: "Delete my account",
+                on_save,
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ValidateShareLink {
+    from: Memo<Result<NaiveDate, ValidationError>>,
+    to: Memo<Result<NaiveDate, ValidationError>>,
+    expires_days: Memo<Result<i32, ValidationError>>,
+}
+
+async fn do_create_share_link(
+    user: &User,
+    validate: &ValidateShareLink,
+) -> Result<CreatedShareLink, EditError> {
+    let from = validate.from.read().clone()?;
+    let to = validate.to.read().clone()?;
+    let expires_days = validate.expires_days.read().clone()?;
+
+    let (start, _) = get_utc_times_for_date(from, user.timezone).map_err(EditError::Server)?;
+    let (_, end) = get_utc_times_for_date(to, user.timezone).map_err(EditError::Server)?;
+
+    let new_share_link = NewShareLink {
+        user_id: user.id,
+        start,
+        end,
+        expires_at: Utc::now() + TimeDelta::days(expires_days.into()),
+    };
+    create_share_link(new_share_link)
+        .await
+        .map_err(EditError::Server)
+}
+
+#[component]
+fn ShareLinkRow(link: ShareLink, on_revoke: Callback<()>) -> Element {
+    let mut error: Signal<Option<ServerFnError>> = use_signal(|| None);
+    let timezone = use_timezone();
+    let first = get_date_for_dt(link.start, timezone);
+    let last = get_date_for_dt(link.end - TimeDelta::seconds(1), timezone);
+    let id = link.id;
+
+    rsx! {
+        li { class: "mb-2",
+            div {
+                {display_date(first)}
+                " – "
+                {display_date(last)}
+            }
+            if link.is_expired(Utc::now()) {
+                div { class: "text-warning", "Expired" }
+            } else {
+                div { class: "text-sm opacity-70",
+                    "Expires on "
+                    {display_date(get_date_for_dt(link.expires_at, timezone))}
+                }
+            }
+            button {
+                r#type: "button",
+                class: "btn btn-error btn-xs",
+                onclick: move |_| {
+                    spawn(async move {
+                        match delete_share_link(id).await {
+                            Ok(()) => on_revoke(()),
+                            Err(err) => error.set(Some(err)),
+                        }
+                    });
+                },
+                "Revoke"
+            }
+            if let Some(err) = error() {
+                div { class: "alert alert-error",
+                    "Error: "
+                    {err.to_string()}
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn ShareLinks(user: Arc<User>) -> Element {
+    let user_id = user.id;
+    let mut links: Resource<Result<Vec<ShareLink>, ServerFnError>> =
+        use_resource(move || async move { get_share_links(user_id).await });
+
+    let today = get_today(user.timezone);
+    let from = use_signal(|| (today - TimeDelta::days(6)).as_raw());
+    let to = use_signal(|| today.as_raw());
+    let expires_days = use_signal(|| "14".to_string());
+
+    let validate = ValidateShareLink {
+        from: use_memo(move || validate_date(&from())),
+        to: use_memo(move || {
+            let to = validate_date(&to())?;
+            match validate_date(&from()) {
+                Ok(from) if to < from => Err(ValidationError(
+                    "End date must not be before start date".to_string(),
+                )),
+                _ => Ok(to),
+            }
+        }),
+        expires_days: use_memo(move || validate_share_link_days(&expires_days())),
+    };
+
+    let mut saving = use_signal(|| Saving::No);
+    let mut path: Signal<Option<String>> = use_signal(|| None);
+
+    let disabled = use_memo(move || saving.read().is_saving());
+    let disabled_save = use_memo(move || {
+        validate.from.read().is_err()
+            || validate.to.read().is_err()
+            || validate.expires_days.read().is_err()
+            || disabled()
+    });
+
+    let validate_clone = validate.clone();
+    let on_save = use_callback(move |()| {
+        let validate = validate_clone.clone();
+        let user = user.clone();
+        spawn(async move {
+            saving.set(Saving::Yes);
+            path.set(None);
+
+            match do_create_share_link(&user, &validate).await {
+                Ok(created) => {
+                    saving.set(Saving::Finished(Ok(())));
+                    path.set(Some(created.path()));
+                    links.restart();
+                }
+                Err(err) => saving.set(Saving::Finished(Err(err))),
+            }
+        });
+    });
+
+    rsx! {
+        p { class: "mb-4",
+            "Create a read only link to part of your timeline, for example to show your doctor. "
+            "Anyone with the link can view those dates until it expires or you revoke it."
+        }
+        match links.read().deref() {
+            Some(Ok(list)) if !list.is_empty() => rsx! {
+                ul { class: "mb-4",
+                    for link in list {
+                        ShareLinkRow {
+                            key: "{link.id}",
+                            link: link.clone(),
+                            on_revoke: move |()| links.restart(),
+                        }
+                    }
+                }
+            },
+            Some(Ok(_)) => rsx! {},
+            Some(Err(err)) => rsx! {
+                div { class: "alert alert-error",
+                    "Error loading share links: "
+                    {err.to_string()}
+                }
+            },
+            None => rsx! {
+                p { class: "alert alert-info", "Loading..." }
+            },
+        }
+        MyForm {
+            InputDate {
+                id: "share_from",
+                label: "From",
+                value: from,
+                validate: validate.from,
+                disabled,
+            }
+            InputDate {
+                id: "share_to",
+                label: "To",
+                value: to,
+                validate: validate.to,
+                disabled,
+            }
+            InputNumber {
+                id: "share_expires_days",
+                label: "Expires after (days)",
+                value: expires_days,
+                validate: validate.expires_days,
+                disabled,
+            }
+            match &*saving.read() {
+                Saving::Yes => rsx! {
+                    div { class: "alert alert-info", "Creating..." }
+                },
+                Saving::Finished(Ok(())) => rsx! {
+                    div { class: "alert alert-success",
+                        if let Some(path) = path() {
+                            div {
+                                "Copy this link now, it will not be shown again: "
+                                a {
+                                    class: "link break-all",
+                                    href: "{path}",
+                                    target: "_blank",
+                                    "{path}"
+                                }
+                            }
+                        }
+                    }
+                },
+                Saving::Finished(Err(err)) => rsx! {
+                    div { class: "alert alert-error",
+                        "Error: "
+                        {err.to_string()}
+                    }
+                },
+                Saving::No => rsx! {},
+            }
+            FormSubmitButton {
+                disabled: disabled_save,
+                title: "Create share link",
+                on_save,
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ValidateSymptomsCsv {
+    from: Memo<Result<NaiveDate, ValidationError>>,
+    to: Memo<Result<NaiveDate, ValidationError>>,
+}
+
+async fn do_export_symptoms_csv(
+    user: &User,
+    validate: &ValidateSymptomsCsv,
+) -> Result<(), EditError> {
+    let from = validate.from.read().clone()?;
+    let to = validate.to.read().clone()?;
+
+    let (start, _) = get_utc_times_for_date(from, user.timezone).map_err(EditError::Server)?;
+    let (_, end) = get_utc_times_for_date(to, user.timezone).map_err(EditError::Server)?;
+
+    let csv = export_symptoms_csv(user.id, start, end)
+        .await
+        .map_err(EditError::Server)?;
+
+    let eval = document::eval(
+        r#"
+        const [filename, text] = await dioxus.recv();
+        const url = URL.createObjectURL(new Blob([text], { type: "text/csv" }));
+        const link = document.createElement("a");
+        link.href = url;
+        link.download = filename;
+        link.click();
+        URL.revokeObjectURL(url);
+        "#,
+    );
+    let filename = format!("symptoms-{from}-{to}.csv");
+    eval.send((filename, csv))
+        .map_err(|err| EditError::Server(ServerFnError::new(err.to_string())))
+}
+
+#[component]
+fn SymptomsCsvExport(user: Arc<User>) -> Element {
+    let today = get_today(user.timezone);
+    let from = use_signal(|| (today - TimeDelta::days(29)).as_raw());
+    let to = use_signal(|| today.as_raw());
+
+    let validate = ValidateSymptomsCsv {
+        from: use_memo(move || validate_date(&from())),
+        to: use_memo(move || {
+            let to = validate_date(&to())?;
+            match validate_date(&from()) {
+                Ok(from) if to < from => Err(ValidationError(
+                    "End date must not be before start date".to_string(),
+                )),
+                _ => Ok(to),
+            }
+        }),
+    };
+
+    let mut saving = use_signal(|| Saving::No);
+
+    let disabled = use_memo(move || saving.read().is_saving());
+    let disabled_save = use_memo(move || {
+        validate.from.read().is_err() || validate.to.read().is_err() || disabled()
+    });
+
+    let validate_clone = validate.clone();
+    let on_save = use_callback(move |()| {
+        let validate = validate_clone.clone();
+        let user = user.clone();
+        spawn(async move {
+            saving.set(Saving::Yes);
+            let result = do_export_symptoms_csv(&user, &validate).await;
+            saving.set(Saving::Finished(result));
+        });
+    });
+
+    rsx! {
+        p { class: "mb-4",
+            "Download symptoms as a CSV file with a column per symptom, for spreadsheets."
+        }
+        MyForm {
+            InputDate {
+                id: "symptoms_csv_from",
+                label: "From",
+                value: from,
+                validate: validate.from,
+                disabled,
+            }
+            InputDate {
+                id: "symptoms_csv_to",
+                label: "To",
+                value: to,
+                validate: validate.to,
+                disabled,
+            }
+            match &*saving.read() {
+                Saving::Yes => rsx! {
+                    div { class: "alert alert-info", "Exporting..." }
+                },
+                Saving::Finished(Err(err)) => rsx! {
+                    div { class: "alert alert-error",
+                        "Error: "
+                        {err.to_string()}
+                    }
+                },
+                Saving::Finished(Ok(())) | Saving::No => rsx! {},
+            }
+            FormSubmitButton {
+                disabled: disabled_save,
+                title: "Download symptoms CSV",
+                on_save,
+            }
+        }
+    }
+}
+
+async fn do_create_api_token(
+    user_id: UserId,
+    validate: Memo<Result<String, ValidationError>>,
+    read_write: bool,
+) -> Result<CreatedApiToken, EditError> {
+    let name = validate.read().clone()?;
+    let scope = if read_write {
+        ApiScope::ReadWrite
+    } else {
+        ApiScope::Read
+    };
+
+    create_api_token(NewApiToken {
+        user_id,
+        name,
+        scope,
+    })
+    .await
+    .map_err(EditError::Server)
+}
+
+#[component]
+fn ApiTokenRow(token: ApiToken, on_revoke: Callback<()>) -> Element {
+    let mut error: Signal<Option<ServerFnError>> = use_signal(|| None);
+    let timezone = use_timezone();
+    let id = token.id;
+
+    rsx! {
+        li { class: "mb-2",
+            div { class: "font-bold", "{token.name}" }
+            div { class: "text-sm opacity-70",
+                {token.scope.as_title()}
+                ", created "
+                {display_date(get_date_for_dt(token.created_at, timezone))}
+                ", "
+                match token.last_used_at {
+                    Some(last_used_at) => rsx! {
+                        "last used "
+                        {display_date(get_date_for_dt(last_used_at, timezone))}
+                    },
+                    None => rsx! { "never used" },
+                }
+            }
+            button {
+                r#type: "button",
+                class: "btn btn-error btn-xs",
+                onclick: move |_| {
+                    spawn(async move {
+                        match delete_api_token(id).await {
+                            Ok(()) => on_revoke(()),
+                            Err(err) => error.set(Some(err)),
+                        }
+                    });
+                },
+                "Revoke"
+            }
+            if let Some(err) = error() {
+                div { class: "alert alert-error",
+                    "Error: "
+                    {err.to_string()}
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn ApiTokens(user: Arc<User>) -> Element {
+    let user_id = user.id;
+    let mut tokens: Resource<Result<Vec<ApiToken>, ServerFnError>> =
+        use_resource(move || async move { get_api_tokens(user_id).await });
+
+    let name = use_signal(String::new);
+    let read_write = use_signal(|| false);
+    let validate = use_memo(move || validate_name(&name()));
+
+    let mut saving = use_signal(|| Saving::No);
+    let mut secret: Signal<Option<String>> = use_signal(|| None);
+
+    let disabled = use_memo(move || saving.read().is_saving());
+    let disabled_save = use_memo(move || validate.read().is_err() || disabled());
+
+    let on_save = use_callback(move |()| {
+        spawn(async move {
+            saving.set(Saving::Yes);
+            secret.set(None);
+
+            let result = do_create_api_token(user_id, validate, read_write()).await;
+
+            match result {
+                Ok(created) => {
+                    saving.set(Saving::Finished(Ok(())));
+                    secret.set(Some(created.secret));
+                    tokens.restart();
+                }
+                Err(err) => saving.set(Saving::Finished(Err(err))),
+            }
+        });
+    });
+
+    rsx! {
+        p { class: "mb-4",
+            "Personal access tokens let other programs, such as Home Assistant, use the "
+            code { "/api/v1" }
+            " endpoints on your behalf. Send the token in an "
+            code { "Authorization: Bearer" }
+            " header."
+        }
+        match tokens.read().deref() {
+            Some(Ok(list)) if !list.is_empty() => rsx! {
+                ul { class: "mb-4",
+                    for token in list {
+                        ApiTokenRow {
+                            key: "{token.id}",
+                            token: token.clone(),
+                            on_revoke: move |()| tokens.restart(),
+                        }
+                    }
+                }
+            },
+            Some(Ok(_)) => rsx! {},
+            Some(Err(err)) => rsx! {
+                div { class: "alert alert-error",
+                    "Error loading tokens: "
+                    {err.to_string()}
+                }
+            },
+            None => rsx! {
+                p { class: "alert alert-info", "Loading..." }
+            },
+        }
+        MyForm {
+            InputString {
+                id: "api_token_name",
+                label: "Token name",
+                value: name,
+                validate,
+                disabled,
+            }
+            InputBoolean {
+                id: "api_token_read_write",
+                label: "Allow changes",
+                value: read_write,
+                disabled,
+            }
+            match &*saving.read() {
+                Saving::Yes => rsx! {
+                    div { class: "alert alert-info", "Creating..." }
+                },
+                Saving::Finished(Ok(())) => rsx! {
+                    div { class: "alert alert-success",
+                        if let Some(secret) = secret() {
+                            div {
+                                "Copy this token now, it will not be shown again: "
+                                code { class: "break-all", "{secret}" }
+                            }
+                        }
+                    }
+                },
+                Saving::Finished(Err(err)) => rsx! {
+                    div { class: "alert alert-error",
+                        "Error: "
+                        {err.to_string()}
+                    }
+                },
+                Saving::No => rsx! {},
+            }
+            FormSubmitButton {
+                disabled: disabled_save,
+                title: "Create token",
+                on_save,
+            }
+        }
+    }
+}
+
+#[component]
+fn PalettePreview(colours: Vec<PaletteColour>) -> Element {
+    rsx! {
+        div {
+            for colour in colours {
+                ColourButton {
+                    colour: colour.hsv(),
+                    name: colour.name.clone(),
+                    on_click: move |_| {},
+                    selected: false,
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ValidatePalette {
+    name: Memo<Result<String, ValidationError>>,
+    colours: Memo<Result<Vec<PaletteColour>, ValidationError>>,
+}
+
+async fn do_save_colour_palette(
+    user_id: UserId,
+    kind: ColourPaletteKind,
+    id: Option<ColourPaletteId>,
+    validate: &ValidatePalette,
+) -> Result<ColourPalette, EditError> {
+    let name = validate.name.read().clone()?;
+    let colours = validate.colours.read().clone()?;
+
+    match id {
+        Some(id) => {
+            let changes = ChangeColourPalette {
+                name: MaybeSet::Set(name),
+                colours: MaybeSet::Set(colours),
+            };
+            update_colour_palette(id, changes).await
+        }
+        None => {
+            let palette = NewColourPalette {
+                user_id,
+                name,
+                kind,
+                colours,
+            };
+            create_colour_palette(palette).await
+        }
+    }
+    .map_err(EditError::Server)
+}
+
+#[component]
+fn ColourPaletteForm(
+    user_id: UserId,
+    kind: ColourPaletteKind,
+    palette: Option<ColourPalette>,
+    on_saved: Callback<()>,
+) -> Element {
+    let id = palette.as_ref().map(|palette| palette.id);
+    let name = use_signal(|| {
+        palette
+            .as_ref()
+            .map(|palette| palette.name.clone())
+            .unwrap_or_default()
+    });
+    let colours = use_signal(|| {
+        let colours = palette
+            .as_ref()
+            .map(|palette| palette.colours.clone())
+            .unwrap_or_else(|| kind.default_colours());
+        format_palette_colours(&colours)
+    });
+
+    let validate = ValidatePalette {
+        name: use_memo(move || validate_name(&name())),
+        colours: use_memo(move || validate_palette_colours(&colours())),
+    };
+
+    let mut saving = use_signal(|| Saving::No);
+
+    let disabled = use_memo(move || saving.read().is_saving());
+    let disabled_save = use_memo(move || {
+        validate.name.read().is_err() || validate.colours.read().is_err() || disabled()
+    });
+
+    let validate_clone = validate.clone();
+    let on_save = use_callback(move |()| {
+        let validate = validate_clone.clone();
+        spawn(async move {
+            saving.set(Saving::Yes);
+
+            let result = do_save_colour_palette(user_id, kind, id, &validate).await;
+
+            match result {
+                Ok(_) => {
+                    saving.set(Saving::Finished(Ok(())));
+                    on_saved(());
+                }
+                Err(err) => saving.set(Saving::Finished(Err(err))),
+            }
+        });
+    });
+
+    rsx! {
+        MyForm {
+            InputString {
+                id: "colour_palette_name",
+                label: "Palette name",
+                value: name,
+                validate: validate.name,
+                disabled,
+            }
+            InputTextArea {
+                id: "colour_palette_colours",
+                label: "Colours, one per line as name: hue, saturation, value",
+                value: colours,
+                validate: validate.colours,
+                disabled,
+            }
+            if let Ok(colours) = validate.colours.read().clone() {
+                PalettePreview { colours }
+            }
+            match &*saving.read() {
+                Saving::Yes => rsx! {
+                    div { class: "alert alert-info", "Saving..." }
+                },
+                Saving::Finished(Ok(())) => rsx! {
+                    div { class: "alert alert-success", "Saved!" }
+                },
+                Saving::Finished(Err(err)) => rsx! {
+                    div { class: "alert alert-error",
+                        "Error: "
+                        {err.to_string()}
+                    }
+                },
+                Saving::No => rsx! {},
+            }
+            FormSubmitButton {
+                disabled: disabled_save,
+                title: if id.is_some() { "Save palette" } else { "Create palette" },
+                on_save,
+            }
+        }
+    }
+}
+
+#[component]
+fn ColourPaletteRow(user_id: UserId, palette: ColourPalette, on_change: Callback<()>) -> Element {
+    let mut error: Signal<Option<ServerFnError>> = use_signal(|| None);
+    let mut editing = use_signal(|| false);
+    let id = palette.id;
+    let kind = palette.kind;
+
+    rsx! {
+        li { class: "mb-4",
+            div { class: "font-bold",
+                "{palette.name}"
+                if palette.is_active {
+                    span { class: "badge badge-success ml-2", "Active" }
+                }
+            }
+            PalettePreview { colours: palette.colours.clone() }
+            div { class: "flex gap-2",
+                if !palette.is_active {
+                    button {
+                        r#type: "button",
+                        class: "btn btn-primary btn-xs",
+                        onclick: move |_| {
+                            spawn(async move {
+                                match set_active_colour_palette(kind, Some(id)).await {
+                                    Ok(()) => on_change(()),
+                                    Err(err) => error.set(Some(err)),
+                                }
+                            });
+                        },
+                        "Use"
+                    }
+                }
+                button {
+                    r#type: "button",
+                    class: "btn btn-secondary btn-xs",
+                    onclick: move |_| editing.set(!editing()),
+                    if editing() { "Close" } else { "Edit" }
+                }
+                button {
+                    r#type: "button",
+                    class: "btn btn-error btn-xs",
+                    onclick: move |_| {
+                        spawn(async move {
+                            match delete_colour_palette(id).await {
+                                Ok(()) => on_change(()),
+                                Err(err) => error.set(Some(err)),
+                            }
+                        });
+                    },
+                    "Delete"
+                }
+            }
+            if editing() {
+                ColourPaletteForm {
+                    user_id,
+                    kind,
+                    palette: Some(palette.clone()),
+                    on_saved: move |()| {
+                        editing.set(false);
+                        on_change(());
+                    },
+                }
+            }
+            if let Some(err) = error() {
+                div { class: "alert alert-error",
+                    "Error: "
+                    {err.to_string()}
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn ColourPalettes(user: Arc<User>, kind: ColourPaletteKind) -> Element {
+    let user_id = user.id;
+    let mut palettes: Resource<Result<Vec<ColourPalette>, ServerFnError>> =
+        use_resource(move || async move { get_colour_palettes(user_id).await });
+    let mut error: Signal<Option<ServerFnError>> = use_signal(|| None);
+
+    // Remount the create form after each save so it starts empty again.
+    let mut form_key = use_signal(|| 0);
+
+    rsx! {
+        h3 { class: "text-lg font-bold mb-2", "{kind.as_title()} colours" }
+        match palettes.read().deref() {
+            Some(Ok(list)) => {
+                let list: Vec<ColourPalette> = list
+                    .iter()
+                    .filter(|palette| palette.kind == kind)
+                    .cloned()
+                    .collect();
+                let using_default = !list.iter().any(|palette| palette.is_active);
+                rsx! {
+                    ul { class: "mb-4",
+                        li { class: "mb-4",
+                            div { class: "font-bold",
+                                "Default"
+                                if using_default {
+                                    span { class: "badge badge-success ml-2", "Active" }
+                                }
+                            }
+                            PalettePreview { colours: kind.default_colours() }
+                            if !using_default {
+                                button {
+                                    r#type: "button",
+                                    class: "btn btn-primary btn-xs",
+                                    onclick: move |_| {
+                                        spawn(async move {
+                                            match set_active_colour_palette(kind, None).await {
+                                                Ok(()) => palettes.restart(),
+                                                Err(err) => error.set(Some(err)),
+                                            }
+                                        });
+                                    },
+                                    "Use"
+                                }
+                            }
+                        }
+                        for palette in list {
+                            ColourPaletteRow {
+                                key: "{palette.id}",
+                                user_id,
+                                palette: palette.clone(),
+                                on_change: move |()| palettes.restart(),
+                            }
+                        }
+                    }
+                }
+            }
+            Some(Err(err)) => rsx! {
+                div { class: "alert alert-error",
+                    "Error loading palettes: "
+                    {err.to_string()}
+                }
+            },
+            None => rsx! {
+                p { class: "alert alert-info", "Loading..." }
+            },
+        }
+        if let Some(err) = error() {
+            div { class: "alert alert-error",
+                "Error: "
+                {err.to_string()}
+            }
+        }
+        details { class: "mb-4",
+            summary { class: "cursor-pointer", "New {kind.as_id()} palette" }
+            ColourPaletteForm {
+                key: "{form_key}",
+                user_id,
+                kind,
+                palette: None,
+                on_saved: move |()| {
+                    form_key.set(form_key() + 1);
+                    palettes.restart();
+                },
+            }
+        }
+    }
+}
+
+async fn do_create_consumption_type(
+    user_id: UserId,
+    name: Memo<Result<String, ValidationError>>,
+    icon: Memo<Result<String, ValidationError>>,
+    base_type: Memo<Result<ConsumptionType, ValidationError>>,
+) -> Result<CustomConsumptionType, EditError> {
+    let name = name.read().clone()?;
+    let icon = icon.read().clone()?;
+    let base_type = base_type.read().clone()?;
+
+    create_consumption_type(NewCustomConsumptionType {
+        user_id,
+        name,
+        icon,
+        base_type,
+    })
+    .await
+    .map_err(EditError::Server)
+}
+
+#[component]
+fn ConsumptionTypeRow(consumption_type: CustomConsumptionType, on_delete: Callback<()>) -> Element {
+    let mut error: Signal<Option<ServerFnError>> = use_signal(|| None);
+    let id = consumption_type.id;
+
+    rsx! {
+        li { class: "mb-2",
+            div { class: "font-bold",
+                span { class: "mr-2", "{consumption_type.icon}" }
+                "{consumption_type.name}"
+            }
+            div { class: "text-sm opacity-70",
+                "Treated as "
+                {consumption_type.base_type.as_title()}
+            }
+            button {
+                r#type: "button",
+                class: "btn btn-error btn-xs",
+                onclick: move |_| {
+                    spawn(async move {
+                        match delete_consumption_type(id).await {
+                            Ok(()) => on_delete(()),
+                            Err(err) => error.set(Some(err)),
+                        }
+                    });
+                },
+                "Delete"
+            }
+            if let Some(err) = error() {
+                div { class: "alert alert-error",
+                    "Error: "
+                    {err.to_string()}
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn ConsumptionTypes(user: Arc<User>) -> Element {
+    let user_id = user.id;
+    let mut consumption_types: Resource<Result<Vec<CustomConsumptionType>, ServerFnError>> =
+        use_context();
+
+    let mut name = use_signal(String::new);
+    let mut icon = use_signal(String::new);
+    let mut base_type: Signal<Option<ConsumptionType>> = use_signal(|| None);
+    let name_validate = use_memo(move || validate_name(&name()));
+    let icon_validate = use_memo(move || validate_consumption_type_icon(&icon()));
+    let base_type_validate = use_memo(move || validate_consumption_type(base_type()));
+
+    let mut saving = use_signal(|| Saving::No);
+
+    let disabled = use_memo(move || saving.read().is_saving());
+    let disabled_save = use_memo(move || {
+        name_validate.read().is_err()
+            || icon_validate.read().is_err()
+            || base_type_validate.read().is_err()
+            || disabled()
+    });
+
+    let on_save = use_callback(move |()| {
+        spawn(async move {
+            saving.set(Saving::Yes);
+
+            let result = do_create_consumption_type(
+                user_id,
+                name_validate,
+                icon_validate,
+                base_type_validate,
+            )
+            .await;
+
+            match result {
+                Ok(_consumption_type) => {
+                    saving.set(Saving::Finished(Ok(())));
+                    name.set(String::new());
+                    icon.set(String::new());
+                    base_type.set(None);
+                    consumption_types.restart();
+                }
+                Err(err) => saving.set(Saving::Finished(Err(err))),
+            }
+        });
+    });
+
+    rsx! {
+        p { class: "mb-4",
+            "Add your own ways of consuming things. Each one is treated like its base type, "
+            "for example when checking a consumable suits the consumption."
+        }
+        match consumption_types.read().deref() {
+            Some(Ok(list)) if !list.is_empty() => rsx! {
+                ul { class: "mb-4",
+                    for consumption_type in list {
+                        ConsumptionTypeRow {
+                            key: "{consumption_type.id}",
+                            consumption_type: consumption_type.clone(),
+                            on_delete: move |()| consumption_types.restart(),
+                        }
+                    }
+                }
+            },
+            Some(Ok(_)) => rsx! {},
+            Some(Err(err)) => rsx! {
+                div { class: "alert alert-error",
+                    "Error loading consumption types: "
+                    {err.to_string()}
+                }
+            },
+            None => rsx! {
+                p { class: "alert alert-info", "Loading..." }
+            },
+        }
+        MyForm {
+            InputString {
+                id: "consumption_type_name",
+                label: "Name",
+                value: name,
+                validate: name_validate,
+                disabled,
+            }
+            InputString {
+                id: "consumption_type_icon",
+                label: "Icon",
+                value: icon,
+                validate: icon_validate,
+                disabled,
+            }
+            InputConsumptionType {
+                id: "consumption_type_base_type",
+                label: "Base type",
+                value: base_type,
+                validate: base_type_validate,
+                disabled,
+            }
+            match &*saving.read() {
+                Saving::Yes => rsx! {
+                    div { class: "alert alert-info", "Creating..." }
+                },
+                Saving::Finished(Ok(())) => rsx! {
+                    div { class: "alert alert-success", "Consumption type created" }
+                },
+                Saving::Finished(Err(err)) => rsx! {
+                    div { class: "alert alert-error",
+                        "Error: "
+                        {err.to_string()}
+                    }
+                },
+                Saving::No => rsx! {},
+            }
+            FormSubmitButton {
+                disabled: disabled_save,
+                title: "Add consumption type",
+                on_save,
+            }
+        }
+    }
+}
+
+#[component]
+fn EntryTemplateRow(template: EntryTemplate, on_delete: Callback<()>) -> Element {
+    let mut error: Signal<Option<ServerFnError>> = use_signal(|| None);
+    let id = template.id;
+
+    rsx! {
+        li { class: "mb-2",
+            div { class: "font-bold", "{template.name}" }
+            div { class: "text-sm opacity-70", {template.kind().as_title()} }
+            button {
+                r#type: "button",
+                class: "btn btn-error btn-xs",
+                onclick: move |_| {
+                    spawn(async move {
+                        match delete_entry_template(id).await {
+                            Ok(()) => on_delete(()),
+                            Err(err) => error.set(Some(err)),
+                        }
+                    });
+                },
+                "Delete"
+            }
+            if let Some(err) = error() {
+                div { class: "alert alert-error",
+                    "Error: "
+                    {err.to_string()}
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn EntryTemplates() -> Element {
+    let mut templates: Resource<Result<Vec<EntryTemplate>, ServerFnError>> = use_context();
+
+    rsx! {
+        p { class: "mb-4",
+            "Save an entry as a template from its row on the timeline, then start new "
+            "entries from it next to the create buttons."
+        }
+        match templates.read().deref() {
+            Some(Ok(list)) if !list.is_empty() => rsx! {
+                ul { class: "mb-4",
+                    for template in list {
+                        EntryTemplateRow {
+                            key: "{template.id}",
+                            template: template.clone(),
+                            on_delete: move |()| templates.restart(),
+                        }
+                    }
+                }
+            },
+            Some(Ok(_)) => rsx! {
+                p { class: "mb-4", "No templates yet." }
+            },
+            Some(Err(err)) => rsx! {
+                div { class: "alert alert-error",
+                    "Error loading templates: "
+                    {err.to_string()}
+                }
+            },
+            None => rsx! {
+                p { class: "alert alert-info", "Loading..." }
+            },
+        }
+    }
+}
+
+async fn do_create_webhook(
+    user_id: UserId,
+    validate: Memo<Result<String, ValidationError>>,
+    kinds: Vec<EntryKind>,
+) -> Result<CreatedWebhook, EditError> {
+    let url = validate.read().clone()?;
+
+    create_webhook(NewWebhook {
+        user_id,
+        url,
+        kinds,
+    })
+    .await
+    .map_err(EditError::Server)
+}
+
+#[component]
+fn WebhookRow(webhook: Webhook, on_delete: Callback<()>) -> Element {
+    let mut error: Signal<Option<ServerFnError>> = use_signal(|| None);
+    let timezone = use_timezone();
+    let id = webhook.id;
+    let kinds = if webhook.kinds.is_empty() {
+        "All entries".to_string()
+    } else {
+        webhook
+            .kinds
+            .iter()
+            .map(|kind| kind.as_title())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    rsx! {
+        li { class: "mb-2",
+            div { class: "font-bold break-all", "{webhook.url}" }
+            div { class: "text-sm opacity-70",
+                "{kinds}, created "
+                {display_date(get_date_for_dt(webhook.created_at, timezone))}
+            }
+            button {
+                r#type: "button",
+                class: "btn btn-error btn-xs",
+                onclick: move |_| {
+                    spawn(async move {
+                        match delete_webhook(id).await {
+                            Ok(()) => on_delete(()),
+                            Err(err) => error.set(Some(err)),
+                        }
+                    });
+                },
+                "Delete"
+            }
+            if let Some(err) = error() {
+                div { class: "alert alert-error",
+                    "Error: "
+                    {err.to_string()}
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn Webhooks(user: Arc<User>) -> Element {
+    let user_id = user.id;
+    let mut webhooks: Resource<Result<Vec<Webhook>, ServerFnError>> =
+        use_resource(move || async move { get_webhooks(user_id).await });
+
+    let url = use_signal(String::new);
+    let mut kinds: Signal<Vec<EntryKind>> = use_signal(Vec::new);
+    let validate = use_memo(move || validate_webhook_url(&url()));
+
+    let mut saving = use_signal(|| Saving::No);
+    let mut secret: Signal<Option<String>> = use_signal(|| None);
+
+    let disabled = use_memo(move || saving.read().is_saving());
+    let disabled_save = use_memo(move || validate.read().is_err() || disabled());
+
+    let on_save = use_callback(move |()| {
+        spawn(async move {
+            saving.set(Saving::Yes);
+            secret.set(None);
+
+            let result = do_create_webhook(user_id, validate, kinds()).await;
+
+            match result {
+                Ok(created) => {
+                    saving.set(Saving::Finished(Ok(())));
+                    secret.set(Some(created.secret));
+                    webhooks.restart();
+                }
+                Err(err) => saving.set(Saving::Finished(Err(err))),
+            }
+        });
+    });
+
+    rsx! {
+        p { class: "mb-4",
+            "Webhooks are sent a JSON POST request whenever an entry is created, updated or deleted. "
+            "Each request is signed with an HMAC-SHA256 of the body in the "
+            code { "X-Penguin-Nurse-Signature" }
+            " header."
+        }
+        match webhooks.read().deref() {
+            Some(Ok(list)) if !list.is_empty() => rsx! {
+                ul { class: "mb-4",
+                    for webhook in list {
+                        WebhookRow {
+                            key: "{webhook.id}",
+                            webhook: webhook.clone(),
+                            on_delete: move |()| webhooks.restart(),
+                        }
+                    }
+                }
+            },
+            Some(Ok(_)) => rsx! {},
+            Some(Err(err)) => rsx! {
+                div { class: "alert alert-error",
+                    "Error loading webhooks: "
+                    {err.to_string()}
+                }
+            },
+            None => rsx! {
+                p { class: "alert alert-info", "Loading..." }
+            },
+        }
+        MyForm {
+            InputString {
+                id: "webhook_url",
+                label: "URL",
+                value: url,
+                validate,
+                disabled,
+            }
+            fieldset { class: "fieldset border-2 rounded-md p-4 mb-4",
+                legend { class: "fieldset-legend px-2", "Entries (none selected means all)" }
+                for kind in EntryKind::all_values().iter().copied() {
+                    label { key: "{kind.as_id()}", class: "label",
+                        input {
+                            r#type: "checkbox",
+                            class: "checkbox checkbox-sm",
+                            checked: kinds.read().contains(&kind),
+                            disabled,
+                            oninput: move |e| {
+                                let mut kinds = kinds.write();
+                                kinds.retain(|k| *k != kind);
+                                if e.checked() {
+                                    kinds.push(kind);
+                                }
+                            },
+                        }
+                        {kind.as_title()}
+                    }
+                }
+            }
+            match &*saving.read() {
+                Saving::Yes => rsx! {
+                    div { class: "alert alert-info", "Creating..." }
+                },
+                Saving::Finished(Ok(())) => rsx! {
+                    div { class: "alert alert-success",
+                        if let Some(secret) = secret() {
+                            div {
+                                "Copy this signing secret now, it will not be shown again: "
+                                code { class: "break-all", "{secret}" }
+                            }
+                        }
+                    }
+                },
+                Saving::Finished(Err(err)) => rsx! {
+                    div { class: "alert alert-error",
+                        "Error: "
+                        {err.to_string()}
+                    }
+                },
+                Saving::No => rsx! {},
+            }
+            FormSubmitButton {
+                disabled: disabled_save,
+                title: "Create webhook",
+                on_save,
+            }
+        }
+    }
+}
+
+#[component]
+pub fn Preferences() -> Element {
+    let user = use_user().ok().flatten();
+
+    let Some(user) = user else {
+        return rsx! {
+            p { class: "alert alert-error", "You are not logged in." }
+        };
+    };
+
+    rsx! {
+        div { class: "ml-2 mr-2",
+            h1 { class: "text-2xl font-bold mb-4", "Preferences" }
+            PreferencesUpdate { user: user.clone() }
+            h2 { class: "text-xl font-bold mt-8 mb-4", "Your data" }
+            p { class: "mb-4",
+                "Download everything you have recorded as a JSON file."
+            }
+            a {
+                class: "btn btn-outline btn-accent",
+                href: "/api/v1/export",
+                download: true,
+                "Download my data"
+            }
+            SymptomsCsvExport { user: user.clone() }
+            h2 { class: "text-xl font-bold mt-8 mb-4", "Colour palettes" }
+            p { class: "mb-4",
+                "The active palette sets the preset colour buttons when recording an entry."
+            }
+            for kind in ColourPaletteKind::all_values().iter().copied() {
+                ColourPalettes { key: "{kind.as_id()}", user: user.clone(), kind }
+            }
+            h2 { class: "text-xl font-bold mt-8 mb-4", "Consumption types" }
+            ConsumptionTypes { user: user.clone() }
+            h2 { class: "text-xl font-bold mt-8 mb-4", "Entry templates" }
+            EntryTemplates {}
+            h2 { class: "text-xl font-bold mt-8 mb-4", "Share links" }
+            ShareLinks { user: user.clone() }
+            h2 { class: "text-xl font-bold mt-8 mb-4", "API tokens" }
+            ApiTokens { user: user.clone() }
+            h2 { class: "text-xl font-bold mt-8 mb-4", "Webhooks" }
+            Webhooks { user: user.clone() }
+            h2 { class: "text-xl font-bold mt-8 mb-4 text-error", "Delete account" }
+            DeleteAccount { user }
+        }
+    }
+}
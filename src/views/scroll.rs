@@ -0,0 +1,117 @@
+use dioxus::prelude::*;
+use dioxus_fullstack::ServerFnError;
+
+use crate::{
+    functions::entries::get_timeline_before,
+    models::{Entry, EntryCursor},
+    use_timezone, use_user,
+};
+
+use super::{search::LinkedDay, shared::entries_by_date};
+
+/// Number of entries fetched each time the end of the list comes into view.
+const SCROLL_CHUNK: i64 = 50;
+
+/// Element that triggers loading more entries when scrolled near.
+const SCROLL_END_ID: &str = "timeline-scroll-end";
+
+#[component]
+pub fn TimelineScroll() -> Element {
+    let user_id = use_user().ok().flatten().map(|user| user.id);
+    let timezone = use_timezone();
+
+    let mut entries: Signal<Vec<Entry>> = use_signal(Vec::new);
+    let mut before: Signal<Option<EntryCursor>> = use_signal(|| None);
+    let mut done = use_signal(|| false);
+    let mut loading = use_signal(|| false);
+    let mut error: Signal<Option<ServerFnError>> = use_signal(|| None);
+
+    let load_more = use_callback(move |()| {
+        let Some(user_id) = user_id else {
+            return;
+        };
+        if *loading.peek() || *done.peek() || error.peek().is_some() {
+            return;
+        }
+        loading.set(true);
+        spawn(async move {
+            let cursor = *before.peek();
+            match get_timeline_before(user_id, cursor, SCROLL_CHUNK).await {
+                Ok(page) => {
+                    entries.write().extend(page.entries);
+                    match page.next {
+                        Some(next) => before.set(Some(next)),
+                        None => done.set(true),
+                    }
+                }
+                Err(err) => error.set(Some(err)),
+            }
+            loading.set(false);
+        });
+    });
+
+    let reload = use_callback(move |()| {
+        entries.set(Vec::new());
+        before.set(None);
+        done.set(false);
+        error.set(None);
+        load_more(());
+    });
+
+    // Ask for more whenever the end of the list is close to the bottom of
+    // the window. Stops once the list has gone away.
+    use_future(move || async move {
+        let mut eval = document::eval(&format!(
+            r#"
+            let seen = false;
+            while (true) {{
+                const end = document.getElementById({SCROLL_END_ID:?});
+                if (end) {{
+                    seen = true;
+                    if (end.getBoundingClientRect().top < window.innerHeight + 400) {{
+                        dioxus.send(true);
+                    }}
+                }} else if (seen) {{
+                    break;
+                }}
+                await new Promise(r => setTimeout(r, 300));
+            }}
+            "#
+        ));
+        while let Ok(true) = eval.recv::<bool>().await {
+            load_more(());
+        }
+    });
+
+    if user_id.is_none() {
+        return rsx! {
+            p { class: "alert alert-error", "You are not logged in." }
+        };
+    }
+
+    let days = entries_by_date(&entries.read(), timezone);
+
+    rsx! {
+        div { class: "ml-2 mr-2",
+            for (date , entries) in days.into_iter().rev() {
+                LinkedDay {
+                    key: "{date}",
+                    date,
+                    entries,
+                    on_change: move |()| reload(()),
+                }
+            }
+            if let Some(err) = error() {
+                div { class: "alert alert-error",
+                    "Error loading timeline: "
+                    {err.to_string()}
+                }
+            } else if done() {
+                p { class: "alert alert-info mt-4", "No older entries." }
+            } else {
+                p { class: "alert alert-info mt-4", "Loading..." }
+            }
+            div { id: SCROLL_END_ID }
+        }
+    }
+}
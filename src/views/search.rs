@@ -0,0 +1,132 @@
+use std::{collections::HashSet, ops::Deref};
+
+use chrono::NaiveDate;
+use dioxus::prelude::*;
+use dioxus_fullstack::ServerFnError;
+use dioxus_router::{Link, navigator};
+
+use crate::{
+    Route,
+    components::timeline::{DialogReference, EntryFocus},
+    dt::display_date,
+    functions::entries::search_entries_by_comment,
+    models::{Entry, EntryId, SortDirection},
+    use_timezone, use_user,
+};
+
+use super::{shared::entries_by_date, timeline::EntryRow};
+
+/// One day of entries, headed by a link to that day's timeline.
+#[component]
+pub(super) fn LinkedDay(date: NaiveDate, entries: Vec<Entry>, on_change: Callback<()>) -> Element {
+    let selected: Signal<Option<EntryId>> = use_signal(|| None);
+    let expanded: Signal<HashSet<EntryId>> = use_signal(HashSet::new);
+
+    rsx! {
+        div { class: "font-bold text-lg mt-4",
+            Link {
+                to: Route::TimelineList {
+                    date,
+                    dialog: DialogReference::Idle,
+                    sort: SortDirection::default(),
+                    focus: EntryFocus::default(),
+                },
+                class: "link",
+                {display_date(date)}
+            }
+        }
+        table { class: "block sm:table",
+            thead { class: "hidden sm:table-header-group",
+                tr {
+                    th { "When" }
+                    th { "What" }
+                    th { "How Long" }
+                    th { "Details" }
+                }
+            }
+            tbody { class: "block sm:table-row-group",
+                for entry in entries {
+                    EntryRow {
+                        key: "{entry.get_id().as_str()}",
+                        entry: entry.clone(),
+                        date,
+                        sort: SortDirection::default(),
+                        selected,
+                        expanded,
+                        print_only: false,
+                        read_only: false,
+                        on_change,
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn EntrySearch(query: ReadSignal<Option<String>>) -> Element {
+    let query = use_memo(move || query().unwrap_or_default());
+    let user_id = use_user().ok().flatten().map(|user| user.id);
+    let timezone = use_timezone();
+    let navigator = navigator();
+
+    let mut results: Resource<Result<Vec<Entry>, ServerFnError>> =
+        use_resource(move || async move {
+            match user_id {
+                Some(user_id) => search_entries_by_comment(user_id, query()).await,
+                None => Ok(Vec::new()),
+            }
+        });
+
+    if user_id.is_none() {
+        return rsx! {
+            p { class: "alert alert-error", "You are not logged in." }
+        };
+    }
+
+    rsx! {
+        div { class: "ml-2 mr-2",
+            h1 { class: "font-bold text-2xl", "Search" }
+            div { class: "mt-2 mb-2",
+                input {
+                    class: "input",
+                    r#type: "search",
+                    value: query(),
+                    oninput: move |e| {
+                        navigator.replace(Route::EntrySearch { query: e.value() });
+                    },
+                    placeholder: "Search comments...",
+                }
+            }
+            match results.read().deref() {
+                Some(Err(err)) => rsx! {
+                    div { class: "alert alert-error",
+                        "Error searching entries: "
+                        {err.to_string()}
+                    }
+                },
+                Some(Ok(entries)) => {
+                    let days = entries_by_date(entries, timezone);
+                    rsx! {
+                        if query().trim().is_empty() {
+                            p { class: "alert alert-info", "Enter a word to find entries with matching comments." }
+                        } else if days.is_empty() {
+                            p { class: "alert alert-info", "No entries found." }
+                        }
+                        for (date , entries) in days.into_iter().rev() {
+                            LinkedDay {
+                                key: "{date}",
+                                date,
+                                entries,
+                                on_change: move |()| results.restart(),
+                            }
+                        }
+                    }
+                }
+                None => rsx! {
+                    p { class: "alert alert-info", "Loading..." }
+                },
+            }
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::{BTreeMap, HashSet};
+use std::ops::Deref;
+
+use chrono::{NaiveDate, TimeDelta, Utc};
+use chrono_tz::Tz;
+use dioxus::prelude::*;
+use dioxus_fullstack::ServerFnError;
+
+use crate::{
+    dt::{display_date, get_date_for_dt},
+    functions::share_links::get_shared_timeline,
+    models::{self, Entry, EntryId, SortDirection},
+    use_timezone,
+};
+
+use super::timeline::EntryRow;
+
+/// Group the shared entries by the local date they happened on.
+pub(super) fn entries_by_date(
+    entries: &[Entry],
+    timezone: Option<Tz>,
+) -> BTreeMap<NaiveDate, Vec<Entry>> {
+    let mut days: BTreeMap<NaiveDate, Vec<Entry>> = BTreeMap::new();
+    for entry in entries {
+        let date = get_date_for_dt(entry.time.with_timezone(&Utc), timezone);
+        days.entry(date).or_default().push(entry.clone());
+    }
+    days
+}
+
+#[component]
+fn SharedDay(date: NaiveDate, entries: Vec<Entry>) -> Element {
+    let selected: Signal<Option<EntryId>> = use_signal(|| None);
+    let expanded: Signal<HashSet<EntryId>> = use_signal(HashSet::new);
+
+    rsx! {
+        div { class: "font-bold text-lg mt-4", {display_date(date)} }
+        table { class: "block sm:table print:table print:w-full",
+            thead { class: "hidden sm:table-header-group print:table-header-group",
+                tr {
+                    th { "When" }
+                    th { "What" }
+                    th { "How Long" }
+                    th { "Details" }
+                }
+            }
+            tbody { class: "block sm:table-row-group print:table-row-group",
+                for entry in entries {
+                    EntryRow {
+                        key: "{entry.get_id().as_str()}",
+                        entry: entry.clone(),
+                        date,
+                        sort: SortDirection::Ascending,
+                        selected,
+                        expanded,
+                        print_only: false,
+                        read_only: true,
+                        on_change: move |()| {},
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn SharedTimeline(token: ReadSignal<String>) -> Element {
+    let shared: Resource<Result<models::SharedTimeline, ServerFnError>> =
+        use_resource(move || async move { get_shared_timeline(token()).await });
+    let timezone = use_timezone();
+
+    rsx! {
+        div { class: "ml-2 mr-2",
+            h1 { class: "font-bold text-2xl", "Shared timeline" }
+            match shared.read().deref() {
+                Some(Err(err)) => rsx! {
+                    div { class: "alert alert-error",
+                        "Error loading timeline: "
+                        {err.to_string()}
+                    }
+                },
+                Some(Ok(shared)) => {
+                    let first = get_date_for_dt(shared.start, timezone);
+                    let last = get_date_for_dt(shared.end - TimeDelta::seconds(1), timezone);
+                    let expires = get_date_for_dt(shared.expires_at, timezone);
+                    let days = entries_by_date(&shared.entries, timezone);
+                    rsx! {
+                        p {
+                            {display_date(first)}
+                            " – "
+                            {display_date(last)}
+                        }
+                        p { class: "text-sm opacity-70 print:hidden",
+                            "This read only link expires on "
+                            {display_date(expires)}
+                            "."
+                        }
+                        if days.is_empty() {
+                            p { class: "alert alert-info", "No entries found for these dates." }
+                        }
+                        for (date , entries) in days {
+                            SharedDay { key: "{date}", date, entries }
+                        }
+                    }
+                }
+                None => rsx! {
+                    p { class: "alert alert-info", "Loading..." }
+                },
+            }
+        }
+    }
+}
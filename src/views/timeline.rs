@@ -1,6 +1,9 @@
-use std::ops::Deref;
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Deref,
+};
 
-use chrono::{NaiveDate, Utc};
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, TimeDelta, Utc};
 use dioxus::prelude::*;
 use dioxus_fullstack::ServerFnError;
 use dioxus_router::navigator;
@@ -9,121 +12,528 @@ use tap::Pipe;
 use crate::{
     Route,
     components::{
-        StrIcon,
-        buttons::{ChangeButton, CreateButton, DeleteButton, NavButton},
+        ElementIcon, StrIcon,
+        buttons::{ChangeButton, CreateButton, DeleteButton, NavButton, PrintButton},
         consumptions::{
-            self, ConsumptionDetails, ConsumptionItemList, ConsumptionTypeIcon,
-            consumption_duration, consumption_errors,
+            self, ConsumptionDetails, ConsumptionIntakeTag, ConsumptionItemList,
+            ConsumptionRouteIcon, consumption_duration, consumption_errors,
         },
-        events::EventTime,
+        entry_templates::{EntryTemplateCreate, EntryTemplatePicker},
+        events::{EventTime, Markdown},
         exercises::{ExerciseDetails, ExerciseTypeIcon},
+        fluid_adjustments::{
+            FluidAdjustmentDetails, fluid_adjustment_icon, fluid_adjustment_title,
+        },
         health_metrics::{HealthMetricDetails, HealthMetricIcon, health_metric_title},
+        medication_doses::{MedicationDoseDetails, medication_dose_icon, medication_dose_title},
         notes::{NoteDetails, note_icon, note_title},
         poos::{self, PooDetails, PooDuration, PooIcon, poo_title},
         refluxs::{RefluxDetails, reflux_duration, reflux_icon, reflux_title},
         symptoms::{SymptomDetails, symptom_icon, symptom_title},
-        timeline::{ActiveDialog, DialogReference, TimelineDialog},
+        timeline::{ActiveDialog, DialogReference, EntryFocus, TimelineDialog},
+        undo::use_undo_log,
         wee_urges::{self, WeeUrgeDetails, WeeUrgeIcon, wee_urge_title},
         wees::{self, WeeDetails, WeeDuration, WeeIcon, wee_title},
     },
-    dt::{display_date, get_date_for_dt, get_utc_times_for_date},
+    dt::{display_date, get_date_for_dt, get_today, get_utc_times_for_date},
+    forms::{
+        EditError, FieldValue, InputDateTime, InputTextArea, Saving, validate_comments,
+        validate_fixed_offset_date_time,
+    },
     functions::{
         consumables::get_consumable_by_id,
-        consumptions::{get_consumption_by_id, get_consumptions_for_time_range},
-        exercises::{get_exercise_by_id, get_exercises_for_time_range},
-        health_metrics::{get_health_metric_by_id, get_health_metrics_for_time_range},
-        notes::{get_note_by_id, get_notes_for_time_range},
-        poos::{get_poo_by_id, get_poos_for_time_range},
-        refluxs::{get_reflux_by_id, get_refluxs_for_time_range},
-        symptoms::{get_symptom_by_id, get_symptoms_for_time_range},
-        wee_urges::{get_wee_urge_by_id, get_wee_urges_for_time_range},
-        wees::{get_wee_by_id, get_wees_for_time_range},
+        consumptions::{get_consumption_by_id, update_consumption},
+        entries::{get_entry_by_id, get_entry_counts_for_range, timeline_json},
+        entry_templates::get_entry_template_data,
+        exercises::{get_exercise_by_id, update_exercise},
+        fluid_adjustments::{get_fluid_adjustment_by_id, update_fluid_adjustment},
+        health_metrics::{get_health_metric_by_id, update_health_metric},
+        medication_doses::{get_medication_dose_by_id, update_medication_dose},
+        notes::{get_note_by_id, update_note},
+        poos::{get_poo_by_id, update_poo},
+        refluxs::{get_reflux_by_id, update_reflux},
+        symptoms::{get_symptom_by_id, update_symptom},
+        wee_urges::{get_wee_urge_by_id, update_wee_urge},
+        wees::{get_wee_by_id, update_wee},
     },
-    models::{Consumable, Consumption, Entry, EntryData, EntryId, Timeline},
-    use_user,
+    i18n::{format_prefs, t},
+    models::{
+        ChangeConsumption, ChangeExercise, ChangeFluidAdjustment, ChangeHealthMetric,
+        ChangeMedicationDose, ChangeNote, ChangePoo, ChangeReflux, ChangeSymptom, ChangeWee,
+        ChangeWeeUrge, Consumable, Consumption, ConsumptionRoute, Entry, EntryData, EntryGroup,
+        EntryId, EntryKind, EntryTemplateId, FluidBalance, MaybeSet, SortDirection, Timeline,
+        UserId, format_datetime_long,
+    },
+    use_consumption_types, use_timezone, use_user,
 };
 
 #[component]
-fn EntryRow(
+fn FluidBalanceSummary(balance: FluidBalance) -> Element {
+    let net = balance.net().round(0);
+    let net_class = if net < 0 {
+        "text-warning"
+    } else {
+        "text-success"
+    };
+
+    rsx! {
+        div { class: "ml-2 mr-2 sm:ml-0 sm:mr-0 mb-2 flex flex-wrap gap-4",
+            span { "In: {balance.input.round(0)} ml" }
+            span { "Out: {balance.output.round(0)} ml" }
+            if balance.adjustment != 0 {
+                span { "Adjustments: {balance.adjustment.round(0)} ml" }
+            }
+            if balance.solids > 0 {
+                span { class: "opacity-70", "Solids: {balance.solids}" }
+            }
+            span { class: net_class, "Net: {net} ml" }
+        }
+    }
+}
+
+/// Net fluid balance for the day so far, shown against fluid entries.
+#[component]
+fn RunningBalance(balance: bigdecimal::BigDecimal) -> Element {
+    let net = balance.round(0);
+    let net_class = if net < 0 {
+        "text-warning"
+    } else {
+        "text-success"
+    };
+
+    rsx! {
+        div { class: "text-xs {net_class}", "Balance so far: {net} ml" }
+    }
+}
+
+/// Change only the time and comments of an entry, leaving every other field
+/// untouched.
+async fn update_entry(
+    entry: &Entry,
+    time: MaybeSet<DateTime<FixedOffset>>,
+    comments: MaybeSet<Option<String>>,
+) -> Result<(), ServerFnError> {
+    match &entry.data {
+        EntryData::Wee(wee) => {
+            update_wee(
+                wee.id,
+                ChangeWee {
+                    time,
+                    comments,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        }
+        EntryData::WeeUrge(wee_urge) => {
+            update_wee_urge(
+                wee_urge.id,
+                ChangeWeeUrge {
+                    time,
+                    comments,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        }
+        EntryData::Poo(poo) => {
+            update_poo(
+                poo.id,
+                ChangePoo {
+                    time,
+                    comments,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        }
+        EntryData::Consumption(consumption) => {
+            let changes = ChangeConsumption {
+                time,
+                comments,
+                ..Default::default()
+            };
+            update_consumption(consumption.consumption.id, changes).await?;
+        }
+        EntryData::Exercise(exercise) => {
+            update_exercise(
+                exercise.id,
+                ChangeExercise {
+                    time,
+                    comments,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        }
+        EntryData::HealthMetric(health_metric) => {
+            let changes = ChangeHealthMetric {
+                time,
+                comments,
+                ..Default::default()
+            };
+            update_health_metric(health_metric.id, changes).await?;
+        }
+        EntryData::Symptom(symptom) => {
+            update_symptom(
+                symptom.id,
+                ChangeSymptom {
+                    time,
+                    comments,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        }
+        EntryData::Reflux(reflux) => {
+            update_reflux(
+                reflux.id,
+                ChangeReflux {
+                    time,
+                    comments,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        }
+        EntryData::Note(note) => {
+            update_note(
+                note.id,
+                ChangeNote {
+                    time,
+                    comments,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        }
+        EntryData::FluidAdjustment(fluid_adjustment) => {
+            let changes = ChangeFluidAdjustment {
+                time,
+                comments,
+                ..Default::default()
+            };
+            update_fluid_adjustment(fluid_adjustment.id, changes).await?;
+        }
+        EntryData::Medication(medication_dose) => {
+            let changes = ChangeMedicationDose {
+                time,
+                comments,
+                ..Default::default()
+            };
+            update_medication_dose(medication_dose.dose.id, changes).await?;
+        }
+    }
+    Ok(())
+}
+
+#[component]
+fn EntryTimeEdit(entry: ReadSignal<Entry>, on_change: Callback<()>) -> Element {
+    let time = use_signal(|| entry().time.as_raw());
+    let timezone = use_timezone();
+    let validate = use_memo(move || validate_fixed_offset_date_time(&time(), timezone));
+
+    let mut saving = use_signal(|| Saving::No);
+
+    let disabled = use_memo(move || saving.read().is_saving());
+    let disabled_save = use_memo(move || validate.read().is_err() || disabled());
+
+    let on_save = move |_| {
+        spawn(async move {
+            let Ok(new_time) = validate() else {
+                return;
+            };
+            saving.set(Saving::Yes);
+
+            match update_entry(&entry(), MaybeSet::Set(new_time), MaybeSet::NoChange).await {
+                Ok(()) => {
+                    saving.set(Saving::Finished(Ok(())));
+                    on_change(());
+                }
+                Err(err) => saving.set(Saving::Finished(Err(EditError::Server(err)))),
+            }
+        });
+    };
+
+    rsx! {
+        form {
+            novalidate: true,
+            action: "javascript:void(0);",
+            class: "w-full",
+            InputDateTime {
+                id: "inline_time",
+                label: "Time",
+                value: time,
+                validate,
+                disabled,
+            }
+            if let Saving::Finished(Err(err)) = &*saving.read() {
+                div { class: "alert alert-error",
+                    "Error: "
+                    {err.to_string()}
+                }
+            }
+            button {
+                r#type: "submit",
+                class: "btn btn-primary btn-sm",
+                disabled: disabled_save(),
+                onclick: on_save,
+                "Save time"
+            }
+        }
+    }
+}
+
+/// Clear the planned flag of a consumption, optionally moving it to now.
+#[component]
+fn ConsumptionMarkDone(consumption: ReadSignal<Consumption>, on_change: Callback<()>) -> Element {
+    let mut saving = use_signal(|| Saving::No);
+    let disabled = saving.read().is_saving();
+
+    let mark_done = move |time_now: bool| {
+        let id = consumption.read().id;
+        let time = if time_now {
+            MaybeSet::Set(Utc::now().with_timezone(&Local).fixed_offset())
+        } else {
+            MaybeSet::NoChange
+        };
+        spawn(async move {
+            saving.set(Saving::Yes);
+            let changes = ChangeConsumption {
+                time,
+                planned: MaybeSet::Set(false),
+                ..Default::default()
+            };
+            match update_consumption(id, changes).await {
+                Ok(_consumption) => {
+                    saving.set(Saving::Finished(Ok(())));
+                    on_change(());
+                }
+                Err(err) => saving.set(Saving::Finished(Err(EditError::Server(err)))),
+            }
+        });
+    };
+
+    rsx! {
+        button {
+            r#type: "button",
+            class: "btn btn-outline btn-secondary",
+            disabled,
+            onclick: move |_| mark_done(false),
+            "Mark done"
+        }
+        button {
+            r#type: "button",
+            class: "btn btn-outline btn-secondary",
+            disabled,
+            onclick: move |_| mark_done(true),
+            "Done now"
+        }
+        if let Saving::Finished(Err(err)) = &*saving.read() {
+            div { class: "alert alert-error",
+                "Error: "
+                {err.to_string()}
+            }
+        }
+    }
+}
+
+#[component]
+fn EntryCommentEdit(entry: ReadSignal<Entry>, on_change: Callback<()>) -> Element {
+    let comments = use_signal(|| entry().comments().unwrap_or_default().to_string());
+    let validate = use_memo(move || validate_comments(&comments()));
+
+    let mut saving = use_signal(|| Saving::No);
+
+    let disabled = use_memo(move || saving.read().is_saving());
+    let disabled_save = use_memo(move || validate.read().is_err() || disabled());
+
+    let on_save = move |_| {
+        spawn(async move {
+            let Ok(new_comments) = validate() else {
+                return;
+            };
+            saving.set(Saving::Yes);
+
+            match update_entry(&entry(), MaybeSet::NoChange, MaybeSet::Set(new_comments)).await {
+                Ok(()) => {
+                    saving.set(Saving::Finished(Ok(())));
+                    on_change(());
+                }
+                Err(err) => saving.set(Saving::Finished(Err(EditError::Server(err)))),
+            }
+        });
+    };
+
+    rsx! {
+        form {
+            novalidate: true,
+            action: "javascript:void(0);",
+            class: "w-full",
+            if let Some(current) = entry().comments() {
+                Markdown { content: current.to_string() }
+            }
+            InputTextArea {
+                id: "inline_comments",
+                label: "Comments",
+                value: comments,
+                validate,
+                disabled,
+            }
+            if let Saving::Finished(Err(err)) = &*saving.read() {
+                div { class: "alert alert-error",
+                    "Error: "
+                    {err.to_string()}
+                }
+            }
+            button {
+                r#type: "submit",
+                class: "btn btn-primary btn-sm",
+                disabled: disabled_save(),
+                onclick: on_save,
+                "Save comments"
+            }
+        }
+    }
+}
+
+#[component]
+pub(super) fn EntryRow(
     entry: ReadSignal<Entry>,
     date: ReadSignal<NaiveDate>,
+    sort: ReadSignal<SortDirection>,
     selected: Signal<Option<EntryId>>,
+    /// Rows showing their actions, clicking a row toggles it.
+    expanded: Signal<HashSet<EntryId>>,
+    print_only: ReadSignal<bool>,
+    read_only: ReadSignal<bool>,
+    /// Net fluid balance for the day up to and including this entry.
+    balance: Option<bigdecimal::BigDecimal>,
+    on_change: Callback<()>,
 ) -> Element {
     let navigator = navigator();
     let entry: Entry = entry();
     let id = entry.get_id();
+    let is_expanded = selected() == Some(id) || expanded.read().contains(&id);
     let update_dialog_reference = DialogReference::get_update_dialog_reference(&entry);
     let delete_dialog_reference = DialogReference::get_delete_dialog_reference(&entry);
+    let span = get_utc_times_for_date(date(), use_timezone())
+        .map(|(start, end)| entry.day_span(start, end))
+        .unwrap_or_default();
+    let planned = matches!(&entry.data, EntryData::Consumption(consumption) if consumption.consumption.planned);
+    let consumption_types = use_consumption_types();
 
     rsx! {
         tr {
-            class: "hover:bg-gray-500 border-blue-300 mt-2 mb-2 p-2 border-2 w-full sm:w-auto sm:border-none inline-block sm:table-row",
-            onclick: move |_| selected.set(Some(id)),
-            td { class: "block sm:table-cell border-blue-300 sm:border-t-2",
+            id: id.as_str(),
+            class: if selected() == Some(id) { "bg-base-300" },
+            class: if planned { "opacity-50" },
+            class: if print_only() { "hidden print:table-row print:break-inside-avoid" } else { "hover:bg-gray-500 border-blue-300 mt-2 mb-2 p-2 border-2 w-full sm:w-auto sm:border-none inline-block sm:table-row print:table-row print:border-none print:break-inside-avoid" },
+            onclick: move |_| {
+                if !read_only() {
+                    if expanded.write().insert(id) {
+                        selected.set(Some(id));
+                    } else {
+                        expanded.write().remove(&id);
+                        if selected() == Some(id) {
+                            selected.set(None);
+                        }
+                    }
+                }
+            },
+            td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2",
                 EventTime { time: entry.time }
+                if let Some(edited_at) = entry.edited_at() {
+                    span {
+                        class: "badge badge-ghost badge-xs ml-1",
+                        title: format!(
+                            "Edited {}",
+                            format_datetime_long(&edited_at.with_timezone(&Local).fixed_offset(), &format_prefs()),
+                        ),
+                        "edited"
+                    }
+                }
+                if span.from_previous_day {
+                    div { class: "text-xs opacity-70", {t("Continues from previous day")} }
+                }
+                if span.into_next_day {
+                    div { class: "text-xs opacity-70", {t("Continues into next day")} }
+                }
             }
             match &entry.data {
                 EntryData::Wee(wee) => {
                     rsx! {
-                        td { class: "block sm:table-cell border-blue-300 sm:border-t-2",
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2",
                             StrIcon { title: wee_title(), icon: WeeIcon() }
                         }
-                        td { class: "block sm:table-cell border-blue-300 sm:border-t-2",
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2",
                             WeeDuration { duration: wee.duration }
                         }
-                        td { class: "block sm:table-cell border-blue-300 sm:border-t-2",
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2",
                             WeeDetails { wee: wee.clone() }
+                            if let Some(balance) = &balance {
+                                RunningBalance { balance: balance.clone() }
+                            }
                         }
                     }
                 }
                 EntryData::WeeUrge(wee_urge) => {
                     rsx! {
-                        td { class: "block sm:table-cell border-blue-300 sm:border-t-2",
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2",
                             StrIcon { title: wee_urge_title(), icon: WeeUrgeIcon() }
                         }
-                        td { class: "block sm:table-cell border-blue-300 sm:border-t-2" }
-                        td { class: "block sm:table-cell border-blue-300 sm:border-t-2",
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2" }
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2",
                             WeeUrgeDetails { wee_urge: wee_urge.clone() }
                         }
                     }
                 }
                 EntryData::Poo(poo) => {
                     rsx! {
-                        td { class: "block sm:table-cell border-blue-300 sm:border-t-2",
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2",
                             StrIcon { title: poo_title(), icon: PooIcon() }
                         }
-                        td { class: "block sm:table-cell border-blue-300 sm:border-t-2",
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2",
                             PooDuration { duration: poo.duration }
                         }
-                        td { class: "block sm:table-cell border-blue-300 sm:border-t-2",
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2",
                             PooDetails { poo: poo.clone() }
                         }
                     }
                 }
                 EntryData::Consumption(consumption) => {
+                    let route = ConsumptionRoute::for_consumption(&consumption.consumption, &consumption_types);
+                    let title = route.as_title();
                     rsx! {
-                        td { class: "block sm:table-cell border-blue-300 sm:border-t-2",
-                            StrIcon {
-                                title: &consumption.consumption.consumption_type.as_title(),
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2",
+                            ElementIcon {
+                                title: rsx! { "{title}" },
                                 icon: rsx! {
-                                    ConsumptionTypeIcon { consumption_type: consumption.consumption.consumption_type }
+                                    ConsumptionRouteIcon { route }
                                 },
                             }
                         }
-                        td { class: "block sm:table-cell border-blue-300 sm:border-t-2",
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2",
                             consumption_duration { duration: consumption.consumption.duration }
                         }
-                        td { class: "block sm:table-cell border-blue-300 sm:border-t-2",
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2",
+                            if consumption.consumption.planned {
+                                span { class: "badge badge-outline badge-sm mr-1", "Planned" }
+                            }
+                            ConsumptionIntakeTag { consumption: consumption.clone() }
                             ConsumptionDetails { consumption: consumption.consumption.clone() }
                             if !consumption.items.is_empty() {
                                 ConsumptionItemList { list: consumption.items.clone() }
                             }
+                            if let Some(balance) = &balance {
+                                RunningBalance { balance: balance.clone() }
+                            }
                         }
                     }
                 }
                 EntryData::Exercise(exercise) => {
                     rsx! {
-                        td { class: "block sm:table-cell border-blue-300 sm:border-t-2",
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2",
                             StrIcon {
                                 title: &exercise.exercise_type.as_title(),
                                 icon: rsx! {
@@ -132,62 +542,90 @@ fn EntryRow(
                             }
 
                         }
-                        td { class: "block sm:table-cell border-blue-300 sm:border-t-2",
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2",
 
                             WeeDuration { duration: exercise.duration }
                         }
-                        td { class: "block sm:table-cell border-blue-300 sm:border-t-2",
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2",
                             ExerciseDetails { exercise: exercise.clone() }
                         }
                     }
                 }
                 EntryData::HealthMetric(health_metric) => {
                     rsx! {
-                        td { class: "block sm:table-cell border-blue-300 sm:border-t-2",
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2",
                             StrIcon { title: health_metric_title(), icon: HealthMetricIcon() }
 
                         }
-                        td { class: "block sm:table-cell border-blue-300 sm:border-t-2" }
-                        td { class: "block sm:table-cell border-blue-300 sm:border-t-2",
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2" }
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2",
                             HealthMetricDetails { health_metric: health_metric.clone() }
                         }
                     }
                 }
                 EntryData::Symptom(symptom) => {
                     rsx! {
-                        td { class: "block sm:table-cell border-blue-300 sm:border-t-2",
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2",
                             StrIcon { title: symptom_title(), icon: symptom_icon() }
                         }
-                        td { class: "block sm:table-cell border-blue-300 sm:border-t-2" }
-                        td { class: "block sm:table-cell border-blue-300 sm:border-t-2",
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2" }
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2",
                             SymptomDetails { symptom: symptom.clone() }
                         }
                     }
                 }
                 EntryData::Reflux(reflux) => {
                     rsx! {
-                        td { class: "block sm:table-cell border-blue-300 sm:border-t-2",
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2",
                             StrIcon { title: reflux_title(), icon: reflux_icon() }
                         }
-                        td { class: "block sm:table-cell border-blue-300 sm:border-t-2",
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2",
                             reflux_duration { duration: reflux.duration }
                         }
-                        td { class: "block sm:table-cell border-blue-300 sm:border-t-2",
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2",
                             RefluxDetails { reflux: reflux.clone() }
                         }
                     }
                 }
                 EntryData::Note(note) => {
                     rsx! {
-                        td { class: "block sm:table-cell border-blue-300 sm:border-t-2",
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2",
                             StrIcon { title: note_title(), icon: note_icon() }
                         }
-                        td { class: "block sm:table-cell border-blue-300 sm:border-t-2" }
-                        td { class: "block sm:table-cell border-blue-300 sm:border-t-2",
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2" }
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2",
                             NoteDetails { note: note.clone() }
                         }
                     }
                 }
+                EntryData::FluidAdjustment(fluid_adjustment) => {
+                    rsx! {
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2",
+                            StrIcon {
+                                title: fluid_adjustment_title(),
+                                icon: fluid_adjustment_icon(),
+                            }
+                        }
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2" }
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2",
+                            FluidAdjustmentDetails { fluid_adjustment: fluid_adjustment.clone() }
+                        }
+                    }
+                }
+                EntryData::Medication(medication_dose) => {
+                    rsx! {
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2",
+                            StrIcon {
+                                title: medication_dose_title(),
+                                icon: medication_dose_icon(),
+                            }
+                        }
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2" }
+                        td { class: "block sm:table-cell print:table-cell border-blue-300 sm:border-t-2 print:border-t-2",
+                            MedicationDoseDetails { medication_dose: medication_dose.clone() }
+                        }
+                    }
+                }
             }
         }
         if let EntryData::Consumption(consumption) = &entry.data {
@@ -201,7 +639,7 @@ fn EntryRow(
                     .map(|error| {
                         rsx! {
                             tr {
-                                td { colspan: 4, class: "block sm:table-cell",
+                                td { colspan: 4, class: "block sm:table-cell print:hidden",
                                     div { class: "text-error", {error} }
                                 }
                             }
@@ -210,14 +648,20 @@ fn EntryRow(
             }
         }
 
-        if selected() == Some(id) {
-            td { colspan: 4, class: "block sm:table-cell",
+        if is_expanded {
+            td {
+                colspan: 4,
+                class: "block sm:table-cell print:hidden",
+                // Keep keys typed into the inline forms away from row navigation.
+                onkeydown: move |event| event.stop_propagation(),
                 div { class: "flex flex-wrap gap-2",
                     ChangeButton {
                         on_click: move |_| {
                             navigator
                                 .push(Route::TimelineList {
                                     date: date(),
+                                    sort: sort(),
+                                    focus: EntryFocus::default(),
                                     dialog: update_dialog_reference.clone(),
                                 });
                         },
@@ -228,11 +672,16 @@ fn EntryRow(
                             navigator
                                 .push(Route::TimelineList {
                                     date: date(),
+                                    sort: sort(),
+                                    focus: EntryFocus::default(),
                                     dialog: delete_dialog_reference.clone(),
                                 });
                         },
                         "Delete"
                     }
+                    EntryTimeEdit { entry: entry.clone(), on_change }
+                    EntryCommentEdit { entry: entry.clone(), on_change }
+                    EntryTemplateCreate { entry: entry.clone() }
                     match entry.data {
                         EntryData::Consumption(consumption) => {
                             let consumption = consumption.consumption;
@@ -242,6 +691,8 @@ fn EntryRow(
                                         navigator
                                             .push(Route::TimelineList {
                                                 date: date(),
+                                                sort: sort(),
+                                                focus: EntryFocus::default(),
                                                 dialog: DialogReference::UpdateIngredients {
                                                     consumption_id: consumption.id,
                                                 },
@@ -249,6 +700,9 @@ fn EntryRow(
                                     },
                                     "Ingredients"
                                 }
+                                if consumption.planned {
+                                    ConsumptionMarkDone { consumption: consumption.clone(), on_change }
+                                }
                             }
                         }
                         _ => rsx! {},
@@ -259,13 +713,208 @@ fn EntryRow(
     }
 }
 
+#[component]
+fn EntryGroupRows(
+    group: ReadSignal<EntryGroup>,
+    date: ReadSignal<NaiveDate>,
+    sort: ReadSignal<SortDirection>,
+    selected: Signal<Option<EntryId>>,
+    expanded_entries: Signal<HashSet<EntryId>>,
+    balances: HashMap<EntryId, bigdecimal::BigDecimal>,
+    on_change: Callback<()>,
+) -> Element {
+    let mut expanded = use_signal(|| false);
+    let group: EntryGroup = group();
+    let count = group.len();
+
+    rsx! {
+        // Collapsed entries are hidden on screen but still printed in full.
+        for (index, entry) in group.iter().enumerate() {
+            EntryRow {
+                key: "{entry.get_id().as_str()}",
+                entry: entry.clone(),
+                date: date(),
+                sort: sort(),
+                selected,
+                expanded: expanded_entries,
+                print_only: !expanded() && index > 0 && selected() != Some(entry.get_id())
+                    && !expanded_entries.read().contains(&entry.get_id()),
+                read_only: false,
+                balance: balances.get(&entry.get_id()).cloned(),
+                on_change,
+            }
+        }
+        tr { class: "block sm:table-row print:hidden",
+            td { colspan: 4, class: "block sm:table-cell print:hidden",
+                button {
+                    class: "btn btn-ghost btn-xs",
+                    onclick: move |_| expanded.set(!expanded()),
+                    if expanded() {
+                        "Collapse {count} similar entries"
+                    } else {
+                        "Show {count - 1} more similar entries"
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn entry_count(count: i64) -> String {
+    if count == 1 {
+        "1 entry".to_string()
+    } else {
+        format!("{count} entries")
+    }
+}
+
+#[component]
+fn EmptyTimeline(
+    user_id: UserId,
+    date: ReadSignal<NaiveDate>,
+    sort: ReadSignal<SortDirection>,
+) -> Element {
+    let navigator = navigator();
+    let timezone = use_timezone();
+
+    // Entry counts for the previous and next days, if those days exist.
+    let neighbours: Resource<Result<Vec<(NaiveDate, i64)>, ServerFnError>> =
+        use_resource(move || async move {
+            let dates: Vec<NaiveDate> = [date().pred_opt(), date().succ_opt()]
+                .into_iter()
+                .flatten()
+                .collect();
+            let windows = dates
+                .iter()
+                .map(|date| get_utc_times_for_date(*date, timezone))
+                .collect::<Result<Vec<_>, _>>()?;
+            let counts = get_entry_counts_for_range(user_id, windows).await?;
+            Ok(dates.into_iter().zip(counts).collect())
+        });
+
+    let hints = match neighbours.read().deref() {
+        Some(Ok(neighbours)) => neighbours
+            .iter()
+            .filter(|(_, count)| *count > 0)
+            .copied()
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    rsx! {
+        p { class: "alert alert-info",
+            "No entries found for this date."
+            for (hint_date , count) in hints {
+                button {
+                    class: "btn btn-ghost btn-xs",
+                    onclick: move |_| {
+                        navigator
+                            .push(Route::TimelineList {
+                                date: hint_date,
+                                sort: sort(),
+                                focus: EntryFocus::default(),
+                                dialog: DialogReference::Idle,
+                            });
+                    },
+                    if hint_date < date() {
+                        "← Previous day has {entry_count(count)}"
+                    } else {
+                        "Next day has {entry_count(count)} →"
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Expand or collapse the actions of every entry of one kind at once.
+#[component]
+fn ExpandByKind(
+    entry_ids: Vec<EntryId>,
+    mut expanded_entries: Signal<HashSet<EntryId>>,
+) -> Element {
+    let kinds = EntryKind::all_values()
+        .iter()
+        .copied()
+        .filter(|kind| entry_ids.iter().any(|id| id.kind() == *kind))
+        .collect::<Vec<_>>();
+
+    rsx! {
+        div { class: "flex flex-wrap gap-2 mb-2 print:hidden",
+            for kind in kinds {
+                {
+                    let ids = entry_ids
+                        .iter()
+                        .copied()
+                        .filter(|id| id.kind() == kind)
+                        .collect::<Vec<_>>();
+                    let all_expanded = ids.iter().all(|id| expanded_entries.read().contains(id));
+                    let title = kind.as_title().to_lowercase();
+                    rsx! {
+                        button {
+                            key: "{kind.as_id()}",
+                            class: "btn btn-outline btn-xs",
+                            onclick: move |_| {
+                                let mut expanded_entries = expanded_entries.write();
+                                for id in &ids {
+                                    if all_expanded {
+                                        expanded_entries.remove(id);
+                                    } else {
+                                        expanded_entries.insert(*id);
+                                    }
+                                }
+                            },
+                            if all_expanded {
+                                "Collapse all {title}"
+                            } else {
+                                "Expand all {title}"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Select the visible timeline row `step` rows away from `current`, or the
+/// first or last row if nothing is selected, and scroll it into view. Rows in
+/// collapsed groups are skipped.
+async fn step_visible_entry(current: Option<EntryId>, step: i32) -> Option<EntryId> {
+    let current = current.map(|id| id.as_str()).unwrap_or_default();
+    let mut eval = document::eval(&format!(
+        r##"
+        const rows = Array.from(document.querySelectorAll("#timeline-rows > tr[id]"))
+            .filter((row) => row.offsetParent !== null);
+        const index = rows.findIndex((row) => row.id === {current:?});
+        const next = index === -1
+            ? ({step} > 0 ? 0 : rows.length - 1)
+            : Math.min(Math.max(index + {step}, 0), rows.length - 1);
+        const row = rows[next];
+        if (row) {{
+            row.scrollIntoView({{ block: "nearest" }});
+            dioxus.send(row.id);
+        }} else {{
+            dioxus.send(null);
+        }}
+        "##
+    ));
+    let entry_id: Option<String> = eval.recv().await.ok()?;
+    entry_id?.parse().ok()
+}
+
 #[component]
 pub fn TimelineList(
     date: ReadSignal<NaiveDate>,
     dialog: ReadSignal<Option<DialogReference>>,
+    sort: ReadSignal<Option<SortDirection>>,
+    focus: ReadSignal<Option<EntryFocus>>,
 ) -> Element {
     let navigator = navigator();
-    let selected: Signal<Option<EntryId>> = use_signal(|| None);
+    let sort_direction = use_memo(move || sort().unwrap_or_default());
+    let mut selected: Signal<Option<EntryId>> = use_signal(|| None);
+    let mut expanded_entries: Signal<HashSet<EntryId>> = use_signal(HashSet::new);
+    let undo_log = use_undo_log();
     let user = use_user().ok().flatten();
 
     let Some(user) = user.as_ref() else {
@@ -275,6 +924,10 @@ pub fn TimelineList(
     };
 
     let user_id = user.pipe(|x| x.id);
+    let timezone = user.timezone;
+    let group_window = user
+        .group_window_minutes
+        .map(|minutes| TimeDelta::minutes(minutes.into()));
 
     let dialog: Resource<Result<ActiveDialog, ServerFnError>> = use_resource(move || async move {
         let Some(dialog) = dialog() else {
@@ -284,6 +937,7 @@ pub fn TimelineList(
             DialogReference::CreateWee { user_id } => {
                 ActiveDialog::Wee(wees::ActiveDialog::Change(wees::Operation::Create {
                     user_id,
+                    template: None,
                 }))
                 .pipe(Ok)
             }
@@ -301,7 +955,10 @@ pub fn TimelineList(
                 ActiveDialog::Wee(wees::ActiveDialog::Delete(wee)).pipe(Ok)
             }
             DialogReference::CreateWeeUrge { user_id } => ActiveDialog::WeeUrge(
-                wee_urges::ActiveDialog::Change(wee_urges::Operation::Create { user_id }),
+                wee_urges::ActiveDialog::Change(wee_urges::Operation::Create {
+                    user_id,
+                    template: None,
+                }),
             )
             .pipe(Ok),
             DialogReference::UpdateWeeUrge { wee_urge_id } => {
@@ -322,6 +979,7 @@ pub fn TimelineList(
             DialogReference::CreatePoo { user_id } => {
                 ActiveDialog::Poo(poos::ActiveDialog::Change(poos::Operation::Create {
                     user_id,
+                    template: None,
                 }))
                 .pipe(Ok)
             }
@@ -338,12 +996,13 @@ pub fn TimelineList(
                     .ok_or(ServerFnError::new("Cannot find poo"))?;
                 ActiveDialog::Poo(poos::ActiveDialog::Delete(poo)).pipe(Ok)
             }
-            DialogReference::CreateConsumption { user_id } => {
-                ActiveDialog::Consumption(consumptions::ActiveDialog::UpdateBasic(
-                    consumptions::Operation::Create { user_id },
-                ))
-                .pipe(Ok)
-            }
+            DialogReference::CreateConsumption { user_id } => ActiveDialog::Consumption(
+                consumptions::ActiveDialog::UpdateBasic(consumptions::Operation::Create {
+                    user_id,
+                    template: None,
+                }),
+            )
+            .pipe(Ok),
             DialogReference::UpdateBasic { consumption_id } => {
                 let consumption = get_consumption_by_id(consumption_id)
                     .await?
@@ -400,7 +1059,10 @@ pub fn TimelineList(
             }
             DialogReference::CreateExercise { user_id } => {
                 ActiveDialog::Exercise(crate::components::exercises::ActiveDialog::Change(
-                    crate::components::exercises::Operation::Create { user_id },
+                    crate::components::exercises::Operation::Create {
+                        user_id,
+                        template: None,
+                    },
                 ))
                 .pipe(Ok)
             }
@@ -422,7 +1084,10 @@ pub fn TimelineList(
             }
             DialogReference::CreateHealthMetric { user_id } => {
                 ActiveDialog::HealthMetric(crate::components::health_metrics::ActiveDialog::Change(
-                    crate::components::health_metrics::Operation::Create { user_id },
+                    crate::components::health_metrics::Operation::Create {
+                        user_id,
+                        template: None,
+                    },
                 ))
                 .pipe(Ok)
             }
@@ -446,7 +1111,10 @@ pub fn TimelineList(
             }
             DialogReference::CreateSymptom { user_id } => {
                 ActiveDialog::Symptom(crate::components::symptoms::ActiveDialog::Change(
-                    crate::components::symptoms::Operation::Create { user_id },
+                    crate::components::symptoms::Operation::Create {
+                        user_id,
+                        template: None,
+                    },
                 ))
                 .pipe(Ok)
             }
@@ -468,7 +1136,10 @@ pub fn TimelineList(
             }
             DialogReference::CreateReflux { user_id } => {
                 ActiveDialog::Reflux(crate::components::refluxs::ActiveDialog::Change(
-                    crate::components::refluxs::Operation::Create { user_id },
+                    crate::components::refluxs::Operation::Create {
+                        user_id,
+                        template: None,
+                    },
                 ))
                 .pipe(Ok)
             }
@@ -490,7 +1161,10 @@ pub fn TimelineList(
             }
             DialogReference::CreateNote { user_id } => {
                 ActiveDialog::Note(crate::components::notes::ActiveDialog::Change(
-                    crate::components::notes::Operation::Create { user_id },
+                    crate::components::notes::Operation::Create {
+                        user_id,
+                        template: None,
+                    },
                 ))
                 .pipe(Ok)
             }
@@ -509,56 +1183,183 @@ pub fn TimelineList(
                     .ok_or(ServerFnError::new("Cannot find note"))?;
                 ActiveDialog::Note(crate::components::notes::ActiveDialog::Delete(note)).pipe(Ok)
             }
+            DialogReference::CreateFluidAdjustment { user_id } => ActiveDialog::FluidAdjustment(
+                crate::components::fluid_adjustments::ActiveDialog::Change(
+                    crate::components::fluid_adjustments::Operation::Create {
+                        user_id,
+                        template: None,
+                    },
+                ),
+            )
+            .pipe(Ok),
+            DialogReference::UpdateFluidAdjustment {
+                fluid_adjustment_id,
+            } => {
+                let fluid_adjustment = get_fluid_adjustment_by_id(fluid_adjustment_id)
+                    .await?
+                    .ok_or(ServerFnError::new("Cannot find fluid adjustment"))?;
+                ActiveDialog::FluidAdjustment(
+                    crate::components::fluid_adjustments::ActiveDialog::Change(
+                        crate::components::fluid_adjustments::Operation::Update {
+                            fluid_adjustment,
+                        },
+                    ),
+                )
+                .pipe(Ok)
+            }
+            DialogReference::DeleteFluidAdjustment {
+                fluid_adjustment_id,
+            } => {
+                let fluid_adjustment = get_fluid_adjustment_by_id(fluid_adjustment_id)
+                    .await?
+                    .ok_or(ServerFnError::new("Cannot find fluid adjustment"))?;
+                ActiveDialog::FluidAdjustment(
+                    crate::components::fluid_adjustments::ActiveDialog::Delete(fluid_adjustment),
+                )
+                .pipe(Ok)
+            }
+            DialogReference::CreateMedication { user_id } => {
+                ActiveDialog::Medication(crate::components::medication_doses::ActiveDialog::Change(
+                    crate::components::medication_doses::Operation::Create {
+                        user_id,
+                        template: None,
+                    },
+                ))
+                .pipe(Ok)
+            }
+            DialogReference::UpdateMedication { medication_dose_id } => {
+                let medication_dose = get_medication_dose_by_id(medication_dose_id)
+                    .await?
+                    .ok_or(ServerFnError::new("Cannot find medication dose"))?;
+                ActiveDialog::Medication(crate::components::medication_doses::ActiveDialog::Change(
+                    crate::components::medication_doses::Operation::Update { medication_dose },
+                ))
+                .pipe(Ok)
+            }
+            DialogReference::DeleteMedication { medication_dose_id } => {
+                let medication_dose = get_medication_dose_by_id(medication_dose_id)
+                    .await?
+                    .ok_or(ServerFnError::new("Cannot find medication dose"))?;
+                ActiveDialog::Medication(crate::components::medication_doses::ActiveDialog::Delete(
+                    medication_dose,
+                ))
+                .pipe(Ok)
+            }
+            DialogReference::CreateFromTemplate { entry_template_id } => {
+                let data = get_entry_template_data(entry_template_id).await?;
+                ActiveDialog::create_from_template(user_id, data).pipe(Ok)
+            }
             DialogReference::Idle => Ok(ActiveDialog::Idle),
         }
     });
 
     let mut timeline: Resource<Result<Timeline, ServerFnError>> =
         use_resource(move || async move {
-            let (start, end) = get_utc_times_for_date(date())?;
-
-            let mut timeline = Timeline::new();
-            let wees = get_wees_for_time_range(user_id, start, end).await?;
-            timeline.add_wees(wees);
-
-            let wee_urgencies = get_wee_urges_for_time_range(user_id, start, end).await?;
-            timeline.add_wee_urges(wee_urgencies);
-
-            let poos = get_poos_for_time_range(user_id, start, end).await?;
-            timeline.add_poos(poos);
-
-            let consumptions = get_consumptions_for_time_range(user_id, start, end).await?;
-            timeline.add_consumptions(consumptions);
-
-            let exercises = get_exercises_for_time_range(user_id, start, end).await?;
-            timeline.add_exercises(exercises);
-
-            let health_metrics = get_health_metrics_for_time_range(user_id, start, end).await?;
-            timeline.add_health_metrics(health_metrics);
+            undo_log.track();
+            let (start, end) = get_utc_times_for_date(date(), timezone)?;
+            let json = timeline_json(user_id, start, end).await?;
+            let mut timeline: Timeline = serde_json::from_str(&json)
+                .map_err(|err| ServerFnError::new(format!("Failed to read timeline: {err}")))?;
+            timeline.sort_by_direction(sort_direction());
 
-            let symptoms = get_symptoms_for_time_range(user_id, start, end).await?;
-            timeline.add_symptoms(symptoms);
-
-            let refluxs = get_refluxs_for_time_range(user_id, start, end).await?;
-            timeline.add_refluxs(refluxs);
+            Ok(timeline)
+        });
 
-            let notes = get_notes_for_time_range(user_id, start, end).await?;
-            timeline.add_notes(notes);
+    let on_keydown = move |event: KeyboardEvent| {
+        let step = match event.key() {
+            Key::ArrowDown => 1,
+            Key::ArrowUp => -1,
+            Key::Escape => {
+                selected.set(None);
+                expanded_entries.write().clear();
+                return;
+            }
+            Key::Enter => {
+                let Some(entry_id) = selected() else {
+                    return;
+                };
+                let entry = match &*timeline.read() {
+                    Some(Ok(timeline)) => timeline
+                        .iter()
+                        .find(|entry| entry.get_id() == entry_id)
+                        .cloned(),
+                    _ => None,
+                };
+                if let Some(entry) = entry {
+                    event.prevent_default();
+                    navigator.push(Route::TimelineList {
+                        date: date(),
+                        sort: sort_direction(),
+                        focus: EntryFocus::default(),
+                        dialog: DialogReference::get_update_dialog_reference(&entry),
+                    });
+                }
+                return;
+            }
+            _ => return,
+        };
+        event.prevent_default();
+        spawn(async move {
+            if let Some(entry_id) = step_visible_entry(selected(), step).await {
+                selected.set(Some(entry_id));
+            }
+        });
+    };
 
-            timeline.sort();
+    use_effect(move || {
+        let Some(EntryFocus(Some(entry_id))) = focus() else {
+            return;
+        };
+        // The row only exists once the timeline has loaded.
+        let found = match &*timeline.read() {
+            Some(Ok(timeline)) => timeline.iter().any(|entry| entry.get_id() == entry_id),
+            _ => return,
+        };
+        if found {
+            selected.set(Some(entry_id));
+            document::eval(&format!(
+                "document.getElementById({:?})?.scrollIntoView({{ block: 'center' }});",
+                entry_id.as_str()
+            ));
+            return;
+        }
+        // The link may have the wrong date, so go to the entry's date instead.
+        spawn(async move {
+            let Ok(Some(entry)) = get_entry_by_id(entry_id).await else {
+                return;
+            };
+            let entry_date = get_date_for_dt(entry.time.with_timezone(&Utc), timezone);
+            if entry_date != date() {
+                navigator.replace(Route::TimelineList {
+                    date: entry_date,
+                    dialog: DialogReference::Idle,
+                    sort: sort_direction(),
+                    focus: EntryFocus(Some(entry_id)),
+                });
+            }
+        });
+    });
 
-            Ok(timeline)
+    let create_from_template = move |entry_template_id: EntryTemplateId| {
+        navigator.push(Route::TimelineList {
+            date: date(),
+            sort: sort_direction(),
+            focus: EntryFocus::default(),
+            dialog: DialogReference::CreateFromTemplate { entry_template_id },
         });
+    };
 
     rsx! {
         div { class: "ml-2 mr-2",
-            div { class: "font-bold text-lg", "Inputs" }
-            div { class: "mb-2 flex flex-wrap gap-2",
+            div { class: "font-bold text-lg print:hidden", "Inputs" }
+            div { class: "mb-2 flex flex-wrap gap-2 print:hidden",
                 CreateButton {
                     on_click: move |_| {
                         navigator
                             .push(Route::TimelineList {
                                 date: date(),
+                                sort: sort_direction(),
+                                focus: EntryFocus::default(),
                                 dialog: DialogReference::CreateConsumption {
                                     user_id,
                                 },
@@ -566,11 +1367,14 @@ pub fn TimelineList(
                     },
                     "Consumption"
                 }
+                EntryTemplatePicker { kind: EntryKind::Consumption, on_select: create_from_template }
                 CreateButton {
                     on_click: move |_| {
                         navigator
                             .push(Route::TimelineList {
                                 date: date(),
+                                sort: sort_direction(),
+                                focus: EntryFocus::default(),
                                 dialog: DialogReference::CreateExercise {
                                     user_id,
                                 },
@@ -578,11 +1382,29 @@ pub fn TimelineList(
                     },
                     "Exercise"
                 }
+                EntryTemplatePicker { kind: EntryKind::Exercise, on_select: create_from_template }
+                CreateButton {
+                    on_click: move |_| {
+                        navigator
+                            .push(Route::TimelineList {
+                                date: date(),
+                                sort: sort_direction(),
+                                focus: EntryFocus::default(),
+                                dialog: DialogReference::CreateMedication {
+                                    user_id,
+                                },
+                            });
+                    },
+                    "Medication"
+                }
+                EntryTemplatePicker { kind: EntryKind::Medication, on_select: create_from_template }
                 CreateButton {
                     on_click: move |_| {
                         navigator
                             .push(Route::TimelineList {
                                 date: date(),
+                                sort: sort_direction(),
+                                focus: EntryFocus::default(),
                                 dialog: DialogReference::CreateNote {
                                     user_id,
                                 },
@@ -590,14 +1412,17 @@ pub fn TimelineList(
                     },
                     "Notes"
                 }
+                EntryTemplatePicker { kind: EntryKind::Note, on_select: create_from_template }
             }
-            div { class: "font-bold text-lg", "Outputs" }
-            div { class: "mb-2 flex flex-wrap gap-2",
+            div { class: "font-bold text-lg print:hidden", "Outputs" }
+            div { class: "mb-2 flex flex-wrap gap-2 print:hidden",
                 CreateButton {
                     on_click: move |_| {
                         navigator
                             .push(Route::TimelineList {
                                 date: date(),
+                                sort: sort_direction(),
+                                focus: EntryFocus::default(),
                                 dialog: DialogReference::CreateWeeUrge {
                                     user_id,
                                 },
@@ -605,11 +1430,14 @@ pub fn TimelineList(
                     },
                     "Wee Urge"
                 }
+                EntryTemplatePicker { kind: EntryKind::WeeUrge, on_select: create_from_template }
                 CreateButton {
                     on_click: move |_| {
                         navigator
                             .push(Route::TimelineList {
                                 date: date(),
+                                sort: sort_direction(),
+                                focus: EntryFocus::default(),
                                 dialog: DialogReference::CreateWee {
                                     user_id,
                                 },
@@ -617,11 +1445,14 @@ pub fn TimelineList(
                     },
                     "Wee"
                 }
+                EntryTemplatePicker { kind: EntryKind::Wee, on_select: create_from_template }
                 CreateButton {
                     on_click: move |_| {
                         navigator
                             .push(Route::TimelineList {
                                 date: date(),
+                                sort: sort_direction(),
+                                focus: EntryFocus::default(),
                                 dialog: DialogReference::CreatePoo {
                                     user_id,
                                 },
@@ -629,11 +1460,14 @@ pub fn TimelineList(
                     },
                     "Poo"
                 }
+                EntryTemplatePicker { kind: EntryKind::Poo, on_select: create_from_template }
                 CreateButton {
                     on_click: move |_| {
                         navigator
                             .push(Route::TimelineList {
                                 date: date(),
+                                sort: sort_direction(),
+                                focus: EntryFocus::default(),
                                 dialog: DialogReference::CreateHealthMetric {
                                     user_id,
                                 },
@@ -641,11 +1475,14 @@ pub fn TimelineList(
                     },
                     "Health Metric"
                 }
+                EntryTemplatePicker { kind: EntryKind::HealthMetric, on_select: create_from_template }
                 CreateButton {
                     on_click: move |_| {
                         navigator
                             .push(Route::TimelineList {
                                 date: date(),
+                                sort: sort_direction(),
+                                focus: EntryFocus::default(),
                                 dialog: DialogReference::CreateSymptom {
                                     user_id,
                                 },
@@ -653,11 +1490,14 @@ pub fn TimelineList(
                     },
                     "Symptom"
                 }
+                EntryTemplatePicker { kind: EntryKind::Symptom, on_select: create_from_template }
                 CreateButton {
                     on_click: move |_| {
                         navigator
                             .push(Route::TimelineList {
                                 date: date(),
+                                sort: sort_direction(),
+                                focus: EntryFocus::default(),
                                 dialog: DialogReference::CreateReflux {
                                     user_id,
                                 },
@@ -665,10 +1505,26 @@ pub fn TimelineList(
                     },
                     "Reflux"
                 }
+                EntryTemplatePicker { kind: EntryKind::Reflux, on_select: create_from_template }
+                CreateButton {
+                    on_click: move |_| {
+                        navigator
+                            .push(Route::TimelineList {
+                                date: date(),
+                                sort: sort_direction(),
+                                focus: EntryFocus::default(),
+                                dialog: DialogReference::CreateFluidAdjustment {
+                                    user_id,
+                                },
+                            });
+                    },
+                    "Fluid Adjustment"
+                }
+                EntryTemplatePicker { kind: EntryKind::FluidAdjustment, on_select: create_from_template }
             }
 
             div { class: "font-bold text-lg", {display_date(date())} }
-            div { class: "mb-2 flex flex-wrap gap-2",
+            div { class: "mb-2 flex flex-wrap gap-2 print:hidden",
                 NavButton {
                     on_click: move |_| {
                         let new_date = date().pred_opt();
@@ -676,6 +1532,8 @@ pub fn TimelineList(
                             navigator
                                 .push(Route::TimelineList {
                                     date: new_date,
+                                    sort: sort_direction(),
+                                    focus: EntryFocus::default(),
                                     dialog: DialogReference::Idle,
                                 });
                         }
@@ -684,10 +1542,12 @@ pub fn TimelineList(
                 }
                 NavButton {
                     on_click: move |_| {
-                        let new_date = get_date_for_dt(Utc::now());
+                        let new_date = get_today(timezone);
                         navigator
                             .push(Route::TimelineList {
                                 date: new_date,
+                                sort: sort_direction(),
+                                focus: EntryFocus::default(),
                                 dialog: DialogReference::Idle,
                             });
                     },
@@ -700,12 +1560,27 @@ pub fn TimelineList(
                             navigator
                                 .push(Route::TimelineList {
                                     date: new_date,
+                                    sort: sort_direction(),
+                                    focus: EntryFocus::default(),
                                     dialog: DialogReference::Idle,
                                 });
                         }
                     },
                     ">"
                 }
+                NavButton {
+                    on_click: move |_| {
+                        navigator.push(Route::TimelineScroll {});
+                    },
+                    "Scroll"
+                }
+                PrintButton {}
+                a {
+                    class: "btn btn-outline btn-accent align-top",
+                    href: "/api/v1/report.pdf?start={date()}&end={date()}",
+                    target: "_blank",
+                    "PDF"
+                }
             }
         }
 
@@ -717,32 +1592,92 @@ pub fn TimelineList(
                 }
             },
             Some(Ok(timeline)) if timeline.is_empty() => rsx! {
-                p { class: "alert alert-info", "No entries found for this date." }
+                EmptyTimeline { user_id, date: date(), sort: sort_direction() }
             },
-            Some(Ok(timeline)) => rsx! {
-                div { class: "ml-2 mr-2 sm:ml-0 sm:mr-0",
-                    table { class: "block sm:table",
-                        thead { class: "hidden sm:table-header-group",
-                            tr {
-                                th { "When" }
-                                th { "What" }
-                                th { "How Long" }
-                                th { "Details" }
+            Some(Ok(entries)) => {
+                // Entries carried over from the previous day count towards it.
+                let day_start = get_utc_times_for_date(date(), timezone)
+                    .map(|(start, _)| start)
+                    .unwrap_or(DateTime::<Utc>::MIN_UTC);
+                let balances: HashMap<EntryId, bigdecimal::BigDecimal> = entries
+                    .with_running_balance(day_start)
+                    .into_iter()
+                    .map(|(entry, balance)| (entry.get_id(), balance.net()))
+                    .collect();
+                rsx! {
+                    FluidBalanceSummary { balance: entries.fluid_balance(day_start) }
+                    ExpandByKind {
+                        entry_ids: entries.iter().map(|entry| entry.get_id()).collect::<Vec<_>>(),
+                        expanded_entries,
+                    }
+                    div {
+                        class: "ml-2 mr-2 sm:ml-0 sm:mr-0 focus:outline-none",
+                        tabindex: "0",
+                        onkeydown: on_keydown,
+                        table { class: "block sm:table print:table print:w-full",
+                            thead { class: "hidden sm:table-header-group print:table-header-group",
+                                tr {
+                                    th {
+                                        button {
+                                            class: "cursor-pointer",
+                                            title: "Change sort order",
+                                            onclick: move |_| {
+                                                navigator
+                                                    .replace(Route::TimelineList {
+                                                        date: date(),
+                                                        dialog: DialogReference::Idle,
+                                                        sort: sort_direction().reverse(),
+                                                        focus: EntryFocus::default(),
+                                                    });
+                                            },
+                                            "When "
+                                            if sort_direction() == SortDirection::Ascending {
+                                                "▲"
+                                            } else {
+                                                "▼"
+                                            }
+                                        }
+                                    }
+                                    th { "What" }
+                                    th { "How Long" }
+                                    th { "Details" }
+                                }
                             }
-                        }
-                        tbody { class: "block sm:table-row-group",
-                            for entry in timeline.iter() {
-                                EntryRow {
-                                    key: "{entry.get_id().as_str()}",
-                                    entry: entry.clone(),
-                                    date: date(),
-                                    selected,
+                            tbody {
+                                id: "timeline-rows",
+                                class: "block sm:table-row-group print:table-row-group",
+                                for group in entries.grouped_entries(group_window) {
+                                    if group.len() == 1 {
+                                        EntryRow {
+                                            key: "{group.first().get_id().as_str()}",
+                                            entry: group.first().clone(),
+                                            date: date(),
+                                            sort: sort_direction(),
+                                            selected,
+                                            expanded: expanded_entries,
+                                            print_only: false,
+                                            read_only: false,
+                                            balance: balances.get(&group.first().get_id()).cloned(),
+                                            on_change: move |()| timeline.restart(),
+                                        }
+                                    } else {
+                                        EntryGroupRows {
+                                            key: "group-{group.first().get_id().as_str()}",
+                                            group: group.clone(),
+                                            date: date(),
+                                            sort: sort_direction(),
+                                            selected,
+                                            expanded_entries,
+                                            balances: balances.clone(),
+                                            on_change: move |()| timeline.restart(),
+                                        }
+                                    }
                                 }
                             }
                         }
                     }
                 }
-            },
+            }
             None => {
                 rsx! {
                     p { class: "alert alert-info", "Loading..." }
@@ -765,6 +1700,8 @@ pub fn TimelineList(
                         navigator
                             .replace(Route::TimelineList {
                                 date: date(),
+                                sort: sort_direction(),
+                                focus: EntryFocus::default(),
                                 dialog,
                             });
                     },
@@ -772,6 +1709,8 @@ pub fn TimelineList(
                         navigator
                             .push(Route::TimelineList {
                                 date: date(),
+                                sort: sort_direction(),
+                                focus: EntryFocus::default(),
                                 dialog: DialogReference::UpdateBasic {
                                     consumption_id: consumption.id,
                                 },
@@ -781,6 +1720,8 @@ pub fn TimelineList(
                         navigator
                             .push(Route::TimelineList {
                                 date: date(),
+                                sort: sort_direction(),
+                                focus: EntryFocus::default(),
                                 dialog: DialogReference::UpdateIngredients {
                                     consumption_id: consumption.id,
                                 },
@@ -790,6 +1731,8 @@ pub fn TimelineList(
                         navigator
                             .push(Route::TimelineList {
                                 date: date(),
+                                sort: sort_direction(),
+                                focus: EntryFocus::default(),
                                 dialog: DialogReference::IngredientUpdateBasic {
                                     parent_id: consumption.id,
                                     consumable_id: consumable.id,
@@ -800,6 +1743,8 @@ pub fn TimelineList(
                         navigator
                             .push(Route::TimelineList {
                                 date: date(),
+                                sort: sort_direction(),
+                                focus: EntryFocus::default(),
                                 dialog: DialogReference::IngredientUpdateIngredients {
                                     parent_id: consumption.id,
                                     consumable_id: consumable.id,
@@ -810,6 +1755,8 @@ pub fn TimelineList(
                         navigator
                             .push(Route::TimelineList {
                                 date: date(),
+                                sort: sort_direction(),
+                                focus: EntryFocus::default(),
                                 dialog: DialogReference::Idle,
                             });
                     },
@@ -9,11 +9,11 @@ use tap::Pipe;
 use crate::Route;
 use crate::components::buttons::{ChangeButton, DeleteButton, NavButton};
 use crate::components::users::{
-    ActiveDialog, DetailsDialogReference, ListDialogReference, UserCreate, UserDelete, UserUpdate,
-    UserUpdatePassword,
+    ActiveDialog, DetailsDialogReference, LastLogin, ListDialogReference, UserCreate, UserDelete,
+    UserUpdate, UserUpdatePassword,
 };
-use crate::functions::users::{get_user_by_id, get_users};
-use crate::models::{User, UserId};
+use crate::functions::users::{get_user_by_id, get_users_paged};
+use crate::models::{User, UserId, UserPage};
 
 #[component]
 pub fn UserItem(user: ReadSignal<User>, on_click: Callback<User>) -> Element {
@@ -36,6 +36,12 @@ pub fn UserItem(user: ReadSignal<User>, on_click: Callback<User>) -> Element {
             }
             td { class: "block sm:table-cell border-blue-300 sm:border-t-2",
                 {if user.is_admin { "Admin" } else { "User" }}
+                if !user.is_active {
+                    span { class: "badge badge-warning ml-2", "Disabled" }
+                }
+            }
+            td { class: "block sm:table-cell border-blue-300 sm:border-t-2",
+                LastLogin { last_login_at: user.last_login_at }
             }
         }
     }
@@ -151,6 +157,10 @@ pub fn UserDetail(user_id: UserId, dialog: ReadSignal<Option<DetailsDialogRefere
                             td { "Role" }
                             td { {if obj.is_admin { "Admin" } else { "User" }} }
                         }
+                        tr {
+                            td { "Active" }
+                            td { {if obj.is_active { "Yes" } else { "No" }} }
+                        }
                         tr {
                             td { "Created" }
                             td { {obj.created_at.with_timezone(&Local).to_string()} }
@@ -159,6 +169,12 @@ pub fn UserDetail(user_id: UserId, dialog: ReadSignal<Option<DetailsDialogRefere
                             td { "Updated" }
                             td { {obj.updated_at.with_timezone(&Local).to_string()} }
                         }
+                        tr {
+                            td { "Last Login" }
+                            td {
+                                LastLogin { last_login_at: obj.last_login_at }
+                            }
+                        }
                     }
                 }
 
@@ -200,6 +216,8 @@ pub fn UserDetail(user_id: UserId, dialog: ReadSignal<Option<DetailsDialogRefere
                             navigator
                                 .push(Route::UserList {
                                     dialog: ListDialogReference::Idle,
+                                    page: 0,
+                                    search: String::new(),
                                 });
                         },
                         "User List"
@@ -240,9 +258,21 @@ pub fn UserDetail(user_id: UserId, dialog: ReadSignal<Option<DetailsDialogRefere
     }
 }
 
+const USERS_PAGE_SIZE: usize = 20;
+
 #[component]
-pub fn UserList(dialog: ReadSignal<Option<ListDialogReference>>) -> Element {
-    let mut users = use_resource(|| async { get_users().await });
+pub fn UserList(
+    dialog: ReadSignal<Option<ListDialogReference>>,
+    page: ReadSignal<Option<usize>>,
+    search: ReadSignal<Option<String>>,
+) -> Element {
+    let page = use_memo(move || page().unwrap_or_default());
+    let search = use_memo(move || search().unwrap_or_default());
+
+    let mut users = use_resource(move || async move {
+        let offset = page() * USERS_PAGE_SIZE;
+        get_users_paged(offset as i64, USERS_PAGE_SIZE as i64, search()).await
+    });
 
     let dialog: Resource<Result<ActiveDialog, ServerFnError>> = use_resource(move || async move {
         let Some(dialog) = dialog() else {
@@ -250,24 +280,24 @@ pub fn UserList(dialog: ReadSignal<Option<ListDialogReference>>) -> Element {
         };
         match dialog {
             ListDialogReference::Create => ActiveDialog::Create.pipe(Ok),
-            // ListDialogReference::Update { user_id } => {
-            //     let user = get_user_by_id(user_id).await?.ok_or(
-            //         ServerFnError::<String>::ServerError("Cannot find user".to_string()),
-            //     )?;
-            //     ActiveDialog::Change(user).pipe(Ok)
-            // }
-            // ListDialogReference::Password { user_id } => {
-            //     let user = get_user_by_id(user_id).await?.ok_or(
-            //         ServerFnError::<String>::ServerError("Cannot find user".to_string()),
-            //     )?;
-            //     ActiveDialog::Password(user).pipe(Ok)
-            // }
-            // ListDialogReference::Delete { user_id } => {
-            //     let user = get_user_by_id(user_id).await?.ok_or(
-            //         ServerFnError::<String>::ServerError("Cannot find user".to_string()),
-            //     )?;
-            //     ActiveDialog::Delete(user).pipe(Ok)
-            // }
+            ListDialogReference::Update { user_id } => {
+                let user = get_user_by_id(user_id)
+                    .await?
+                    .ok_or(ServerFnError::new("Cannot find user"))?;
+                ActiveDialog::Change(user).pipe(Ok)
+            }
+            ListDialogReference::Password { user_id } => {
+                let user = get_user_by_id(user_id)
+                    .await?
+                    .ok_or(ServerFnError::new("Cannot find user"))?;
+                ActiveDialog::Password(user).pipe(Ok)
+            }
+            ListDialogReference::Delete { user_id } => {
+                let user = get_user_by_id(user_id)
+                    .await?
+                    .ok_or(ServerFnError::new("Cannot find user"))?;
+                ActiveDialog::Delete(user).pipe(Ok)
+            }
             ListDialogReference::Idle => Ok(ActiveDialog::Idle),
         }
     });
@@ -275,8 +305,29 @@ pub fn UserList(dialog: ReadSignal<Option<ListDialogReference>>) -> Element {
     let navigator = navigator();
 
     rsx! {
+        div { class: "ml-2 mr-2 mb-2",
+            input {
+                class: "input",
+                r#type: "search",
+                value: search(),
+                oninput: move |e| {
+                    navigator
+                        .replace(Route::UserList {
+                            dialog: ListDialogReference::Idle,
+                            page: 0,
+                            search: e.value(),
+                        });
+                },
+                placeholder: "Search username, email or name...",
+            }
+        }
+
         match users() {
-            Some(Ok(users)) => {
+            Some(Ok(UserPage { users, total })) => {
+                let total = usize::try_from(total).unwrap_or_default();
+                let first = page() * USERS_PAGE_SIZE;
+                let last = (first + users.len()).min(total);
+                let has_next = last < total;
                 rsx! {
                     if users.is_empty() {
                         p { {"No users found."} }
@@ -290,6 +341,7 @@ pub fn UserList(dialog: ReadSignal<Option<ListDialogReference>>) -> Element {
                                         th { "Name" }
                                         th { "Email" }
                                         th { "Role" }
+                                        th { "Last Login" }
                                     }
                                 }
                                 tbody { class: "block sm:table-row-group",
@@ -310,6 +362,41 @@ pub fn UserList(dialog: ReadSignal<Option<ListDialogReference>>) -> Element {
                             }
                         }
                     }
+                    div { class: "ml-2 mr-2 mt-2 mb-2 flex flex-wrap items-center gap-2",
+                        button {
+                            r#type: "button",
+                            class: "btn btn-outline btn-accent align-top",
+                            disabled: page() == 0,
+                            onclick: move |_| {
+                                navigator
+                                    .push(Route::UserList {
+                                        dialog: ListDialogReference::Idle,
+                                        page: page().saturating_sub(1),
+                                        search: search(),
+                                    });
+                            },
+                            "<"
+                        }
+                        if total == 0 {
+                            span { "0 users" }
+                        } else {
+                            span { "{first + 1}-{last} of {total} users" }
+                        }
+                        button {
+                            r#type: "button",
+                            class: "btn btn-outline btn-accent align-top",
+                            disabled: !has_next,
+                            onclick: move |_| {
+                                navigator
+                                    .push(Route::UserList {
+                                        dialog: ListDialogReference::Idle,
+                                        page: page() + 1,
+                                        search: search(),
+                                    });
+                            },
+                            ">"
+                        }
+                    }
                 }
             }
             Some(Err(err)) => {
@@ -334,6 +421,8 @@ pub fn UserList(dialog: ReadSignal<Option<ListDialogReference>>) -> Element {
                     navigator
                         .push(Route::UserList {
                             dialog: ListDialogReference::Create,
+                            page: page(),
+                            search: search(),
                         });
                 },
                 "Create User"
@@ -355,6 +444,8 @@ pub fn UserList(dialog: ReadSignal<Option<ListDialogReference>>) -> Element {
                         navigator
                             .push(Route::UserList {
                                 dialog: ListDialogReference::Idle,
+                                page: page(),
+                                search: search(),
                             });
                     },
                 }